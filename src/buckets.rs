@@ -0,0 +1,214 @@
+//! Grouping projects by the age of their build artifacts.
+//!
+//! `--bucket-ages` sorts the projects that passed filtering into four
+//! buckets by how long ago their build artifacts were last modified, and
+//! shows how many projects and how much space each bucket holds.
+//! `--clean-bucket` selects the projects of one bucket for cleaning, so that
+//! e.g. everything untouched for over a year can be removed in one command.
+//! Projects whose artifacts have no readable modification time are in no
+//! bucket, so they are never cleaned by bucket.
+
+use std::time::SystemTime;
+
+use clap::ValueEnum;
+
+use crate::{filtering::artifact_mtime, project::Project};
+
+/// How long ago a project's build artifacts were last modified.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
+pub enum AgeBucket {
+    /// Less than 30 days
+    #[value(name = "<30d")]
+    Recent,
+
+    /// 30 to 90 days
+    #[value(name = "30-90d", alias = "30–90d")]
+    Months,
+
+    /// 90 days to a year
+    #[value(name = "90-365d", alias = "90–365d")]
+    Year,
+
+    /// More than a year (365 days)
+    #[value(name = ">1y")]
+    Older,
+}
+
+impl AgeBucket {
+    /// All buckets, youngest first.
+    pub const ALL: [Self; 4] = [Self::Recent, Self::Months, Self::Year, Self::Older];
+
+    /// The label of the bucket, as accepted by `--clean-bucket`.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Recent => "<30d",
+            Self::Months => "30-90d",
+            Self::Year => "90-365d",
+            Self::Older => ">1y",
+        }
+    }
+
+    /// The bucket for build artifacts last modified `days` days ago.
+    #[must_use]
+    pub const fn for_days(days: u64) -> Self {
+        match days {
+            0..30 => Self::Recent,
+            30..90 => Self::Months,
+            90..365 => Self::Year,
+            _ => Self::Older,
+        }
+    }
+
+    /// The bucket of `project` at the time `now`, or `None` if the
+    /// modification time of its artifacts can't be read.
+    #[must_use]
+    pub fn of(project: &Project, now: SystemTime) -> Option<Self> {
+        let age = now
+            .duration_since(artifact_mtime(project)?)
+            .unwrap_or_default();
+        Some(Self::for_days(age.as_secs() / 86_400))
+    }
+}
+
+/// The number and total artifact size of the projects in a bucket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BucketTotals {
+    /// The bucket these totals are for
+    pub bucket: AgeBucket,
+
+    /// Number of projects in the bucket
+    pub count: usize,
+
+    /// Total artifact size of the projects in the bucket, in bytes
+    pub size: u64,
+}
+
+/// Sort `projects` into age buckets at the time `now`.
+///
+/// Returns the totals of every bucket, youngest first, including empty ones.
+/// Projects in no bucket (see [`AgeBucket::of`]) aren't counted.
+#[must_use]
+pub fn bucket_totals(projects: &[Project], now: SystemTime) -> Vec<BucketTotals> {
+    let mut totals: Vec<BucketTotals> = AgeBucket::ALL
+        .iter()
+        .map(|&bucket| BucketTotals {
+            bucket,
+            count: 0,
+            size: 0,
+        })
+        .collect();
+
+    for project in projects {
+        let Some(bucket) = AgeBucket::of(project, now) else {
+            continue;
+        };
+        let entry = &mut totals[bucket as usize];
+        entry.count += 1;
+        entry.size += project.total_size();
+    }
+
+    totals
+}
+
+/// Keep only the projects of `projects` that fall into `bucket` at the time `now`.
+#[must_use]
+pub fn select_bucket(projects: Vec<Project>, bucket: AgeBucket, now: SystemTime) -> Vec<Project> {
+    projects
+        .into_iter()
+        .filter(|project| AgeBucket::of(project, now) == Some(bucket))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+
+    const DAY: u64 = 86_400;
+
+    fn project(name: &str, size: u64, age_days: u64, now: SystemTime) -> Project {
+        let root = PathBuf::from("/work").join(name);
+        let mut project = Project::new(
+            ProjectType::Rust,
+            root.clone(),
+            vec![BuildArtifacts {
+                path: root.join("target"),
+                size,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some(name.to_string()),
+        );
+        project.newest_file = Some(now - Duration::from_secs(age_days * DAY));
+        project
+    }
+
+    #[test]
+    fn test_for_days_boundaries() {
+        assert_eq!(AgeBucket::for_days(0), AgeBucket::Recent);
+        assert_eq!(AgeBucket::for_days(29), AgeBucket::Recent);
+        assert_eq!(AgeBucket::for_days(30), AgeBucket::Months);
+        assert_eq!(AgeBucket::for_days(89), AgeBucket::Months);
+        assert_eq!(AgeBucket::for_days(90), AgeBucket::Year);
+        assert_eq!(AgeBucket::for_days(364), AgeBucket::Year);
+        assert_eq!(AgeBucket::for_days(365), AgeBucket::Older);
+    }
+
+    #[test]
+    fn test_labels_parse() {
+        for bucket in AgeBucket::ALL {
+            assert_eq!(AgeBucket::from_str(bucket.label(), false), Ok(bucket));
+        }
+        assert_eq!(AgeBucket::from_str("30–90d", false), Ok(AgeBucket::Months));
+    }
+
+    #[test]
+    fn test_bucket_totals_and_selection() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000 * DAY);
+        let projects = vec![
+            project("fresh", 10, 2, now),
+            project("old", 300, 400, now),
+            project("older", 200, 900, now),
+            project("quarter", 50, 120, now),
+        ];
+
+        let totals = bucket_totals(&projects, now);
+        let summary: Vec<(AgeBucket, usize, u64)> =
+            totals.iter().map(|t| (t.bucket, t.count, t.size)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (AgeBucket::Recent, 1, 10),
+                (AgeBucket::Months, 0, 0),
+                (AgeBucket::Year, 1, 50),
+                (AgeBucket::Older, 2, 500),
+            ]
+        );
+
+        let selected = select_bucket(projects, AgeBucket::Older, now);
+        let names: Vec<_> = selected.iter().filter_map(|p| p.name.as_deref()).collect();
+        assert_eq!(names, vec!["old", "older"]);
+    }
+
+    #[test]
+    fn test_unreadable_dates_are_in_no_bucket() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000 * DAY);
+        let mut undated = project("undated", 100, 0, now);
+        undated.newest_file = None;
+
+        assert_eq!(AgeBucket::of(&undated, now), None);
+        assert!(
+            bucket_totals(std::slice::from_ref(&undated), now)
+                .iter()
+                .all(|total| total.count == 0)
+        );
+        for bucket in AgeBucket::ALL {
+            assert!(select_bucket(vec![undated.clone()], bucket, now).is_empty());
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Per-root size budgets.
+//!
+//! A scan root from the config file can carry a budget for the total size of
+//! the build artifacts found under it, e.g.
+//! `dirs = [{ path = "~/code", budget = "100GB" }]`. When a root is over its
+//! budget, the planning stage proposes cleaning its oldest projects, largest
+//! first among equally old ones, until the root is back under budget. Roots
+//! without a budget are left to the regular filters.
+
+use std::{cmp::Reverse, path::PathBuf};
+
+use crate::{filtering::artifact_modified, project::Project};
+
+/// A scan root with a size budget for its build artifacts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RootBudget {
+    /// The scan root the budget applies to
+    pub root: PathBuf,
+
+    /// Maximum total artifact size under `root`, in bytes
+    pub budget: u64,
+}
+
+/// How a budgeted root compares to its budget, and what was proposed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BudgetReport {
+    /// The scan root the budget applies to
+    pub root: PathBuf,
+
+    /// Maximum total artifact size under `root`, in bytes
+    pub budget: u64,
+
+    /// Total artifact size of all projects found under `root`, in bytes
+    pub total: u64,
+
+    /// Number of projects proposed for cleaning
+    pub proposed_count: usize,
+
+    /// Total artifact size of the proposed projects, in bytes
+    pub proposed_size: u64,
+}
+
+impl BudgetReport {
+    /// Whether the root was over budget before cleaning.
+    #[must_use]
+    pub const fn is_over_budget(&self) -> bool {
+        self.total > self.budget
+    }
+
+    /// Whether cleaning the proposed projects brings the root back under budget.
+    #[must_use]
+    pub const fn is_satisfied(&self) -> bool {
+        self.total.saturating_sub(self.proposed_size) <= self.budget
+    }
+}
+
+/// Find the budget that applies to `project`.
+///
+/// When budgeted roots are nested, the innermost one wins.
+fn budget_index(project: &Project, budgets: &[RootBudget]) -> Option<usize> {
+    budgets
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| project.root_path.starts_with(&b.root))
+        .max_by_key(|(_, b)| b.root.components().count())
+        .map(|(i, _)| i)
+}
+
+/// Restrict `candidates` to the projects needed to bring each root under budget.
+///
+/// # Arguments
+///
+/// * `all` - Every project found by the scan; their artifacts count toward the
+///   root totals even if they were filtered out as cleaning candidates
+/// * `candidates` - Projects that passed filtering and may be cleaned
+/// * `budgets` - Budgeted scan roots
+///
+/// # Returns
+///
+/// The candidates to clean, in their original order, together with one
+/// [`BudgetReport`] per budget. Candidates under a root without a budget are
+/// kept as-is; candidates under a root that is within budget are dropped.
+#[must_use]
+pub fn plan_budget_cleanup(
+    all: &[Project],
+    candidates: Vec<Project>,
+    budgets: &[RootBudget],
+) -> (Vec<Project>, Vec<BudgetReport>) {
+    let mut totals = vec![0u64; budgets.len()];
+    for project in all {
+        if let Some(i) = budget_index(project, budgets) {
+            totals[i] += project.total_size();
+        }
+    }
+
+    // Oldest first, then largest first, per budgeted root
+    let mut ranked: Vec<(usize, usize)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(pos, project)| budget_index(project, budgets).map(|i| (pos, i)))
+        .collect();
+    ranked.sort_by_cached_key(|&(pos, _)| {
+        let project = &candidates[pos];
+        (artifact_modified(project), Reverse(project.total_size()))
+    });
+
+    let mut remaining = totals.clone();
+    let mut keep = vec![false; candidates.len()];
+    let mut reports: Vec<BudgetReport> = budgets
+        .iter()
+        .zip(&totals)
+        .map(|(b, &total)| BudgetReport {
+            root: b.root.clone(),
+            budget: b.budget,
+            total,
+            proposed_count: 0,
+            proposed_size: 0,
+        })
+        .collect();
+
+    for (pos, i) in ranked {
+        if remaining[i] <= budgets[i].budget {
+            continue;
+        }
+        let size = candidates[pos].total_size();
+        remaining[i] = remaining[i].saturating_sub(size);
+        reports[i].proposed_count += 1;
+        reports[i].proposed_size += size;
+        keep[pos] = true;
+    }
+
+    let planned = candidates
+        .into_iter()
+        .zip(keep)
+        .filter(|(project, proposed)| *proposed || budget_index(project, budgets).is_none())
+        .map(|(project, _)| project)
+        .collect();
+
+    (planned, reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use std::{fs, path::Path};
+    use tempfile::TempDir;
+
+    fn project_at(root: &Path, name: &str, size: u64) -> anyhow::Result<Project> {
+        let path = root.join(name);
+        let target = path.join("target");
+        fs::create_dir_all(&target)?;
+
+        Ok(Project::new(
+            ProjectType::Rust,
+            path,
+            vec![BuildArtifacts {
+                path: target,
+                size,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some(name.to_string()),
+        ))
+    }
+
+    /// Backdate a project's artifact directory by `days`.
+    #[cfg(unix)]
+    fn set_age(project: &Project, days: u64) -> anyhow::Result<()> {
+        use std::{
+            fs::{File, FileTimes},
+            time::{Duration, SystemTime},
+        };
+
+        let mtime = SystemTime::now() - Duration::from_secs(days * 86_400);
+        File::open(&project.build_arts[0].path)?.set_times(FileTimes::new().set_modified(mtime))?;
+        Ok(())
+    }
+
+    fn names(projects: &[Project]) -> Vec<&str> {
+        projects.iter().filter_map(|p| p.name.as_deref()).collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_over_budget_root_proposes_oldest_until_under() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path().join("code");
+        let projects = vec![
+            project_at(&root, "new", 50)?,
+            project_at(&root, "old", 30)?,
+            project_at(&root, "older", 20)?,
+        ];
+        set_age(&projects[0], 1)?;
+        set_age(&projects[1], 100)?;
+        set_age(&projects[2], 200)?;
+        let budgets = [RootBudget { root, budget: 55 }];
+
+        let (planned, reports) = plan_budget_cleanup(&projects, projects.clone(), &budgets);
+
+        // 100 bytes total: dropping "older" (20) and "old" (30) gets to 50
+        assert_eq!(names(&planned), vec!["old", "older"]);
+        assert_eq!(reports[0].total, 100);
+        assert_eq!(reports[0].proposed_count, 2);
+        assert_eq!(reports[0].proposed_size, 50);
+        assert!(reports[0].is_over_budget());
+        assert!(reports[0].is_satisfied());
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_age_prefers_largest() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path().join("code");
+        let mut small = project_at(&root, "small", 10)?;
+        let mut large = project_at(&root, "large", 40)?;
+        // Unreadable modification times compare equal
+        small.build_arts[0].path = root.join("missing-a");
+        large.build_arts[0].path = root.join("missing-b");
+        let projects = vec![small, large];
+        let budgets = [RootBudget { root, budget: 20 }];
+
+        let (planned, _) = plan_budget_cleanup(&projects, projects.clone(), &budgets);
+
+        assert_eq!(names(&planned), vec!["large"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_within_budget_and_unbudgeted_roots() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let budgeted = tmp.path().join("code");
+        let other = tmp.path().join("work");
+        let projects = vec![
+            project_at(&budgeted, "a", 10)?,
+            project_at(&other, "b", 500)?,
+        ];
+        let budgets = [RootBudget {
+            root: budgeted,
+            budget: 100,
+        }];
+
+        let (planned, reports) = plan_budget_cleanup(&projects, projects.clone(), &budgets);
+
+        assert_eq!(names(&planned), vec!["b"]);
+        assert!(!reports[0].is_over_budget());
+        assert_eq!(reports[0].proposed_count, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_filtered_out_projects_count_toward_total() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path().join("code");
+        let protected = project_at(&root, "protected", 80)?;
+        let candidate = project_at(&root, "candidate", 30)?;
+        let all = vec![protected, candidate.clone()];
+        let budgets = [RootBudget { root, budget: 60 }];
+
+        let (planned, reports) = plan_budget_cleanup(&all, vec![candidate], &budgets);
+
+        assert_eq!(names(&planned), vec!["candidate"]);
+        assert_eq!(reports[0].total, 110);
+        assert!(!reports[0].is_satisfied());
+        Ok(())
+    }
+
+    #[test]
+    fn test_innermost_budget_wins() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let outer = tmp.path().join("code");
+        let inner = outer.join("big");
+        let project = project_at(&inner, "app", 10)?;
+        let budgets = [
+            RootBudget {
+                root: outer,
+                budget: 0,
+            },
+            RootBudget {
+                root: inner,
+                budget: 100,
+            },
+        ];
+
+        assert_eq!(budget_index(&project, &budgets), Some(1));
+        Ok(())
+    }
+}
@@ -0,0 +1,362 @@
+//! Package manager caches outside of any project.
+//!
+//! Besides the build directories inside projects, package managers keep
+//! shared download caches that grow without bound: Homebrew and apt keep old
+//! package downloads, pip keeps wheels, cargo keeps every crate ever fetched,
+//! and Go keeps every module version. sccache keeps compiled objects up to its
+//! size limit. All of them are refilled on demand, so removing them only costs
+//! download or compile time.
+//!
+//! Caches managed by a system tool (Homebrew, apt) are cleaned by running that
+//! tool's own cleanup command. The others are plain directories and are removed
+//! like build directories, honoring the trash setting.
+
+use std::{
+    env,
+    ffi::OsString,
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    cleaner::RemovalStrategy,
+    error::{CleanDevDirsError, Result, ResultExt},
+    utils::DirStats,
+};
+
+/// A package manager cache that can be reported and cleaned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SystemCache {
+    /// Downloaded bottles and source archives (`brew --cache`)
+    Homebrew,
+
+    /// Downloaded `.deb` packages in `/var/cache/apt/archives`
+    Apt,
+
+    /// Downloaded and built wheels (`pip cache dir`)
+    Pip,
+
+    /// Downloaded and extracted crates in `~/.cargo/registry`
+    ///
+    /// The registry index is kept, since it is slow to fetch again.
+    CargoRegistry,
+
+    /// Downloaded modules in `~/go/pkg/mod` (`go env GOMODCACHE`)
+    GoModules,
+
+    /// Compiled objects cached by sccache's local disk cache (`SCCACHE_DIR`)
+    Sccache,
+}
+
+impl SystemCache {
+    /// Every supported cache.
+    pub const ALL: [Self; 6] = [
+        Self::Homebrew,
+        Self::Apt,
+        Self::Pip,
+        Self::CargoRegistry,
+        Self::GoModules,
+        Self::Sccache,
+    ];
+
+    /// The directories of this cache that exist on this machine.
+    #[must_use]
+    pub fn paths(self) -> Vec<PathBuf> {
+        let (Some(home), Some(cache_dir)) = (dirs::home_dir(), dirs::cache_dir()) else {
+            return Vec::new();
+        };
+
+        self.candidate_paths(|name| env::var_os(name), &home, &cache_dir)
+            .into_iter()
+            .filter(|path| path.is_dir())
+            .collect()
+    }
+
+    /// Where this cache lives, given environment variables, the home
+    /// directory and the platform's cache directory.
+    ///
+    /// Variables that move the cache take precedence over the defaults.
+    fn candidate_paths(
+        self,
+        var: impl Fn(&str) -> Option<OsString>,
+        home: &Path,
+        cache_dir: &Path,
+    ) -> Vec<PathBuf> {
+        let non_empty = |name| var(name).filter(|value: &OsString| !value.is_empty());
+
+        match self {
+            Self::Homebrew => vec![
+                non_empty("HOMEBREW_CACHE")
+                    .map_or_else(|| cache_dir.join("Homebrew"), PathBuf::from),
+            ],
+            Self::Apt => vec![PathBuf::from("/var/cache/apt/archives")],
+            Self::Pip => vec![non_empty("PIP_CACHE_DIR").map_or_else(
+                || {
+                    if cfg!(windows) {
+                        cache_dir.join("pip").join("Cache")
+                    } else {
+                        cache_dir.join("pip")
+                    }
+                },
+                PathBuf::from,
+            )],
+            Self::CargoRegistry => {
+                let registry = non_empty("CARGO_HOME")
+                    .map_or_else(|| home.join(".cargo"), PathBuf::from)
+                    .join("registry");
+                vec![registry.join("cache"), registry.join("src")]
+            }
+            Self::GoModules => vec![non_empty("GOMODCACHE").map_or_else(
+                || {
+                    non_empty("GOPATH")
+                        .and_then(|gopath| env::split_paths(&gopath).next())
+                        .unwrap_or_else(|| home.join("go"))
+                        .join("pkg")
+                        .join("mod")
+                },
+                PathBuf::from,
+            )],
+            Self::Sccache => vec![non_empty("SCCACHE_DIR").map_or_else(
+                || {
+                    if cfg!(windows) {
+                        cache_dir.join("Mozilla").join("sccache").join("cache")
+                    } else if cfg!(target_os = "macos") {
+                        cache_dir.join("Mozilla.sccache")
+                    } else {
+                        cache_dir.join("sccache")
+                    }
+                },
+                PathBuf::from,
+            )],
+        }
+    }
+
+    /// The command that cleans this cache, for caches not removed directly.
+    ///
+    /// Homebrew's default cleanup only removes downloads of outdated versions
+    /// and those older than its prune age, so the downloads of what is
+    /// installed now stay available for reinstalls.
+    const fn cleanup_command(self) -> Option<(&'static str, &'static [&'static str])> {
+        match self {
+            Self::Homebrew => Some(("brew", &["cleanup"])),
+            Self::Apt => Some(("apt-get", &["clean"])),
+            Self::Pip | Self::CargoRegistry | Self::GoModules | Self::Sccache => None,
+        }
+    }
+}
+
+impl Display for SystemCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Homebrew => "Homebrew cache",
+            Self::Apt => "apt cache",
+            Self::Pip => "pip cache",
+            Self::CargoRegistry => "cargo registry",
+            Self::GoModules => "Go module cache",
+            Self::Sccache => "sccache cache",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A cache found on this machine, with its size.
+#[derive(Clone, Debug)]
+pub struct CacheUsage {
+    /// The cache
+    pub cache: SystemCache,
+
+    /// Its directories
+    pub paths: Vec<PathBuf>,
+
+    /// Total size of its directories in bytes
+    pub size: u64,
+}
+
+impl CacheUsage {
+    /// Clean the cache, with its cleanup command or by removing its
+    /// directories, and return the space freed in bytes.
+    ///
+    /// A cleanup command may keep part of the cache, so its directories are
+    /// measured again afterwards. After the sccache cache is removed, its statistics are reset with
+    /// `sccache --zero-stats`, if sccache is installed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cleanup command can't be run or fails, or a
+    /// directory can't be removed.
+    pub fn clean(&self, strategy: RemovalStrategy) -> Result<u64> {
+        if let Some((program, args)) = self.cache.cleanup_command() {
+            run_command(program, args)?;
+            let remaining: u64 = self
+                .paths
+                .iter()
+                .map(|path| DirStats::collect(path).size)
+                .sum();
+            return Ok(self.size.saturating_sub(remaining));
+        }
+
+        for path in &self.paths {
+            strategy.remove(path).with_context(|| {
+                CleanDevDirsError::cleanup(format!("Failed to remove {}", path.display()))
+            })?;
+        }
+
+        if self.cache == SystemCache::Sccache {
+            // Only the statistics are stale now; not worth failing over
+            let _ = run_command("sccache", &["--zero-stats"]);
+        }
+        Ok(self.size)
+    }
+}
+
+/// Find and measure the given caches, leaving out the ones that are absent or empty.
+#[must_use]
+pub fn measure(caches: &[SystemCache]) -> Vec<CacheUsage> {
+    caches
+        .par_iter()
+        .filter_map(|&cache| usage_of(cache, cache.paths()))
+        .collect()
+}
+
+/// Measure `paths` as the directories of `cache`, or `None` if they hold nothing.
+fn usage_of(cache: SystemCache, paths: Vec<PathBuf>) -> Option<CacheUsage> {
+    let size = paths.iter().map(|path| DirStats::collect(path).size).sum();
+    (size > 0).then_some(CacheUsage { cache, paths, size })
+}
+
+/// Run a cleanup command, failing with its error output if it doesn't succeed.
+fn run_command(program: &str, args: &[&str]) -> Result<()> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| CleanDevDirsError::cleanup(format!("Failed to run {program}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CleanDevDirsError::cleanup(format!(
+            "{program} failed: {}",
+            stderr.trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use super::*;
+    use tempfile::TempDir;
+
+    fn candidates(cache: SystemCache, vars: &[(&str, &str)]) -> Vec<PathBuf> {
+        let vars: HashMap<&str, OsString> = vars
+            .iter()
+            .map(|(name, value)| (*name, OsString::from(value)))
+            .collect();
+        cache.candidate_paths(
+            |name| vars.get(name).cloned(),
+            Path::new("/home/dev"),
+            Path::new("/home/dev/.cache"),
+        )
+    }
+
+    #[test]
+    fn test_default_locations() {
+        assert_eq!(
+            candidates(SystemCache::Homebrew, &[]),
+            vec![PathBuf::from("/home/dev/.cache/Homebrew")]
+        );
+        assert_eq!(
+            candidates(SystemCache::CargoRegistry, &[]),
+            vec![
+                PathBuf::from("/home/dev/.cargo/registry/cache"),
+                PathBuf::from("/home/dev/.cargo/registry/src"),
+            ]
+        );
+        assert_eq!(
+            candidates(SystemCache::GoModules, &[]),
+            vec![PathBuf::from("/home/dev/go/pkg/mod")]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sccache_location() {
+        assert_eq!(
+            candidates(SystemCache::Sccache, &[]),
+            vec![PathBuf::from("/home/dev/.cache/sccache")]
+        );
+        assert_eq!(
+            candidates(SystemCache::Sccache, &[("SCCACHE_DIR", "/srv/sccache")]),
+            vec![PathBuf::from("/srv/sccache")]
+        );
+    }
+
+    #[test]
+    fn test_environment_overrides() {
+        assert_eq!(
+            candidates(SystemCache::Pip, &[("PIP_CACHE_DIR", "/tmp/pip")]),
+            vec![PathBuf::from("/tmp/pip")]
+        );
+        assert_eq!(
+            candidates(SystemCache::CargoRegistry, &[("CARGO_HOME", "/opt/cargo")])[0],
+            PathBuf::from("/opt/cargo/registry/cache")
+        );
+        assert_eq!(
+            candidates(SystemCache::GoModules, &[("GOPATH", "/opt/go")]),
+            vec![PathBuf::from("/opt/go/pkg/mod")]
+        );
+        assert_eq!(
+            candidates(
+                SystemCache::GoModules,
+                &[("GOPATH", "/opt/go"), ("GOMODCACHE", "/srv/mod")]
+            ),
+            vec![PathBuf::from("/srv/mod")]
+        );
+        // Empty variables are treated as unset
+        assert_eq!(
+            candidates(SystemCache::Homebrew, &[("HOMEBREW_CACHE", "")]),
+            vec![PathBuf::from("/home/dev/.cache/Homebrew")]
+        );
+    }
+
+    #[test]
+    fn test_usage_and_direct_removal() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let registry = tmp.path().join("registry");
+        let paths = vec![registry.join("cache"), registry.join("src")];
+        for path in &paths {
+            fs::create_dir_all(path)?;
+        }
+        fs::write(paths[0].join("serde-1.0.0.crate"), vec![0u8; 300])?;
+        fs::write(paths[1].join("lib.rs"), vec![0u8; 200])?;
+
+        let usage = usage_of(SystemCache::CargoRegistry, paths.clone());
+        assert_eq!(usage.as_ref().map(|u| u.size), Some(500));
+
+        let freed = usage
+            .map(|usage| usage.clean(RemovalStrategy::Permanent))
+            .transpose()?;
+        assert_eq!(freed, Some(500));
+        assert!(paths.iter().all(|path| !path.exists()));
+        assert!(registry.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_caches_are_left_out() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        assert!(usage_of(SystemCache::Pip, vec![tmp.path().to_path_buf()]).is_none());
+        assert!(usage_of(SystemCache::Pip, vec![tmp.path().join("missing")]).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_cleanup_command_is_an_error() {
+        let err = run_command("clean-dev-dirs-no-such-tool", &["clean"]).err();
+        assert!(err.is_some_and(|e| e.to_string().contains("Failed to run")));
+    }
+}
@@ -5,24 +5,52 @@
 //! reporting, error handling, and provides detailed statistics about the
 //! cleanup operation.
 
-use anyhow::Result;
 use colored::Colorize;
-use humansize::{DECIMAL, format_size};
-use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use std::fs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
-use crate::executables;
-use crate::project::{Project, Projects};
+use crate::config::DisplayOptions;
+use crate::error::{CleanDevDirsError, Result};
+use crate::executables::{PreserveStatus, PreservedExecutable};
+use crate::i18n::Message;
+use crate::journal::Journal;
+use crate::progress::{Progress, ProgressMode, Worker};
+use crate::project::{BuildArtifacts, Project, ProjectType, Projects};
+use crate::utils::{
+    SizeUnits, force_remove_dir, format_bytes, remove_empty_dir, rename_aside, restore_aside,
+    stale_asides,
+};
+use crate::{
+    executables,
+    hooks::{self, PreCleanHook},
+    volumes,
+};
+
+/// Most build directories moved to the trash by one call when batching.
+///
+/// Bounds how many directories a single failing call takes down with it
+/// before they are retried one at a time.
+const TRASH_BATCH_SIZE: usize = 100;
 
 /// Strategy for removing build directories.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RemovalStrategy {
-    /// Permanently delete the directory (default, uses `fs::remove_dir_all`).
+    /// Permanently delete the directory, including read-only and, on Windows,
+    /// over-long paths (see [`force_remove_dir`]).
     Permanent,
 
     /// Move the directory to the system trash (recoverable deletion).
+    ///
+    /// The path is passed to the trash as it is: the Windows shell, which
+    /// moves files to the Recycle Bin, doesn't accept `\\?\` extended-length
+    /// paths, so a directory beyond `MAX_PATH` can only be removed permanently.
     Trash,
 }
 
@@ -36,6 +64,162 @@ impl RemovalStrategy {
             Self::Permanent
         }
     }
+
+    /// Remove the directory at `path` using this strategy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory could not be deleted or moved to the trash.
+    pub fn remove(self, path: &Path) -> Result<()> {
+        match self {
+            Self::Permanent => force_remove_dir(path).map_err(|e| {
+                CleanDevDirsError::cleanup(format!("failed to remove {}", path.display()))
+                    .with_source(e)
+            }),
+            Self::Trash => trash::delete(path).map_err(|e| {
+                CleanDevDirsError::cleanup(format!("failed to move {} to trash", path.display()))
+                    .with_source(e)
+            }),
+        }
+    }
+}
+
+/// How failed removals are retried.
+///
+/// Antivirus scanners and indexing services briefly lock freshly written
+/// files, which makes removals fail spuriously, mostly on Windows. A removal
+/// that failed with a [transient](CleanDevDirsError::is_transient) error is
+/// retried after [`delay`](Self::delay), doubling the wait before each further
+/// retry. Other errors, such as a missing file or a denied permission on Unix,
+/// won't go away by waiting and are returned right away.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first failed attempt
+    pub retries: u32,
+
+    /// Wait before the first retry
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Default number of retries.
+    pub const DEFAULT_RETRIES: u32 = 3;
+
+    /// Default wait before the first retry.
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+    /// Wait before retry number `retry` (starting at 1).
+    #[must_use]
+    pub const fn backoff(self, retry: u32) -> Duration {
+        self.delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+
+    /// Run `attempt` until it succeeds, fails with an error that isn't
+    /// transient, or the retries are exhausted.
+    ///
+    /// `on_retry` is called with the retry number and the wait before each retry.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt, noting how many attempts were made.
+    pub fn run<T>(
+        self,
+        mut attempt: impl FnMut() -> Result<T>,
+        mut on_retry: impl FnMut(u32, Duration),
+    ) -> Result<T> {
+        let mut retry = 0;
+        loop {
+            match attempt() {
+                Err(e) if retry < self.retries && e.is_transient() => {
+                    retry += 1;
+                    let delay = self.backoff(retry);
+                    on_retry(retry, delay);
+                    thread::sleep(delay);
+                }
+                Err(e) if retry > 0 => {
+                    return Err(e.with_note(&format!("(gave up after {} attempts)", retry + 1)));
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: Self::DEFAULT_RETRIES,
+            delay: Self::DEFAULT_DELAY,
+        }
+    }
+}
+
+/// How build directories are removed during a cleanup.
+#[derive(Clone, Copy, Debug)]
+pub struct Removal<'a> {
+    /// Whether to permanently delete or move to the system trash
+    pub strategy: RemovalStrategy,
+
+    /// How failed removals are retried
+    pub retry: RetryPolicy,
+
+    /// Whether to also remove the directories left empty by a removal, up to
+    /// (but never including) the project root
+    pub prune_empty_parents: bool,
+
+    /// Whether to move build directories to the trash in batches, after all
+    /// projects are prepared, instead of one call per directory
+    pub batch_trash: bool,
+
+    /// Whether to rename build directories aside before deleting them, so
+    /// their paths disappear at once
+    pub rename_first: bool,
+
+    /// Where each removal is recorded before it begins and after it ends,
+    /// if anywhere (see [`crate::journal`])
+    pub journal: Option<&'a Journal>,
+}
+
+impl Removal<'_> {
+    /// Whether build directories are queued and moved to the trash in batches.
+    ///
+    /// Only applies to [`RemovalStrategy::Trash`]; permanent deletion has no
+    /// per-call overhead to save.
+    #[must_use]
+    pub const fn batches_trash(self) -> bool {
+        self.batch_trash && matches!(self.strategy, RemovalStrategy::Trash)
+    }
+
+    /// Whether build directories are renamed aside before they are deleted.
+    ///
+    /// Only applies to [`RemovalStrategy::Permanent`]; a move to the trash
+    /// already takes the directory away in one step.
+    #[must_use]
+    pub const fn renames_first(self) -> bool {
+        self.rename_first && matches!(self.strategy, RemovalStrategy::Permanent)
+    }
+}
+
+/// A project that failed to clean.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CleanFailure {
+    /// Root directory of the project
+    pub root: PathBuf,
+
+    /// What went wrong, with the chain of causes
+    pub message: String,
+}
+
+impl Display for CleanFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to clean {}: {}",
+            self.root.display(),
+            self.message
+        )
+    }
 }
 
 /// Structured result returned after a cleanup operation.
@@ -46,14 +230,37 @@ pub struct CleanResult {
     /// Number of projects successfully cleaned.
     pub success_count: usize,
 
-    /// Total bytes actually freed during cleanup.
+    /// Total bytes freed during cleanup.
+    ///
+    /// This is [`verified_freed`](Self::verified_freed) when sizes were verified,
+    /// and [`estimated_freed`](Self::estimated_freed) otherwise.
     pub total_freed: u64,
 
+    /// Bytes freed according to the sizes measured during the scan.
+    pub estimated_freed: u64,
+
+    /// Bytes freed according to sizes re-measured just before each removal,
+    /// or `None` when sizes were not verified.
+    pub verified_freed: Option<u64>,
+
     /// Estimated total size before cleanup (from cached scan data).
     pub estimated_size: u64,
 
-    /// Error messages for projects that failed to clean.
-    pub errors: Vec<String>,
+    /// Projects that failed to clean.
+    pub errors: Vec<CleanFailure>,
+
+    /// Projects that were deliberately left alone, such as those vetoed by
+    /// the `pre_project_clean` hook, each with the reason.
+    pub skipped: Vec<String>,
+
+    /// Build directories moved to the trash; empty when they were deleted
+    /// permanently. Their space isn't reclaimed until the trash is emptied.
+    pub trashed: Vec<PathBuf>,
+
+    /// Projects that failed to clean because permission was denied, such as
+    /// build directories created as root by a Docker container. Some of their
+    /// build directories may already be gone.
+    pub permission_denied: Vec<Project>,
 }
 
 /// Handles the cleanup of build directories from development projects.
@@ -85,7 +292,7 @@ impl Cleaner {
     /// Clean build directories from a collection of projects.
     ///
     /// This method performs the main cleanup operation by:
-    /// 1. Setting up a progress bar for user feedback (unless `quiet`)
+    /// 1. Setting up a progress bar for user feedback (unless hidden)
     /// 2. Processing projects in parallel for efficiency
     /// 3. Collecting and reporting any errors that occur
     /// 4. Returning a [`CleanResult`] with detailed statistics
@@ -93,10 +300,24 @@ impl Cleaner {
     /// # Arguments
     ///
     /// * `projects` - A collection of projects to clean
-    /// * `keep_executables` - Whether to preserve compiled executables before cleaning
-    /// * `quiet` - When `true`, suppresses all human-readable output (progress bars, messages).
-    ///   Used by the `--json` flag so that only the final JSON is printed.
-    /// * `removal_strategy` - Whether to permanently delete or move to system trash
+    /// * `keep_executables` - `Some(keep_latest_only)` to preserve compiled executables
+    ///   before cleaning, pruning older preserved copies when `keep_latest_only` is set;
+    ///   `None` to skip preservation
+    /// * `progress` - How progress is reported. [`ProgressMode::Hidden`] suppresses all
+    ///   human-readable output (progress bars, messages) so that `--json` prints only the
+    ///   final JSON; [`ProgressMode::Plain`] prints periodic status lines for CI logs.
+    /// * `removal` - Whether to permanently delete or move to system trash, and how
+    ///   failed removals are retried
+    /// * `pre_clean_hook` - Optional `pre_project_clean` hook run before each project is
+    ///   cleaned; a failing hook skips that project. Its stdout goes to stderr when
+    ///   [`PreCleanHook::stdout_to_stderr`] is set, as it should be for structured output.
+    /// * `verify_sizes` - Re-measure each build directory just before removing it, instead
+    ///   of trusting the size found during the scan
+    /// * `display` - The language of the progress messages and the units of sizes
+    ///
+    /// When [`Removal::batches_trash`] is set, the projects are only prepared in
+    /// parallel (hooks, executables, sizes), and their build directories are then
+    /// moved to the trash together, see [`trash_in_batches`].
     ///
     /// # Panics
     ///
@@ -123,133 +344,204 @@ impl Cleaner {
     #[must_use]
     pub fn clean_projects(
         projects: Projects,
-        keep_executables: bool,
-        quiet: bool,
-        removal_strategy: RemovalStrategy,
+        keep_executables: Option<bool>,
+        progress: ProgressMode,
+        removal: Removal<'_>,
+        pre_clean_hook: Option<PreCleanHook<'_>>,
+        verify_sizes: bool,
+        display: &DisplayOptions,
     ) -> CleanResult {
         let total_projects = projects.len();
         let total_size: u64 = projects.get_total_size();
 
-        let progress = if quiet {
-            ProgressBar::hidden()
-        } else {
-            let action = match removal_strategy {
-                RemovalStrategy::Permanent => "Starting cleanup...",
-                RemovalStrategy::Trash => "Moving to trash...",
-            };
-            println!("\n{}", action.cyan());
-
-            let pb = ProgressBar::new(total_projects as u64);
-            if let Ok(style) = ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
-            {
-                pb.set_style(style.progress_chars("█▉▊▋▌▍▎▏  "));
-            }
-            pb
-        };
+        if progress != ProgressMode::Hidden {
+            println!("\n{}", display.text(start_message(removal.strategy)).cyan());
+        }
+        let progress = progress.bar(total_projects as u64);
+        progress.set_message(freed_message(0, display.units));
 
-        let cleaned_size = Arc::new(Mutex::new(0u64));
-        let errors = Arc::new(Mutex::new(Vec::new()));
+        let cleaned_size = Arc::new(Mutex::new(FreedSize::default()));
+        let errors = Mutex::new(Vec::new());
+        let skipped = Mutex::new(Vec::new());
+        let trashed = Mutex::new(Vec::new());
+        let permission_denied = Mutex::new(Vec::new());
+        let queue = removal.batches_trash().then(|| Mutex::new(Vec::new()));
 
-        // Clean projects in parallel
+        // Clean projects in parallel, each with its own spinner below the overall bar
         projects.into_par_iter().for_each(|project| {
-            let result = clean_single_project(&project, keep_executables, removal_strategy);
+            let worker = progress.worker();
+            let result = clean_single_project(
+                &project,
+                keep_executables,
+                removal,
+                pre_clean_hook,
+                verify_sizes,
+                queue.as_ref(),
+                &worker,
+            );
+            drop(worker);
 
-            let action = match removal_strategy {
-                RemovalStrategy::Permanent => "Cleaned",
-                RemovalStrategy::Trash => "Trashed",
+            let record_removed = |freed_size, removed: Vec<PathBuf>| {
+                if let Ok(mut size) = cleaned_size.lock() {
+                    size.add(freed_size);
+                    progress.set_message(freed_message(size.total(), display.units));
+                }
+                if matches!(removal.strategy, RemovalStrategy::Trash)
+                    && let Ok(mut trashed) = trashed.lock()
+                {
+                    trashed.extend(removed);
+                }
             };
 
             match result {
-                Ok(freed_size) => {
-                    if let Ok(mut size) = cleaned_size.lock() {
-                        *size += freed_size;
+                Ok(Cleaned::Skipped(reason)) => {
+                    if let Ok(mut skipped) = skipped.lock() {
+                        skipped.push(format!("Skipped {}: {reason}", project.root_path.display()));
                     }
-
-                    progress.set_message(format!(
-                        "{action} {} ({})",
-                        project
-                            .root_path
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("unknown"),
-                        format_size(freed_size, DECIMAL)
-                    ));
                 }
-                Err(e) => {
+                Ok(Cleaned::Removed(freed_size, removed)) => record_removed(freed_size, removed),
+                Err(failure) => {
+                    // What went before the failure is gone all the same
+                    record_removed(failure.freed, failure.removed);
+                    let e = failure.error;
                     if let Ok(mut errs) = errors.lock() {
-                        errs.push(format!(
-                            "Failed to clean {}: {e}",
-                            project.root_path.display()
-                        ));
+                        errs.push(CleanFailure {
+                            root: project.root_path.clone(),
+                            message: format!("{e:#}"),
+                        });
+                    }
+                    if e.is_permission_denied()
+                        && let Ok(mut denied) = permission_denied.lock()
+                    {
+                        denied.push(project);
                     }
                 }
             }
 
-            progress.inc(1);
+            progress.inc();
         });
 
-        let finish_msg = match removal_strategy {
-            RemovalStrategy::Permanent => "[OK] Cleanup complete",
-            RemovalStrategy::Trash => "[OK] Moved to trash",
+        if let Some(queue) = queue {
+            let queued = queue.into_inner().unwrap_or_default();
+            let (failed, denied) = trash_queued(
+                queued,
+                removal,
+                &progress,
+                &cleaned_size,
+                &trashed,
+                display.units,
+            );
+            if let Ok(mut errs) = errors.lock() {
+                errs.extend(failed);
+            }
+            if let Ok(mut permission_denied) = permission_denied.lock() {
+                permission_denied.extend(denied);
+            }
+        }
+
+        let finish_msg = match removal.strategy {
+            RemovalStrategy::Permanent => Message::CleanupComplete,
+            RemovalStrategy::Trash => Message::MovedToTrashDone,
         };
-        progress.finish_with_message(finish_msg);
+        progress.finish_with_message(display.text(finish_msg));
 
-        let final_cleaned_size = cleaned_size.lock().map_or(0, |s| *s);
-        let errors = Arc::try_unwrap(errors)
-            .unwrap_or_else(|arc| {
-                arc.lock()
-                    .map_or_else(|_| Mutex::new(Vec::new()), |g| Mutex::new(g.clone()))
-            })
-            .into_inner()
-            .unwrap_or_default();
+        let freed = cleaned_size
+            .lock()
+            .map_or_else(|_| FreedSize::default(), |s| *s);
+        let errors = errors.into_inner().unwrap_or_default();
 
-        let success_count = total_projects - errors.len();
+        let skipped = skipped.into_inner().unwrap_or_default();
+        let success_count = total_projects - errors.len() - skipped.len();
 
         CleanResult {
             success_count,
-            total_freed: final_cleaned_size,
+            total_freed: freed.total(),
+            estimated_freed: freed.estimated,
+            verified_freed: freed.verified,
             estimated_size: total_size,
             errors,
+            skipped,
+            trashed: trashed.into_inner().unwrap_or_default(),
+            permission_denied: permission_denied.into_inner().unwrap_or_default(),
         }
     }
 
     /// Print a human-readable cleanup summary to stdout.
     ///
     /// This is called from `main` when `--json` is **not** active.
-    pub fn print_summary(result: &CleanResult) {
+    pub fn print_summary(result: &CleanResult, display: &DisplayOptions) {
+        let lang = display.lang;
         if !result.errors.is_empty() {
-            println!("\n{}", "[!] Some errors occurred during cleanup:".yellow());
+            println!("\n{}", Message::SomeErrors.text(lang).yellow());
             for error in &result.errors {
-                eprintln!("  {}", error.red());
+                eprintln!("  {}", error.to_string().red());
             }
         }
 
-        println!("\n{}", "Cleanup Summary:".bold());
+        println!("\n{}", Message::CleanupSummary.text(lang).bold());
+        let cleaned = result.success_count.to_string().green();
         println!(
-            "  [OK] Successfully cleaned: {} projects",
-            result.success_count.to_string().green()
+            "  {}",
+            Message::Cleaned.format(lang, &[("count", &cleaned)])
         );
 
         if !result.errors.is_empty() {
+            let failed = result.errors.len().to_string().red();
             println!(
-                "  [FAIL] Failed to clean: {} projects",
-                result.errors.len().to_string().red()
+                "  {}",
+                Message::FailedToClean.format(lang, &[("count", &failed)])
             );
         }
+        if !result.skipped.is_empty() {
+            let skipped = result.skipped.len().to_string().yellow();
+            println!(
+                "  {}",
+                Message::Skipped.format(lang, &[("count", &skipped)])
+            );
+            for reason in &result.skipped {
+                println!("    {}", reason.dimmed());
+            }
+        }
 
-        println!(
-            "  Total space freed: {}",
-            format_size(result.total_freed, DECIMAL)
-                .bright_green()
-                .bold()
-        );
+        let freed = display.bytes(result.total_freed).bright_green().bold();
+        let verified = if result.verified_freed.is_some() {
+            format!(" {}", Message::Verified.text(lang))
+        } else {
+            String::new()
+        };
+        if result.trashed.is_empty() {
+            println!(
+                "  {}{verified}",
+                Message::SpaceFreed.format(lang, &[("size", &freed)])
+            );
+        } else {
+            println!(
+                "  {}{verified} {}",
+                Message::MovedToTrash.format(lang, &[("size", &freed)]),
+                Message::NotYetReclaimed.text(lang).yellow()
+            );
+            if crate::trash_bin::SUPPORTED {
+                println!("  {}", Message::RunTrashEmpty.text(lang));
+            } else {
+                println!("  {}", Message::EmptyTrash.text(lang));
+            }
+        }
 
-        if result.total_freed != result.estimated_size {
-            let difference = result.estimated_size.abs_diff(result.total_freed);
+        if let Some(verified) = result.verified_freed
+            && verified != result.estimated_freed
+        {
+            let difference = display
+                .bytes(result.estimated_freed.abs_diff(verified))
+                .yellow();
             println!(
-                "  Difference from estimate: {}",
-                format_size(difference, DECIMAL).yellow()
+                "  {}",
+                Message::ScanEstimate.format(
+                    lang,
+                    &[
+                        ("size", &display.bytes(result.estimated_freed)),
+                        ("difference", &difference),
+                    ]
+                )
             );
         }
     }
@@ -258,27 +550,43 @@ impl Cleaner {
 /// Clean the build directory for a single project.
 ///
 /// This function handles the cleanup of an individual project's build directory.
-/// It calculates the actual size before deletion and then removes the entire
-/// directory tree, either permanently or by moving it to the system trash.
+/// It removes the entire directory tree, either permanently or by moving it to
+/// the system trash, optionally re-measuring its size just before removal.
 ///
 /// # Arguments
 ///
 /// * `project` - The project whose build directory should be cleaned
-/// * `keep_executables` - Whether to preserve compiled executables before cleaning
-/// * `removal_strategy` - Whether to permanently delete or move to system trash
+/// * `keep_executables` - `Some(keep_latest_only)` to preserve compiled executables
+///   before cleaning, `None` to skip preservation
+/// * `removal` - Whether to permanently delete or move to system trash, and how
+///   failed removals are retried
+/// * `pre_clean_hook` - Optional `pre_project_clean` hook to run first
+/// * `verify_sizes` - Re-measure each build directory just before removing it
+/// * `queue` - When batching trash moves, where the build directories are queued
+///   instead of being removed right away
+/// * `worker` - Progress spinner updated with the step currently running
 ///
 /// # Returns
 ///
-/// - `Ok(u64)` - The number of bytes freed by the cleanup
-/// - `Err(anyhow::Error)` - If the cleanup operation failed
+/// - `Ok(Cleaned::Removed(..))` - The number of bytes freed by the cleanup,
+///   and the build directories that were removed (none of the queued ones)
+/// - `Ok(Cleaned::Skipped(..))` - If the project is protected, or the
+///   `pre_project_clean` hook vetoed it
+/// - `Err(PartialFailure { .. })` - If the cleanup operation failed, with the
+///   build directories removed before the failure
 ///
 /// # Behavior
 ///
-/// 1. Checks if the build directory exists (returns 0 if not)
+/// 1. Skips a protected project, then runs the `pre_project_clean` hook, if
+///    any, and skips the project if it exits with a non-zero status
 /// 2. Optionally preserves compiled executables
-/// 3. Calculates the actual size of the directory before deletion
-/// 4. Removes the directory (permanently or via trash, based on `removal_strategy`)
-/// 5. Returns the amount of space freed
+/// 3. Skips build directories that no longer exist, and a Go project's `bin/`
+///    when executables are preserved
+/// 4. Re-measures each directory before deletion when `verify_sizes` is set
+/// 5. Removes the directory (permanently or via trash, based on `removal`),
+///    retrying failed removals with exponential backoff, or queues it; see
+///    [`remove_build_dir`]
+/// 6. Returns the amount of space freed
 ///
 /// # Error Conditions
 ///
@@ -287,24 +595,53 @@ impl Cleaner {
 /// - Files within the directory are locked or in use by other processes
 /// - The file system encounters I/O errors during deletion
 /// - The system trash is not available (when using [`RemovalStrategy::Trash`])
+/// - The `pre_project_clean` hook can't be started
 fn clean_single_project(
     project: &Project,
-    keep_executables: bool,
-    removal_strategy: RemovalStrategy,
-) -> Result<u64> {
+    keep_executables: Option<bool>,
+    removal: Removal<'_>,
+    pre_clean_hook: Option<PreCleanHook<'_>>,
+    verify_sizes: bool,
+    queue: Option<&Mutex<Vec<QueuedDir>>>,
+    worker: &Worker,
+) -> std::result::Result<Cleaned, PartialFailure> {
+    // Protected projects are normally filtered out earlier; never touch them here.
+    if project.protected {
+        return Ok(Cleaned::Skipped("the project is protected".to_string()));
+    }
+
+    if let Some(hook) = pre_clean_hook {
+        worker.set_message(format!(
+            "Running pre_project_clean for {}",
+            project.root_path.display()
+        ));
+        if let Some(status) =
+            hooks::run_pre_project_clean(hook.template, project, hook.stdout_to_stderr)?
+        {
+            return Ok(Cleaned::Skipped(format!(
+                "pre_project_clean hook exited with {status}"
+            )));
+        }
+    }
+
     // Preserve executables before deletion if requested
-    if keep_executables {
-        match executables::preserve_executables(project) {
+    if let Some(keep_latest_only) = keep_executables {
+        worker.set_message(format!(
+            "Preserving executables from {}",
+            project.root_path.display()
+        ));
+        match executables::preserve_executables(project, keep_latest_only) {
             Ok(preserved) => {
                 if !preserved.is_empty() {
                     eprintln!(
-                        "  Preserved {} executable(s) from {}",
+                        "  Preserved {} executable(s) from {}{}",
                         preserved.len(),
                         project
                             .root_path
                             .file_name()
                             .and_then(|n| n.to_str())
-                            .unwrap_or("unknown")
+                            .unwrap_or("unknown"),
+                        preservation_details(&preserved)
                     );
                 }
             }
@@ -317,7 +654,11 @@ fn clean_single_project(
         }
     }
 
-    let mut total_freed = 0u64;
+    let mut freed = FreedSize {
+        estimated: 0,
+        verified: verify_sizes.then_some(0),
+    };
+    let mut removed = Vec::new();
 
     for artifact in &project.build_arts {
         let build_dir = &artifact.path;
@@ -326,20 +667,445 @@ fn clean_single_project(
             continue;
         }
 
-        // Get the actual size before deletion (might be different from the cached size)
-        total_freed += crate::utils::calculate_dir_size(build_dir);
+        // A Go project's bin/ holds the very executables being kept
+        if keep_executables.is_some()
+            && executables::holds_preserved_executables(project, build_dir)
+        {
+            continue;
+        }
 
-        // Remove the build directory using the chosen strategy
-        match removal_strategy {
-            RemovalStrategy::Permanent => fs::remove_dir_all(build_dir)?,
-            RemovalStrategy::Trash => {
-                trash::delete(build_dir)
-                    .map_err(|e| anyhow::anyhow!("failed to move to trash: {e}"))?;
+        // The cached size from the scan may be stale; re-measure it if requested
+        let verified = verify_sizes.then(|| {
+            worker.set_message(format!("Measuring {}", build_dir.display()));
+            crate::utils::calculate_dir_size(build_dir)
+        });
+
+        if let Some(queue) = queue {
+            if let Ok(mut queue) = queue.lock() {
+                queue.push(QueuedDir {
+                    root: project.root_path.clone(),
+                    kind: project.kind.clone(),
+                    name: project.name.clone(),
+                    path: build_dir.clone(),
+                    freed: FreedSize {
+                        estimated: artifact.size,
+                        verified,
+                    },
+                });
             }
+            continue;
+        }
+
+        if let Err(error) = remove_build_dir(build_dir, removal, worker) {
+            return Err(PartialFailure {
+                error,
+                freed,
+                removed,
+            });
+        }
+
+        freed.add(FreedSize {
+            estimated: artifact.size,
+            verified,
+        });
+        removed.push(build_dir.clone());
+
+        if removal.prune_empty_parents {
+            remove_empty_parents(build_dir, &project.root_path);
+        }
+    }
+
+    Ok(Cleaned::Removed(freed, removed))
+}
+
+/// What cleaning a single project came to.
+#[derive(Debug)]
+enum Cleaned {
+    /// Its build directories were removed (or queued), freeing this much
+    Removed(FreedSize, Vec<PathBuf>),
+
+    /// It was deliberately left alone, for this reason
+    Skipped(String),
+}
+
+/// A project whose cleanup failed, possibly after some of its build
+/// directories were already removed.
+#[derive(Debug)]
+struct PartialFailure {
+    /// Why the cleanup failed
+    error: CleanDevDirsError,
+
+    /// Space freed by the build directories removed before the failure
+    freed: FreedSize,
+
+    /// The build directories removed before the failure
+    removed: Vec<PathBuf>,
+}
+
+impl From<CleanDevDirsError> for PartialFailure {
+    fn from(error: CleanDevDirsError) -> Self {
+        Self {
+            error,
+            freed: FreedSize::default(),
+            removed: Vec::new(),
         }
     }
+}
+
+/// Remove `build_dir` using the strategy of `removal`, retrying failed attempts.
+///
+/// With [`Removal::renames_first`], the directory is renamed to a hidden
+/// sibling first (see [`rename_aside`]) and deleted from there, so a build
+/// running at the same time never sees it half-removed. If the rename fails,
+/// e.g. because the directory is a mount point, it is deleted in place. If
+/// the removal fails, whatever is left is renamed back. Siblings left aside
+/// by earlier runs that were interrupted are deleted first.
+///
+/// With a journal, the removal is recorded before it begins and after it
+/// ends. A directory whose removal can't be recorded is left alone.
+fn remove_build_dir(build_dir: &Path, removal: Removal<'_>, worker: &Worker) -> Result<()> {
+    if removal.renames_first() {
+        remove_stale_asides(build_dir, removal, worker);
+    }
+
+    let Some(journal) = removal.journal else {
+        return remove_unjournaled(build_dir, removal, worker);
+    };
 
-    Ok(total_freed)
+    journal
+        .removing(build_dir, removal.strategy)
+        .map_err(|e| CleanDevDirsError::cleanup("not removing it").with_source(e))?;
+    let result = remove_unjournaled(build_dir, removal, worker);
+    let error = result.as_ref().err().map(|e| format!("{e:#}"));
+    journal.removed(build_dir, removal.strategy, error);
+    result
+}
+
+/// Delete what earlier runs renamed aside from `build_dir` but didn't get to
+/// delete (see [`stale_asides`]), recording each removal in the journal.
+///
+/// Failures are only recorded: the leftovers aren't part of the project's
+/// build directories, and the next run tries again.
+fn remove_stale_asides(build_dir: &Path, removal: Removal<'_>, worker: &Worker) {
+    for stale in stale_asides(build_dir) {
+        worker.set_message(format!("Removing leftover {}", stale.display()));
+        if let Some(journal) = removal.journal
+            && journal.removing(&stale, removal.strategy).is_err()
+        {
+            continue;
+        }
+        let result = force_remove_dir(&stale);
+        if let Some(journal) = removal.journal {
+            journal.removed(
+                &stale,
+                removal.strategy,
+                result.err().map(|e| e.to_string()),
+            );
+        }
+    }
+}
+
+/// Remove `build_dir` like [`remove_build_dir`], without recording it.
+fn remove_unjournaled(build_dir: &Path, removal: Removal<'_>, worker: &Worker) -> Result<()> {
+    let action = match removal.strategy {
+        RemovalStrategy::Permanent => "Removing",
+        RemovalStrategy::Trash => "Moving to trash",
+    };
+    worker.set_message(format!("{action} {}", build_dir.display()));
+
+    let renamed = if removal.renames_first() {
+        rename_aside(build_dir).ok()
+    } else {
+        None
+    };
+    let target = renamed.as_deref().unwrap_or(build_dir);
+
+    removal
+        .retry
+        .run(
+            || removal.strategy.remove(target),
+            |retry, delay| {
+                worker.set_message(format!(
+                    "Retrying {} in {}ms ({retry}/{})",
+                    build_dir.display(),
+                    delay.as_millis(),
+                    removal.retry.retries
+                ));
+            },
+        )
+        .map_err(|e| match renamed.as_deref() {
+            Some(aside) if restore_aside(aside, build_dir).is_ok() => {
+                e.with_note(&format!("(renamed from {} and back)", build_dir.display()))
+            }
+            Some(_) => e.with_note(&format!("(renamed from {})", build_dir.display())),
+            None => e,
+        })
+}
+
+/// A build directory waiting to be moved to the trash in a batch.
+#[derive(Debug)]
+struct QueuedDir {
+    /// Root of the project the directory belongs to
+    root: PathBuf,
+
+    /// Type of the project the directory belongs to
+    kind: ProjectType,
+
+    /// Name of the project the directory belongs to
+    name: Option<String>,
+
+    /// The build directory
+    path: PathBuf,
+
+    /// Space freed once it is moved
+    freed: FreedSize,
+}
+
+/// Move the `queued` build directories to the trash in batches and record
+/// the outcome in the cleanup totals.
+///
+/// Returns one error message per project with a directory that couldn't be
+/// moved; its other directories are moved and counted all the same. Also
+/// returns the projects whose directories couldn't be moved for lack of
+/// permission, with just those directories.
+fn trash_queued(
+    queued: Vec<QueuedDir>,
+    removal: Removal<'_>,
+    progress: &Progress,
+    cleaned_size: &Mutex<FreedSize>,
+    trashed: &Mutex<Vec<PathBuf>>,
+    units: SizeUnits,
+) -> (Vec<CleanFailure>, Vec<Project>) {
+    let worker = progress.worker();
+    let (moved, failed) = trash_in_batches(queued, removal.retry, &worker, |paths| {
+        if let Some(journal) = removal.journal {
+            for path in paths {
+                journal.removing(path, removal.strategy).map_err(|e| {
+                    CleanDevDirsError::cleanup("not moving to trash").with_source(e)
+                })?;
+            }
+        }
+        let result = trash::delete_all(paths).map_err(|e| {
+            CleanDevDirsError::cleanup(format!(
+                "failed to move {} directories to trash",
+                paths.len()
+            ))
+            .with_source(e)
+        });
+        if let Some(journal) = removal.journal {
+            let error = result.as_ref().err().map(|e| format!("{e:#}"));
+            for path in paths {
+                // A failed call may still have moved part of its batch
+                let error = error.clone().filter(|_| path.exists());
+                journal.removed(path, removal.strategy, error);
+            }
+        }
+        result
+    });
+    drop(worker);
+
+    for dir in moved {
+        if removal.prune_empty_parents {
+            remove_empty_parents(&dir.path, &dir.root);
+        }
+        if let Ok(mut size) = cleaned_size.lock() {
+            size.add(dir.freed);
+            progress.set_message(freed_message(size.total(), units));
+        }
+        if let Ok(mut trashed) = trashed.lock() {
+            trashed.push(dir.path);
+        }
+    }
+
+    let mut by_project: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+    let mut denied: BTreeMap<PathBuf, Project> = BTreeMap::new();
+    for (dir, e) in failed {
+        if e.is_permission_denied() {
+            denied
+                .entry(dir.root.clone())
+                .or_insert_with(|| Project::new(dir.kind, dir.root.clone(), Vec::new(), dir.name))
+                .build_arts
+                .push(BuildArtifacts {
+                    path: dir.path,
+                    size: dir.freed.estimated,
+                    files: 0,
+                    dirs: 0,
+                    newest_file: None,
+                    estimated: false,
+                });
+        }
+        by_project
+            .entry(dir.root)
+            .or_default()
+            .push(format!("{e:#}"));
+    }
+    let errors = by_project
+        .into_iter()
+        .map(|(root, errors)| CleanFailure {
+            root,
+            message: errors.join("; "),
+        })
+        .collect();
+    (errors, denied.into_values().collect())
+}
+
+/// Move the `queued` build directories to the trash with `move_batch`, a
+/// few calls per volume instead of one per directory.
+///
+/// Some trash backends have a high fixed cost per call (on macOS every call
+/// goes through the Finder), which dominates when hundreds of directories are
+/// moved. The directories are grouped by the volume they're on and moved up
+/// to [`TRASH_BATCH_SIZE`] at a time. When a call fails, the directories of
+/// that batch still in place are moved one by one under `retry`, so a single
+/// directory that can't be moved doesn't fail the rest.
+///
+/// Returns the directories that were moved, and those that weren't with the
+/// error of their last attempt.
+fn trash_in_batches(
+    queued: Vec<QueuedDir>,
+    retry: RetryPolicy,
+    worker: &Worker,
+    move_batch: impl Fn(&[&Path]) -> Result<()>,
+) -> (Vec<QueuedDir>, Vec<(QueuedDir, CleanDevDirsError)>) {
+    let mut by_volume: BTreeMap<Option<PathBuf>, Vec<QueuedDir>> = BTreeMap::new();
+    for dir in queued {
+        by_volume
+            .entry(volumes::volume_of(&dir.path))
+            .or_default()
+            .push(dir);
+    }
+
+    let mut moved = Vec::new();
+    let mut failed = Vec::new();
+    for (volume, mut dirs) in by_volume {
+        let volume =
+            volume.map_or_else(|| "unknown volume".to_string(), |v| v.display().to_string());
+        while !dirs.is_empty() {
+            let rest = dirs.split_off(dirs.len().min(TRASH_BATCH_SIZE));
+            let batch = std::mem::replace(&mut dirs, rest);
+
+            worker.set_message(format!(
+                "Moving {} directories on {volume} to trash",
+                batch.len()
+            ));
+            let paths: Vec<&Path> = batch.iter().map(|dir| dir.path.as_path()).collect();
+            if move_batch(&paths).is_ok() {
+                moved.extend(batch);
+                continue;
+            }
+
+            for dir in batch {
+                // A failed call may still have moved part of its batch
+                if !dir.path.exists() {
+                    moved.push(dir);
+                    continue;
+                }
+
+                worker.set_message(format!("Moving to trash {}", dir.path.display()));
+                let attempt = retry.run(
+                    || move_batch(&[dir.path.as_path()]),
+                    |attempt, delay| {
+                        worker.set_message(format!(
+                            "Retrying {} in {}ms ({attempt}/{})",
+                            dir.path.display(),
+                            delay.as_millis(),
+                            retry.retries
+                        ));
+                    },
+                );
+                match attempt {
+                    Ok(()) => moved.push(dir),
+                    Err(e) => failed.push((dir, e)),
+                }
+            }
+        }
+    }
+
+    (moved, failed)
+}
+
+/// Remove the directories above `path` that are now empty, stopping at the
+/// first one that isn't and never removing `root` or anything outside it.
+///
+/// Empty directories are deleted permanently with either removal strategy:
+/// there's nothing in them to recover, and restoring a trashed build
+/// directory recreates its parents. Failures are ignored, since the build
+/// directory itself is already gone.
+fn remove_empty_parents(path: &Path, root: &Path) {
+    for parent in path
+        .ancestors()
+        .skip(1)
+        .take_while(|parent| *parent != root && parent.starts_with(root))
+    {
+        // `remove_dir` refuses non-empty directories, so nothing is lost
+        // even if a file appears in the meantime.
+        if remove_empty_dir(parent).is_err() {
+            break;
+        }
+    }
+}
+
+/// Bytes freed by a cleanup, as estimated by the scan and optionally verified.
+#[derive(Clone, Copy, Debug, Default)]
+struct FreedSize {
+    estimated: u64,
+    verified: Option<u64>,
+}
+
+impl FreedSize {
+    fn add(&mut self, other: Self) {
+        self.estimated += other.estimated;
+        self.verified = match (self.verified, other.verified) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// The verified size if known, otherwise the estimate.
+    fn total(self) -> u64 {
+        self.verified.unwrap_or(self.estimated)
+    }
+}
+
+/// The message shown when a cleanup with `strategy` starts.
+const fn start_message(strategy: RemovalStrategy) -> Message {
+    match strategy {
+        RemovalStrategy::Permanent => Message::StartingCleanup,
+        RemovalStrategy::Trash => Message::MovingToTrash,
+    }
+}
+
+/// Overall progress message with the space freed so far, e.g. `"1.20 GB freed"`.
+fn freed_message(freed: u64, units: SizeUnits) -> String {
+    format!("{} freed", format_bytes(freed, units))
+}
+
+/// Summarize skipped identical copies and pruned old copies, e.g. `" (2 unchanged)"`.
+///
+/// Returns an empty string when every executable was simply copied.
+fn preservation_details(preserved: &[PreservedExecutable]) -> String {
+    let unchanged = preserved
+        .iter()
+        .filter(|p| p.status == PreserveStatus::Unchanged)
+        .count();
+    let pruned: usize = preserved.iter().map(|p| p.pruned.len()).sum();
+
+    let mut details = Vec::new();
+    if unchanged > 0 {
+        details.push(format!("{unchanged} unchanged"));
+    }
+    if pruned > 0 {
+        details.push(format!(
+            "{pruned} older cop{} pruned",
+            if pruned == 1 { "y" } else { "ies" }
+        ));
+    }
+
+    if details.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", details.join(", "))
+    }
 }
 
 impl Default for Cleaner {
@@ -355,3 +1121,353 @@ impl Default for Cleaner {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, io};
+
+    #[test]
+    fn test_retry_backoff_doubles() {
+        let policy = RetryPolicy {
+            retries: 3,
+            delay: Duration::from_millis(100),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_retry_succeeds_after_transient_failures() {
+        let policy = RetryPolicy {
+            retries: 3,
+            delay: Duration::from_millis(1),
+        };
+        let mut calls = 0;
+        let mut waits = Vec::new();
+
+        let result = policy.run(
+            || {
+                calls += 1;
+                if calls < 3 {
+                    return Err(CleanDevDirsError::cleanup("file is locked")
+                        .with_source(io::Error::from(io::ErrorKind::ResourceBusy)));
+                }
+                Ok(calls)
+            },
+            |retry, delay| waits.push((retry, delay)),
+        );
+
+        assert_eq!(result.ok(), Some(3));
+        assert_eq!(
+            waits,
+            vec![(1, Duration::from_millis(1)), (2, Duration::from_millis(2))]
+        );
+    }
+
+    #[test]
+    fn test_retry_gives_up_when_exhausted() {
+        let policy = RetryPolicy {
+            retries: 2,
+            delay: Duration::ZERO,
+        };
+        let mut calls = 0;
+
+        let result: Result<()> = policy.run(
+            || {
+                calls += 1;
+                Err(CleanDevDirsError::cleanup("file is locked")
+                    .with_source(io::Error::from(io::ErrorKind::ResourceBusy)))
+            },
+            |_, _| {},
+        );
+
+        assert_eq!(calls, 3);
+        let message = result.err().map(|e| e.to_string());
+        assert_eq!(
+            message.as_deref(),
+            Some("file is locked (gave up after 3 attempts)")
+        );
+    }
+
+    #[test]
+    fn test_retry_skips_errors_that_are_not_transient() {
+        let policy = RetryPolicy {
+            retries: 3,
+            delay: Duration::ZERO,
+        };
+        let mut calls = 0;
+
+        let result: Result<()> = policy.run(
+            || {
+                calls += 1;
+                Err(CleanDevDirsError::cleanup("denied")
+                    .with_source(io::Error::from(io::ErrorKind::PermissionDenied)))
+            },
+            |_, _| {},
+        );
+
+        assert_eq!(calls, 1);
+        assert_eq!(
+            result.err().map(|e| e.to_string()).as_deref(),
+            Some("denied")
+        );
+    }
+
+    #[test]
+    fn test_no_retries_keeps_error_unchanged() {
+        let policy = RetryPolicy {
+            retries: 0,
+            delay: Duration::ZERO,
+        };
+        let result: Result<()> =
+            policy.run(|| Err(CleanDevDirsError::cleanup("denied")), |_, _| {});
+        assert_eq!(
+            result.err().map(|e| e.to_string()).as_deref(),
+            Some("denied")
+        );
+    }
+
+    #[test]
+    fn test_remove_empty_parents_stops_at_root_and_non_empty() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let root = tmp.path().join("app");
+        fs::create_dir_all(root.join("vendor/ruby/3.3"))?;
+        fs::create_dir_all(root.join("dist/wrapper"))?;
+        fs::write(root.join("dist/keep.txt"), "kept")?;
+
+        remove_empty_parents(&root.join("vendor/ruby/3.3/bundle"), &root);
+        remove_empty_parents(&root.join("dist/wrapper/out"), &root);
+
+        assert!(!root.join("vendor").exists());
+        assert!(!root.join("dist/wrapper").exists());
+        assert!(root.join("dist/keep.txt").exists());
+
+        // The project root is kept even when it ends up empty
+        fs::remove_dir_all(root.join("dist"))?;
+        fs::create_dir_all(root.join("build"))?;
+        remove_empty_parents(&root.join("build/out"), &root);
+        assert!(root.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_trash_batch_retries_each_directory() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let queued = ["app", "lib", "locked"]
+            .into_iter()
+            .map(|name| {
+                let path = tmp.path().join(name).join("target");
+                fs::create_dir_all(&path)?;
+                Ok(QueuedDir {
+                    root: tmp.path().join(name),
+                    kind: ProjectType::Rust,
+                    name: None,
+                    path,
+                    freed: FreedSize::default(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let calls = std::cell::RefCell::new(Vec::new());
+        let policy = RetryPolicy {
+            retries: 1,
+            delay: Duration::ZERO,
+        };
+        let worker = ProgressMode::Hidden.bar(1).worker();
+        let (moved, failed) = trash_in_batches(queued, policy, &worker, |paths| {
+            calls.borrow_mut().push(paths.len());
+            if paths
+                .iter()
+                .any(|path| path.starts_with(tmp.path().join("locked")))
+            {
+                return Err(CleanDevDirsError::cleanup("directory is in use")
+                    .with_source(io::Error::from(io::ErrorKind::ResourceBusy)));
+            }
+            for path in paths {
+                fs::remove_dir_all(path)
+                    .map_err(|e| CleanDevDirsError::cleanup("remove failed").with_source(e))?;
+            }
+            Ok(())
+        });
+
+        // One call for the whole batch, then one per directory plus a retry
+        assert_eq!(calls.into_inner(), vec![3, 1, 1, 1, 1]);
+        let moved: Vec<_> = moved.iter().map(|dir| dir.root.clone()).collect();
+        assert_eq!(moved, vec![tmp.path().join("app"), tmp.path().join("lib")]);
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].0.path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_failure_keeps_the_directories_removed_before_it() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let target = tmp.path().join("target");
+        fs::create_dir_all(&target)?;
+        fs::write(target.join("app"), "binary")?;
+        // A file where a directory is expected can't be removed as one
+        let broken = tmp.path().join("dist");
+        fs::write(&broken, "file")?;
+        let artifact = |path: &Path, size| BuildArtifacts {
+            path: path.to_path_buf(),
+            size,
+            files: 1,
+            dirs: 0,
+            newest_file: None,
+            estimated: false,
+        };
+        let project = Project::new(
+            ProjectType::Node,
+            tmp.path().to_path_buf(),
+            vec![artifact(&target, 6), artifact(&broken, 4)],
+            None,
+        );
+        let removal = Removal {
+            strategy: RemovalStrategy::Permanent,
+            retry: RetryPolicy::default(),
+            prune_empty_parents: false,
+            batch_trash: false,
+            rename_first: false,
+            journal: None,
+        };
+
+        let worker = ProgressMode::Hidden.bar(1).worker();
+        let result = clean_single_project(&project, None, removal, None, false, None, &worker);
+        let Err(failure) = result else {
+            anyhow::bail!("removing a file as a directory should fail");
+        };
+        assert_eq!(failure.removed, vec![target.clone()]);
+        assert_eq!(failure.freed.total(), 6);
+        assert!(!target.exists());
+
+        fs::create_dir_all(&target)?;
+        let result = Cleaner::clean_projects(
+            Projects::from(vec![project]),
+            None,
+            ProgressMode::Hidden,
+            removal,
+            None,
+            false,
+            &DisplayOptions::default(),
+        );
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.total_freed, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_protected_project_is_skipped() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let target = tmp.path().join("target");
+        fs::create_dir_all(&target)?;
+        fs::write(target.join("app"), "binary")?;
+        let mut project = Project::new(
+            ProjectType::Rust,
+            tmp.path().to_path_buf(),
+            vec![BuildArtifacts {
+                path: target.clone(),
+                size: 6,
+                files: 1,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            None,
+        );
+        project.protected = true;
+
+        let result = Cleaner::clean_projects(
+            Projects::from(vec![project]),
+            None,
+            ProgressMode::Hidden,
+            Removal {
+                strategy: RemovalStrategy::Permanent,
+                retry: RetryPolicy::default(),
+                prune_empty_parents: false,
+                batch_trash: false,
+                rename_first: false,
+                journal: None,
+            },
+            None,
+            false,
+            &DisplayOptions::default(),
+        );
+
+        assert!(target.exists());
+        assert!(result.errors.is_empty());
+        assert_eq!(result.success_count, 0);
+        assert_eq!(result.skipped.len(), 1);
+        assert!(result.skipped[0].ends_with("the project is protected"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_first_leaves_nothing_behind() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let target = tmp.path().join("target");
+        fs::create_dir_all(target.join("debug"))?;
+        fs::write(target.join("debug/app"), "binary")?;
+        fs::write(tmp.path().join("Cargo.toml"), "[package]")?;
+        // Left aside by an earlier run that was interrupted
+        let stale = tmp.path().join(".target.deleting-1-0");
+        fs::create_dir_all(stale.join("release"))?;
+
+        let removal = Removal {
+            strategy: RemovalStrategy::Permanent,
+            retry: RetryPolicy::default(),
+            prune_empty_parents: false,
+            batch_trash: false,
+            rename_first: true,
+            journal: None,
+        };
+        assert!(removal.renames_first());
+        let worker = ProgressMode::Hidden.bar(1).worker();
+        remove_build_dir(&target, removal, &worker)?;
+
+        let left: Vec<_> = fs::read_dir(tmp.path())?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect::<std::io::Result<_>>()?;
+        assert_eq!(left, vec![std::ffi::OsString::from("Cargo.toml")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_journal_records_each_removal() -> anyhow::Result<()> {
+        use crate::journal::{self, JournalSummary, RemovalOutcome};
+
+        let tmp = tempfile::TempDir::new()?;
+        let target = tmp.path().join("target");
+        fs::create_dir_all(target.join("debug"))?;
+        let journal_path = tmp.path().join("journal.jsonl");
+        let journal = Journal::start(&journal_path, &[tmp.path().to_path_buf()])?;
+
+        let removal = Removal {
+            strategy: RemovalStrategy::Permanent,
+            retry: RetryPolicy {
+                retries: 0,
+                delay: Duration::ZERO,
+            },
+            prune_empty_parents: false,
+            batch_trash: false,
+            rename_first: false,
+            journal: Some(&journal),
+        };
+        let worker = ProgressMode::Hidden.bar(1).worker();
+        remove_build_dir(&target, removal, &worker)?;
+        let missing = tmp.path().join("missing");
+        assert!(remove_build_dir(&missing, removal, &worker).is_err());
+
+        let summary = JournalSummary::from_entries(&journal::load(&journal_path)?);
+        assert!(!summary.completed());
+        assert_eq!(summary.removals.len(), 2);
+        assert_eq!(summary.removals[0].path, target);
+        assert_eq!(summary.removals[0].outcome, RemovalOutcome::Removed);
+        assert!(matches!(
+            summary.removals[1].outcome,
+            RemovalOutcome::Failed(_)
+        ));
+        Ok(())
+    }
+}
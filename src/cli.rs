@@ -7,14 +7,32 @@
 //! Helper methods on [`Cli`] accept a [`FileConfig`] reference so that config-file
 //! values act as defaults that CLI arguments can override (layered config).
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use clap::{Parser, Subcommand, ValueEnum};
 
+use clean_dev_dirs::buckets::AgeBucket;
+use clean_dev_dirs::budget::RootBudget;
+use clean_dev_dirs::caches::SystemCache;
+use clean_dev_dirs::cleaner::RetryPolicy;
 use clean_dev_dirs::config::file::{FileConfig, expand_tilde};
+use clean_dev_dirs::config::local::DEFAULT_KEEP_MARKER;
 use clean_dev_dirs::config::{
-    ExecutionOptions, FilterOptions, ProjectFilter, ScanOptions, SortCriteria, SortOptions,
+    AgeSource, DEFAULT_CONTAINER_CACHES, DEFAULT_NODE_CACHES, DisplayOptions, ExecutionOptions,
+    FilterOptions, FollowSymlinks, LockWait, Preselect, ProjectFilter, RustSubdir, ScanOptions,
+    SortCriteria, SortOptions, TypeFilter,
 };
+use clean_dev_dirs::history::parse_age;
+use clean_dev_dirs::i18n::Lang;
+use clean_dev_dirs::output::OutputFormat;
+use clean_dev_dirs::parse_size;
+use clean_dev_dirs::paths::Paths;
+use clean_dev_dirs::plan::DEFAULT_TOLERANCE_PERCENT;
+use clean_dev_dirs::remote::{DEFAULT_REMOTE_BIN, RemoteHost, forwarded_args};
+use clean_dev_dirs::risk::{RiskLevel, RiskOverrides};
+use clean_dev_dirs::utils::{SizeBackendKind, SizeUnits};
 
 /// Command-line arguments for filtering projects during cleanup.
 ///
@@ -39,13 +57,16 @@ struct FilteringArgs {
     #[arg(short = 'd', long)]
     keep_days: Option<u32>,
 
-    /// Sort projects by the given criterion before display
+    /// Sort projects by the given criteria before display
     ///
     /// Supported values: size (largest first), age (oldest first),
-    /// name (alphabetical), type (grouped by project type).
+    /// name (alphabetical), type (grouped by project type), files (most
+    /// files first), growth (most grown since the previous run first). Several
+    /// comma-separated criteria can be given (e.g. size,name); later ones
+    /// break ties left by earlier ones, and remaining ties are ordered by path.
     /// Use --reverse to flip the order.
-    #[arg(long, value_enum)]
-    sort: Option<SortCriteria>,
+    #[arg(long, value_enum, value_delimiter = ',')]
+    sort: Vec<SortCriteria>,
 
     /// Reverse the sort order
     ///
@@ -54,6 +75,16 @@ struct FilteringArgs {
     #[arg(long)]
     reverse: bool,
 
+    /// List projects smaller than \[SIZE\] as one summary line (e.g. 10MB)
+    ///
+    /// Keeps the project listing readable when it would be flooded with tiny
+    /// entries such as `__pycache__` directories: they are shown as a single
+    /// line like "137 small projects totalling 420 MB". They are still
+    /// cleaned along with the others. Accepts the same size formats as
+    /// --keep-size.
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    collapse_below: Option<u64>,
+
     /// Filter projects by name using a glob or regex pattern
     ///
     /// By default the pattern is treated as a glob (*, ?, [abc]).
@@ -64,6 +95,115 @@ struct FilteringArgs {
     ///   --name "regex:^client-.*" (regex mode)
     #[arg(long)]
     name: Option<String>,
+
+    /// Only include projects whose name matches this regular expression
+    ///
+    /// Applied after detection, in addition to --name.
+    /// Example: --name-filter '-poc$'
+    #[arg(long, value_name = "REGEX", allow_hyphen_values = true)]
+    name_filter: Option<String>,
+
+    /// Only include projects whose root path matches this regular expression
+    ///
+    /// The full path is matched, so unanchored patterns match anywhere in it.
+    /// Example: --path-filter '/clients/'
+    #[arg(long, value_name = "REGEX", allow_hyphen_values = true)]
+    path_filter: Option<String>,
+
+    /// Skip projects with a high rebuild cost that would free less than \[SIZE\]
+    ///
+    /// Rebuild cost is estimated from the project type and artifact size
+    /// (e.g. reinstalling `node_modules` or recompiling a Rust `target/` is
+    /// expensive). Accepts the same size formats as --keep-size.
+    #[arg(long, value_name = "SIZE")]
+    min_benefit: Option<String>,
+
+    /// Ignore projects whose git repository had a commit in the last \[DAYS\] days
+    ///
+    /// Uses the latest commit of the repository enclosing each project, which
+    /// is more reliable than build directory timestamps when CI or tooling
+    /// touches files. Projects outside a git repository are not affected.
+    #[arg(long, value_name = "DAYS", alias = "repo-active-days")]
+    repo_idle_days: Option<u32>,
+
+    /// Ignore projects whose build directories contain fewer than \[N\] files
+    ///
+    /// Useful where inodes run out before disk space, e.g. on small VMs and
+    /// CI runners full of `node_modules` trees. Combine with --sort files.
+    #[arg(long, value_name = "N")]
+    min_files: Option<u64>,
+
+    /// Only clean build artifacts older than the project's newest source file
+    ///
+    /// If the artifacts are newer than every source file, the build is
+    /// current and kept; if sources changed since the last build, the
+    /// artifacts are stale and cleaned.
+    #[arg(long)]
+    stale_only: bool,
+
+    /// Only clean build directories up to this risk level
+    ///
+    /// Each build directory is rated by what is lost when it's deleted:
+    /// `low` for output the next build writes again (`target/`,
+    /// `__pycache__/`), `medium` for installed dependencies that have to be
+    /// downloaded again (`node_modules/`, `.venv/`) and `high` for vendored
+    /// code that may have been patched (`vendor/`). Riskier directories are
+    /// left out, and projects left without any are skipped.
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    max_risk: Option<RiskLevel>,
+
+    /// Only clean these build directories; comma-separated or repeated
+    ///
+    /// Directories are named by their path within the project or their name,
+    /// e.g. `--only-artifacts .next,dist` cleans the framework build output
+    /// of Node.js projects and keeps their `node_modules/`. Projects without
+    /// any of them are skipped.
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    only_artifacts: Vec<String>,
+
+    /// Leave these build directories alone; comma-separated or repeated
+    ///
+    /// Named like for --only-artifacts: `--skip-artifacts node_modules`
+    /// cleans `.next/`, `dist/` and the other build output of Node.js
+    /// projects, but keeps their installed dependencies. Projects left
+    /// without any build directory are skipped.
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    skip_artifacts: Vec<String>,
+
+    /// Only include projects matching a boolean filter expression
+    ///
+    /// Compares `size`, `age`, `files`, `type`, `name` and `path` with
+    /// `<`, `<=`, `>`, `>=`, `==`, `!=`, `~` (regex) and `!~`, combined with
+    /// NOT, AND and OR (binding in that order) and parentheses. Applied in
+    /// addition to the other filters.
+    /// Example: --filter-expr 'size > 1GB AND age > 90d OR type == node AND size > 300MB'
+    #[arg(long, value_name = "EXPR")]
+    filter_expr: Option<String>,
+
+    /// Show how many projects and how much space each age bucket holds
+    ///
+    /// Sorts the projects that pass all other filters into buckets by the age
+    /// of their build artifacts (<30d, 30-90d, 90-365d, >1y) and prints the
+    /// totals per bucket instead of cleaning anything.
+    #[arg(
+        long,
+        conflicts_with_all = ["clean_bucket", "json", "output", "print0", "total_only", "interactive", "host", "apply_plan", "why"]
+    )]
+    bucket_ages: bool,
+
+    /// Clean every project in the given age bucket without asking
+    ///
+    /// One of <30d, 30-90d, 90-365d or >1y, e.g. `--clean-bucket '>1y'`.
+    /// Only projects that pass all other filters and whose build artifacts
+    /// fall into the bucket are cleaned. Implies --yes; combine with
+    /// --dry-run to preview the bucket.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "BUCKET",
+        conflicts_with_all = ["interactive", "host", "apply_plan"]
+    )]
+    clean_bucket: Option<AgeBucket>,
 }
 
 /// Command-line arguments for controlling cleanup execution behavior.
@@ -94,6 +234,14 @@ struct ExecutionArgs {
     #[arg(short = 'i', long)]
     interactive: bool,
 
+    /// Which projects start out checked in interactive selection
+    ///
+    /// `stale` checks projects whose build directories haven't been modified
+    /// in 30 days, `large` checks projects with at least 1 GB of artifacts.
+    /// Defaults to `all`.
+    #[arg(long, value_enum)]
+    preselect: Option<Preselect>,
+
     /// Copy compiled executables to <project>/bin/ before cleaning
     ///
     /// When enabled, preserves compiled binaries (e.g. from target/release/
@@ -102,6 +250,15 @@ struct ExecutionArgs {
     #[arg(short = 'k', long)]
     keep_executables: bool,
 
+    /// Remove older preserved copies, keeping only the latest executables
+    ///
+    /// By default, when a preserved executable changed since the last run, the
+    /// previous copy is kept next to it as `<name>.<hash>`. With this flag
+    /// the previous copy is overwritten and older copies are pruned.
+    /// Only has an effect together with --keep-executables.
+    #[arg(long)]
+    keep_latest_only: bool,
+
     /// Permanently delete directories instead of moving them to the system trash
     ///
     /// By default, build directories are moved to the system trash (Recycle Bin
@@ -109,6 +266,152 @@ struct ExecutionArgs {
     /// flag is set, directories are permanently removed (`rm -rf` style) instead.
     #[arg(long)]
     permanent: bool,
+
+    /// Remove the cleaned build directories from the trash afterwards
+    ///
+    /// Moves build directories to the trash as usual, then permanently
+    /// deletes them from it so the space is reclaimed right away. Only the
+    /// directories removed by this run are deleted from the trash. Not
+    /// supported on macOS.
+    #[arg(long, conflicts_with = "permanent")]
+    empty_trash: bool,
+
+    /// Skip projects with uncommitted changes in their git repository
+    ///
+    /// For projects inside a git work tree, staged, unstaged and untracked
+    /// changes under the project root (ignoring the build directories) cause
+    /// the project to be reported as dirty and left untouched.
+    #[arg(long)]
+    skip_dirty: bool,
+
+    /// Include projects owned by other users
+    ///
+    /// By default, projects whose root directory belongs to another user
+    /// (by uid on Unix, owner SID on Windows) are skipped, which keeps shared
+    /// machines safe. Use this flag as an administrator to clean everything.
+    #[arg(long)]
+    all_users: bool,
+
+    /// Show a desktop notification when cleanup finishes
+    ///
+    /// Useful for long or scheduled runs. A `[hooks] post_clean` command in
+    /// the config file is run after cleanup as well, independently of this flag.
+    #[arg(long)]
+    notify: bool,
+
+    /// Re-measure each build directory just before removing it
+    ///
+    /// The sizes shown before cleaning come from the scan and can be stale by
+    /// the time a directory is removed. With this flag every directory is
+    /// walked again right before removal, and the summary reports both the
+    /// scan estimate and the verified space freed.
+    #[arg(long)]
+    verify_sizes: bool,
+
+    /// Remove the directories a removal leaves empty
+    ///
+    /// Removing `vendor/bundle/` leaves an empty `vendor/` behind, and build
+    /// directories nested in wrapper directories do the same. With this flag
+    /// such now-empty directories are removed as well, up to but never
+    /// including the project root. Directories that still hold anything are
+    /// kept.
+    #[arg(long)]
+    prune_empty_parents: bool,
+
+    /// Move build directories to the trash in batches
+    ///
+    /// Each move to the trash has a fixed cost, which on macOS (where every
+    /// call goes through the Finder) makes trashing hundreds of directories
+    /// slow. With this flag the directories are moved after all projects are
+    /// prepared, grouped by volume, many per call. If a batch fails, its
+    /// directories are retried one at a time, so only the ones that really
+    /// can't be moved are reported as failed. Ignored with --permanent.
+    #[arg(long)]
+    batch_trash: bool,
+
+    /// Rename each build directory aside before deleting it permanently
+    ///
+    /// Deleting a large directory in place takes a while, and a build using
+    /// it meanwhile finds it half-removed. With this flag the directory is
+    /// first renamed to a hidden sibling (`.target.deleting-...`), which is
+    /// instant, and then deleted from there. If it can't be renamed, e.g.
+    /// because it is a mount point, it is deleted in place. Ignored when
+    /// moving to the trash.
+    #[arg(long)]
+    rename_first: bool,
+
+    /// Record each removal in a journal before it begins and after it ends
+    ///
+    /// If the process dies in the middle of a cleanup, `clean-dev-dirs
+    /// status` then shows whether the last run completed, what it removed
+    /// and which directories it was in the middle of removing. The journal
+    /// is `journal.jsonl` in the state directory, replaced by each run.
+    #[arg(long)]
+    journal: bool,
+
+    /// Retry a failed removal this many times before giving up [default: 3]
+    ///
+    /// Antivirus and indexing services can briefly lock files, making a
+    /// removal fail spuriously. A project is only reported as failed once
+    /// all retries have failed. Use 0 to disable retries.
+    #[arg(long, value_name = "N")]
+    delete_retries: Option<u32>,
+
+    /// Milliseconds to wait before the first retry [default: 100]
+    ///
+    /// The wait doubles before each further retry.
+    #[arg(long, value_name = "MS")]
+    delete_retry_delay: Option<u64>,
+
+    /// What to do when another run is already cleaning [default: error]
+    ///
+    /// Runs that may remove directories hold a lock file in the state
+    /// directory from the scan until the cleanup ends, so overlapping runs
+    /// (e.g. from cron) never remove the same directories twice. `wait`
+    /// blocks until the other run finishes, `skip` exits successfully
+    /// without doing anything, and `error` exits with an error. Dry runs
+    /// don't take the lock.
+    #[arg(long, value_enum, value_name = "MODE")]
+    lock_wait: Option<LockWait>,
+
+    /// Record the build directories a dry run would remove in \[FILE\]
+    ///
+    /// Requires --dry-run. The plan lists every directory with its measured
+    /// size, so it can be reviewed before being executed with --apply-plan.
+    #[arg(long, value_name = "FILE")]
+    plan_file: Option<PathBuf>,
+
+    /// Remove the build directories recorded in a plan instead of scanning
+    ///
+    /// Each project is detected again first, with the current filters. Only
+    /// directories found as its build directories are removed; directories
+    /// outside the project, that no longer exist or whose size changed by
+    /// more than --plan-tolerance are left alone. Confirmation, --yes and --dry-run work as usual.
+    #[arg(long, value_name = "FILE", conflicts_with = "plan_file")]
+    apply_plan: Option<PathBuf>,
+
+    /// Allowed size change, in percent, for directories in an applied plan [default: 10]
+    #[arg(long, value_name = "PERCENT", requires = "apply_plan")]
+    plan_tolerance: Option<u32>,
+
+    /// Write run metrics for Prometheus to \[FILE\]
+    ///
+    /// Writes the number of projects detected, the reclaimable and reclaimed
+    /// bytes, the scan duration and the time of the run in the Prometheus
+    /// text format, e.g. into the node exporter's textfile collector
+    /// directory. The file is replaced atomically after every run.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["host", "apply_plan"])]
+    metrics_file: Option<PathBuf>,
+
+    /// Run in CI mode
+    ///
+    /// Implies non-interactive operation and disables colors, emoji and
+    /// spinners; progress is printed as plain lines every few seconds instead.
+    /// Refuses to delete anything unless --yes (or --dry-run) is also given.
+    /// Enabled automatically when the CI environment variable is set, unless
+    /// the config file sets `ci = false`.
+    #[arg(long)]
+    ci: bool,
 }
 
 /// Command-line arguments for controlling directory scanning behavior.
@@ -116,11 +419,14 @@ struct ExecutionArgs {
 /// These options affect how directories are traversed and what information
 /// is collected during the scanning phase.
 #[derive(Parser)]
+#[allow(clippy::struct_excessive_bools)]
 struct ScanningArgs {
     /// The number of threads to use for directory scanning
     ///
-    /// A value of 0 uses the default number of threads (typically the number of CPU cores).
-    /// Higher values can improve scanning performance on systems with fast storage.
+    /// A value of 0 (the default) picks the number from the storage the
+    /// scanned directories are on: one per CPU core on SSDs, 2 on spinning
+    /// disks and 4 on network mounts, following the slowest of them. Run
+    /// with `--verbose` to see the choice.
     #[arg(short = 't', long)]
     threads: Option<usize>,
 
@@ -131,20 +437,41 @@ struct ScanningArgs {
     #[arg(short = 'v', long)]
     verbose: bool,
 
-    /// Directories to ignore by default
+    /// Show which detector matched each project and why
+    ///
+    /// Lists, for every project found, the detector that recognized it, the
+    /// marker files it found in the project root and the build directories
+    /// it chose. With `--json`, each project gets a `detection` object with
+    /// the same information.
+    #[arg(long)]
+    explain: bool,
+
+    /// Directories to ignore entirely
     ///
-    /// These directories will be completely ignored during scanning. Can be specified
-    /// multiple times to ignore multiple directory patterns.
+    /// Directories with this name are never descended into, so no project
+    /// below them is found. Can be specified multiple times.
     #[arg(long, action = clap::ArgAction::Append)]
     ignore: Vec<PathBuf>,
 
     /// Directories to skip during scanning
     ///
-    /// These directories will be skipped during scans, but their parent directories
-    /// may still be processed. Can be specified multiple times.
+    /// Directories with this name are not checked for a project themselves,
+    /// but the directories below them are still scanned. Can be specified
+    /// multiple times.
     #[arg(long, action = clap::ArgAction::Append)]
     skip: Vec<PathBuf>,
 
+    /// Check hidden directories for projects too
+    ///
+    /// By default, directories whose name starts with `.` (other than
+    /// `.cargo` and the `allow_hidden` list of the config file) are not
+    /// checked for a project themselves, though the directories below them
+    /// are. With this flag, a hidden worktree such as `.worktrees-main` is
+    /// found as well. Version control, virtual environment and other
+    /// excluded directories are still passed over.
+    #[arg(long)]
+    scan_hidden: bool,
+
     /// Maximum directory depth to scan
     ///
     /// Limits how deep into the directory tree the scanner will traverse.
@@ -152,6 +479,136 @@ struct ScanningArgs {
     /// When not set, the scan is unlimited.
     #[arg(long)]
     max_depth: Option<usize>,
+
+    /// Stop sizing a build directory once it reaches this size (e.g. 5GB)
+    ///
+    /// Sizing an enormous `target/` can take minutes. Past the threshold the
+    /// walk stops and the size is shown as an estimate (`≥5 GB (estimated)`).
+    /// Exact sizes are measured only for the projects that end up being
+    /// cleaned. Size filters and sorting see the estimate.
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    quick_size_threshold: Option<u64>,
+
+    /// How build directories are walked to measure them
+    ///
+    /// `portable` walks them the same way on every platform. `native` uses
+    /// the platform's directory APIs, which is faster on large trees:
+    /// `fstatat` relative to each open directory on Unix, and
+    /// `FindFirstFileExW` with large fetches on Windows. Both report the same
+    /// sizes.
+    #[arg(long, value_enum, value_name = "BACKEND")]
+    size_backend: Option<SizeBackendKind>,
+
+    /// Name of the marker file that protects a project
+    ///
+    /// A project whose root contains a file with this name is listed as
+    /// protected and never cleaned, even with `--yes`. Defaults to
+    /// `.clean-dev-dirs-keep`; an empty name turns marker files off.
+    #[arg(long, value_name = "NAME")]
+    keep_marker: Option<String>,
+
+    /// Where to take the age of build artifacts from
+    ///
+    /// `dir-mtime` uses the modification time of the artifact directory, which
+    /// doesn't change when only a nested file is rebuilt. `newest-file` uses
+    /// the most recent modification time of any file inside the artifacts,
+    /// which is accurate but walks each artifact during the scan. The age is
+    /// used for display, `--sort age` and `--keep-days`.
+    #[arg(long, value_enum)]
+    age_source: Option<AgeSource>,
+
+    /// Which symbolic links to directories to follow while scanning [default: roots]
+    ///
+    /// By default a directory given to scan is followed if it is a symbolic
+    /// link, but links found below it are not, so projects reached only
+    /// through a symlink (say, to another drive) are not found. `always`
+    /// follows every link to a directory and scans each directory once,
+    /// however many links lead to it, so link loops end the walk there.
+    /// `never` doesn't even follow a scan root that is a link.
+    #[arg(long, value_enum, value_name = "MODE")]
+    follow_symlinks: Option<FollowSymlinks>,
+
+    /// Also clean caches kept inside Rust projects
+    ///
+    /// Rust projects built with `CARGO_HOME` or `SCCACHE_DIR` pointing into
+    /// the project keep a `.cargo/registry/`, `.cargo/git/` or `.sccache/`
+    /// there. With this flag, these are cleaned along with `target/`; the
+    /// rest of `.cargo/` (such as `config.toml`) is left alone.
+    #[arg(long)]
+    cargo_caches: bool,
+
+    /// Also clean conda environments kept inside Python projects
+    ///
+    /// Environments created with `conda create -p ./env` (or in `.conda/`)
+    /// are recognized by their `conda-meta/` directory and cleaned along
+    /// with the other Python caches. They have to be created again before
+    /// the project can run, so this is off by default.
+    #[arg(long)]
+    conda_envs: bool,
+
+    /// Clean only the build tool caches inside Node.js projects
+    ///
+    /// Instead of the whole `node_modules/`, only the caches that build tools
+    /// keep there are cleaned: `node_modules/.cache/`, `node_modules/.vite/`
+    /// and `.turbo/` by default, or the `node_caches` list of the config
+    /// file. The installed dependencies stay, so no reinstall is needed.
+    #[arg(long)]
+    node_caches_only: bool,
+
+    /// Also clean container caches kept inside projects
+    ///
+    /// Compose setups often keep caches in the repository: an image build
+    /// cache exported to `.buildx-cache/`, or the `node_modules/` of a
+    /// container mirrored under `.docker/`. These, `.docker/cache/` and
+    /// `.docker/buildkit/`, or the `container_cache_patterns` of the config
+    /// file, are cleaned with the project's build directories. The next image build fills them
+    /// again, so this is off by default.
+    #[arg(long)]
+    container_caches: bool,
+
+    /// Clean only these subdirectories of Rust target directories; comma-separated
+    ///
+    /// Instead of the whole `target/`, only the chosen kinds of
+    /// subdirectories are cleaned, for the host build and for each target
+    /// triple: `--rust-subdirs incremental,doc` drops the incremental
+    /// compilation caches and generated documentation but keeps compiled
+    /// dependencies. `-v` lists how much each of them takes up.
+    #[arg(long, value_enum, value_name = "SUBDIRS", value_delimiter = ',')]
+    rust_subdirs: Vec<RustSubdir>,
+
+    /// Also report and prune reclaimable Docker data
+    ///
+    /// Dangling images, stopped containers and unused build cache are listed
+    /// after the build directories and pruned after a separate confirmation.
+    /// Requires the `docker` client; if the daemon can't be reached, only a
+    /// warning is shown. Ignored with machine-readable output.
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "output", "print0", "host", "apply_plan"]
+    )]
+    docker: bool,
+
+    /// Also report and clean package manager caches
+    ///
+    /// Covers the Homebrew and apt download caches, the pip cache, downloaded
+    /// crates in the cargo registry, the Go module cache and sccache's local
+    /// cache. They are listed after the build directories and cleaned after a
+    /// separate confirmation. Individual caches can be turned off in the
+    /// `[caches]` config table.
+    /// Ignored with machine-readable output.
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "output", "print0", "host", "apply_plan"]
+    )]
+    system_caches: bool,
+
+    /// Append the projects found to the scan database
+    ///
+    /// Each scan is added to `scans.db` in the state directory, with every
+    /// project and build directory and their sizes, so disk usage can be
+    /// followed over time with `clean-dev-dirs query` or any `SQLite` client.
+    #[arg(long)]
+    record_scans: bool,
 }
 
 /// Top-level subcommands.
@@ -162,6 +619,68 @@ pub(crate) enum Commands {
         #[command(subcommand)]
         command: ConfigCommand,
     },
+    /// Print the JSON Schema describing the `--json` output
+    Schema,
+    /// Show recent cleanup runs and the total space they reclaimed
+    History {
+        /// Only include runs from the last AGE (e.g. `12h`, `30d`, `2w`)
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        since: Option<Duration>,
+
+        /// Number of recent runs to list
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        limit: usize,
+    },
+    /// Keep a project out of cleanups for a while
+    Snooze {
+        /// Project, or directory of projects, to snooze
+        #[arg(required_unless_present_any = ["list", "clear"])]
+        path: Option<PathBuf>,
+
+        /// How long to snooze for (e.g. `12h`, `30d`, `2w`)
+        #[arg(long = "for", value_name = "AGE", value_parser = parse_age, default_value = "30d")]
+        duration: Duration,
+
+        /// List the active snoozes
+        #[arg(long, conflicts_with_all = ["path", "clear"])]
+        list: bool,
+
+        /// Remove the snooze of PATH, or every snooze when no PATH is given
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Manage the build directories moved to the trash
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+    /// Show whether the last journaled cleanup run completed and what it did
+    ///
+    /// Runs are journaled with --journal or `journal = true` under
+    /// `[execution]`.
+    Status,
+    /// Run an SQL query against the recorded scans, or print their schema
+    ///
+    /// Scans are recorded with --record-scans or `record_scans = true`. The
+    /// database is opened read-only; without SQL, the tables are printed.
+    Query {
+        /// SQL statement, e.g. `SELECT kind, SUM(size) FROM projects GROUP BY kind`
+        sql: Option<String>,
+    },
+    /// Run an alias from the `[aliases]` table of the config file
+    ///
+    /// Same as `clean-dev-dirs @<ALIAS>`. Arguments after the alias are
+    /// added to the ones it stands for.
+    Run {
+        /// Name of the alias
+        alias: String,
+    },
+    /// Suggest directories to scan and offer to save them in the config file
+    Discover {
+        /// Save the suggestions without asking for confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
 }
 
 /// Subcommands for `config`.
@@ -173,6 +692,31 @@ pub(crate) enum ConfigCommand {
     Init,
     /// Print the path to the config file
     Path,
+    /// Check the config file for unknown keys and invalid values
+    Validate,
+    /// Print the value of a setting, e.g. `config get scanning.threads`
+    Get {
+        /// Dotted key of the setting, e.g. `filtering.keep_size`
+        key: String,
+    },
+    /// Change a setting, e.g. `config set filtering.keep_size 200MB`
+    ///
+    /// Comments and formatting in the rest of the file are kept. The file is
+    /// created from the default template if it doesn't exist yet.
+    Set {
+        /// Dotted key of the setting, e.g. `filtering.keep_size`
+        key: String,
+
+        /// New value; strings don't need quotes
+        value: String,
+    },
+}
+
+/// Subcommands for `trash`.
+#[derive(Subcommand)]
+pub(crate) enum TrashCommand {
+    /// Permanently delete the build directories clean-dev-dirs moved to the trash
+    Empty,
 }
 
 /// Main command-line interface structure.
@@ -189,6 +733,7 @@ pub(crate) enum ConfigCommand {
 )]
 #[command(version)]
 #[command(author)]
+#[allow(clippy::struct_excessive_bools)]
 pub(crate) struct Cli {
     /// Subcommand (e.g. `config`)
     #[command(subcommand)]
@@ -202,6 +747,30 @@ pub(crate) struct Cli {
     #[arg(num_args = 0..)]
     dirs: Vec<PathBuf>,
 
+    /// Read the directories to check from stdin instead of scanning
+    ///
+    /// Paths are separated by newlines, or by NUL bytes if there are any.
+    /// Each path is checked for a project without walking below it, and a
+    /// build directory such as `node_modules` stands for the project it
+    /// belongs to: `fd -t d -g node_modules | clean-dev-dirs --stdin --dry-run`
+    #[arg(long, conflicts_with_all = ["dirs", "host", "apply_plan"])]
+    stdin: bool,
+
+    /// Show why a directory is or isn't cleaned, then exit
+    ///
+    /// Runs the scan for this one path with the other options given and
+    /// prints each step: the rules that keep the walk from reaching it, what
+    /// every detector found (or which marker files it missed) and the
+    /// filters that leave its projects out. Nothing is removed. With
+    /// `--json`, the trace is printed as JSON, each rule named after its
+    /// option or config key.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["stdin", "output", "print0", "total_only", "interactive", "host", "apply_plan"]
+    )]
+    why: Option<PathBuf>,
+
     /// Project type to clean (all, rust, node, python, go, java, cpp, swift, dotnet, ruby, elixir, deno)
     ///
     /// Restricts cleaning to specific project types. If not specified, all
@@ -209,14 +778,134 @@ pub(crate) struct Cli {
     #[arg(short = 'p', long)]
     project_type: Option<ProjectFilter>,
 
+    /// Project type to include; can be given several times
+    ///
+    /// Like --project-type, but several types can be selected at once:
+    /// `--type rust --type node`, or comma-separated as `--type rust,node`.
+    /// Adds to a type given with --project-type.
+    #[arg(long = "type", value_name = "TYPE", value_delimiter = ',')]
+    types: Vec<ProjectFilter>,
+
+    /// Project type to leave out; can be given several times
+    ///
+    /// Projects of these types are never scanned, even if included with
+    /// --project-type or --type: `--exclude-type node --exclude-type python`.
+    #[arg(long, value_name = "TYPE", value_delimiter = ',')]
+    exclude_type: Vec<ProjectFilter>,
+
     /// Output results as a single JSON object for scripting/piping
     ///
     /// When enabled, all human-readable output (colors, progress bars, emojis)
     /// is suppressed and a single JSON document is printed to stdout.
-    /// Incompatible with `--interactive`.
+    /// Incompatible with `--interactive`. Cleaning requires `--yes`, since no
+    /// confirmation prompt can be shown; use `--dry-run` to only list projects.
     #[arg(long)]
     json: bool,
 
+    /// Print results in a machine-readable format instead of the normal output
+    ///
+    /// `json` is equivalent to `--json`; `csv` prints one row per project
+    /// (`path,name,type,size_bytes,files,age_days,action`); `md` prints a Markdown
+    /// table. Incompatible with `--interactive`.
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with = "json")]
+    output: Option<OutputFormat>,
+
+    /// Write the `--json` document to \[FILE\] and keep the normal output
+    ///
+    /// The progress, project list and summary stay on the terminal as usual,
+    /// and the same document `--json` would print is written to the file
+    /// once the dry run or cleanup is done. Cleaning still asks for
+    /// confirmation unless `--yes` is given.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["json", "output", "print0", "total_only", "host", "why"]
+    )]
+    json_file: Option<PathBuf>,
+
+    /// Write a self-contained HTML report of the run to \[FILE\]
+    ///
+    /// The report has the totals, charts of the space and projects per
+    /// type, the volumes involved and a sortable table of the projects, for
+    /// sharing the results of a scan. Everything is inline, so the file
+    /// opens offline. The normal output is unchanged.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["host", "why"])]
+    report_html: Option<PathBuf>,
+
+    /// Print only the build directory paths that would be cleaned, separated by NUL
+    ///
+    /// For pipelines such as `clean-dev-dirs --print0 | xargs -0 du -sh`.
+    /// Implies `--dry-run` and quiet output: nothing else is written to
+    /// stdout, nothing is removed and there is never a prompt. All filters
+    /// still apply.
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "output", "interactive", "host"]
+    )]
+    print0: bool,
+
+    /// Print only the total size that could be reclaimed, then exit
+    ///
+    /// For quick checks in scripts. The total is a plain byte count, or
+    /// formatted in the given units with `--units`. Implies `--dry-run` and
+    /// quiet output like `--print0`: no project list, nothing removed and
+    /// never a prompt. All filters still apply.
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "output", "print0", "interactive", "host"]
+    )]
+    total_only: bool,
+
+    /// Units used to display sizes [default: decimal]
+    ///
+    /// `binary` shows powers of 1024 (KiB, MiB, GiB), `decimal` powers of
+    /// 1000 (kB, MB, GB) and `bytes` exact byte counts. Applies to all
+    /// human-readable output and to the `*_formatted` fields of `--json`.
+    #[arg(long, global = true, value_enum, value_name = "UNITS")]
+    units: Option<SizeUnits>,
+
+    /// Language of the messages [default: from the locale]
+    ///
+    /// `en` for English, `de` for German. Without it, the language comes
+    /// from `LC_ALL`, `LC_MESSAGES` or `LANG`, falling back to English.
+    /// Machine-readable output is always in English.
+    #[arg(long, global = true, value_enum, value_name = "LANG")]
+    lang: Option<Lang>,
+
+    /// Show full project paths in human-readable output
+    ///
+    /// Projects are listed relative to the directory their scan root is in,
+    /// e.g. `code/web/app` for a project found by scanning `~/code`. This
+    /// shows their absolute paths instead. Machine-readable output always
+    /// has absolute paths.
+    #[arg(long)]
+    absolute_paths: bool,
+
+    /// Named config profile to apply on top of the config file defaults
+    ///
+    /// Selects a `[profile.<NAME>]` table from config.toml. Values in the
+    /// profile override the top-level config; CLI arguments still win.
+    /// Also applies to `config show`.
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Scan and clean a remote machine over SSH instead of this one
+    ///
+    /// Can be given several times. Each host runs its own copy of
+    /// clean-dev-dirs with the other options given here; the results are
+    /// shown together, and after confirmation each host removes exactly the
+    /// directories listed. Uses the local `ssh` client in batch mode.
+    #[arg(
+        long,
+        value_name = "HOST",
+        conflicts_with_all = ["interactive", "json", "output", "plan_file", "apply_plan"]
+    )]
+    host: Vec<String>,
+
+    /// Command that runs clean-dev-dirs on the remote hosts [default: clean-dev-dirs]
+    #[arg(long, value_name = "COMMAND", requires = "host")]
+    remote_bin: Option<String>,
+
     /// Execution options
     #[command(flatten)]
     execution: ExecutionArgs,
@@ -231,10 +920,157 @@ pub(crate) struct Cli {
 }
 
 impl Cli {
-    /// Whether `--json` structured output mode is enabled.
+    /// The machine-readable output format, if `--json` or `--output` was given.
+    #[must_use]
+    pub(crate) fn output_format(&self) -> Option<OutputFormat> {
+        self.output
+            .or_else(|| self.json.then_some(OutputFormat::Json))
+            .or_else(|| self.print0.then_some(OutputFormat::Paths0))
+            .or_else(|| {
+                self.total_only.then_some(if self.units.is_some() {
+                    OutputFormat::TotalFormatted
+                } else {
+                    OutputFormat::Total
+                })
+            })
+    }
+
+    /// The units to display sizes in.
+    ///
+    /// Resolution order: CLI `--units` > config `units` > decimal. Invalid
+    /// config values fall back to decimal.
+    #[must_use]
+    pub(crate) fn size_units(&self, config: &FileConfig) -> SizeUnits {
+        self.units
+            .or_else(|| {
+                config
+                    .units
+                    .as_ref()
+                    .and_then(|s| SizeUnits::from_str(s, true).ok())
+            })
+            .unwrap_or_default()
+    }
+
+    /// The language to show messages in.
+    ///
+    /// Resolution order: CLI `--lang` > config `lang` > the locale
+    /// environment variables > English. Invalid config values are ignored.
+    #[must_use]
+    pub(crate) fn lang(&self, config: &FileConfig) -> Lang {
+        self.lang
+            .or_else(|| {
+                config
+                    .lang
+                    .as_ref()
+                    .and_then(|s| Lang::from_str(s, true).ok())
+            })
+            .unwrap_or_else(Lang::from_env)
+    }
+
+    /// Whether to show absolute project paths.
+    ///
+    /// Resolution order: CLI `--absolute-paths` > config `absolute_paths` > false.
+    #[must_use]
+    pub(crate) fn absolute_paths(&self, config: &FileConfig) -> bool {
+        self.absolute_paths || config.absolute_paths.unwrap_or(false)
+    }
+
+    /// The options for human-readable output: size units, language, path
+    /// display and the risk levels of the config file.
+    #[must_use]
+    pub(crate) fn display_options(&self, config: &FileConfig) -> DisplayOptions {
+        DisplayOptions {
+            units: self.size_units(config),
+            lang: self.lang(config),
+            absolute_paths: self.absolute_paths(config),
+            risk_overrides: RiskOverrides::from_config(config),
+        }
+    }
+
+    /// The config profile selected with `--profile`, if any.
+    #[must_use]
+    pub(crate) fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// The remote machines to work on, with the arguments to pass on to them.
+    ///
+    /// `raw_args` are the command-line arguments without the program name.
+    #[must_use]
+    pub(crate) fn remote_hosts(&self, raw_args: Vec<String>) -> Vec<RemoteHost> {
+        let bin = self.remote_bin.as_deref().unwrap_or(DEFAULT_REMOTE_BIN);
+        let args = forwarded_args(raw_args);
+        self.host
+            .iter()
+            .map(|host| RemoteHost::new(host, bin, args.clone()))
+            .collect()
+    }
+
+    /// Whether the directories to check are read from stdin (`--stdin`).
+    #[must_use]
+    pub(crate) const fn stdin(&self) -> bool {
+        self.stdin
+    }
+
+    /// The directory to trace, if `--why` was given.
+    #[must_use]
+    pub(crate) fn why(&self) -> Option<&Path> {
+        self.why.as_deref()
+    }
+
+    /// Where to record the cleanup plan, if `--plan-file` was given.
+    #[must_use]
+    pub(crate) fn plan_file(&self) -> Option<&Path> {
+        self.execution.plan_file.as_deref()
+    }
+
+    /// Where to write Prometheus metrics, if `--metrics-file` was given.
+    #[must_use]
+    pub(crate) fn metrics_file(&self) -> Option<&Path> {
+        self.execution.metrics_file.as_deref()
+    }
+
+    /// Whether to only show the totals per age bucket (`--bucket-ages`).
+    #[must_use]
+    pub(crate) const fn bucket_ages(&self) -> bool {
+        self.filtering.bucket_ages
+    }
+
+    /// The size below which projects are collapsed into one listing line.
+    ///
+    /// Priority: CLI argument > config file > 0 (list every project).
+    #[must_use]
+    pub(crate) fn collapse_below(&self, config: &FileConfig) -> u64 {
+        self.filtering
+            .collapse_below
+            .or_else(|| {
+                config
+                    .filtering
+                    .collapse_below
+                    .as_deref()
+                    .and_then(|s| parse_size(s).ok())
+            })
+            .unwrap_or(0)
+    }
+
+    /// The age bucket to clean, if `--clean-bucket` was given.
     #[must_use]
-    pub(crate) const fn json(&self) -> bool {
-        self.json
+    pub(crate) const fn clean_bucket(&self) -> Option<AgeBucket> {
+        self.filtering.clean_bucket
+    }
+
+    /// The plan to apply instead of scanning, if `--apply-plan` was given.
+    #[must_use]
+    pub(crate) fn apply_plan(&self) -> Option<&Path> {
+        self.execution.apply_plan.as_deref()
+    }
+
+    /// The allowed size change when applying a plan, in percent.
+    #[must_use]
+    pub(crate) fn plan_tolerance(&self) -> u32 {
+        self.execution
+            .plan_tolerance
+            .unwrap_or(DEFAULT_TOLERANCE_PERCENT)
     }
 
     /// Resolve the target directories from CLI args, config file, or default.
@@ -262,7 +1098,7 @@ impl Cli {
         if let Some(ref dirs) = config.dirs
             && !dirs.is_empty()
         {
-            return dirs.iter().map(|d| expand_tilde(d)).collect();
+            return dirs.iter().map(|d| expand_tilde(d.path())).collect();
         }
 
         if let Some(ref dir) = config.dir {
@@ -272,6 +1108,35 @@ impl Cli {
         vec![PathBuf::from(".")]
     }
 
+    /// Collect the size budgets of the config file's `dirs` entries.
+    ///
+    /// Budgets only apply to roots taken from the config file, so this is
+    /// empty when directories are given on the command line or on stdin.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a budget is not a valid size.
+    pub(crate) fn root_budgets(&self, config: &FileConfig) -> anyhow::Result<Vec<RootBudget>> {
+        if !self.dirs.is_empty() || self.stdin {
+            return Ok(Vec::new());
+        }
+
+        config
+            .dirs
+            .iter()
+            .flatten()
+            .filter_map(|entry| entry.budget().map(|budget| (entry.path(), budget)))
+            .map(|(path, budget)| {
+                let budget = parse_size(budget)
+                    .map_err(|e| anyhow::anyhow!("Invalid budget for {}: {e}", path.display()))?;
+                Ok(RootBudget {
+                    root: expand_tilde(path),
+                    budget,
+                })
+            })
+            .collect()
+    }
+
     /// Extract project filter from CLI args and config file.
     ///
     /// Priority: CLI argument > config file > default (`All`).
@@ -298,6 +1163,35 @@ impl Cli {
             .unwrap_or_default()
     }
 
+    /// Resolve the project types to include and exclude.
+    ///
+    /// The included types are those of `--project-type` and `--type`, if
+    /// either was given, and otherwise those of the config file's
+    /// `project_type` and `[filtering] types`. The excluded types are those of
+    /// `--exclude-type`, or else of `[filtering] exclude_types`. Unknown type
+    /// names in the config file are ignored.
+    #[must_use]
+    pub(crate) fn type_filter(&self, config: &FileConfig) -> TypeFilter {
+        let include: Vec<ProjectFilter> = if self.project_type.is_some() || !self.types.is_empty() {
+            self.project_type
+                .into_iter()
+                .chain(self.types.iter().copied())
+                .collect()
+        } else {
+            std::iter::once(self.project_filter(config))
+                .chain(parse_types(config.filtering.types.as_deref()))
+                .collect()
+        };
+
+        let exclude = if self.exclude_type.is_empty() {
+            parse_types(config.filtering.exclude_types.as_deref())
+        } else {
+            self.exclude_type.clone()
+        };
+
+        TypeFilter::new(include, exclude)
+    }
+
     /// Extract execution options from CLI args and config file.
     ///
     /// For boolean flags, the CLI flag (if set to `true`) takes priority,
@@ -318,21 +1212,105 @@ impl Cli {
     #[must_use]
     pub(crate) fn execution_options(&self, config: &FileConfig) -> ExecutionOptions {
         ExecutionOptions {
-            dry_run: self.execution.dry_run || config.execution.dry_run.unwrap_or(false),
-            interactive: self.execution.interactive
-                || config.execution.interactive.unwrap_or(false),
+            dry_run: self.print0
+                || self.total_only
+                || self.execution.dry_run
+                || config.execution.dry_run.unwrap_or(false),
+            interactive: !self.print0
+                && !self.total_only
+                && self.filtering.clean_bucket.is_none()
+                && (self.execution.interactive || config.execution.interactive.unwrap_or(false)),
+            preselect: self
+                .execution
+                .preselect
+                .or_else(|| {
+                    config
+                        .execution
+                        .preselect
+                        .as_ref()
+                        .and_then(|s| Preselect::from_str(s, true).ok())
+                })
+                .unwrap_or_default(),
             keep_executables: self.execution.keep_executables
                 || config.execution.keep_executables.unwrap_or(false),
+            keep_latest_only: self.execution.keep_latest_only
+                || config.execution.keep_latest_only.unwrap_or(false),
             use_trash: !self.execution.permanent && config.execution.use_trash.unwrap_or(true),
-            yes: self.execution.yes,
+            yes: self.execution.yes || self.filtering.clean_bucket.is_some(),
+            empty_trash: self.execution.empty_trash,
+            skip_dirty: self.execution.skip_dirty || config.execution.skip_dirty.unwrap_or(false),
+            all_users: self.execution.all_users || config.execution.all_users.unwrap_or(false),
+            notify: self.execution.notify || config.execution.notify.unwrap_or(false),
+            json_file: self.json_file.clone(),
+            report_html: self.report_html.clone(),
+            verify_sizes: self.execution.verify_sizes
+                || config.execution.verify_sizes.unwrap_or(false),
+            prune_empty_parents: self.execution.prune_empty_parents
+                || config.execution.prune_empty_parents.unwrap_or(false),
+            batch_trash: self.execution.batch_trash
+                || config.execution.batch_trash.unwrap_or(false),
+            rename_first: self.execution.rename_first
+                || config.execution.rename_first.unwrap_or(false),
+            journal: self.execution.journal || config.execution.journal.unwrap_or(false),
+            delete_retries: self
+                .execution
+                .delete_retries
+                .or(config.execution.delete_retries)
+                .unwrap_or(RetryPolicy::DEFAULT_RETRIES),
+            delete_retry_delay: self
+                .execution
+                .delete_retry_delay
+                .or(config.execution.delete_retry_delay)
+                .map_or(RetryPolicy::DEFAULT_DELAY, Duration::from_millis),
+            lock_wait: self
+                .execution
+                .lock_wait
+                .or_else(|| {
+                    config
+                        .execution
+                        .lock_wait
+                        .as_ref()
+                        .and_then(|s| LockWait::from_str(s, true).ok())
+                })
+                .unwrap_or_default(),
+            ci: self.execution.ci
+                || config
+                    .execution
+                    .ci
+                    .unwrap_or_else(|| ci_env_enabled(std::env::var("CI").ok().as_deref())),
+            post_clean: config.hooks.post_clean.clone(),
+            pre_project_clean: config.hooks.pre_project_clean.clone(),
+            paths: Paths::from_config(config),
+        }
+    }
+
+    /// Where artifact ages come from: CLI `--age-source` > config
+    /// `scanning.age_source` > the directory's own modification time.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the config value isn't a valid age
+    /// source.
+    fn age_source(&self, config: &FileConfig) -> anyhow::Result<AgeSource> {
+        if let Some(source) = self.scanning.age_source {
+            return Ok(source);
         }
+        Ok(config
+            .scanning
+            .age_source
+            .as_deref()
+            .map(AgeSource::from_config)
+            .transpose()?
+            .unwrap_or_default())
     }
 
     /// Extract scanning options from CLI args and config file.
     ///
     /// - **threads**: CLI > config > `0` (default)
     /// - **verbose**: CLI flag `||` config value `||` `false`
-    /// - **skip**: merged from both sources (config values first, then CLI)
+    /// - **skip**, **ignore**: merged from both sources (config values first, then CLI)
+    /// - **`allow_hidden`**, **`exclude_names`**, **`no_exclude_names`**: config only
+    /// - **`extra_artifacts`**: config only, keyed by project type (unknown types are ignored)
     ///
     /// # Examples
     ///
@@ -342,16 +1320,23 @@ impl Cli {
     /// # mod cli { include!("cli.rs"); }
     /// # use cli::Cli;
     /// let args = Cli::parse_from(&["clean-dev-dirs", "--verbose", "--threads", "4"]);
-    /// let options = args.scan_options(&FileConfig::default());
+    /// let options = args.scan_options(&FileConfig::default())?;
     /// assert!(options.verbose);
     /// assert_eq!(options.threads, 4);
+    /// # Ok::<(), anyhow::Error>(())
     /// ```
-    #[must_use]
-    pub(crate) fn scan_options(&self, config: &FileConfig) -> ScanOptions {
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `scanning.age_source` isn't a valid
+    /// age source.
+    pub(crate) fn scan_options(&self, config: &FileConfig) -> anyhow::Result<ScanOptions> {
         let mut skip = config.scanning.skip.clone().unwrap_or_default();
         skip.extend(self.scanning.skip.clone());
+        let mut ignore = config.scanning.ignore.clone().unwrap_or_default();
+        ignore.extend(self.scanning.ignore.clone());
 
-        ScanOptions {
+        Ok(ScanOptions {
             verbose: self.scanning.verbose || config.scanning.verbose.unwrap_or(false),
             threads: self
                 .scanning
@@ -359,8 +1344,84 @@ impl Cli {
                 .or(config.scanning.threads)
                 .unwrap_or(0),
             skip,
+            ignore,
+            scan_hidden: self.scanning.scan_hidden || config.scanning.scan_hidden.unwrap_or(false),
+            allow_hidden: config.scanning.allow_hidden.clone().unwrap_or_default(),
+            exclude_names: config.scanning.exclude_names.clone().unwrap_or_default(),
+            no_exclude_names: config.scanning.no_exclude_names.clone().unwrap_or_default(),
             max_depth: self.scanning.max_depth.or(config.scanning.max_depth),
-        }
+            quick_size_threshold: self
+                .scanning
+                .quick_size_threshold
+                .or_else(|| {
+                    config
+                        .scanning
+                        .quick_size_threshold
+                        .as_deref()
+                        .and_then(|s| parse_size(s).ok())
+                })
+                .filter(|&threshold| threshold > 0),
+            size_backend: self
+                .scanning
+                .size_backend
+                .or_else(|| {
+                    config
+                        .scanning
+                        .size_backend
+                        .as_ref()
+                        .and_then(|s| SizeBackendKind::from_str(s, true).ok())
+                })
+                .unwrap_or_default(),
+            keep_marker: Some(
+                self.scanning
+                    .keep_marker
+                    .clone()
+                    .or_else(|| config.scanning.keep_marker.clone())
+                    .unwrap_or_else(|| DEFAULT_KEEP_MARKER.to_string()),
+            )
+            .filter(|marker| !marker.is_empty()),
+            age_source: self.age_source(config)?,
+            follow_symlinks: self.scanning.follow_symlinks.unwrap_or_else(|| {
+                let configured = config.scanning.follow_symlinks.as_deref();
+                configured.map_or_else(FollowSymlinks::default, |s| {
+                    FollowSymlinks::from_str(s, true).unwrap_or_default()
+                })
+            }),
+            cargo_caches: self.scanning.cargo_caches
+                || config.scanning.cargo_caches.unwrap_or(false),
+            conda_envs: self.scanning.conda_envs || config.scanning.conda_envs.unwrap_or(false),
+            excluded_artifacts: excluded_artifacts(config),
+            python_venvs: include_venv(config),
+            node_caches_only: self.scanning.node_caches_only
+                || config.scanning.node_caches_only.unwrap_or(false),
+            node_caches: config
+                .scanning
+                .node_caches
+                .clone()
+                .unwrap_or_else(|| DEFAULT_NODE_CACHES.iter().map(PathBuf::from).collect()),
+            container_caches: self.scanning.container_caches
+                || config.scanning.container_caches.unwrap_or(false),
+            container_cache_patterns: config
+                .scanning
+                .container_cache_patterns
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CONTAINER_CACHES.map(String::from).to_vec()),
+            rust_subdirs: rust_subdirs(&self.scanning.rust_subdirs, config),
+            docker: self.scanning.docker || config.scanning.docker.unwrap_or(false),
+            record_scans: self.scanning.record_scans
+                || config.scanning.record_scans.unwrap_or(false),
+            system_caches: if self.scanning.system_caches || config.caches.enabled.unwrap_or(false)
+            {
+                SystemCache::ALL
+                    .into_iter()
+                    .filter(|&cache| config.caches.includes(cache))
+                    .collect()
+            } else {
+                Vec::new()
+            },
+            extra_artifacts: extra_artifacts(config),
+            explain: self.scanning.explain,
+        })
     }
 
     /// Extract filtering options from CLI args and config file.
@@ -398,6 +1459,53 @@ impl Cli {
                 .name
                 .clone()
                 .or_else(|| config.filtering.name_pattern.clone()),
+            name_filter: self
+                .filtering
+                .name_filter
+                .clone()
+                .or_else(|| config.filtering.name_filter.clone()),
+            path_filter: self
+                .filtering
+                .path_filter
+                .clone()
+                .or_else(|| config.filtering.path_filter.clone()),
+            min_benefit: self
+                .filtering
+                .min_benefit
+                .clone()
+                .or_else(|| config.filtering.min_benefit.clone()),
+            repo_idle_days: self
+                .filtering
+                .repo_idle_days
+                .or(config.filtering.repo_idle_days)
+                .unwrap_or(0),
+            min_files: self
+                .filtering
+                .min_files
+                .or(config.filtering.min_files)
+                .unwrap_or(0),
+            stale_only: self.filtering.stale_only || config.filtering.stale_only.unwrap_or(false),
+            max_risk: self.filtering.max_risk.or_else(|| {
+                config
+                    .filtering
+                    .max_risk
+                    .as_deref()
+                    .and_then(|s| RiskLevel::from_str(s, true).ok())
+            }),
+            risk_overrides: RiskOverrides::from_config(config),
+            only_artifacts: artifact_names(
+                &self.filtering.only_artifacts,
+                config.filtering.only_artifacts.as_deref(),
+            ),
+            skip_artifacts: artifact_names(
+                &self.filtering.skip_artifacts,
+                config.filtering.skip_artifacts.as_deref(),
+            ),
+            filter_expr: self
+                .filtering
+                .filter_expr
+                .clone()
+                .or_else(|| config.filtering.filter_expr.clone()),
         }
     }
 
@@ -414,36 +1522,123 @@ impl Cli {
     /// # use cli::Cli;
     /// let args = Cli::parse_from(&["clean-dev-dirs", "--sort", "size", "--reverse"]);
     /// let sort_opts = args.sort_options(&FileConfig::default());
-    /// assert_eq!(sort_opts.criteria, Some(SortCriteria::Size));
+    /// assert_eq!(sort_opts.criteria, vec![SortCriteria::Size]);
     /// assert!(sort_opts.reverse);
     /// ```
     #[must_use]
     pub(crate) fn sort_options(&self, config: &FileConfig) -> SortOptions {
         SortOptions {
-            criteria: self.filtering.sort.or_else(|| {
+            criteria: if self.filtering.sort.is_empty() {
                 config
                     .filtering
                     .sort
-                    .as_ref()
-                    .and_then(|s| SortCriteria::from_str(s, true).ok())
-            }),
+                    .as_deref()
+                    .map(|s| {
+                        s.split(',')
+                            .filter_map(|c| SortCriteria::from_str(c.trim(), true).ok())
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            } else {
+                self.filtering.sort.clone()
+            },
             reverse: self.filtering.reverse || config.filtering.reverse.unwrap_or(false),
         }
     }
 }
 
+/// The build directory names given on the command line, or else those of
+/// the config.
+fn artifact_names(cli: &[String], config: Option<&[String]>) -> Vec<String> {
+    if cli.is_empty() {
+        config.unwrap_or_default().to_vec()
+    } else {
+        cli.to_vec()
+    }
+}
+
+/// The Rust target subdirectories given on the command line, or else those
+/// of the config, skipping unknown names.
+fn rust_subdirs(cli: &[RustSubdir], config: &FileConfig) -> Vec<RustSubdir> {
+    if !cli.is_empty() {
+        return cli.to_vec();
+    }
+    let configured = config.scanning.rust_subdirs.iter().flatten();
+    configured
+        .filter_map(|name| RustSubdir::from_str(name, true).ok())
+        .collect()
+}
+
+/// The extra artifacts of the `[artifacts.<type>]` tables of the config,
+/// skipping unknown project types.
+fn extra_artifacts(config: &FileConfig) -> HashMap<ProjectFilter, Vec<PathBuf>> {
+    config
+        .artifacts
+        .iter()
+        .filter_map(|(kind, artifacts)| {
+            let filter = ProjectFilter::from_str(kind, true).ok()?;
+            Some((filter, artifacts.extra.clone().unwrap_or_default()))
+        })
+        .collect()
+}
+
+/// The excluded build directories of the `[artifacts.<type>]` tables of the
+/// config, skipping unknown project types.
+fn excluded_artifacts(config: &FileConfig) -> HashMap<ProjectFilter, Vec<String>> {
+    config
+        .artifacts
+        .iter()
+        .filter_map(|(kind, artifacts)| {
+            let filter = ProjectFilter::from_str(kind, true).ok()?;
+            Some((filter, artifacts.exclude.clone()?))
+        })
+        .collect()
+}
+
+/// Whether `include_venv` under `[artifacts.python]` turns on cleaning
+/// Python virtual environments.
+fn include_venv(config: &FileConfig) -> bool {
+    config
+        .artifacts
+        .iter()
+        .find(|(kind, _)| kind.eq_ignore_ascii_case("python"))
+        .and_then(|(_, artifacts)| artifacts.include_venv)
+        .unwrap_or(false)
+}
+
+/// The project types named in a config file list, skipping unknown names.
+fn parse_types(names: Option<&[String]>) -> Vec<ProjectFilter> {
+    names
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|name| ProjectFilter::from_str(name.trim(), true).ok())
+        .collect()
+}
+
+/// Whether the value of the `CI` environment variable turns on CI mode.
+///
+/// CI services set it to `true` or `1`; an unset or empty variable, `0` and
+/// `false` leave CI mode off.
+fn ci_env_enabled(value: Option<&str>) -> bool {
+    value.is_some_and(|v| {
+        let v = v.trim();
+        !v.is_empty() && v != "0" && !v.eq_ignore_ascii_case("false")
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use clap::Parser;
     use clean_dev_dirs::config::file::{
-        FileConfig, FileExecutionConfig, FileFilterConfig, FileScanConfig,
+        FileArtifactConfig, FileCachesConfig, FileConfig, FileDirEntry, FileExecutionConfig,
+        FileFilterConfig, FileScanConfig,
     };
 
     // ── Existing tests (updated for FileConfig parameter) ──────────────
 
     #[test]
-    fn test_default_values() {
+    fn test_default_values() -> anyhow::Result<()> {
         let args = Cli::parse_from(["clean-dev-dirs"]);
         let config = FileConfig::default();
 
@@ -456,7 +1651,7 @@ mod tests {
         assert!(!exec_opts.keep_executables);
         assert!(exec_opts.use_trash);
 
-        let scan_opts = args.scan_options(&config);
+        let scan_opts = args.scan_options(&config)?;
         assert!(!scan_opts.verbose);
         assert_eq!(scan_opts.threads, 0);
         assert!(scan_opts.skip.is_empty());
@@ -465,6 +1660,11 @@ mod tests {
         assert_eq!(filter_opts.keep_size, "0");
         assert_eq!(filter_opts.keep_days, 0);
         assert!(filter_opts.name_pattern.is_none());
+        assert!(filter_opts.min_benefit.is_none());
+        assert_eq!(filter_opts.repo_idle_days, 0);
+        assert_eq!(filter_opts.min_files, 0);
+        assert!(!filter_opts.stale_only);
+        Ok(())
     }
 
     #[test]
@@ -508,6 +1708,55 @@ mod tests {
         assert_eq!(all_args.project_filter(&config), ProjectFilter::All);
     }
 
+    #[test]
+    fn test_type_filter_cli_and_config() {
+        let args = Cli::parse_from([
+            "clean-dev-dirs",
+            "--type",
+            "rust",
+            "--type",
+            "node,python",
+            "--exclude-type",
+            "python",
+        ]);
+        let filter = args.type_filter(&FileConfig::default());
+        assert!(filter.includes("rust") && filter.includes("node"));
+        assert!(!filter.includes("python") && !filter.includes("go"));
+
+        let args = Cli::parse_from(["clean-dev-dirs", "-p", "go", "--type", "zig"]);
+        assert_eq!(
+            args.type_filter(&FileConfig::default()),
+            TypeFilter::new([ProjectFilter::Go, ProjectFilter::Zig], [])
+        );
+
+        let config = FileConfig {
+            project_type: Some("rust".to_string()),
+            filtering: FileFilterConfig {
+                types: Some(vec!["node".to_string(), "nonsense".to_string()]),
+                exclude_types: Some(vec!["python".to_string()]),
+                ..FileFilterConfig::default()
+            },
+            ..FileConfig::default()
+        };
+        let from_config = Cli::parse_from(["clean-dev-dirs"]).type_filter(&config);
+        assert_eq!(
+            from_config,
+            TypeFilter::new(
+                [ProjectFilter::Rust, ProjectFilter::Node],
+                [ProjectFilter::Python]
+            )
+        );
+
+        // Types given on the command line replace the configured ones
+        let from_cli = Cli::parse_from(["clean-dev-dirs", "--type", "go", "--exclude-type", "go"])
+            .type_filter(&config);
+        assert_eq!(
+            from_cli,
+            TypeFilter::new([ProjectFilter::Go], [ProjectFilter::Go])
+        );
+        assert!(!from_cli.includes("go"));
+    }
+
     #[test]
     fn test_project_filter_short_flag() {
         let config = FileConfig::default();
@@ -538,6 +1787,76 @@ mod tests {
         let args_short = Cli::parse_from(["clean-dev-dirs", "-k"]);
         let exec_opts_short = args_short.execution_options(&config);
         assert!(exec_opts_short.keep_executables);
+        assert!(!exec_opts_short.keep_latest_only);
+    }
+
+    #[test]
+    fn test_preselect_flag_and_config() {
+        let args = Cli::parse_from(["clean-dev-dirs", "-i"]);
+        assert_eq!(
+            args.execution_options(&FileConfig::default()).preselect,
+            Preselect::All
+        );
+
+        let config = FileConfig {
+            execution: FileExecutionConfig {
+                preselect: Some("stale".to_string()),
+                ..FileExecutionConfig::default()
+            },
+            ..FileConfig::default()
+        };
+        assert_eq!(args.execution_options(&config).preselect, Preselect::Stale);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "-i", "--preselect", "large"]);
+        assert_eq!(args.execution_options(&config).preselect, Preselect::Large);
+    }
+
+    #[test]
+    fn test_keep_latest_only_flag_and_config() {
+        let args = Cli::parse_from(["clean-dev-dirs", "-k", "--keep-latest-only"]);
+        assert!(
+            args.execution_options(&FileConfig::default())
+                .keep_latest_only
+        );
+
+        let config = FileConfig {
+            execution: FileExecutionConfig {
+                keep_latest_only: Some(true),
+                ..FileExecutionConfig::default()
+            },
+            ..FileConfig::default()
+        };
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(args.execution_options(&config).keep_latest_only);
+    }
+
+    #[test]
+    fn test_ci_flag_and_config() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--ci"]);
+        assert!(args.execution_options(&FileConfig::default()).ci);
+
+        // An explicit config value takes precedence over the CI environment variable
+        let config = |ci| FileConfig {
+            execution: FileExecutionConfig {
+                ci: Some(ci),
+                ..FileExecutionConfig::default()
+            },
+            ..FileConfig::default()
+        };
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(args.execution_options(&config(true)).ci);
+        assert!(!args.execution_options(&config(false)).ci);
+    }
+
+    #[test]
+    fn test_ci_env_enabled() {
+        assert!(ci_env_enabled(Some("true")));
+        assert!(ci_env_enabled(Some("1")));
+        assert!(ci_env_enabled(Some("yes")));
+        assert!(!ci_env_enabled(None));
+        assert!(!ci_env_enabled(Some("")));
+        assert!(!ci_env_enabled(Some("0")));
+        assert!(!ci_env_enabled(Some("FALSE")));
     }
 
     #[test]
@@ -556,6 +1875,22 @@ mod tests {
         assert!(!exec_opts.use_trash);
     }
 
+    #[test]
+    fn test_empty_trash_flag() {
+        let config = FileConfig::default();
+        let args = Cli::parse_from(["clean-dev-dirs", "--empty-trash"]);
+        let exec_opts = args.execution_options(&config);
+        assert!(exec_opts.use_trash);
+        assert!(exec_opts.empty_trash);
+
+        assert!(
+            !Cli::parse_from(["clean-dev-dirs"])
+                .execution_options(&config)
+                .empty_trash
+        );
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--empty-trash", "--permanent"]).is_err());
+    }
+
     #[test]
     fn test_config_use_trash_false_disables_trash() {
         let args = Cli::parse_from(["clean-dev-dirs"]);
@@ -587,7 +1922,7 @@ mod tests {
     }
 
     #[test]
-    fn test_scanning_options() {
+    fn test_scanning_options() -> anyhow::Result<()> {
         let config = FileConfig::default();
         let args = Cli::parse_from([
             "clean-dev-dirs",
@@ -599,13 +1934,14 @@ mod tests {
             "--skip",
             ".git",
         ]);
-        let scan_opts = args.scan_options(&config);
+        let scan_opts = args.scan_options(&config)?;
 
         assert!(scan_opts.verbose);
         assert_eq!(scan_opts.threads, 8);
         assert_eq!(scan_opts.skip.len(), 2);
         assert!(scan_opts.skip.contains(&PathBuf::from("node_modules")));
         assert!(scan_opts.skip.contains(&PathBuf::from(".git")));
+        Ok(())
     }
 
     #[test]
@@ -649,8 +1985,8 @@ mod tests {
         let args = Cli::parse_from(["clean-dev-dirs"]);
         let config = FileConfig {
             dirs: Some(vec![
-                PathBuf::from("/config/dir1"),
-                PathBuf::from("/config/dir2"),
+                PathBuf::from("/config/dir1").into(),
+                PathBuf::from("/config/dir2").into(),
             ]),
             ..FileConfig::default()
         };
@@ -661,7 +1997,56 @@ mod tests {
     }
 
     #[test]
-    fn test_short_flags() {
+    fn test_root_budgets() -> anyhow::Result<()> {
+        let config = FileConfig {
+            dirs: Some(vec![
+                FileDirEntry::Root {
+                    path: PathBuf::from("/code"),
+                    budget: Some("100GB".to_string()),
+                },
+                PathBuf::from("/work").into(),
+            ]),
+            ..FileConfig::default()
+        };
+
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(
+            args.directories(&config),
+            vec![PathBuf::from("/code"), PathBuf::from("/work")]
+        );
+        assert_eq!(
+            args.root_budgets(&config)?,
+            vec![RootBudget {
+                root: PathBuf::from("/code"),
+                budget: 100_000_000_000,
+            }]
+        );
+
+        // Directories from the command line replace the budgeted roots
+        let args = Cli::parse_from(["clean-dev-dirs", "/code"]);
+        assert!(args.root_budgets(&config)?.is_empty());
+        let args = Cli::parse_from(["clean-dev-dirs", "--stdin"]);
+        assert!(args.stdin());
+        assert!(args.root_budgets(&config)?.is_empty());
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--stdin", "/code"]).is_err());
+
+        let invalid = FileConfig {
+            dirs: Some(vec![FileDirEntry::Root {
+                path: PathBuf::from("/code"),
+                budget: Some("lots".to_string()),
+            }]),
+            ..FileConfig::default()
+        };
+        assert!(
+            Cli::parse_from(["clean-dev-dirs"])
+                .root_budgets(&invalid)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_short_flags() -> anyhow::Result<()> {
         let config = FileConfig::default();
         let args = Cli::parse_from([
             "clean-dev-dirs",
@@ -680,16 +2065,17 @@ mod tests {
         assert_eq!(filter_opts.keep_size, "50MB");
         assert_eq!(filter_opts.keep_days, 7);
 
-        let scan_opts = args.scan_options(&config);
+        let scan_opts = args.scan_options(&config)?;
         assert_eq!(scan_opts.threads, 2);
         assert!(scan_opts.verbose);
 
         let exec_opts = args.execution_options(&config);
         assert!(exec_opts.interactive);
+        Ok(())
     }
 
     #[test]
-    fn test_multiple_skip_directories() {
+    fn test_multiple_skip_directories() -> anyhow::Result<()> {
         let config = FileConfig::default();
         let args = Cli::parse_from([
             "clean-dev-dirs",
@@ -703,7 +2089,7 @@ mod tests {
             "__pycache__",
         ]);
 
-        let scan_opts = args.scan_options(&config);
+        let scan_opts = args.scan_options(&config)?;
         assert_eq!(scan_opts.skip.len(), 4);
 
         let expected_dirs = vec![
@@ -716,6 +2102,7 @@ mod tests {
         for expected_dir in expected_dirs {
             assert!(scan_opts.skip.contains(&expected_dir));
         }
+        Ok(())
     }
 
     #[test]
@@ -736,7 +2123,7 @@ mod tests {
     }
 
     #[test]
-    fn test_zero_values() {
+    fn test_zero_values() -> anyhow::Result<()> {
         let config = FileConfig::default();
         let args = Cli::parse_from([
             "clean-dev-dirs",
@@ -752,14 +2139,15 @@ mod tests {
         assert_eq!(filter_opts.keep_size, "0");
         assert_eq!(filter_opts.keep_days, 0);
 
-        let scan_opts = args.scan_options(&config);
+        let scan_opts = args.scan_options(&config)?;
         assert_eq!(scan_opts.threads, 0);
+        Ok(())
     }
 
     // ── Config merging tests ───────────────────────────────────────────
 
     #[test]
-    fn test_config_values_used_when_cli_absent() {
+    fn test_config_values_used_when_cli_absent() -> anyhow::Result<()> {
         let args = Cli::parse_from(["clean-dev-dirs"]);
         let config = FileConfig {
             project_type: Some("rust".to_string()),
@@ -775,14 +2163,48 @@ mod tests {
                 verbose: Some(true),
                 skip: Some(vec![PathBuf::from(".cargo")]),
                 ignore: Some(vec![PathBuf::from(".git")]),
+                scan_hidden: None,
+                allow_hidden: None,
+                exclude_names: None,
+                no_exclude_names: None,
                 max_depth: None,
+                quick_size_threshold: None,
+                size_backend: None,
+                keep_marker: None,
+                age_source: None,
+                follow_symlinks: None,
+                cargo_caches: None,
+                conda_envs: None,
+                node_caches_only: None,
+                node_caches: None,
+                container_caches: None,
+                container_cache_patterns: None,
+                rust_subdirs: None,
+                docker: Some(true),
+                record_scans: None,
             },
             execution: FileExecutionConfig {
                 keep_executables: Some(true),
+                keep_latest_only: None,
                 interactive: Some(true),
+                preselect: None,
                 dry_run: Some(true),
                 use_trash: Some(true),
+                skip_dirty: None,
+                all_users: None,
+                notify: None,
+                verify_sizes: None,
+                prune_empty_parents: None,
+                batch_trash: None,
+                rename_first: None,
+                journal: None,
+                safe_mode: None,
+                delete_retries: None,
+                delete_retry_delay: None,
+                lock_wait: None,
+                ci: None,
             },
+            ..FileConfig::default()
         };
 
         assert_eq!(
@@ -795,20 +2217,22 @@ mod tests {
         assert_eq!(filter_opts.keep_size, "50MB");
         assert_eq!(filter_opts.keep_days, 7);
 
-        let scan_opts = args.scan_options(&config);
+        let scan_opts = args.scan_options(&config)?;
         assert_eq!(scan_opts.threads, 4);
         assert!(scan_opts.verbose);
         assert_eq!(scan_opts.skip, vec![PathBuf::from(".cargo")]);
+        assert!(scan_opts.docker);
 
         let exec_opts = args.execution_options(&config);
         assert!(exec_opts.keep_executables);
         assert!(exec_opts.interactive);
         assert!(exec_opts.dry_run);
         assert!(exec_opts.use_trash);
+        Ok(())
     }
 
     #[test]
-    fn test_cli_overrides_config_values() {
+    fn test_cli_overrides_config_values() -> anyhow::Result<()> {
         let args = Cli::parse_from([
             "clean-dev-dirs",
             "/cli/dir",
@@ -843,12 +2267,53 @@ mod tests {
         assert_eq!(filter_opts.keep_size, "100MB");
         assert_eq!(filter_opts.keep_days, 30);
 
-        let scan_opts = args.scan_options(&config);
+        let scan_opts = args.scan_options(&config)?;
         assert_eq!(scan_opts.threads, 8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_system_caches_from_cli_and_config() -> anyhow::Result<()> {
+        let plain = Cli::parse_from(["clean-dev-dirs"]);
+        let flag = Cli::parse_from(["clean-dev-dirs", "--system-caches"]);
+        let config = |enabled| FileConfig {
+            caches: FileCachesConfig {
+                enabled,
+                apt: Some(false),
+                go_modules: Some(true),
+                ..FileCachesConfig::default()
+            },
+            ..FileConfig::default()
+        };
+        let without_apt = vec![
+            SystemCache::Homebrew,
+            SystemCache::Pip,
+            SystemCache::CargoRegistry,
+            SystemCache::GoModules,
+            SystemCache::Sccache,
+        ];
+
+        assert!(
+            plain
+                .scan_options(&FileConfig::default())?
+                .system_caches
+                .is_empty()
+        );
+        assert!(plain.scan_options(&config(None))?.system_caches.is_empty());
+        assert_eq!(
+            flag.scan_options(&FileConfig::default())?.system_caches,
+            SystemCache::ALL
+        );
+        assert_eq!(flag.scan_options(&config(None))?.system_caches, without_apt);
+        assert_eq!(
+            plain.scan_options(&config(Some(true)))?.system_caches,
+            without_apt
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_skip_dirs_merged_from_both_sources() {
+    fn test_skip_dirs_merged_from_both_sources() -> anyhow::Result<()> {
         let args = Cli::parse_from(["clean-dev-dirs", "--skip", "node_modules"]);
         let config = FileConfig {
             scanning: FileScanConfig {
@@ -858,11 +2323,181 @@ mod tests {
             ..FileConfig::default()
         };
 
-        let scan_opts = args.scan_options(&config);
+        let scan_opts = args.scan_options(&config)?;
         assert_eq!(scan_opts.skip.len(), 3);
         assert!(scan_opts.skip.contains(&PathBuf::from(".cargo")));
         assert!(scan_opts.skip.contains(&PathBuf::from("vendor")));
         assert!(scan_opts.skip.contains(&PathBuf::from("node_modules")));
+        assert!(scan_opts.ignore.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_backend_cli_over_config() -> anyhow::Result<()> {
+        let mut config = FileConfig::default();
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(
+            args.scan_options(&config)?.size_backend,
+            SizeBackendKind::Portable
+        );
+
+        config.scanning.size_backend = Some("native".to_string());
+        assert_eq!(
+            args.scan_options(&config)?.size_backend,
+            SizeBackendKind::Native
+        );
+        let args = Cli::parse_from(["clean-dev-dirs", "--size-backend", "portable"]);
+        assert_eq!(
+            args.scan_options(&config)?.size_backend,
+            SizeBackendKind::Portable
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_age_source_cli_over_config() -> anyhow::Result<()> {
+        let mut config = FileConfig::default();
+        config.scanning.age_source = Some("newest-file".to_string());
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(
+            args.scan_options(&config)?.age_source,
+            AgeSource::NewestFile
+        );
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--age-source", "dir-mtime"]);
+        assert_eq!(args.scan_options(&config)?.age_source, AgeSource::DirMtime);
+
+        config.scanning.age_source = Some("newest".to_string());
+        assert!(args.scan_options(&config).is_ok());
+        let error = Cli::parse_from(["clean-dev-dirs"])
+            .scan_options(&config)
+            .err()
+            .map(|e| e.to_string());
+        assert!(error.is_some_and(|e| e.contains("`scanning.age_source`")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_follow_symlinks_cli_over_config() -> anyhow::Result<()> {
+        let mut config = FileConfig::default();
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(
+            args.scan_options(&config)?.follow_symlinks,
+            FollowSymlinks::Roots
+        );
+
+        config.scanning.follow_symlinks = Some("always".to_string());
+        assert_eq!(
+            args.scan_options(&config)?.follow_symlinks,
+            FollowSymlinks::Always
+        );
+        let args = Cli::parse_from(["clean-dev-dirs", "--follow-symlinks", "never"]);
+        assert_eq!(
+            args.scan_options(&config)?.follow_symlinks,
+            FollowSymlinks::Never
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_keep_marker_cli_over_config() -> anyhow::Result<()> {
+        let mut config = FileConfig::default();
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(
+            args.scan_options(&config)?.keep_marker.as_deref(),
+            Some(DEFAULT_KEEP_MARKER)
+        );
+
+        config.scanning.keep_marker = Some(".keep-build".to_string());
+        assert_eq!(
+            args.scan_options(&config)?.keep_marker.as_deref(),
+            Some(".keep-build")
+        );
+        let args = Cli::parse_from(["clean-dev-dirs", "--keep-marker", ""]);
+        assert_eq!(args.scan_options(&config)?.keep_marker, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collapse_below_cli_over_config() {
+        let mut config = FileConfig::default();
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(args.collapse_below(&config), 0);
+
+        config.filtering.collapse_below = Some("10MB".to_string());
+        assert_eq!(args.collapse_below(&config), 10_000_000);
+        let args = Cli::parse_from(["clean-dev-dirs", "--collapse-below", "1MiB"]);
+        assert_eq!(args.collapse_below(&config), 1_048_576);
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--collapse-below", "tiny"]).is_err());
+    }
+
+    #[test]
+    fn test_quick_size_threshold_cli_over_config() -> anyhow::Result<()> {
+        let mut config = FileConfig::default();
+        config.scanning.quick_size_threshold = Some("5GB".to_string());
+
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(
+            args.scan_options(&config)?.quick_size_threshold,
+            Some(5_000_000_000)
+        );
+        let args = Cli::parse_from(["clean-dev-dirs", "--quick-size-threshold", "1GiB"]);
+        assert_eq!(
+            args.scan_options(&config)?.quick_size_threshold,
+            Some(1_073_741_824)
+        );
+        let args = Cli::parse_from(["clean-dev-dirs", "--quick-size-threshold", "0"]);
+        assert_eq!(args.scan_options(&config)?.quick_size_threshold, None);
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--quick-size-threshold", "big"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_hidden_flag_and_allow_hidden_config() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let scan_opts = args.scan_options(&FileConfig::default())?;
+        assert!(!scan_opts.scan_hidden);
+        assert!(scan_opts.allow_hidden.is_empty());
+
+        let mut config = FileConfig::default();
+        config.scanning.allow_hidden = Some(vec![PathBuf::from(".config")]);
+        let args = Cli::parse_from(["clean-dev-dirs", "--scan-hidden"]);
+        let scan_opts = args.scan_options(&config)?;
+        assert!(scan_opts.scan_hidden);
+        assert_eq!(scan_opts.allow_hidden, vec![PathBuf::from(".config")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_exclude_names_from_config() -> anyhow::Result<()> {
+        let mut config = FileConfig::default();
+        config.scanning.exclude_names = Some(vec![PathBuf::from("generated")]);
+        config.scanning.no_exclude_names = Some(vec![PathBuf::from("dist")]);
+
+        let scan_opts = Cli::parse_from(["clean-dev-dirs"]).scan_options(&config)?;
+        assert_eq!(scan_opts.exclude_names, vec![PathBuf::from("generated")]);
+        assert_eq!(scan_opts.no_exclude_names, vec![PathBuf::from("dist")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_dirs_merged_from_both_sources() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs", "--ignore", "archive"]);
+        let config = FileConfig {
+            scanning: FileScanConfig {
+                ignore: Some(vec![PathBuf::from(".git")]),
+                ..FileScanConfig::default()
+            },
+            ..FileConfig::default()
+        };
+
+        let scan_opts = args.scan_options(&config)?;
+        assert_eq!(
+            scan_opts.ignore,
+            vec![PathBuf::from(".git"), PathBuf::from("archive")]
+        );
+        assert!(scan_opts.skip.is_empty());
+        Ok(())
     }
 
     #[test]
@@ -872,8 +2507,23 @@ mod tests {
             execution: FileExecutionConfig {
                 dry_run: Some(false),
                 interactive: Some(true),
+                preselect: None,
                 keep_executables: Some(false),
+                keep_latest_only: None,
                 use_trash: Some(true),
+                skip_dirty: None,
+                all_users: None,
+                notify: None,
+                verify_sizes: None,
+                prune_empty_parents: None,
+                batch_trash: None,
+                rename_first: None,
+                journal: None,
+                safe_mode: None,
+                delete_retries: None,
+                delete_retry_delay: None,
+                lock_wait: None,
+                ci: None,
             },
             ..FileConfig::default()
         };
@@ -927,6 +2577,60 @@ mod tests {
         assert_eq!(args.project_filter(&config), ProjectFilter::All);
     }
 
+    #[test]
+    fn test_size_units_resolution() {
+        let config = FileConfig {
+            units: Some("Binary".to_string()),
+            ..FileConfig::default()
+        };
+
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(args.size_units(&FileConfig::default()), SizeUnits::Decimal);
+        assert_eq!(args.size_units(&config), SizeUnits::Binary);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--units", "bytes"]);
+        assert_eq!(args.size_units(&config), SizeUnits::Bytes);
+
+        let invalid = FileConfig {
+            units: Some("furlongs".to_string()),
+            ..FileConfig::default()
+        };
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(args.size_units(&invalid), SizeUnits::Decimal);
+    }
+
+    #[test]
+    fn test_absolute_paths_resolution() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(!args.absolute_paths(&FileConfig::default()));
+
+        let config = FileConfig {
+            absolute_paths: Some(true),
+            ..FileConfig::default()
+        };
+        assert!(args.absolute_paths(&config));
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--absolute-paths"]);
+        assert!(args.absolute_paths(&FileConfig::default()));
+    }
+
+    #[test]
+    fn test_lang_resolution() {
+        let config = FileConfig {
+            lang: Some("DE".to_string()),
+            ..FileConfig::default()
+        };
+
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(args.lang(&config), Lang::De);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--lang", "en"]);
+        assert_eq!(args.lang(&config), Lang::En);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--lang", "de"]);
+        assert_eq!(args.lang(&FileConfig::default()), Lang::De);
+    }
+
     // ── Sorting option tests ────────────────────────────────────────────
 
     #[test]
@@ -935,7 +2639,7 @@ mod tests {
         let config = FileConfig::default();
         let sort_opts = args.sort_options(&config);
 
-        assert!(sort_opts.criteria.is_none());
+        assert!(sort_opts.criteria.is_empty());
         assert!(!sort_opts.reverse);
     }
 
@@ -945,7 +2649,7 @@ mod tests {
         let config = FileConfig::default();
         let sort_opts = args.sort_options(&config);
 
-        assert_eq!(sort_opts.criteria, Some(SortCriteria::Size));
+        assert_eq!(sort_opts.criteria, vec![SortCriteria::Size]);
         assert!(!sort_opts.reverse);
     }
 
@@ -958,12 +2662,14 @@ mod tests {
             ("age", SortCriteria::Age),
             ("name", SortCriteria::Name),
             ("type", SortCriteria::Type),
+            ("files", SortCriteria::Files),
+            ("growth", SortCriteria::Growth),
         ];
 
         for (input, expected) in test_cases {
             let args = Cli::parse_from(["clean-dev-dirs", "--sort", input]);
             let sort_opts = args.sort_options(&config);
-            assert_eq!(sort_opts.criteria, Some(expected));
+            assert_eq!(sort_opts.criteria, vec![expected]);
         }
     }
 
@@ -973,7 +2679,7 @@ mod tests {
         let config = FileConfig::default();
         let sort_opts = args.sort_options(&config);
 
-        assert_eq!(sort_opts.criteria, Some(SortCriteria::Name));
+        assert_eq!(sort_opts.criteria, vec![SortCriteria::Name]);
         assert!(sort_opts.reverse);
     }
 
@@ -983,7 +2689,7 @@ mod tests {
         let config = FileConfig::default();
         let sort_opts = args.sort_options(&config);
 
-        assert!(sort_opts.criteria.is_none());
+        assert!(sort_opts.criteria.is_empty());
         assert!(sort_opts.reverse);
     }
 
@@ -1000,7 +2706,7 @@ mod tests {
         };
         let sort_opts = args.sort_options(&config);
 
-        assert_eq!(sort_opts.criteria, Some(SortCriteria::Age));
+        assert_eq!(sort_opts.criteria, vec![SortCriteria::Age]);
         assert!(sort_opts.reverse);
     }
 
@@ -1016,7 +2722,7 @@ mod tests {
         };
         let sort_opts = args.sort_options(&config);
 
-        assert_eq!(sort_opts.criteria, Some(SortCriteria::Name));
+        assert_eq!(sort_opts.criteria, vec![SortCriteria::Name]);
     }
 
     #[test]
@@ -1031,7 +2737,7 @@ mod tests {
         };
         let sort_opts = args.sort_options(&config);
 
-        assert!(sort_opts.criteria.is_none());
+        assert!(sort_opts.criteria.is_empty());
     }
 
     #[test]
@@ -1046,7 +2752,42 @@ mod tests {
         };
         let sort_opts = args.sort_options(&config);
 
-        assert_eq!(sort_opts.criteria, Some(SortCriteria::Size));
+        assert_eq!(sort_opts.criteria, vec![SortCriteria::Size]);
+    }
+
+    #[test]
+    fn test_sort_options_multiple_criteria() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--sort", "size,name"]);
+        let config = FileConfig::default();
+        let sort_opts = args.sort_options(&config);
+
+        assert_eq!(
+            sort_opts.criteria,
+            vec![SortCriteria::Size, SortCriteria::Name]
+        );
+    }
+
+    #[test]
+    fn test_sort_options_multiple_criteria_from_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let config = FileConfig {
+            filtering: FileFilterConfig {
+                sort: Some("type, Age".to_string()),
+                ..FileFilterConfig::default()
+            },
+            ..FileConfig::default()
+        };
+        let sort_opts = args.sort_options(&config);
+
+        assert_eq!(
+            sort_opts.criteria,
+            vec![SortCriteria::Type, SortCriteria::Age]
+        );
+    }
+
+    #[test]
+    fn test_sort_options_rejects_unknown_cli_criterion() {
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--sort", "size,bogus"]).is_err());
     }
 
     #[test]
@@ -1093,18 +2834,68 @@ mod tests {
     }
 
     #[test]
-    fn test_name_pattern_config_fallback() {
-        let args = Cli::parse_from(["clean-dev-dirs"]);
+    fn test_name_and_path_filters() {
+        let args = Cli::parse_from([
+            "clean-dev-dirs",
+            "--name-filter",
+            "-poc$",
+            "--path-filter",
+            "/clients/",
+        ]);
         let config = FileConfig {
             filtering: FileFilterConfig {
-                name_pattern: Some("api-*".to_string()),
+                name_filter: Some("config".to_string()),
+                path_filter: Some("/work/".to_string()),
                 ..FileFilterConfig::default()
             },
             ..FileConfig::default()
         };
 
         let filter_opts = args.filter_options(&config);
-        assert_eq!(filter_opts.name_pattern.as_deref(), Some("api-*"));
+        assert_eq!(filter_opts.name_filter.as_deref(), Some("-poc$"));
+        assert_eq!(filter_opts.path_filter.as_deref(), Some("/clients/"));
+
+        let filter_opts = Cli::parse_from(["clean-dev-dirs"]).filter_options(&config);
+        assert_eq!(filter_opts.name_filter.as_deref(), Some("config"));
+        assert_eq!(filter_opts.path_filter.as_deref(), Some("/work/"));
+    }
+
+    #[test]
+    fn test_filter_expr_cli_over_config() {
+        let config = FileConfig {
+            filtering: FileFilterConfig {
+                filter_expr: Some("age > 90d".to_string()),
+                ..FileFilterConfig::default()
+            },
+            ..FileConfig::default()
+        };
+
+        let args = Cli::parse_from([
+            "clean-dev-dirs",
+            "--filter-expr",
+            "size > 1GB OR type == node",
+        ]);
+        assert_eq!(
+            args.filter_options(&config).filter_expr.as_deref(),
+            Some("size > 1GB OR type == node")
+        );
+        let filter_opts = Cli::parse_from(["clean-dev-dirs"]).filter_options(&config);
+        assert_eq!(filter_opts.filter_expr.as_deref(), Some("age > 90d"));
+    }
+
+    #[test]
+    fn test_name_pattern_config_fallback() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let config = FileConfig {
+            filtering: FileFilterConfig {
+                name_pattern: Some("api-*".to_string()),
+                ..FileFilterConfig::default()
+            },
+            ..FileConfig::default()
+        };
+
+        let filter_opts = args.filter_options(&config);
+        assert_eq!(filter_opts.name_pattern.as_deref(), Some("api-*"));
     }
 
     #[test]
@@ -1121,4 +2912,660 @@ mod tests {
         let filter_opts = args.filter_options(&config);
         assert_eq!(filter_opts.name_pattern.as_deref(), Some("cli-pat*"));
     }
+
+    #[test]
+    fn test_node_caches_only_flag_and_config() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let scan_opts = args.scan_options(&FileConfig::default())?;
+        assert!(!scan_opts.node_caches_only);
+        assert_eq!(scan_opts.node_caches.len(), DEFAULT_NODE_CACHES.len());
+
+        let mut config = FileConfig::default();
+        config.scanning.node_caches = Some(vec![PathBuf::from(".next/cache")]);
+        let args = Cli::parse_from(["clean-dev-dirs", "--node-caches-only"]);
+        let scan_opts = args.scan_options(&config)?;
+        assert!(scan_opts.node_caches_only);
+        assert_eq!(scan_opts.node_caches, vec![PathBuf::from(".next/cache")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_container_caches_flag_and_config() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let scan_opts = args.scan_options(&FileConfig::default())?;
+        assert!(!scan_opts.container_caches);
+        assert_eq!(
+            scan_opts.container_cache_patterns,
+            DEFAULT_CONTAINER_CACHES.map(String::from)
+        );
+
+        let mut config = FileConfig::default();
+        config.scanning.container_caches = Some(true);
+        config.scanning.container_cache_patterns = Some(vec!["docker/*/cache".to_string()]);
+        let scan_opts = args.scan_options(&config)?;
+        assert!(scan_opts.container_caches);
+        assert_eq!(scan_opts.container_cache_patterns, ["docker/*/cache"]);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--container-caches"]);
+        assert!(args.scan_options(&FileConfig::default())?.container_caches);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rust_subdirs_flag_and_config() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(
+            args.scan_options(&FileConfig::default())?
+                .rust_subdirs
+                .is_empty()
+        );
+
+        let mut config = FileConfig::default();
+        config.scanning.rust_subdirs = Some(vec!["Doc".to_string(), "objects".to_string()]);
+        let scan_opts = args.scan_options(&config)?;
+        assert_eq!(scan_opts.rust_subdirs, vec![RustSubdir::Doc]);
+
+        let args = Cli::parse_from([
+            "clean-dev-dirs",
+            "--rust-subdirs",
+            "incremental,fingerprint",
+        ]);
+        assert_eq!(
+            args.scan_options(&config)?.rust_subdirs,
+            vec![RustSubdir::Incremental, RustSubdir::Fingerprint]
+        );
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--rust-subdirs", "objects"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_flag() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(!args.scan_options(&FileConfig::default())?.explain);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--explain", "--json"]);
+        assert!(args.scan_options(&FileConfig::default())?.explain);
+        Ok(())
+    }
+
+    #[test]
+    fn test_extra_artifacts_from_config() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let mut config = FileConfig::default();
+        config.artifacts.insert(
+            "rust".to_string(),
+            FileArtifactConfig {
+                extra: Some(vec![PathBuf::from(".criterion")]),
+                risk: None,
+                exclude: None,
+                include_venv: None,
+            },
+        );
+        config.artifacts.insert(
+            "cobol".to_string(),
+            FileArtifactConfig {
+                extra: Some(vec![PathBuf::from("out")]),
+                risk: None,
+                exclude: None,
+                include_venv: None,
+            },
+        );
+
+        let scan_opts = args.scan_options(&config)?;
+        assert_eq!(scan_opts.extra_artifacts.len(), 1);
+        assert_eq!(
+            scan_opts.extra_artifacts.get(&ProjectFilter::Rust),
+            Some(&vec![PathBuf::from(".criterion")])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_include_venv_from_config() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let mut config = FileConfig::default();
+        assert!(!args.scan_options(&config)?.python_venvs);
+
+        // Only the Python table counts
+        config.artifacts.insert(
+            "all".to_string(),
+            FileArtifactConfig {
+                include_venv: Some(true),
+                ..FileArtifactConfig::default()
+            },
+        );
+        assert!(!args.scan_options(&config)?.python_venvs);
+
+        config.artifacts.insert(
+            "Python".to_string(),
+            FileArtifactConfig {
+                include_venv: Some(true),
+                ..FileArtifactConfig::default()
+            },
+        );
+        assert!(args.scan_options(&config)?.python_venvs);
+        Ok(())
+    }
+
+    #[test]
+    fn test_excluded_artifacts_from_config() -> anyhow::Result<()> {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let mut config = FileConfig::default();
+        for kind in ["node", "nosuchtype"] {
+            config.artifacts.insert(
+                kind.to_string(),
+                FileArtifactConfig {
+                    exclude: Some(vec!["dist".to_string()]),
+                    ..FileArtifactConfig::default()
+                },
+            );
+        }
+
+        let excluded = args.scan_options(&config)?.excluded_artifacts;
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(
+            excluded.get(&ProjectFilter::Node),
+            Some(&vec!["dist".to_string()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_benefit_cli_overrides_config() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--min-benefit", "200MB"]);
+        let config = FileConfig {
+            filtering: FileFilterConfig {
+                min_benefit: Some("50MB".to_string()),
+                ..FileFilterConfig::default()
+            },
+            ..FileConfig::default()
+        };
+
+        assert_eq!(
+            args.filter_options(&config).min_benefit.as_deref(),
+            Some("200MB")
+        );
+        assert_eq!(
+            Cli::parse_from(["clean-dev-dirs"])
+                .filter_options(&config)
+                .min_benefit
+                .as_deref(),
+            Some("50MB")
+        );
+    }
+
+    #[test]
+    fn test_skip_dirty_cli_or_config() {
+        let config = FileConfig::default();
+        assert!(
+            !Cli::parse_from(["clean-dev-dirs"])
+                .execution_options(&config)
+                .skip_dirty
+        );
+        assert!(
+            Cli::parse_from(["clean-dev-dirs", "--skip-dirty"])
+                .execution_options(&config)
+                .skip_dirty
+        );
+
+        let config = FileConfig {
+            execution: FileExecutionConfig {
+                skip_dirty: Some(true),
+                ..FileExecutionConfig::default()
+            },
+            ..FileConfig::default()
+        };
+        assert!(
+            Cli::parse_from(["clean-dev-dirs"])
+                .execution_options(&config)
+                .skip_dirty
+        );
+    }
+
+    #[test]
+    fn test_repo_idle_days_cli_overrides_config() {
+        let config = FileConfig {
+            filtering: FileFilterConfig {
+                repo_idle_days: Some(90),
+                ..FileFilterConfig::default()
+            },
+            ..FileConfig::default()
+        };
+
+        let from_config = Cli::parse_from(["clean-dev-dirs"]).filter_options(&config);
+        assert_eq!(from_config.repo_idle_days, 90);
+
+        let from_cli =
+            Cli::parse_from(["clean-dev-dirs", "--repo-idle-days", "180"]).filter_options(&config);
+        assert_eq!(from_cli.repo_idle_days, 180);
+
+        let alias =
+            Cli::parse_from(["clean-dev-dirs", "--repo-active-days", "14"]).filter_options(&config);
+        assert_eq!(alias.repo_idle_days, 14);
+    }
+
+    #[test]
+    fn test_stale_only_cli_or_config() {
+        let config = FileConfig {
+            filtering: FileFilterConfig {
+                stale_only: Some(true),
+                ..FileFilterConfig::default()
+            },
+            ..FileConfig::default()
+        };
+
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(!args.filter_options(&FileConfig::default()).stale_only);
+        assert!(args.filter_options(&config).stale_only);
+
+        let from_cli = Cli::parse_from(["clean-dev-dirs", "--stale-only"]);
+        assert!(from_cli.filter_options(&FileConfig::default()).stale_only);
+    }
+
+    #[test]
+    fn test_min_files_cli_overrides_config() {
+        let config = FileConfig {
+            filtering: FileFilterConfig {
+                min_files: Some(5_000),
+                ..FileFilterConfig::default()
+            },
+            ..FileConfig::default()
+        };
+
+        let from_config = Cli::parse_from(["clean-dev-dirs"]).filter_options(&config);
+        assert_eq!(from_config.min_files, 5_000);
+
+        let from_cli =
+            Cli::parse_from(["clean-dev-dirs", "--min-files", "100"]).filter_options(&config);
+        assert_eq!(from_cli.min_files, 100);
+    }
+
+    #[test]
+    fn test_max_risk_cli_overrides_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(args.filter_options(&FileConfig::default()).max_risk, None);
+
+        let mut config = FileConfig::default();
+        config.filtering.max_risk = Some("Medium".to_string());
+        assert_eq!(
+            args.filter_options(&config).max_risk,
+            Some(RiskLevel::Medium)
+        );
+
+        let from_cli = Cli::parse_from(["clean-dev-dirs", "--max-risk", "low"]);
+        assert_eq!(
+            from_cli.filter_options(&config).max_risk,
+            Some(RiskLevel::Low)
+        );
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--max-risk", "none"]).is_err());
+    }
+
+    #[test]
+    fn test_artifact_names_cli_overrides_config() {
+        let mut config = FileConfig::default();
+        config.filtering.skip_artifacts = Some(vec!["node_modules".to_string()]);
+
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        let options = args.filter_options(&config);
+        assert!(options.only_artifacts.is_empty());
+        assert_eq!(options.skip_artifacts, vec!["node_modules"]);
+
+        let args = Cli::parse_from([
+            "clean-dev-dirs",
+            "--only-artifacts",
+            ".next,dist",
+            "--skip-artifacts",
+            "dist",
+            "--skip-artifacts",
+            ".nuxt",
+        ]);
+        let options = args.filter_options(&config);
+        assert_eq!(options.only_artifacts, vec![".next", "dist"]);
+        assert_eq!(options.skip_artifacts, vec!["dist", ".nuxt"]);
+    }
+
+    #[test]
+    fn test_plan_flags() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--dry-run", "--plan-file", "plan.json"]);
+        assert_eq!(args.plan_file(), Some(Path::new("plan.json")));
+        assert_eq!(args.apply_plan(), None);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--apply-plan", "plan.json"]);
+        assert_eq!(args.apply_plan(), Some(Path::new("plan.json")));
+        assert_eq!(args.plan_tolerance(), DEFAULT_TOLERANCE_PERCENT);
+
+        let args = Cli::parse_from([
+            "clean-dev-dirs",
+            "--apply-plan",
+            "plan.json",
+            "--plan-tolerance",
+            "25",
+        ]);
+        assert_eq!(args.plan_tolerance(), 25);
+
+        assert!(
+            Cli::try_parse_from(["clean-dev-dirs", "--plan-file", "a", "--apply-plan", "b"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--plan-tolerance", "5"]).is_err());
+    }
+
+    #[test]
+    fn test_remote_hosts() {
+        let raw = [
+            "--host",
+            "builder1",
+            "--host",
+            "builder2",
+            "--keep-days",
+            "30",
+        ];
+        let args = Cli::parse_from(std::iter::once("clean-dev-dirs").chain(raw));
+        let hosts = args.remote_hosts(raw.iter().map(ToString::to_string).collect());
+
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].host, "builder1");
+        assert_eq!(hosts[1].host, "builder2");
+        assert_eq!(hosts[0].bin, DEFAULT_REMOTE_BIN);
+        assert_eq!(hosts[0].args, vec!["--keep-days", "30"]);
+
+        assert!(
+            Cli::parse_from(["clean-dev-dirs"])
+                .remote_hosts(Vec::new())
+                .is_empty()
+        );
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--host", "b1", "--json"]).is_err());
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--remote-bin", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_profile_flag() {
+        assert_eq!(Cli::parse_from(["clean-dev-dirs"]).profile(), None);
+        assert_eq!(
+            Cli::parse_from(["clean-dev-dirs", "--profile", "safe"]).profile(),
+            Some("safe")
+        );
+        assert_eq!(
+            Cli::parse_from(["clean-dev-dirs", "config", "show", "--profile", "safe"]).profile(),
+            Some("safe")
+        );
+    }
+
+    #[test]
+    fn test_schema_subcommand() {
+        let args = Cli::parse_from(["clean-dev-dirs", "schema"]);
+        assert!(matches!(args.subcommand, Some(Commands::Schema)));
+    }
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(Cli::parse_from(["clean-dev-dirs"]).output_format(), None);
+        assert_eq!(
+            Cli::parse_from(["clean-dev-dirs", "--json"]).output_format(),
+            Some(OutputFormat::Json)
+        );
+        assert_eq!(
+            Cli::parse_from(["clean-dev-dirs", "--output", "csv"]).output_format(),
+            Some(OutputFormat::Csv)
+        );
+        assert_eq!(
+            Cli::parse_from(["clean-dev-dirs", "--output", "md"]).output_format(),
+            Some(OutputFormat::Markdown)
+        );
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--json", "--output", "csv"]).is_err());
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--output", "paths0"]).is_err());
+    }
+
+    #[test]
+    fn test_print0_never_cleans_or_prompts() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--print0", "--yes"]);
+        assert_eq!(args.output_format(), Some(OutputFormat::Paths0));
+
+        let mut config = FileConfig::default();
+        config.execution.interactive = Some(true);
+        let opts = args.execution_options(&config);
+        assert!(opts.dry_run);
+        assert!(!opts.interactive);
+
+        for other in ["--json", "--interactive", "--docker"] {
+            assert!(Cli::try_parse_from(["clean-dev-dirs", "--print0", other]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_total_only_never_cleans_or_prompts() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--total-only", "--yes"]);
+        assert_eq!(args.output_format(), Some(OutputFormat::Total));
+
+        let mut config = FileConfig::default();
+        config.execution.interactive = Some(true);
+        let opts = args.execution_options(&config);
+        assert!(opts.dry_run);
+        assert!(!opts.interactive);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--total-only", "--units", "binary"]);
+        assert_eq!(args.output_format(), Some(OutputFormat::TotalFormatted));
+
+        for other in ["--json", "--print0", "--interactive", "--bucket-ages"] {
+            assert!(Cli::try_parse_from(["clean-dev-dirs", "--total-only", other]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_why_flag() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--why", "/code/app", "--json", "/code"]);
+        assert_eq!(args.why(), Some(Path::new("/code/app")));
+        assert_eq!(args.output_format(), Some(OutputFormat::Json));
+        assert_eq!(
+            args.directories(&FileConfig::default()),
+            vec![PathBuf::from("/code")]
+        );
+        assert_eq!(Cli::parse_from(["clean-dev-dirs"]).why(), None);
+
+        for other in ["--stdin", "--print0", "--interactive", "--bucket-ages"] {
+            assert!(Cli::try_parse_from(["clean-dev-dirs", "--why", "/code/app", other]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_age_bucket_flags() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--bucket-ages"]);
+        assert!(args.bucket_ages());
+        assert_eq!(args.clean_bucket(), None);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--clean-bucket", ">1y"]);
+        assert!(!args.bucket_ages());
+        assert_eq!(args.clean_bucket(), Some(AgeBucket::Older));
+
+        // Cleaning a bucket never asks
+        let mut config = FileConfig::default();
+        config.execution.interactive = Some(true);
+        let opts = args.execution_options(&config);
+        assert!(opts.yes);
+        assert!(!opts.interactive);
+
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--clean-bucket", "2y"]).is_err());
+        assert!(
+            Cli::try_parse_from(["clean-dev-dirs", "--bucket-ages", "--clean-bucket", "<30d"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["clean-dev-dirs", "--bucket-ages", "--json"]).is_err());
+    }
+
+    #[test]
+    fn test_notify_and_post_clean_hook() {
+        let mut config = FileConfig::default();
+        config.hooks.post_clean = Some("./report.sh".to_string());
+
+        let exec_opts = Cli::parse_from(["clean-dev-dirs", "--notify"]).execution_options(&config);
+        assert!(exec_opts.notify);
+        assert_eq!(exec_opts.post_clean.as_deref(), Some("./report.sh"));
+
+        let exec_opts =
+            Cli::parse_from(["clean-dev-dirs"]).execution_options(&FileConfig::default());
+        assert!(!exec_opts.notify);
+        assert!(exec_opts.post_clean.is_none());
+    }
+
+    #[test]
+    fn test_json_file_keeps_human_output() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--dry-run", "--json-file", "out.json"]);
+        assert_eq!(args.output_format(), None);
+        assert_eq!(
+            args.execution_options(&FileConfig::default()).json_file,
+            Some(PathBuf::from("out.json"))
+        );
+
+        for other in ["--json", "--print0", "--total-only"] {
+            assert!(
+                Cli::try_parse_from(["clean-dev-dirs", "--json-file", "out.json", other]).is_err(),
+                "{other}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_report_html() {
+        let args = Cli::parse_from(["clean-dev-dirs", "--json", "--report-html", "report.html"]);
+        assert_eq!(args.output_format(), Some(OutputFormat::Json));
+        assert_eq!(
+            args.execution_options(&FileConfig::default()).report_html,
+            Some(PathBuf::from("report.html"))
+        );
+        assert!(
+            Cli::try_parse_from(["clean-dev-dirs", "--report-html", "r.html", "--why", "."])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_all_users_cli_or_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(!args.execution_options(&FileConfig::default()).all_users);
+
+        let mut config = FileConfig::default();
+        config.execution.all_users = Some(true);
+        assert!(args.execution_options(&config).all_users);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--all-users"]);
+        assert!(args.execution_options(&FileConfig::default()).all_users);
+    }
+
+    #[test]
+    fn test_delete_retry_options() {
+        let exec_opts =
+            Cli::parse_from(["clean-dev-dirs"]).execution_options(&FileConfig::default());
+        assert_eq!(exec_opts.delete_retries, 3);
+        assert_eq!(exec_opts.delete_retry_delay, Duration::from_millis(100));
+
+        let mut config = FileConfig::default();
+        config.execution.delete_retries = Some(5);
+        config.execution.delete_retry_delay = Some(250);
+        let exec_opts = Cli::parse_from(["clean-dev-dirs"]).execution_options(&config);
+        assert_eq!(exec_opts.delete_retries, 5);
+        assert_eq!(exec_opts.delete_retry_delay, Duration::from_millis(250));
+
+        // CLI values take priority over the config file
+        let exec_opts = Cli::parse_from([
+            "clean-dev-dirs",
+            "--delete-retries",
+            "0",
+            "--delete-retry-delay",
+            "10",
+        ])
+        .execution_options(&config);
+        assert_eq!(exec_opts.delete_retries, 0);
+        assert_eq!(exec_opts.delete_retry_delay, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_lock_wait_flag_and_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert_eq!(
+            args.execution_options(&FileConfig::default()).lock_wait,
+            LockWait::Error
+        );
+
+        let mut config = FileConfig::default();
+        config.execution.lock_wait = Some("skip".to_string());
+        assert_eq!(args.execution_options(&config).lock_wait, LockWait::Skip);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--lock-wait", "wait"]);
+        assert_eq!(args.execution_options(&config).lock_wait, LockWait::Wait);
+    }
+
+    #[test]
+    fn test_verify_sizes_cli_or_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(!args.execution_options(&FileConfig::default()).verify_sizes);
+
+        let mut config = FileConfig::default();
+        config.execution.verify_sizes = Some(true);
+        assert!(args.execution_options(&config).verify_sizes);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--verify-sizes"]);
+        assert!(args.execution_options(&FileConfig::default()).verify_sizes);
+    }
+
+    #[test]
+    fn test_prune_empty_parents_cli_or_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(
+            !args
+                .execution_options(&FileConfig::default())
+                .prune_empty_parents
+        );
+
+        let mut config = FileConfig::default();
+        config.execution.prune_empty_parents = Some(true);
+        assert!(args.execution_options(&config).prune_empty_parents);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--prune-empty-parents"]);
+        assert!(
+            args.execution_options(&FileConfig::default())
+                .prune_empty_parents
+        );
+    }
+
+    #[test]
+    fn test_batch_trash_cli_or_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(!args.execution_options(&FileConfig::default()).batch_trash);
+
+        let mut config = FileConfig::default();
+        config.execution.batch_trash = Some(true);
+        assert!(args.execution_options(&config).batch_trash);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--batch-trash"]);
+        assert!(args.execution_options(&FileConfig::default()).batch_trash);
+    }
+
+    #[test]
+    fn test_rename_first_cli_or_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(!args.execution_options(&FileConfig::default()).rename_first);
+
+        let mut config = FileConfig::default();
+        config.execution.rename_first = Some(true);
+        assert!(args.execution_options(&config).rename_first);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--rename-first"]);
+        assert!(args.execution_options(&FileConfig::default()).rename_first);
+    }
+
+    #[test]
+    fn test_journal_cli_or_config() {
+        let args = Cli::parse_from(["clean-dev-dirs"]);
+        assert!(!args.execution_options(&FileConfig::default()).journal);
+
+        let mut config = FileConfig::default();
+        config.execution.journal = Some(true);
+        assert!(args.execution_options(&config).journal);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "--journal"]);
+        assert!(args.execution_options(&FileConfig::default()).journal);
+
+        let args = Cli::parse_from(["clean-dev-dirs", "status"]);
+        assert!(matches!(args.subcommand, Some(Commands::Status)));
+    }
 }
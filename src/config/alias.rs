@@ -0,0 +1,263 @@
+//! Aliases for common invocations.
+//!
+//! The `[aliases]` table of the config file names argument lists, so a long
+//! cron line can be shortened to `clean-dev-dirs @weekly` (or
+//! `clean-dev-dirs run weekly`):
+//!
+//! ```toml
+//! [aliases]
+//! weekly = "--keep-days 14 --keep-size 200MB --yes"
+//! work = "@weekly ~/work --profile work"
+//! ```
+//!
+//! An alias is expanded in place by [`expand`] before the arguments are
+//! parsed, and anything given after it is kept, so `@weekly --dry-run` adds
+//! to the alias. Arguments are split at whitespace, with single and double
+//! quotes and backslashes working as in a shell, but without variables. A
+//! leading `~/` is expanded to the home directory, as in the rest of the
+//! config file. An alias that starts with another `@alias` is expanded in
+//! turn; one that ends up referring to itself is an error.
+
+use std::{collections::BTreeMap, ffi::OsString, path::Path};
+
+use super::file::expand_tilde;
+use crate::error::{CleanDevDirsError, Result};
+
+/// The alias the command line `args` (including the program name) invokes,
+/// and how many arguments name it: `@<name>` or `run <name>` as the first
+/// argument.
+#[must_use]
+pub fn invoked_alias(args: &[OsString]) -> Option<(&str, usize)> {
+    let first = args.get(1)?.to_str()?;
+    if let Some(name) = first.strip_prefix('@') {
+        return (!name.is_empty()).then_some((name, 1));
+    }
+
+    // `run --help` and the like are left to the argument parser
+    let name = args.get(2)?.to_str()?;
+    (first == "run" && !name.starts_with('-')).then_some((name, 2))
+}
+
+/// Replace the alias that `args` invokes (see [`invoked_alias`]) by its
+/// arguments from `aliases`.
+///
+/// Arguments that don't start with an alias are returned unchanged.
+///
+/// # Errors
+///
+/// Returns an error if the alias (or one it refers to) isn't defined, has
+/// an unterminated quote, or refers back to itself.
+pub fn expand(args: Vec<OsString>, aliases: &BTreeMap<String, String>) -> Result<Vec<OsString>> {
+    let Some((name, consumed)) = invoked_alias(&args) else {
+        return Ok(args);
+    };
+
+    let words = alias_words(name, aliases)?;
+    let mut expanded = Vec::with_capacity(args.len() + words.len());
+    let mut args = args.into_iter();
+    expanded.extend(args.next());
+    expanded.extend(words.into_iter().map(OsString::from));
+    expanded.extend(args.skip(consumed));
+    Ok(expanded)
+}
+
+/// The arguments the alias `name` stands for, with any alias it starts with
+/// expanded.
+///
+/// # Errors
+///
+/// Returns an error if an alias in the chain isn't defined, can't be split
+/// into arguments, or the chain comes back to an alias already in it.
+pub fn alias_words(name: &str, aliases: &BTreeMap<String, String>) -> Result<Vec<String>> {
+    let mut chain = vec![name.to_string()];
+    let mut words = lookup(name, aliases)?;
+
+    while let Some(next) = words
+        .first()
+        .and_then(|word| word.strip_prefix('@'))
+        .filter(|next| !next.is_empty())
+        .map(str::to_string)
+    {
+        if chain.contains(&next) {
+            chain.push(next);
+            return Err(CleanDevDirsError::config(format!(
+                "Alias cycle: {}",
+                chain.join(" -> ")
+            )));
+        }
+
+        let rest = words.split_off(1);
+        words = lookup(&next, aliases)?;
+        words.extend(rest);
+        chain.push(next);
+    }
+
+    Ok(words)
+}
+
+/// The arguments of the alias `name`, without expanding other aliases.
+fn lookup(name: &str, aliases: &BTreeMap<String, String>) -> Result<Vec<String>> {
+    let Some(line) = aliases.get(name) else {
+        if aliases.is_empty() {
+            return Err(CleanDevDirsError::config(format!(
+                "Unknown alias '{name}': no aliases are defined in the config file"
+            )));
+        }
+        let available: Vec<&str> = aliases.keys().map(String::as_str).collect();
+        return Err(CleanDevDirsError::config(format!(
+            "Unknown alias '{name}' (available: {})",
+            available.join(", ")
+        )));
+    };
+
+    let words = split_words(line).ok_or_else(|| {
+        CleanDevDirsError::config(format!("Alias '{name}' has an unterminated quote"))
+    })?;
+    Ok(words
+        .into_iter()
+        .map(|word| {
+            if word == "~" || word.starts_with("~/") {
+                expand_tilde(Path::new(&word)).display().to_string()
+            } else {
+                word
+            }
+        })
+        .collect())
+}
+
+/// Split `line` into arguments the way a shell would, without expanding
+/// anything: whitespace separates arguments, single quotes keep everything
+/// up to the next single quote, double quotes keep everything but `\"` and
+/// `\\`, and a backslash outside quotes keeps the next character.
+///
+/// Returns `None` if a quote isn't closed.
+fn split_words(line: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // Whether `word` has started, so `''` gives an empty argument
+    let mut in_word = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+                continue;
+            }
+            '\'' => loop {
+                match chars.next()? {
+                    '\'' => break,
+                    c => word.push(c),
+                }
+            },
+            '"' => loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => match chars.next()? {
+                        c @ ('"' | '\\') => word.push(c),
+                        c => {
+                            word.push('\\');
+                            word.push(c);
+                        }
+                    },
+                    c => word.push(c),
+                }
+            },
+            '\\' => word.extend(chars.next()),
+            c => word.push(c),
+        }
+        in_word = true;
+    }
+
+    if in_word {
+        words.push(word);
+    }
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, line)| ((*name).to_string(), (*line).to_string()))
+            .collect()
+    }
+
+    fn args(line: &str) -> Vec<OsString> {
+        line.split(' ').map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words("  --keep-days 14\t--name-pattern 'my app*' \"a \\\"b\\\"\" c\\ d ''"),
+            Some(
+                [
+                    "--keep-days",
+                    "14",
+                    "--name-pattern",
+                    "my app*",
+                    "a \"b\"",
+                    "c d",
+                    ""
+                ]
+                .map(String::from)
+                .to_vec()
+            )
+        );
+        assert_eq!(split_words(""), Some(vec![]));
+        assert_eq!(split_words("--name-pattern 'open"), None);
+    }
+
+    #[test]
+    fn test_expand_alias() -> anyhow::Result<()> {
+        let table = aliases(&[
+            ("weekly", "--keep-days 14 --yes"),
+            ("work", "@weekly ~/work"),
+        ]);
+
+        assert_eq!(
+            expand(args("cdd @weekly --dry-run"), &table)?,
+            args("cdd --keep-days 14 --yes --dry-run")
+        );
+        let home = expand_tilde(Path::new("~/work"));
+        let mut expected = args("cdd --keep-days 14 --yes");
+        expected.push(home.into_os_string());
+        assert_eq!(expand(args("cdd run work"), &table)?, expected);
+        // Anything that isn't an alias is left to the argument parser
+        for line in ["cdd ~/code --json", "cdd run --help", "cdd @", "cdd"] {
+            assert_eq!(expand(args(line), &table)?, args(line));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_alias_errors() {
+        let table = aliases(&[
+            ("a", "@b --yes"),
+            ("b", "@a"),
+            ("quote", "--name-pattern 'x"),
+        ]);
+
+        let message = |name| alias_words(name, &table).map_err(|e| e.to_string());
+        assert_eq!(message("a"), Err("Alias cycle: a -> b -> a".to_string()));
+        assert_eq!(
+            message("nightly"),
+            Err("Unknown alias 'nightly' (available: a, b, quote)".to_string())
+        );
+        assert_eq!(
+            message("quote"),
+            Err("Alias 'quote' has an unterminated quote".to_string())
+        );
+        assert!(
+            alias_words("weekly", &BTreeMap::new())
+                .is_err_and(|e| e.to_string().contains("no aliases are defined"))
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Display configuration for human-readable output.
+//!
+//! This module defines the options that control how projects, sizes and
+//! messages are shown: the units of sizes, the language of messages, how
+//! project paths are written, and the risk levels set in the config file.
+
+use crate::{
+    i18n::{Lang, Message},
+    risk::RiskOverrides,
+    utils::{SizeUnits, format_bytes},
+};
+
+/// Configuration for how results are shown.
+///
+/// Built once from the command line and the config file and passed to
+/// everything that prints, so no formatting depends on process-wide state.
+#[derive(Clone, Debug, Default)]
+pub struct DisplayOptions {
+    /// Units to show sizes in
+    pub units: SizeUnits,
+
+    /// Language of the messages
+    pub lang: Lang,
+
+    /// Whether to show absolute project paths instead of paths relative to
+    /// the scan root
+    pub absolute_paths: bool,
+
+    /// Risk levels set in the config file on top of the built-in ones
+    pub risk_overrides: RiskOverrides,
+}
+
+impl DisplayOptions {
+    /// Format a byte count in the selected units.
+    #[must_use]
+    pub fn bytes(&self, size: u64) -> String {
+        format_bytes(size, self.units)
+    }
+
+    /// The text of `message` in the selected language.
+    #[must_use]
+    pub const fn text(&self, message: Message) -> &'static str {
+        message.text(self.lang)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_options() {
+        let opts = DisplayOptions {
+            units: SizeUnits::Bytes,
+            lang: Lang::De,
+            ..DisplayOptions::default()
+        };
+
+        assert_eq!(opts.bytes(1_500), "1500 B");
+        assert_eq!(opts.text(Message::Directory), "Verzeichnis");
+        assert_eq!(DisplayOptions::default().bytes(1_500), "1.50 kB");
+    }
+}
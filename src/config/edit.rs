@@ -0,0 +1,262 @@
+//! Reading and changing single settings of the configuration file.
+//!
+//! `clean-dev-dirs config get <KEY>` and `config set <KEY> <VALUE>` address a
+//! setting by its dotted key, such as `filtering.keep_size` or
+//! `profile.ci.execution.use_trash`. Changes are made with `toml_edit`, so
+//! the comments and layout of the rest of the file are kept.
+
+use toml_edit::{DocumentMut, Item, Key, Table, TableLike, TomlError, Value};
+
+use super::validate::{self, Severity};
+use crate::error::{CleanDevDirsError, Result};
+
+/// Look up the setting at the dotted `key` in the TOML config `content`.
+///
+/// Strings are returned without quotes, other values as written in TOML.
+/// Returns `None` if the setting isn't in the file.
+///
+/// # Errors
+///
+/// Returns an error if `content` isn't valid TOML, or if `key` isn't a
+/// setting the config knows or names a whole table.
+pub fn get(content: &str, key: &str) -> Result<Option<String>> {
+    let path = parse_key(key)?;
+    let doc: DocumentMut = content.parse().map_err(invalid_toml)?;
+
+    let mut item = doc.as_item();
+    for segment in &path {
+        match item.get(segment) {
+            Some(child) => item = child,
+            None => return Ok(None),
+        }
+    }
+
+    match item {
+        Item::Value(Value::String(s)) => Ok(Some(s.value().clone())),
+        Item::Value(value) => Ok(Some(value.to_string().trim().to_string())),
+        _ => Err(CleanDevDirsError::config(format!(
+            "`{key}` is a table; name one of its settings, e.g. `{key}.<name>`"
+        ))),
+    }
+}
+
+/// Set the setting at the dotted `key` in the TOML config `content` and
+/// return the updated config.
+///
+/// `value` is read as a TOML value where that fits the setting (`4`, `true`,
+/// `["~/code", "~/work"]`) and as a plain string otherwise, so `200MB` needs
+/// no quotes. Missing tables are created.
+///
+/// # Errors
+///
+/// Returns an error if `content` isn't valid TOML, if `key` isn't a setting
+/// the config knows, or if `value` isn't valid for it.
+pub fn set(content: &str, key: &str, value: &str) -> Result<String> {
+    let path = parse_key(key)?;
+    let doc: DocumentMut = content.parse().map_err(invalid_toml)?;
+    let existing_errors = error_messages(content);
+
+    let typed = value.parse::<Value>().ok();
+    let plain = Value::from(value);
+
+    let mut problem = None;
+    for candidate in typed.into_iter().chain([plain]) {
+        let mut updated = doc.clone();
+        let new_top_level_key = path.len() == 1 && !updated.contains_key(&path[0]);
+        insert(updated.as_table_mut(), &path, candidate)?;
+        if new_top_level_key {
+            keep_below_leading_comments(updated.as_table_mut(), &path[0]);
+        }
+        let updated = updated.to_string();
+
+        match error_messages(&updated)
+            .into_iter()
+            .find(|message| !existing_errors.contains(message))
+        {
+            Some(message) => problem = problem.or(Some(message)),
+            None => return Ok(updated),
+        }
+    }
+
+    Err(CleanDevDirsError::config(format!(
+        "Invalid value `{value}` for `{key}`: {}",
+        problem.unwrap_or_default()
+    )))
+}
+
+/// The error for a config file that isn't valid TOML.
+fn invalid_toml(error: TomlError) -> CleanDevDirsError {
+    CleanDevDirsError::config("The config file is not valid TOML").with_source(error)
+}
+
+/// Split a dotted key into its parts, checking that the config knows it.
+fn parse_key(key: &str) -> Result<Vec<String>> {
+    let path: Vec<String> = Key::parse(key)
+        .map_err(|e| CleanDevDirsError::config(format!("Invalid key `{key}`: {}", e.message())))?
+        .iter()
+        .map(|k| k.get().to_string())
+        .collect();
+
+    let parts: Vec<&str> = path.iter().map(String::as_str).collect();
+    if let Some(message) = validate::unknown_key(&parts) {
+        return Err(CleanDevDirsError::config(message));
+    }
+    Ok(path)
+}
+
+/// Put `value` at `path` below `table`, creating the tables in between.
+///
+/// A replaced value keeps its formatting, including a trailing comment.
+fn insert(table: &mut dyn TableLike, path: &[String], mut value: Value) -> Result<()> {
+    let Some((key, parents)) = path.split_last() else {
+        return Err(CleanDevDirsError::config("The key is empty"));
+    };
+
+    let mut table = table;
+    for (depth, parent) in parents.iter().enumerate() {
+        let item = table.entry(parent).or_insert_with(|| {
+            let mut new = Table::new();
+            new.set_implicit(true);
+            Item::Table(new)
+        });
+        table = item.as_table_like_mut().ok_or_else(|| {
+            CleanDevDirsError::config(format!("`{}` is not a table", path[..=depth].join(".")))
+        })?;
+    }
+
+    match table.get_mut(key) {
+        Some(Item::Value(old)) => {
+            *value.decor_mut() = old.decor().clone();
+            *old = value;
+        }
+        Some(Item::Table(_) | Item::ArrayOfTables(_)) => {
+            return Err(CleanDevDirsError::config(format!(
+                "`{}` is a table; set one of its settings instead",
+                path.join(".")
+            )));
+        }
+        _ => {
+            value.decor_mut().clear();
+            table.insert(key, Item::Value(value));
+        }
+    }
+    Ok(())
+}
+
+/// Move the comments at the start of the file above the new top-level `key`.
+///
+/// Top-level keys are written before the first table, and comments before
+/// the first table belong to its header. Without this, the first key added to
+/// a file that only has tables (like the template) would end up above the
+/// file's opening comments.
+pub(crate) fn keep_below_leading_comments(root: &mut Table, key: &str) {
+    if root.iter().any(|(k, item)| k != key && item.is_value()) {
+        return;
+    }
+
+    let first = root
+        .iter()
+        .filter_map(|(k, item)| Some((item.as_table()?.position()?, k.to_string())))
+        .min();
+    let Some(first) = first.and_then(|(_, k)| root.get_mut(&k)?.as_table_mut()) else {
+        return;
+    };
+
+    let comments = first
+        .decor()
+        .prefix()
+        .and_then(|p| p.as_str())
+        .unwrap_or_default()
+        .to_string();
+    first.decor_mut().set_prefix("\n");
+    if let Some(mut key) = root.key_mut(key) {
+        key.leaf_decor_mut().set_prefix(comments);
+    }
+}
+
+/// Messages of the errors [`validate::validate`] finds in `content`.
+fn error_messages(content: &str) -> Vec<String> {
+    validate::validate(content)
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| d.message)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "# my config\n\n[filtering]\nkeep_size = \"50MB\"  # skip small ones\n\n[scanning]\n# threads = 0\n";
+
+    #[test]
+    fn test_get() -> Result<()> {
+        let content = "dirs = [\"~/code\"]\n\n[filtering]\nkeep_size = \"50MB\"\nkeep_days = 7\n";
+
+        assert_eq!(
+            get(content, "filtering.keep_size")?.as_deref(),
+            Some("50MB")
+        );
+        assert_eq!(get(content, "filtering.keep_days")?.as_deref(), Some("7"));
+        assert_eq!(get(content, "dirs")?.as_deref(), Some("[\"~/code\"]"));
+        assert_eq!(get(content, "scanning.threads")?, None);
+        assert!(get(content, "filtering").is_err());
+        assert!(get(content, "filtering.keep_sizee").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_keeps_comments_and_infers_types() -> Result<()> {
+        let updated = set(CONFIG, "filtering.keep_size", "200MB")?;
+        assert_eq!(
+            updated,
+            CONFIG.replace("\"50MB\"  # skip", "\"200MB\"  # skip")
+        );
+
+        let updated = set(CONFIG, "scanning.threads", "4")?;
+        assert!(updated.contains("[scanning]\nthreads = 4\n# threads = 0\n"));
+
+        // A number is only kept as a number where the setting takes one
+        let updated = set(CONFIG, "filtering.keep_size", "100")?;
+        assert!(updated.contains("keep_size = \"100\""));
+
+        let updated = set(CONFIG, "dirs", "[\"~/code\", \"~/work\"]")?;
+        assert_eq!(
+            get(&updated, "dirs")?.as_deref(),
+            Some("[\"~/code\", \"~/work\"]")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_creates_tables() -> Result<()> {
+        let updated = set("", "profile.ci.execution.use_trash", "false")?;
+        assert_eq!(updated, "[profile.ci.execution]\nuse_trash = false\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_top_level_key_stays_below_leading_comments() -> Result<()> {
+        let updated = set(CONFIG, "units", "binary")?;
+        assert_eq!(
+            updated,
+            "# my config\n\nunits = \"binary\"\n\n[filtering]\nkeep_size = \"50MB\"  # skip small ones\n\n[scanning]\n# threads = 0\n"
+        );
+
+        let updated = set(&updated, "project_type", "rust")?;
+        assert!(
+            updated.starts_with("# my config\n\nunits = \"binary\"\nproject_type = \"rust\"\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_rejects_unknown_keys_and_invalid_values() {
+        let unknown = set(CONFIG, "filtering.keep_sizee", "1GB");
+        assert!(unknown.is_err_and(|e| e.to_string().contains("did you mean `keep_size`?")));
+
+        assert!(set(CONFIG, "scanning.threads", "many").is_err());
+        assert!(set(CONFIG, "filtering.keep_size", "50XB").is_err());
+        assert!(set(CONFIG, "filtering", "1").is_err());
+    }
+}
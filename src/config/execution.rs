@@ -3,6 +3,43 @@
 //! This module defines the options that control how cleanup operations are executed,
 //! including dry-run mode and interactive selection.
 
+use std::{path::PathBuf, time::Duration};
+
+use clap::ValueEnum;
+
+use crate::paths::Paths;
+
+/// Which projects start out checked in interactive selection.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Default)]
+pub enum Preselect {
+    /// Check every project
+    #[default]
+    All,
+
+    /// Check nothing
+    None,
+
+    /// Check projects whose build artifacts haven't been modified in 30 days
+    Stale,
+
+    /// Check projects whose build artifacts take up at least 1 GB
+    Large,
+}
+
+/// What to do when another run holds the instance lock.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Default)]
+pub enum LockWait {
+    /// Wait until the other run finishes
+    Wait,
+
+    /// Exit successfully without doing anything
+    Skip,
+
+    /// Exit with an error
+    #[default]
+    Error,
+}
+
 /// Configuration for cleanup execution behavior.
 ///
 /// This struct provides a simplified interface to execution-related options,
@@ -16,9 +53,15 @@ pub struct ExecutionOptions {
     /// Whether to use interactive project selection
     pub interactive: bool,
 
+    /// Which projects start out checked in interactive selection
+    pub preselect: Preselect,
+
     /// Whether to preserve compiled executables before cleaning
     pub keep_executables: bool,
 
+    /// Whether to prune older preserved copies of executables, keeping only the latest
+    pub keep_latest_only: bool,
+
     /// Whether to move directories to the system trash instead of permanently deleting them.
     ///
     /// Defaults to `true`. Set to `false` via the `--permanent` CLI flag or
@@ -29,6 +72,75 @@ pub struct ExecutionOptions {
     ///
     /// Set via `--yes` / `-y`. CLI-only; not configurable via TOML.
     pub yes: bool,
+
+    /// Whether to remove the trashed build directories from the trash right
+    /// after the cleanup.
+    ///
+    /// Set via `--empty-trash`. CLI-only; not configurable via TOML.
+    pub empty_trash: bool,
+
+    /// Whether to skip projects with uncommitted changes in their git repository.
+    pub skip_dirty: bool,
+
+    /// Whether to include projects owned by other users, which are skipped by default.
+    pub all_users: bool,
+
+    /// Whether to show a desktop notification when a cleanup run finishes.
+    pub notify: bool,
+
+    /// File to write the `--json` document to, while the human-readable
+    /// output stays on the terminal.
+    ///
+    /// Set via `--json-file`. CLI-only; not configurable via TOML.
+    pub json_file: Option<PathBuf>,
+
+    /// File to write a self-contained HTML report of the run to (see
+    /// [`report`](crate::report)), next to the normal output.
+    ///
+    /// Set via `--report-html`. CLI-only; not configurable via TOML.
+    pub report_html: Option<PathBuf>,
+
+    /// Whether to re-measure each build directory just before removing it, so the
+    /// reported space freed doesn't rely on sizes from the scan.
+    pub verify_sizes: bool,
+
+    /// Whether to remove the directories a removal leaves empty, up to the project root.
+    pub prune_empty_parents: bool,
+
+    /// Whether to move build directories to the trash in batches per volume
+    /// instead of one at a time.
+    pub batch_trash: bool,
+
+    /// Whether to rename build directories to a hidden sibling before deleting
+    /// them permanently, so their paths disappear at once.
+    pub rename_first: bool,
+
+    /// Whether to record each removal in the journal before it begins and
+    /// after it ends, so `status` can tell what an interrupted run did.
+    pub journal: bool,
+
+    /// How many times a failed removal is retried before the project is marked as failed.
+    pub delete_retries: u32,
+
+    /// Wait before the first retry of a failed removal; doubled for each further retry.
+    pub delete_retry_delay: Duration,
+
+    /// What to do when another run that may clean holds the instance lock.
+    pub lock_wait: LockWait,
+
+    /// Whether to run in CI mode: no prompts, colors or spinners, and no
+    /// deletion without `yes`.
+    pub ci: bool,
+
+    /// Shell command to run after a cleanup run finishes (`[hooks] post_clean`).
+    pub post_clean: Option<String>,
+
+    /// Command template to run before each project is cleaned (`[hooks] pre_project_clean`).
+    pub pre_project_clean: Option<String>,
+
+    /// Where the history, journal, snoozes and other files kept between
+    /// runs are read and written.
+    pub paths: Paths,
 }
 
 #[cfg(test)]
@@ -40,9 +152,29 @@ mod tests {
         let exec_opts = ExecutionOptions {
             dry_run: true,
             interactive: false,
+            preselect: Preselect::All,
             keep_executables: false,
+            keep_latest_only: false,
             use_trash: false,
             yes: false,
+            empty_trash: false,
+            skip_dirty: false,
+            all_users: false,
+            notify: false,
+            json_file: None,
+            report_html: None,
+            verify_sizes: false,
+            prune_empty_parents: false,
+            batch_trash: false,
+            rename_first: false,
+            journal: false,
+            delete_retries: 3,
+            delete_retry_delay: Duration::from_millis(100),
+            lock_wait: LockWait::Error,
+            ci: false,
+            post_clean: None,
+            pre_project_clean: None,
+            paths: Paths::default(),
         };
 
         assert!(exec_opts.dry_run);
@@ -56,9 +188,29 @@ mod tests {
         let original = ExecutionOptions {
             dry_run: true,
             interactive: false,
+            preselect: Preselect::All,
             keep_executables: true,
+            keep_latest_only: false,
             use_trash: true,
             yes: false,
+            empty_trash: false,
+            skip_dirty: false,
+            all_users: false,
+            notify: false,
+            json_file: None,
+            report_html: None,
+            verify_sizes: false,
+            prune_empty_parents: false,
+            batch_trash: false,
+            rename_first: false,
+            journal: false,
+            delete_retries: 3,
+            delete_retry_delay: Duration::from_millis(100),
+            lock_wait: LockWait::Error,
+            ci: false,
+            post_clean: None,
+            pre_project_clean: None,
+            paths: Paths::default(),
         };
         let cloned = original.clone();
 
@@ -17,6 +17,13 @@
 //! # dir = "~/Projects"
 //! # Multiple directories:
 //! # dirs = ["~/Projects", "~/work/client"]
+//! # Directories with a size budget for their build artifacts:
+//! # dirs = [{ path = "~/code", budget = "100GB" }, "~/work/client"]
+//! units = "binary"    # sizes in GiB/MiB; "decimal" (default) or "bytes"
+//! lang = "de"         # messages in German; the locale's language by default
+//! # absolute_paths = true  # show full project paths instead of relative to the scan roots
+//! # state_dir = "/scratch/clean-dev-dirs/state"
+//! # cache_dir = "/scratch/clean-dev-dirs/cache"
 //!
 //! [filtering]
 //! keep_size = "50MB"
@@ -24,6 +31,13 @@
 //! sort = "size"
 //! reverse = false
 //! # name_pattern = "my-*"
+//! # min_benefit = "100MB"
+//! # repo_idle_days = 180
+//! # min_files = 10000
+//! # stale_only = true
+//! # max_risk = "low"
+//! # skip_artifacts = ["node_modules"]
+//! # filter_expr = "size > 1GB AND age > 90d"
 //!
 //! [scanning]
 //! threads = 4
@@ -31,18 +45,47 @@
 //! skip = [".cargo", "vendor"]
 //! ignore = [".git"]
 //! max_depth = 5
+//! # quick_size_threshold = "5GB"
+//! # size_backend = "native"
+//! # keep_marker = ".keep-build"
 //!
 //! [execution]
 //! keep_executables = true
 //! interactive = false
 //! dry_run = false
 //! use_trash = true    # default; set to false for permanent deletion
+//!
+//! [artifacts.rust]
+//! extra = ["flamegraphs", ".criterion"]
+//!
+//! [hooks]
+//! post_clean = "~/bin/after-clean.sh"
+//! pre_project_clean = "/usr/local/bin/backup.sh {path} {type}"
+//!
+//! [caches]
+//! enabled = true
+//! apt = false
+//!
+//! # Aliases for common invocations, run with `clean-dev-dirs @weekly`
+//! [aliases]
+//! weekly = "--keep-days 14 --keep-size 200MB --yes"
+//!
+//! # Named profiles, selected with `--profile aggressive`
+//! [profile.aggressive.filtering]
+//! keep_days = 0
+//! keep_size = "0"
 //! ```
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use serde::Deserialize;
 
+use crate::caches::SystemCache;
+use crate::error::{CleanDevDirsError, Result};
+
 /// Top-level configuration file structure.
 ///
 /// All fields are `Option<T>` so we can detect which values are present in the
@@ -53,11 +96,26 @@ pub struct FileConfig {
     pub project_type: Option<String>,
 
     /// Default directories to scan (plural; takes priority over `dir`)
-    pub dirs: Option<Vec<PathBuf>>,
+    pub dirs: Option<Vec<FileDirEntry>>,
 
     /// Default directory to scan (legacy single-dir; kept for backward compatibility)
     pub dir: Option<PathBuf>,
 
+    /// Units used to display sizes (`"binary"`, `"decimal"` or `"bytes"`)
+    pub units: Option<String>,
+
+    /// Language of the messages (`"en"` or `"de"`); the locale's by default
+    pub lang: Option<String>,
+
+    /// Show project paths in full rather than relative to their scan roots
+    pub absolute_paths: Option<bool>,
+
+    /// Directory for the history, snoozes and size snapshots (see [`paths`](crate::paths))
+    pub state_dir: Option<PathBuf>,
+
+    /// Directory for disposable data (see [`paths`](crate::paths))
+    pub cache_dir: Option<PathBuf>,
+
     /// Filtering options
     #[serde(default)]
     pub filtering: FileFilterConfig,
@@ -69,6 +127,69 @@ pub struct FileConfig {
     /// Execution options
     #[serde(default)]
     pub execution: FileExecutionConfig,
+
+    /// Per-project-type artifact settings, keyed by type name (e.g. `"rust"`, `"node"`).
+    ///
+    /// The special key `"all"` applies to every project type.
+    #[serde(default)]
+    pub artifacts: BTreeMap<String, FileArtifactConfig>,
+    /// Commands to run at points in the cleanup lifecycle
+    #[serde(default)]
+    pub hooks: FileHooksConfig,
+
+    /// Package manager caches to report and clean
+    #[serde(default)]
+    pub caches: FileCachesConfig,
+
+    /// Named profiles (`[profile.<name>]`) selectable with `--profile <name>`.
+    #[serde(default)]
+    pub profile: BTreeMap<String, FileProfileConfig>,
+
+    /// Argument lists run with `@<name>` or `run <name>` (see [`alias`](super::alias))
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+}
+
+/// An entry of the `dirs` list: a plain path or a table with a size budget.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum FileDirEntry {
+    /// A directory to scan, e.g. `"~/code"`
+    Path(PathBuf),
+
+    /// A directory with an optional budget, e.g. `{ path = "~/code", budget = "100GB" }`
+    Root {
+        /// Directory to scan
+        path: PathBuf,
+
+        /// Maximum total artifact size under this directory (e.g., `"100GB"`)
+        budget: Option<String>,
+    },
+}
+
+impl FileDirEntry {
+    /// The directory to scan, as written in the config file.
+    #[must_use]
+    pub const fn path(&self) -> &PathBuf {
+        match self {
+            Self::Path(path) | Self::Root { path, .. } => path,
+        }
+    }
+
+    /// The size budget for this directory, if one is set.
+    #[must_use]
+    pub fn budget(&self) -> Option<&str> {
+        match self {
+            Self::Path(_) => None,
+            Self::Root { budget, .. } => budget.as_deref(),
+        }
+    }
+}
+
+impl From<PathBuf> for FileDirEntry {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
 }
 
 /// Filtering options from the configuration file.
@@ -86,8 +207,47 @@ pub struct FileFilterConfig {
     /// Whether to reverse the sort order
     pub reverse: Option<bool>,
 
+    /// Size below which projects are listed as one summary line (e.g., `"10MB"`)
+    pub collapse_below: Option<String>,
+
     /// Optional name pattern (glob or `regex:…` prefix) to filter projects by name
     pub name_pattern: Option<String>,
+
+    /// Regular expression a project's name must match
+    pub name_filter: Option<String>,
+
+    /// Regular expression a project's root path must match
+    pub path_filter: Option<String>,
+
+    /// Minimum reclaimable size for projects with a high rebuild cost (e.g., `"100MB"`)
+    pub min_benefit: Option<String>,
+
+    /// Minimum days since the enclosing git repository's last commit
+    pub repo_idle_days: Option<u32>,
+
+    /// Minimum number of files in a project's build directories
+    pub min_files: Option<u64>,
+
+    /// Only clean artifacts older than the newest source file
+    pub stale_only: Option<bool>,
+
+    /// Highest risk of the build directories to clean (`"low"`, `"medium"` or `"high"`)
+    pub max_risk: Option<String>,
+
+    /// Build directories to clean, by name or path within the project (e.g. `[".next", "dist"]`)
+    pub only_artifacts: Option<Vec<String>>,
+
+    /// Build directories to leave out, by name or path within the project (e.g. `["node_modules"]`)
+    pub skip_artifacts: Option<Vec<String>>,
+
+    /// Filter expression projects must match (e.g. `"size > 1GB AND age > 90d"`)
+    pub filter_expr: Option<String>,
+
+    /// Project types to include (e.g., `["rust", "node"]`)
+    pub types: Option<Vec<String>>,
+
+    /// Project types to leave out (e.g., `["python"]`)
+    pub exclude_types: Option<Vec<String>>,
 }
 
 /// Scanning options from the configuration file.
@@ -99,31 +259,229 @@ pub struct FileScanConfig {
     /// Whether to show verbose output
     pub verbose: Option<bool>,
 
-    /// Directories to skip during scanning
+    /// Directories not checked for a project themselves (still descended into)
     pub skip: Option<Vec<PathBuf>>,
 
-    /// Directories to ignore during scanning
+    /// Directories never descended into during scanning
     pub ignore: Option<Vec<PathBuf>>,
 
+    /// Whether hidden directories are checked for a project
+    pub scan_hidden: Option<bool>,
+
+    /// Hidden directories checked for a project even without `scan_hidden`
+    pub allow_hidden: Option<Vec<PathBuf>>,
+
+    /// Directory names never checked for a project, besides the built-in ones
+    pub exclude_names: Option<Vec<PathBuf>>,
+
+    /// Built-in excluded directory names to check for a project after all
+    pub no_exclude_names: Option<Vec<PathBuf>>,
+
     /// Maximum directory depth to scan
     pub max_depth: Option<usize>,
+
+    /// Size at which sizing a build directory stops (e.g. `"5GB"`)
+    pub quick_size_threshold: Option<String>,
+
+    /// How build directories are measured (`"portable"` or `"native"`)
+    pub size_backend: Option<String>,
+
+    /// Name of the marker file that protects a project (empty = disabled)
+    pub keep_marker: Option<String>,
+
+    /// Where artifact ages come from (`"dir-mtime"` or `"newest-file"`)
+    pub age_source: Option<String>,
+
+    /// Which symbolic links the scan follows (`"never"`, `"roots"` or `"always"`)
+    pub follow_symlinks: Option<String>,
+
+    /// Whether to clean caches kept inside Rust projects
+    pub cargo_caches: Option<bool>,
+
+    /// Whether to clean conda environments kept inside Python projects
+    pub conda_envs: Option<bool>,
+
+    /// Whether to clean only the build tool caches of Node.js projects
+    pub node_caches_only: Option<bool>,
+
+    /// Cache directories of Node.js projects, relative to the project root
+    pub node_caches: Option<Vec<PathBuf>>,
+
+    /// Whether to clean container caches kept inside projects
+    pub container_caches: Option<bool>,
+
+    /// Patterns of container cache directories, relative to the project root
+    pub container_cache_patterns: Option<Vec<String>>,
+
+    /// Subdirectories of Rust target directories to clean instead of the
+    /// whole directory (`"incremental"`, `"fingerprint"`, `"deps"`, `"doc"`)
+    pub rust_subdirs: Option<Vec<String>>,
+
+    /// Whether to report and prune reclaimable Docker data as well
+    pub docker: Option<bool>,
+
+    /// Whether to append every scan to the scan database
+    pub record_scans: Option<bool>,
 }
 
 /// Execution options from the configuration file.
-#[derive(Deserialize, Default, Debug)]
+#[derive(Deserialize, Default, Debug, Clone)]
 pub struct FileExecutionConfig {
     /// Whether to preserve compiled executables
     pub keep_executables: Option<bool>,
 
+    /// Whether to prune older preserved copies of executables
+    pub keep_latest_only: Option<bool>,
+
     /// Whether to use interactive selection
     pub interactive: Option<bool>,
 
+    /// Which projects start out checked in interactive selection (e.g., `"stale"`)
+    pub preselect: Option<String>,
+
     /// Whether to run in dry-run mode
     pub dry_run: Option<bool>,
 
     /// Whether to move directories to the system trash instead of permanently deleting them.
     /// Defaults to `true` when absent. Set to `false` for permanent deletion.
     pub use_trash: Option<bool>,
+
+    /// Whether to skip projects with uncommitted git changes
+    pub skip_dirty: Option<bool>,
+
+    /// Whether to include projects owned by other users
+    pub all_users: Option<bool>,
+
+    /// Whether to show a desktop notification after cleanup
+    pub notify: Option<bool>,
+
+    /// Whether to re-measure build directories just before removing them
+    pub verify_sizes: Option<bool>,
+
+    /// Whether to remove directories left empty by a removal
+    pub prune_empty_parents: Option<bool>,
+
+    /// Whether to move build directories to the trash in batches
+    pub batch_trash: Option<bool>,
+
+    /// Whether to rename build directories aside before deleting them
+    pub rename_first: Option<bool>,
+
+    /// Whether to record each removal in the journal
+    pub journal: Option<bool>,
+
+    /// Whether the first run is a dry run; defaults to `true` only without a config file
+    pub safe_mode: Option<bool>,
+
+    /// How many times a failed removal is retried
+    pub delete_retries: Option<u32>,
+
+    /// Wait in milliseconds before the first retry of a failed removal
+    pub delete_retry_delay: Option<u64>,
+
+    /// What to do when another run holds the instance lock (e.g., `"skip"`)
+    pub lock_wait: Option<String>,
+
+    /// Whether to run in CI mode; when absent, detected from the `CI` environment variable
+    pub ci: Option<bool>,
+}
+
+/// Package manager cache settings from the configuration file.
+///
+/// Caches are only looked at when `enabled` is set (or `--system-caches` is
+/// given); each one can then be left out by setting it to `false`.
+#[derive(Deserialize, Default, Debug)]
+pub struct FileCachesConfig {
+    /// Whether to report and clean package manager caches
+    pub enabled: Option<bool>,
+
+    /// Whether to include the Homebrew download cache
+    pub homebrew: Option<bool>,
+
+    /// Whether to include apt's downloaded packages
+    pub apt: Option<bool>,
+
+    /// Whether to include the pip cache
+    pub pip: Option<bool>,
+
+    /// Whether to include downloaded crates in the cargo registry
+    pub cargo_registry: Option<bool>,
+
+    /// Whether to include the Go module cache
+    pub go_modules: Option<bool>,
+
+    /// Whether to include the sccache compiler cache
+    pub sccache: Option<bool>,
+}
+
+impl FileCachesConfig {
+    /// Whether `cache` is included when caches are enabled (the default).
+    #[must_use]
+    pub fn includes(&self, cache: SystemCache) -> bool {
+        match cache {
+            SystemCache::Homebrew => self.homebrew,
+            SystemCache::Apt => self.apt,
+            SystemCache::Pip => self.pip,
+            SystemCache::CargoRegistry => self.cargo_registry,
+            SystemCache::GoModules => self.go_modules,
+            SystemCache::Sccache => self.sccache,
+        }
+        .unwrap_or(true)
+    }
+}
+
+/// User hook commands from the configuration file.
+#[derive(Deserialize, Default, Debug)]
+pub struct FileHooksConfig {
+    /// Shell command run after a completed cleanup (e.g., `"~/bin/report.sh"`)
+    pub post_clean: Option<String>,
+
+    /// Command template run before each project is cleaned (e.g., `"backup.sh {path}"`)
+    pub pre_project_clean: Option<String>,
+}
+
+/// A named profile from the configuration file.
+///
+/// Every value set in a profile overrides the corresponding top-level value
+/// when the profile is selected; unset values fall back to the top level.
+#[derive(Deserialize, Default, Debug)]
+pub struct FileProfileConfig {
+    /// Project type filter override
+    pub project_type: Option<String>,
+
+    /// Size units override
+    pub units: Option<String>,
+
+    /// Filtering overrides
+    #[serde(default)]
+    pub filtering: FileFilterConfig,
+
+    /// Scanning overrides
+    #[serde(default)]
+    pub scanning: FileScanConfig,
+
+    /// Execution overrides
+    #[serde(default)]
+    pub execution: FileExecutionConfig,
+}
+
+/// Artifact settings for a single project type from the configuration file.
+#[derive(Deserialize, Default, Debug)]
+pub struct FileArtifactConfig {
+    /// Additional directories (relative to the project root) to clean for this type
+    pub extra: Option<Vec<PathBuf>>,
+
+    /// Risk levels of build directories, keyed by their path within the
+    /// project or their name (see [`risk`](crate::risk))
+    pub risk: Option<BTreeMap<String, String>>,
+
+    /// Build directories that are never detected for this type, by their
+    /// path within the project or their name
+    pub exclude: Option<Vec<String>>,
+
+    /// Whether virtual environments (`venv/` and `.venv/`) are cleaned; only
+    /// read from `[artifacts.python]`, and off by default
+    pub include_venv: Option<bool>,
 }
 
 /// Expand a leading `~` in a path to the user's home directory.
@@ -148,7 +506,124 @@ pub fn expand_tilde(path: &Path) -> PathBuf {
     path.to_path_buf()
 }
 
+impl FileFilterConfig {
+    /// Layer `self` on top of `base`, keeping `base` values only where `self` is unset.
+    fn or(self, base: Self) -> Self {
+        Self {
+            keep_size: self.keep_size.or(base.keep_size),
+            keep_days: self.keep_days.or(base.keep_days),
+            sort: self.sort.or(base.sort),
+            reverse: self.reverse.or(base.reverse),
+            collapse_below: self.collapse_below.or(base.collapse_below),
+            name_pattern: self.name_pattern.or(base.name_pattern),
+            name_filter: self.name_filter.or(base.name_filter),
+            path_filter: self.path_filter.or(base.path_filter),
+            min_benefit: self.min_benefit.or(base.min_benefit),
+            repo_idle_days: self.repo_idle_days.or(base.repo_idle_days),
+            min_files: self.min_files.or(base.min_files),
+            stale_only: self.stale_only.or(base.stale_only),
+            max_risk: self.max_risk.or(base.max_risk),
+            only_artifacts: self.only_artifacts.or(base.only_artifacts),
+            skip_artifacts: self.skip_artifacts.or(base.skip_artifacts),
+            filter_expr: self.filter_expr.or(base.filter_expr),
+            types: self.types.or(base.types),
+            exclude_types: self.exclude_types.or(base.exclude_types),
+        }
+    }
+}
+
+impl FileScanConfig {
+    /// Layer `self` on top of `base`, keeping `base` values only where `self` is unset.
+    fn or(self, base: Self) -> Self {
+        Self {
+            threads: self.threads.or(base.threads),
+            verbose: self.verbose.or(base.verbose),
+            skip: self.skip.or(base.skip),
+            ignore: self.ignore.or(base.ignore),
+            scan_hidden: self.scan_hidden.or(base.scan_hidden),
+            allow_hidden: self.allow_hidden.or(base.allow_hidden),
+            exclude_names: self.exclude_names.or(base.exclude_names),
+            no_exclude_names: self.no_exclude_names.or(base.no_exclude_names),
+            max_depth: self.max_depth.or(base.max_depth),
+            quick_size_threshold: self.quick_size_threshold.or(base.quick_size_threshold),
+            size_backend: self.size_backend.or(base.size_backend),
+            keep_marker: self.keep_marker.or(base.keep_marker),
+            age_source: self.age_source.or(base.age_source),
+            follow_symlinks: self.follow_symlinks.or(base.follow_symlinks),
+            cargo_caches: self.cargo_caches.or(base.cargo_caches),
+            conda_envs: self.conda_envs.or(base.conda_envs),
+            node_caches_only: self.node_caches_only.or(base.node_caches_only),
+            node_caches: self.node_caches.or(base.node_caches),
+            container_caches: self.container_caches.or(base.container_caches),
+            container_cache_patterns: self
+                .container_cache_patterns
+                .or(base.container_cache_patterns),
+            rust_subdirs: self.rust_subdirs.or(base.rust_subdirs),
+            docker: self.docker.or(base.docker),
+            record_scans: self.record_scans.or(base.record_scans),
+        }
+    }
+}
+
+impl FileExecutionConfig {
+    /// Layer `self` on top of `base`, keeping `base` values only where `self` is unset.
+    fn or(self, base: Self) -> Self {
+        Self {
+            keep_executables: self.keep_executables.or(base.keep_executables),
+            keep_latest_only: self.keep_latest_only.or(base.keep_latest_only),
+            interactive: self.interactive.or(base.interactive),
+            preselect: self.preselect.or(base.preselect),
+            dry_run: self.dry_run.or(base.dry_run),
+            use_trash: self.use_trash.or(base.use_trash),
+            skip_dirty: self.skip_dirty.or(base.skip_dirty),
+            all_users: self.all_users.or(base.all_users),
+            notify: self.notify.or(base.notify),
+            verify_sizes: self.verify_sizes.or(base.verify_sizes),
+            prune_empty_parents: self.prune_empty_parents.or(base.prune_empty_parents),
+            batch_trash: self.batch_trash.or(base.batch_trash),
+            rename_first: self.rename_first.or(base.rename_first),
+            journal: self.journal.or(base.journal),
+            safe_mode: self.safe_mode.or(base.safe_mode),
+            delete_retries: self.delete_retries.or(base.delete_retries),
+            delete_retry_delay: self.delete_retry_delay.or(base.delete_retry_delay),
+            lock_wait: self.lock_wait.or(base.lock_wait),
+            ci: self.ci.or(base.ci),
+        }
+    }
+}
+
 impl FileConfig {
+    /// Merge the named profile over the top-level configuration.
+    ///
+    /// Values set in `[profile.<name>]` win over top-level values; everything
+    /// else is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no profile with the given name is defined.
+    pub fn with_profile(mut self, name: &str) -> Result<Self> {
+        let Some(profile) = self.profile.remove(name) else {
+            let available: Vec<&str> = self.profile.keys().map(String::as_str).collect();
+            if available.is_empty() {
+                return Err(CleanDevDirsError::config(format!(
+                    "Unknown profile '{name}': no profiles are defined in the config file"
+                )));
+            }
+            return Err(CleanDevDirsError::config(format!(
+                "Unknown profile '{name}' (available: {})",
+                available.join(", ")
+            )));
+        };
+
+        self.project_type = profile.project_type.or(self.project_type);
+        self.units = profile.units.or(self.units);
+        self.filtering = profile.filtering.or(self.filtering);
+        self.scanning = profile.scanning.or(self.scanning);
+        self.execution = profile.execution.or(self.execution);
+
+        Ok(self)
+    }
+
     /// Returns the path where the configuration file is expected.
     ///
     /// The configuration file is located at `<config_dir>/clean-dev-dirs/config.toml`,
@@ -174,7 +649,7 @@ impl FileConfig {
     /// Returns an error if:
     /// - The config file exists but cannot be read
     /// - The config file exists but contains invalid TOML or unexpected fields
-    pub fn load() -> anyhow::Result<Self> {
+    pub fn load() -> Result<Self> {
         let Some(path) = Self::config_path() else {
             return Ok(Self::default());
         };
@@ -184,11 +659,13 @@ impl FileConfig {
         }
 
         let content = std::fs::read_to_string(&path).map_err(|e| {
-            anyhow::anyhow!("Failed to read config file at {}: {e}", path.display())
+            CleanDevDirsError::config(format!("Failed to read config file at {}", path.display()))
+                .with_source(e)
         })?;
 
         let config: Self = toml::from_str(&content).map_err(|e| {
-            anyhow::anyhow!("Failed to parse config file at {}: {e}", path.display())
+            CleanDevDirsError::config(format!("Failed to parse config file at {}", path.display()))
+                .with_source(e)
         })?;
 
         Ok(config)
@@ -211,6 +688,10 @@ mod tests {
         assert!(config.filtering.sort.is_none());
         assert!(config.filtering.reverse.is_none());
         assert!(config.filtering.name_pattern.is_none());
+        assert!(config.filtering.min_benefit.is_none());
+        assert!(config.filtering.repo_idle_days.is_none());
+        assert!(config.filtering.min_files.is_none());
+        assert!(config.filtering.stale_only.is_none());
         assert!(config.scanning.threads.is_none());
         assert!(config.scanning.verbose.is_none());
         assert!(config.scanning.skip.is_none());
@@ -219,6 +700,7 @@ mod tests {
         assert!(config.execution.interactive.is_none());
         assert!(config.execution.dry_run.is_none());
         assert!(config.execution.use_trash.is_none());
+        assert!(config.artifacts.is_empty());
     }
 
     #[test]
@@ -233,6 +715,7 @@ keep_days = 7
 sort = "size"
 reverse = true
 name_pattern = "my-*"
+min_benefit = "100MB"
 
 [scanning]
 threads = 4
@@ -242,6 +725,7 @@ ignore = [".git"]
 
 [execution]
 keep_executables = true
+keep_latest_only = true
 interactive = false
 dry_run = false
 use_trash = true
@@ -256,6 +740,7 @@ use_trash = true
         assert_eq!(config.filtering.sort, Some("size".to_string()));
         assert_eq!(config.filtering.reverse, Some(true));
         assert_eq!(config.filtering.name_pattern, Some("my-*".to_string()));
+        assert_eq!(config.filtering.min_benefit, Some("100MB".to_string()));
         assert_eq!(config.scanning.threads, Some(4));
         assert_eq!(config.scanning.verbose, Some(true));
         assert_eq!(
@@ -264,6 +749,7 @@ use_trash = true
         );
         assert_eq!(config.scanning.ignore, Some(vec![PathBuf::from(".git")]));
         assert_eq!(config.execution.keep_executables, Some(true));
+        assert_eq!(config.execution.keep_latest_only, Some(true));
         assert_eq!(config.execution.interactive, Some(false));
         assert_eq!(config.execution.dry_run, Some(false));
         assert_eq!(config.execution.use_trash, Some(true));
@@ -278,13 +764,31 @@ use_trash = true
 
         assert_eq!(
             config.dirs,
-            Some(vec![PathBuf::from("~/Projects"), PathBuf::from("~/work")])
+            Some(vec![
+                PathBuf::from("~/Projects").into(),
+                PathBuf::from("~/work").into()
+            ])
         );
         assert!(config.dir.is_none());
 
         Ok(())
     }
 
+    #[test]
+    fn test_parse_dirs_with_budget() -> anyhow::Result<()> {
+        let toml_content = r#"dirs = [{ path = "~/code", budget = "100GB" }, "~/work"]"#;
+        let config: FileConfig = toml::from_str(toml_content)?;
+        let dirs = config.dirs.unwrap_or_default();
+
+        assert_eq!(dirs.len(), 2);
+        assert_eq!(dirs[0].path(), &PathBuf::from("~/code"));
+        assert_eq!(dirs[0].budget(), Some("100GB"));
+        assert_eq!(dirs[1].path(), &PathBuf::from("~/work"));
+        assert_eq!(dirs[1].budget(), None);
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_partial_config() -> anyhow::Result<()> {
         let toml_content = r#"
@@ -494,4 +998,105 @@ use_trash = false
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_artifacts_section() -> anyhow::Result<()> {
+        let toml_content = r#"
+[artifacts.rust]
+extra = ["flamegraphs", ".criterion"]
+
+[artifacts.all]
+extra = ["coverage"]
+"#;
+        let config: FileConfig = toml::from_str(toml_content)?;
+
+        assert_eq!(config.artifacts.len(), 2);
+        assert_eq!(
+            config.artifacts.get("rust").and_then(|a| a.extra.clone()),
+            Some(vec![
+                PathBuf::from("flamegraphs"),
+                PathBuf::from(".criterion")
+            ])
+        );
+        assert_eq!(
+            config.artifacts.get("all").and_then(|a| a.extra.clone()),
+            Some(vec![PathBuf::from("coverage")])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_overrides_top_level() -> anyhow::Result<()> {
+        let toml_content = r#"
+project_type = "rust"
+
+[filtering]
+keep_size = "50MB"
+keep_days = 30
+
+[profile.aggressive]
+project_type = "all"
+
+[profile.aggressive.filtering]
+keep_days = 0
+
+[profile.aggressive.execution]
+use_trash = false
+"#;
+        let config: FileConfig = toml::from_str(toml_content)?;
+        assert!(config.profile.contains_key("aggressive"));
+
+        let merged = config.with_profile("aggressive")?;
+        assert_eq!(merged.project_type, Some("all".to_string()));
+        assert_eq!(merged.filtering.keep_days, Some(0));
+        assert_eq!(merged.filtering.keep_size, Some("50MB".to_string()));
+        assert_eq!(merged.execution.use_trash, Some(false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_profile_errors() -> anyhow::Result<()> {
+        let config: FileConfig = toml::from_str("[profile.safe.filtering]\nkeep_days = 90\n")?;
+        let err = config.with_profile("missing").err();
+        assert!(err.is_some_and(|e| e.to_string().contains("available: safe")));
+
+        let result = FileConfig::default().with_profile("safe");
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_hooks_section() -> anyhow::Result<()> {
+        let config: FileConfig = toml::from_str(
+            r#"
+[execution]
+notify = true
+verify_sizes = true
+delete_retries = 5
+delete_retry_delay = 250
+ci = false
+
+[hooks]
+post_clean = "echo done"
+pre_project_clean = "backup.sh {path}"
+"#,
+        )?;
+
+        assert_eq!(config.execution.notify, Some(true));
+        assert_eq!(config.execution.verify_sizes, Some(true));
+        assert_eq!(config.execution.delete_retries, Some(5));
+        assert_eq!(config.execution.delete_retry_delay, Some(250));
+        assert_eq!(config.execution.ci, Some(false));
+        assert_eq!(config.hooks.post_clean.as_deref(), Some("echo done"));
+        assert_eq!(
+            config.hooks.pre_project_clean.as_deref(),
+            Some("backup.sh {path}")
+        );
+        assert!(FileConfig::default().hooks.post_clean.is_none());
+
+        Ok(())
+    }
 }
@@ -4,13 +4,17 @@
 //! criteria used to determine which projects should be scanned, cleaned, and
 //! how they should be ordered in the output.
 
+use std::collections::HashSet;
+
 use clap::ValueEnum;
 
+use crate::risk::{RiskLevel, RiskOverrides};
+
 /// Enumeration of supported project type filters.
 ///
 /// This enum is used to restrict scanning and cleaning to specific types of
 /// development projects.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, ValueEnum, Default)]
 pub enum ProjectFilter {
     /// Include all supported project types
     #[default]
@@ -19,19 +23,19 @@ pub enum ProjectFilter {
     /// Include only Rust projects (Cargo.toml + target/)
     Rust,
 
-    /// Include only Node.js projects (package.json + `node_modules`/)
+    /// Include only Node.js projects (package.json + `node_modules/` or framework build output)
     Node,
 
     /// Include only Python projects (Python config files + cache dirs)
     Python,
 
-    /// Include only Go projects (go.mod + vendor/)
+    /// Include only Go projects (go.mod + vendor/ or bin/)
     Go,
 
     /// Include only Java/Kotlin projects (pom.xml or build.gradle + target/ or build/)
     Java,
 
-    /// Include only C/C++ projects (CMakeLists.txt or Makefile + build/)
+    /// Include only C/C++ projects (CMakeLists.txt or Makefile + build/, or Visual Studio .vs/ and x64/Debug|Release)
     Cpp,
 
     /// Include only Swift projects (Package.swift + .build/)
@@ -63,8 +67,115 @@ pub enum ProjectFilter {
     /// Include only Zig projects (build.zig + zig-cache/ or zig-out/)
     Zig,
 
-    /// Include only Scala projects (build.sbt + target/)
+    /// Include only Scala projects (build.sbt + target/ or project/target/)
     Scala,
+
+    /// Include only Android projects (settings.gradle + module build/ and .cxx/)
+    Android,
+
+    /// Include only Lua projects (*.rockspec + `lua_modules`/ or .luarocks/)
+    Lua,
+
+    /// Include only Nim projects (*.nimble + nimcache/)
+    Nim,
+
+    /// Include only OCaml projects (dune-project + _build/)
+    #[value(name = "ocaml")]
+    OCaml,
+
+    /// Include only Erlang projects (rebar.config + _build/)
+    Erlang,
+
+    /// Include only Julia projects (Project.toml + .julia/compiled/ or deps/usr/)
+    Julia,
+
+    /// Include only R projects (renv.lock + renv/library/)
+    R,
+}
+
+impl ProjectFilter {
+    /// Name of the [`Detector`](crate::detector::Detector) this filter selects,
+    /// or `None` for [`ProjectFilter::All`].
+    ///
+    /// The names are the values accepted by `--project-type`.
+    #[must_use]
+    pub const fn detector_name(self) -> Option<&'static str> {
+        match self {
+            Self::All => None,
+            Self::Rust => Some("rust"),
+            Self::Node => Some("node"),
+            Self::Python => Some("python"),
+            Self::Go => Some("go"),
+            Self::Java => Some("java"),
+            Self::Cpp => Some("cpp"),
+            Self::Swift => Some("swift"),
+            Self::DotNet => Some("dotnet"),
+            Self::Ruby => Some("ruby"),
+            Self::Elixir => Some("elixir"),
+            Self::Deno => Some("deno"),
+            Self::Php => Some("php"),
+            Self::Haskell => Some("haskell"),
+            Self::Dart => Some("dart"),
+            Self::Zig => Some("zig"),
+            Self::Scala => Some("scala"),
+            Self::Android => Some("android"),
+            Self::Lua => Some("lua"),
+            Self::Nim => Some("nim"),
+            Self::OCaml => Some("ocaml"),
+            Self::Erlang => Some("erlang"),
+            Self::Julia => Some("julia"),
+            Self::R => Some("r"),
+        }
+    }
+
+    /// Whether projects found by the detector named `detector` pass this filter.
+    #[must_use]
+    pub fn includes(self, detector: &str) -> bool {
+        self.detector_name().is_none_or(|name| name == detector)
+    }
+}
+
+/// The project types to scan for: a set of types to include and a set to exclude.
+///
+/// A type passes when the include set is empty or contains it, and the
+/// exclude set doesn't. [`ProjectFilter::All`] adds no restriction and is
+/// left out of both sets.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TypeFilter {
+    /// Types to include; empty includes every type
+    include: HashSet<ProjectFilter>,
+
+    /// Types to leave out
+    exclude: HashSet<ProjectFilter>,
+}
+
+impl TypeFilter {
+    /// A filter including the types of `include`, minus those of `exclude`.
+    #[must_use]
+    pub fn new(
+        include: impl IntoIterator<Item = ProjectFilter>,
+        exclude: impl IntoIterator<Item = ProjectFilter>,
+    ) -> Self {
+        let restricting = |filter: &ProjectFilter| *filter != ProjectFilter::All;
+        Self {
+            include: include.into_iter().filter(restricting).collect(),
+            exclude: exclude.into_iter().filter(restricting).collect(),
+        }
+    }
+
+    /// Whether projects found by the detector named `detector` pass this filter.
+    #[must_use]
+    pub fn includes(&self, detector: &str) -> bool {
+        let named = |filter: &ProjectFilter| filter.detector_name() == Some(detector);
+        (self.include.is_empty() || self.include.iter().any(named))
+            && !self.exclude.iter().any(named)
+    }
+}
+
+impl From<ProjectFilter> for TypeFilter {
+    fn from(filter: ProjectFilter) -> Self {
+        Self::new([filter], [])
+    }
 }
 
 /// Configuration for project filtering criteria.
@@ -81,6 +192,41 @@ pub struct FilterOptions {
 
     /// Optional name pattern (glob or `regex:…` prefix) to filter projects by name
     pub name_pattern: Option<String>,
+
+    /// Optional regular expression a project's name must match
+    pub name_filter: Option<String>,
+
+    /// Optional regular expression a project's root path must match
+    pub path_filter: Option<String>,
+
+    /// Minimum reclaimable size required to clean a project with a high rebuild cost
+    pub min_benefit: Option<String>,
+
+    /// Minimum number of days since the enclosing git repository's last commit
+    pub repo_idle_days: u32,
+
+    /// Minimum number of files in a project's build directories
+    pub min_files: u64,
+
+    /// Only keep projects whose sources changed since their artifacts were built
+    pub stale_only: bool,
+
+    /// Highest risk of the build directories to clean; riskier ones are left out
+    pub max_risk: Option<RiskLevel>,
+
+    /// Risk levels set in the config file on top of the built-in ones
+    pub risk_overrides: RiskOverrides,
+
+    /// Build directories to clean, by name or path within the project (e.g.
+    /// `.next` or `vendor/bundle`); all when empty
+    pub only_artifacts: Vec<String>,
+
+    /// Build directories to leave out, by name or path within the project
+    pub skip_artifacts: Vec<String>,
+
+    /// Optional [filter expression](crate::filter_expr) projects must match
+    /// (e.g. `size > 1GB AND age > 90d`)
+    pub filter_expr: Option<String>,
 }
 
 /// Enumeration of supported sorting criteria for project output.
@@ -91,6 +237,8 @@ pub struct FilterOptions {
 /// - `Age`: oldest first (ascending)
 /// - `Name`: alphabetical (ascending)
 /// - `Type`: grouped by type name alphabetically
+/// - `Files`: most files first (descending)
+/// - `Growth`: largest growth since the previous run first (descending)
 #[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
 pub enum SortCriteria {
     /// Sort by build artifacts size (largest first by default)
@@ -104,16 +252,25 @@ pub enum SortCriteria {
 
     /// Sort by project type name alphabetically
     Type,
+
+    /// Sort by number of files in the build artifacts (most first by default)
+    Files,
+
+    /// Sort by how much the build artifacts grew since the previous run
+    /// (fastest-growing first by default; projects not measured before last)
+    Growth,
 }
 
 /// Configuration for project sorting behavior.
 ///
 /// Controls how the list of projects is ordered before display or processing.
-/// When `criteria` is `None`, projects are displayed in scan order.
+/// Later criteria break ties left by earlier ones; remaining ties are broken
+/// by project path. When `criteria` is empty, projects are displayed in scan
+/// order.
 #[derive(Clone, Debug)]
 pub struct SortOptions {
-    /// The sorting criterion to apply, or `None` to preserve scan order
-    pub criteria: Option<SortCriteria>,
+    /// The sorting criteria to apply in order, or empty to preserve scan order
+    pub criteria: Vec<SortCriteria>,
 
     /// Whether to reverse the sort order
     pub reverse: bool,
@@ -161,6 +318,32 @@ mod tests {
         assert_ne!(ProjectFilter::Zig, ProjectFilter::Scala);
     }
 
+    #[test]
+    fn test_type_filter_includes_and_excludes() {
+        let all = TypeFilter::default();
+        assert!(all.includes("rust") && all.includes("node"));
+        assert_eq!(TypeFilter::from(ProjectFilter::All), all);
+
+        let rust = TypeFilter::from(ProjectFilter::Rust);
+        assert!(rust.includes("rust"));
+        assert!(!rust.includes("node"));
+
+        let some = TypeFilter::new([ProjectFilter::Rust, ProjectFilter::Node], []);
+        assert!(some.includes("rust") && some.includes("node"));
+        assert!(!some.includes("python"));
+
+        let without = TypeFilter::new([], [ProjectFilter::Node, ProjectFilter::Python]);
+        assert!(without.includes("rust") && without.includes("go"));
+        assert!(!without.includes("node") && !without.includes("python"));
+
+        let both = TypeFilter::new(
+            [ProjectFilter::Rust, ProjectFilter::Node],
+            [ProjectFilter::Node, ProjectFilter::All],
+        );
+        assert!(both.includes("rust"));
+        assert!(!both.includes("node"));
+    }
+
     #[test]
     fn test_project_filter_copy() {
         let original = ProjectFilter::Rust;
@@ -181,6 +364,17 @@ mod tests {
             keep_size: "100MB".to_string(),
             keep_days: 30,
             name_pattern: None,
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
         };
 
         assert_eq!(filter_opts.keep_size, "100MB");
@@ -194,6 +388,17 @@ mod tests {
             keep_size: "100MB".to_string(),
             keep_days: 30,
             name_pattern: None,
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
         };
         let cloned = original.clone();
 
@@ -208,6 +413,17 @@ mod tests {
             keep_size: "0".to_string(),
             keep_days: 0,
             name_pattern: Some("my-app*".to_string()),
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
         };
         assert_eq!(with_glob.name_pattern.as_deref(), Some("my-app*"));
 
@@ -215,6 +431,17 @@ mod tests {
             keep_size: "0".to_string(),
             keep_days: 0,
             name_pattern: Some("regex:^client-.*".to_string()),
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
         };
         assert_eq!(with_regex.name_pattern.as_deref(), Some("regex:^client-.*"));
     }
@@ -240,26 +467,29 @@ mod tests {
     #[test]
     fn test_sort_options_creation() {
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Size),
+            criteria: vec![SortCriteria::Size, SortCriteria::Name],
             reverse: false,
         };
-        assert_eq!(sort_opts.criteria, Some(SortCriteria::Size));
+        assert_eq!(
+            sort_opts.criteria,
+            vec![SortCriteria::Size, SortCriteria::Name]
+        );
         assert!(!sort_opts.reverse);
     }
 
     #[test]
     fn test_sort_options_none_criteria() {
         let sort_opts = SortOptions {
-            criteria: None,
+            criteria: Vec::new(),
             reverse: false,
         };
-        assert!(sort_opts.criteria.is_none());
+        assert!(sort_opts.criteria.is_empty());
     }
 
     #[test]
     fn test_sort_options_clone() {
         let original = SortOptions {
-            criteria: Some(SortCriteria::Age),
+            criteria: vec![SortCriteria::Age],
             reverse: true,
         };
         let cloned = original.clone();
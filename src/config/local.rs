@@ -0,0 +1,226 @@
+//! Per-directory configuration overrides.
+//!
+//! Project owners can drop a `.clean-dev-dirs.toml` file into a project root to
+//! adjust how that single project is handled, independently of the user's global
+//! configuration file.
+//!
+//! # Example
+//!
+//! ```toml
+//! # Never clean this project's artifacts
+//! protected = true
+//!
+//! # Also treat these directories (relative to the project root) as artifacts
+//! extra_artifacts = ["coverage/", ".nyc_output"]
+//! ```
+//!
+//! A project can also be protected with an empty marker file in its root,
+//! `.clean-dev-dirs-keep` by default (see [`DEFAULT_KEEP_MARKER`]).
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::{CleanDevDirsError, Result};
+
+/// File name of the per-directory override file looked up in project roots.
+pub const LOCAL_CONFIG_FILE_NAME: &str = ".clean-dev-dirs.toml";
+
+/// Default name of the marker file that protects the project it's in.
+pub const DEFAULT_KEEP_MARKER: &str = ".clean-dev-dirs-keep";
+
+/// Settings read from a project's `.clean-dev-dirs.toml` file.
+///
+/// All fields are optional so that an empty file is valid and has no effect.
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LocalConfig {
+    /// When `true`, the project is never cleaned.
+    pub protected: Option<bool>,
+
+    /// Additional directories, relative to the project root, to clean alongside
+    /// the detected build artifacts.
+    pub extra_artifacts: Option<Vec<PathBuf>>,
+}
+
+impl LocalConfig {
+    /// Load the override file from `project_root`, if one exists.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(None)` if the project root has no `.clean-dev-dirs.toml`
+    /// - `Ok(Some(config))` if the file was read and parsed successfully
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or is not valid TOML.
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(LOCAL_CONFIG_FILE_NAME);
+
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            CleanDevDirsError::config(format!("Failed to read {}", path.display())).with_source(e)
+        })?;
+
+        let config: Self = toml::from_str(&content).map_err(|e| {
+            CleanDevDirsError::config(format!("Failed to parse {}", path.display())).with_source(e)
+        })?;
+
+        Ok(Some(config))
+    }
+
+    /// Whether the project is marked as protected.
+    #[must_use]
+    pub fn is_protected(&self) -> bool {
+        self.protected.unwrap_or(false)
+    }
+
+    /// Resolve `extra_artifacts` against `project_root`.
+    ///
+    /// Entries that are absolute or that would escape the project root (via `..`)
+    /// are dropped, so a checked-in override file can never point the cleaner
+    /// outside of its own project.
+    #[must_use]
+    pub fn resolved_extra_artifacts(&self, project_root: &Path) -> Vec<PathBuf> {
+        self.extra_artifacts
+            .iter()
+            .flatten()
+            .filter_map(|rel| resolve_within(project_root, rel))
+            .collect()
+    }
+}
+
+/// Join a relative artifact path onto `project_root`, refusing to leave it.
+///
+/// Returns `None` for absolute paths, paths containing `..`, and paths that
+/// resolve to the project root itself.
+#[must_use]
+pub fn resolve_within(project_root: &Path, rel: &Path) -> Option<PathBuf> {
+    let mut resolved = project_root.to_path_buf();
+
+    for component in rel.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    (resolved != project_root).then_some(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_full_local_config() -> anyhow::Result<()> {
+        let config: LocalConfig = toml::from_str(
+            r#"
+protected = true
+extra_artifacts = ["coverage/", ".nyc_output"]
+"#,
+        )?;
+
+        assert!(config.is_protected());
+        assert_eq!(
+            config.extra_artifacts,
+            Some(vec![
+                PathBuf::from("coverage/"),
+                PathBuf::from(".nyc_output")
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_empty_local_config() -> anyhow::Result<()> {
+        let config: LocalConfig = toml::from_str("")?;
+
+        assert!(!config.is_protected());
+        assert!(config.extra_artifacts.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_key_is_rejected() {
+        let result = toml::from_str::<LocalConfig>("protect = true");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_returns_none_without_file() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        assert!(LocalConfig::load(tmp.path())?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_reads_file() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        std::fs::write(
+            tmp.path().join(LOCAL_CONFIG_FILE_NAME),
+            "protected = true\n",
+        )?;
+
+        let config = LocalConfig::load(tmp.path())?;
+        assert!(config.is_some_and(|c| c.is_protected()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_malformed_file_errors() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        std::fs::write(
+            tmp.path().join(LOCAL_CONFIG_FILE_NAME),
+            "protected = \"yes\"\n",
+        )?;
+
+        assert!(LocalConfig::load(tmp.path()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolved_extra_artifacts_rejects_escaping_paths() {
+        let config = LocalConfig {
+            protected: None,
+            extra_artifacts: Some(vec![
+                PathBuf::from("coverage"),
+                PathBuf::from("./reports/html"),
+                PathBuf::from("../sibling"),
+                PathBuf::from("/etc"),
+            ]),
+        };
+
+        let resolved = config.resolved_extra_artifacts(Path::new("/project"));
+
+        assert_eq!(
+            resolved,
+            vec![
+                PathBuf::from("/project/coverage"),
+                PathBuf::from("/project/reports/html"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_within() {
+        let root = Path::new("/project");
+
+        assert_eq!(
+            resolve_within(root, Path::new("a/b")),
+            Some(PathBuf::from("/project/a/b"))
+        );
+        assert_eq!(resolve_within(root, Path::new(".")), None);
+        assert_eq!(resolve_within(root, Path::new("a/../../b")), None);
+    }
+}
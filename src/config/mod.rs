@@ -3,12 +3,22 @@
 //! This module contains all configuration structures used throughout the application,
 //! including filtering, scanning, execution options, and persistent file-based configuration.
 
+pub mod alias;
+pub mod display;
+pub mod edit;
 pub mod execution;
 pub mod file;
 pub mod filter;
+pub mod local;
 pub mod scan;
+pub mod validate;
 
-pub use execution::ExecutionOptions;
+pub use display::DisplayOptions;
+pub use execution::{ExecutionOptions, LockWait, Preselect};
 pub use file::FileConfig;
-pub use filter::{FilterOptions, ProjectFilter, SortCriteria, SortOptions};
-pub use scan::ScanOptions;
+pub use filter::{FilterOptions, ProjectFilter, SortCriteria, SortOptions, TypeFilter};
+pub use local::LocalConfig;
+pub use scan::{
+    AgeSource, DEFAULT_CONTAINER_CACHES, DEFAULT_NODE_CACHES, FollowSymlinks, RustSubdir,
+    ScanOptions,
+};
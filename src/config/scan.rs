@@ -3,13 +3,141 @@
 //! This module defines the options that control how directories are scanned
 //! and what information is collected during the scanning process.
 
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    caches::SystemCache,
+    config::ProjectFilter,
+    error::{CleanDevDirsError, Result},
+    utils::SizeBackendKind,
+};
+
+/// Cache directories cleaned by `--node-caches-only` unless configured
+/// otherwise, relative to the project root.
+pub const DEFAULT_NODE_CACHES: [&str; 3] = ["node_modules/.cache", "node_modules/.vite", ".turbo"];
+
+/// Patterns of the container cache directories cleaned by
+/// `--container-caches` unless configured otherwise, relative to the
+/// project root.
+pub const DEFAULT_CONTAINER_CACHES: [&str; 4] = [
+    ".buildx-cache",
+    ".docker/cache",
+    ".docker/buildkit",
+    ".docker/**/node_modules",
+];
+
+/// Where the age of a project's build artifacts is taken from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Default)]
+pub enum AgeSource {
+    /// Modification time of the artifact directory itself
+    ///
+    /// Cheap, but only changes when entries are added to or removed from the
+    /// directory, not when a nested file is rebuilt.
+    #[default]
+    DirMtime,
+
+    /// Most recent modification time of any file inside the artifacts
+    ///
+    /// Reflects the last build accurately, at the cost of walking the
+    /// artifact directories during the scan.
+    NewestFile,
+}
+
+impl AgeSource {
+    /// Parse the `scanning.age_source` value of a config file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error naming the valid values if `value` isn't
+    /// one of them.
+    pub fn from_config(value: &str) -> Result<Self> {
+        Self::from_str(value, true).map_err(|_| {
+            let valid: Vec<String> = Self::value_variants()
+                .iter()
+                .filter_map(Self::to_possible_value)
+                .map(|v| format!("`{}`", v.get_name()))
+                .collect();
+            CleanDevDirsError::config(format!(
+                "Invalid value `{value}` for `scanning.age_source`: expected one of {}",
+                valid.join(", ")
+            ))
+        })
+    }
+}
+
+/// A well-known subdirectory of a Rust `target/` directory.
+///
+/// `--rust-subdirs` cleans only these instead of the whole `target/`, and
+/// verbose output breaks the size of `target/` down into them.
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    ValueEnum,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RustSubdir {
+    /// Incremental compilation caches (`<profile>/incremental/`), usually
+    /// the largest part and the cheapest to lose
+    Incremental,
+
+    /// Cargo's records of what is up to date (`<profile>/.fingerprint/`);
+    /// without them, the next build compiles everything again
+    Fingerprint,
+
+    /// Compiled crates and dependencies (`<profile>/deps/`)
+    Deps,
+
+    /// Generated documentation (`doc/`)
+    Doc,
+}
+
+impl RustSubdir {
+    /// Name of the directory.
+    #[must_use]
+    pub const fn dir_name(self) -> &'static str {
+        match self {
+            Self::Incremental => "incremental",
+            Self::Fingerprint => ".fingerprint",
+            Self::Deps => "deps",
+            Self::Doc => "doc",
+        }
+    }
+}
+
+/// Which symbolic links to directories the scan follows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Default)]
+pub enum FollowSymlinks {
+    /// Follow no symbolic links, not even a scan root that is one
+    Never,
+
+    /// Follow scan roots that are symbolic links, but no links below them
+    #[default]
+    Roots,
+
+    /// Follow every symbolic link to a directory; each directory is scanned
+    /// once however many links lead to it, which also stops link loops
+    Always,
+}
 
 /// Configuration for directory scanning behavior.
 ///
 /// This struct contains options that control how directories are traversed
 /// and what information is collected during the scanning process.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ScanOptions {
     /// Whether to show verbose output including scan errors
     pub verbose: bool,
@@ -17,17 +145,128 @@ pub struct ScanOptions {
     /// Number of threads to use for scanning (0 = default)
     pub threads: usize,
 
-    /// List of directory patterns to skip during scanning
+    /// Directory names that are not checked for a project themselves; the
+    /// directories below them are still scanned
     pub skip: Vec<PathBuf>,
 
+    /// Directory names that are never descended into, so nothing below them
+    /// is scanned
+    pub ignore: Vec<PathBuf>,
+
+    /// Whether hidden directories (names starting with `.`) are checked for a
+    /// project like any other directory
+    pub scan_hidden: bool,
+
+    /// Names of hidden directories that are checked for a project even
+    /// without `scan_hidden`, in addition to `.cargo`
+    pub allow_hidden: Vec<PathBuf>,
+
+    /// Directory names never checked for a project, in addition to the
+    /// built-in ones (`target`, `dist`, `vendor`, ...)
+    pub exclude_names: Vec<PathBuf>,
+
+    /// Built-in excluded directory names that are checked for a project
+    /// like any other directory
+    pub no_exclude_names: Vec<PathBuf>,
+
     /// Maximum directory depth to scan (None = unlimited)
     pub max_depth: Option<usize>,
+
+    /// Size in bytes at which sizing a build directory stops; its size is
+    /// then only a lower bound (None = always size completely)
+    pub quick_size_threshold: Option<u64>,
+
+    /// How build directories are walked to measure them
+    pub size_backend: SizeBackendKind,
+
+    /// Additional artifact directories (relative to the project root) to clean
+    /// for each project type. Entries under [`ProjectFilter::All`] apply to every type.
+    pub extra_artifacts: HashMap<ProjectFilter, Vec<PathBuf>>,
+
+    /// Build directories, by their path within the project or their name,
+    /// that are left out of each project type's detection. Entries under
+    /// [`ProjectFilter::All`] apply to every type.
+    pub excluded_artifacts: HashMap<ProjectFilter, Vec<String>>,
+
+    /// Name of the marker file that protects a project from cleaning when
+    /// present in its root (None = marker files are not looked for)
+    pub keep_marker: Option<String>,
+
+    /// Where artifact ages, used for display, sorting and `keep_days`, come from
+    pub age_source: AgeSource,
+
+    /// Which symbolic links to directories the scan follows
+    pub follow_symlinks: FollowSymlinks,
+
+    /// Whether to also clean caches kept inside Rust projects
+    /// (`.cargo/registry`, `.cargo/git` and `.sccache`)
+    pub cargo_caches: bool,
+
+    /// Whether to also clean conda environments kept inside Python projects
+    /// (`env/` and `.conda/`)
+    pub conda_envs: bool,
+
+    /// Whether to also clean virtual environments kept inside Python
+    /// projects (`venv/` and `.venv/`)
+    pub python_venvs: bool,
+
+    /// Whether to clean only the build tool caches of Node.js projects
+    /// (`node_caches`) and keep their installed dependencies
+    pub node_caches_only: bool,
+
+    /// Cache directories, relative to the project root, that
+    /// `node_caches_only` cleans
+    pub node_caches: Vec<PathBuf>,
+
+    /// Whether to also clean the container caches kept inside projects
+    /// (`container_cache_patterns`)
+    pub container_caches: bool,
+
+    /// Patterns of container cache directories, relative to the project
+    /// root, that `container_caches` cleans
+    pub container_cache_patterns: Vec<String>,
+
+    /// Subdirectories of Rust `target/` directories to clean instead of the
+    /// whole directory (empty = all of `target/`)
+    pub rust_subdirs: Vec<RustSubdir>,
+
+    /// Whether to report and prune reclaimable Docker data as well
+    pub docker: bool,
+
+    /// Package manager caches to report and clean as well
+    pub system_caches: Vec<SystemCache>,
+
+    /// Whether to record which detector matched each project, and why
+    pub explain: bool,
+
+    /// Whether to append the projects found to the scan database
+    /// (see [`scan_db`](crate::scan_db))
+    pub record_scans: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_age_source_from_config() {
+        assert_eq!(
+            AgeSource::from_config("newest-file").ok(),
+            Some(AgeSource::NewestFile)
+        );
+        assert_eq!(
+            AgeSource::from_config("Dir-Mtime").ok(),
+            Some(AgeSource::DirMtime)
+        );
+        assert_eq!(
+            AgeSource::from_config("newest").err().map(|e| e.to_string()),
+            Some(
+                "Invalid value `newest` for `scanning.age_source`: expected one of `dir-mtime`, `newest-file`"
+                    .to_string()
+            )
+        );
+    }
+
     #[test]
     fn test_scan_options_creation() {
         let scan_opts = ScanOptions {
@@ -35,6 +274,7 @@ mod tests {
             threads: 4,
             skip: vec![PathBuf::from("test")],
             max_depth: None,
+            ..ScanOptions::default()
         };
 
         assert!(scan_opts.verbose);
@@ -49,6 +289,7 @@ mod tests {
             threads: 4,
             skip: vec![PathBuf::from("test")],
             max_depth: None,
+            ..ScanOptions::default()
         };
         let cloned = original.clone();
 
@@ -56,4 +297,13 @@ mod tests {
         assert_eq!(original.threads, cloned.threads);
         assert_eq!(original.skip, cloned.skip);
     }
+
+    #[test]
+    fn test_scan_options_default_has_no_extra_artifacts() {
+        let scan_opts = ScanOptions::default();
+
+        assert!(scan_opts.extra_artifacts.is_empty());
+        assert!(scan_opts.skip.is_empty());
+        assert!(scan_opts.max_depth.is_none());
+    }
 }
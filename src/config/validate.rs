@@ -0,0 +1,575 @@
+//! Checking the configuration file for mistakes.
+//!
+//! Loading the config ignores keys it doesn't know, so a typo like
+//! `keep_sizee` silently has no effect, while a value of the wrong type
+//! makes the whole file fail to load. `clean-dev-dirs config validate` runs
+//! [`validate`] to report all of these at once, each with the line it was
+//! found on:
+//!
+//! - TOML syntax errors and values of the wrong type
+//! - unknown keys, with the likely intended key when there is one
+//! - invalid sizes, name patterns, regular expressions and age sources
+//! - unknown project types
+//! - scan directories that don't exist
+//! - aliases that are undefined, unterminated or refer back to themselves
+//!
+//! The known keys come from the config structs themselves, so they never
+//! drift from what [`FileConfig`] actually reads.
+
+use std::{
+    fmt::{self, Write as _},
+    ops::Range,
+};
+
+use clap::ValueEnum;
+use serde::{
+    Deserialize,
+    de::{self, Deserializer, Visitor},
+};
+use toml_edit::{ImDocument, Item, TableLike};
+
+use super::alias::alias_words;
+use super::file::{
+    FileArtifactConfig, FileCachesConfig, FileConfig, FileExecutionConfig, FileFilterConfig,
+    FileHooksConfig, FileProfileConfig, FileScanConfig, expand_tilde,
+};
+use super::{AgeSource, ProjectFilter};
+use crate::detector::stays_in_project;
+use crate::filtering::{compile_filter_expr, compile_filter_regex, compile_name_matcher};
+use crate::utils::parse_size;
+
+/// Keys of a table in the `dirs` list.
+const DIR_ENTRY_KEYS: &[&str] = &["path", "budget"];
+
+/// How serious a problem is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The setting is ignored or the directory is skipped, but the config loads
+    Warning,
+
+    /// The config fails to load, or runs using it fail
+    Error,
+}
+
+/// A problem found in the configuration file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+
+    /// Line of the file the problem was found on (1-based), if known
+    pub line: Option<usize>,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Check the TOML config `content` and return every problem found, in the
+/// order of the file.
+///
+/// An empty result means the config is valid.
+#[must_use]
+pub fn validate(content: &str) -> Vec<Diagnostic> {
+    let report = |severity, span: Option<Range<usize>>, message| Diagnostic {
+        severity,
+        line: span.map(|span| line_of(content, span.start)),
+        message,
+    };
+
+    let doc = match ImDocument::parse(content) {
+        Ok(doc) => doc,
+        Err(e) => return vec![report(Severity::Error, e.span(), e.message().to_string())],
+    };
+
+    let mut diagnostics = Vec::new();
+    check_keys(doc.as_table(), &mut Vec::new(), &mut diagnostics, &report);
+
+    match toml::from_str::<FileConfig>(content) {
+        Ok(config) => {
+            check_values(&config, doc.as_table(), &mut diagnostics, &report);
+            check_aliases(&config, doc.as_table(), &mut diagnostics, &report);
+            check_artifacts(&config, doc.as_table(), &mut diagnostics, &report);
+        }
+        Err(e) => diagnostics.push(report(Severity::Error, e.span(), e.message().to_string())),
+    }
+
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+/// Report the keys of `table` (found at `path`) that the config doesn't know,
+/// then check the tables below it.
+fn check_keys<'a>(
+    table: &'a dyn TableLike,
+    path: &mut Vec<&'a str>,
+    diagnostics: &mut Vec<Diagnostic>,
+    report: &impl Fn(Severity, Option<Range<usize>>, String) -> Diagnostic,
+) {
+    let known = known_keys(path);
+
+    for (key, item) in table.iter() {
+        if let Some(known) = known
+            && !known.contains(&key)
+        {
+            let span = table.key(key).and_then(toml_edit::Key::span);
+            let message = unknown_key_message(key, path, known);
+            diagnostics.push(report(Severity::Warning, span, message));
+            continue;
+        }
+
+        path.push(key);
+        if let Some(child) = item.as_table_like() {
+            check_keys(child, path, diagnostics, report);
+        } else if let Some(entries) = item.as_array() {
+            for entry in entries.iter().filter_map(|v| v.as_inline_table()) {
+                check_keys(entry, path, diagnostics, report);
+            }
+        } else if let Some(entries) = item.as_array_of_tables() {
+            for entry in entries {
+                check_keys(entry, path, diagnostics, report);
+            }
+        }
+        path.pop();
+    }
+}
+
+/// The keys allowed in the table at `path`, or `None` if any key is (such as
+/// profile names under `[profile]`).
+fn known_keys(path: &[&str]) -> Option<&'static [&'static str]> {
+    match path {
+        [] => Some(fields_of::<FileConfig>()),
+        ["dirs"] => Some(DIR_ENTRY_KEYS),
+        ["filtering"] | ["profile", _, "filtering"] => Some(fields_of::<FileFilterConfig>()),
+        ["scanning"] | ["profile", _, "scanning"] => Some(fields_of::<FileScanConfig>()),
+        ["execution"] | ["profile", _, "execution"] => Some(fields_of::<FileExecutionConfig>()),
+        ["hooks"] => Some(fields_of::<FileHooksConfig>()),
+        ["caches"] => Some(fields_of::<FileCachesConfig>()),
+        ["artifacts", _] => Some(fields_of::<FileArtifactConfig>()),
+        ["profile", _] => Some(fields_of::<FileProfileConfig>()),
+        _ => None,
+    }
+}
+
+/// Describe the unknown `key` of the table at `path`, pointing at the key
+/// that was probably meant.
+fn unknown_key_message(key: &str, path: &[&str], known: &[&str]) -> String {
+    let table = if path.is_empty() {
+        "at the top level".to_string()
+    } else {
+        format!("in [{}]", path.join("."))
+    };
+    let mut message = format!("Unknown key `{key}` {table}");
+
+    if let Some(close) = known
+        .iter()
+        .filter(|k| edit_distance(key, k) <= 2)
+        .min_by_key(|k| edit_distance(key, k))
+    {
+        let _ = write!(message, " (did you mean `{close}`?)");
+    } else if let Some(section) = ["filtering", "scanning", "execution"]
+        .into_iter()
+        .find(|section| known_keys(&[section]).is_some_and(|keys| keys.contains(&key)))
+    {
+        let _ = write!(message, " (it belongs in [{section}])");
+    }
+    message
+}
+
+/// Describe the first part of the dotted key `path` that the config doesn't
+/// know, or return `None` if `path` names a known setting or table.
+pub(crate) fn unknown_key(path: &[&str]) -> Option<String> {
+    (0..path.len()).find_map(|depth| {
+        let (parents, key) = (&path[..depth], path[depth]);
+        let known = known_keys(parents)?;
+        (!known.contains(&key)).then(|| unknown_key_message(key, parents, known))
+    })
+}
+
+/// Check the values of a config that loaded, reporting them at their keys in
+/// `doc`.
+fn check_values(
+    config: &FileConfig,
+    doc: &dyn TableLike,
+    diagnostics: &mut Vec<Diagnostic>,
+    report: &impl Fn(Severity, Option<Range<usize>>, String) -> Diagnostic,
+) {
+    let filtering = std::iter::once((None, &config.filtering)).chain(
+        config
+            .profile
+            .iter()
+            .map(|(name, profile)| (Some(name.as_str()), &profile.filtering)),
+    );
+
+    for (profile, filter) in filtering {
+        let table = profile.map_or_else(
+            || vec!["filtering"],
+            |name| vec!["profile", name, "filtering"],
+        );
+        let span = |key| span_of(doc, &[table.as_slice(), &[key]].concat());
+
+        for (key, value) in [
+            ("keep_size", &filter.keep_size),
+            ("min_benefit", &filter.min_benefit),
+            ("collapse_below", &filter.collapse_below),
+        ] {
+            if let Some(size) = value
+                && let Err(e) = parse_size(size)
+            {
+                let message = format!("Invalid size `{size}` for `{key}`: {e}");
+                diagnostics.push(report(Severity::Error, span(key), message));
+            }
+        }
+
+        if let Err(e) = compile_name_matcher(filter.name_pattern.as_deref()) {
+            let message = format!("{e:#}");
+            diagnostics.push(report(Severity::Error, span("name_pattern"), message));
+        }
+        for key in ["name_filter", "path_filter"] {
+            let pattern = match key {
+                "name_filter" => filter.name_filter.as_deref(),
+                _ => filter.path_filter.as_deref(),
+            };
+            if let Err(e) = compile_filter_regex(pattern, key) {
+                diagnostics.push(report(Severity::Error, span(key), format!("{e:#}")));
+            }
+        }
+        if let Err(e) = compile_filter_expr(filter.filter_expr.as_deref()) {
+            let message = format!("{e:#}");
+            diagnostics.push(report(Severity::Error, span("filter_expr"), message));
+        }
+
+        for (key, names) in [
+            ("types", &filter.types),
+            ("exclude_types", &filter.exclude_types),
+        ] {
+            for name in unknown_types(names.as_deref()) {
+                let message = format!("Unknown project type `{name}` in `{key}`");
+                diagnostics.push(report(Severity::Warning, span(key), message));
+            }
+        }
+    }
+
+    check_scanning(config, doc, diagnostics, report);
+
+    if let Some(dir) = &config.dir
+        && !expand_tilde(dir).is_dir()
+    {
+        let message = format!("Directory `{}` does not exist", dir.display());
+        diagnostics.push(report(Severity::Warning, span_of(doc, &["dir"]), message));
+    }
+
+    let entries = doc.get("dirs").and_then(Item::as_array);
+    for (index, entry) in config.dirs.iter().flatten().enumerate() {
+        let span = entries
+            .and_then(|e| e.get(index))
+            .and_then(toml_edit::Value::span);
+
+        if !expand_tilde(entry.path()).is_dir() {
+            let message = format!("Directory `{}` does not exist", entry.path().display());
+            diagnostics.push(report(Severity::Warning, span.clone(), message));
+        }
+        if let Some(budget) = entry.budget()
+            && let Err(e) = parse_size(budget)
+        {
+            let message = format!("Invalid size `{budget}` for `budget`: {e}");
+            diagnostics.push(report(Severity::Error, span, message));
+        }
+    }
+}
+
+/// Report the aliases that can't be expanded, at their keys in `doc`.
+fn check_aliases(
+    config: &FileConfig,
+    doc: &dyn TableLike,
+    diagnostics: &mut Vec<Diagnostic>,
+    report: &impl Fn(Severity, Option<Range<usize>>, String) -> Diagnostic,
+) {
+    for name in config.aliases.keys() {
+        if let Err(e) = alias_words(name, &config.aliases) {
+            let span = span_of(doc, &["aliases", name]);
+            diagnostics.push(report(Severity::Error, span, e.to_string()));
+        }
+    }
+}
+
+/// Check the values of the scanning settings, at the top level and in
+/// every profile, reporting them at their keys in `doc`.
+fn check_scanning(
+    config: &FileConfig,
+    doc: &dyn TableLike,
+    diagnostics: &mut Vec<Diagnostic>,
+    report: &impl Fn(Severity, Option<Range<usize>>, String) -> Diagnostic,
+) {
+    let scanning = std::iter::once((None, &config.scanning)).chain(
+        config
+            .profile
+            .iter()
+            .map(|(name, profile)| (Some(name.as_str()), &profile.scanning)),
+    );
+    for (profile, scan) in scanning {
+        let table = profile.map_or_else(
+            || vec!["scanning"],
+            |name| vec!["profile", name, "scanning"],
+        );
+        let span = |key| span_of(doc, &[table.as_slice(), &[key]].concat());
+
+        if let Some(size) = &scan.quick_size_threshold
+            && let Err(e) = parse_size(size)
+        {
+            let key = "quick_size_threshold";
+            let message = format!("Invalid size `{size}` for `{key}`: {e}");
+            diagnostics.push(report(Severity::Error, span(key), message));
+        }
+        if let Some(value) = &scan.age_source
+            && let Err(e) = AgeSource::from_config(value)
+        {
+            diagnostics.push(report(Severity::Error, span("age_source"), e.to_string()));
+        }
+        let key = "container_cache_patterns";
+        for pattern in scan.container_cache_patterns.iter().flatten() {
+            if let Err(e) = glob::Pattern::new(pattern) {
+                let message = format!("Invalid pattern `{pattern}` in `{key}`: {e}");
+                diagnostics.push(report(Severity::Error, span(key), message));
+            } else if !stays_in_project(pattern) {
+                let message =
+                    format!("Pattern `{pattern}` in `{key}` leaves the project and is ignored");
+                diagnostics.push(report(Severity::Warning, span(key), message));
+            }
+        }
+    }
+}
+
+/// Report artifact settings that don't apply to the project type they are
+/// set for, at their keys in `doc`.
+fn check_artifacts(
+    config: &FileConfig,
+    doc: &dyn TableLike,
+    diagnostics: &mut Vec<Diagnostic>,
+    report: &impl Fn(Severity, Option<Range<usize>>, String) -> Diagnostic,
+) {
+    for (kind, artifacts) in &config.artifacts {
+        if artifacts.include_venv.is_some() && !kind.eq_ignore_ascii_case("python") {
+            let span = span_of(doc, &["artifacts", kind, "include_venv"]);
+            let message = format!(
+                "`include_venv` only applies to [artifacts.python], not [artifacts.{kind}]"
+            );
+            diagnostics.push(report(Severity::Warning, span, message));
+        }
+    }
+}
+
+/// The names in a list of project types that aren't a known type.
+fn unknown_types(names: Option<&[String]>) -> impl Iterator<Item = &String> {
+    names
+        .unwrap_or_default()
+        .iter()
+        .filter(|name| ProjectFilter::from_str(name.trim(), true).is_err())
+}
+
+/// Span of the key at `path` in `doc`, if present.
+fn span_of(doc: &dyn TableLike, path: &[&str]) -> Option<Range<usize>> {
+    let (last, parents) = path.split_last()?;
+    let table = parents
+        .iter()
+        .try_fold(doc, |table, key| table.get(key)?.as_table_like())?;
+    table.key(last)?.span()
+}
+
+/// The 1-based line of the byte `offset` in `content`.
+fn line_of(content: &str, offset: usize) -> usize {
+    content
+        .get(..offset)
+        .unwrap_or(content)
+        .matches('\n')
+        .count()
+        + 1
+}
+
+/// Number of single-character edits that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != *cb);
+            row.push(substitute.min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Names of the fields `T` reads, as declared by `#[derive(Deserialize)]`.
+///
+/// Serde hands a struct's field names to the deserializer, so one that only
+/// records them gives the known keys without keeping a second list in sync.
+fn fields_of<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// A deserializer that records the field names of the struct asking for them.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("only structs are supported"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("field names recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(diagnostics: &[Diagnostic]) -> Vec<Option<usize>> {
+        diagnostics.iter().map(|d| d.line).collect()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let content = "units = \"binary\"\ndirs = [\"/\", { path = \"/\", budget = \"1GB\" }]\n\n[filtering]\nkeep_size = \"50MB\"\nname_pattern = \"my-*\"\n\n[artifacts.rust]\nextra = [\"docs/book\"]\n\n[profile.ci.execution]\nuse_trash = false\n";
+        assert_eq!(validate(content), Vec::new());
+        assert_eq!(validate(""), Vec::new());
+    }
+
+    #[test]
+    fn test_unknown_keys_with_suggestions() {
+        let content = "keep_days = 7\n\n[filtering]\nkeep_sizee = \"50MB\"\n\n[scaning]\nthreads = 2\n\n[artifacts.rust]\nextras = []\n";
+        let diagnostics = validate(content);
+
+        assert_eq!(
+            lines(&diagnostics),
+            vec![Some(1), Some(4), Some(6), Some(10)]
+        );
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+        assert!(diagnostics[0].message.contains("it belongs in [filtering]"));
+        assert!(diagnostics[1].message.contains("did you mean `keep_size`?"));
+        assert!(diagnostics[2].message.contains("did you mean `scanning`?"));
+        assert!(diagnostics[3].message.contains("in [artifacts.rust]"));
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let content = "dirs = [{ path = \"/\", budget = \"lots\" }, \"/no/such/dir\"]\n\n[filtering]\nkeep_size = \"50XB\"\nname_filter = \"(\"\nfilter_expr = \"size >\"\n\n[profile.big.filtering]\nmin_benefit = \"1.2.3GB\"\n\n[profile.big.scanning]\nquick_size_threshold = \"huge\"\nage_source = \"newest\"\n";
+        let diagnostics = validate(content);
+
+        let found: Vec<(Option<usize>, Severity)> =
+            diagnostics.iter().map(|d| (d.line, d.severity)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some(1), Severity::Error),
+                (Some(1), Severity::Warning),
+                (Some(4), Severity::Error),
+                (Some(5), Severity::Error),
+                (Some(6), Severity::Error),
+                (Some(9), Severity::Error),
+                (Some(12), Severity::Error),
+                (Some(13), Severity::Error),
+            ]
+        );
+        assert!(diagnostics[0].message.contains("`lots`"));
+        assert!(diagnostics[1].message.contains("/no/such/dir"));
+        assert!(
+            diagnostics[4]
+                .message
+                .contains("expected a value after `>`")
+        );
+    }
+
+    #[test]
+    fn test_invalid_aliases() {
+        let content = "[aliases]\nweekly = \"--keep-days 14\"\nloop = \"@loop\"\nquote = \"--name-pattern 'x\"\n";
+        let diagnostics = validate(content);
+
+        assert_eq!(lines(&diagnostics), vec![Some(3), Some(4)]);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+        assert!(diagnostics[0].message.contains("loop -> loop"));
+        assert!(diagnostics[1].message.contains("unterminated quote"));
+    }
+
+    #[test]
+    fn test_unknown_project_types() {
+        let content = "[filtering]\ntypes = [\"rust\", \"rsut\"]\nexclude_types = [\"Node\"]\n";
+        let diagnostics = validate(content);
+
+        assert_eq!(lines(&diagnostics), vec![Some(2)]);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert!(diagnostics[0].message.contains("`rsut` in `types`"));
+    }
+
+    #[test]
+    fn test_container_cache_patterns() {
+        let content = "[scanning]\ncontainer_cache_patterns = [\".docker/**/node_modules\", \"[cache\"]\n\n[profile.ci.scanning]\ncontainer_cache_patterns = [\"../shared/cache\"]\n";
+        let diagnostics = validate(content);
+
+        let found: Vec<(Option<usize>, Severity)> =
+            diagnostics.iter().map(|d| (d.line, d.severity)).collect();
+        assert_eq!(
+            found,
+            vec![(Some(2), Severity::Error), (Some(5), Severity::Warning)]
+        );
+        assert!(diagnostics[0].message.contains("`[cache`"));
+        assert!(diagnostics[1].message.contains("leaves the project"));
+    }
+
+    #[test]
+    fn test_include_venv_outside_python() {
+        let content =
+            "[artifacts.python]\ninclude_venv = true\n\n[artifacts.node]\ninclude_venv = true\n";
+        let diagnostics = validate(content);
+
+        assert_eq!(lines(&diagnostics), vec![Some(5)]);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert!(diagnostics[0].message.contains("not [artifacts.node]"));
+    }
+
+    #[test]
+    fn test_syntax_and_type_errors() {
+        let syntax = validate("[filtering]\nkeep_size = \n");
+        assert_eq!(lines(&syntax), vec![Some(2)]);
+        assert_eq!(syntax[0].severity, Severity::Error);
+
+        let wrong_type = validate("[filtering]\n\nkeep_days = \"seven\"\n");
+        assert_eq!(lines(&wrong_type), vec![Some(3)]);
+        assert_eq!(wrong_type[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_fields_of_follows_config_structs() {
+        assert!(fields_of::<FileFilterConfig>().contains(&"keep_size"));
+        assert!(fields_of::<FileConfig>().contains(&"profile"));
+        assert_eq!(edit_distance("keep_sizee", "keep_size"), 1);
+        assert_eq!(edit_distance("scaning", "scanning"), 1);
+    }
+}
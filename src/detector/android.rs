@@ -0,0 +1,155 @@
+//! Android projects: a Gradle build with the Android plugin and its modules.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{DetectContext, Detector, existing_artifacts, java::extract_gradle_project_name};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Gradle settings files, Groovy and Kotlin DSL.
+const SETTINGS_FILES: [&str; 2] = ["settings.gradle", "settings.gradle.kts"];
+
+/// Gradle build scripts, Groovy and Kotlin DSL.
+const BUILD_FILES: [&str; 2] = ["build.gradle", "build.gradle.kts"];
+
+/// Detects Android projects built with Gradle.
+///
+/// # Detection Criteria
+///
+/// 1. `settings.gradle` or `settings.gradle.kts` file exists in directory
+/// 2. `gradle.properties` or `local.properties` file exists in directory
+/// 3. The root or a module build script applies an Android plugin (`com.android.*`)
+///
+/// The project cleans the root `build/` and `.gradle/` directories and the
+/// `build/` and `.cxx/` (native build) directories of every module, so a
+/// multi-module app is reported as one project. Modules are the ones listed
+/// with `include` in the settings, plus any subdirectory with a build script.
+///
+/// The project name is the `rootProject.name` of the Gradle settings.
+#[derive(Debug)]
+pub struct AndroidDetector;
+
+impl Detector for AndroidDetector {
+    fn name(&self) -> &'static str {
+        "android"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Android
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &[
+            "settings.gradle",
+            "settings.gradle.kts",
+            "gradle.properties",
+            "local.properties",
+            "build.gradle",
+            "build.gradle.kts",
+        ]
+    }
+
+    fn matches(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let settings = SETTINGS_FILES
+            .iter()
+            .map(|name| path.join(name))
+            .find(|file| file.is_file())?;
+        if !path.join("gradle.properties").is_file() && !path.join("local.properties").is_file() {
+            return None;
+        }
+
+        let content = ctx.read_file(&settings).unwrap_or_default();
+        let modules = find_modules(path, &content);
+        let uses_android = std::iter::once(path)
+            .chain(modules.iter().map(PathBuf::as_path))
+            .any(|dir| applies_android_plugin(dir, ctx));
+        if !uses_android {
+            return None;
+        }
+
+        let module_dirs = modules
+            .iter()
+            .flat_map(|module| [module.join("build"), module.join(".cxx")]);
+        existing_artifacts(
+            [path.join("build"), path.join(".gradle")]
+                .into_iter()
+                .chain(module_dirs),
+        )
+    }
+
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        extract_gradle_project_name(path, ctx)
+    }
+}
+
+/// The module directories of the Gradle build in `root`.
+///
+/// These are the projects listed with `include` in the settings (`':app'`,
+/// `":feature:login"`), and the immediate subdirectories with a build script.
+fn find_modules(root: &Path, settings: &str) -> BTreeSet<PathBuf> {
+    let mut modules: BTreeSet<PathBuf> = settings
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("include"))
+        .flat_map(included_projects)
+        .map(|project| root.join(project.trim_start_matches(':').replace(':', "/")))
+        .filter(|dir| dir.is_dir())
+        .collect();
+
+    if let Ok(entries) = fs::read_dir(root) {
+        modules.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|dir| dir.is_dir() && has_build_script(dir)),
+        );
+    }
+
+    modules
+}
+
+/// The quoted project paths of an `include` line, such as `include ':app', ':lib'`.
+fn included_projects(line: &str) -> Vec<String> {
+    line.split(['"', '\''])
+        .skip(1)
+        .step_by(2)
+        .filter(|project| project.starts_with(':'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Return true if `dir` has a Gradle build script.
+fn has_build_script(dir: &Path) -> bool {
+    BUILD_FILES.iter().any(|name| dir.join(name).is_file())
+}
+
+/// Return true if the build script in `dir` mentions an Android Gradle plugin.
+fn applies_android_plugin(dir: &Path, ctx: &DetectContext<'_>) -> bool {
+    BUILD_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|file| file.is_file())
+        .filter_map(|file| ctx.read_file(&file))
+        .any(|content| content.contains("com.android"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_included_projects() {
+        assert_eq!(
+            included_projects("include ':app', ':lib'"),
+            vec![":app", ":lib"]
+        );
+        assert_eq!(
+            included_projects(r#"include(":feature:login")"#),
+            vec![":feature:login"]
+        );
+        assert!(included_projects("includeBuild(\"build-logic\")").is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Container caches kept inside projects.
+//!
+//! Compose-based repositories often bind-mount caches into the project: a
+//! local `BuildKit` cache exported to `.buildx-cache/`, or the `node_modules/`
+//! of a container mirrored under `.docker/`. They are large and rebuilt by
+//! the next `docker compose build`, but belong to no ecosystem's detector.
+//! With `container_caches` enabled, the directories matching the configured
+//! patterns (relative to the project root) are cleaned with the project's
+//! other build directories.
+//!
+//! A pattern is a path with `*`, `?` and `[...]` wildcards in its components
+//! and `**` for any number of directories. The search doesn't descend into a
+//! directory once it matches, and `**` stops [`WILDCARD_DEPTH`] levels down,
+//! so a pattern like `.docker/**/node_modules` doesn't walk through the
+//! dependencies it finds.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use glob::{MatchOptions, Pattern};
+
+/// How many directory levels `**` descends at most.
+const WILDCARD_DEPTH: usize = 8;
+
+/// The directories in the project in `root` that match one of `patterns`,
+/// sorted, without those inside another match.
+///
+/// Patterns that are absolute or lead out of the project are ignored.
+#[must_use]
+pub(super) fn container_caches(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    for pattern in patterns {
+        if let Some(components) = pattern_components(pattern) {
+            collect(root, &components, WILDCARD_DEPTH, &mut found);
+        }
+    }
+
+    found.sort();
+    found.dedup();
+    let mut outermost: Vec<PathBuf> = Vec::new();
+    for path in found {
+        if !outermost.iter().any(|outer| path.starts_with(outer)) {
+            outermost.push(path);
+        }
+    }
+    outermost
+}
+
+/// Whether `path`, in the project in `root`, matches one of `patterns`.
+#[must_use]
+pub(crate) fn is_container_cache(root: &Path, path: &Path, patterns: &[String]) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::default()
+    };
+
+    patterns
+        .iter()
+        .filter_map(|pattern| pattern_components(pattern))
+        .filter_map(|components| Pattern::new(&components.join("/")).ok())
+        .any(|pattern| pattern.matches_path_with(relative, options))
+}
+
+/// Whether `pattern` is relative and stays inside the project; other
+/// patterns are ignored.
+#[must_use]
+pub(crate) fn stays_in_project(pattern: &str) -> bool {
+    pattern_components(pattern).is_some()
+}
+
+/// The components of `pattern`, or `None` if it is empty, absolute or has a
+/// `..` component.
+fn pattern_components(pattern: &str) -> Option<Vec<&str>> {
+    if pattern.starts_with(['/', '\\']) {
+        return None;
+    }
+    let components: Vec<&str> = pattern
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect();
+    (!components.is_empty() && !components.contains(&"..")).then_some(components)
+}
+
+/// Add the directories below `dir` that match the pattern `components` to
+/// `found`, with `depth` levels left for `**`.
+fn collect(dir: &Path, components: &[&str], depth: usize, found: &mut Vec<PathBuf>) {
+    let Some((&first, rest)) = components.split_first() else {
+        found.push(dir.to_path_buf());
+        return;
+    };
+
+    if first == "**" {
+        collect(dir, rest, depth, found);
+        if depth > 0 {
+            for subdir in subdirs(dir) {
+                if !found.contains(&subdir) {
+                    collect(&subdir, components, depth - 1, found);
+                }
+            }
+        }
+    } else if Pattern::escape(first) == first {
+        let subdir = dir.join(first);
+        if subdir.is_dir() {
+            collect(&subdir, rest, depth, found);
+        }
+    } else if let Ok(pattern) = Pattern::new(first) {
+        for subdir in subdirs(dir) {
+            let name = subdir.file_name().map(|name| name.to_string_lossy());
+            if name.is_some_and(|name| pattern.matches(&name)) {
+                collect(&subdir, rest, depth, found);
+            }
+        }
+    }
+}
+
+/// The directories directly in `dir`, without following symbolic links.
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_CONTAINER_CACHES;
+
+    fn defaults() -> Vec<String> {
+        DEFAULT_CONTAINER_CACHES.map(String::from).to_vec()
+    }
+
+    #[test]
+    fn test_container_caches() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let root = tmp.path();
+        for dir in [
+            ".buildx-cache/blobs",
+            ".docker/node_modules/dep",
+            ".docker/volumes/web/node_modules/dep/node_modules",
+            ".docker/config",
+            "src/node_modules",
+        ] {
+            fs::create_dir_all(root.join(dir))?;
+        }
+
+        assert_eq!(
+            container_caches(root, &defaults()),
+            vec![
+                root.join(".buildx-cache"),
+                root.join(".docker/node_modules"),
+                root.join(".docker/volumes/web/node_modules"),
+            ]
+        );
+
+        let patterns = ["*/node_modules", "/etc", "../other", "src/[n]ode_modules"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            container_caches(root, &patterns),
+            vec![
+                root.join(".docker/node_modules"),
+                root.join("src/node_modules")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_container_cache() {
+        let root = Path::new("/work/app");
+        let is_cache = |relative| is_container_cache(root, &root.join(relative), &defaults());
+
+        assert!(is_cache(".buildx-cache"));
+        assert!(is_cache(".docker/node_modules"));
+        assert!(is_cache(".docker/volumes/web/node_modules"));
+        assert!(!is_cache("node_modules"));
+        assert!(!is_cache(".docker/config"));
+        assert!(!is_container_cache(
+            root,
+            Path::new("/elsewhere/.buildx-cache"),
+            &defaults()
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+//! C/C++ projects built with `CMake`, Make or Visual Studio (`MSBuild`).
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{DetectContext, Detector, existing_artifacts, find_file_with_extension};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Output directories of Visual Studio C++ builds: the configurations of the
+/// x86 platform at the top level, and those of the other platforms in a
+/// directory named after the platform.
+const MSBUILD_OUTPUT_DIRS: [&str; 6] = [
+    "Debug",
+    "Release",
+    "x64/Debug",
+    "x64/Release",
+    "ARM64/Debug",
+    "ARM64/Release",
+];
+
+/// Detects C/C++ projects.
+///
+/// # Detection Criteria
+///
+/// 1. `CMakeLists.txt` + `build/` directory (`CMake`)
+/// 2. `Makefile` + `build/` directory (`Make`)
+/// 3. A `.vcxproj` file, or a `.sln` solution referencing one, + `.vs/` or
+///    output directories such as `x64/Debug/` and `x64/Release/` (Visual Studio)
+///
+/// Visual Studio keeps its `IntelliSense` database and other caches in the
+/// hidden `.vs/` directory next to the solution, and writes the binaries and
+/// intermediate files of every configuration to `<Platform>/<Configuration>/`
+/// (`Debug/` and `Release/` for x86). Solutions without C++ projects belong
+/// to the .NET detector.
+///
+/// The project name is the one given to `project()` in `CMakeLists.txt`,
+/// then the name of the `.sln` or `.vcxproj` file.
+#[derive(Debug)]
+pub struct CppDetector;
+
+impl Detector for CppDetector {
+    fn name(&self) -> &'static str {
+        "cpp"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Cpp
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["CMakeLists.txt", "Makefile", "*.sln", "*.vcxproj"]
+    }
+
+    fn matches(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let has_build_file = path.join("CMakeLists.txt").exists() || path.join("Makefile").exists();
+        let build_dir = has_build_file.then(|| path.join("build"));
+
+        let msbuild_dirs = is_visual_cpp(path, ctx)
+            .then(|| {
+                std::iter::once(path.join(".vs"))
+                    .chain(MSBUILD_OUTPUT_DIRS.iter().map(|dir| path.join(dir)))
+            })
+            .into_iter()
+            .flatten();
+
+        existing_artifacts(build_dir.into_iter().chain(msbuild_dirs))
+    }
+
+    /// Looks for `project(name` patterns in `CMakeLists.txt`, then for a
+    /// solution or project file.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        ctx.read_file(&path.join("CMakeLists.txt"))
+            .and_then(|content| extract_cmake_project_name(&content))
+            .or_else(|| {
+                find_file_with_extension(path, "sln")
+                    .or_else(|| find_file_with_extension(path, "vcxproj"))
+                    .and_then(|file| Some(file.file_stem()?.to_str()?.to_string()))
+            })
+    }
+}
+
+/// The name given to the first `project()` call of a `CMakeLists.txt`.
+fn extract_cmake_project_name(content: &str) -> Option<String> {
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("project(") || trimmed.starts_with("PROJECT(") {
+            let inner = trimmed
+                .trim_start_matches("project(")
+                .trim_start_matches("PROJECT(")
+                .trim_end_matches(')')
+                .trim();
+
+            // The project name is the first word/token
+            let name = inner.split_whitespace().next()?;
+            // Remove possible surrounding quotes
+            let name = name.trim_matches('"').trim_matches('\'');
+            if !name.is_empty() {
+                return Some(name.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+/// Return true if `path` holds a Visual Studio C++ project, or a solution
+/// with one.
+fn is_visual_cpp(path: &Path, ctx: &DetectContext<'_>) -> bool {
+    find_file_with_extension(path, "vcxproj").is_some()
+        || files_with_extension(path, "sln")
+            .iter()
+            .filter_map(|solution| ctx.read_file(solution))
+            .any(|content| content.contains(".vcxproj"))
+}
+
+/// All files in `dir` with the given extension.
+fn files_with_extension(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file() && path.extension().and_then(|e| e.to_str()) == Some(extension)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_cmake_project_name() {
+        assert_eq!(
+            extract_cmake_project_name(
+                "cmake_minimum_required(VERSION 3.20)\nproject(engine CXX)\n"
+            ),
+            Some("engine".to_string())
+        );
+        assert_eq!(
+            extract_cmake_project_name("PROJECT(\"Legacy\")\n"),
+            Some("Legacy".to_string())
+        );
+        assert_eq!(
+            extract_cmake_project_name("add_executable(app main.cpp)\n"),
+            None
+        );
+    }
+}
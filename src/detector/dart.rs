@@ -0,0 +1,57 @@
+//! Dart/Flutter projects: `pubspec.yaml` with `.dart_tool/` or `build/`.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, existing_artifacts};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Dart/Flutter projects.
+///
+/// # Detection Criteria
+///
+/// 1. `pubspec.yaml` file exists in directory
+/// 2. At least one of `.dart_tool/` or `build/` exists
+///
+/// Both directories are cleaned when present. The project name is the
+/// `name:` field of `pubspec.yaml`.
+#[derive(Debug)]
+pub struct DartDetector;
+
+impl Detector for DartDetector {
+    fn name(&self) -> &'static str {
+        "dart"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Dart
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["pubspec.yaml"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !path.join("pubspec.yaml").exists() {
+            return None;
+        }
+
+        existing_artifacts([path.join(".dart_tool"), path.join("build")])
+    }
+
+    /// Reads the `name:` field using simple line parsing.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        let content = ctx.read_file(&path.join("pubspec.yaml"))?;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if let Some(rest) = trimmed.strip_prefix("name:") {
+                let name = rest.trim().trim_matches('"').trim_matches('\'').to_string();
+                if !name.is_empty() {
+                    return Some(name);
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,71 @@
+//! Deno projects: `deno.json` with a `vendor/` or `node_modules/` directory.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, from_str};
+
+use super::{DetectContext, Detector, artifact};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Deno projects.
+///
+/// # Detection Criteria
+///
+/// 1. `deno.json` or `deno.jsonc` file exists in directory
+/// 2. `vendor/` directory exists (from `deno vendor`), or `node_modules/`
+///    does (Deno 2 npm support) without a `package.json`, to avoid overlap
+///    with Node.js
+///
+/// Deno detection runs before Node.js so that a project with `deno.json` and
+/// `node_modules/` (but no `package.json`) is classified as Deno. The project
+/// name is the `name` field of the configuration file.
+#[derive(Debug)]
+pub struct DenoDetector;
+
+impl Detector for DenoDetector {
+    fn name(&self) -> &'static str {
+        "deno"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Deno
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["deno.json", "deno.jsonc"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        config_path(path)?;
+
+        // vendor/ directory (created by `deno vendor`)
+        let vendor_dir = path.join("vendor");
+        if vendor_dir.exists() {
+            return Some(vec![artifact(vendor_dir)]);
+        }
+
+        // node_modules/ (Deno 2 npm support) — only when no package.json exists
+        let node_modules = path.join("node_modules");
+        (node_modules.exists() && !path.join("package.json").exists())
+            .then(|| vec![artifact(node_modules)])
+    }
+
+    /// Reads the `name` field of `deno.json` or `deno.jsonc`, if the file can
+    /// be parsed.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        config_path(path)
+            .and_then(|config| ctx.read_file(&config))
+            .and_then(|content| {
+                let json = from_str::<Value>(&content).ok()?;
+                Some(json.get("name")?.as_str()?.to_string())
+            })
+    }
+}
+
+/// The Deno configuration file of `path`, preferring `deno.json` over `deno.jsonc`.
+fn config_path(path: &Path) -> Option<PathBuf> {
+    ["deno.json", "deno.jsonc"]
+        .into_iter()
+        .map(|name| path.join(name))
+        .find(|config| config.exists())
+}
@@ -0,0 +1,45 @@
+//! .NET/C# projects: a `.csproj` file with `bin/` and `obj/` directories.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, existing_artifacts, find_file_with_extension};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects .NET/C# projects.
+///
+/// # Detection Criteria
+///
+/// 1. At least one `.csproj` file exists in the directory
+/// 2. At least one of `bin/` or `obj/` directories exists
+///
+/// Both directories are cleaned when present. The project name is the name
+/// of the `.csproj` file.
+#[derive(Debug)]
+pub struct DotNetDetector;
+
+impl Detector for DotNetDetector {
+    fn name(&self) -> &'static str {
+        "dotnet"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::DotNet
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["*.csproj"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let build_arts = existing_artifacts([path.join("bin"), path.join("obj")])?;
+        find_file_with_extension(path, "csproj")?;
+        Some(build_arts)
+    }
+
+    fn extract_name(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<String> {
+        find_file_with_extension(path, "csproj")?
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(std::string::ToString::to_string)
+    }
+}
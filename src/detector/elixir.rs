@@ -0,0 +1,61 @@
+//! Elixir projects: `mix.exs` with a `_build/` directory.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, artifact};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Elixir/Mix projects.
+///
+/// # Detection Criteria
+///
+/// 1. `mix.exs` file exists in directory
+/// 2. `_build/` subdirectory exists in directory
+///
+/// The project name is the `app:` atom of the Mix project definition.
+#[derive(Debug)]
+pub struct ElixirDetector;
+
+impl Detector for ElixirDetector {
+    fn name(&self) -> &'static str {
+        "elixir"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Elixir
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["mix.exs"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let build_dir = path.join("_build");
+
+        (path.join("mix.exs").exists() && build_dir.exists()).then(|| vec![artifact(build_dir)])
+    }
+
+    /// Looks for the `app: :atom_name` pattern.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        let content = ctx.read_file(&path.join("mix.exs"))?;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if let Some(pos) = trimmed.find("app:") {
+                let after = trimmed[pos + 4..].trim_start();
+                if let Some(atom) = after.strip_prefix(':') {
+                    // Elixir atom names consist of alphanumeric chars and underscores
+                    let name: String = atom
+                        .chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    if !name.is_empty() {
+                        return Some(name);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,111 @@
+//! Erlang projects: a rebar3 `rebar.config` with a `_build/` directory.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{DetectContext, Detector, artifact};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Erlang projects built with rebar3.
+///
+/// # Detection Criteria
+///
+/// 1. `rebar.config` file exists in directory
+/// 2. `_build/` subdirectory exists in directory
+/// 3. No `mix.exs` file exists in directory
+///
+/// Elixir libraries sometimes ship a `rebar.config` as well; with a
+/// `mix.exs` present, `_build/` is Mix's and the project is left to the
+/// Elixir detector.
+///
+/// The project name is the application name of the `src/*.app.src` file, or
+/// else the release name of the `relx` section of `rebar.config`.
+#[derive(Debug)]
+pub struct ErlangDetector;
+
+impl Detector for ErlangDetector {
+    fn name(&self) -> &'static str {
+        "erlang"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Erlang
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["rebar.config"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let build_dir = path.join("_build");
+
+        (path.join("rebar.config").exists() && !path.join("mix.exs").exists() && build_dir.exists())
+            .then(|| vec![artifact(build_dir)])
+    }
+
+    /// Tries `src/*.app.src` first, then the `relx` release in
+    /// `rebar.config`.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        find_app_src(path)
+            .and_then(|app_src| ctx.read_file(&app_src))
+            .and_then(|content| atom_after(&content, "{application,"))
+            .or_else(|| {
+                ctx.read_file(&path.join("rebar.config"))
+                    .and_then(|content| atom_after(&content, "{release,"))
+            })
+    }
+}
+
+/// The first `*.app.src` file in the `src/` directory of `path`.
+fn find_app_src(path: &Path) -> Option<PathBuf> {
+    fs::read_dir(path.join("src"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|file| {
+            file.is_file()
+                && file
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.ends_with(".app.src"))
+        })
+}
+
+/// The atom following the first `prefix` in `content`, ignoring whitespace,
+/// such as `my_app` in `{application, my_app, [...]}`.
+fn atom_after(content: &str, prefix: &str) -> Option<String> {
+    let compact: String = content.split_whitespace().collect();
+    let start = compact.find(prefix)? + prefix.len();
+    let atom: String = compact[start..]
+        .trim_start_matches('{')
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '@')
+        .collect();
+    (!atom.is_empty()).then_some(atom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atom_after() {
+        assert_eq!(
+            atom_after(
+                "{application, my_app,\n [{description, \"An app\"}]}.\n",
+                "{application,"
+            ),
+            Some("my_app".to_string())
+        );
+        assert_eq!(
+            atom_after(
+                "{relx, [{release, {my_release, \"0.1.0\"}, [my_app]}]}.",
+                "{release,"
+            ),
+            Some("my_release".to_string())
+        );
+        assert_eq!(atom_after("{erl_opts, [debug_info]}.", "{release,"), None);
+    }
+}
@@ -0,0 +1,132 @@
+//! Go projects: `go.mod` with a `vendor/` directory or compiled `bin/` output.
+//!
+//! Most modules don't vendor their dependencies; those live in the shared
+//! module cache (`~/go/pkg/mod`), which `--system-caches` cleans rather than
+//! any single project.
+
+use std::{
+    fs,
+    io::Read as _,
+    path::{Path, PathBuf},
+};
+
+use super::{DetectContext, Detector, existing_artifacts};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// The first bytes of a compiled executable: ELF, PE (`MZ`), and 64-bit,
+/// 32-bit and universal Mach-O.
+const EXECUTABLE_MAGIC: [&[u8]; 5] = [
+    b"\x7fELF",
+    b"MZ",
+    &[0xcf, 0xfa, 0xed, 0xfe],
+    &[0xce, 0xfa, 0xed, 0xfe],
+    &[0xca, 0xfe, 0xba, 0xbe],
+];
+
+/// Detects Go projects.
+///
+/// # Detection Criteria
+///
+/// 1. `go.mod` file exists in directory
+/// 2. `vendor/` subdirectory exists in directory, or a `bin/` subdirectory
+///    that holds nothing but compiled executables (as `go build -o bin/`
+///    leaves it)
+///
+/// A `bin/` with scripts or any other files in it is left alone, since it
+/// may be part of the sources.
+///
+/// The project name is the last component of the module path in `go.mod`.
+#[derive(Debug)]
+pub struct GoDetector;
+
+impl Detector for GoDetector {
+    fn name(&self) -> &'static str {
+        "go"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Go
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["go.mod"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !path.join("go.mod").exists() {
+            return None;
+        }
+
+        let bin_dir = Some(path.join("bin")).filter(|dir| holds_only_executables(dir));
+        existing_artifacts(std::iter::once(path.join("vendor")).chain(bin_dir))
+    }
+
+    /// Looks for the first line starting with `module ` and takes the last
+    /// component of the module path, for a cleaner name.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        let content = ctx.read_file(&path.join("go.mod"))?;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with("module ") {
+                let module_path = line.strip_prefix("module ")?.trim();
+
+                // Take the last component of the module path for a cleaner name
+                if let Some(name) = module_path.split('/').next_back() {
+                    return Some(name.to_string());
+                }
+
+                return Some(module_path.to_string());
+            }
+        }
+
+        None
+    }
+}
+
+/// Return true if `dir` is a non-empty directory of compiled executables
+/// and nothing else.
+fn holds_only_executables(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+
+    let files: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    !files.is_empty() && files.iter().all(|file| is_compiled_executable(file))
+}
+
+/// Return true if `path` is a file that starts like a compiled executable.
+fn is_compiled_executable(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    let Ok(len) = fs::File::open(path).and_then(|mut file| file.read(&mut magic)) else {
+        return false;
+    };
+
+    EXECUTABLE_MAGIC
+        .iter()
+        .any(|prefix| magic[..len].starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_holds_only_executables() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let bin = tmp.path().join("bin");
+        fs::create_dir(&bin)?;
+        assert!(!holds_only_executables(&bin));
+
+        fs::write(bin.join("server"), b"\x7fELF\x02\x01\x01")?;
+        fs::write(bin.join("server.exe"), b"MZ\x90\x00")?;
+        fs::write(bin.join("cli"), [0xcf, 0xfa, 0xed, 0xfe, 0x07])?;
+        assert!(holds_only_executables(&bin));
+
+        fs::write(bin.join("release.sh"), "#!/bin/sh\n")?;
+        assert!(!holds_only_executables(&bin));
+        assert!(!holds_only_executables(&tmp.path().join("missing")));
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Haskell projects built with Stack or Cabal.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, artifact, find_file_with_extension};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Haskell projects.
+///
+/// # Detection Criteria
+///
+/// 1. `stack.yaml` + `.stack-work/` directory (Stack)
+/// 2. `cabal.project` or a `*.cabal` file + `dist-newstyle/` directory (Cabal)
+///
+/// The project name is the `name:` field of the `*.cabal` file or of
+/// `package.yaml` (hpack).
+#[derive(Debug)]
+pub struct HaskellDetector;
+
+impl Detector for HaskellDetector {
+    fn name(&self) -> &'static str {
+        "haskell"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Haskell
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["stack.yaml", "cabal.project", "*.cabal", "package.yaml"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        // Stack project: stack.yaml + .stack-work/
+        let stack_work = path.join(".stack-work");
+        if path.join("stack.yaml").exists() && stack_work.exists() {
+            return Some(vec![artifact(stack_work)]);
+        }
+
+        // Cabal project: (cabal.project OR *.cabal file) + dist-newstyle/
+        let dist_newstyle = path.join("dist-newstyle");
+        let has_cabal = || {
+            path.join("cabal.project").exists() || find_file_with_extension(path, "cabal").is_some()
+        };
+
+        (dist_newstyle.exists() && has_cabal()).then(|| vec![artifact(dist_newstyle)])
+    }
+
+    /// Tries the `*.cabal` file first, then `package.yaml`.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        // Try *.cabal file first
+        if let Some(cabal_file) = find_file_with_extension(path, "cabal")
+            && let Some(content) = ctx.read_file(&cabal_file)
+        {
+            for line in content.lines() {
+                let trimmed = line.trim();
+                if let Some(rest) = trimmed.strip_prefix("name:") {
+                    let name = rest.trim().to_string();
+                    if !name.is_empty() {
+                        return Some(name);
+                    }
+                }
+            }
+        }
+
+        // Try package.yaml (hpack)
+        let package_yaml = path.join("package.yaml");
+        if package_yaml.exists()
+            && let Some(content) = ctx.read_file(&package_yaml)
+        {
+            for line in content.lines() {
+                let trimmed = line.trim();
+                if let Some(rest) = trimmed.strip_prefix("name:") {
+                    let name = rest.trim().trim_matches('"').trim_matches('\'').to_string();
+                    if !name.is_empty() {
+                        return Some(name);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,111 @@
+//! Java/Kotlin projects built with Maven or Gradle.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, artifact, extract_quoted_value};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Java/Kotlin projects.
+///
+/// # Detection Criteria
+///
+/// 1. `pom.xml` + `target/` directory (Maven)
+/// 2. `build.gradle` or `build.gradle.kts` + `build/` directory (Gradle)
+///
+/// The project name is the `<artifactId>` of `pom.xml`, or the
+/// `rootProject.name` of the Gradle settings.
+#[derive(Debug)]
+pub struct JavaDetector;
+
+impl Detector for JavaDetector {
+    fn name(&self) -> &'static str {
+        "java"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Java
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &[
+            "pom.xml",
+            "build.gradle",
+            "build.gradle.kts",
+            "settings.gradle",
+            "settings.gradle.kts",
+        ]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        // Maven project: pom.xml + target/
+        if is_maven_project(path) {
+            return Some(vec![artifact(path.join("target"))]);
+        }
+
+        // Gradle project: build.gradle(.kts) + build/
+        let has_gradle =
+            path.join("build.gradle").exists() || path.join("build.gradle.kts").exists();
+        let build_dir = path.join("build");
+
+        (has_gradle && build_dir.exists()).then(|| vec![artifact(build_dir)])
+    }
+
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        if is_maven_project(path) {
+            extract_maven_project_name(path, ctx)
+        } else {
+            extract_gradle_project_name(path, ctx)
+        }
+    }
+}
+
+/// Return true if `path` has a `pom.xml` and a Maven `target/` directory.
+fn is_maven_project(path: &Path) -> bool {
+    path.join("pom.xml").exists() && path.join("target").exists()
+}
+
+/// Extract the project name from a Maven `pom.xml` file.
+///
+/// Looks for `<artifactId>` tags and extracts the text content.
+fn extract_maven_project_name(path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+    let content = ctx.read_file(&path.join("pom.xml"))?;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("<artifactId>") && trimmed.ends_with("</artifactId>") {
+            let name = trimmed
+                .strip_prefix("<artifactId>")?
+                .strip_suffix("</artifactId>")?;
+            return Some(name.to_string());
+        }
+    }
+
+    None
+}
+
+/// Extract the project name from a Gradle project.
+///
+/// Looks for `settings.gradle` or `settings.gradle.kts` and extracts
+/// the `rootProject.name` value.
+pub(super) fn extract_gradle_project_name(path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+    for settings_file in &["settings.gradle", "settings.gradle.kts"] {
+        let settings_path = path.join(settings_file);
+        if settings_path.exists()
+            && let Some(content) = ctx.read_file(&settings_path)
+        {
+            for line in content.lines() {
+                let trimmed = line.trim();
+                if trimmed.contains("rootProject.name") && trimmed.contains('=') {
+                    return extract_quoted_value(trimmed).or_else(|| {
+                        trimmed
+                            .split('=')
+                            .nth(1)
+                            .map(|s| s.trim().trim_matches('\'').to_string())
+                    });
+                }
+            }
+        }
+    }
+
+    None
+}
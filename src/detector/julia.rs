@@ -0,0 +1,95 @@
+//! Julia projects: a `Project.toml` with precompile caches in a project-local
+//! depot or built dependencies.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, existing_artifacts, extract_quoted_value};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Julia projects.
+///
+/// # Detection Criteria
+///
+/// 1. `Project.toml` file exists in directory
+/// 2. At least one of [`DEPOT_CACHES`] or `deps/usr/` exists
+///
+/// `.julia/` is a depot kept inside the project (with `JULIA_DEPOT_PATH`
+/// pointing at it). Only its precompile caches and logs are cleaned: the
+/// rest holds installed packages, registries, artifacts and package
+/// settings, which may not be downloadable again as they were. `deps/usr/`
+/// holds the binaries a package's `deps/build.jl` installs when it is built.
+///
+/// The project name is the `name` field of `Project.toml`.
+#[derive(Debug)]
+pub struct JuliaDetector;
+
+/// The directories of a project-local `.julia/` depot that Julia writes
+/// again by itself: precompiled packages and logs.
+const DEPOT_CACHES: [&str; 2] = ["compiled", "logs"];
+
+impl Detector for JuliaDetector {
+    fn name(&self) -> &'static str {
+        "julia"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Julia
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["Project.toml", "Manifest.toml"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !path.join("Project.toml").is_file() {
+            return None;
+        }
+
+        let depot = path.join(".julia");
+        existing_artifacts(
+            DEPOT_CACHES
+                .iter()
+                .map(|dir| depot.join(dir))
+                .chain([path.join("deps").join("usr")]),
+        )
+    }
+
+    /// Reads `name = "Name"` from `Project.toml`.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        ctx.read_file(&path.join("Project.toml"))
+            .and_then(|content| extract_project_name(&content))
+    }
+}
+
+/// The top-level `name` field of a `Project.toml`, before its first table.
+fn extract_project_name(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| {
+            line.strip_prefix("name")
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+        .find_map(extract_quoted_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_project_name() {
+        assert_eq!(
+            extract_project_name(
+                "name = \"DataTools\"\nuuid = \"0b6ae3cc-0000-0000-0000-000000000000\"\n\n[deps]\n"
+            ),
+            Some("DataTools".to_string())
+        );
+        assert_eq!(
+            extract_project_name("[deps]\nname = \"NotTheProject\"\n"),
+            None
+        );
+        assert_eq!(extract_project_name("names = \"x\"\n"), None);
+    }
+}
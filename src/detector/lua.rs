@@ -0,0 +1,82 @@
+//! Lua projects: a `LuaRocks` rockspec with a `lua_modules/` or `.luarocks/` tree.
+
+use std::path::Path;
+
+use super::{
+    DetectContext, Detector, existing_artifacts, extract_quoted_value, find_file_with_extension,
+};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Lua projects managed with `LuaRocks`.
+///
+/// # Detection Criteria
+///
+/// 1. A `*.rockspec` file exists in directory
+/// 2. At least one of `lua_modules/` or `.luarocks/` exists
+///
+/// Both directories are cleaned when present; they hold the rocks installed
+/// with `luarocks --tree lua_modules` and the project-local `LuaRocks`
+/// configuration and cache.
+///
+/// The project name is the `package` field of the rockspec.
+#[derive(Debug)]
+pub struct LuaDetector;
+
+impl Detector for LuaDetector {
+    fn name(&self) -> &'static str {
+        "lua"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Lua
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["*.rockspec"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let build_arts = existing_artifacts([path.join("lua_modules"), path.join(".luarocks")])?;
+        find_file_with_extension(path, "rockspec")?;
+        Some(build_arts)
+    }
+
+    /// Reads `package = "name"` from the rockspec.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        find_file_with_extension(path, "rockspec")
+            .and_then(|rockspec| ctx.read_file(&rockspec))
+            .and_then(|content| extract_package_name(&content))
+    }
+}
+
+/// The `package` field of a rockspec, such as `package = "lua-cjson"`.
+fn extract_package_name(rockspec: &str) -> Option<String> {
+    rockspec
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            line.strip_prefix("package")
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+        .find_map(|line| extract_quoted_value(&line.replace('\'', "\"")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_package_name() {
+        assert_eq!(
+            extract_package_name(
+                "rockspec_format = \"3.0\"\npackage = \"lua-cjson\"\nversion = \"2.1.0-1\"\n"
+            ),
+            Some("lua-cjson".to_string())
+        );
+        assert_eq!(
+            extract_package_name("package='penlight'\n"),
+            Some("penlight".to_string())
+        );
+        assert_eq!(extract_package_name("packages = {}\n"), None);
+    }
+}
@@ -0,0 +1,472 @@
+//! Project detectors, one per supported ecosystem.
+//!
+//! A [`Detector`] recognizes one type of project from the files in a
+//! directory, lists the build artifacts it can clean, and extracts the
+//! project's name. The scanner runs every detector in [`DETECTORS`] on each
+//! directory it visits; adding a project type means adding a detector there
+//! (and a matching [`ProjectFilter`](crate::config::ProjectFilter) variant).
+//!
+//! Detectors only look at the directory they are given. Sizes are measured
+//! later by the scanner, and per-directory overrides are applied on top.
+
+mod android;
+mod container;
+mod cpp;
+mod dart;
+mod deno;
+mod dotnet;
+mod elixir;
+mod erlang;
+mod go;
+mod haskell;
+mod java;
+mod julia;
+mod lua;
+mod nim;
+mod node;
+mod ocaml;
+mod php;
+mod python;
+mod r;
+mod ruby;
+mod rust;
+mod scala;
+mod swift;
+mod zig;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+pub use android::AndroidDetector;
+pub use cpp::CppDetector;
+pub use dart::DartDetector;
+pub use deno::DenoDetector;
+pub use dotnet::DotNetDetector;
+pub use elixir::ElixirDetector;
+pub use erlang::ErlangDetector;
+pub use go::GoDetector;
+pub use haskell::HaskellDetector;
+pub use java::JavaDetector;
+pub use julia::JuliaDetector;
+pub use lua::LuaDetector;
+pub use nim::NimDetector;
+pub use node::NodeDetector;
+pub use ocaml::OCamlDetector;
+pub use php::PhpDetector;
+pub use python::PythonDetector;
+pub use r::RDetector;
+pub use ruby::RubyDetector;
+pub use rust::RustDetector;
+pub use scala::ScalaDetector;
+pub use swift::SwiftDetector;
+pub use zig::ZigDetector;
+
+pub(crate) use container::{is_container_cache, stays_in_project};
+pub(crate) use python::{CONDA_ENV_DIRS, VENV_DIRS};
+pub(crate) use rust::target_subdirs;
+
+use crate::{
+    config::ScanOptions,
+    error::{CleanDevDirsError, ScanErrors},
+    project::{BuildArtifacts, Detection, Project, ProjectType},
+    vcs::remote_repository_name,
+};
+
+/// Every detector, in priority order.
+///
+/// When two detectors claim the same build directory, it belongs to the one
+/// listed first, so more specific ecosystems come before more generic ones:
+/// Scala before Java, since both use `target/`, Android before Java, since
+/// both use Gradle's `build/`, Deno before Node since Deno 2 projects may
+/// also have a `node_modules/`, and Elixir before Erlang and OCaml, since all
+/// three build into `_build/`.
+pub static DETECTORS: &[&dyn Detector] = &[
+    &RustDetector,
+    &DenoDetector,
+    &NodeDetector,
+    &ScalaDetector,
+    &AndroidDetector,
+    &JavaDetector,
+    &SwiftDetector,
+    &DotNetDetector,
+    &PythonDetector,
+    &GoDetector,
+    &CppDetector,
+    &RubyDetector,
+    &ElixirDetector,
+    &ErlangDetector,
+    &OCamlDetector,
+    &PhpDetector,
+    &HaskellDetector,
+    &DartDetector,
+    &ZigDetector,
+    &LuaDetector,
+    &NimDetector,
+    &JuliaDetector,
+    &RDetector,
+];
+
+/// Recognizes one type of development project.
+pub trait Detector: Sync {
+    /// Identifier of the detector, as accepted by `--project-type` (e.g. `"rust"`).
+    fn name(&self) -> &'static str;
+
+    /// Type of the projects this detector finds.
+    fn kind(&self) -> ProjectType;
+
+    /// The files in a project root that this detector recognizes projects by,
+    /// as file names or `*.ext` patterns (e.g. `"Cargo.toml"`, `"*.csproj"`).
+    ///
+    /// Used to explain detections, and to recognize projects whose only
+    /// artifacts are container caches; otherwise [`Detector::matches`] decides.
+    fn markers(&self) -> &'static [&'static str];
+
+    /// The build artifacts of the project in `path`.
+    ///
+    /// Returns `None` if `path` isn't a project of this type, or has nothing
+    /// to clean. The artifacts are not measured yet.
+    fn matches(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>>;
+
+    /// The name of the project in `path`, as read from its manifest.
+    ///
+    /// Returns `None` if the manifest doesn't name the project;
+    /// [`Detector::detect`] then falls back to other names.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String>;
+
+    /// Detect a project of this type in `path`.
+    ///
+    /// The project is named by its manifest, else by the git repository it is
+    /// the root of, else by its directory. With `explain` set in the scan
+    /// options, it records which markers and artifacts it was detected by.
+    /// With `container_caches` set, the project's container caches are added
+    /// to its artifacts.
+    fn detect(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<Project> {
+        let mut build_arts = self.matches(path, ctx);
+        if ctx.options.container_caches {
+            build_arts = with_container_caches(build_arts, path, self.markers(), ctx.options);
+        }
+        let build_arts = build_arts?;
+        let detection = ctx.options.explain.then(|| Detection {
+            detector: self.name().to_string(),
+            markers: found_markers(path, self.markers()),
+            artifacts: build_arts.iter().map(|a| a.path.clone()).collect(),
+        });
+
+        let mut project = Project::new(
+            self.kind(),
+            path.to_path_buf(),
+            build_arts,
+            project_name(path, self.extract_name(path, ctx)),
+        );
+        project.detection = detection;
+        Some(project)
+    }
+}
+
+/// What a detector may consult besides the directory itself.
+#[derive(Debug)]
+pub struct DetectContext<'a> {
+    /// Options of the running scan
+    pub options: &'a ScanOptions,
+
+    /// Problems reported in verbose mode once the scan is done
+    errors: &'a ScanErrors,
+}
+
+impl<'a> DetectContext<'a> {
+    /// Create a context that collects problems into `errors`.
+    #[must_use]
+    pub const fn new(options: &'a ScanOptions, errors: &'a ScanErrors) -> Self {
+        Self { options, errors }
+    }
+
+    /// Record a problem, such as an unreadable manifest.
+    pub fn report(&self, error: CleanDevDirsError) {
+        self.errors.record(error);
+    }
+
+    /// Read a file, reporting the error if it can't be read.
+    #[must_use]
+    pub fn read_file(&self, path: &Path) -> Option<String> {
+        fs::read_to_string(path)
+            .map_err(|e| {
+                self.report(
+                    CleanDevDirsError::scan(format!("Error reading {}", path.display()))
+                        .with_source(e),
+                );
+            })
+            .ok()
+    }
+}
+
+/// A build artifact at `path`, to be measured later.
+const fn artifact(path: PathBuf) -> BuildArtifacts {
+    BuildArtifacts {
+        path,
+        size: 0,
+        files: 0,
+        dirs: 0,
+        newest_file: None,
+        estimated: false,
+    }
+}
+
+/// The paths that exist, as build artifacts; `None` if there are none.
+fn existing_artifacts(paths: impl IntoIterator<Item = PathBuf>) -> Option<Vec<BuildArtifacts>> {
+    let build_arts: Vec<_> = paths
+        .into_iter()
+        .filter(|path| path.exists())
+        .map(artifact)
+        .collect();
+    (!build_arts.is_empty()).then_some(build_arts)
+}
+
+/// `build_arts` of the project in `path` with its container caches added,
+/// except those inside another artifact.
+///
+/// A directory with one of `markers` is a project even if its container
+/// caches are all there is to clean.
+fn with_container_caches(
+    build_arts: Option<Vec<BuildArtifacts>>,
+    path: &Path,
+    markers: &[&str],
+    options: &ScanOptions,
+) -> Option<Vec<BuildArtifacts>> {
+    if build_arts.is_none() && found_markers(path, markers).is_empty() {
+        return None;
+    }
+    let caches = container::container_caches(path, &options.container_cache_patterns);
+
+    let mut build_arts = build_arts.unwrap_or_default();
+    for cache in caches {
+        if !build_arts.iter().any(|a| cache.starts_with(&a.path)) {
+            build_arts.push(artifact(cache));
+        }
+    }
+    (!build_arts.is_empty()).then_some(build_arts)
+}
+
+/// The names of the files in `dir` that match one of `markers`, sorted.
+fn found_markers(dir: &Path, markers: &[&str]) -> Vec<String> {
+    let patterns: Vec<glob::Pattern> = markers
+        .iter()
+        .filter_map(|marker| glob::Pattern::new(marker).ok())
+        .collect();
+
+    let mut found: Vec<String> = marker_files(dir, &patterns).collect();
+    found.sort();
+    found
+}
+
+/// The files in `dir` that match one of `markers`, sorted, and the markers
+/// that no file matches, in the order given.
+pub(crate) fn marker_trace(dir: &Path, markers: &[&str]) -> (Vec<String>, Vec<String>) {
+    let found = found_markers(dir, markers);
+    let missing = markers
+        .iter()
+        .filter(|marker| {
+            glob::Pattern::new(marker)
+                .is_ok_and(|pattern| !found.iter().any(|name| pattern.matches(name)))
+        })
+        .map(|marker| (*marker).to_string())
+        .collect();
+    (found, missing)
+}
+
+/// Return true if `dir` holds a marker file of any detector, so it looks
+/// like the root of a project.
+pub(crate) fn has_project_marker(dir: &Path) -> bool {
+    static PATTERNS: OnceLock<Vec<glob::Pattern>> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        DETECTORS
+            .iter()
+            .flat_map(|detector| detector.markers())
+            .filter_map(|marker| glob::Pattern::new(marker).ok())
+            .collect()
+    });
+
+    marker_files(dir, patterns).next().is_some()
+}
+
+/// The names of the files in `dir` that match one of `patterns`, in
+/// directory order.
+fn marker_files(dir: &Path, patterns: &[glob::Pattern]) -> impl Iterator<Item = String> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| patterns.iter().any(|pattern| pattern.matches(name)))
+        })
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+}
+
+/// Extract a quoted string value from a line.
+fn extract_quoted_value(line: &str) -> Option<String> {
+    let start = line.find('"')?;
+    let end = line.rfind('"')?;
+
+    if start == end {
+        return None;
+    }
+
+    Some(line[start + 1..end].to_string())
+}
+
+/// The name of the project in `path`, given the name from its manifest.
+///
+/// The same for every project type, so that projects without a name in
+/// their manifest don't all show up (and sort) as unnamed:
+///
+/// 1. the name from the manifest, unless it's blank
+/// 2. the name of the git repository, from the URL of its `origin` remote,
+///    when `path` is the root of the work tree
+/// 3. the name of the directory
+fn project_name(path: &Path, manifest_name: Option<String>) -> Option<String> {
+    manifest_name
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| remote_repository_name(path))
+        .or_else(|| fallback_to_directory_name(path))
+}
+
+/// Fallback to directory name
+///
+/// A relative path such as `.` is resolved first, so scanning the current
+/// directory still names its project.
+fn fallback_to_directory_name(path: &Path) -> Option<String> {
+    let resolved;
+    let path = if path.file_name().is_some() {
+        path
+    } else {
+        resolved = fs::canonicalize(path).ok()?;
+        &resolved
+    };
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(std::string::ToString::to_string)
+}
+
+/// Find the first file with a given extension in a directory.
+fn find_file_with_extension(dir: &Path, extension: &str) -> Option<PathBuf> {
+    let entries = fs::read_dir(dir).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some(extension) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum;
+
+    use super::*;
+    use crate::config::ProjectFilter;
+
+    #[test]
+    fn test_every_project_filter_has_a_detector() {
+        for filter in ProjectFilter::value_variants() {
+            let Some(name) = filter.detector_name() else {
+                assert_eq!(*filter, ProjectFilter::All);
+                continue;
+            };
+
+            assert_eq!(
+                filter.to_possible_value().map(|v| v.get_name().to_string()),
+                Some(name.to_string())
+            );
+            assert!(
+                DETECTORS.iter().any(|d| d.name() == name),
+                "no detector named {name}"
+            );
+        }
+        assert_eq!(DETECTORS.len(), ProjectFilter::value_variants().len() - 1);
+    }
+
+    #[test]
+    fn test_detector_priority() {
+        let position = |name| DETECTORS.iter().position(|d| d.name() == name);
+        assert!(position("scala") < position("java"));
+        assert!(position("android") < position("java"));
+        assert!(position("deno") < position("node"));
+        assert!(position("elixir") < position("erlang"));
+        assert!(position("elixir") < position("ocaml"));
+    }
+
+    #[test]
+    fn test_found_markers() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        for name in ["App.sln", "CMakeLists.txt", "README.md"] {
+            fs::write(tmp.path().join(name), "")?;
+        }
+        fs::create_dir(tmp.path().join("Makefile"))?;
+
+        assert_eq!(
+            found_markers(tmp.path(), &["Makefile", "CMakeLists.txt", "*.sln"]),
+            vec!["App.sln", "CMakeLists.txt"]
+        );
+        assert!(found_markers(&tmp.path().join("missing"), &["*.sln"]).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_project_marker() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        fs::write(tmp.path().join("notes.txt"), "")?;
+        assert!(!has_project_marker(tmp.path()));
+
+        fs::write(tmp.path().join("go.mod"), "module example.com/app\n")?;
+        assert!(has_project_marker(tmp.path()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_quoted_value() {
+        assert_eq!(
+            extract_quoted_value(r#"name = "my-project""#),
+            Some("my-project".to_string())
+        );
+        assert_eq!(
+            extract_quoted_value(r#"name = "with spaces""#),
+            Some("with spaces".to_string())
+        );
+        assert_eq!(extract_quoted_value("no quotes here"), None);
+        // Single quote mark is not a pair
+        assert_eq!(extract_quoted_value(r#"only "one"#), None);
+    }
+
+    #[test]
+    fn test_fallback_to_directory_name() {
+        assert_eq!(
+            fallback_to_directory_name(Path::new("/some/project-name")),
+            Some("project-name".to_string())
+        );
+        assert_eq!(
+            fallback_to_directory_name(Path::new("/some/my_app")),
+            Some("my_app".to_string())
+        );
+    }
+
+    #[test]
+    fn test_read_errors_are_reported() {
+        let errors = ScanErrors::new(ScanErrors::VERBOSE_LIMIT);
+        let missing = Path::new("/nonexistent/Cargo.toml");
+
+        let options = ScanOptions::default();
+        assert_eq!(
+            DetectContext::new(&options, &errors).read_file(missing),
+            None
+        );
+        assert_eq!(errors.count(), 1);
+        assert_eq!(errors.take_kept().len(), 1);
+    }
+}
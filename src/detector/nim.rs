@@ -0,0 +1,49 @@
+//! Nim projects: a `.nimble` package file with a `nimcache/` directory.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, artifact, find_file_with_extension};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Nim projects.
+///
+/// # Detection Criteria
+///
+/// 1. A `*.nimble` file exists in directory
+/// 2. `nimcache/` directory exists
+///
+/// Nimble names a package after its `.nimble` file, so the project name is
+/// the name of that file.
+#[derive(Debug)]
+pub struct NimDetector;
+
+impl Detector for NimDetector {
+    fn name(&self) -> &'static str {
+        "nim"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Nim
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["*.nimble"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let nimcache = path.join("nimcache");
+        if !nimcache.is_dir() {
+            return None;
+        }
+
+        find_file_with_extension(path, "nimble")?;
+        Some(vec![artifact(nimcache)])
+    }
+
+    fn extract_name(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<String> {
+        find_file_with_extension(path, "nimble")?
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(std::string::ToString::to_string)
+    }
+}
@@ -0,0 +1,306 @@
+//! Node.js projects: `package.json` with a `node_modules/` directory or the
+//! build output of a JavaScript framework.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use serde_json::{Value, from_str};
+
+use super::{DetectContext, Detector, artifact};
+use crate::error::CleanDevDirsError;
+use crate::project::{BuildArtifacts, ProjectType};
+use crate::vcs;
+
+/// Directories that JavaScript frameworks and bundlers write their build
+/// output to, cleaned as artifacts of their own next to `node_modules/`.
+const FRAMEWORK_OUTPUT_DIRS: [&str; 5] =
+    [".next", ".nuxt", ".svelte-kit", "dist", "storybook-static"];
+
+/// Frameworks and bundlers whose config file marks `dist/` as their output.
+const DIST_TOOLS: [&str; 4] = ["next", "nuxt", "svelte", "vite"];
+
+/// File extensions of the [`DIST_TOOLS`] config files, as in `vite.config.ts`.
+const CONFIG_EXTENSIONS: [&str; 6] = ["js", "mjs", "cjs", "ts", "mts", "cts"];
+
+/// Detects Node.js projects.
+///
+/// Each of `node_modules/` and the [framework output
+/// directories](FRAMEWORK_OUTPUT_DIRS) is a separate artifact, so one can be
+/// cleaned while the others are kept (see `--skip-artifacts`).
+///
+/// Workspace roots (a `workspaces` field in `package.json`, or a
+/// `pnpm-workspace.yaml`) are reported as a single project whose artifacts
+/// are those of the root plus those of every member package. The members
+/// themselves are dropped later by the scanner.
+///
+/// # Detection Criteria
+///
+/// 1. `package.json` file exists in directory
+/// 2. `node_modules/` or a framework output directory exists in directory,
+///    or in one of its workspace members
+///
+/// Since `dist/` is also a common name for hand-written or published files,
+/// it only counts in a package with `node_modules/` or the config file of a
+/// framework that builds into it (see [`DIST_TOOLS`]). Output directories
+/// with files tracked by git are never artifacts.
+///
+/// With `node_caches_only` enabled, the artifacts are the configured
+/// `node_caches` (such as `node_modules/.cache/`) of the project and its
+/// workspace members instead, and at least one of them must exist.
+///
+/// The project name is the `name` field of `package.json`.
+#[derive(Debug)]
+pub struct NodeDetector;
+
+impl Detector for NodeDetector {
+    fn name(&self) -> &'static str {
+        "node"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Node
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["package.json", "pnpm-workspace.yaml"]
+    }
+
+    fn matches(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !path.join("package.json").exists() {
+            return None;
+        }
+
+        if ctx.options.node_caches_only {
+            return cache_dirs(path, &ctx.options.node_caches);
+        }
+
+        let node_modules = path.join("node_modules");
+        let build_arts: Vec<_> = Some(node_modules)
+            .filter(|dir| dir.exists())
+            .into_iter()
+            .chain(output_dirs(path))
+            .chain(workspace_members(path).into_iter().flat_map(|member| {
+                Some(member.join("node_modules"))
+                    .filter(|dir| dir.is_dir() && !dir.is_symlink())
+                    .into_iter()
+                    .chain(output_dirs(&member))
+            }))
+            .map(artifact)
+            .collect();
+
+        (!build_arts.is_empty()).then_some(build_arts)
+    }
+
+    /// Parses the whole file as JSON; both read and parse errors are reported.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        let package_json = path.join("package.json");
+        let content = ctx.read_file(&package_json)?;
+
+        match from_str::<Value>(&content) {
+            Ok(json) => json
+                .get("name")
+                .and_then(|v| v.as_str())
+                .map(std::string::ToString::to_string),
+            Err(e) => {
+                ctx.report(
+                    CleanDevDirsError::scan(format!("Error parsing {}", package_json.display()))
+                        .with_source(e),
+                );
+                None
+            }
+        }
+    }
+}
+
+/// The framework output directories of the package in `package` that are
+/// build output: existing, not a symbolic link, not tracked by git, and for
+/// `dist/`, next to `node_modules/` or a [`DIST_TOOLS`] config file.
+fn output_dirs(package: &Path) -> Vec<PathBuf> {
+    FRAMEWORK_OUTPUT_DIRS
+        .iter()
+        .map(|dir| package.join(dir))
+        .filter(|dir| dir.is_dir() && !dir.is_symlink())
+        .filter(|dir| !dir.ends_with("dist") || builds_into_dist(package))
+        .filter(|dir| !vcs::is_tracked(dir))
+        .collect()
+}
+
+/// Whether the package in `package` has `node_modules/` or the config file
+/// of a framework that builds into `dist/`.
+fn builds_into_dist(package: &Path) -> bool {
+    package.join("node_modules").exists()
+        || DIST_TOOLS.iter().any(|tool| {
+            CONFIG_EXTENSIONS
+                .iter()
+                .any(|ext| package.join(format!("{tool}.config.{ext}")).is_file())
+        })
+}
+
+/// The existing `caches` of the project in `path` and its workspace members.
+///
+/// Entries that aren't a plain relative path, such as `../shared` or an
+/// absolute path, are ignored so nothing outside a package is cleaned.
+fn cache_dirs(path: &Path, caches: &[PathBuf]) -> Option<Vec<BuildArtifacts>> {
+    let caches: Vec<&PathBuf> = caches
+        .iter()
+        .filter(|cache| {
+            cache
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        })
+        .collect();
+
+    let build_arts: Vec<_> = std::iter::once(path.to_path_buf())
+        .chain(workspace_members(path))
+        .flat_map(|package| caches.iter().map(move |cache| package.join(cache)))
+        .filter(|dir| dir.is_dir() && !dir.is_symlink())
+        .map(artifact)
+        .collect();
+
+    (!build_arts.is_empty()).then_some(build_arts)
+}
+
+/// Find the member packages of a Node.js workspace rooted at `path`.
+///
+/// Patterns are read from the `workspaces` field of `package.json` (either
+/// an array or Yarn's `{ "packages": [...] }` form) and from the `packages`
+/// list of `pnpm-workspace.yaml`. Patterns prefixed with `!` exclude
+/// matching packages. Only directories containing a `package.json` count as
+/// members. Returns an empty list when `path` is not a workspace root.
+fn workspace_members(path: &Path) -> Vec<PathBuf> {
+    let mut patterns = package_json_workspaces(&path.join("package.json"));
+    patterns.extend(pnpm_workspace_packages(&path.join("pnpm-workspace.yaml")));
+
+    let (excludes, includes): (Vec<_>, Vec<_>) = patterns.iter().partition(|p| p.starts_with('!'));
+    let excludes: Vec<glob::Pattern> = excludes
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p.trim_start_matches('!')).ok())
+        .collect();
+
+    let mut members: Vec<PathBuf> = includes
+        .iter()
+        .filter_map(|pattern| glob::glob(&path.join(pattern).to_string_lossy()).ok())
+        .flat_map(|paths| paths.filter_map(Result::ok))
+        .filter(|member| member != path && member.join("package.json").is_file())
+        .filter(|member| !member.components().any(|c| c.as_os_str() == "node_modules"))
+        .filter(|member| {
+            let relative = member.strip_prefix(path).unwrap_or(member);
+            !excludes.iter().any(|ex| ex.matches_path(relative))
+        })
+        .collect();
+
+    members.sort();
+    members.dedup();
+    members
+}
+
+/// Read the workspace patterns from a `package.json` `workspaces` field.
+fn package_json_workspaces(package_json: &Path) -> Vec<String> {
+    let Some(json) = fs::read_to_string(package_json)
+        .ok()
+        .and_then(|content| from_str::<Value>(&content).ok())
+    else {
+        return Vec::new();
+    };
+
+    let workspaces = json.get("workspaces");
+    workspaces
+        .and_then(|w| w.get("packages"))
+        .or(workspaces)
+        .and_then(Value::as_array)
+        .map(|patterns| {
+            patterns
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read the workspace patterns from the `packages` list of a `pnpm-workspace.yaml`.
+///
+/// Only the block list form (`packages:` followed by `- pattern` lines) is
+/// understood, which is what pnpm itself writes.
+fn pnpm_workspace_packages(pnpm_workspace: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(pnpm_workspace) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .skip_while(|line| line.trim_end() != "packages:")
+        .skip(1)
+        .take_while(|line| {
+            line.trim().is_empty() || line.starts_with(char::is_whitespace) || line.starts_with('-')
+        })
+        .filter_map(|line| line.trim().strip_prefix('-'))
+        .map(|item| {
+            let item = item.split(" #").next().unwrap_or(item).trim();
+            item.trim_matches(|c| c == '\'' || c == '"').to_string()
+        })
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_yarn_workspaces_object_form() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path().join("yarn-repo");
+        fs::create_dir_all(root.join("apps/site"))?;
+        fs::write(
+            root.join("package.json"),
+            r#"{"workspaces": {"packages": ["apps/*"], "nohoist": ["**/x"]}}"#,
+        )?;
+        fs::write(root.join("apps/site/package.json"), "{}")?;
+
+        assert_eq!(workspace_members(&root), vec![root.join("apps/site")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dist_needs_node_modules_or_a_framework_config() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let package = tmp.path();
+        fs::create_dir_all(package.join("dist"))?;
+        fs::create_dir_all(package.join(".next"))?;
+        assert_eq!(output_dirs(package), vec![package.join(".next")]);
+
+        fs::write(package.join("vite.config.ts"), "")?;
+        assert_eq!(
+            output_dirs(package),
+            vec![package.join(".next"), package.join("dist")]
+        );
+
+        fs::remove_file(package.join("vite.config.ts"))?;
+        fs::create_dir_all(package.join("node_modules"))?;
+        assert_eq!(
+            output_dirs(package),
+            vec![package.join(".next"), package.join("dist")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pnpm_workspace_packages_parsing() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let file = tmp.path().join("pnpm-workspace.yaml");
+        fs::write(
+            &file,
+            "# workspace\npackages:\n  - packages/*\n\n  - \"apps/**\"\nonlyBuiltDependencies:\n  - esbuild\n",
+        )?;
+
+        assert_eq!(
+            pnpm_workspace_packages(&file),
+            vec!["packages/*", "apps/**"]
+        );
+        Ok(())
+    }
+}
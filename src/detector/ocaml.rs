@@ -0,0 +1,71 @@
+//! OCaml projects: a dune `dune-project` file with a `_build/` directory.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, artifact};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects OCaml projects built with dune.
+///
+/// # Detection Criteria
+///
+/// 1. `dune-project` file exists in directory
+/// 2. `_build/` subdirectory exists in directory
+///
+/// The project name is the `(name ...)` stanza of `dune-project`.
+#[derive(Debug)]
+pub struct OCamlDetector;
+
+impl Detector for OCamlDetector {
+    fn name(&self) -> &'static str {
+        "ocaml"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::OCaml
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["dune-project"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let build_dir = path.join("_build");
+
+        (path.join("dune-project").exists() && build_dir.exists())
+            .then(|| vec![artifact(build_dir)])
+    }
+
+    /// Reads the `(name ...)` stanza.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        ctx.read_file(&path.join("dune-project"))
+            .and_then(|content| extract_dune_name(&content))
+    }
+}
+
+/// The project name of a `dune-project` file, such as `(name my_lib)`.
+fn extract_dune_name(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("(name")?;
+        let name = rest.trim().trim_end_matches(')').trim().trim_matches('"');
+        (!name.is_empty()).then(|| name.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_dune_name() {
+        assert_eq!(
+            extract_dune_name("(lang dune 3.11)\n(name my_lib)\n(generate_opam_files true)\n"),
+            Some("my_lib".to_string())
+        );
+        assert_eq!(
+            extract_dune_name("(lang dune 3.0)\n (name \"quoted\")\n"),
+            Some("quoted".to_string())
+        );
+        assert_eq!(extract_dune_name("(lang dune 3.0)\n"), None);
+    }
+}
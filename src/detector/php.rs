@@ -0,0 +1,53 @@
+//! PHP projects: `composer.json` with a `vendor/` directory.
+
+use std::path::Path;
+
+use serde_json::{Value, from_str};
+
+use super::{DetectContext, Detector, artifact};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects PHP/Composer projects.
+///
+/// # Detection Criteria
+///
+/// 1. `composer.json` file exists in directory
+/// 2. `vendor/` subdirectory exists in directory
+///
+/// The project name is the package part of the `name` field of `composer.json`.
+#[derive(Debug)]
+pub struct PhpDetector;
+
+impl Detector for PhpDetector {
+    fn name(&self) -> &'static str {
+        "php"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Php
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["composer.json"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let vendor_dir = path.join("vendor");
+
+        (path.join("composer.json").exists() && vendor_dir.exists())
+            .then(|| vec![artifact(vendor_dir)])
+    }
+
+    /// The name is typically `vendor/package`; only the package component is
+    /// returned.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        ctx.read_file(&path.join("composer.json"))
+            .and_then(|content| {
+                let json = from_str::<Value>(&content).ok()?;
+                let name = json.get("name")?.as_str()?;
+                // composer.json name is "vendor/package"; return just the package part.
+                let package = name.split('/').next_back().unwrap_or(name);
+                Some(package.to_string())
+            })
+    }
+}
@@ -0,0 +1,312 @@
+//! Python projects: a packaging or dependency file with cache and build directories.
+//!
+//! Besides the usual caches, Jupyter leaves a `.ipynb_checkpoints/` next to
+//! every notebook that was opened, so data-science projects collect them
+//! throughout their tree. Those are cleaned wherever they are, down to a
+//! few levels below the project root.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use super::{DetectContext, Detector, artifact, extract_quoted_value, has_project_marker};
+use crate::{
+    project::{BuildArtifacts, ProjectType},
+    scanner::Scanner,
+};
+
+/// Files that mark a directory as a Python project.
+const CONFIG_FILES: [&str; 9] = [
+    "requirements.txt",
+    "setup.py",
+    "pyproject.toml",
+    "setup.cfg",
+    "Pipfile",
+    "pipenv.lock",
+    "poetry.lock",
+    "environment.yml",
+    "environment.yaml",
+];
+
+/// Cache and build directories of a Python project.
+const BUILD_DIRS: [&str; 7] = [
+    "__pycache__",
+    ".pytest_cache",
+    "build",
+    "dist",
+    ".eggs",
+    ".tox",
+    ".coverage",
+];
+
+/// Virtual environments, which are only cleaned with `include_venv`.
+pub(crate) const VENV_DIRS: [&str; 2] = ["venv", ".venv"];
+
+/// The directory Jupyter keeps notebook checkpoints in.
+const CHECKPOINT_DIR: &str = ".ipynb_checkpoints";
+
+/// How many levels below the project root checkpoint directories are looked for.
+const CHECKPOINT_DEPTH: usize = 4;
+
+/// Directories that conda environments created inside a project usually live in.
+pub(crate) const CONDA_ENV_DIRS: [&str; 2] = ["env", ".conda"];
+
+/// Detects Python projects.
+///
+/// # Detection Criteria
+///
+/// A Python project is identified by having:
+/// 1. At least one of: requirements.txt, setup.py, pyproject.toml, setup.cfg, Pipfile,
+///    or a conda environment.yml
+/// 2. At least one of the cache/build directories: `__pycache__`, `.pytest_cache`, build, dist, .eggs,
+///    a `*.egg-info` directory, or a `.ipynb_checkpoints` directory in the project
+///
+/// All of them that are present are cleaned. Checkpoint directories are
+/// looked for in the subdirectories too, except in hidden and build
+/// directories and in nested projects. Virtual environments in `venv/` or
+/// `.venv/` are working environments rather than build output, so they are
+/// only cleaned with `python_venvs` enabled (`include_venv` under
+/// `[artifacts.python]`). With `conda_envs` enabled, conda environments in
+/// `env/` or `.conda/` (recognized by their `conda-meta/`) are cleaned as
+/// well.
+#[derive(Debug)]
+pub struct PythonDetector;
+
+impl Detector for PythonDetector {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Python
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &[
+            "requirements.txt",
+            "setup.py",
+            "pyproject.toml",
+            "setup.cfg",
+            "Pipfile",
+            "environment.yml",
+            "environment.yaml",
+        ]
+    }
+
+    fn matches(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !CONFIG_FILES.iter().any(|&file| path.join(file).exists()) {
+            return None;
+        }
+
+        let mut build_arts: Vec<BuildArtifacts> = BUILD_DIRS
+            .iter()
+            .map(|&dir_name| path.join(dir_name))
+            .filter(|dir_path| dir_path.is_dir())
+            .map(artifact)
+            .collect();
+        if ctx.options.python_venvs {
+            build_arts.extend(
+                VENV_DIRS
+                    .iter()
+                    .map(|&dir_name| path.join(dir_name))
+                    .filter(|dir_path| dir_path.is_dir())
+                    .map(artifact),
+            );
+        }
+
+        // Also collect any *.egg-info directories present in the project root.
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                let entry_path = entry.path();
+                if entry_path.is_dir()
+                    && entry_path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.ends_with(".egg-info"))
+                {
+                    build_arts.push(artifact(entry_path));
+                }
+            }
+        }
+
+        build_arts.extend(checkpoint_dirs(path).into_iter().map(artifact));
+        if ctx.options.conda_envs {
+            build_arts.extend(
+                CONDA_ENV_DIRS
+                    .iter()
+                    .map(|&dir_name| path.join(dir_name))
+                    .filter(|dir| dir.join("conda-meta").is_dir())
+                    .map(artifact),
+            );
+        }
+
+        (!build_arts.is_empty()).then_some(build_arts)
+    }
+
+    /// Tries the configuration files in order of preference:
+    ///
+    /// 1. pyproject.toml (from [project] name or [tool.poetry] name)
+    /// 2. setup.py (from name= parameter)
+    /// 3. setup.cfg (from [metadata] name)
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        read_if_exists(&path.join("pyproject.toml"), ctx)
+            .and_then(|content| extract_name_from_toml_like_content(&content))
+            .or_else(|| {
+                read_if_exists(&path.join("setup.py"), ctx)
+                    .and_then(|content| extract_name_from_python_content(&content))
+            })
+            .or_else(|| {
+                read_if_exists(&path.join("setup.cfg"), ctx)
+                    .and_then(|content| extract_name_from_cfg_content(&content))
+            })
+    }
+}
+
+/// The `.ipynb_checkpoints` directories in the project in `root`.
+///
+/// Hidden directories, build directories and nested projects aren't looked
+/// into, and neither are the checkpoint directories themselves.
+fn checkpoint_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut walker = WalkDir::new(root)
+        .max_depth(CHECKPOINT_DEPTH)
+        .into_iter()
+        .filter_entry(|entry| entry.file_type().is_dir());
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else { continue };
+        if entry.depth() == 0 {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy();
+        if name == CHECKPOINT_DIR {
+            found.push(entry.into_path());
+            walker.skip_current_dir();
+        } else if name.starts_with('.')
+            || BUILD_DIRS.contains(&name.as_ref())
+            || VENV_DIRS.contains(&name.as_ref())
+            || CONDA_ENV_DIRS.contains(&name.as_ref())
+            || Scanner::is_excluded_directory(entry.path())
+            || has_project_marker(entry.path())
+        {
+            walker.skip_current_dir();
+        }
+    }
+    found
+}
+
+/// Read a configuration file, if the project has one.
+fn read_if_exists(file: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+    if !file.exists() {
+        return None;
+    }
+
+    ctx.read_file(file)
+}
+
+/// Extract name from TOML-like content (pyproject.toml)
+fn extract_name_from_toml_like_content(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("name") && line.contains('='))
+        .and_then(extract_quoted_value)
+}
+
+/// Extract name from Python content (setup.py)
+fn extract_name_from_python_content(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| line.contains("name") && line.contains('='))
+        .and_then(extract_quoted_value)
+}
+
+/// Extract name from INI-style configuration content (setup.cfg)
+fn extract_name_from_cfg_content(content: &str) -> Option<String> {
+    let mut in_metadata_section = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line == "[metadata]" {
+            in_metadata_section = true;
+        } else if line.starts_with('[') && line.ends_with(']') {
+            in_metadata_section = false;
+        } else if in_metadata_section && line.starts_with("name") && line.contains('=') {
+            return line.split('=').nth(1).map(|name| name.trim().to_string());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_dirs() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let root = tmp.path();
+        for dir in [
+            ".ipynb_checkpoints",
+            "notebooks/eda/.ipynb_checkpoints/nested/.ipynb_checkpoints",
+            ".venv/lib/.ipynb_checkpoints",
+            "node_modules/pkg/.ipynb_checkpoints",
+            "a/b/c/d/.ipynb_checkpoints",
+            "tool/.ipynb_checkpoints",
+        ] {
+            fs::create_dir_all(root.join(dir))?;
+        }
+        // A nested project keeps its own checkpoints
+        fs::write(root.join("tool/pyproject.toml"), "")?;
+
+        let mut found = checkpoint_dirs(root);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                root.join(".ipynb_checkpoints"),
+                root.join("notebooks/eda/.ipynb_checkpoints"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_name_from_cfg_content() {
+        let content = "[metadata]\nname = my-package\nversion = 1.0\n";
+        assert_eq!(
+            extract_name_from_cfg_content(content),
+            Some("my-package".to_string())
+        );
+
+        // Name in wrong section should not be found
+        let wrong_section = "[options]\nname = not-this\n";
+        assert_eq!(extract_name_from_cfg_content(wrong_section), None);
+
+        // Multiple sections — name must be in [metadata]
+        let multi = "[options]\nkey = val\n\n[metadata]\nname = correct\n\n[other]\nname = wrong\n";
+        assert_eq!(
+            extract_name_from_cfg_content(multi),
+            Some("correct".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_name_from_python_content() {
+        let content = "from setuptools import setup\nsetup(\n    name=\"my-pkg\",\n)\n";
+        assert_eq!(
+            extract_name_from_python_content(content),
+            Some("my-pkg".to_string())
+        );
+
+        let no_name = "from setuptools import setup\nsetup(version=\"1.0\")\n";
+        assert_eq!(extract_name_from_python_content(no_name), None);
+    }
+}
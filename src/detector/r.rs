@@ -0,0 +1,73 @@
+//! R projects: an renv lockfile with its project library.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, artifact};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects R projects using renv.
+///
+/// # Detection Criteria
+///
+/// 1. `renv.lock` file exists in directory
+/// 2. `renv/library/` directory exists
+///
+/// Only the package library is cleaned; `renv/activate.R` and the renv
+/// settings next to it are part of the project and stay. `renv::restore()`
+/// reinstalls the library from the lockfile.
+///
+/// The project name is the `Package` field of the `DESCRIPTION` file, if the
+/// project is a package.
+#[derive(Debug)]
+pub struct RDetector;
+
+impl Detector for RDetector {
+    fn name(&self) -> &'static str {
+        "r"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::R
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["renv.lock", "DESCRIPTION"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let library = path.join("renv").join("library");
+
+        (path.join("renv.lock").is_file() && library.is_dir()).then(|| vec![artifact(library)])
+    }
+
+    /// Reads `Package: name` from `DESCRIPTION`.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        ctx.read_file(&path.join("DESCRIPTION"))
+            .and_then(|content| extract_package_field(&content))
+    }
+}
+
+/// The `Package` field of an R `DESCRIPTION` file.
+fn extract_package_field(content: &str) -> Option<String> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("Package:"))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_package_field() {
+        assert_eq!(
+            extract_package_field("Package: tidyplot\nVersion: 0.1.0\nTitle: Plots\n"),
+            Some("tidyplot".to_string())
+        );
+        assert_eq!(extract_package_field("Title: Analysis\n"), None);
+        assert_eq!(extract_package_field("Package:\n"), None);
+    }
+}
@@ -0,0 +1,64 @@
+//! Ruby projects: a `Gemfile` with installed bundles.
+
+use std::{fs, path::Path};
+
+use super::{DetectContext, Detector, existing_artifacts, extract_quoted_value};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Ruby projects.
+///
+/// # Detection Criteria
+///
+/// 1. `Gemfile` file exists in directory
+/// 2. At least one of `.bundle/` or `vendor/bundle/` directories exists
+///
+/// Both directories are cleaned when present. The project name is read from
+/// the `.gemspec` file.
+#[derive(Debug)]
+pub struct RubyDetector;
+
+impl Detector for RubyDetector {
+    fn name(&self) -> &'static str {
+        "ruby"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Ruby
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["Gemfile", "*.gemspec"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !path.join("Gemfile").exists() {
+            return None;
+        }
+
+        existing_artifacts([path.join(".bundle"), path.join("vendor").join("bundle")])
+    }
+
+    /// Parses the `spec.name` or `s.name` assignment of a `.gemspec` file.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        let entries = fs::read_dir(path).ok()?;
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            if entry_path.is_file()
+                && entry_path.extension().and_then(|e| e.to_str()) == Some("gemspec")
+                && let Some(content) = ctx.read_file(&entry_path)
+            {
+                for line in content.lines() {
+                    let trimmed = line.trim();
+                    if trimmed.contains(".name")
+                        && trimmed.contains('=')
+                        && let Some(name) = extract_quoted_value(trimmed)
+                    {
+                        return Some(name);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
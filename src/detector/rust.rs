@@ -0,0 +1,350 @@
+//! Rust projects: `Cargo.toml` with a `target/` directory.
+//!
+//! Cross-compiling (with `cargo build --target` or `cross`) puts each target
+//! triple's build under `target/<triple>/`, and some setups, such as cargo
+//! xtask scripts, use extra target directories named `target-*/` next to
+//! `target/`. Those are collected per triple, so each one can be judged and
+//! cleaned on its own.
+//!
+//! With `--rust-subdirs`, only some well-known subdirectories of the target
+//! directories are cleaned instead, such as the incremental compilation
+//! caches that make up much of their size.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use super::{DetectContext, Detector, artifact, extract_quoted_value};
+use crate::config::RustSubdir;
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// How deep below a target directory its well-known subdirectories can be:
+/// `<triple>/<profile>/incremental/` is three levels down.
+const SUBDIR_DEPTH: usize = 3;
+
+/// Detects Rust projects.
+///
+/// # Detection Criteria
+///
+/// 1. `Cargo.toml` file exists in directory
+/// 2. `target/` subdirectory (or a sibling `target-*/` Cargo target
+///    directory) exists in directory, or, with `cargo_caches` enabled, one of
+///    the project-local caches (`.cargo/registry/`, `.cargo/git/` or
+///    `.sccache/`)
+/// 3. The directory is not a member of a Cargo workspace, whose artifacts
+///    are managed by the workspace root
+///
+/// With `rust_subdirs` set, the artifacts are the selected subdirectories of
+/// the target directories (see [`target_subdirs`]), and at least one of them
+/// or a project-local cache must exist.
+///
+/// The project name is the `name` field of `Cargo.toml`.
+#[derive(Debug)]
+pub struct RustDetector;
+
+impl Detector for RustDetector {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Rust
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["Cargo.toml"]
+    }
+
+    fn matches(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !path.join("Cargo.toml").exists() {
+            return None;
+        }
+
+        let target_dirs: Vec<PathBuf> = if ctx.options.rust_subdirs.is_empty() {
+            target_dirs(path)
+                .iter()
+                .flat_map(|dir| split_by_target_triple(dir))
+                .collect()
+        } else {
+            target_dirs(path)
+                .iter()
+                .flat_map(|dir| target_subdirs(dir, &ctx.options.rust_subdirs))
+                .map(|(_, subdir)| subdir)
+                .collect()
+        };
+        let local_caches = if ctx.options.cargo_caches {
+            local_caches(path)
+        } else {
+            Vec::new()
+        };
+
+        if target_dirs.is_empty() && local_caches.is_empty() {
+            return None;
+        }
+
+        // Skip workspace members — their artifacts are managed by the workspace root.
+        if is_inside_cargo_workspace(path) {
+            return None;
+        }
+
+        Some(
+            target_dirs
+                .into_iter()
+                .chain(local_caches)
+                .map(artifact)
+                .collect(),
+        )
+    }
+
+    /// Uses a line-by-line approach rather than a full TOML parser for
+    /// simplicity and performance: the first `name = "..."` line wins.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        let content = ctx.read_file(&path.join("Cargo.toml"))?;
+        parse_toml_name_field(&content)
+    }
+}
+
+/// The Cargo target directories of the project in `path`: `target/` and any
+/// sibling `target-*/` directory that Cargo wrote to.
+fn target_dirs(path: &Path) -> Vec<PathBuf> {
+    let target_dir = path.join("target");
+    let mut siblings: Vec<PathBuf> = fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| {
+            dir.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("target-"))
+                && is_cargo_target_dir(dir)
+        })
+        .collect();
+    siblings.sort();
+
+    Some(target_dir)
+        .filter(|dir| dir.exists())
+        .into_iter()
+        .chain(siblings)
+        .collect()
+}
+
+/// Return true if `dir` looks like a directory Cargo builds into.
+///
+/// Cargo marks its target directories with `CACHEDIR.TAG` and
+/// `.rustc_info.json`; older builds are recognized by a profile directory.
+fn is_cargo_target_dir(dir: &Path) -> bool {
+    ["CACHEDIR.TAG", ".rustc_info.json", "debug", "release"]
+        .iter()
+        .any(|name| dir.join(name).exists())
+}
+
+/// The parts of `target_dir` to clean, one per target triple.
+///
+/// If nothing was cross-compiled, that is the whole directory. Otherwise each
+/// `<triple>/` subdirectory is listed on its own, next to the directories of
+/// the host build (`debug/`, `release/`, `doc/`, ...).
+fn split_by_target_triple(target_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(target_dir) else {
+        return vec![target_dir.to_path_buf()];
+    };
+
+    let mut children: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect();
+
+    if !children.iter().any(|dir| is_target_triple_dir(dir)) {
+        return vec![target_dir.to_path_buf()];
+    }
+
+    children.sort();
+    children
+}
+
+/// Return true if `dir` holds the build of one target triple, like
+/// `target/aarch64-unknown-linux-gnu/`.
+///
+/// Triples always contain a `-`, and their directory holds profile
+/// directories (`debug/`, `release/`, ...), each with Cargo's `deps/` or
+/// `.fingerprint/`. A host profile directory is one level shallower.
+fn is_target_triple_dir(dir: &Path) -> bool {
+    let has_triple_name = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains('-'));
+
+    has_triple_name
+        && fs::read_dir(dir).is_ok_and(|entries| {
+            entries.flatten().any(|entry| {
+                let profile = entry.path();
+                profile.join("deps").is_dir() || profile.join(".fingerprint").is_dir()
+            })
+        })
+}
+
+/// The subdirectories of the kinds `kinds` in the Cargo target directory
+/// `dir` (or in a part of one, such as `target/<triple>/`), sorted by path.
+///
+/// Profile subdirectories are found for the host build
+/// (`debug/incremental/`) and for each target triple
+/// (`<triple>/release/deps/`); generated documentation is `doc/` of either.
+/// The walk doesn't descend into a subdirectory it found, so the `deps/` of
+/// a build script's output isn't reported on its own.
+pub(crate) fn target_subdirs(dir: &Path, kinds: &[RustSubdir]) -> Vec<(RustSubdir, PathBuf)> {
+    let kind_of = |name: &str| kinds.iter().copied().find(|kind| kind.dir_name() == name);
+
+    let mut found = Vec::new();
+    let mut walk = WalkDir::new(dir).max_depth(SUBDIR_DEPTH).into_iter();
+    while let Some(entry) = walk.next() {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if let Some(kind) = entry.file_name().to_str().and_then(kind_of) {
+            found.push((kind, entry.into_path()));
+            walk.skip_current_dir();
+        }
+    }
+
+    found.sort_by(|(_, a), (_, b)| a.cmp(b));
+    found
+}
+
+/// Caches kept inside a Rust project rather than in the user's home.
+///
+/// Builds that point `CARGO_HOME` at the project (common in CI and
+/// container setups) leave a `.cargo/registry/` and `.cargo/git/` next to
+/// `.cargo/config.toml`, and `SCCACHE_DIR=.sccache` leaves a compiler
+/// cache. Only the directories that exist are returned.
+fn local_caches(path: &Path) -> Vec<PathBuf> {
+    let cargo_home = path.join(".cargo");
+    [
+        cargo_home.join("registry"),
+        cargo_home.join("git"),
+        path.join(".sccache"),
+    ]
+    .into_iter()
+    .filter(|dir| dir.is_dir())
+    .collect()
+}
+
+/// Return true if the given `Cargo.toml` declares a `[workspace]` section.
+fn is_cargo_workspace_root(cargo_toml: &Path) -> bool {
+    fs::read_to_string(cargo_toml)
+        .is_ok_and(|content| content.lines().any(|line| line.trim() == "[workspace]"))
+}
+
+/// Return true if `path` is inside a Rust workspace (an ancestor directory
+/// contains a `Cargo.toml` that declares `[workspace]`).
+fn is_inside_cargo_workspace(path: &Path) -> bool {
+    path.ancestors()
+        .skip(1) // skip `path` itself
+        .any(|ancestor| {
+            let cargo_toml = ancestor.join("Cargo.toml");
+            cargo_toml.exists() && is_cargo_workspace_root(&cargo_toml)
+        })
+}
+
+/// Parse the name field from TOML content.
+fn parse_toml_name_field(content: &str) -> Option<String> {
+    content
+        .lines()
+        .find_map(|line| extract_name_from_line(line.trim()))
+}
+
+/// Extract the name from a single TOML line if it contains a name field.
+fn extract_name_from_line(line: &str) -> Option<String> {
+    if !is_name_line(line) {
+        return None;
+    }
+
+    extract_quoted_value(line)
+}
+
+/// Check if a line contains a name field assignment.
+fn is_name_line(line: &str) -> bool {
+    line.starts_with("name") && line.contains('=')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::ScanOptions, error::ScanErrors};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_name_line() {
+        assert!(is_name_line("name = \"test\""));
+        assert!(is_name_line("name=\"test\""));
+        assert!(!is_name_line("version = \"1.0\""));
+        assert!(!is_name_line("# name = \"commented\""));
+        assert!(!is_name_line("name: \"yaml style\""));
+    }
+
+    #[test]
+    fn test_parse_toml_name_field() {
+        let content = "[package]\nname = \"test-project\"\nversion = \"0.1.0\"\n";
+        assert_eq!(
+            parse_toml_name_field(content),
+            Some("test-project".to_string())
+        );
+
+        let no_name = "[package]\nversion = \"0.1.0\"\n";
+        assert_eq!(parse_toml_name_field(no_name), None);
+
+        let empty = "";
+        assert_eq!(parse_toml_name_field(empty), None);
+    }
+
+    #[test]
+    fn test_is_cargo_workspace_root() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let cargo_toml = tmp.path().join("Cargo.toml");
+
+        // A workspace root must contain a bare `[workspace]` section header.
+        fs::write(
+            &cargo_toml,
+            "[workspace]\nmembers = [\"crate-a\", \"crate-b\"]\n",
+        )?;
+        assert!(is_cargo_workspace_root(&cargo_toml));
+
+        // A regular package Cargo.toml is not a workspace root.
+        fs::write(
+            &cargo_toml,
+            "[package]\nname = \"my-crate\"\nversion = \"0.1.0\"\n",
+        )?;
+        assert!(!is_cargo_workspace_root(&cargo_toml));
+
+        // A non-existent file returns false.
+        assert!(!is_cargo_workspace_root(Path::new(
+            "/nonexistent/Cargo.toml"
+        )));
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_without_scanning() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path();
+        fs::write(path.join("Cargo.toml"), "[package]\nname = \"direct\"\n")?;
+
+        let options = ScanOptions::default();
+        let errors = ScanErrors::default();
+        let ctx = DetectContext::new(&options, &errors);
+        assert!(RustDetector.detect(path, &ctx).is_none());
+
+        fs::create_dir(path.join("target"))?;
+        let project = RustDetector.detect(path, &ctx);
+        assert_eq!(
+            project.as_ref().and_then(|p| p.name.as_deref()),
+            Some("direct")
+        );
+        assert_eq!(project.map(|p| p.build_arts.len()), Some(1));
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+//! Scala projects built with sbt: `build.sbt` with its `target/` directories.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, existing_artifacts};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects sbt-based Scala projects.
+///
+/// # Detection Criteria
+///
+/// 1. `build.sbt` file exists in directory
+/// 2. At least one of `target/`, `project/target/` or `project/project/target/` exists
+///
+/// Besides the project's own `target/`, sbt compiles the build definition in
+/// `project/` into `project/target/` (and its meta-build into
+/// `project/project/target/`); all of them are cleaned. The dependency cache
+/// in `~/.ivy2` (or Coursier's) is shared by every project and left alone.
+///
+/// The project name is the `name := "..."` setting of `build.sbt`, also
+/// when it's scoped (`ThisBuild / name`) or inside `.settings(...)`.
+#[derive(Debug)]
+pub struct ScalaDetector;
+
+impl Detector for ScalaDetector {
+    fn name(&self) -> &'static str {
+        "scala"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Scala
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["build.sbt"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !path.join("build.sbt").exists() {
+            return None;
+        }
+
+        let meta = path.join("project");
+        existing_artifacts([
+            path.join("target"),
+            meta.join("target"),
+            meta.join("project").join("target"),
+        ])
+    }
+
+    /// Reads the `name := "..."` setting of `build.sbt`.
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        ctx.read_file(&path.join("build.sbt"))
+            .and_then(|content| extract_sbt_name(&content))
+    }
+}
+
+/// The value of the first `name := "..."` setting in a `build.sbt`.
+///
+/// Other settings ending in `name`, such as `moduleName` or
+/// `organizationName`, are skipped.
+fn extract_sbt_name(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        line.match_indices("name").find_map(|(start, _)| {
+            let standalone = line[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+            if !standalone {
+                return None;
+            }
+
+            let value = line[start + "name".len()..]
+                .trim_start()
+                .strip_prefix(":=")?
+                .trim_start()
+                .strip_prefix('"')?;
+            let end = value.find('"')?;
+            Some(value[..end].to_string())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_sbt_name() {
+        assert_eq!(
+            extract_sbt_name("name := \"my-scala-app\"\nscalaVersion := \"3.3.0\"\n"),
+            Some("my-scala-app".to_string())
+        );
+        assert_eq!(
+            extract_sbt_name("ThisBuild / name := \"scoped\"\n"),
+            Some("scoped".to_string())
+        );
+        assert_eq!(
+            extract_sbt_name(
+                "lazy val root = (project in file(\".\"))\n  .settings(name := \"root\", version := \"0.1\")\n"
+            ),
+            Some("root".to_string())
+        );
+        assert_eq!(
+            extract_sbt_name("organizationName := \"Acme\"\nmoduleName := \"core\"\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Swift Package Manager projects: `Package.swift` with a `.build/` directory.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, artifact, extract_quoted_value};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Swift projects.
+///
+/// # Detection Criteria
+///
+/// 1. `Package.swift` file exists
+/// 2. `.build/` directory exists
+///
+/// The project name is the `name:` given to the `Package(` initializer.
+#[derive(Debug)]
+pub struct SwiftDetector;
+
+impl Detector for SwiftDetector {
+    fn name(&self) -> &'static str {
+        "swift"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Swift
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["Package.swift"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        let build_dir = path.join(".build");
+
+        (path.join("Package.swift").exists() && build_dir.exists())
+            .then(|| vec![artifact(build_dir)])
+    }
+
+    fn extract_name(&self, path: &Path, ctx: &DetectContext<'_>) -> Option<String> {
+        let content = ctx.read_file(&path.join("Package.swift"))?;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.contains("name:") {
+                return extract_quoted_value(trimmed);
+            }
+        }
+
+        None
+    }
+}
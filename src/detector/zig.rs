@@ -0,0 +1,45 @@
+//! Zig projects: `build.zig` with `zig-cache/` or `zig-out/`.
+
+use std::path::Path;
+
+use super::{DetectContext, Detector, existing_artifacts};
+use crate::project::{BuildArtifacts, ProjectType};
+
+/// Detects Zig projects.
+///
+/// # Detection Criteria
+///
+/// 1. `build.zig` file exists in directory
+/// 2. At least one of `zig-cache/` or `zig-out/` exists
+///
+/// Both directories are cleaned when present. `build.zig` has no standard
+/// name field, so the project is named by the fallbacks of
+/// [`Detector::detect`].
+#[derive(Debug)]
+pub struct ZigDetector;
+
+impl Detector for ZigDetector {
+    fn name(&self) -> &'static str {
+        "zig"
+    }
+
+    fn kind(&self) -> ProjectType {
+        ProjectType::Zig
+    }
+
+    fn markers(&self) -> &'static [&'static str] {
+        &["build.zig"]
+    }
+
+    fn matches(&self, path: &Path, _ctx: &DetectContext<'_>) -> Option<Vec<BuildArtifacts>> {
+        if !path.join("build.zig").exists() {
+            return None;
+        }
+
+        existing_artifacts([path.join("zig-cache"), path.join("zig-out")])
+    }
+
+    fn extract_name(&self, _path: &Path, _ctx: &DetectContext<'_>) -> Option<String> {
+        None
+    }
+}
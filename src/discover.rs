@@ -0,0 +1,227 @@
+//! Discovery of likely code roots for first-time setup.
+//!
+//! `clean-dev-dirs discover` looks for the directories a user keeps their
+//! checkouts in, so they can be written to the `dirs` config key instead of
+//! being typed in by hand. Two kinds of directories are suggested:
+//!
+//! - the usual places for code directly in the home directory
+//!   ([`COMMON_ROOTS`]), when they contain at least one checkout
+//! - any other directory in the home directory that holds at least
+//!   [`MIN_CHECKOUTS`] version-controlled checkouts
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use toml_edit::{Array, DocumentMut, value};
+
+use crate::config::edit::keep_below_leading_comments;
+use crate::error::{CleanDevDirsError, Result, ResultExt};
+
+/// Directory names in the home directory that commonly hold code.
+pub const COMMON_ROOTS: &[&str] = &["code", "Projects", "src", "dev", "work", "repos"];
+
+/// Minimum number of checkouts for a directory outside [`COMMON_ROOTS`] to be suggested.
+pub const MIN_CHECKOUTS: usize = 3;
+
+/// How deep below a candidate root checkouts are looked for.
+const CHECKOUT_DEPTH: usize = 2;
+
+/// Marker directories (or files, for worktrees) of supported version control systems.
+const VCS_MARKERS: &[&str] = &[".git", ".hg", ".svn", ".jj"];
+
+/// A directory suggested as a scan root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    /// The suggested directory
+    pub path: PathBuf,
+
+    /// Number of checkouts found in it, up to two levels deep
+    pub checkouts: usize,
+}
+
+/// Find likely code roots among the direct children of `home`.
+///
+/// Candidates are sorted by the number of checkouts, most first.
+#[must_use]
+pub fn discover_roots(home: &Path) -> Vec<Candidate> {
+    let Ok(entries) = fs::read_dir(home) else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<Candidate> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !path.is_symlink() && !is_hidden(path))
+        .filter_map(|path| {
+            let common = path
+                .file_name()
+                .is_some_and(|name| COMMON_ROOTS.iter().any(|root| name == *root));
+            let checkouts = count_checkouts(&path, CHECKOUT_DEPTH);
+            let min = if common { 1 } else { MIN_CHECKOUTS };
+            (checkouts >= min).then_some(Candidate { path, checkouts })
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| b.checkouts.cmp(&a.checkouts).then(a.path.cmp(&b.path)));
+    candidates
+}
+
+/// Count the checkouts below `dir`, looking at most `depth` levels deep.
+///
+/// Checkouts aren't searched for nested checkouts, so submodules and
+/// vendored repositories don't count twice.
+fn count_checkouts(dir: &Path, depth: usize) -> usize {
+    if depth == 0 {
+        return 0;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !path.is_symlink() && !is_hidden(path))
+        .map(|path| {
+            if is_checkout(&path) {
+                1
+            } else {
+                count_checkouts(&path, depth - 1)
+            }
+        })
+        .sum()
+}
+
+/// Return true if `path` is the root of a version-controlled checkout.
+fn is_checkout(path: &Path) -> bool {
+    VCS_MARKERS.iter().any(|marker| path.join(marker).exists())
+}
+
+/// Return true for dot-directories such as `.cache`.
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+/// Write `path` the way it would be typed in the config, with `~` for `home`.
+#[must_use]
+pub fn display_path(path: &Path, home: &Path) -> String {
+    path.strip_prefix(home).map_or_else(
+        |_| path.display().to_string(),
+        |rest| format!("~/{}", rest.display()),
+    )
+}
+
+/// Add a top-level `dirs` key listing `dirs` to the TOML config `content`.
+///
+/// The key is added with `toml_edit` after the other top-level keys, before
+/// the first table; comments and formatting of the rest of the file are kept.
+///
+/// # Errors
+///
+/// Returns an error if `content` can't be parsed, or already sets `dirs`
+/// or `dir`.
+pub fn config_with_dirs(content: &str, dirs: &[String]) -> Result<String> {
+    let mut doc: DocumentMut = content.parse().context(CleanDevDirsError::config(
+        "The config file is not valid TOML",
+    ))?;
+    if doc.contains_key("dirs") || doc.contains_key("dir") {
+        return Err(CleanDevDirsError::config(
+            "The config file already sets the directories to scan",
+        ));
+    }
+
+    doc.insert("dirs", value(dirs.iter().collect::<Array>()));
+    keep_below_leading_comments(doc.as_table_mut(), "dirs");
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn checkout(path: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(path.join(".git"))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_discover_roots() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let home = tmp.path();
+
+        // A common root with a single checkout, one nested a level deeper
+        checkout(&home.join("code/api"))?;
+        checkout(&home.join("code/clients/web"))?;
+        // Submodules inside a checkout don't count twice
+        checkout(&home.join("code/api/vendor/lib"))?;
+        // Another directory with enough checkouts
+        for name in ["a", "b", "c"] {
+            checkout(&home.join("gitlab").join(name))?;
+        }
+        // Not enough checkouts, an empty common root, and a hidden directory
+        checkout(&home.join("Downloads/tool"))?;
+        fs::create_dir(home.join("src"))?;
+        for name in ["a", "b", "c"] {
+            checkout(&home.join(".cache").join(name))?;
+        }
+
+        assert_eq!(
+            discover_roots(home),
+            vec![
+                Candidate {
+                    path: home.join("gitlab"),
+                    checkouts: 3,
+                },
+                Candidate {
+                    path: home.join("code"),
+                    checkouts: 2,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_path() {
+        let home = Path::new("/home/me");
+        assert_eq!(display_path(&home.join("code"), home), "~/code");
+        assert_eq!(display_path(Path::new("/srv/code"), home), "/srv/code");
+    }
+
+    #[test]
+    fn test_config_with_dirs_inserts_before_first_table() -> anyhow::Result<()> {
+        let content = "# my config\nproject_type = \"rust\"\n\n[filtering]\nkeep_days = 7\n";
+        let dirs = vec!["~/code".to_string(), "~/work".to_string()];
+
+        assert_eq!(
+            config_with_dirs(content, &dirs)?,
+            "# my config\nproject_type = \"rust\"\ndirs = [\"~/code\", \"~/work\"]\n\n[filtering]\nkeep_days = 7\n"
+        );
+        // Only tables, like the template: the opening comments stay on top
+        assert_eq!(
+            config_with_dirs("# my config\n\n[filtering]\nkeep_days = 7\n", &dirs)?,
+            "# my config\n\ndirs = [\"~/code\", \"~/work\"]\n\n[filtering]\nkeep_days = 7\n"
+        );
+        assert_eq!(
+            config_with_dirs("", &dirs)?,
+            "dirs = [\"~/code\", \"~/work\"]\n"
+        );
+        assert_eq!(
+            config_with_dirs("units = \"binary\"", &dirs)?,
+            "units = \"binary\"\ndirs = [\"~/code\", \"~/work\"]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_with_dirs_refuses_existing_dirs() {
+        let dirs = vec!["~/code".to_string()];
+        assert!(config_with_dirs("dirs = [\"~/src\"]\n", &dirs).is_err());
+        assert!(config_with_dirs("dir = \"~/src\"\n", &dirs).is_err());
+        assert!(config_with_dirs("not toml", &dirs).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+//! Reclaimable Docker data: dangling images, stopped containers and build cache.
+//!
+//! With `--docker`, these are reported next to the build directories found on
+//! disk and pruned after confirmation, like `docker system prune` would. The
+//! `docker` command-line client is used, so the daemon it is configured for
+//! (`DOCKER_HOST`, the current context) is the one inspected. Sizes are parsed
+//! from Docker's rounded, human-readable output and are therefore approximate.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    process::Command,
+};
+
+use crate::{
+    error::{CleanDevDirsError, Result, ResultExt},
+    utils::parse_size,
+};
+
+/// A kind of Docker data that can be pruned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DockerResource {
+    /// Untagged images no longer referenced by any tag, typically left behind by rebuilds
+    DanglingImages,
+
+    /// Containers that have exited or were never started
+    StoppedContainers,
+
+    /// Build cache not used by any running build
+    ///
+    /// Only the dangling part, which no image refers to, is pruned, as
+    /// `docker builder prune` does without `--all`: the cache of the images
+    /// still around keeps their rebuilds fast.
+    BuildCache,
+}
+
+impl DockerResource {
+    /// Every resource, in the order they are reported and pruned.
+    ///
+    /// Containers come before images, since removing a stopped container can
+    /// leave its image dangling.
+    pub const ALL: [Self; 3] = [
+        Self::StoppedContainers,
+        Self::DanglingImages,
+        Self::BuildCache,
+    ];
+
+    /// Arguments of the `docker` command that prunes this resource.
+    const fn prune_args(self) -> &'static [&'static str] {
+        match self {
+            Self::DanglingImages => &["image", "prune", "--force"],
+            Self::StoppedContainers => &["container", "prune", "--force"],
+            Self::BuildCache => &["builder", "prune", "--force"],
+        }
+    }
+}
+
+impl Display for DockerResource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::DanglingImages => "dangling images",
+            Self::StoppedContainers => "stopped containers",
+            Self::BuildCache => "build cache",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How much of a [`DockerResource`] there is to prune.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DockerUsage {
+    /// The kind of data
+    pub resource: DockerResource,
+
+    /// Number of images, containers or cache entries
+    pub count: usize,
+
+    /// Reclaimable space in bytes
+    pub size: u64,
+}
+
+/// Access to the Docker daemon through the `docker` command-line client.
+#[derive(Clone, Debug)]
+pub struct Docker {
+    /// Docker client executable
+    bin: String,
+}
+
+impl Default for Docker {
+    fn default() -> Self {
+        Self {
+            bin: "docker".to_string(),
+        }
+    }
+}
+
+impl Docker {
+    /// Measure the reclaimable data of every [`DockerResource`].
+    ///
+    /// Resources with nothing to reclaim are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `docker` client can't be run, or fails, for
+    /// example because the daemon isn't running.
+    pub fn usage(&self) -> Result<Vec<DockerUsage>> {
+        let mut usage = Vec::new();
+
+        for resource in DockerResource::ALL {
+            let (count, size) = match resource {
+                DockerResource::DanglingImages => sum_sizes(&self.run(&[
+                    "image",
+                    "ls",
+                    "--filter",
+                    "dangling=true",
+                    "--format",
+                    "{{.Size}}",
+                ])?),
+                // The size of a container is its writable layer, shown
+                // before the size of the image it's based on
+                DockerResource::StoppedContainers => sum_sizes(&self.run(&[
+                    "container",
+                    "ls",
+                    "--all",
+                    "--filter",
+                    "status=exited",
+                    "--filter",
+                    "status=created",
+                    "--filter",
+                    "status=dead",
+                    "--format",
+                    "{{.Size}}",
+                ])?),
+                DockerResource::BuildCache => build_cache_usage(&self.run(&[
+                    "system",
+                    "df",
+                    "--format",
+                    "{{.Type}}\t{{.TotalCount}}\t{{.Reclaimable}}",
+                ])?),
+            };
+
+            if count > 0 {
+                usage.push(DockerUsage {
+                    resource,
+                    count,
+                    size,
+                });
+            }
+        }
+
+        Ok(usage)
+    }
+
+    /// Prune `resource`, returning the space Docker reports as reclaimed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `docker` client can't be run or fails.
+    pub fn prune(&self, resource: DockerResource) -> Result<u64> {
+        let output = self.run(resource.prune_args())?;
+        Ok(reclaimed_space(&output))
+    }
+
+    /// Run the Docker client with `args` and return its standard output.
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new(&self.bin)
+            .args(args)
+            .output()
+            .with_context(|| {
+                CleanDevDirsError::cleanup(format!(
+                    "Failed to run {} (is Docker installed?)",
+                    self.bin
+                ))
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CleanDevDirsError::cleanup(format!(
+                "{} {} failed: {}",
+                self.bin,
+                args.join(" "),
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Count the lines of a `--format {{.Size}}` listing and add up their sizes.
+///
+/// Only the first word of each line is used, which drops the image size that
+/// container listings append as `(virtual 1.2GB)`.
+fn sum_sizes(listing: &str) -> (usize, u64) {
+    listing
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .fold((0, 0), |(count, size), item| {
+            (count + 1, size + parse_docker_size(item).unwrap_or(0))
+        })
+}
+
+/// Find the build cache row of a `docker system df` listing.
+fn build_cache_usage(listing: &str) -> (usize, u64) {
+    listing
+        .lines()
+        .find_map(|line| {
+            let mut fields = line.split('\t');
+            if fields.next()? != "Build Cache" {
+                return None;
+            }
+            let count = fields.next()?.trim().parse().ok()?;
+            // Reclaimable space may be followed by a percentage, as in "1.2GB (50%)"
+            let size = parse_docker_size(fields.next()?.split_whitespace().next()?)?;
+            Some((count, size))
+        })
+        .unwrap_or((0, 0))
+}
+
+/// Read the reclaimed space from the output of a prune command.
+///
+/// Image and container pruning end with `Total reclaimed space: 1.2GB`,
+/// build cache pruning with `Total: 1.2GB`. Anything else counts as nothing.
+fn reclaimed_space(output: &str) -> u64 {
+    output
+        .lines()
+        .rev()
+        .find_map(|line| {
+            line.strip_prefix("Total reclaimed space:")
+                .or_else(|| line.strip_prefix("Total:"))
+        })
+        .and_then(|size| parse_docker_size(size.trim()))
+        .unwrap_or(0)
+}
+
+/// Parse a size as printed by Docker, such as `0B`, `12.5kB`, `1.2GB` or `2TB`.
+fn parse_docker_size(size: &str) -> Option<u64> {
+    if let Some(terabytes) = size.strip_suffix("TB") {
+        return parse_size(&format!("{terabytes}GB"))
+            .ok()?
+            .checked_mul(1000);
+    }
+
+    // Plain bytes carry a "B" unit that parse_size doesn't accept
+    let size = size
+        .strip_suffix('B')
+        .filter(|number| number.ends_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(size);
+    parse_size(size).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_docker_size() {
+        assert_eq!(parse_docker_size("0B"), Some(0));
+        assert_eq!(parse_docker_size("512B"), Some(512));
+        assert_eq!(parse_docker_size("12.5kB"), Some(12_500));
+        assert_eq!(parse_docker_size("1.2GB"), Some(1_200_000_000));
+        assert_eq!(parse_docker_size("2TB"), Some(2_000_000_000_000));
+        assert_eq!(parse_docker_size("N/A"), None);
+    }
+
+    #[test]
+    fn test_sum_sizes() {
+        assert_eq!(sum_sizes(""), (0, 0));
+        assert_eq!(
+            sum_sizes("1.5kB (virtual 1.2GB)\n0B (virtual 80MB)\n"),
+            (2, 1_500)
+        );
+    }
+
+    #[test]
+    fn test_build_cache_usage() {
+        let listing = "Images\t5\t1.2GB (50%)\nContainers\t2\t0B (0%)\n\
+                       Local Volumes\t1\t0B\nBuild Cache\t42\t3.5GB\n";
+        assert_eq!(build_cache_usage(listing), (42, 3_500_000_000));
+        assert_eq!(build_cache_usage("Images\t5\t1.2GB (50%)\n"), (0, 0));
+    }
+
+    #[test]
+    fn test_reclaimed_space() {
+        assert_eq!(
+            reclaimed_space(
+                "Deleted Images:\ndeleted: sha256:abc\n\nTotal reclaimed space: 1.2GB\n"
+            ),
+            1_200_000_000
+        );
+        assert_eq!(
+            reclaimed_space("ID\tRECLAIMABLE\tSIZE\nabc\ttrue\t2MB\nTotal:\t2MB\n"),
+            2_000_000
+        );
+        assert_eq!(reclaimed_space(""), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_usage_and_prune_through_client() -> anyhow::Result<()> {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let tmp = tempfile::TempDir::new()?;
+        // Stands in for the docker client, answering the commands used above
+        let bin = tmp.path().join("docker");
+        fs::write(
+            &bin,
+            r#"#!/bin/sh
+case "$1 $2" in
+  'image ls') printf '100MB\n50MB\n' ;;
+  'container ls') ;;
+  'system df') printf 'Images\t3\t150MB (40%%)\nBuild Cache\t7\t1GB\n' ;;
+  'image prune') printf 'Total reclaimed space: 150MB\n' ;;
+  *) echo "unexpected: $*" >&2; exit 1 ;;
+esac
+"#,
+        )?;
+        fs::set_permissions(&bin, fs::Permissions::from_mode(0o755))?;
+        let docker = Docker {
+            bin: bin.display().to_string(),
+        };
+
+        assert_eq!(
+            docker.usage()?,
+            vec![
+                DockerUsage {
+                    resource: DockerResource::DanglingImages,
+                    count: 2,
+                    size: 150_000_000,
+                },
+                DockerUsage {
+                    resource: DockerResource::BuildCache,
+                    count: 7,
+                    size: 1_000_000_000,
+                },
+            ]
+        );
+        assert_eq!(docker.prune(DockerResource::DanglingImages)?, 150_000_000);
+
+        let err = docker.prune(DockerResource::BuildCache).err();
+        assert!(err.is_some_and(|e| e.to_string().contains("unexpected: builder prune")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_client_is_an_error() {
+        let docker = Docker {
+            bin: "clean-dev-dirs-no-such-docker".to_string(),
+        };
+        let err = docker.usage().err();
+        assert!(err.is_some_and(|e| e.to_string().contains("is Docker installed?")));
+    }
+}
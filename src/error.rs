@@ -0,0 +1,551 @@
+//! Error type of the library API.
+//!
+//! Fallible functions of the library return [`CleanDevDirsError`], which
+//! tells which stage failed (scanning, configuration, cleanup, the files kept
+//! in the state directory, or output) and keeps the
+//! underlying error (an I/O error, a TOML parse error, ...) as its
+//! [`source`](Error::source). The binary turns it into an `anyhow::Error`.
+//!
+//! The `Display` output is the context only, such as `Failed to read config
+//! file at ~/.config/clean-dev-dirs/config.toml`. The alternate form (`{:#}`)
+//! appends the chain of sources, separated by `: `, like `anyhow` does.
+//!
+//! Errors met while scanning don't stop the scan. They are collected in
+//! [`ScanErrors`], which counts all of them but keeps only a bounded number,
+//! since walking millions of directories can run into as many errors.
+
+use std::{
+    error::Error,
+    fmt, io,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// Result type of the library API.
+pub type Result<T, E = CleanDevDirsError> = std::result::Result<T, E>;
+
+/// The underlying error of a [`CleanDevDirsError`].
+pub type BoxedError = Box<dyn Error + Send + Sync + 'static>;
+
+/// An error of the library API, by the stage that failed.
+#[derive(Debug)]
+pub enum CleanDevDirsError {
+    /// A directory or a project in it couldn't be scanned
+    Scan {
+        /// What was being scanned, e.g. `Error reading /work/app/Cargo.toml`
+        context: String,
+
+        /// The error that caused it, if any
+        source: Option<BoxedError>,
+    },
+
+    /// A configuration file or setting is unreadable or invalid
+    Config {
+        /// Which file or setting is affected, and how
+        context: String,
+
+        /// The error that caused it, if any
+        source: Option<BoxedError>,
+    },
+
+    /// A build directory couldn't be cleaned
+    Cleanup {
+        /// Which step of the cleanup failed
+        context: String,
+
+        /// The error that caused it, if any
+        source: Option<BoxedError>,
+    },
+
+    /// A file kept between runs, such as the run history, a plan, the
+    /// journal or the lock, couldn't be read or written
+    State {
+        /// Which file is affected, and how
+        context: String,
+
+        /// The error that caused it, if any
+        source: Option<BoxedError>,
+    },
+
+    /// Output couldn't be rendered or written, or a prompt couldn't be shown
+    Output {
+        /// What was being written or shown
+        context: String,
+
+        /// The error that caused it, if any
+        source: Option<BoxedError>,
+    },
+}
+
+impl CleanDevDirsError {
+    /// A scan error without an underlying error.
+    #[must_use]
+    pub fn scan(context: impl Into<String>) -> Self {
+        Self::Scan {
+            context: context.into(),
+            source: None,
+        }
+    }
+
+    /// A configuration error without an underlying error.
+    #[must_use]
+    pub fn config(context: impl Into<String>) -> Self {
+        Self::Config {
+            context: context.into(),
+            source: None,
+        }
+    }
+
+    /// A cleanup error without an underlying error.
+    #[must_use]
+    pub fn cleanup(context: impl Into<String>) -> Self {
+        Self::Cleanup {
+            context: context.into(),
+            source: None,
+        }
+    }
+
+    /// A state file error without an underlying error.
+    #[must_use]
+    pub fn state(context: impl Into<String>) -> Self {
+        Self::State {
+            context: context.into(),
+            source: None,
+        }
+    }
+
+    /// An output error without an underlying error.
+    #[must_use]
+    pub fn output(context: impl Into<String>) -> Self {
+        Self::Output {
+            context: context.into(),
+            source: None,
+        }
+    }
+
+    /// Attach the error that caused this one.
+    #[must_use]
+    pub fn with_source(mut self, error: impl Into<BoxedError>) -> Self {
+        match &mut self {
+            Self::Scan { source, .. }
+            | Self::Config { source, .. }
+            | Self::Cleanup { source, .. }
+            | Self::State { source, .. }
+            | Self::Output { source, .. } => {
+                *source = Some(error.into());
+            }
+        }
+        self
+    }
+
+    /// What failed, without the underlying error.
+    #[must_use]
+    pub fn context(&self) -> &str {
+        match self {
+            Self::Scan { context, .. }
+            | Self::Config { context, .. }
+            | Self::Cleanup { context, .. }
+            | Self::State { context, .. }
+            | Self::Output { context, .. } => context,
+        }
+    }
+
+    /// Whether the error was caused by missing permissions (`EACCES` or
+    /// `EPERM`, access denied on Windows), anywhere in its chain of sources.
+    #[must_use]
+    pub fn is_permission_denied(&self) -> bool {
+        self.has_io_error(|kind, _| kind == io::ErrorKind::PermissionDenied)
+    }
+
+    /// Whether the error is one that may go away when retried: an
+    /// interrupted call, a busy or locked file, or a directory that got a new
+    /// entry while it was being removed, anywhere in its chain of sources.
+    ///
+    /// On Windows, a file that an antivirus scanner or indexer holds open
+    /// can't be deleted until it lets go, which fails with access denied,
+    /// a sharing violation or a lock violation. These count as transient
+    /// whatever kind `std` maps them to; access denied on Unix doesn't.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        self.has_io_error(|kind, code| {
+            matches!(
+                kind,
+                io::ErrorKind::Interrupted
+                    | io::ErrorKind::WouldBlock
+                    | io::ErrorKind::ResourceBusy
+                    | io::ErrorKind::DirectoryNotEmpty
+            ) || is_windows_lock_error(code)
+        })
+    }
+
+    /// Whether an I/O error in the chain of sources, directly or inside a
+    /// trash error, matches `matches`, given its kind and OS error code.
+    fn has_io_error(&self, matches: impl Fn(io::ErrorKind, Option<i32>) -> bool) -> bool {
+        let mut source = self.source();
+        while let Some(error) = source {
+            let io_error = error.downcast_ref::<io::Error>().map_or_else(
+                || {
+                    error
+                        .downcast_ref::<trash::Error>()
+                        .and_then(trash_io_error)
+                },
+                |e| Some((e.kind(), e.raw_os_error())),
+            );
+            if io_error.is_some_and(|(kind, code)| matches(kind, code)) {
+                return true;
+            }
+            source = error.source();
+        }
+        false
+    }
+
+    /// Append `note` to the context, keeping the source.
+    #[must_use]
+    pub(crate) fn with_note(mut self, note: &str) -> Self {
+        match &mut self {
+            Self::Scan { context, .. }
+            | Self::Config { context, .. }
+            | Self::Cleanup { context, .. }
+            | Self::State { context, .. }
+            | Self::Output { context, .. } => {
+                context.push(' ');
+                context.push_str(note);
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for CleanDevDirsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.context())?;
+
+        if f.alternate() {
+            let mut source = self.source();
+            while let Some(error) = source {
+                write!(f, ": {error}")?;
+                source = error.source();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for CleanDevDirsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Scan { source, .. }
+            | Self::Config { source, .. }
+            | Self::Cleanup { source, .. }
+            | Self::State { source, .. }
+            | Self::Output { source, .. } => source
+                .as_deref()
+                .map(|error| error as &(dyn Error + 'static)),
+        }
+    }
+}
+
+/// Turn the error of a result into a [`CleanDevDirsError`] caused by it,
+/// like `anyhow::Context` does for `anyhow::Error`.
+pub(crate) trait ResultExt<T> {
+    /// Replace the error with `error`, keeping it as the source.
+    fn context(self, error: CleanDevDirsError) -> Result<T>;
+
+    /// Replace the error with the one `error` makes, keeping it as the
+    /// source. `error` is only called on failure.
+    fn with_context(self, error: impl FnOnce() -> CleanDevDirsError) -> Result<T>;
+}
+
+impl<T, E: Into<BoxedError>> ResultExt<T> for std::result::Result<T, E> {
+    fn context(self, error: CleanDevDirsError) -> Result<T> {
+        self.map_err(|source| error.with_source(source))
+    }
+
+    fn with_context(self, error: impl FnOnce() -> CleanDevDirsError) -> Result<T> {
+        self.map_err(|source| error().with_source(source))
+    }
+}
+
+/// Windows error codes of a file held open by another process:
+/// `ERROR_ACCESS_DENIED` (for a file left pending deletion),
+/// `ERROR_SHARING_VIOLATION` and `ERROR_LOCK_VIOLATION`.
+#[cfg(windows)]
+const WINDOWS_LOCK_ERRORS: [i32; 3] = [5, 32, 33];
+
+/// Whether `code` is one of the [`WINDOWS_LOCK_ERRORS`].
+#[cfg(windows)]
+fn is_windows_lock_error(code: Option<i32>) -> bool {
+    code.is_some_and(|code| WINDOWS_LOCK_ERRORS.contains(&code))
+}
+
+/// Whether `code` is a Windows error of a file held open by another process,
+/// never the case elsewhere.
+#[cfg(not(windows))]
+const fn is_windows_lock_error(_code: Option<i32>) -> bool {
+    false
+}
+
+/// The kind and OS error code of the I/O error a failed move to the trash
+/// was caused by, if known.
+///
+/// The trash backends report OS errors by code rather than as [`io::Error`].
+fn trash_io_error(error: &trash::Error) -> Option<(io::ErrorKind, Option<i32>)> {
+    match error {
+        trash::Error::Os { code, .. } => {
+            Some((io::Error::from_raw_os_error(*code).kind(), Some(*code)))
+        }
+        #[cfg(all(
+            unix,
+            not(target_os = "macos"),
+            not(target_os = "ios"),
+            not(target_os = "android")
+        ))]
+        trash::Error::FileSystem { source, .. } => Some((source.kind(), source.raw_os_error())),
+        _ => None,
+    }
+}
+
+/// The errors of a scan: all of them counted, the first few kept.
+#[derive(Debug, Default)]
+pub struct ScanErrors {
+    /// Number of errors recorded, kept or not
+    count: AtomicUsize,
+
+    /// How many errors to keep
+    limit: usize,
+
+    /// The first `limit` errors
+    kept: Mutex<Vec<CleanDevDirsError>>,
+}
+
+impl ScanErrors {
+    /// How many errors are kept for reporting in verbose mode.
+    pub const VERBOSE_LIMIT: usize = 100;
+
+    /// Collect errors, keeping the first `limit` of them.
+    ///
+    /// With a limit of 0, errors are only counted.
+    #[must_use]
+    pub const fn new(limit: usize) -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            limit,
+            kept: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Count `error`, and keep it unless the limit was reached.
+    pub fn record(&self, error: CleanDevDirsError) {
+        let index = self.count.fetch_add(1, Ordering::Relaxed);
+        if index < self.limit
+            && let Ok(mut kept) = self.kept.lock()
+        {
+            kept.push(error);
+        }
+    }
+
+    /// Number of errors recorded, including the ones not kept.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Take the errors kept so far, leaving none.
+    #[must_use]
+    pub fn take_kept(&self) -> Vec<CleanDevDirsError> {
+        self.kept
+            .lock()
+            .map(|mut kept| std::mem::take(&mut *kept))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[test]
+    fn test_display_shows_sources_in_alternate_form() {
+        let io = io::Error::new(io::ErrorKind::PermissionDenied, "access denied");
+        let error = CleanDevDirsError::config("Failed to read config.toml").with_source(io);
+
+        assert_eq!(error.to_string(), "Failed to read config.toml");
+        assert_eq!(
+            format!("{error:#}"),
+            "Failed to read config.toml: access denied"
+        );
+        assert!(
+            error
+                .source()
+                .and_then(|e| e.downcast_ref::<io::Error>())
+                .is_some_and(|e| e.kind() == io::ErrorKind::PermissionDenied)
+        );
+    }
+
+    #[test]
+    fn test_permission_denied_anywhere_in_chain() {
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+        let error = CleanDevDirsError::cleanup("failed to remove /app/target")
+            .with_source(denied)
+            .with_note("(gave up after 4 attempts)");
+        assert!(error.is_permission_denied());
+
+        let os = trash::Error::Os {
+            code: io::Error::from(io::ErrorKind::PermissionDenied)
+                .raw_os_error()
+                .unwrap_or(13),
+            description: "Permission denied".to_string(),
+        };
+        assert!(
+            CleanDevDirsError::cleanup("failed to move /app/target to trash")
+                .with_source(os)
+                .is_permission_denied()
+        );
+
+        let busy = io::Error::new(io::ErrorKind::ResourceBusy, "busy");
+        assert!(
+            !CleanDevDirsError::cleanup("x")
+                .with_source(busy)
+                .is_permission_denied()
+        );
+        assert!(!CleanDevDirsError::cleanup("x").is_permission_denied());
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        for kind in [
+            io::ErrorKind::Interrupted,
+            io::ErrorKind::WouldBlock,
+            io::ErrorKind::ResourceBusy,
+            io::ErrorKind::DirectoryNotEmpty,
+        ] {
+            assert!(
+                CleanDevDirsError::cleanup("failed to remove /app/target")
+                    .with_source(io::Error::from(kind))
+                    .is_transient(),
+                "{kind:?}"
+            );
+        }
+
+        for kind in [io::ErrorKind::PermissionDenied, io::ErrorKind::NotFound] {
+            assert!(
+                !CleanDevDirsError::cleanup("failed to remove /app/target")
+                    .with_source(io::Error::from(kind))
+                    .is_transient(),
+                "{kind:?}"
+            );
+        }
+        assert!(!CleanDevDirsError::cleanup("x").is_transient());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_access_denied_is_not_transient_on_unix() {
+        let eacces = io::Error::from_raw_os_error(13);
+        assert!(
+            !CleanDevDirsError::cleanup("failed to remove /app/target")
+                .with_source(eacces)
+                .is_transient()
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_windows_lock_errors_are_transient() {
+        for code in [5, 32, 33] {
+            assert!(
+                CleanDevDirsError::cleanup("failed to remove C:\\app\\target")
+                    .with_source(io::Error::from_raw_os_error(code))
+                    .is_transient(),
+                "{code}"
+            );
+        }
+        let os = trash::Error::Os {
+            code: 32,
+            description: "The file is in use".to_string(),
+        };
+        assert!(
+            CleanDevDirsError::cleanup("failed to move C:\\app\\target to trash")
+                .with_source(os)
+                .is_transient()
+        );
+    }
+
+    #[test]
+    fn test_variants_and_notes() {
+        assert!(matches!(
+            CleanDevDirsError::scan("x"),
+            CleanDevDirsError::Scan { .. }
+        ));
+        assert!(matches!(
+            CleanDevDirsError::cleanup("x"),
+            CleanDevDirsError::Cleanup { .. }
+        ));
+        assert!(matches!(
+            CleanDevDirsError::state("x"),
+            CleanDevDirsError::State { .. }
+        ));
+        assert!(matches!(
+            CleanDevDirsError::output("x"),
+            CleanDevDirsError::Output { .. }
+        ));
+
+        let error = CleanDevDirsError::cleanup("file is locked").with_note("(gave up)");
+        assert_eq!(error.to_string(), "file is locked (gave up)");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_scan_errors_count_all_but_keep_few() {
+        let errors = ScanErrors::new(2);
+        for i in 0..5 {
+            errors.record(CleanDevDirsError::scan(format!("error {i}")));
+        }
+
+        assert_eq!(errors.count(), 5);
+        let kept: Vec<String> = errors.take_kept().iter().map(ToString::to_string).collect();
+        assert_eq!(kept, vec!["error 0", "error 1"]);
+        assert!(errors.take_kept().is_empty());
+
+        let counted_only = ScanErrors::new(0);
+        counted_only.record(CleanDevDirsError::scan("x"));
+        assert_eq!(counted_only.count(), 1);
+        assert!(counted_only.take_kept().is_empty());
+    }
+
+    #[test]
+    fn test_result_context_keeps_the_source() {
+        let read: std::result::Result<(), io::Error> =
+            Err(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+        let error = read
+            .context(CleanDevDirsError::state("Failed to read history.jsonl"))
+            .err();
+
+        assert!(matches!(error, Some(CleanDevDirsError::State { .. })));
+        assert_eq!(
+            error.map(|e| format!("{e:#}")).as_deref(),
+            Some("Failed to read history.jsonl: no such file")
+        );
+
+        let ok: std::result::Result<u8, io::Error> = Ok(1);
+        let called = std::cell::Cell::new(false);
+        let value = ok.with_context(|| {
+            called.set(true);
+            CleanDevDirsError::output("x")
+        });
+        assert_eq!(value.ok(), Some(1));
+        assert!(!called.get());
+    }
+
+    #[test]
+    fn test_converts_into_anyhow() {
+        let error = CleanDevDirsError::config("Invalid size `5XB`").with_source("unknown unit");
+        let error = anyhow::Error::from(error);
+
+        assert_eq!(format!("{error:#}"), "Invalid size `5XB`: unknown unit");
+    }
+}
@@ -3,17 +3,25 @@
 //! This module provides functionality to copy compiled executables out of
 //! build directories before they are deleted during cleanup. This allows
 //! users to retain usable binaries while still reclaiming build artifact space.
+//!
+//! Preserved files are compared by their BLAKE3 hash, so repeated runs don't
+//! copy a binary again when an identical one is already in place. When a
+//! different file already exists at the destination, it is kept next to the
+//! new one under a `<name>.<hash>` name, unless only the latest copy should be
+//! kept, in which case such older copies are pruned.
 
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
-
+use crate::error::{CleanDevDirsError, Result, ResultExt};
 use crate::project::{Project, ProjectType};
 
 /// Extensions to exclude when looking for Rust executables.
 const RUST_EXCLUDED_EXTENSIONS: &[&str] = &["d", "rmeta", "rlib", "a", "so", "dylib", "dll", "pdb"];
 
+/// JAR name suffixes that are never runnable (attached source and doc bundles).
+const JAVA_EXCLUDED_JAR_SUFFIXES: &[&str] = &["-sources.jar", "-javadoc.jar", "-plain.jar"];
+
 /// Check whether a file is an executable binary.
 ///
 /// On Unix, this inspects the permission bits for the executable flag.
@@ -33,6 +41,18 @@ fn is_executable(path: &Path, _metadata: &fs::Metadata) -> bool {
         .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"))
 }
 
+/// Number of hex digits of the hash used to name older preserved copies.
+const OLD_COPY_HASH_LEN: usize = 12;
+
+/// What happened to a single executable during preservation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreserveStatus {
+    /// The file was copied to its destination
+    Copied,
+    /// An identical file already existed at the destination, so nothing was copied
+    Unchanged,
+}
+
 /// A record of a single preserved executable file.
 #[derive(Debug)]
 pub struct PreservedExecutable {
@@ -40,6 +60,10 @@ pub struct PreservedExecutable {
     pub source: PathBuf,
     /// Destination path where the file was copied
     pub destination: PathBuf,
+    /// Whether the file was copied or skipped as an identical copy
+    pub status: PreserveStatus,
+    /// Older preserved copies of this file that were removed
+    pub pruned: Vec<PathBuf>,
 }
 
 /// Preserve compiled executables from a project's build directory.
@@ -47,23 +71,37 @@ pub struct PreservedExecutable {
 /// Copies executable files to `<project_root>/bin/` before the build
 /// directory is deleted. The behavior depends on the project type:
 ///
-/// - **Rust**: copies executables from `target/release/` and `target/debug/`
+/// - **Rust**: copies executables from `target/release/` and `target/debug/`,
+///   and those of cross-compiled target triples to `bin/<triple>/`
 /// - **Python**: copies `.whl` files from `dist/` and `.so`/`.pyd` extensions from `build/`
-/// - **Node / Go / Java / C++ / Swift / .NET**: no-op (their cleanable dirs are dependencies or build outputs not easily preservable)
+/// - **Go**: copies executables from artifact directories other than `vendor/`
+///   (e.g. a `dist/` directory added through the `[artifacts]` config)
+/// - **Java**: copies runnable JARs from `target/` (Maven) and `build/libs/` (Gradle)
+/// - **.NET**: copies `bin/Release/**/publish/` outputs to `<project_root>/publish/`,
+///   since `bin/` itself is about to be removed
+/// - **Node / C++ / Swift / others**: no-op (their cleanable dirs are dependencies or build outputs not easily preservable)
+///
+/// Files identical to what is already at the destination are not copied again
+/// and are reported as [`PreserveStatus::Unchanged`]. A different file at the
+/// destination is renamed to `<name>.<hash>` before being replaced, or, when
+/// `keep_latest_only` is set, overwritten with any such older copies pruned.
 ///
 /// # Errors
 ///
-/// Returns an error if creating destination directories or copying files fails.
-pub fn preserve_executables(project: &Project) -> Result<Vec<PreservedExecutable>> {
+/// Returns an error if creating destination directories, hashing, or copying files fails.
+pub fn preserve_executables(
+    project: &Project,
+    keep_latest_only: bool,
+) -> Result<Vec<PreservedExecutable>> {
     match project.kind {
-        ProjectType::Rust => preserve_rust_executables(project),
-        ProjectType::Python => preserve_python_executables(project),
+        ProjectType::Rust => preserve_rust_executables(project, keep_latest_only),
+        ProjectType::Python => preserve_python_executables(project, keep_latest_only),
+        ProjectType::Go => preserve_go_executables(project, keep_latest_only),
+        ProjectType::Java => preserve_java_executables(project, keep_latest_only),
+        ProjectType::DotNet => preserve_dotnet_publish_outputs(project, keep_latest_only),
         ProjectType::Node
-        | ProjectType::Go
-        | ProjectType::Java
         | ProjectType::Cpp
         | ProjectType::Swift
-        | ProjectType::DotNet
         | ProjectType::Ruby
         | ProjectType::Elixir
         | ProjectType::Deno
@@ -71,59 +109,100 @@ pub fn preserve_executables(project: &Project) -> Result<Vec<PreservedExecutable
         | ProjectType::Haskell
         | ProjectType::Dart
         | ProjectType::Zig
-        | ProjectType::Scala => Ok(Vec::new()),
+        | ProjectType::Scala
+        | ProjectType::Android
+        | ProjectType::Lua
+        | ProjectType::Nim
+        | ProjectType::OCaml
+        | ProjectType::Erlang
+        | ProjectType::Julia
+        | ProjectType::R => Ok(Vec::new()),
     }
 }
 
+/// Return true if the build directory `dir` of `project` is where its
+/// executables already are, so it must stay when they are preserved.
+///
+/// This is the `bin/` of a Go project, which holds its `go build -o bin/`
+/// output and is also where other executables are preserved to.
+#[must_use]
+pub fn holds_preserved_executables(project: &Project, dir: &Path) -> bool {
+    project.kind == ProjectType::Go && dir == project.root_path.join("bin")
+}
+
 /// Preserve Rust executables from `target/release/` and `target/debug/`.
-fn preserve_rust_executables(project: &Project) -> Result<Vec<PreservedExecutable>> {
-    let Some(primary) = project.build_arts.first() else {
-        return Ok(Vec::new());
-    };
-    let target_dir = &primary.path;
+///
+/// Cross-compiled executables from `target/<triple>/release/` and
+/// `target/<triple>/debug/` go to `bin/<triple>/<profile>/`.
+fn preserve_rust_executables(
+    project: &Project,
+    keep_latest_only: bool,
+) -> Result<Vec<PreservedExecutable>> {
     let bin_dir = project.root_path.join("bin");
     let mut preserved = Vec::new();
 
-    for profile in &["release", "debug"] {
-        let profile_dir = target_dir.join(profile);
+    for (profile_dir, dest) in rust_profile_dirs(project) {
         if !profile_dir.is_dir() {
             continue;
         }
 
-        let dest_dir = bin_dir.join(profile);
+        let dest_dir = bin_dir.join(dest);
         let executables = find_rust_executables(&profile_dir)?;
 
         if executables.is_empty() {
             continue;
         }
 
-        fs::create_dir_all(&dest_dir)
-            .with_context(|| format!("Failed to create {}", dest_dir.display()))?;
+        fs::create_dir_all(&dest_dir).with_context(|| {
+            CleanDevDirsError::cleanup(format!("Failed to create {}", dest_dir.display()))
+        })?;
 
         for exe_path in executables {
             let Some(file_name) = exe_path.file_name() else {
                 continue;
             };
             let dest_path = dest_dir.join(file_name);
-
-            fs::copy(&exe_path, &dest_path).with_context(|| {
-                format!(
-                    "Failed to copy {} to {}",
-                    exe_path.display(),
-                    dest_path.display()
-                )
-            })?;
-
-            preserved.push(PreservedExecutable {
-                source: exe_path,
-                destination: dest_path,
-            });
+            preserved.push(preserve_file(&exe_path, &dest_path, keep_latest_only)?);
         }
     }
 
     Ok(preserved)
 }
 
+/// The `release/` and `debug/` directories in a Rust project's artifacts,
+/// with their destination relative to `bin/`.
+///
+/// An artifact is either a whole target directory, a host profile directory
+/// of a target directory split by triple (`target/release/`), or the
+/// directory of one triple (`target/<triple>/`).
+fn rust_profile_dirs(project: &Project) -> Vec<(PathBuf, PathBuf)> {
+    const PROFILES: [&str; 2] = ["release", "debug"];
+
+    let mut dirs = Vec::new();
+    for artifact in &project.build_arts {
+        let path = &artifact.path;
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+
+        if PROFILES.iter().any(|profile| name == *profile) {
+            dirs.push((path.clone(), PathBuf::from(name)));
+            continue;
+        }
+
+        let is_triple = path.parent().is_some_and(|p| p != project.root_path);
+        for profile in PROFILES {
+            let dest = if is_triple {
+                Path::new(name).join(profile)
+            } else {
+                PathBuf::from(profile)
+            };
+            dirs.push((path.join(profile), dest));
+        }
+    }
+    dirs
+}
+
 /// Find executable files in a Rust profile directory (e.g. `target/release/`).
 ///
 /// Returns files that pass [`is_executable`] and are not build metadata
@@ -132,11 +211,14 @@ fn preserve_rust_executables(project: &Project) -> Result<Vec<PreservedExecutabl
 fn find_rust_executables(profile_dir: &Path) -> Result<Vec<PathBuf>> {
     let mut executables = Vec::new();
 
-    let entries = fs::read_dir(profile_dir)
-        .with_context(|| format!("Failed to read {}", profile_dir.display()))?;
+    let entries = fs::read_dir(profile_dir).with_context(|| {
+        CleanDevDirsError::cleanup(format!("Failed to read {}", profile_dir.display()))
+    })?;
 
     for entry in entries {
-        let entry = entry?;
+        let entry = entry.with_context(|| {
+            CleanDevDirsError::cleanup(format!("Failed to read {}", profile_dir.display()))
+        })?;
         let path = entry.path();
 
         if !path.is_file() {
@@ -151,7 +233,9 @@ fn find_rust_executables(profile_dir: &Path) -> Result<Vec<PathBuf>> {
         }
 
         // Check if file is executable
-        let metadata = path.metadata()?;
+        let metadata = path.metadata().with_context(|| {
+            CleanDevDirsError::cleanup(format!("Failed to read {}", path.display()))
+        })?;
         if is_executable(&path, &metadata) {
             executables.push(path);
         }
@@ -161,13 +245,26 @@ fn find_rust_executables(profile_dir: &Path) -> Result<Vec<PathBuf>> {
 }
 
 /// Preserve Python build outputs: `.whl` from `dist/` and C extensions from `build/`.
-fn preserve_python_executables(project: &Project) -> Result<Vec<PreservedExecutable>> {
+fn preserve_python_executables(
+    project: &Project,
+    keep_latest_only: bool,
+) -> Result<Vec<PreservedExecutable>> {
     let root = &project.root_path;
     let bin_dir = root.join("bin");
     let mut preserved = Vec::new();
 
-    collect_wheel_files(&root.join("dist"), &bin_dir, &mut preserved)?;
-    collect_native_extensions(&root.join("build"), &bin_dir, &mut preserved)?;
+    collect_wheel_files(
+        &root.join("dist"),
+        &bin_dir,
+        keep_latest_only,
+        &mut preserved,
+    )?;
+    collect_native_extensions(
+        &root.join("build"),
+        &bin_dir,
+        keep_latest_only,
+        &mut preserved,
+    )?;
 
     Ok(preserved)
 }
@@ -176,6 +273,7 @@ fn preserve_python_executables(project: &Project) -> Result<Vec<PreservedExecuta
 fn collect_wheel_files(
     dist_dir: &Path,
     bin_dir: &Path,
+    keep_latest_only: bool,
     preserved: &mut Vec<PreservedExecutable>,
 ) -> Result<()> {
     if !dist_dir.is_dir() {
@@ -189,7 +287,7 @@ fn collect_wheel_files(
     for entry in entries.flatten() {
         let path = entry.path();
         if path.extension().and_then(|e| e.to_str()) == Some("whl") {
-            copy_to_bin(&path, bin_dir, preserved)?;
+            copy_to_bin(&path, bin_dir, keep_latest_only, preserved)?;
         }
     }
 
@@ -200,6 +298,7 @@ fn collect_wheel_files(
 fn collect_native_extensions(
     build_dir: &Path,
     bin_dir: &Path,
+    keep_latest_only: bool,
     preserved: &mut Vec<PreservedExecutable>,
 ) -> Result<()> {
     if !build_dir.is_dir() {
@@ -221,42 +320,294 @@ fn collect_native_extensions(
             .is_some_and(|ext| ext == "so" || ext == "pyd");
 
         if is_native_ext {
-            copy_to_bin(path, bin_dir, preserved)?;
+            copy_to_bin(path, bin_dir, keep_latest_only, preserved)?;
         }
     }
 
     Ok(())
 }
 
+/// Preserve Go binaries from artifact directories other than `vendor/`.
+///
+/// `vendor/` only ever holds module sources, and `go build -o bin/` output in
+/// the project's own `bin/` directory is where they are preserved to; that
+/// directory is not removed while executables are kept (see
+/// [`holds_preserved_executables`]).
+fn preserve_go_executables(
+    project: &Project,
+    keep_latest_only: bool,
+) -> Result<Vec<PreservedExecutable>> {
+    let bin_dir = project.root_path.join("bin");
+    let vendor_dir = project.root_path.join("vendor");
+    let mut preserved = Vec::new();
+
+    for artifact in &project.build_arts {
+        if artifact.path == vendor_dir || artifact.path == bin_dir || !artifact.path.is_dir() {
+            continue;
+        }
+
+        for entry in walkdir::WalkDir::new(&artifact.path)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+        {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+
+            let metadata = path.metadata().with_context(|| {
+                CleanDevDirsError::cleanup(format!("Failed to read {}", path.display()))
+            })?;
+            if is_executable(path, &metadata) {
+                copy_to_bin(path, &bin_dir, keep_latest_only, &mut preserved)?;
+            }
+        }
+    }
+
+    Ok(preserved)
+}
+
+/// Preserve runnable JARs from Maven's `target/` and Gradle's `build/libs/`.
+///
+/// Source, Javadoc and Gradle "plain" JARs are skipped, as are the
+/// `original-*.jar` files the Maven Shade plugin leaves next to the fat JAR.
+fn preserve_java_executables(
+    project: &Project,
+    keep_latest_only: bool,
+) -> Result<Vec<PreservedExecutable>> {
+    let root = &project.root_path;
+    let bin_dir = root.join("bin");
+    let mut preserved = Vec::new();
+
+    for jar_dir in [root.join("target"), root.join("build/libs")] {
+        let Ok(entries) = fs::read_dir(&jar_dir) else {
+            continue;
+        };
+
+        let mut jars: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && is_runnable_jar(path))
+            .collect();
+        jars.sort();
+
+        for jar in jars {
+            copy_to_bin(&jar, &bin_dir, keep_latest_only, &mut preserved)?;
+        }
+    }
+
+    Ok(preserved)
+}
+
+/// Whether `path` looks like a JAR worth keeping.
+fn is_runnable_jar(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+
+    Path::new(name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+        && !name.starts_with("original-")
+        && !JAVA_EXCLUDED_JAR_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+/// Preserve `dotnet publish` output from `bin/Release/**/publish/`.
+///
+/// Each publish directory is copied as a whole, since a published app needs
+/// its assemblies and runtime config next to each other. The path between
+/// `bin/Release/` and `publish/` (target framework and optional runtime
+/// identifier) is kept, so `bin/Release/net8.0/linux-x64/publish/` ends up in
+/// `publish/net8.0/linux-x64/`.
+fn preserve_dotnet_publish_outputs(
+    project: &Project,
+    keep_latest_only: bool,
+) -> Result<Vec<PreservedExecutable>> {
+    let release_dir = project.root_path.join("bin").join("Release");
+    let publish_root = project.root_path.join("publish");
+    let mut preserved = Vec::new();
+
+    if !release_dir.is_dir() {
+        return Ok(preserved);
+    }
+
+    let publish_dirs: Vec<PathBuf> = walkdir::WalkDir::new(&release_dir)
+        .into_iter()
+        .filter_entry(|entry| {
+            // Don't descend into a publish directory looking for nested ones
+            entry.depth() == 0
+                || entry
+                    .path()
+                    .parent()
+                    .and_then(Path::file_name)
+                    .is_none_or(|parent| parent != "publish")
+        })
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| entry.file_type().is_dir() && entry.file_name() == "publish")
+        .map(walkdir::DirEntry::into_path)
+        .collect();
+
+    for publish_dir in publish_dirs {
+        let Some(rel) = publish_dir
+            .parent()
+            .and_then(|parent| parent.strip_prefix(&release_dir).ok())
+        else {
+            continue;
+        };
+        let dest_dir = publish_root.join(rel);
+
+        for entry in walkdir::WalkDir::new(&publish_dir)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+        {
+            let Ok(file_rel) = entry.path().strip_prefix(&publish_dir) else {
+                continue;
+            };
+            let dest_path = dest_dir.join(file_rel);
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent).with_context(|| {
+                    CleanDevDirsError::cleanup(format!("Failed to create {}", parent.display()))
+                })?;
+            }
+
+            preserved.push(preserve_file(entry.path(), &dest_path, keep_latest_only)?);
+        }
+    }
+
+    Ok(preserved)
+}
+
 /// Copy a single file into `bin_dir`, creating the directory if needed,
 /// and record it as a [`PreservedExecutable`].
 fn copy_to_bin(
     source: &Path,
     bin_dir: &Path,
+    keep_latest_only: bool,
     preserved: &mut Vec<PreservedExecutable>,
 ) -> Result<()> {
-    fs::create_dir_all(bin_dir)
-        .with_context(|| format!("Failed to create {}", bin_dir.display()))?;
+    fs::create_dir_all(bin_dir).with_context(|| {
+        CleanDevDirsError::cleanup(format!("Failed to create {}", bin_dir.display()))
+    })?;
 
     let Some(file_name) = source.file_name() else {
         return Ok(());
     };
     let dest_path = bin_dir.join(file_name);
 
-    fs::copy(source, &dest_path).with_context(|| {
-        format!(
-            "Failed to copy {} to {}",
-            source.display(),
-            dest_path.display()
-        )
-    })?;
+    preserved.push(preserve_file(source, &dest_path, keep_latest_only)?);
 
-    preserved.push(PreservedExecutable {
+    Ok(())
+}
+
+/// Copy `source` to `destination` unless an identical file is already there.
+///
+/// The destination directory must already exist. See [`preserve_executables`]
+/// for how an existing, different destination file is handled.
+fn preserve_file(
+    source: &Path,
+    destination: &Path,
+    keep_latest_only: bool,
+) -> Result<PreservedExecutable> {
+    let mut status = PreserveStatus::Copied;
+
+    if destination.is_file() {
+        let existing = hash_file(destination)?;
+
+        if existing == hash_file(source)? {
+            status = PreserveStatus::Unchanged;
+        } else if !keep_latest_only {
+            let old_copy = old_copy_path(destination, &existing);
+            fs::rename(destination, &old_copy).with_context(|| {
+                CleanDevDirsError::cleanup(format!(
+                    "Failed to rename {} to {}",
+                    destination.display(),
+                    old_copy.display()
+                ))
+            })?;
+        }
+    }
+
+    if status == PreserveStatus::Copied {
+        fs::copy(source, destination).with_context(|| {
+            CleanDevDirsError::cleanup(format!(
+                "Failed to copy {} to {}",
+                source.display(),
+                destination.display()
+            ))
+        })?;
+    }
+
+    let pruned = if keep_latest_only {
+        prune_old_copies(destination)?
+    } else {
+        Vec::new()
+    };
+
+    Ok(PreservedExecutable {
         source: source.to_path_buf(),
-        destination: dest_path,
-    });
+        destination: destination.to_path_buf(),
+        status,
+        pruned,
+    })
+}
 
-    Ok(())
+/// Compute the BLAKE3 hash of a file's contents.
+fn hash_file(path: &Path) -> Result<blake3::Hash> {
+    let file = fs::File::open(path).with_context(|| {
+        CleanDevDirsError::cleanup(format!("Failed to open {}", path.display()))
+    })?;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(file).with_context(|| {
+        CleanDevDirsError::cleanup(format!("Failed to hash {}", path.display()))
+    })?;
+    Ok(hasher.finalize())
+}
+
+/// Path under which an older copy of `destination` with hash `hash` is kept.
+fn old_copy_path(destination: &Path, hash: &blake3::Hash) -> PathBuf {
+    let mut name = destination.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(&hash.to_hex()[..OLD_COPY_HASH_LEN]);
+    destination.with_file_name(name)
+}
+
+/// Remove older copies of `destination` (`<name>.<hash>` siblings).
+fn prune_old_copies(destination: &Path) -> Result<Vec<PathBuf>> {
+    let (Some(dir), Some(file_name)) = (
+        destination.parent(),
+        destination.file_name().and_then(|n| n.to_str()),
+    ) else {
+        return Ok(Vec::new());
+    };
+    let prefix = format!("{file_name}.");
+
+    let mut pruned = Vec::new();
+    for entry in fs::read_dir(dir)
+        .with_context(|| CleanDevDirsError::cleanup(format!("Failed to read {}", dir.display())))?
+        .flatten()
+    {
+        let name = entry.file_name();
+        let is_old_copy = name
+            .to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .is_some_and(|suffix| {
+                suffix.len() == OLD_COPY_HASH_LEN && suffix.bytes().all(|b| b.is_ascii_hexdigit())
+            });
+
+        if is_old_copy {
+            let path = entry.path();
+            fs::remove_file(&path).with_context(|| {
+                CleanDevDirsError::cleanup(format!("Failed to remove {}", path.display()))
+            })?;
+            pruned.push(path);
+        }
+    }
+
+    Ok(pruned)
 }
 
 #[cfg(test)]
@@ -272,12 +623,16 @@ mod tests {
             ProjectType::Python => root.join("__pycache__"),
             ProjectType::Node | ProjectType::Deno => root.join("node_modules"),
             ProjectType::Go | ProjectType::Ruby | ProjectType::Php => root.join("vendor"),
-            ProjectType::Cpp | ProjectType::Dart => root.join("build"),
+            ProjectType::Cpp | ProjectType::Dart | ProjectType::Android => root.join("build"),
             ProjectType::Swift => root.join(".build"),
             ProjectType::DotNet => root.join("obj"),
-            ProjectType::Elixir => root.join("_build"),
+            ProjectType::Elixir | ProjectType::OCaml | ProjectType::Erlang => root.join("_build"),
             ProjectType::Haskell => root.join(".stack-work"),
             ProjectType::Zig => root.join("zig-cache"),
+            ProjectType::Lua => root.join("lua_modules"),
+            ProjectType::Nim => root.join("nimcache"),
+            ProjectType::Julia => root.join(".julia/compiled"),
+            ProjectType::R => root.join("renv/library"),
         };
 
         fs::create_dir_all(&build_dir)?;
@@ -288,6 +643,10 @@ mod tests {
             vec![BuildArtifacts {
                 path: build_dir,
                 size: 0,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
             }],
             Some("test-project".to_string()),
         ))
@@ -311,7 +670,7 @@ mod tests {
         let dep_file = release_dir.join("my-binary.d");
         fs::write(&dep_file, b"dep info")?;
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
 
         assert_eq!(result.len(), 1);
         assert_eq!(
@@ -338,7 +697,7 @@ mod tests {
         let dep_file = release_dir.join("my-binary.d");
         fs::write(&dep_file, b"dep info")?;
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
 
         assert_eq!(result.len(), 1);
         assert_eq!(
@@ -365,7 +724,7 @@ mod tests {
         fs::write(&non_exe, b"not executable")?;
         fs::set_permissions(&non_exe, fs::Permissions::from_mode(0o644))?;
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert!(result.is_empty());
 
         Ok(())
@@ -383,7 +742,7 @@ mod tests {
         let non_exe = release_dir.join("some-file.txt");
         fs::write(&non_exe, b"not executable")?;
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert!(result.is_empty());
 
         Ok(())
@@ -394,19 +753,143 @@ mod tests {
         let tmp = TempDir::new()?;
         let project = create_test_project(&tmp, ProjectType::Node)?;
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert!(result.is_empty());
 
         Ok(())
     }
 
     #[test]
-    fn test_go_is_noop() -> anyhow::Result<()> {
+    fn test_go_vendor_only_is_noop() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let project = create_test_project(&tmp, ProjectType::Go)?;
+        fs::write(tmp.path().join("vendor/tool"), b"module source")?;
+
+        let result = preserve_executables(&project, false)?;
+        assert!(result.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_go_bin_is_kept_with_executables() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_test_project(&tmp, ProjectType::Go)?;
+        assert!(holds_preserved_executables(
+            &project,
+            &tmp.path().join("bin")
+        ));
+        assert!(!holds_preserved_executables(
+            &project,
+            &tmp.path().join("vendor")
+        ));
+
+        let rust = create_test_project(&tmp, ProjectType::Rust)?;
+        assert!(!holds_preserved_executables(&rust, &tmp.path().join("bin")));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_preserve_go_executables_from_extra_artifact_unix() -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new()?;
+        let mut project = create_test_project(&tmp, ProjectType::Go)?;
+
+        let dist_dir = tmp.path().join("dist/app_linux_amd64");
+        fs::create_dir_all(&dist_dir)?;
+        let exe_path = dist_dir.join("app");
+        fs::write(&exe_path, b"fake binary")?;
+        fs::set_permissions(&exe_path, fs::Permissions::from_mode(0o755))?;
+        fs::write(tmp.path().join("dist/checksums.txt"), b"sums")?;
+
+        project.build_arts.push(BuildArtifacts {
+            path: tmp.path().join("dist"),
+            size: 0,
+            files: 0,
+            dirs: 0,
+            newest_file: None,
+            estimated: false,
+        });
+
+        let result = preserve_executables(&project, false)?;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].source, exe_path);
+        assert_eq!(result[0].destination, tmp.path().join("bin/app"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preserve_java_jars() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_test_project(&tmp, ProjectType::Java)?;
+
+        let target_dir = tmp.path().join("target");
+        for name in [
+            "app-1.0.jar",
+            "app-1.0-sources.jar",
+            "app-1.0-javadoc.jar",
+            "original-app-1.0.jar",
+            "app-1.0.pom",
+        ] {
+            fs::write(target_dir.join(name), b"content")?;
+        }
+
+        let libs_dir = tmp.path().join("build/libs");
+        fs::create_dir_all(&libs_dir)?;
+        fs::write(libs_dir.join("service-all.jar"), b"fat jar")?;
+        fs::write(libs_dir.join("service-plain.jar"), b"plain jar")?;
+
+        let result = preserve_executables(&project, false)?;
+        let names: Vec<_> = result
+            .iter()
+            .filter_map(|p| p.destination.file_name().and_then(|n| n.to_str()))
+            .collect();
+
+        assert_eq!(names, vec!["app-1.0.jar", "service-all.jar"]);
+        assert!(tmp.path().join("bin/app-1.0.jar").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preserve_dotnet_publish_outputs() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_test_project(&tmp, ProjectType::DotNet)?;
+
+        let publish_dir = tmp.path().join("bin/Release/net8.0/linux-x64/publish");
+        fs::create_dir_all(publish_dir.join("runtimes"))?;
+        fs::write(publish_dir.join("MyApp.dll"), b"assembly")?;
+        fs::write(publish_dir.join("runtimes/native.so"), b"native")?;
+
+        // Regular build output outside publish/ is not preserved
+        fs::write(tmp.path().join("bin/Release/net8.0/MyApp.dll"), b"assembly")?;
+        fs::create_dir_all(tmp.path().join("bin/Debug/net8.0/publish"))?;
+        fs::write(
+            tmp.path().join("bin/Debug/net8.0/publish/MyApp.dll"),
+            b"debug assembly",
+        )?;
+
+        let result = preserve_executables(&project, false)?;
+        assert_eq!(result.len(), 2);
+
+        let dest_dir = tmp.path().join("publish/net8.0/linux-x64");
+        assert!(dest_dir.join("MyApp.dll").exists());
+        assert!(dest_dir.join("runtimes/native.so").exists());
+
+        Ok(())
+    }
 
-        let result = preserve_executables(&project)?;
+    #[test]
+    fn test_preserve_dotnet_without_release_is_noop() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_test_project(&tmp, ProjectType::DotNet)?;
+
+        let result = preserve_executables(&project, false)?;
         assert!(result.is_empty());
+        assert!(!tmp.path().join("publish").exists());
 
         Ok(())
     }
@@ -416,7 +899,7 @@ mod tests {
         let tmp = TempDir::new()?;
         let project = create_test_project(&tmp, ProjectType::Rust)?;
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert!(result.is_empty());
         assert!(!tmp.path().join("bin").exists());
 
@@ -442,7 +925,7 @@ mod tests {
             fs::set_permissions(&exe_path, fs::Permissions::from_mode(0o755))?;
         }
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert_eq!(result.len(), 3);
 
         for preserved in &result {
@@ -490,7 +973,7 @@ mod tests {
         fs::write(&exe_path, b"real binary")?;
         fs::set_permissions(&exe_path, fs::Permissions::from_mode(0o755))?;
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert_eq!(result.len(), 1);
         assert!(
             result[0]
@@ -556,7 +1039,7 @@ mod tests {
             fs::set_permissions(&exe_path, fs::Permissions::from_mode(0o755))?;
         }
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert_eq!(result.len(), 2);
 
         let dest_names: Vec<_> = result
@@ -570,6 +1053,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_preserve_rust_cross_compiled_unix() -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new()?;
+        let mut project = create_test_project(&tmp, ProjectType::Rust)?;
+        let target = tmp.path().join("target");
+        project.build_arts[0].path = target.join("release");
+        let mut triple = project.build_arts[0].clone();
+        triple.path = target.join("aarch64-unknown-linux-gnu");
+        project.build_arts.push(triple);
+
+        for dir in ["release", "aarch64-unknown-linux-gnu/release"] {
+            let exe_path = target.join(dir).join("my-binary");
+            fs::create_dir_all(target.join(dir))?;
+            fs::write(&exe_path, b"fake binary")?;
+            fs::set_permissions(&exe_path, fs::Permissions::from_mode(0o755))?;
+        }
+
+        let mut destinations: Vec<_> = preserve_executables(&project, false)?
+            .into_iter()
+            .map(|p| p.destination)
+            .collect();
+        destinations.sort();
+
+        assert_eq!(
+            destinations,
+            vec![
+                tmp.path()
+                    .join("bin/aarch64-unknown-linux-gnu/release/my-binary"),
+                tmp.path().join("bin/release/my-binary"),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_preserve_python_so_extensions_unix() -> anyhow::Result<()> {
@@ -585,7 +1105,7 @@ mod tests {
         )?;
         fs::write(build_dir.join("another.so"), b"shared object")?;
 
-        let result = preserve_python_executables(&project)?;
+        let result = preserve_python_executables(&project, false)?;
         assert_eq!(result.len(), 2);
 
         for preserved in &result {
@@ -645,7 +1165,7 @@ mod tests {
             fs::write(&exe_path, b"fake binary")?;
         }
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert_eq!(result.len(), 2);
 
         let dest_names: Vec<_> = result
@@ -675,7 +1195,7 @@ mod tests {
 
         fs::write(release_dir.join("my-binary.exe"), b"real binary")?;
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert_eq!(result.len(), 1);
         assert!(
             result[0]
@@ -703,7 +1223,7 @@ mod tests {
             fs::write(release_dir.join(name), b"fake binary")?;
         }
 
-        let result = preserve_executables(&project)?;
+        let result = preserve_executables(&project, false)?;
         assert_eq!(result.len(), 3);
 
         Ok(())
@@ -724,7 +1244,7 @@ mod tests {
         )?;
         fs::write(build_dir.join("another.pyd"), b"python extension")?;
 
-        let result = preserve_python_executables(&project)?;
+        let result = preserve_python_executables(&project, false)?;
         assert_eq!(result.len(), 2);
 
         for preserved in &result {
@@ -750,7 +1270,7 @@ mod tests {
         )?;
         fs::write(dist_dir.join("mypackage-1.0.0.tar.gz"), b"tarball content")?;
 
-        let result = preserve_python_executables(&project)?;
+        let result = preserve_python_executables(&project, false)?;
         assert_eq!(result.len(), 1);
         assert!(
             result[0]
@@ -767,7 +1287,7 @@ mod tests {
         let tmp = TempDir::new()?;
         let project = create_test_project(&tmp, ProjectType::Python)?;
 
-        let result = preserve_python_executables(&project)?;
+        let result = preserve_python_executables(&project, false)?;
         assert!(result.is_empty());
 
         Ok(())
@@ -781,7 +1301,7 @@ mod tests {
         fs::create_dir_all(tmp.path().join("dist"))?;
         fs::create_dir_all(tmp.path().join("build"))?;
 
-        let result = preserve_python_executables(&project)?;
+        let result = preserve_python_executables(&project, false)?;
         assert!(result.is_empty());
 
         Ok(())
@@ -805,7 +1325,7 @@ mod tests {
         #[cfg(windows)]
         fs::write(build_dir.join("native.pyd"), b"python extension")?;
 
-        let result = preserve_python_executables(&project)?;
+        let result = preserve_python_executables(&project, false)?;
         assert_eq!(result.len(), 2);
 
         Ok(())
@@ -821,7 +1341,7 @@ mod tests {
         let whl_path = dist_dir.join("pkg-1.0-py3-none-any.whl");
         fs::write(&whl_path, b"wheel content")?;
 
-        let result = preserve_python_executables(&project)?;
+        let result = preserve_python_executables(&project, false)?;
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].source, whl_path);
         assert_eq!(
@@ -831,4 +1351,69 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_identical_copy_is_skipped() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_test_project(&tmp, ProjectType::Java)?;
+        fs::write(tmp.path().join("target/app.jar"), b"jar v1")?;
+
+        let first = preserve_executables(&project, false)?;
+        assert_eq!(first[0].status, PreserveStatus::Copied);
+
+        let second = preserve_executables(&project, false)?;
+        assert_eq!(second[0].status, PreserveStatus::Unchanged);
+        assert_eq!(fs::read_dir(tmp.path().join("bin"))?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_changed_copy_keeps_previous_version() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_test_project(&tmp, ProjectType::Java)?;
+        let jar = tmp.path().join("target/app.jar");
+
+        fs::write(&jar, b"jar v1")?;
+        preserve_executables(&project, false)?;
+        fs::write(&jar, b"jar v2")?;
+        let result = preserve_executables(&project, false)?;
+
+        assert_eq!(result[0].status, PreserveStatus::Copied);
+        assert_eq!(fs::read(tmp.path().join("bin/app.jar"))?, b"jar v2");
+
+        let old_copy = old_copy_path(&tmp.path().join("bin/app.jar"), &blake3::hash(b"jar v1"));
+        assert_eq!(fs::read(old_copy)?, b"jar v1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keep_latest_only_prunes_older_copies() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_test_project(&tmp, ProjectType::Java)?;
+        let jar = tmp.path().join("target/app.jar");
+
+        fs::write(&jar, b"jar v1")?;
+        preserve_executables(&project, false)?;
+        fs::write(&jar, b"jar v2")?;
+        preserve_executables(&project, false)?;
+        fs::write(tmp.path().join("bin/app.jar.notes"), b"unrelated")?;
+
+        fs::write(&jar, b"jar v3")?;
+        let result = preserve_executables(&project, true)?;
+
+        assert_eq!(result[0].status, PreserveStatus::Copied);
+        assert_eq!(result[0].pruned.len(), 1);
+
+        let mut remaining: Vec<_> = fs::read_dir(tmp.path().join("bin"))?
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["app.jar", "app.jar.notes"]);
+        assert_eq!(fs::read(tmp.path().join("bin/app.jar"))?, b"jar v3");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,636 @@
+//! Composite filter expressions, for `--filter-expr`.
+//!
+//! The other filters each test one property and must all pass. A filter
+//! expression combines tests on a project's properties with boolean logic,
+//! such as
+//!
+//! ```text
+//! size > 1GB AND age > 90d OR type == node AND size > 300MB
+//! ```
+//!
+//! A test compares a field with a value:
+//!
+//! | Field   | Meaning                                        | Operators                       |
+//! |---------|------------------------------------------------|---------------------------------|
+//! | `size`  | total size of the build directories (`300MB`)  | `<` `<=` `>` `>=` `==` `!=`     |
+//! | `age`   | time since the build directories were modified | `<` `<=` `>` `>=` `==` `!=`     |
+//! | `files` | number of files in the build directories       | `<` `<=` `>` `>=` `==` `!=`     |
+//! | `type`  | project type, as for `--project-type`          | `==` `!=`                       |
+//! | `name`  | project name (empty if unknown)                | `==` `!=` `~` `!~`              |
+//! | `path`  | project root path                              | `==` `!=` `~` `!~`              |
+//!
+//! Sizes take the same formats as `--keep-size`. Ages are a number of days,
+//! optionally followed by `h` (hours), `d` (days) or `w` (weeks); a project
+//! whose modification time can't be read matches no `age` test. `~` and
+//! `!~` match a regular expression anywhere in the name or path. Values
+//! with spaces or operator characters are quoted with `'` or `"`.
+//!
+//! `NOT` binds tighter than `AND`, which binds tighter than `OR`, so the
+//! example above reads `(size > 1GB AND age > 90d) OR (type == node AND
+//! size > 300MB)`. Parentheses group explicitly. The keywords are case
+//! insensitive, and `!`, `&&` and `||` can be used instead. The grammar:
+//!
+//! ```text
+//! expr       = and { ("OR" | "||") and }
+//! and        = not { ("AND" | "&&") not }
+//! not        = ("NOT" | "!") not | "(" expr ")" | comparison
+//! comparison = field operator value
+//! ```
+//!
+//! An expression that can't be parsed is reported with the column of the
+//! problem, so the mistake can be found in a long expression.
+
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use clap::ValueEnum;
+use regex::Regex;
+
+use crate::{
+    config::ProjectFilter,
+    error::{CleanDevDirsError, Result},
+    filtering::artifact_mtime,
+    output::type_name,
+    project::Project,
+    utils::parse_size,
+};
+
+/// A parsed filter expression.
+#[derive(Clone, Debug)]
+pub struct FilterExpr {
+    root: Node,
+}
+
+impl FilterExpr {
+    /// Parse `source`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error pointing at the column of the first problem if
+    /// `source` isn't a valid expression, names an unknown field or project
+    /// type, or has a value that doesn't fit its field.
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source).map_err(|e| e.into_error(source))?;
+        let mut parser = Parser {
+            source,
+            tokens,
+            pos: 0,
+        };
+        let root = parser.expr().map_err(|e| e.into_error(source))?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            let message = format!("expected AND or OR before `{}`", token.text(source));
+            return Err(ParseError::new(message, token.start).into_error(source));
+        }
+        Ok(Self { root })
+    }
+
+    /// Whether `project` matches the expression.
+    #[must_use]
+    pub fn matches(&self, project: &Project) -> bool {
+        self.matches_at(project, SystemTime::now())
+    }
+
+    /// Whether `project` matches the expression, with ages counted up to `now`.
+    #[must_use]
+    pub fn matches_at(&self, project: &Project, now: SystemTime) -> bool {
+        self.root.eval(project, now)
+    }
+}
+
+impl FromStr for FilterExpr {
+    type Err = CleanDevDirsError;
+
+    fn from_str(source: &str) -> Result<Self> {
+        Self::parse(source)
+    }
+}
+
+/// A node of the expression tree.
+#[derive(Clone, Debug)]
+enum Node {
+    Or(Box<Self>, Box<Self>),
+    And(Box<Self>, Box<Self>),
+    Not(Box<Self>),
+    Test(Test),
+}
+
+impl Node {
+    fn eval(&self, project: &Project, now: SystemTime) -> bool {
+        match self {
+            Self::Or(lhs, rhs) => lhs.eval(project, now) || rhs.eval(project, now),
+            Self::And(lhs, rhs) => lhs.eval(project, now) && rhs.eval(project, now),
+            Self::Not(node) => !node.eval(project, now),
+            Self::Test(test) => test.eval(project, now),
+        }
+    }
+}
+
+/// A comparison of one field of a project with a value.
+#[derive(Clone, Debug)]
+enum Test {
+    Size(Op, u64),
+    Age(Op, Duration),
+    Files(Op, u64),
+    Type(Op, &'static str),
+    Name(Op, Text),
+    Path(Op, Text),
+}
+
+impl Test {
+    fn eval(&self, project: &Project, now: SystemTime) -> bool {
+        match self {
+            Self::Size(op, size) => op.compare(&project.total_size(), size),
+            Self::Age(op, age) => artifact_mtime(project).is_some_and(|modified| {
+                op.compare(&now.duration_since(modified).unwrap_or_default(), age)
+            }),
+            Self::Files(op, files) => op.compare(&project.total_files(), files),
+            Self::Type(op, kind) => op.compare(type_name(&project.kind), *kind),
+            Self::Name(op, text) => text.eval(*op, project.name.as_deref().unwrap_or("")),
+            Self::Path(op, text) => text.eval(*op, &project.root_path.to_string_lossy()),
+        }
+    }
+}
+
+/// The value a name or path is compared with.
+#[derive(Clone, Debug)]
+enum Text {
+    /// Compared as a whole, with `==` or `!=`
+    Exact(String),
+
+    /// Searched for, with `~` or `!~`
+    Pattern(Regex),
+}
+
+impl Text {
+    fn eval(&self, op: Op, value: &str) -> bool {
+        match self {
+            Self::Exact(text) => op.compare(value, text.as_str()),
+            Self::Pattern(re) => re.is_match(value) == (op == Op::Matches),
+        }
+    }
+}
+
+/// A comparison operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Matches,
+    NotMatches,
+}
+
+impl Op {
+    /// `lhs op rhs`. The match operators only apply to regular expressions,
+    /// which [`Text`] handles, so they never hold here.
+    fn compare<T: PartialOrd + ?Sized>(self, lhs: &T, rhs: &T) -> bool {
+        match self {
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+            Self::Eq => lhs == rhs,
+            Self::Ne => lhs != rhs,
+            Self::Matches | Self::NotMatches => false,
+        }
+    }
+
+    const fn is_ordering(self) -> bool {
+        matches!(self, Self::Lt | Self::Le | Self::Gt | Self::Ge)
+    }
+
+    const fn is_matching(self) -> bool {
+        matches!(self, Self::Matches | Self::NotMatches)
+    }
+}
+
+/// A token of the expression, with its byte range in the source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    /// The text of the token as written in `source`.
+    fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TokenKind {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Op(Op),
+    /// A field name or value, unquoted
+    Word(String),
+}
+
+/// Characters that end an unquoted word.
+const SPECIAL: &str = "()<>=!~&|'\"";
+
+/// Split `source` into tokens.
+fn tokenize(source: &str) -> std::result::Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut next_is = |expected: char| chars.next_if(|&(_, c)| c == expected).is_some();
+        let kind = match c {
+            '(' => TokenKind::Open,
+            ')' => TokenKind::Close,
+            '&' if next_is('&') => TokenKind::And,
+            '|' if next_is('|') => TokenKind::Or,
+            '&' | '|' => {
+                let message = format!("unexpected `{c}`, use `{c}{c}` or {}", keyword(c));
+                return Err(ParseError::new(message, start));
+            }
+            '<' if next_is('=') => TokenKind::Op(Op::Le),
+            '<' => TokenKind::Op(Op::Lt),
+            '>' if next_is('=') => TokenKind::Op(Op::Ge),
+            '>' => TokenKind::Op(Op::Gt),
+            '=' => {
+                next_is('=');
+                TokenKind::Op(Op::Eq)
+            }
+            '!' if next_is('=') => TokenKind::Op(Op::Ne),
+            '!' if next_is('~') => TokenKind::Op(Op::NotMatches),
+            '!' => TokenKind::Not,
+            '~' => TokenKind::Op(Op::Matches),
+            '\'' | '"' => {
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, q)) if q == c => break,
+                        Some((_, other)) => word.push(other),
+                        None => {
+                            return Err(ParseError::new("unterminated quote", start));
+                        }
+                    }
+                }
+                TokenKind::Word(word)
+            }
+            _ => {
+                let mut word = c.to_string();
+                while let Some((_, c)) =
+                    chars.next_if(|&(_, c)| !c.is_whitespace() && !SPECIAL.contains(c))
+                {
+                    word.push(c);
+                }
+                match word.to_ascii_lowercase().as_str() {
+                    "and" => TokenKind::And,
+                    "or" => TokenKind::Or,
+                    "not" => TokenKind::Not,
+                    _ => TokenKind::Word(word),
+                }
+            }
+        };
+        let end = chars.peek().map_or(source.len(), |&(i, _)| i);
+        tokens.push(Token { kind, start, end });
+    }
+
+    Ok(tokens)
+}
+
+/// The keyword spelled `&&` or `||`.
+const fn keyword(c: char) -> &'static str {
+    if c == '&' { "AND" } else { "OR" }
+}
+
+/// A problem at byte offset `at` of the expression.
+#[derive(Debug)]
+struct ParseError {
+    message: String,
+    at: usize,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>, at: usize) -> Self {
+        Self {
+            message: message.into(),
+            at,
+        }
+    }
+
+    /// The error, with the expression and a caret under the problem.
+    fn into_error(self, source: &str) -> CleanDevDirsError {
+        let column = source[..self.at].chars().count();
+        CleanDevDirsError::config(format!(
+            "Invalid filter expression: {} at column {}\n  {source}\n  {}^",
+            self.message,
+            column + 1,
+            " ".repeat(column)
+        ))
+    }
+}
+
+/// Recursive-descent parser over the tokens of an expression.
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+type ParseResult<T> = std::result::Result<T, ParseError>;
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consume the next token if it is `kind`.
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        let found = self.peek().is_some_and(|token| token.kind == *kind);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Byte offset of the next token, or the end of the source.
+    fn here(&self) -> usize {
+        self.peek().map_or(self.source.len(), |token| token.start)
+    }
+
+    /// `expr = and { OR and }`
+    fn expr(&mut self) -> ParseResult<Node> {
+        let mut node = self.and()?;
+        while self.eat(&TokenKind::Or) {
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    /// `and = not { AND not }`
+    fn and(&mut self) -> ParseResult<Node> {
+        let mut node = self.not()?;
+        while self.eat(&TokenKind::And) {
+            node = Node::And(Box::new(node), Box::new(self.not()?));
+        }
+        Ok(node)
+    }
+
+    /// `not = NOT not | "(" expr ")" | comparison`
+    fn not(&mut self) -> ParseResult<Node> {
+        if self.eat(&TokenKind::Not) {
+            return Ok(Node::Not(Box::new(self.not()?)));
+        }
+
+        let open = self.here();
+        if self.eat(&TokenKind::Open) {
+            let node = self.expr()?;
+            if !self.eat(&TokenKind::Close) {
+                let column = self.source[..open].chars().count() + 1;
+                let message = format!("expected `)` to close the `(` at column {column}");
+                return Err(ParseError::new(message, self.here()));
+            }
+            return Ok(node);
+        }
+
+        self.comparison().map(Node::Test)
+    }
+
+    /// `comparison = field operator value`
+    fn comparison(&mut self) -> ParseResult<Test> {
+        let (field, field_at) = match self.peek() {
+            Some(Token {
+                kind: TokenKind::Word(word),
+                start,
+                ..
+            }) => (word.to_ascii_lowercase(), *start),
+            Some(token) => {
+                let message = format!("expected a field before `{}`", token.text(self.source));
+                return Err(ParseError::new(message, token.start));
+            }
+            None => return Err(ParseError::new("expected a field", self.source.len())),
+        };
+        if !FIELDS.contains(&field.as_str()) {
+            let message = format!(
+                "unknown field `{}` (expected {})",
+                self.source_text(field_at),
+                FIELDS.join(", ")
+            );
+            return Err(ParseError::new(message, field_at));
+        }
+        self.pos += 1;
+
+        let Some(Token {
+            kind: TokenKind::Op(op),
+            start: op_at,
+            ..
+        }) = self.peek().cloned()
+        else {
+            let message = format!("expected an operator after `{field}`");
+            return Err(ParseError::new(message, self.here()));
+        };
+        self.pos += 1;
+
+        let Some(Token {
+            kind: TokenKind::Word(value),
+            start: value_at,
+            ..
+        }) = self.peek().cloned()
+        else {
+            let message = format!("expected a value after `{}`", self.source_text(op_at));
+            return Err(ParseError::new(message, self.here()));
+        };
+        self.pos += 1;
+
+        test(&field, op, &value).map_err(|(message, at_value)| {
+            ParseError::new(message, if at_value { value_at } else { op_at })
+        })
+    }
+
+    /// The text of the token starting at byte offset `start`.
+    fn source_text(&self, start: usize) -> &str {
+        self.tokens
+            .iter()
+            .find(|token| token.start == start)
+            .map_or("", |token| token.text(self.source))
+    }
+}
+
+/// The fields a comparison can test.
+const FIELDS: [&str; 6] = ["size", "age", "files", "type", "name", "path"];
+
+/// The test of `field` with `op` and `value`.
+///
+/// On error, returns the message and whether it is about the value (rather
+/// than the operator).
+fn test(field: &str, op: Op, value: &str) -> std::result::Result<Test, (String, bool)> {
+    let allowed = match field {
+        "size" | "age" | "files" => !op.is_matching(),
+        "type" => !op.is_ordering() && !op.is_matching(),
+        _ => !op.is_ordering(),
+    };
+    if !allowed {
+        return Err((
+            format!("`{field}` can't be compared with this operator"),
+            false,
+        ));
+    }
+
+    match field {
+        "size" => parse_size(value)
+            .map(|size| Test::Size(op, size))
+            .map_err(|_| (format!("invalid size `{value}`"), true)),
+        "age" => parse_age(value)
+            .map(|age| Test::Age(op, age))
+            .ok_or_else(|| (format!("invalid age `{value}` (e.g. 90d, 12h or 2w)"), true)),
+        "files" => value
+            .parse()
+            .map(|files| Test::Files(op, files))
+            .map_err(|_| (format!("invalid number of files `{value}`"), true)),
+        "type" => ProjectFilter::from_str(value, true)
+            .ok()
+            .and_then(ProjectFilter::detector_name)
+            .map(|kind| Test::Type(op, kind))
+            .ok_or_else(|| (format!("unknown project type `{value}`"), true)),
+        _ => {
+            let text = if op.is_matching() {
+                Regex::new(value)
+                    .map(Text::Pattern)
+                    .map_err(|_| (format!("invalid regular expression `{value}`"), true))?
+            } else {
+                Text::Exact(value.to_string())
+            };
+            Ok(if field == "name" {
+                Test::Name(op, text)
+            } else {
+                Test::Path(op, text)
+            })
+        }
+    }
+}
+
+/// Parse an age such as `90d`, `12h`, `2w` or `90` (days).
+fn parse_age(value: &str) -> Option<Duration> {
+    let value = value.to_ascii_lowercase();
+    let (number, hours_per_unit) = [("h", 1), ("d", 24), ("w", 24 * 7)]
+        .into_iter()
+        .find_map(|(unit, hours)| Some((value.strip_suffix(unit)?, hours)))
+        .unwrap_or((value.as_str(), 24));
+    let number: u64 = number.parse().ok()?;
+    Some(Duration::from_hours(number.checked_mul(hours_per_unit)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use std::path::PathBuf;
+
+    fn project(kind: ProjectType, name: &str, size: u64, files: u64, days_old: u64) -> Project {
+        let root = PathBuf::from(format!("/work/{name}"));
+        let mut project = Project::new(
+            kind,
+            root.clone(),
+            vec![BuildArtifacts {
+                path: root.join("build"),
+                size,
+                files,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some(name.to_string()),
+        );
+        project.newest_file = Some(now() - Duration::from_hours(days_old * 24));
+        project
+    }
+
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_hours(1_000 * 24)
+    }
+
+    fn matches(expr: &str, project: &Project) -> Result<bool> {
+        Ok(FilterExpr::parse(expr)?.matches_at(project, now()))
+    }
+
+    fn error(expr: &str) -> String {
+        FilterExpr::parse(expr).map_or_else(|e| e.to_string(), |_| String::new())
+    }
+
+    #[test]
+    fn test_precedence() -> anyhow::Result<()> {
+        let expr = "size > 1GB AND age > 90d OR type == node AND size > 300MB";
+        let big_old = project(ProjectType::Rust, "api", 2_000_000_000, 0, 100);
+        let big_new = project(ProjectType::Rust, "api", 2_000_000_000, 0, 10);
+        let node = project(ProjectType::Node, "web", 400_000_000, 0, 1);
+        let small_node = project(ProjectType::Node, "web", 100_000_000, 0, 1);
+
+        assert!(matches(expr, &big_old)?);
+        assert!(!matches(expr, &big_new)?);
+        assert!(matches(expr, &node)?);
+        assert!(!matches(expr, &small_node)?);
+
+        // NOT binds tighter than AND, and parentheses override both
+        assert!(matches("not type == node and size > 1gb", &big_new)?);
+        assert!(!matches("not (type == rust and size > 1GB)", &big_new)?);
+        assert!(matches("!(age < 2w) || files >= 5", &big_old)?);
+        assert!(matches(
+            "(type = node || type = rust) && age >= 10",
+            &big_new
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fields() -> anyhow::Result<()> {
+        let app = project(ProjectType::Python, "my app", 5_000, 42, 3);
+
+        assert!(matches("files == 42 and files != 41 and files < 43", &app)?);
+        assert!(matches("age > 48h and age <= 3d and age < 1w", &app)?);
+        assert!(matches(
+            "name == 'my app' and name ~ \"^my \" and name !~ api",
+            &app
+        )?);
+        assert!(matches("path ~ '^/work/' and path != /work", &app)?);
+        assert!(matches("type != rust and size <= 5KB", &app)?);
+
+        // Without a modification time, no age test matches
+        let mut unknown = project(ProjectType::Python, "gone", 5_000, 42, 3);
+        unknown.newest_file = None;
+        assert!(!matches("age > 1d", &unknown)?);
+        assert!(!matches("age < 1000w", &unknown)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_problem() {
+        assert_eq!(
+            error("size > AND age > 90d"),
+            "Invalid filter expression: expected a value after `>` at column 8\n  \
+             size > AND age > 90d\n         ^"
+        );
+        assert!(
+            error("size > 1GB age > 90d").contains("expected AND or OR before `age` at column 12")
+        );
+        assert!(error("(size > 1GB").contains("expected `)` to close the `(` at column 1"));
+        assert!(error("sise > 1GB").contains("unknown field `sise`"));
+        assert!(error("size 1GB").contains("expected an operator after `size`"));
+        assert!(error("size > lots").contains("invalid size `lots` at column 8"));
+        assert!(error("age > 3m").contains("invalid age `3m`"));
+        assert!(error("type == nod").contains("unknown project type `nod`"));
+        assert!(error("type > node").contains("`type` can't be compared"));
+        assert!(error("size ~ 1GB").contains("`size` can't be compared"));
+        assert!(error("name ~ '('").contains("invalid regular expression"));
+        assert!(error("name == 'x").contains("unterminated quote at column 9"));
+        assert!(error("size > 1GB & age > 1d").contains("use `&&` or AND"));
+        assert!(error("").contains("expected a field at column 1"));
+        assert!(error("size > 1GB AND").contains("expected a field at column 15"));
+    }
+}
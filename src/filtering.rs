@@ -3,25 +3,31 @@
 //! This module provides functions for filtering projects based on various criteria
 //! such as size and modification time.
 
-use anyhow::Result;
 use chrono::{DateTime, Local};
 use glob::Pattern as GlobPattern;
 use rayon::prelude::*;
 use regex::Regex;
-use std::cmp::Reverse;
+use std::cmp::Ordering;
 use std::fs;
+use std::path::Path;
 use std::time::SystemTime;
+use walkdir::{DirEntry, WalkDir};
 
 use crate::config::filter::SortCriteria;
 use crate::config::{FilterOptions, SortOptions};
-use crate::project::{Project, ProjectType};
-use crate::utils::parse_size;
+use crate::error::{CleanDevDirsError, Result};
+use crate::filter_expr::FilterExpr;
+use crate::project::{Project, ProjectType, RebuildCost};
+use crate::risk::{RiskLevel, RiskOverrides, artifact_keys, artifact_risk, normalize_key};
+use crate::scanner::Scanner;
+use crate::utils::{SizeUnits, format_bytes, parse_size};
+use crate::why::Reason;
 
 /// Compiled name pattern used to filter projects by name.
 ///
 /// Precompiled once before the parallel filter pass to avoid repeated
 /// compilation inside the hot path.
-enum NameMatcher {
+pub(crate) enum NameMatcher {
     None,
     Glob(GlobPattern),
     Regex(Regex),
@@ -42,26 +48,66 @@ impl NameMatcher {
 /// - `None` or empty string → `NameMatcher::None` (no filtering)
 /// - `"regex:<expr>"` → compiled regular expression
 /// - anything else → glob pattern
-fn compile_name_matcher(pattern: Option<&str>) -> Result<NameMatcher> {
+pub(crate) fn compile_name_matcher(pattern: Option<&str>) -> Result<NameMatcher> {
     let Some(pat) = pattern else {
         return Ok(NameMatcher::None);
     };
     if pat.is_empty() {
         return Ok(NameMatcher::None);
     }
+    let invalid = || CleanDevDirsError::config(format!("Invalid name pattern `{pat}`"));
     if let Some(regex_pat) = pat.strip_prefix("regex:") {
-        Ok(NameMatcher::Regex(Regex::new(regex_pat)?))
-    } else {
-        Ok(NameMatcher::Glob(GlobPattern::new(pat)?))
+        return Regex::new(regex_pat)
+            .map(NameMatcher::Regex)
+            .map_err(|e| invalid().with_source(e));
     }
+    GlobPattern::new(pat)
+        .map(NameMatcher::Glob)
+        .map_err(|e| invalid().with_source(e))
+}
+
+/// Compile an optional `--name-filter`/`--path-filter` regular expression.
+///
+/// `option` names the setting in the error message, so an invalid pattern is
+/// reported against the flag that supplied it.
+pub(crate) fn compile_filter_regex(pattern: Option<&str>, option: &str) -> Result<Option<Regex>> {
+    pattern
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            Regex::new(p).map_err(|e| {
+                CleanDevDirsError::config(format!("Invalid {option} regex `{p}`")).with_source(e)
+            })
+        })
+        .transpose()
+}
+
+/// Parse an optional `--filter-expr` expression; empty means no expression.
+pub(crate) fn compile_filter_expr(expr: Option<&str>) -> Result<Option<FilterExpr>> {
+    expr.filter(|e| !e.trim().is_empty())
+        .map(FilterExpr::parse)
+        .transpose()
 }
 
 /// Filter projects based on size and modification time criteria.
 ///
 /// This function applies parallel filtering to remove projects that don't meet
 /// the specified criteria:
+/// - Projects marked as protected via their `.clean-dev-dirs.toml`
 /// - Projects smaller than the minimum size threshold
 /// - Projects modified more recently than the specified number of days
+/// - Projects with a high rebuild cost that reclaim less than `min_benefit`
+/// - Projects whose git repository saw a commit within `repo_idle_days` days
+/// - Projects whose build directories hold fewer than `min_files` files
+/// - With `stale_only`, projects whose artifacts are newer than their sources;
+///   for Rust, each target triple's artifacts are judged on their own
+/// - With `max_risk`, build directories that are riskier to delete, and
+///   projects left without any
+/// - Build directories not named in `only_artifacts` (when it isn't empty) or
+///   named in `skip_artifacts`, and projects left without any
+/// - Projects whose name or root path does not match `name_filter` / `path_filter`
+/// - Projects that don't match the [filter expression](crate::filter_expr)
+///   `filter_expr`, which is checked against the build directories left by
+///   the filters above
 ///
 /// # Arguments
 ///
@@ -71,24 +117,38 @@ fn compile_name_matcher(pattern: Option<&str>) -> Result<NameMatcher> {
 /// # Returns
 ///
 /// - `Ok(Vec<Project>)` - Filtered list of projects that meet all criteria
-/// - `Err(anyhow::Error)` - If size parsing fails, or file system errors occur
+/// - `Err(CleanDevDirsError::Config)` - If a size or pattern in `filter_opts` is invalid
 ///
 /// # Errors
 ///
 /// This function can return errors if:
-/// - The size string in `filter_opts.keep_size` cannot be parsed (invalid format)
+/// - The size string in `filter_opts.keep_size` or `filter_opts.min_benefit` cannot be parsed (invalid format)
 /// - Size value overflow occurs during parsing
+/// - `filter_opts.name_pattern`, `filter_opts.name_filter` or `filter_opts.path_filter`
+///   is not a valid pattern
+/// - `filter_opts.filter_expr` is not a valid filter expression
 ///
 /// # Examples
 ///
 /// ```no_run
 /// # use clean_dev_dirs::{filtering::filter_projects, config::FilterOptions, project::Project};
-/// # use anyhow::Result;
-/// # fn example(projects: Vec<Project>) -> Result<()> {
+/// # use clean_dev_dirs::risk::RiskOverrides;
+/// # fn example(projects: Vec<Project>) -> clean_dev_dirs::Result<()> {
 /// let filter_opts = FilterOptions {
 ///     keep_size: "100MB".to_string(),
 ///     keep_days: 30,
 ///     name_pattern: None,
+///     name_filter: None,
+///     path_filter: None,
+///     min_benefit: None,
+///     repo_idle_days: 0,
+///     min_files: 0,
+///     stale_only: false,
+///     max_risk: None,
+///     risk_overrides: RiskOverrides::default(),
+///     only_artifacts: vec![],
+///     skip_artifacts: vec![],
+///     filter_expr: None,
 /// };
 /// let filtered = filter_projects(projects, &filter_opts)?;
 /// # Ok(())
@@ -98,26 +158,382 @@ pub fn filter_projects(
     projects: Vec<Project>,
     filter_opts: &FilterOptions,
 ) -> Result<Vec<Project>> {
-    let keep_size_bytes = parse_size(&filter_opts.keep_size)?;
-    let keep_days = filter_opts.keep_days;
-    let name_matcher = compile_name_matcher(filter_opts.name_pattern.as_deref())?;
+    let filters = Filters::new(filter_opts)?;
 
     Ok(projects
         .into_par_iter()
-        .filter(|project| meets_size_criteria(project, keep_size_bytes))
-        .filter(|project| meets_time_criteria(project, keep_days))
+        .filter(|project| !project.protected)
+        .filter_map(|project| filters.narrow(project).ok())
         .filter(|project| {
-            let name = project.name.as_deref().unwrap_or("");
-            name_matcher.is_match(name)
+            Criterion::ALL
+                .into_iter()
+                .all(|criterion| filters.meets(criterion, project))
         })
         .collect())
 }
 
+/// The filters of `filter_opts` that leave `project` out, for `--why`.
+///
+/// Checks the same criteria as [`filter_projects`], in the same order, but
+/// reports every one that fails instead of stopping at the first. The
+/// `stale_only`, `max_risk`, `only_artifacts` and `skip_artifacts` filters
+/// may drop some of the build directories; the later criteria are checked
+/// against the ones left, and not at all once none are. An empty list means
+/// the project is kept.
+///
+/// # Errors
+///
+/// Returns an error if a size or pattern in `filter_opts` is invalid, like
+/// [`filter_projects`].
+pub fn filter_reasons(project: &Project, filter_opts: &FilterOptions) -> Result<Vec<Reason>> {
+    let filters = Filters::new(filter_opts)?;
+
+    let mut reasons = Vec::new();
+    if project.protected {
+        reasons.push(Reason::new(
+            "protected",
+            "protected by its .clean-dev-dirs.toml or the keep marker",
+        ));
+    }
+
+    match filters.narrow(project.clone()) {
+        Ok(project) => reasons.extend(
+            Criterion::ALL
+                .into_iter()
+                .filter(|&criterion| !filters.meets(criterion, &project))
+                .map(|criterion| filters.reason(criterion, &project)),
+        ),
+        Err(reason) => reasons.push(reason),
+    }
+    Ok(reasons)
+}
+
+/// A filter a project is checked against once its build directories have
+/// been narrowed down by [`Filters::narrow`].
+#[derive(Clone, Copy, Debug)]
+enum Criterion {
+    KeepSize,
+    KeepDays,
+    MinBenefit,
+    RepoIdleDays,
+    MinFiles,
+    NamePattern,
+    NameFilter,
+    PathFilter,
+    FilterExpr,
+}
+
+impl Criterion {
+    /// Every criterion, in the order they are checked.
+    const ALL: [Self; 9] = [
+        Self::KeepSize,
+        Self::KeepDays,
+        Self::MinBenefit,
+        Self::RepoIdleDays,
+        Self::MinFiles,
+        Self::NamePattern,
+        Self::NameFilter,
+        Self::PathFilter,
+        Self::FilterExpr,
+    ];
+}
+
+/// The filters of a [`FilterOptions`], with its sizes and patterns parsed
+/// once before the parallel filter pass.
+struct Filters<'a> {
+    opts: &'a FilterOptions,
+    keep_size: u64,
+    min_benefit: u64,
+    name_matcher: NameMatcher,
+    name_filter: Option<Regex>,
+    path_filter: Option<Regex>,
+    filter_expr: Option<FilterExpr>,
+}
+
+impl<'a> Filters<'a> {
+    /// Parse the sizes and patterns of `opts`.
+    fn new(opts: &'a FilterOptions) -> Result<Self> {
+        Ok(Self {
+            opts,
+            keep_size: parse_size(&opts.keep_size)?,
+            min_benefit: opts
+                .min_benefit
+                .as_deref()
+                .map(parse_size)
+                .transpose()?
+                .unwrap_or(0),
+            name_matcher: compile_name_matcher(opts.name_pattern.as_deref())?,
+            name_filter: compile_filter_regex(opts.name_filter.as_deref(), "--name-filter")?,
+            path_filter: compile_filter_regex(opts.path_filter.as_deref(), "--path-filter")?,
+            filter_expr: compile_filter_expr(opts.filter_expr.as_deref())?,
+        })
+    }
+
+    /// Drop the build directories of `project` that the `stale_only`,
+    /// `max_risk`, `only_artifacts` and `skip_artifacts` filters leave out,
+    /// or tell which filter left none.
+    fn narrow(&self, mut project: Project) -> std::result::Result<Project, Reason> {
+        if self.opts.stale_only {
+            project = retain_stale(project).ok_or_else(|| {
+                Reason::new(
+                    "stale_only",
+                    "its build directories are newer than its sources",
+                )
+            })?;
+        }
+        if let Some(max_risk) = self.opts.max_risk {
+            project =
+                retain_max_risk(project, max_risk, &self.opts.risk_overrides).ok_or_else(|| {
+                    Reason::new(
+                        "max_risk",
+                        format!("all its build directories are riskier to delete than {max_risk}"),
+                    )
+                })?;
+        }
+        for (rule, names, matching) in [
+            ("only_artifacts", &self.opts.only_artifacts, true),
+            ("skip_artifacts", &self.opts.skip_artifacts, false),
+        ] {
+            project = retain_named(project, names, matching).ok_or_else(|| {
+                let detail = format!(
+                    "it leaves out all its build directories ({})",
+                    names.join(", ")
+                );
+                Reason::new(rule, detail)
+            })?;
+        }
+        Ok(project)
+    }
+
+    /// Whether `project` meets `criterion`.
+    fn meets(&self, criterion: Criterion, project: &Project) -> bool {
+        let name = project.name.as_deref().unwrap_or("");
+        match criterion {
+            Criterion::KeepSize => meets_size_criteria(project, self.keep_size),
+            Criterion::KeepDays => meets_time_criteria(project, self.opts.keep_days),
+            Criterion::MinBenefit => meets_benefit_criteria(project, self.min_benefit),
+            Criterion::RepoIdleDays => meets_repo_idle_criteria(project, self.opts.repo_idle_days),
+            Criterion::MinFiles => project.total_files() >= self.opts.min_files,
+            Criterion::NamePattern => self.name_matcher.is_match(name),
+            Criterion::NameFilter => self.name_filter.as_ref().is_none_or(|re| re.is_match(name)),
+            Criterion::PathFilter => self
+                .path_filter
+                .as_ref()
+                .is_none_or(|re| re.is_match(&project.root_path.to_string_lossy())),
+            Criterion::FilterExpr => self
+                .filter_expr
+                .as_ref()
+                .is_none_or(|expr| expr.matches(project)),
+        }
+    }
+
+    /// Why `project` doesn't meet `criterion`.
+    fn reason(&self, criterion: Criterion, project: &Project) -> Reason {
+        let name = project.name.as_deref().unwrap_or("");
+        let size = || format_bytes(project.total_size(), SizeUnits::Decimal);
+        match criterion {
+            Criterion::KeepSize => Reason::new(
+                "keep_size",
+                format!(
+                    "{} of build directories, less than {}",
+                    size(),
+                    self.opts.keep_size
+                ),
+            ),
+            Criterion::KeepDays => Reason::new(
+                "keep_days",
+                format!(
+                    "build directories modified within the last {} days",
+                    self.opts.keep_days
+                ),
+            ),
+            Criterion::MinBenefit => Reason::new(
+                "min_benefit",
+                format!("expensive to rebuild, and only {} to reclaim", size()),
+            ),
+            Criterion::RepoIdleDays => Reason::new(
+                "repo_idle_days",
+                format!(
+                    "its git repository had a commit within the last {} days",
+                    self.opts.repo_idle_days
+                ),
+            ),
+            Criterion::MinFiles => Reason::new(
+                "min_files",
+                format!(
+                    "{} files in its build directories, fewer than {}",
+                    project.total_files(),
+                    self.opts.min_files
+                ),
+            ),
+            Criterion::NamePattern => Reason::new(
+                "name_pattern",
+                format!("name `{name}` doesn't match the name pattern"),
+            ),
+            Criterion::NameFilter => Reason::new(
+                "name_filter",
+                format!("name `{name}` doesn't match --name-filter"),
+            ),
+            Criterion::PathFilter => {
+                Reason::new("path_filter", "its path doesn't match --path-filter")
+            }
+            Criterion::FilterExpr => {
+                Reason::new("filter_expr", "it doesn't match the filter expression")
+            }
+        }
+    }
+}
+
 /// Check if a project meets the size criteria.
 fn meets_size_criteria(project: &Project, min_size: u64) -> bool {
     project.total_size() >= min_size
 }
 
+/// Check if cleaning a project is worth its rebuild cost.
+///
+/// Only projects with a [`RebuildCost::High`] estimate are held to the
+/// `min_benefit` threshold; cheaper rebuilds are always worth cleaning.
+fn meets_benefit_criteria(project: &Project, min_benefit: u64) -> bool {
+    project.rebuild_cost() != RebuildCost::High || project.total_size() >= min_benefit
+}
+
+/// Check if a project's git repository has been idle for long enough.
+///
+/// Projects without a known last commit (e.g. outside a git repository) are
+/// never filtered out.
+fn meets_repo_idle_criteria(project: &Project, repo_idle_days: u32) -> bool {
+    if repo_idle_days == 0 {
+        return true;
+    }
+
+    let Some(last_commit) = project.last_commit else {
+        return true;
+    };
+
+    let cutoff = Local::now() - chrono::Duration::days(i64::from(repo_idle_days));
+    last_commit <= cutoff.timestamp()
+}
+
+/// Keep `project` for `stale_only` if its artifacts are stale.
+///
+/// Rust projects keep only their stale artifacts, so a target triple that
+/// wasn't rebuilt since the last source change is cleaned while a current one
+/// is kept. Other projects are kept or dropped as a whole by [`is_stale`].
+fn retain_stale(mut project: Project) -> Option<Project> {
+    if project.kind != ProjectType::Rust || project.build_arts.len() < 2 {
+        return is_stale(&project).then_some(project);
+    }
+
+    let source = newest_source_mtime(&project)?;
+    project.build_arts.retain(|artifact| {
+        artifact
+            .newest_file
+            .or_else(|| fs::metadata(&artifact.path).ok()?.modified().ok())
+            .is_some_and(|built| source > built)
+    });
+    refresh_newest_file(&mut project);
+
+    (!project.build_arts.is_empty()).then_some(project)
+}
+
+/// Keep only the build directories of `project` that are at most
+/// `max_risk` to delete, dropping the project if none are left.
+fn retain_max_risk(
+    mut project: Project,
+    max_risk: RiskLevel,
+    overrides: &RiskOverrides,
+) -> Option<Project> {
+    project.build_arts.retain(|artifact| {
+        artifact_risk(overrides, &project.kind, &project.root_path, &artifact.path) <= max_risk
+    });
+    refresh_newest_file(&mut project);
+
+    (!project.build_arts.is_empty()).then_some(project)
+}
+
+/// Keep only the build directories of `project` that match one of `names`
+/// (or, when `matching` is false, that match none of them), dropping the
+/// project if none are left. An empty `names` keeps everything.
+///
+/// A name matches a build directory's path within the project (such as
+/// `vendor/bundle`), its name, or the first component of that path, so
+/// `node_modules` also stands for the `node_modules/` of workspace members.
+fn retain_named(mut project: Project, names: &[String], matching: bool) -> Option<Project> {
+    if names.is_empty() {
+        return Some(project);
+    }
+
+    let names: Vec<String> = names.iter().map(|name| normalize_key(name)).collect();
+    project.build_arts.retain(|artifact| {
+        let keys = artifact_keys(&project.root_path, &artifact.path);
+        keys.iter().any(|key| names.contains(key)) == matching
+    });
+    refresh_newest_file(&mut project);
+
+    (!project.build_arts.is_empty()).then_some(project)
+}
+
+/// Update a project's newest file after some of its build directories were
+/// dropped, if it was measured.
+fn refresh_newest_file(project: &mut Project) {
+    if project.newest_file.is_some() {
+        project.newest_file = project
+            .build_arts
+            .iter()
+            .filter_map(|artifact| artifact.newest_file)
+            .max();
+    }
+}
+
+/// Check if a project's artifacts are older than its newest source file.
+///
+/// The artifacts are dated by their newest file, falling back to the build
+/// directory's modification time. If either side can't be dated, the project
+/// isn't considered stale.
+fn is_stale(project: &Project) -> bool {
+    let built = project
+        .build_arts
+        .iter()
+        .filter_map(|artifact| artifact.newest_file)
+        .max()
+        .or_else(|| artifact_mtime(project));
+
+    match (built, newest_source_mtime(project)) {
+        (Some(built), Some(source)) => source > built,
+        _ => false,
+    }
+}
+
+/// Modification time of the newest file under a project's root, outside its
+/// build directories.
+///
+/// Hidden directories such as `.git` and the usual artifact directories of
+/// nested projects are skipped too, since changes there aren't source changes.
+/// So are Rust target directories split by target triple, whose parts are
+/// the build directories.
+fn newest_source_mtime(project: &Project) -> Option<SystemTime> {
+    let is_build_dir = |path: &Path| {
+        project.build_arts.iter().any(|a| {
+            a.path == path || (project.kind == ProjectType::Rust && a.path.parent() == Some(path))
+        })
+    };
+    let is_source_dir = |entry: &DirEntry| {
+        entry.depth() == 0
+            || !entry.file_type().is_dir()
+            || !(entry.file_name().to_string_lossy().starts_with('.')
+                || Scanner::is_excluded_directory(entry.path())
+                || is_build_dir(entry.path()))
+    };
+
+    WalkDir::new(&project.root_path)
+        .into_iter()
+        .filter_entry(is_source_dir)
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .max()
+}
+
 /// Check if a project meets the time criteria.
 fn meets_time_criteria(project: &Project, keep_days: u32) -> bool {
     if keep_days == 0 {
@@ -129,15 +545,8 @@ fn meets_time_criteria(project: &Project, keep_days: u32) -> bool {
 
 /// Check if a project is old enough based on its modification time.
 fn is_project_old_enough(project: &Project, keep_days: u32) -> bool {
-    let Some(primary) = project.build_arts.first() else {
-        return true;
-    };
-    let Result::Ok(metadata) = fs::metadata(&primary.path) else {
-        return true; // If we can't read metadata, don't filter it out
-    };
-
-    let Result::Ok(modified) = metadata.modified() else {
-        return true; // If we can't read modification time, don't filter it out
+    let Some(modified) = artifact_mtime(project) else {
+        return true; // If we can't read the modification time, don't filter it out
     };
 
     let modified_time: DateTime<Local> = modified.into();
@@ -148,22 +557,27 @@ fn is_project_old_enough(project: &Project, keep_days: u32) -> bool {
 
 /// Sort projects in place according to the given sorting options.
 ///
-/// When `sort_opts.criteria` is `None`, the list is left in its current order.
-/// Each criterion has a natural default direction:
+/// When `sort_opts.criteria` is empty, the list is left in its current order.
+/// Criteria are applied in order, each later one breaking ties left by the
+/// ones before it. Each criterion has a natural default direction:
 /// - `Size`: largest first (descending)
 /// - `Age`: oldest first (ascending)
 /// - `Name`: alphabetical, case-insensitive (ascending)
 /// - `Type`: grouped by type name alphabetically
 ///
-/// Setting `sort_opts.reverse` to `true` flips the resulting order.
+/// Projects that compare equal on every criterion are ordered by their root
+/// path, so the result never depends on scan order. Setting
+/// `sort_opts.reverse` to `true` flips the whole resulting order, including
+/// this tie-break.
 ///
-/// For the `Age` criterion a Schwartzian transform is used to avoid
-/// repeated filesystem calls inside the comparator.
+/// A Schwartzian transform is used so that sort keys (in particular the
+/// artifact modification time) are computed once per project rather than
+/// inside the comparator.
 ///
 /// # Arguments
 ///
 /// * `projects` - Mutable reference to the vector of projects to sort
-/// * `sort_opts` - Sorting options specifying criterion and direction
+/// * `sort_opts` - Sorting options specifying criteria and direction
 ///
 /// # Examples
 ///
@@ -172,86 +586,132 @@ fn is_project_old_enough(project: &Project, keep_days: u32) -> bool {
 /// # use clean_dev_dirs::project::Project;
 /// # fn example(mut projects: Vec<Project>) {
 /// let sort_opts = SortOptions {
-///     criteria: Some(SortCriteria::Size),
+///     criteria: vec![SortCriteria::Size, SortCriteria::Name],
 ///     reverse: false,
 /// };
 /// sort_projects(&mut projects, &sort_opts);
 /// # }
 /// ```
 pub fn sort_projects(projects: &mut Vec<Project>, sort_opts: &SortOptions) {
-    let Some(criteria) = sort_opts.criteria else {
+    if sort_opts.criteria.is_empty() {
         return;
-    };
-
-    match criteria {
-        SortCriteria::Size => {
-            projects.sort_by_key(|p| Reverse(p.total_size()));
-        }
-        SortCriteria::Age => {
-            sort_by_age(projects);
-        }
-        SortCriteria::Name => {
-            projects.sort_by(|a, b| {
-                let name_a = a.name.as_deref().unwrap_or("");
-                let name_b = b.name.as_deref().unwrap_or("");
-                name_a.to_lowercase().cmp(&name_b.to_lowercase())
-            });
-        }
-        SortCriteria::Type => {
-            projects.sort_by_key(|a| type_order(&a.kind));
-        }
-    }
-
-    if sort_opts.reverse {
-        projects.reverse();
     }
-}
 
-/// Sort projects by build artifacts modification time (oldest first).
-///
-/// Uses a Schwartzian transform: each project is paired with its modification
-/// time (fetched once), sorted, then the timestamps are discarded.
-fn sort_by_age(projects: &mut Vec<Project>) {
-    let mut decorated: Vec<(Project, SystemTime)> = projects
+    let needs_age = sort_opts.criteria.contains(&SortCriteria::Age);
+    let mut decorated: Vec<(Project, SortKey)> = projects
         .drain(..)
         .map(|p| {
-            let mtime = p
-                .build_arts
-                .first()
-                .and_then(|a| fs::metadata(&a.path).ok())
-                .and_then(|m| m.modified().ok())
-                .unwrap_or(SystemTime::UNIX_EPOCH);
-            (p, mtime)
+            let key = SortKey::new(&p, needs_age);
+            (p, key)
         })
         .collect();
 
-    decorated.sort_by_key(|a| a.1);
+    decorated.sort_by(|(a, ka), (b, kb)| {
+        sort_opts
+            .criteria
+            .iter()
+            .map(|&criteria| ka.compare(kb, criteria))
+            .find(|ord| ord.is_ne())
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.root_path.cmp(&b.root_path))
+    });
 
     projects.extend(decorated.into_iter().map(|(p, _)| p));
+
+    if sort_opts.reverse {
+        projects.reverse();
+    }
+}
+
+/// Precomputed sort keys for a single project.
+struct SortKey {
+    size: u64,
+    files: u64,
+    growth: Option<i64>,
+    modified: SystemTime,
+    name: String,
+    type_order: u8,
+}
+
+impl SortKey {
+    /// Compute the keys for `project`, reading the artifact modification time
+    /// from disk only when `with_age` is set.
+    fn new(project: &Project, with_age: bool) -> Self {
+        Self {
+            size: project.total_size(),
+            files: project.total_files(),
+            growth: project.size_change.map(|change| change.bytes),
+            modified: if with_age {
+                artifact_modified(project)
+            } else {
+                SystemTime::UNIX_EPOCH
+            },
+            name: project.name.as_deref().unwrap_or("").to_lowercase(),
+            type_order: type_order(&project.kind),
+        }
+    }
+
+    /// Compare two keys on a single criterion in its natural direction.
+    fn compare(&self, other: &Self, criteria: SortCriteria) -> Ordering {
+        match criteria {
+            SortCriteria::Size => other.size.cmp(&self.size),
+            SortCriteria::Age => self.modified.cmp(&other.modified),
+            SortCriteria::Name => self.name.cmp(&other.name),
+            SortCriteria::Type => self.type_order.cmp(&other.type_order),
+            SortCriteria::Files => other.files.cmp(&self.files),
+            SortCriteria::Growth => other.growth.cmp(&self.growth),
+        }
+    }
+}
+
+/// Modification time of a project's build artifacts.
+///
+/// This is [`Project::newest_file`] when the scanner measured it, and the
+/// modification time of the primary build artifact directory otherwise.
+/// Falls back to the Unix epoch when it cannot be read, so such projects
+/// count as the oldest.
+pub(crate) fn artifact_modified(project: &Project) -> SystemTime {
+    artifact_mtime(project).unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Like [`artifact_modified`], but `None` when the time cannot be read.
+pub(crate) fn artifact_mtime(project: &Project) -> Option<SystemTime> {
+    project.newest_file.or_else(|| {
+        let primary = project.build_arts.first()?;
+        fs::metadata(&primary.path).ok()?.modified().ok()
+    })
 }
 
 /// Map a `ProjectType` to an ordering index for type-based sorting.
 ///
 /// Types are ordered alphabetically by their display name:
-/// C/C++, Dart, Deno, .NET, Elixir, Go, Haskell, Java, Node, PHP, Python, Ruby, Rust, Scala, Swift, Zig
+/// Android, C/C++, Dart, Deno, .NET, Elixir, Erlang, Go, Haskell, Java, Julia, Lua, Nim, Node,
+/// OCaml, PHP, Python, R, Ruby, Rust, Scala, Swift, Zig
 const fn type_order(kind: &ProjectType) -> u8 {
     match kind {
-        ProjectType::Cpp => 0,
-        ProjectType::Dart => 1,
-        ProjectType::Deno => 2,
-        ProjectType::DotNet => 3,
-        ProjectType::Elixir => 4,
-        ProjectType::Go => 5,
-        ProjectType::Haskell => 6,
-        ProjectType::Java => 7,
-        ProjectType::Node => 8,
-        ProjectType::Php => 9,
-        ProjectType::Python => 10,
-        ProjectType::Ruby => 11,
-        ProjectType::Rust => 12,
-        ProjectType::Scala => 13,
-        ProjectType::Swift => 14,
-        ProjectType::Zig => 15,
+        ProjectType::Android => 0,
+        ProjectType::Cpp => 1,
+        ProjectType::Dart => 2,
+        ProjectType::Deno => 3,
+        ProjectType::DotNet => 4,
+        ProjectType::Elixir => 5,
+        ProjectType::Erlang => 6,
+        ProjectType::Go => 7,
+        ProjectType::Haskell => 8,
+        ProjectType::Java => 9,
+        ProjectType::Julia => 10,
+        ProjectType::Lua => 11,
+        ProjectType::Nim => 12,
+        ProjectType::Node => 13,
+        ProjectType::OCaml => 14,
+        ProjectType::Php => 15,
+        ProjectType::Python => 16,
+        ProjectType::R => 17,
+        ProjectType::Ruby => 18,
+        ProjectType::Rust => 19,
+        ProjectType::Scala => 20,
+        ProjectType::Swift => 21,
+        ProjectType::Zig => 22,
     }
 }
 
@@ -259,6 +719,7 @@ const fn type_order(kind: &ProjectType) -> u8 {
 mod tests {
     use super::*;
     use crate::project::{BuildArtifacts, Project, ProjectType};
+    use crate::snapshots::SizeChange;
     use std::path::PathBuf;
 
     /// Helper function to create a test project
@@ -275,6 +736,10 @@ mod tests {
             vec![BuildArtifacts {
                 path: PathBuf::from(build_path),
                 size,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
             }],
             name,
         )
@@ -309,6 +774,277 @@ mod tests {
         assert!(meets_time_criteria(&project, 0));
     }
 
+    #[test]
+    fn test_time_criteria_uses_newest_file() {
+        // The artifact directory doesn't exist, so only `newest_file` can age it
+        let mut project = create_test_project(
+            ProjectType::Rust,
+            "/missing",
+            "/missing/target",
+            1_000_000,
+            Some("test".to_string()),
+        );
+
+        project.newest_file = Some(SystemTime::now());
+        assert!(!meets_time_criteria(&project, 7));
+
+        project.newest_file = Some(SystemTime::now() - std::time::Duration::from_hours(30 * 24));
+        assert!(meets_time_criteria(&project, 7));
+        assert_eq!(artifact_mtime(&project), project.newest_file);
+    }
+
+    #[test]
+    fn test_stale_only_compares_artifacts_with_sources() -> anyhow::Result<()> {
+        use std::fs::{self, File, FileTimes};
+        use std::time::Duration;
+
+        let tmp = tempfile::TempDir::new()?;
+        let root = tmp.path();
+        let days_ago = |days: u64| SystemTime::now() - Duration::from_hours(days * 24);
+
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/main.rs"), "fn main() {}")?;
+        File::options()
+            .write(true)
+            .open(root.join("src/main.rs"))?
+            .set_times(FileTimes::new().set_modified(days_ago(10)))?;
+
+        // Fresh files in the build, VCS and nested artifact directories don't count
+        for dir in ["target", ".git", "web/node_modules"] {
+            fs::create_dir_all(root.join(dir))?;
+            fs::write(root.join(dir).join("file"), "")?;
+        }
+
+        let mut project = create_test_project(
+            ProjectType::Rust,
+            &root.to_string_lossy(),
+            &root.join("target").to_string_lossy(),
+            1_000_000,
+            Some("test".to_string()),
+        );
+
+        project.build_arts[0].newest_file = Some(days_ago(1));
+        assert!(!is_stale(&project));
+
+        project.build_arts[0].newest_file = Some(days_ago(20));
+        assert!(is_stale(&project));
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_only_judges_each_rust_target_triple() -> anyhow::Result<()> {
+        use std::fs::{self, File, FileTimes};
+        use std::time::Duration;
+
+        let tmp = tempfile::TempDir::new()?;
+        let root = tmp.path();
+        let days_ago = |days: u64| SystemTime::now() - Duration::from_hours(days * 24);
+
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/main.rs"), "fn main() {}")?;
+        File::options()
+            .write(true)
+            .open(root.join("src/main.rs"))?
+            .set_times(FileTimes::new().set_modified(days_ago(10)))?;
+
+        // Fresh files next to the triples in a split target directory don't count
+        let target = root.join("target-cross");
+        fs::create_dir_all(&target)?;
+        fs::write(target.join(".rustc_info.json"), "{}")?;
+
+        let mut project = create_test_project(
+            ProjectType::Rust,
+            &root.to_string_lossy(),
+            &target.join("debug").to_string_lossy(),
+            1_000_000,
+            Some("test".to_string()),
+        );
+        project.build_arts[0].newest_file = Some(days_ago(1));
+        let mut triple = project.build_arts[0].clone();
+        triple.path = target.join("wasm32-unknown-unknown");
+        triple.newest_file = Some(days_ago(20));
+        project.build_arts.push(triple);
+
+        let stale = retain_stale(project.clone()).map(|p| p.build_arts);
+        assert_eq!(
+            stale.map(|arts| arts.into_iter().map(|a| a.path).collect::<Vec<_>>()),
+            Some(vec![target.join("wasm32-unknown-unknown")])
+        );
+
+        project.build_arts[1].newest_file = Some(days_ago(2));
+        assert!(retain_stale(project).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_risk_leaves_out_riskier_artifacts() {
+        let mut project = create_test_project(
+            ProjectType::Python,
+            "/work/app",
+            "/work/app/__pycache__",
+            1_000,
+            Some("app".to_string()),
+        );
+        let mut venv = project.build_arts[0].clone();
+        venv.path = PathBuf::from("/work/app/.venv");
+        project.build_arts.push(venv);
+
+        let paths = |project: Option<Project>| {
+            project.map(|p| p.build_arts.into_iter().map(|a| a.path).collect::<Vec<_>>())
+        };
+        assert_eq!(
+            paths(retain_max_risk(
+                project.clone(),
+                RiskLevel::Low,
+                &RiskOverrides::default()
+            )),
+            Some(vec![PathBuf::from("/work/app/__pycache__")])
+        );
+        assert_eq!(
+            paths(retain_max_risk(
+                project.clone(),
+                RiskLevel::Medium,
+                &RiskOverrides::default()
+            ))
+            .map(|p| p.len()),
+            Some(2)
+        );
+
+        project.build_arts.remove(0);
+        assert!(retain_max_risk(project, RiskLevel::Low, &RiskOverrides::default()).is_none());
+    }
+
+    #[test]
+    fn test_retain_named_artifacts() {
+        let mut project = create_test_project(
+            ProjectType::Node,
+            "/work/web",
+            "/work/web/node_modules",
+            1_000,
+            Some("web".to_string()),
+        );
+        for dir in [".next", "packages/ui/node_modules", "packages/ui/dist"] {
+            let mut artifact = project.build_arts[0].clone();
+            artifact.path = PathBuf::from("/work/web").join(dir);
+            project.build_arts.push(artifact);
+        }
+
+        let names = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let relative = |project: Option<Project>| {
+            project.map(|p| {
+                p.build_arts
+                    .into_iter()
+                    .map(|a| {
+                        a.path
+                            .strip_prefix("/work/web")
+                            .unwrap_or(&a.path)
+                            .to_path_buf()
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            relative(retain_named(
+                project.clone(),
+                &names(&["node_modules/"]),
+                false
+            )),
+            Some(vec![
+                PathBuf::from(".next"),
+                PathBuf::from("packages/ui/dist")
+            ])
+        );
+        assert_eq!(
+            relative(retain_named(
+                project.clone(),
+                &names(&["./.next", "dist"]),
+                true
+            )),
+            Some(vec![
+                PathBuf::from(".next"),
+                PathBuf::from("packages/ui/dist")
+            ])
+        );
+        assert_eq!(
+            relative(retain_named(
+                project.clone(),
+                &names(&["packages/ui/dist"]),
+                true
+            )),
+            Some(vec![PathBuf::from("packages/ui/dist")])
+        );
+        assert_eq!(
+            retain_named(project.clone(), &[], false).map(|p| p.build_arts.len()),
+            Some(4)
+        );
+        assert!(retain_named(project, &names(&[".svelte-kit"]), true).is_none());
+    }
+
+    #[test]
+    fn test_filter_reasons_lists_every_failed_filter() -> anyhow::Result<()> {
+        let mut project = create_test_project(
+            ProjectType::Python,
+            "/work/app",
+            "/work/app/.venv",
+            500,
+            Some("app".to_string()),
+        );
+        let mut filter_opts = FilterOptions {
+            keep_size: "1KB".to_string(),
+            keep_days: 0,
+            name_pattern: Some("lib*".to_string()),
+            name_filter: None,
+            path_filter: Some("^/work/".to_string()),
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 1,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
+        };
+        let rules = |project: &Project, filter_opts: &FilterOptions| -> anyhow::Result<Vec<_>> {
+            Ok(filter_reasons(project, filter_opts)?
+                .into_iter()
+                .map(|reason| reason.rule)
+                .collect())
+        };
+
+        assert_eq!(
+            rules(&project, &filter_opts)?,
+            vec!["keep_size", "min_files", "name_pattern"]
+        );
+
+        // Once max_risk drops every build directory, nothing else is checked
+        project.protected = true;
+        filter_opts.max_risk = Some(RiskLevel::Low);
+        assert_eq!(
+            rules(&project, &filter_opts)?,
+            vec!["protected", "max_risk"]
+        );
+        filter_opts.max_risk = None;
+        filter_opts.skip_artifacts = vec![".venv".to_string()];
+        assert_eq!(
+            rules(&project, &filter_opts)?,
+            vec!["protected", "skip_artifacts"]
+        );
+
+        let kept = create_test_project(
+            ProjectType::Rust,
+            "/work/lib",
+            "/work/lib/target",
+            5_000,
+            Some("lib".to_string()),
+        );
+        filter_opts.min_files = 0;
+        assert!(filter_reasons(&kept, &filter_opts)?.is_empty());
+        assert_eq!(filter_projects(vec![kept], &filter_opts)?.len(), 1);
+        Ok(())
+    }
+
     // ── Sorting tests ───────────────────────────────────────────────────
 
     #[test]
@@ -338,7 +1074,7 @@ mod tests {
         ];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Size),
+            criteria: vec![SortCriteria::Size],
             reverse: false,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -348,6 +1084,94 @@ mod tests {
         assert_eq!(projects[2].total_size(), 100);
     }
 
+    /// Create a project whose build directory holds `files` files.
+    fn create_project_with_files(root_path: &str, files: u64) -> Project {
+        let mut project = create_test_project(
+            ProjectType::Node,
+            root_path,
+            &format!("{root_path}/node_modules"),
+            1_000,
+            None,
+        );
+        project.build_arts[0].files = files;
+        project
+    }
+
+    #[test]
+    fn test_sort_by_files() {
+        let mut projects = vec![
+            create_project_with_files("/a", 10),
+            create_project_with_files("/b", 50_000),
+            create_project_with_files("/c", 200),
+        ];
+
+        let sort_opts = SortOptions {
+            criteria: vec![SortCriteria::Files],
+            reverse: false,
+        };
+        sort_projects(&mut projects, &sort_opts);
+
+        let files: Vec<u64> = projects.iter().map(Project::total_files).collect();
+        assert_eq!(files, vec![50_000, 200, 10]);
+    }
+
+    #[test]
+    fn test_sort_by_growth_puts_unmeasured_last() {
+        let growths = [
+            ("/a", Some(10)),
+            ("/b", None),
+            ("/c", Some(-5_000)),
+            ("/d", Some(800_000)),
+        ];
+        let mut projects: Vec<Project> = growths
+            .into_iter()
+            .map(|(root, growth)| {
+                let mut project = create_project_with_files(root, 1);
+                project.size_change = growth.map(|bytes| SizeChange { bytes, since: 0 });
+                project
+            })
+            .collect();
+
+        let sort_opts = SortOptions {
+            criteria: vec![SortCriteria::Growth],
+            reverse: false,
+        };
+        sort_projects(&mut projects, &sort_opts);
+
+        let roots: Vec<_> = projects.iter().map(|p| p.root_path.clone()).collect();
+        assert_eq!(roots, ["/d", "/a", "/c", "/b"].map(PathBuf::from));
+    }
+
+    #[test]
+    fn test_min_files_filter() -> anyhow::Result<()> {
+        let projects = vec![
+            create_project_with_files("/a", 10),
+            create_project_with_files("/b", 50_000),
+        ];
+
+        let filter_opts = FilterOptions {
+            keep_size: "0".to_string(),
+            keep_days: 0,
+            name_pattern: None,
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 1_000,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
+        };
+
+        let filtered = filter_projects(projects, &filter_opts)?;
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].root_path, PathBuf::from("/b"));
+        Ok(())
+    }
+
     #[test]
     fn test_sort_by_size_reversed() {
         let mut projects = vec![
@@ -375,7 +1199,7 @@ mod tests {
         ];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Size),
+            criteria: vec![SortCriteria::Size],
             reverse: true,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -412,7 +1236,7 @@ mod tests {
         ];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Name),
+            criteria: vec![SortCriteria::Name],
             reverse: false,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -449,7 +1273,7 @@ mod tests {
         ];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Name),
+            criteria: vec![SortCriteria::Name],
             reverse: false,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -480,7 +1304,7 @@ mod tests {
         ];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Name),
+            criteria: vec![SortCriteria::Name],
             reverse: false,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -574,7 +1398,7 @@ mod tests {
         ];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Type),
+            criteria: vec![SortCriteria::Type],
             reverse: false,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -619,7 +1443,7 @@ mod tests {
         ];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Type),
+            criteria: vec![SortCriteria::Type],
             reverse: true,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -656,7 +1480,7 @@ mod tests {
         ];
 
         let sort_opts = SortOptions {
-            criteria: None,
+            criteria: Vec::new(),
             reverse: false,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -672,7 +1496,7 @@ mod tests {
         let mut projects: Vec<Project> = vec![];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Size),
+            criteria: vec![SortCriteria::Size],
             reverse: false,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -691,7 +1515,7 @@ mod tests {
         )];
 
         let sort_opts = SortOptions {
-            criteria: Some(SortCriteria::Name),
+            criteria: vec![SortCriteria::Name],
             reverse: false,
         };
         sort_projects(&mut projects, &sort_opts);
@@ -700,8 +1524,120 @@ mod tests {
         assert_eq!(projects[0].name.as_deref(), Some("only"));
     }
 
+    #[test]
+    fn test_sort_by_size_then_name() {
+        let mut projects = vec![
+            create_test_project(
+                ProjectType::Rust,
+                "/c",
+                "/c/target",
+                100,
+                Some("Charlie".into()),
+            ),
+            create_test_project(
+                ProjectType::Rust,
+                "/a",
+                "/a/target",
+                200,
+                Some("bravo".into()),
+            ),
+            create_test_project(
+                ProjectType::Rust,
+                "/b",
+                "/b/target",
+                100,
+                Some("alpha".into()),
+            ),
+        ];
+
+        let sort_opts = SortOptions {
+            criteria: vec![SortCriteria::Size, SortCriteria::Name],
+            reverse: false,
+        };
+        sort_projects(&mut projects, &sort_opts);
+
+        assert_eq!(projects[0].name.as_deref(), Some("bravo"));
+        assert_eq!(projects[1].name.as_deref(), Some("alpha"));
+        assert_eq!(projects[2].name.as_deref(), Some("Charlie"));
+    }
+
+    #[test]
+    fn test_sort_ties_broken_by_path() {
+        let build = |order: &[&str]| {
+            order
+                .iter()
+                .map(|root| {
+                    create_test_project(
+                        ProjectType::Rust,
+                        root,
+                        &format!("{root}/target"),
+                        100,
+                        Some("same".into()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let sort_opts = SortOptions {
+            criteria: vec![SortCriteria::Size, SortCriteria::Name],
+            reverse: false,
+        };
+
+        let mut first = build(&["/z", "/m", "/a"]);
+        let mut second = build(&["/a", "/z", "/m"]);
+        sort_projects(&mut first, &sort_opts);
+        sort_projects(&mut second, &sort_opts);
+
+        let roots = |projects: &[Project]| {
+            projects
+                .iter()
+                .map(|p| p.root_path.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(roots(&first), roots(&second));
+        assert_eq!(first[0].root_path, PathBuf::from("/a"));
+        assert_eq!(first[2].root_path, PathBuf::from("/z"));
+    }
+
+    #[test]
+    fn test_sort_multi_key_reversed() {
+        let mut projects = vec![
+            create_test_project(
+                ProjectType::Node,
+                "/n",
+                "/n/node_modules",
+                100,
+                Some("web".into()),
+            ),
+            create_test_project(
+                ProjectType::Rust,
+                "/b",
+                "/b/target",
+                100,
+                Some("beta".into()),
+            ),
+            create_test_project(
+                ProjectType::Rust,
+                "/a",
+                "/a/target",
+                100,
+                Some("alpha".into()),
+            ),
+        ];
+
+        let sort_opts = SortOptions {
+            criteria: vec![SortCriteria::Type, SortCriteria::Name],
+            reverse: true,
+        };
+        sort_projects(&mut projects, &sort_opts);
+
+        assert_eq!(projects[0].name.as_deref(), Some("beta"));
+        assert_eq!(projects[1].name.as_deref(), Some("alpha"));
+        assert_eq!(projects[2].name.as_deref(), Some("web"));
+    }
+
     #[test]
     fn test_type_order_values() {
+        assert!(type_order(&ProjectType::Android) < type_order(&ProjectType::Cpp));
         assert!(type_order(&ProjectType::Cpp) < type_order(&ProjectType::Deno));
         assert!(type_order(&ProjectType::Deno) < type_order(&ProjectType::DotNet));
         assert!(type_order(&ProjectType::DotNet) < type_order(&ProjectType::Elixir));
@@ -800,6 +1736,17 @@ mod tests {
             keep_size: "0".to_string(),
             keep_days: 0,
             name_pattern: Some("my-app*".to_string()),
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
         };
 
         let filtered = filter_projects(projects, &filter_opts)?;
@@ -842,6 +1789,17 @@ mod tests {
             keep_size: "0".to_string(),
             keep_days: 0,
             name_pattern: Some("regex:^client-.*".to_string()),
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
         };
 
         let filtered = filter_projects(projects, &filter_opts)?;
@@ -871,6 +1829,17 @@ mod tests {
             keep_size: "0".to_string(),
             keep_days: 0,
             name_pattern: Some("named*".to_string()),
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
         };
 
         let filtered = filter_projects(projects, &filter_opts)?;
@@ -879,4 +1848,260 @@ mod tests {
         assert_eq!(filtered[0].name.as_deref(), Some("named"));
         Ok(())
     }
+
+    #[test]
+    fn test_filter_projects_name_and_path_filters() -> anyhow::Result<()> {
+        let projects = vec![
+            create_test_project(
+                ProjectType::Rust,
+                "/work/clients/acme-poc",
+                "/work/clients/acme-poc/target",
+                1000,
+                Some("acme-poc".into()),
+            ),
+            create_test_project(
+                ProjectType::Rust,
+                "/work/clients/acme",
+                "/work/clients/acme/target",
+                1000,
+                Some("acme".into()),
+            ),
+            create_test_project(
+                ProjectType::Rust,
+                "/home/scratch/demo-poc",
+                "/home/scratch/demo-poc/target",
+                1000,
+                Some("demo-poc".into()),
+            ),
+        ];
+
+        let filter_opts = FilterOptions {
+            keep_size: "0".to_string(),
+            keep_days: 0,
+            name_pattern: None,
+            name_filter: Some("-poc$".to_string()),
+            path_filter: Some("/clients/".to_string()),
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
+        };
+
+        let filtered = filter_projects(projects, &filter_opts)?;
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name.as_deref(), Some("acme-poc"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_projects_invalid_filter_regex() {
+        let filter_opts = FilterOptions {
+            keep_size: "0".to_string(),
+            keep_days: 0,
+            name_pattern: None,
+            name_filter: None,
+            path_filter: Some("([unclosed".to_string()),
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
+        };
+
+        let result = filter_projects(Vec::new(), &filter_opts);
+        assert!(result.is_err_and(|e| {
+            e.to_string()
+                .contains("Invalid --path-filter regex `([unclosed`")
+        }));
+    }
+
+    #[test]
+    fn test_filter_projects_filter_expr() -> anyhow::Result<()> {
+        let projects = vec![
+            create_test_project(
+                ProjectType::Rust,
+                "/work/api",
+                "/work/api/target",
+                2_000_000_000,
+                Some("api".into()),
+            ),
+            create_test_project(
+                ProjectType::Node,
+                "/work/web",
+                "/work/web/node_modules",
+                400_000_000,
+                Some("web".into()),
+            ),
+            create_test_project(
+                ProjectType::Rust,
+                "/work/cli",
+                "/work/cli/target",
+                400_000_000,
+                Some("cli".into()),
+            ),
+        ];
+        let mut filter_opts = FilterOptions {
+            keep_size: "0".to_string(),
+            keep_days: 0,
+            name_pattern: None,
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: Some("size > 1GB OR type == node AND size > 300MB".to_string()),
+        };
+
+        let reasons = filter_reasons(&projects[2], &filter_opts)?;
+        assert_eq!(
+            reasons.iter().map(|r| r.rule).collect::<Vec<_>>(),
+            vec!["filter_expr"]
+        );
+        let filtered = filter_projects(projects, &filter_opts)?;
+        let names: Vec<_> = filtered.iter().filter_map(|p| p.name.as_deref()).collect();
+        assert_eq!(names, vec!["api", "web"]);
+
+        filter_opts.filter_expr = Some("size >> 1GB".to_string());
+        assert!(filter_projects(Vec::new(), &filter_opts).is_err_and(|e| {
+            e.to_string()
+                .contains("expected a value after `>` at column 7")
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_projects_excludes_protected() -> anyhow::Result<()> {
+        let mut protected = create_test_project(
+            ProjectType::Rust,
+            "/keep",
+            "/keep/target",
+            1000,
+            Some("keep".into()),
+        );
+        protected.protected = true;
+
+        let projects = vec![
+            protected,
+            create_test_project(
+                ProjectType::Rust,
+                "/clean",
+                "/clean/target",
+                1000,
+                Some("clean".into()),
+            ),
+        ];
+
+        let filter_opts = FilterOptions {
+            keep_size: "0".to_string(),
+            keep_days: 0,
+            name_pattern: None,
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
+        };
+
+        let filtered = filter_projects(projects, &filter_opts)?;
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name.as_deref(), Some("clean"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_meets_benefit_criteria() {
+        let small_node =
+            create_test_project(ProjectType::Node, "/js", "/js/node_modules", 5_000, None);
+        let large_node = create_test_project(
+            ProjectType::Node,
+            "/big",
+            "/big/node_modules",
+            50_000_000,
+            None,
+        );
+        let small_python =
+            create_test_project(ProjectType::Python, "/py", "/py/__pycache__", 5_000, None);
+
+        assert!(!meets_benefit_criteria(&small_node, 1_000_000));
+        assert!(meets_benefit_criteria(&large_node, 1_000_000));
+        assert!(meets_benefit_criteria(&small_python, 1_000_000));
+        assert!(meets_benefit_criteria(&small_node, 0));
+    }
+
+    #[test]
+    fn test_filter_projects_min_benefit() -> anyhow::Result<()> {
+        let projects = vec![
+            create_test_project(
+                ProjectType::Node,
+                "/tiny",
+                "/tiny/node_modules",
+                10_000,
+                Some("tiny".into()),
+            ),
+            create_test_project(
+                ProjectType::Python,
+                "/cache",
+                "/cache/__pycache__",
+                10_000,
+                Some("cache".into()),
+            ),
+        ];
+
+        let filter_opts = FilterOptions {
+            keep_size: "0".to_string(),
+            keep_days: 0,
+            name_pattern: None,
+            name_filter: None,
+            path_filter: None,
+            min_benefit: Some("1MB".to_string()),
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
+        };
+
+        let filtered = filter_projects(projects, &filter_opts)?;
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name.as_deref(), Some("cache"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_meets_repo_idle_criteria() {
+        let mut project = create_test_project(ProjectType::Rust, "/r", "/r/target", 1000, None);
+
+        // Unknown last commit is never filtered
+        assert!(meets_repo_idle_criteria(&project, 180));
+
+        project.last_commit = Some(Local::now().timestamp());
+        assert!(meets_repo_idle_criteria(&project, 0));
+        assert!(!meets_repo_idle_criteria(&project, 180));
+
+        project.last_commit = Some((Local::now() - chrono::Duration::days(365)).timestamp());
+        assert!(meets_repo_idle_criteria(&project, 180));
+    }
 }
@@ -0,0 +1,373 @@
+//! Persistent log of past cleanup runs.
+//!
+//! Every cleanup that actually removes build directories appends one JSON
+//! line to `history.jsonl` in the state directory (e.g.
+//! `~/.local/state/clean-dev-dirs/` on Linux, see [`paths`](crate::paths)).
+//! The `history` subcommand reads
+//! it back to list recent runs and the total space reclaimed over time.
+//! Dry runs are not recorded.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write as _,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, FixedOffset, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::cleaner::CleanResult;
+use crate::error::{CleanDevDirsError, Result, ResultExt};
+use crate::paths::Paths;
+use crate::trash_bin::TrashedItem;
+
+/// A single cleanup run in the history log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// When the run finished, in RFC 3339 format
+    pub finished_at: String,
+
+    /// Directories that were scanned, or the plan file that was applied
+    pub roots: Vec<PathBuf>,
+
+    /// Number of projects cleaned successfully
+    pub projects_cleaned: usize,
+
+    /// Number of projects that failed to clean
+    pub failures: usize,
+
+    /// Bytes freed by the run
+    pub bytes_freed: u64,
+
+    /// Trash entries of the build directories the run moved to the trash,
+    /// until they're purged or gone from the trash
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash_items: Vec<TrashedItem>,
+}
+
+impl RunRecord {
+    /// Record a run that just finished with `result`, which left the
+    /// `trash_items` in the trash.
+    #[must_use]
+    pub fn new(roots: Vec<PathBuf>, result: &CleanResult, trash_items: Vec<TrashedItem>) -> Self {
+        Self {
+            finished_at: Local::now().to_rfc3339(),
+            roots,
+            projects_cleaned: result.success_count,
+            failures: result.errors.len(),
+            bytes_freed: result.total_freed,
+            trash_items,
+        }
+    }
+
+    /// When the run finished, or `None` if the timestamp can't be parsed.
+    #[must_use]
+    pub fn finished_at(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.finished_at).ok()
+    }
+}
+
+/// Totals over a set of runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HistoryTotals {
+    /// Number of runs
+    pub runs: usize,
+
+    /// Projects cleaned across all runs
+    pub projects_cleaned: usize,
+
+    /// Bytes freed across all runs
+    pub bytes_freed: u64,
+}
+
+impl HistoryTotals {
+    /// Sum up the given runs.
+    #[must_use]
+    pub fn from_records(records: &[RunRecord]) -> Self {
+        records.iter().fold(Self::default(), |totals, record| Self {
+            runs: totals.runs + 1,
+            projects_cleaned: totals.projects_cleaned + record.projects_cleaned,
+            bytes_freed: totals.bytes_freed + record.bytes_freed,
+        })
+    }
+}
+
+/// Returns the path of the history log.
+///
+/// The log is `history.jsonl` in the [state directory](Paths::state_dir),
+/// or `None` if that cannot be determined.
+#[must_use]
+pub fn history_path(paths: &Paths) -> Option<PathBuf> {
+    paths.state_file("history.jsonl")
+}
+
+/// Append `record` to the history log at `path`, creating it if needed.
+///
+/// # Errors
+///
+/// Returns an error if the log or its directory can't be written.
+pub fn append(path: &Path, record: &RunRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            CleanDevDirsError::state(format!("Failed to create {}", parent.display()))
+        })?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| {
+            CleanDevDirsError::state(format!("Failed to open history log {}", path.display()))
+        })?;
+    let line = serde_json::to_string(record).context(CleanDevDirsError::state(
+        "Failed to serialize the history record",
+    ))?;
+    writeln!(file, "{line}").with_context(|| {
+        CleanDevDirsError::state(format!("Failed to write history log {}", path.display()))
+    })
+}
+
+/// Read every run from the history log at `path`, oldest first.
+///
+/// A missing log means no runs yet. Lines that can't be parsed (e.g. a write
+/// cut short) are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the log exists but can't be read.
+pub fn load(path: &Path) -> Result<Vec<RunRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).with_context(|| {
+        CleanDevDirsError::state(format!("Failed to read history log {}", path.display()))
+    })?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Drop the recorded trash entries from every run in the history log at
+/// `path`, once they've been purged from the trash or are gone from it.
+///
+/// The rest of each run is kept, as are lines that can't be parsed.
+///
+/// # Errors
+///
+/// Returns an error if the log can't be read or written.
+pub fn forget_trash_items(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(path).with_context(|| {
+        CleanDevDirsError::state(format!("Failed to read history log {}", path.display()))
+    })?;
+    let mut rewritten = String::with_capacity(content.len());
+    for line in content.lines() {
+        match serde_json::from_str::<RunRecord>(line) {
+            Ok(record) if !record.trash_items.is_empty() => {
+                let record = RunRecord {
+                    trash_items: Vec::new(),
+                    ..record
+                };
+                let line = serde_json::to_string(&record).context(CleanDevDirsError::state(
+                    "Failed to serialize the history record",
+                ))?;
+                rewritten.push_str(&line);
+            }
+            _ => rewritten.push_str(line),
+        }
+        rewritten.push('\n');
+    }
+
+    let temp = path.with_extension("jsonl.tmp");
+    fs::write(&temp, rewritten)
+        .and_then(|()| fs::rename(&temp, path))
+        .with_context(|| {
+            CleanDevDirsError::state(format!("Failed to write history log {}", path.display()))
+        })
+}
+
+/// Keep only the runs that finished within `age` of now.
+#[must_use]
+pub fn since(records: Vec<RunRecord>, age: Duration) -> Vec<RunRecord> {
+    let Ok(age) = chrono::Duration::from_std(age) else {
+        return records;
+    };
+    let cutoff = Local::now() - age;
+
+    records
+        .into_iter()
+        .filter(|record| record.finished_at().is_some_and(|at| at >= cutoff))
+        .collect()
+}
+
+/// Parse an age such as `"30d"`, `"2w"` or `"12h"` into a duration.
+///
+/// Supported units are `h` (hours), `d` (days) and `w` (weeks).
+///
+/// # Errors
+///
+/// Returns an error if the number or the unit is missing or invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use clean_dev_dirs::history::parse_age;
+/// assert_eq!(parse_age("2d").unwrap(), Duration::from_hours(48));
+/// ```
+pub fn parse_age(age: &str) -> Result<Duration> {
+    let age = age.trim();
+    let split = age.len() - age.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (number, unit) = age.split_at(split);
+
+    let Ok(number) = number.parse::<u64>() else {
+        return Err(CleanDevDirsError::state(format!(
+            "Invalid age '{age}': expected a number followed by h, d or w (e.g. 30d)"
+        )));
+    };
+    let hours = match unit {
+        "h" => 1,
+        "d" => 24,
+        "w" => 7 * 24,
+        _ => {
+            return Err(CleanDevDirsError::state(format!(
+                "Invalid age unit in '{age}': expected h, d or w"
+            )));
+        }
+    };
+
+    number
+        .checked_mul(hours)
+        .map(Duration::from_hours)
+        .ok_or_else(|| CleanDevDirsError::state(format!("Age '{age}' is too large")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(finished_at: &str, bytes_freed: u64) -> RunRecord {
+        RunRecord {
+            finished_at: finished_at.to_string(),
+            roots: vec![PathBuf::from("/work")],
+            projects_cleaned: 2,
+            failures: 0,
+            bytes_freed,
+            trash_items: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_append_and_load() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("nested").join("history.jsonl");
+        assert!(load(&path)?.is_empty());
+
+        let first = record("2026-01-01T10:00:00+00:00", 1_000);
+        let second = record("2026-01-02T10:00:00+00:00", 500);
+        append(&path, &first)?;
+        append(&path, &second)?;
+
+        assert_eq!(load(&path)?, vec![first, second]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_skips_malformed_lines() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("history.jsonl");
+        let good = record("2026-01-01T10:00:00+00:00", 1_000);
+        fs::write(
+            &path,
+            format!("{}\n{{\"finished_at\": \n", serde_json::to_string(&good)?),
+        )?;
+
+        assert_eq!(load(&path)?, vec![good]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_forget_trash_items() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("history.jsonl");
+        let plain = record("2026-01-01T10:00:00+00:00", 1_000);
+        let trashed = RunRecord {
+            trash_items: vec![TrashedItem {
+                original_path: PathBuf::from("/work/app/target"),
+                id: "/home/me/.local/share/Trash/info/target.trashinfo".to_string(),
+                deleted_at: 1_767_261_600,
+            }],
+            ..record("2026-01-02T10:00:00+00:00", 500)
+        };
+        append(&path, &plain)?;
+        append(&path, &trashed)?;
+        fs::write(
+            &path,
+            format!("{}{{\"finished_at\": \n", fs::read_to_string(&path)?),
+        )?;
+
+        forget_trash_items(&path)?;
+
+        let forgotten = RunRecord {
+            trash_items: Vec::new(),
+            ..trashed
+        };
+        assert_eq!(load(&path)?, vec![plain, forgotten]);
+        assert!(fs::read_to_string(&path)?.ends_with("{\"finished_at\": \n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_ignores_trashed_paths_of_older_versions() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("history.jsonl");
+        fs::write(
+            &path,
+            r#"{"finished_at":"2026-01-01T10:00:00+00:00","roots":["/work"],"projects_cleaned":2,"failures":0,"bytes_freed":1000,"trashed":["/work/app/target"]}"#,
+        )?;
+
+        assert_eq!(
+            load(&path)?,
+            vec![record("2026-01-01T10:00:00+00:00", 1_000)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_since_and_totals() {
+        let recent = record(&Local::now().to_rfc3339(), 1_000);
+        let old = record("2020-01-01T00:00:00+00:00", 500);
+        let records = vec![old, recent.clone()];
+
+        assert_eq!(
+            HistoryTotals::from_records(&records),
+            HistoryTotals {
+                runs: 2,
+                projects_cleaned: 4,
+                bytes_freed: 1_500,
+            }
+        );
+
+        let recent_only = since(records, Duration::from_hours(30 * 24));
+        assert_eq!(recent_only, vec![recent]);
+    }
+
+    #[test]
+    fn test_parse_age() -> anyhow::Result<()> {
+        assert_eq!(parse_age("12h")?, Duration::from_hours(12));
+        assert_eq!(parse_age("30d")?, Duration::from_hours(30 * 24));
+        assert_eq!(parse_age("2w")?, Duration::from_hours(14 * 24));
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("3y").is_err());
+        Ok(())
+    }
+}
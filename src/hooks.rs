@@ -0,0 +1,384 @@
+//! Cleanup notifications and user hooks.
+//!
+//! Before each project is cleaned, a user-supplied command
+//! (`[hooks] pre_project_clean`) can run to back up or veto the cleanup; a
+//! non-zero exit status skips that project. The project's fields are
+//! substituted into the command shell-quoted, and are also passed as
+//! environment variables (see [`project_env`]). After a cleanup run completes, the
+//! tool can show a desktop notification (`--notify`) and run another command
+//! (`[hooks] post_clean`) that receives a description of the run through
+//! environment variables, which makes it easy to wire scheduled runs into
+//! logging or chat tooling.
+
+use std::{
+    io,
+    process::{Command, ExitStatus, Stdio},
+};
+
+use notify_rust::Notification;
+
+use crate::{
+    cleaner::CleanResult,
+    error::{CleanDevDirsError, Result},
+    output::type_name,
+    project::Project,
+    utils::{SizeUnits, format_bytes},
+};
+
+/// Environment variables describing a completed run, as passed to hooks.
+///
+/// | Variable | Value |
+/// |----------|-------|
+/// | `CLEAN_DEV_DIRS_PROJECT_COUNT` | Number of projects selected for cleaning |
+/// | `CLEAN_DEV_DIRS_SUCCESS_COUNT` | Number of projects cleaned successfully |
+/// | `CLEAN_DEV_DIRS_FAILURE_COUNT` | Number of projects that failed to clean |
+/// | `CLEAN_DEV_DIRS_TOTAL_FREED` | Bytes freed |
+/// | `CLEAN_DEV_DIRS_TOTAL_FREED_FORMATTED` | Bytes freed, human-readable in `units` (e.g. `1.23 GB`) |
+#[must_use]
+pub fn hook_env(result: &CleanResult, units: SizeUnits) -> Vec<(&'static str, String)> {
+    vec![
+        (
+            "CLEAN_DEV_DIRS_PROJECT_COUNT",
+            (result.success_count + result.errors.len() + result.skipped.len()).to_string(),
+        ),
+        (
+            "CLEAN_DEV_DIRS_SUCCESS_COUNT",
+            result.success_count.to_string(),
+        ),
+        (
+            "CLEAN_DEV_DIRS_FAILURE_COUNT",
+            result.errors.len().to_string(),
+        ),
+        ("CLEAN_DEV_DIRS_TOTAL_FREED", result.total_freed.to_string()),
+        (
+            "CLEAN_DEV_DIRS_TOTAL_FREED_FORMATTED",
+            format_bytes(result.total_freed, units),
+        ),
+    ]
+}
+
+/// Environment variables describing the project a `pre_project_clean` hook
+/// runs for.
+///
+/// | Variable | Value |
+/// |----------|-------|
+/// | `CLEAN_DEV_DIRS_PROJECT_PATH` | The project root directory |
+/// | `CLEAN_DEV_DIRS_PROJECT_NAME` | The project name (empty if unknown) |
+/// | `CLEAN_DEV_DIRS_PROJECT_TYPE` | The project type (e.g. `rust`, `node`) |
+/// | `CLEAN_DEV_DIRS_PROJECT_SIZE` | The total artifact size in bytes |
+#[must_use]
+pub fn project_env(project: &Project) -> Vec<(&'static str, String)> {
+    vec![
+        (
+            "CLEAN_DEV_DIRS_PROJECT_PATH",
+            project.root_path.display().to_string(),
+        ),
+        (
+            "CLEAN_DEV_DIRS_PROJECT_NAME",
+            project.name.clone().unwrap_or_default(),
+        ),
+        (
+            "CLEAN_DEV_DIRS_PROJECT_TYPE",
+            type_name(&project.kind).to_string(),
+        ),
+        (
+            "CLEAN_DEV_DIRS_PROJECT_SIZE",
+            project.total_size().to_string(),
+        ),
+    ]
+}
+
+/// Build a command that runs `command` through the platform shell.
+///
+/// Uses `sh -c` on Unix and `cmd /C` on Windows.
+fn shell_command(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    shell.arg(command);
+    shell
+}
+
+/// Quote a value so the platform shell treats it as a single argument.
+fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Substitute project fields into a `pre_project_clean` command template.
+///
+/// Supported placeholders, each replaced by a shell-quoted value:
+///
+/// - `{path}` - the project root directory
+/// - `{name}` - the project name (empty if unknown)
+/// - `{type}` - the project type (e.g. `rust`, `node`)
+/// - `{size}` - the total artifact size in bytes
+///
+/// The template is expanded in a single pass from left to right, so
+/// placeholders that appear in a substituted value (such as a directory
+/// named `{name}`) are left as they are.
+#[must_use]
+pub fn expand_project_template(template: &str, project: &Project) -> String {
+    let path = project.root_path.display().to_string();
+    let size = project.total_size().to_string();
+    let values = [
+        ("path", path.as_str()),
+        ("name", project.name.as_deref().unwrap_or("")),
+        ("type", type_name(&project.kind)),
+        ("size", size.as_str()),
+    ];
+
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let placeholder = rest
+            .split_once('}')
+            .and_then(|(name, after)| Some((values.iter().find(|(n, _)| *n == name)?, after)));
+        if let Some(((_, value), after)) = placeholder {
+            expanded.push_str(&shell_quote(value));
+            rest = after;
+        } else {
+            expanded.push('{');
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// A `pre_project_clean` hook, run before each project is cleaned.
+#[derive(Clone, Copy, Debug)]
+pub struct PreCleanHook<'a> {
+    /// Command template, see [`expand_project_template`]
+    pub template: &'a str,
+
+    /// Send what the hook prints to stdout to stderr instead, like
+    /// [`run_post_clean`] does
+    pub stdout_to_stderr: bool,
+}
+
+/// Run the `pre_project_clean` hook for a single project.
+///
+/// Returns `None` if the project may be cleaned, or the exit status of the
+/// hook if it vetoed the cleanup. With `stdout_to_stderr`, what the hook
+/// prints to stdout goes to stderr instead, so that it doesn't end up in JSON
+/// or other structured output printed on stdout.
+///
+/// # Errors
+///
+/// Returns an error if the shell cannot be started, in which case the
+/// project must not be cleaned either.
+pub fn run_pre_project_clean(
+    template: &str,
+    project: &Project,
+    stdout_to_stderr: bool,
+) -> Result<Option<ExitStatus>> {
+    let command = expand_project_template(template, project);
+    let mut shell = shell_command(&command);
+    if stdout_to_stderr {
+        shell.stdout(Stdio::from(io::stderr()));
+    }
+    let status = shell.envs(project_env(project)).status().map_err(|e| {
+        CleanDevDirsError::cleanup(format!("Failed to run pre_project_clean hook `{command}`"))
+            .with_source(e)
+    })?;
+
+    Ok((!status.success()).then_some(status))
+}
+
+/// Run the `post_clean` hook command through the platform shell.
+///
+/// The variables from [`hook_env`] are added to the command's environment.
+/// Its output is not captured. With `stdout_to_stderr`, what it prints to
+/// stdout goes to stderr instead, so that it doesn't end up in JSON or other
+/// structured output printed on stdout.
+///
+/// # Errors
+///
+/// Returns an error if the shell cannot be started.
+pub fn run_post_clean(
+    command: &str,
+    result: &CleanResult,
+    units: SizeUnits,
+    stdout_to_stderr: bool,
+) -> Result<ExitStatus> {
+    let mut shell = shell_command(command);
+    if stdout_to_stderr {
+        shell.stdout(Stdio::from(io::stderr()));
+    }
+    shell.envs(hook_env(result, units)).status().map_err(|e| {
+        CleanDevDirsError::cleanup(format!("Failed to run post_clean hook `{command}`"))
+            .with_source(e)
+    })
+}
+
+/// Show a desktop notification summarizing a completed run.
+///
+/// # Errors
+///
+/// Returns an error if no notification service is available.
+pub fn send_notification(result: &CleanResult, units: SizeUnits) -> Result<()> {
+    let failed = if result.errors.is_empty() {
+        String::new()
+    } else {
+        format!(" ({} failed)", result.errors.len())
+    };
+    let body = format!(
+        "Freed {} from {} projects{failed}",
+        format_bytes(result.total_freed, units),
+        result.success_count
+    );
+
+    Notification::new()
+        .summary("clean-dev-dirs")
+        .body(&body)
+        .show()
+        .map_err(|e| CleanDevDirsError::cleanup("Failed to show notification").with_source(e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::CleanFailure;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use std::path::PathBuf;
+
+    fn sample_project(root: &str) -> Project {
+        Project::new(
+            ProjectType::Rust,
+            PathBuf::from(root),
+            vec![BuildArtifacts {
+                path: PathBuf::from(root).join("target"),
+                size: 2048,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some("demo".to_string()),
+        )
+    }
+
+    fn sample_result() -> CleanResult {
+        CleanResult {
+            success_count: 2,
+            total_freed: 1_500_000,
+            estimated_freed: 1_500_000,
+            verified_freed: None,
+            estimated_size: 1_500_000,
+            errors: vec![CleanFailure {
+                root: PathBuf::from("/p"),
+                message: "denied".to_string(),
+            }],
+            skipped: Vec::new(),
+            trashed: Vec::new(),
+            permission_denied: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_hook_env() {
+        let env = hook_env(&sample_result(), SizeUnits::Decimal);
+        let get = |key: &str| env.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str());
+
+        assert_eq!(get("CLEAN_DEV_DIRS_PROJECT_COUNT"), Some("3"));
+        assert_eq!(get("CLEAN_DEV_DIRS_SUCCESS_COUNT"), Some("2"));
+        assert_eq!(get("CLEAN_DEV_DIRS_FAILURE_COUNT"), Some("1"));
+        assert_eq!(get("CLEAN_DEV_DIRS_TOTAL_FREED"), Some("1500000"));
+        assert_eq!(get("CLEAN_DEV_DIRS_TOTAL_FREED_FORMATTED"), Some("1.50 MB"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_post_clean_passes_env() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let out = tmp.path().join("out.txt");
+        let command = format!(
+            "echo \"$CLEAN_DEV_DIRS_SUCCESS_COUNT $CLEAN_DEV_DIRS_TOTAL_FREED\" > '{}'",
+            out.display()
+        );
+
+        let status = run_post_clean(&command, &sample_result(), SizeUnits::Decimal, false)?;
+
+        assert!(status.success());
+        assert_eq!(std::fs::read_to_string(&out)?.trim(), "2 1500000");
+
+        let status = run_post_clean(&command, &sample_result(), SizeUnits::Decimal, true)?;
+        assert!(status.success());
+        assert_eq!(std::fs::read_to_string(out)?.trim(), "2 1500000");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expand_project_template_quotes_values() {
+        let project = sample_project("/work/it's here");
+        let command = expand_project_template("backup.sh {path} {type} {name} {size}", &project);
+
+        assert_eq!(
+            command,
+            "backup.sh '/work/it'\\''s here' 'rust' 'demo' '2048'"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expand_project_template_does_not_expand_values() {
+        let mut project = sample_project("/work/{name}");
+        project.name = Some("$(touch pwned) {path}".to_string());
+        let command = expand_project_template("backup.sh {path} {name} {unknown}", &project);
+
+        assert_eq!(
+            command,
+            "backup.sh '/work/{name}' '$(touch pwned) {path}' {unknown}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pre_project_clean_exit_status() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let project = sample_project(&tmp.path().display().to_string());
+
+        assert_eq!(
+            run_pre_project_clean("test -d {path}", &project, false)?,
+            None
+        );
+        let vetoed = run_pre_project_clean("exit 3", &project, false)?;
+        assert_eq!(vetoed.and_then(|status| status.code()), Some(3));
+        assert_eq!(
+            run_pre_project_clean(
+                "test \"$CLEAN_DEV_DIRS_PROJECT_SIZE\" = 2048",
+                &project,
+                false
+            )?,
+            None
+        );
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_run_pre_project_clean_stdout_to_stderr() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let project = sample_project(&tmp.path().display().to_string());
+
+        // The hook prints to stdout, which must be the same file as stderr
+        let hook = "echo backing up {name} && \
+                    test \"$(readlink /proc/$$/fd/1)\" = \"$(readlink /proc/$$/fd/2)\"";
+        assert_eq!(run_pre_project_clean(hook, &project, true)?, None);
+        Ok(())
+    }
+}
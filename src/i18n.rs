@@ -0,0 +1,581 @@
+//! Translations of the messages shown to users.
+//!
+//! Summaries, prompts and the most common errors are looked up in a small
+//! message catalog instead of being written out in English. Every
+//! [`Message`] has a text in each supported [`Lang`]; placeholders such as
+//! `{count}` are filled in by [`Message::format`], so a translation can put
+//! them wherever its grammar needs them.
+//!
+//! The language is chosen once per run and passed along in
+//! [`DisplayOptions`](crate::config::DisplayOptions): `--lang`, then
+//! `lang` in the config file, then the locale environment variables
+//! (`LC_ALL`, `LC_MESSAGES`, `LANG`), falling back to English. Output meant
+//! for machines (`--json`, `--output`, hook environment variables) is never
+//! translated.
+
+use std::fmt::Display;
+
+use clap::ValueEnum;
+
+/// A language messages can be shown in, selectable with `--lang`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum Lang {
+    /// English
+    #[default]
+    En,
+
+    /// German
+    De,
+}
+
+impl Lang {
+    /// The code of the language, as accepted by `--lang`.
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+        }
+    }
+
+    /// The language of a locale name such as `de_DE.UTF-8`, if supported.
+    #[must_use]
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let language = locale
+            .split(['_', '.', '@', '-'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Self::En),
+            "de" => Some(Self::De),
+            _ => None,
+        }
+    }
+
+    /// The language of the user's locale, from the first of `LC_ALL`,
+    /// `LC_MESSAGES` and `LANG` that is set and not empty.
+    ///
+    /// Unsupported languages and unset variables give English.
+    #[must_use]
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+}
+
+/// A message shown to users, with a text in every [`Lang`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Message {
+    /// Progress message when permanent deletion starts
+    StartingCleanup,
+    /// Progress message when moving to the trash starts
+    MovingToTrash,
+    /// Final progress message after permanent deletion
+    CleanupComplete,
+    /// Final progress message after moving to the trash
+    MovedToTrashDone,
+    /// Heading of the errors of a cleanup
+    SomeErrors,
+    /// Heading of the cleanup summary
+    CleanupSummary,
+    /// Number of projects cleaned (`{count}`)
+    Cleaned,
+    /// Number of projects that failed (`{count}`)
+    FailedToClean,
+    /// Number of projects deliberately left alone (`{count}`)
+    Skipped,
+    /// Space freed by a permanent deletion (`{size}`)
+    SpaceFreed,
+    /// Space moved to the trash (`{size}`)
+    MovedToTrash,
+    /// Marks a size that was verified
+    Verified,
+    /// Notes that trashed space isn't free yet
+    NotYetReclaimed,
+    /// How to empty the trash with the tool
+    RunTrashEmpty,
+    /// How to empty the trash without the tool
+    EmptyTrash,
+    /// Scan estimate next to a verified size (`{size}`, `{difference}`)
+    ScanEstimate,
+    /// Number of projects found by a scan (`{count}`)
+    FoundCount,
+    /// Heading of the list of projects found
+    FoundProjects,
+    /// Space the projects found would free (`{size}`)
+    TotalReclaimable,
+    /// Space the projects found would free, as a lower bound (`{size}`)
+    TotalReclaimableAtLeast,
+    /// Heading of the dry run result
+    DryRunComplete,
+    /// Space a dry run would free (`{size}`)
+    WouldFree,
+    /// Space a dry run would free, as a lower bound (`{size}`)
+    WouldFreeAtLeast,
+    /// Confirmation before moving to the trash (`{count}`, `{dirs}`, `{size}`)
+    ConfirmTrash,
+    /// Confirmation before deleting permanently (`{count}`, `{dirs}`, `{size}`)
+    ConfirmDelete,
+    /// One directory, for `{dirs}`
+    Directory,
+    /// Several directories, for `{dirs}`
+    Directories,
+    /// The cleanup was not confirmed
+    CleanupCancelled,
+    /// A scan found nothing to clean
+    NothingFound,
+    /// Nothing in a plan can still be cleaned
+    PlanEmpty,
+    /// Confirmation is needed but there's no terminal
+    NoTerminal,
+    /// The first run was turned into a dry run
+    SafeMode,
+    /// Title of the interactive selection
+    SelectProjects,
+    /// Running total of the interactive selection (`{selected}`, `{total}`, `{size}`)
+    SelectionTotal,
+    /// Keys of the interactive selection
+    SelectionHelp,
+    /// Keys while typing a filter in the interactive selection
+    FilterHelp,
+    /// Keys while the details of a project are shown
+    DetailsHelp,
+    /// Shown in place of the selection when it is cancelled
+    Cancelled,
+    /// Error when the interactive selection is cancelled
+    SelectionCancelled,
+    /// An error that ended the run (`{error}`)
+    Error,
+    /// Prefix of a warning
+    Warning,
+    /// A tool is working in a project about to be cleaned (`{path}`, `{tools}`)
+    ToolsInUse,
+    /// Whether to clean a project a tool is working in (`{path}`)
+    CleanAnyway,
+    /// Build directories that couldn't be removed for lack of permission (`{count}`)
+    PermissionDenied,
+    /// Why permission was likely denied
+    OwnedByRoot,
+    /// Introduces the command that removes them
+    RunToRemove,
+    /// Introduces the command that removes them, on Windows
+    RunAsAdministrator,
+    /// No project is left once the filters are applied
+    NoMatches,
+    /// Nothing was picked in the interactive selection
+    NoneSelected,
+    /// Whether to keep executables before cleaning
+    KeepExecutables,
+    /// Another run holds the instance lock and this one waits
+    WaitingForLock,
+    /// Another run holds the instance lock and this one is skipped
+    LockHeld,
+    /// A cleanup plan was written (`{count}`, `{path}`)
+    PlanSaved,
+    /// A cleanup plan was read (`{path}`, `{created}`)
+    PlanLoaded,
+    /// A directory of a plan is left alone (`{reason}`)
+    PlanRejected,
+    /// Heading of the projects of a plan
+    PlannedProjects,
+    /// Progress message when a remote scan starts (`{host}`)
+    ScanningHost,
+    /// A remote scan found nothing to clean
+    HostNothingToClean,
+    /// No remote scan found anything to clean
+    NothingOnAnyHost,
+    /// What a remote scan found (`{host}`, `{count}`, `{size}`)
+    HostProjects,
+    /// Confirmation before cleaning remote machines (`{count}`, `{size}`, `{hosts}`)
+    ConfirmRemote,
+    /// Progress message when a remote cleanup starts (`{host}`)
+    CleaningHost,
+    /// Result of a remote cleanup (`{host}`, `{count}`, `{size}`)
+    HostCleaned,
+    /// Docker has nothing to prune
+    NoDockerData,
+    /// Docker data, for `{what}`
+    DockerData,
+    /// No package manager cache has anything to clean
+    NoSystemCaches,
+    /// Heading of the package manager caches
+    SystemCaches,
+    /// Package manager caches, for `{what}`
+    SystemCachesNoun,
+    /// Confirmation before removing Docker data or caches (`{size}`, `{what}`)
+    ConfirmRemove,
+    /// Removing Docker data or caches was not confirmed
+    RemovalCancelled,
+    /// Space freed from Docker data or caches (`{what}`, `{size}`)
+    SpaceFreedFrom,
+}
+
+impl Message {
+    /// Every message, for checking the catalog.
+    pub const ALL: [Self; 71] = [
+        Self::StartingCleanup,
+        Self::MovingToTrash,
+        Self::CleanupComplete,
+        Self::MovedToTrashDone,
+        Self::SomeErrors,
+        Self::CleanupSummary,
+        Self::Cleaned,
+        Self::FailedToClean,
+        Self::Skipped,
+        Self::SpaceFreed,
+        Self::MovedToTrash,
+        Self::Verified,
+        Self::NotYetReclaimed,
+        Self::RunTrashEmpty,
+        Self::EmptyTrash,
+        Self::ScanEstimate,
+        Self::FoundCount,
+        Self::FoundProjects,
+        Self::TotalReclaimable,
+        Self::TotalReclaimableAtLeast,
+        Self::DryRunComplete,
+        Self::WouldFree,
+        Self::WouldFreeAtLeast,
+        Self::ConfirmTrash,
+        Self::ConfirmDelete,
+        Self::Directory,
+        Self::Directories,
+        Self::CleanupCancelled,
+        Self::NothingFound,
+        Self::PlanEmpty,
+        Self::NoTerminal,
+        Self::SafeMode,
+        Self::SelectProjects,
+        Self::SelectionTotal,
+        Self::SelectionHelp,
+        Self::FilterHelp,
+        Self::DetailsHelp,
+        Self::Cancelled,
+        Self::SelectionCancelled,
+        Self::Error,
+        Self::Warning,
+        Self::ToolsInUse,
+        Self::CleanAnyway,
+        Self::PermissionDenied,
+        Self::OwnedByRoot,
+        Self::RunToRemove,
+        Self::RunAsAdministrator,
+        Self::NoMatches,
+        Self::NoneSelected,
+        Self::KeepExecutables,
+        Self::WaitingForLock,
+        Self::LockHeld,
+        Self::PlanSaved,
+        Self::PlanLoaded,
+        Self::PlanRejected,
+        Self::PlannedProjects,
+        Self::ScanningHost,
+        Self::HostNothingToClean,
+        Self::NothingOnAnyHost,
+        Self::HostProjects,
+        Self::ConfirmRemote,
+        Self::CleaningHost,
+        Self::HostCleaned,
+        Self::NoDockerData,
+        Self::DockerData,
+        Self::NoSystemCaches,
+        Self::SystemCaches,
+        Self::SystemCachesNoun,
+        Self::ConfirmRemove,
+        Self::RemovalCancelled,
+        Self::SpaceFreedFrom,
+    ];
+
+    /// The text of the message in `lang`, with its placeholders unfilled.
+    #[must_use]
+    pub const fn text(self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::En => self.english(),
+            Lang::De => self.german(),
+        }
+    }
+
+    /// The text of the message in `lang`, with each `{name}` placeholder
+    /// replaced by its value from `args`.
+    #[must_use]
+    pub fn format(self, lang: Lang, args: &[(&str, &dyn Display)]) -> String {
+        fill(self.text(lang), args)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    const fn english(self) -> &'static str {
+        match self {
+            Self::StartingCleanup => "Starting cleanup...",
+            Self::MovingToTrash => "Moving to trash...",
+            Self::CleanupComplete => "[OK] Cleanup complete",
+            Self::MovedToTrashDone => "[OK] Moved to trash",
+            Self::SomeErrors => "[!] Some errors occurred during cleanup:",
+            Self::CleanupSummary => "Cleanup Summary:",
+            Self::Cleaned => "[OK] Successfully cleaned: {count} projects",
+            Self::FailedToClean => "[FAIL] Failed to clean: {count} projects",
+            Self::Skipped => "[SKIP] Skipped: {count} projects",
+            Self::SpaceFreed => "Total space freed: {size}",
+            Self::MovedToTrash => "Moved to trash: {size}",
+            Self::Verified => "(verified)",
+            Self::NotYetReclaimed => "(space not yet reclaimed)",
+            Self::RunTrashEmpty => "Run `clean-dev-dirs trash empty` to reclaim it",
+            Self::EmptyTrash => "Empty the trash to reclaim it",
+            Self::ScanEstimate => "Scan estimate: {size} (off by {difference})",
+            Self::FoundCount => "Found {count} projects",
+            Self::FoundProjects => "Found projects:",
+            Self::TotalReclaimable => "Total reclaimable space: {size}",
+            Self::TotalReclaimableAtLeast => "Total reclaimable space: at least {size}",
+            Self::DryRunComplete => "[dry-run] Complete.",
+            Self::WouldFree => "Would free up {size}",
+            Self::WouldFreeAtLeast => "Would free up at least {size}",
+            Self::ConfirmTrash => {
+                "About to move {count} {dirs} totalling {size} to the trash. Continue?"
+            }
+            Self::ConfirmDelete => {
+                "About to permanently delete {count} {dirs} totalling {size}. Continue?"
+            }
+            Self::Directory => "directory",
+            Self::Directories => "directories",
+            Self::CleanupCancelled => "Cleanup cancelled, nothing was removed.",
+            Self::NothingFound => "No development directories found!",
+            Self::PlanEmpty => "Nothing in the plan is left to clean!",
+            Self::NoTerminal => {
+                "Cannot ask for confirmation without a terminal; pass --yes to clean"
+            }
+            Self::SafeMode => {
+                "First run: safe mode is on, so this is a dry run and nothing will be removed. \
+                 Run again with --yes, or create a config file with `clean-dev-dirs config init` \
+                 to turn safe mode off."
+            }
+            Self::SelectProjects => "Select projects to clean:",
+            Self::SelectionTotal => "{selected} of {total} selected, {size}",
+            Self::SelectionHelp => {
+                "[↑↓ move, space toggle, t toggle type, → all, ← none, / filter, d details, \
+                 enter confirm, esc cancel]"
+            }
+            Self::FilterHelp => "[type to filter by name, path or type, enter done, esc clear]",
+            Self::DetailsHelp => "[any key to go back]",
+            Self::Cancelled => "cancelled",
+            Self::SelectionCancelled => "Selection cancelled",
+            Self::Error => "Error: {error}",
+            Self::Warning => "Warning:",
+            Self::ToolsInUse => {
+                "{path} is in use by {tools}; cleaning it may cause confusing errors there"
+            }
+            Self::CleanAnyway => "Clean {path} anyway?",
+            Self::PermissionDenied => {
+                "{count} build directories could not be removed: permission denied."
+            }
+            Self::OwnedByRoot => {
+                "They are likely owned by root, e.g. created by a Docker container."
+            }
+            Self::RunToRemove => "To remove them, run:",
+            Self::RunAsAdministrator => "To remove them, run from an administrator prompt:",
+            Self::NoMatches => "No directories match the specified criteria!",
+            Self::NoneSelected => "No projects selected for cleaning!",
+            Self::KeepExecutables => "Keep compiled executables before cleaning?",
+            Self::WaitingForLock => "Waiting for another clean-dev-dirs run to finish...",
+            Self::LockHeld => "Another clean-dev-dirs run is already cleaning, skipping this run.",
+            Self::PlanSaved => "Plan with {count} build directories written to {path}",
+            Self::PlanLoaded => "Loaded plan from {path} (created {created})",
+            Self::PlanRejected => "{reason} - skipped",
+            Self::PlannedProjects => "Planned projects:",
+            Self::ScanningHost => "Scanning {host} over SSH...",
+            Self::HostNothingToClean => "nothing to clean",
+            Self::NothingOnAnyHost => "No directories to clean on any host!",
+            Self::HostProjects => "{host}: {count} projects ({size})",
+            Self::ConfirmRemote => {
+                "About to clean {count} projects totalling {size} on {hosts} hosts. Continue?"
+            }
+            Self::CleaningHost => "Cleaning {host}...",
+            Self::HostCleaned => "{host}: cleaned {count} projects, freed {size}",
+            Self::NoDockerData => "No Docker data to prune!",
+            Self::DockerData => "Docker data",
+            Self::NoSystemCaches => "No system caches to clean!",
+            Self::SystemCaches => "System caches",
+            Self::SystemCachesNoun => "system caches",
+            Self::ConfirmRemove => "About to remove {size} of {what}. Continue?",
+            Self::RemovalCancelled => "Cancelled, nothing was removed.",
+            Self::SpaceFreedFrom => "Space freed from {what}: {size}",
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    const fn german(self) -> &'static str {
+        match self {
+            Self::StartingCleanup => "Aufräumen beginnt...",
+            Self::MovingToTrash => "Verschieben in den Papierkorb...",
+            Self::CleanupComplete => "[OK] Aufräumen abgeschlossen",
+            Self::MovedToTrashDone => "[OK] In den Papierkorb verschoben",
+            Self::SomeErrors => "[!] Beim Aufräumen sind Fehler aufgetreten:",
+            Self::CleanupSummary => "Zusammenfassung:",
+            Self::Cleaned => "[OK] Erfolgreich aufgeräumt: {count} Projekte",
+            Self::FailedToClean => "[FEHLER] Nicht aufgeräumt: {count} Projekte",
+            Self::Skipped => "[SKIP] Übersprungen: {count} Projekte",
+            Self::SpaceFreed => "Freigegebener Speicherplatz: {size}",
+            Self::MovedToTrash => "In den Papierkorb verschoben: {size}",
+            Self::Verified => "(geprüft)",
+            Self::NotYetReclaimed => "(Speicherplatz noch nicht freigegeben)",
+            Self::RunTrashEmpty => "`clean-dev-dirs trash empty` gibt ihn frei",
+            Self::EmptyTrash => "Leeren Sie den Papierkorb, um ihn freizugeben",
+            Self::ScanEstimate => "Schätzung des Scans: {size} (Abweichung {difference})",
+            Self::FoundCount => "{count} Projekte gefunden",
+            Self::FoundProjects => "Gefundene Projekte:",
+            Self::TotalReclaimable => "Freizugebender Speicherplatz: {size}",
+            Self::TotalReclaimableAtLeast => "Freizugebender Speicherplatz: mindestens {size}",
+            Self::DryRunComplete => "[Probelauf] Abgeschlossen.",
+            Self::WouldFree => "Würde {size} freigeben",
+            Self::WouldFreeAtLeast => "Würde mindestens {size} freigeben",
+            Self::ConfirmTrash => {
+                "{count} {dirs} mit insgesamt {size} in den Papierkorb verschieben. Fortfahren?"
+            }
+            Self::ConfirmDelete => {
+                "{count} {dirs} mit insgesamt {size} endgültig löschen. Fortfahren?"
+            }
+            Self::Directory => "Verzeichnis",
+            Self::Directories => "Verzeichnisse",
+            Self::CleanupCancelled => "Aufräumen abgebrochen, nichts wurde entfernt.",
+            Self::NothingFound => "Keine Entwicklungsverzeichnisse gefunden!",
+            Self::PlanEmpty => "Im Plan ist nichts mehr aufzuräumen!",
+            Self::NoTerminal => {
+                "Ohne Terminal ist keine Rückfrage möglich; zum Aufräumen --yes angeben"
+            }
+            Self::SafeMode => {
+                "Erster Lauf: Der sichere Modus ist aktiv, daher ist dies ein Probelauf und \
+                 nichts wird entfernt. Zum Aufräumen erneut mit --yes ausführen oder mit \
+                 `clean-dev-dirs config init` eine Konfigurationsdatei anlegen, um den sicheren \
+                 Modus abzuschalten."
+            }
+            Self::SelectProjects => "Aufzuräumende Projekte auswählen:",
+            Self::SelectionTotal => "{selected} von {total} ausgewählt, {size}",
+            Self::SelectionHelp => {
+                "[↑↓ bewegen, Leertaste umschalten, t Typ umschalten, → alle, ← keine, \
+                 / filtern, d Details, Enter bestätigen, Esc abbrechen]"
+            }
+            Self::FilterHelp => {
+                "[tippen, um nach Name, Pfad oder Typ zu filtern, Enter fertig, Esc löschen]"
+            }
+            Self::DetailsHelp => "[beliebige Taste für zurück]",
+            Self::Cancelled => "abgebrochen",
+            Self::SelectionCancelled => "Auswahl abgebrochen",
+            Self::Error => "Fehler: {error}",
+            Self::Warning => "Warnung:",
+            Self::ToolsInUse => {
+                "{path} wird von {tools} verwendet; Aufräumen kann dort zu verwirrenden \
+                 Fehlern führen"
+            }
+            Self::CleanAnyway => "{path} trotzdem aufräumen?",
+            Self::PermissionDenied => {
+                "{count} Build-Verzeichnisse konnten nicht entfernt werden: Zugriff verweigert."
+            }
+            Self::OwnedByRoot => {
+                "Sie gehören vermutlich root, z. B. weil ein Docker-Container sie angelegt hat."
+            }
+            Self::RunToRemove => "Zum Entfernen ausführen:",
+            Self::RunAsAdministrator => {
+                "Zum Entfernen in einer Eingabeaufforderung als Administrator ausführen:"
+            }
+            Self::NoMatches => "Keine Verzeichnisse entsprechen den angegebenen Kriterien!",
+            Self::NoneSelected => "Keine Projekte zum Aufräumen ausgewählt!",
+            Self::KeepExecutables => "Kompilierte Programme vor dem Aufräumen behalten?",
+            Self::WaitingForLock => "Warte auf das Ende eines anderen Laufs von clean-dev-dirs...",
+            Self::LockHeld => {
+                "Ein anderer Lauf von clean-dev-dirs räumt bereits auf, dieser Lauf wird übersprungen."
+            }
+            Self::PlanSaved => "Plan mit {count} Build-Verzeichnissen nach {path} geschrieben",
+            Self::PlanLoaded => "Plan aus {path} geladen (erstellt {created})",
+            Self::PlanRejected => "{reason} - übersprungen",
+            Self::PlannedProjects => "Geplante Projekte:",
+            Self::ScanningHost => "Durchsuche {host} über SSH...",
+            Self::HostNothingToClean => "nichts aufzuräumen",
+            Self::NothingOnAnyHost => "Auf keinem Host gibt es Verzeichnisse aufzuräumen!",
+            Self::HostProjects => "{host}: {count} Projekte ({size})",
+            Self::ConfirmRemote => {
+                "{count} Projekte mit insgesamt {size} auf {hosts} Hosts aufräumen. Fortfahren?"
+            }
+            Self::CleaningHost => "Räume {host} auf...",
+            Self::HostCleaned => "{host}: {count} Projekte aufgeräumt, {size} freigegeben",
+            Self::NoDockerData => "Keine Docker-Daten zu bereinigen!",
+            Self::DockerData => "Docker-Daten",
+            Self::NoSystemCaches => "Keine System-Caches aufzuräumen!",
+            Self::SystemCaches | Self::SystemCachesNoun => "System-Caches",
+            Self::ConfirmRemove => "{size} an {what} entfernen. Fortfahren?",
+            Self::RemovalCancelled => "Abgebrochen, nichts wurde entfernt.",
+            Self::SpaceFreedFrom => "Von {what} freigegebener Speicherplatz: {size}",
+        }
+    }
+}
+
+/// Replace each `{name}` in `template` with the value of `name` in `args`.
+fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `{name}` placeholders of `text`, sorted.
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_every_translation_has_the_same_placeholders() {
+        for message in Message::ALL {
+            let listed = Message::ALL.iter().filter(|m| **m == message).count();
+            assert_eq!(listed, 1, "{message:?} is listed {listed} times");
+            let english = message.text(Lang::En);
+            let german = message.text(Lang::De);
+            assert!(!german.is_empty(), "{message:?} has no German text");
+            assert_eq!(
+                placeholders(english),
+                placeholders(german),
+                "placeholders of {message:?} differ"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fill_replaces_placeholders() {
+        assert_eq!(
+            fill(
+                Message::ConfirmDelete.text(Lang::En),
+                &[
+                    ("count", &2),
+                    ("dirs", &"directories"),
+                    ("size", &"1.00 GB")
+                ]
+            ),
+            "About to permanently delete 2 directories totalling 1.00 GB. Continue?"
+        );
+        assert_eq!(
+            fill(Message::FoundCount.text(Lang::De), &[("count", &7)]),
+            "7 Projekte gefunden"
+        );
+    }
+
+    #[test]
+    fn test_lang_from_locale() {
+        assert_eq!(Lang::from_locale("de_DE.UTF-8"), Some(Lang::De));
+        assert_eq!(Lang::from_locale("de_AT@euro"), Some(Lang::De));
+        assert_eq!(Lang::from_locale("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("C.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("fr_FR.UTF-8"), None);
+        assert_eq!(Lang::from_locale(""), None);
+    }
+}
@@ -0,0 +1,397 @@
+//! Crash-safe journal of the removals of a cleanup run.
+//!
+//! When the process dies in the middle of a cleanup (killed, out of memory,
+//! or the machine lost power), it isn't clear what was removed and what is
+//! left half-deleted. With `journal` (or `--journal`), a cleanup writes
+//! `journal.jsonl` to the [state directory](Paths::state_dir): one
+//! line when the run starts, one before and one after each build directory
+//! is removed, and one when the run is done. Each line is on disk before
+//! the removal it announces begins, so the journal is accurate up to the
+//! moment the process stopped. Paths are recorded as absolute paths, so the
+//! journal means the same from any directory. The `status` subcommand reads
+//! it back with [`load`] and [`JournalSummary`].
+//!
+//! Only the latest run is kept: starting a run replaces the journal.
+
+use std::{
+    fs::{self, File},
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cleaner::RemovalStrategy,
+    error::{CleanDevDirsError, Result, ResultExt},
+    paths::Paths,
+};
+
+/// What a journal line records.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// A cleanup run started
+    Started {
+        /// Process ID of the run
+        pid: u32,
+
+        /// Directories that were scanned, or the plan file that was applied
+        roots: Vec<PathBuf>,
+    },
+
+    /// A build directory is about to be removed
+    Removing {
+        /// The build directory
+        path: PathBuf,
+
+        /// Whether it is deleted or moved to the trash
+        strategy: RemovalStrategy,
+    },
+
+    /// The removal of a build directory is over
+    Removed {
+        /// The build directory
+        path: PathBuf,
+
+        /// Whether it was deleted or moved to the trash
+        strategy: RemovalStrategy,
+
+        /// Why it couldn't be removed; `None` if it was
+        error: Option<String>,
+    },
+
+    /// The cleanup run is done
+    Finished,
+}
+
+/// One line of the journal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// When it was written, in RFC 3339 format
+    pub at: String,
+
+    /// What happened
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// The journal of a running cleanup, shared by the threads removing build
+/// directories.
+#[derive(Debug)]
+pub struct Journal {
+    file: Mutex<File>,
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Returns the path of the journal.
+    ///
+    /// The journal is `journal.jsonl` in the [state directory](Paths::state_dir),
+    /// or `None` if that cannot be determined.
+    #[must_use]
+    pub fn default_path(paths: &Paths) -> Option<PathBuf> {
+        paths.state_file("journal.jsonl")
+    }
+
+    /// Start the journal of a run over `roots` at `path`, replacing the one
+    /// of the previous run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal or its directory can't be written.
+    pub fn start(path: &Path, roots: &[PathBuf]) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                CleanDevDirsError::state(format!("Failed to create {}", parent.display()))
+            })?;
+        }
+
+        let file = File::create(path).with_context(|| {
+            CleanDevDirsError::state(format!("Failed to create journal {}", path.display()))
+        })?;
+        let journal = Self {
+            file: Mutex::new(file),
+            path: path.to_path_buf(),
+        };
+        journal.write(JournalEvent::Started {
+            pid: std::process::id(),
+            roots: roots.iter().map(|root| absolute(root)).collect(),
+        })?;
+        Ok(journal)
+    }
+
+    /// Append `event`, and wait until it is on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal can't be written.
+    pub fn write(&self, event: JournalEvent) -> Result<()> {
+        let entry = JournalEntry {
+            at: Local::now().to_rfc3339(),
+            event,
+        };
+        let line = serde_json::to_string(&entry).context(CleanDevDirsError::state(
+            "Failed to serialize the journal entry",
+        ))?;
+        let line = format!("{line}\n");
+
+        let mut file = self.file.lock().map_err(|_| {
+            CleanDevDirsError::state(format!("Journal {} is unusable", self.path.display()))
+        })?;
+        file.write_all(line.as_bytes())
+            .and_then(|()| file.sync_data())
+            .with_context(|| {
+                CleanDevDirsError::state(format!("Failed to write journal {}", self.path.display()))
+            })
+    }
+
+    /// Record that `path` is about to be removed with `strategy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal can't be written, in which case the
+    /// directory shouldn't be removed.
+    pub fn removing(&self, path: &Path, strategy: RemovalStrategy) -> Result<()> {
+        self.write(JournalEvent::Removing {
+            path: absolute(path),
+            strategy,
+        })
+    }
+
+    /// Record that the removal of `path` is over, failed with `error` if any.
+    ///
+    /// A failure to write is ignored: the removal has already happened, and
+    /// [`JournalSummary`] reports the directory as interrupted, which is
+    /// then checked against the file system.
+    pub fn removed(&self, path: &Path, strategy: RemovalStrategy, error: Option<String>) {
+        self.write(JournalEvent::Removed {
+            path: absolute(path),
+            strategy,
+            error,
+        })
+        .ok();
+    }
+
+    /// Record that the run is done.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal can't be written.
+    pub fn finish(&self) -> Result<()> {
+        self.write(JournalEvent::Finished)
+    }
+}
+
+/// `path` made absolute against the current directory, or unchanged if that
+/// can't be determined.
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Read the journal at `path`, oldest line first.
+///
+/// A missing journal means no journaled run yet. Lines that can't be parsed,
+/// such as one cut short by a crash, are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the journal exists but can't be read.
+pub fn load(path: &Path) -> Result<Vec<JournalEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).with_context(|| {
+        CleanDevDirsError::state(format!("Failed to read journal {}", path.display()))
+    })?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// How the removal of one build directory ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemovalOutcome {
+    /// It was removed
+    Removed,
+
+    /// It couldn't be removed, for the given reason
+    Failed(String),
+
+    /// The run stopped while removing it, so it may be partly gone
+    Interrupted,
+}
+
+/// A build directory the journaled run set out to remove.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournaledRemoval {
+    /// The build directory
+    pub path: PathBuf,
+
+    /// Whether it was deleted or moved to the trash
+    pub strategy: RemovalStrategy,
+
+    /// How its removal ended
+    pub outcome: RemovalOutcome,
+}
+
+/// What the journal says about the run it covers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JournalSummary {
+    /// When the run started, in RFC 3339 format, or `None` if the journal
+    /// doesn't say
+    pub started_at: Option<String>,
+
+    /// Process ID of the run
+    pub pid: Option<u32>,
+
+    /// Directories that were scanned, or the plan file that was applied
+    pub roots: Vec<PathBuf>,
+
+    /// When the run finished, or `None` if it didn't get that far
+    pub finished_at: Option<String>,
+
+    /// The build directories in the order their removal began; a directory
+    /// tried more than once appears once, with its last outcome
+    pub removals: Vec<JournaledRemoval>,
+}
+
+impl JournalSummary {
+    /// Follow the journal `entries` through the run.
+    #[must_use]
+    pub fn from_entries(entries: &[JournalEntry]) -> Self {
+        let mut summary = Self::default();
+        for entry in entries {
+            match &entry.event {
+                JournalEvent::Started { pid, roots } => {
+                    summary.started_at = Some(entry.at.clone());
+                    summary.pid = Some(*pid);
+                    summary.roots.clone_from(roots);
+                }
+                JournalEvent::Removing { path, strategy } => {
+                    summary.set(path, *strategy, RemovalOutcome::Interrupted);
+                }
+                JournalEvent::Removed {
+                    path,
+                    strategy,
+                    error,
+                } => {
+                    let outcome = error
+                        .clone()
+                        .map_or(RemovalOutcome::Removed, RemovalOutcome::Failed);
+                    summary.set(path, *strategy, outcome);
+                }
+                JournalEvent::Finished => summary.finished_at = Some(entry.at.clone()),
+            }
+        }
+        summary
+    }
+
+    /// Record `outcome` for `path`, replacing an earlier one.
+    fn set(&mut self, path: &Path, strategy: RemovalStrategy, outcome: RemovalOutcome) {
+        if let Some(removal) = self.removals.iter_mut().find(|r| r.path == path) {
+            removal.strategy = strategy;
+            removal.outcome = outcome;
+        } else {
+            self.removals.push(JournaledRemoval {
+                path: path.to_path_buf(),
+                strategy,
+                outcome,
+            });
+        }
+    }
+
+    /// Whether the run got to its end.
+    #[must_use]
+    pub const fn completed(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// The removals that ended with `outcome`.
+    #[must_use]
+    pub fn with_outcome(&self, outcome: &RemovalOutcome) -> Vec<&JournaledRemoval> {
+        self.removals
+            .iter()
+            .filter(|removal| removal.outcome == *outcome)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_journal_replays_a_finished_run() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("state").join("journal.jsonl");
+        let strategy = RemovalStrategy::Permanent;
+
+        let journal = Journal::start(&path, &[PathBuf::from("/work")])?;
+        journal.removing(Path::new("/work/app/target"), strategy)?;
+        journal.removed(Path::new("/work/app/target"), strategy, None);
+        journal.removing(Path::new("/work/web/node_modules"), strategy)?;
+        journal.removed(
+            Path::new("/work/web/node_modules"),
+            strategy,
+            Some("permission denied".to_string()),
+        );
+        journal.finish()?;
+
+        let summary = JournalSummary::from_entries(&load(&path)?);
+        assert!(summary.completed());
+        assert_eq!(summary.pid, Some(std::process::id()));
+        assert_eq!(summary.roots, vec![PathBuf::from("/work")]);
+        let outcomes: Vec<_> = summary.removals.iter().map(|r| &r.outcome).collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                &RemovalOutcome::Removed,
+                &RemovalOutcome::Failed("permission denied".to_string()),
+            ]
+        );
+
+        // A new run replaces the journal
+        Journal::start(&path, &[])?;
+        let summary = JournalSummary::from_entries(&load(&path)?);
+        assert!(!summary.completed());
+        assert!(summary.removals.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_interrupted_run() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("journal.jsonl");
+        let journal = Journal::start(&path, &[PathBuf::from("/work")])?;
+        let target = Path::new("/work/app/target");
+        journal.removing(target, RemovalStrategy::Trash)?;
+        journal.removed(target, RemovalStrategy::Trash, Some("busy".to_string()));
+        // Retried, and the process died during the retry
+        journal.removing(target, RemovalStrategy::Trash)?;
+        drop(journal);
+
+        // The last line was cut short by the crash
+        let mut content = fs::read_to_string(&path)?;
+        content.push_str("{\"at\":\"2026-");
+        fs::write(&path, content)?;
+
+        let summary = JournalSummary::from_entries(&load(&path)?);
+        assert!(!summary.completed());
+        assert_eq!(
+            summary.with_outcome(&RemovalOutcome::Interrupted),
+            vec![&JournaledRemoval {
+                path: target.to_path_buf(),
+                strategy: RemovalStrategy::Trash,
+                outcome: RemovalOutcome::Interrupted,
+            }]
+        );
+        assert!(load(&tmp.path().join("missing.jsonl"))?.is_empty());
+        Ok(())
+    }
+}
@@ -6,23 +6,57 @@
 //! This library provides the core functionality for the clean-dev-dirs CLI tool,
 //! allowing for scanning, filtering, and cleaning development project build artifacts.
 
+pub mod buckets;
+pub mod budget;
+pub mod caches;
 pub mod cleaner;
 pub mod config;
+pub mod detector;
+pub mod discover;
+pub mod docker;
+pub mod error;
 pub mod executables;
+pub mod filter_expr;
 pub mod filtering;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod journal;
+pub mod lock;
+pub mod metrics;
 pub mod output;
+pub mod ownership;
+pub mod paths;
+pub mod plan;
+pub mod processes;
+pub mod progress;
 pub mod project;
+pub mod remote;
+pub mod report;
+pub mod risk;
+pub mod safe_mode;
+#[cfg(feature = "scan-db")]
+pub mod scan_db;
 pub mod scanner;
+pub mod snapshots;
+pub mod snooze;
+pub mod storage;
+pub mod trash_bin;
 pub mod utils;
+pub mod vcs;
+pub mod volumes;
+pub mod why;
 
 // Re-export commonly used types for convenience
 pub use cleaner::{CleanResult, Cleaner};
 pub use config::{
-    ExecutionOptions, FileConfig, FilterOptions, ProjectFilter, ScanOptions, SortCriteria,
-    SortOptions,
+    DisplayOptions, ExecutionOptions, FileConfig, FilterOptions, ProjectFilter, ScanOptions,
+    SortCriteria, SortOptions,
 };
+pub use error::{CleanDevDirsError, Result};
 pub use filtering::filter_projects;
-pub use output::JsonOutput;
-pub use project::{BuildArtifacts, Project, ProjectType, Projects};
+pub use output::{JsonOutput, OutputFormat};
+pub use project::{BuildArtifacts, Project, ProjectType, Projects, RebuildCost};
+pub use risk::RiskLevel;
 pub use scanner::Scanner;
 pub use utils::parse_size;
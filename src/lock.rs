@@ -0,0 +1,199 @@
+//! Keeping overlapping runs from cleaning at the same time.
+//!
+//! Two runs started close together (say, by cron while a manual run is still
+//! going) would both scan the same trees, both decide to remove the same
+//! build directories and both report the space as freed. A run that may
+//! remove anything therefore holds an exclusive lock on `instance.lock` in
+//! the [state directory](Paths::state_dir) from before the scan until
+//! the cleanup is done; `--lock-wait` decides what a second run does
+//! meanwhile.
+//!
+//! The lock is an operating system file lock (`flock` on Unix, `LockFileEx`
+//! on Windows), which the system releases when the process exits, however it
+//! exits. A lock file left behind by a run that crashed or was killed is
+//! therefore stale by construction and simply taken over; only a live
+//! process can block another run. The file holds the PID of the current
+//! holder, so the message for a blocked run can say who holds it.
+
+use std::{
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{Read as _, Seek as _, Write as _},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::LockWait,
+    error::{CleanDevDirsError, Result, ResultExt},
+    paths::Paths,
+};
+
+/// An exclusive lock held for the lifetime of the value.
+///
+/// Dropping it releases the lock; the file itself stays in place.
+#[derive(Debug)]
+pub struct InstanceLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl InstanceLock {
+    /// Returns the path of the instance lock file.
+    ///
+    /// The file is `instance.lock` in the [state directory](Paths::state_dir),
+    /// or `None` if that cannot be determined.
+    #[must_use]
+    pub fn default_path(paths: &Paths) -> Option<PathBuf> {
+        paths.state_dir().map(|dir| dir.join("instance.lock"))
+    }
+
+    /// Take the lock at `path`, creating the file and its directory if needed.
+    ///
+    /// If another process holds the lock, `wait` decides what happens:
+    /// [`LockWait::Wait`] blocks until it is released, [`LockWait::Skip`]
+    /// returns `None`, and [`LockWait::Error`] returns an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock file can't be created or locked, or if
+    /// it is held by another process and `wait` is [`LockWait::Error`].
+    pub fn acquire(path: &Path, wait: LockWait) -> Result<Option<Self>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                CleanDevDirsError::state(format!("Failed to create {}", parent.display()))
+            })?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| {
+                CleanDevDirsError::state(format!("Failed to open lock file {}", path.display()))
+            })?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => match wait {
+                LockWait::Wait => file.lock().with_context(|| {
+                    CleanDevDirsError::state(format!("Failed to lock {}", path.display()))
+                })?,
+                LockWait::Skip => return Ok(None),
+                LockWait::Error => {
+                    return Err(CleanDevDirsError::state(format!(
+                        "Another clean-dev-dirs run{} is already cleaning (lock file {}); \
+                     use --lock-wait wait or --lock-wait skip to run anyway",
+                        holder(&mut file).map_or_else(String::new, |pid| format!(" (pid {pid})")),
+                        path.display()
+                    )));
+                }
+            },
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| {
+                    CleanDevDirsError::state(format!("Failed to lock {}", path.display()))
+                });
+            }
+        }
+
+        let lock = Self {
+            file,
+            path: path.to_path_buf(),
+        };
+        lock.record_pid()?;
+        Ok(Some(lock))
+    }
+
+    /// Path of the lock file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Replace the contents of the lock file with the PID of this process.
+    fn record_pid(&self) -> Result<()> {
+        let mut file = &self.file;
+        file.set_len(0)
+            .and_then(|()| file.rewind())
+            .and_then(|()| writeln!(file, "{}", std::process::id()))
+            .with_context(|| {
+                CleanDevDirsError::state(format!(
+                    "Failed to write lock file {}",
+                    self.path.display()
+                ))
+            })
+    }
+}
+
+/// The PID recorded in a lock file held by another process, if readable.
+fn holder(file: &mut File) -> Option<u32> {
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    content.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_second_run_is_blocked_until_release() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("state").join("instance.lock");
+
+        let first = InstanceLock::acquire(&path, LockWait::Error)?;
+        assert!(first.is_some());
+        assert_eq!(
+            fs::read_to_string(&path)?.trim(),
+            std::process::id().to_string()
+        );
+
+        assert!(InstanceLock::acquire(&path, LockWait::Skip)?.is_none());
+        let err = InstanceLock::acquire(&path, LockWait::Error).err();
+        assert!(err.is_some_and(|e| {
+            e.to_string()
+                .contains(&format!("(pid {})", std::process::id()))
+        }));
+
+        drop(first);
+        assert!(InstanceLock::acquire(&path, LockWait::Error)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_lock_file_is_taken_over() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("instance.lock");
+
+        // Left behind by a run that was killed: the file exists, but nobody holds the lock
+        fs::write(&path, "999999\n")?;
+
+        let lock = InstanceLock::acquire(&path, LockWait::Error)?;
+        assert!(lock.is_some_and(|lock| lock.path() == path));
+        assert_eq!(
+            fs::read_to_string(&path)?.trim(),
+            std::process::id().to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_blocks_until_the_holder_finishes() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("instance.lock");
+
+        let first = InstanceLock::acquire(&path, LockWait::Error)?;
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            drop(first);
+        });
+
+        let second = InstanceLock::acquire(&path, LockWait::Wait)?;
+        assert!(second.is_some());
+        releaser
+            .join()
+            .map_err(|_| anyhow::anyhow!("releaser thread panicked"))?;
+        Ok(())
+    }
+}
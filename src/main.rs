@@ -33,28 +33,71 @@
 mod cli;
 
 use anyhow::{Ok, Result, bail};
+use chrono::Local;
 use clap::Parser;
+#[cfg(feature = "scan-db")]
+use clean_dev_dirs::scan_db;
 use clean_dev_dirs::{
-    cleaner::{Cleaner, RemovalStrategy},
-    config::FileConfig,
+    buckets::{bucket_totals, select_bucket},
+    budget::{RootBudget, plan_budget_cleanup},
+    caches::{self, SystemCache},
+    cleaner::{CleanResult, Cleaner, Removal, RemovalStrategy, RetryPolicy},
+    config::{
+        DEFAULT_CONTAINER_CACHES, DEFAULT_NODE_CACHES, DisplayOptions, FileConfig, LockWait,
+        alias::{self, invoked_alias},
+        edit,
+        local::DEFAULT_KEEP_MARKER,
+        validate::{self, Severity},
+    },
+    discover,
+    docker::Docker,
+    filter_expr::FilterExpr,
     filtering::{filter_projects, sort_projects},
-    output::JsonOutput,
+    history::{self, HistoryTotals, RunRecord},
+    hooks::{self, PreCleanHook},
+    i18n::{Lang, Message},
+    journal::{self, Journal, JournalSummary, RemovalOutcome},
+    lock::InstanceLock,
+    metrics::RunMetrics,
+    output::{JsonOutput, OutputFormat, json_schema, render, write_json_file},
+    ownership::{owner_name, partition_owned},
+    paths::{Paths, default_cache_dir, default_state_dir},
+    plan::CleanupPlan,
+    processes::running_tools,
+    progress::ProgressMode,
     project::{Project, Projects},
-    scanner::Scanner,
+    remote::RemoteHost,
+    report::write_html_report,
+    safe_mode,
+    scanner::{Scanner, parse_root_list},
+    snapshots::{SizeSnapshots, annotate_size_changes},
+    snooze::Snoozes,
+    storage,
+    trash_bin::{self, TrashedItem},
+    vcs::{annotate_last_commits, partition_dirty},
+    why::{DetectorOutcome, DetectorTrace, PathTrace},
 };
-use cli::{Cli, Commands, ConfigCommand};
+use cli::{Cli, Commands, ConfigCommand, TrashCommand};
 use colored::Colorize;
-use humansize::{DECIMAL, format_size};
-use inquire::Confirm;
-use std::process::exit;
+use inquire::{Confirm, InquireError};
+use std::{
+    ffi::OsString,
+    io::{IsTerminal, Read as _, Write as _},
+    path::{Path, PathBuf},
+    process::exit,
+    time::{Duration, Instant, SystemTime},
+};
 
 /// Entry point for the clean-dev-dirs application.
 ///
 /// This function handles all errors gracefully by calling [`inner_main`] and printing
-/// any errors to stderr before exiting with a non-zero status code.
+/// any errors to stderr before exiting with a non-zero status code. Errors
+/// are shown in the language of the locale until the run has chosen one.
 fn main() {
-    if let Err(err) = inner_main() {
-        eprintln!("Error: {err}");
+    let mut lang = Lang::from_env();
+    if let Err(err) = inner_main(&mut lang) {
+        let error = format!("{err:#}");
+        eprintln!("{}", Message::Error.format(lang, &[("error", &error)]));
 
         exit(1);
     }
@@ -70,88 +113,409 @@ fn main() {
 /// Returns errors from thread-pool configuration, directory scanning,
 /// project filtering, interactive selection, file-system operations, or
 /// JSON serialization.
-fn inner_main() -> Result<()> {
-    let args = Cli::parse();
+///
+/// Sets `lang` to the language selected for the run once it is known.
+fn inner_main(lang: &mut Lang) -> Result<()> {
+    let (args, raw_args) = parse_args()?;
+
+    match &args.subcommand {
+        Some(Commands::Config { command }) => {
+            return handle_config_command(command, args.profile());
+        }
+        Some(Commands::Run { alias }) => bail!("Unknown alias '{alias}'"),
+        Some(Commands::Schema) => return print_schema(),
+        Some(
+            Commands::Discover { .. }
+            | Commands::Snooze { .. }
+            | Commands::Trash { .. }
+            | Commands::History { .. }
+            | Commands::Status
+            | Commands::Query { .. },
+        )
+        | None => {}
+    }
+
+    let output_format = args.output_format();
+    let quiet = output_format.is_some();
+    let mut file_config = load_config(quiet);
+    if let Some(profile) = args.profile() {
+        file_config = file_config.with_profile(profile)?;
+    }
+
+    let display = args.display_options(&file_config);
+    *lang = display.lang;
+    if let Some(result) = run_state_command(args.subcommand.as_ref(), &file_config, &display) {
+        return result;
+    }
+    if args.why().is_some() {
+        return explain_path(&args, &file_config, output_format, &display);
+    }
+
+    let mut execution_options = args.execution_options(&file_config);
+    let scan_options = args.scan_options(&file_config)?;
+
+    apply_safe_mode(
+        &mut execution_options,
+        &file_config,
+        args.apply_plan().is_some(),
+        &display,
+    );
+    if execution_options.ci {
+        apply_ci_mode(&mut execution_options)?;
+    }
+
+    check_option_conflicts(&args, &file_config, &execution_options, quiet)?;
 
-    if let Some(Commands::Config { command }) = &args.subcommand {
-        return handle_config_command(command);
+    let progress = ProgressMode::new(quiet, execution_options.ci);
+    let verbose = scan_options.verbose && !quiet;
+    let docker = scan_options.docker && !quiet;
+    let system_caches = if quiet {
+        Vec::new()
+    } else {
+        scan_options.system_caches.clone()
+    };
+
+    let hosts = args.remote_hosts(raw_args);
+    if !hosts.is_empty() {
+        return run_remote(&hosts, &execution_options, verbose, &display);
+    }
+
+    configure_threads(&args, &file_config, scan_options.threads, verbose)?;
+
+    let lock_path = InstanceLock::default_path(&execution_options.paths)
+        .filter(|_| !execution_options.dry_run && !args.bucket_ages());
+    let _instance_lock = match lock_path {
+        Some(path) => match lock_instance(&path, execution_options.lock_wait, quiet, &display)? {
+            Some(lock) => Some(lock),
+            None => return Ok(()),
+        },
+        None => None,
+    };
+
+    if let Some(plan_path) = args.apply_plan() {
+        return run_plan(
+            &args,
+            &file_config,
+            plan_path,
+            output_format,
+            progress,
+            &execution_options,
+            &display,
+        );
+    }
+
+    run_local(
+        &args,
+        &file_config,
+        scan_options,
+        &execution_options,
+        output_format,
+        progress,
+        &display,
+    )?;
+
+    if docker {
+        run_docker(&Docker::default(), &execution_options, &display)?;
     }
 
-    let json_mode = args.json();
-    let file_config = load_config(json_mode);
+    if !system_caches.is_empty() {
+        run_system_caches(&system_caches, &execution_options, verbose, &display)?;
+    }
 
-    let dirs = args.directories(&file_config);
-    let project_filter = args.project_filter(&file_config);
-    let execution_options = args.execution_options(&file_config);
-    let scan_options = args.scan_options(&file_config);
-    let filter_options = args.filter_options(&file_config);
+    Ok(())
+}
 
-    if json_mode && execution_options.interactive {
-        bail!("--json and --interactive cannot be used together");
+/// Clean the build directories recorded in the plan at `plan_path`, for `--apply-plan`.
+fn run_plan(
+    args: &Cli,
+    file_config: &FileConfig,
+    plan_path: &Path,
+    output_format: Option<OutputFormat>,
+    progress: ProgressMode,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let started = Instant::now();
+    let plan = CleanupPlan::load(plan_path)?;
+    let quiet = output_format.is_some();
+    let detected = detect_planned(&plan, args, file_config, opts, quiet, display)?;
+    let projects = check_plan(
+        plan,
+        plan_path,
+        args.plan_tolerance(),
+        &detected,
+        opts.use_trash && !opts.empty_trash,
+        quiet,
+        display,
+    );
+    let mut metrics = RunMetrics {
+        projects_selected: projects.len(),
+        reclaimable_bytes: projects.iter().map(Project::total_size).sum(),
+        ..RunMetrics::default()
+    };
+    if projects.is_empty() {
+        print_empty_result(
+            output_format,
+            opts,
+            display.text(Message::PlanEmpty),
+            display,
+        )?;
+    } else {
+        metrics.reclaimed_bytes = clean_or_preview(
+            projects.into(),
+            &[plan_path.to_path_buf()],
+            None,
+            output_format,
+            progress,
+            opts,
+            display,
+        )?;
     }
+    eprintln!("{}", metrics.summary_line(started.elapsed()));
+    Ok(())
+}
+
+/// Size the thread pool for scanning and cleaning.
+///
+/// `threads` from `--threads` or the config file wins; 0 leaves the choice
+/// to the storage the scanned directories are on (see [`storage`]). Roots
+/// read from stdin or a plan file aren't known yet, so those runs use one
+/// thread per CPU core. In verbose mode the choice is printed with its reason.
+fn configure_threads(
+    args: &Cli,
+    file_config: &FileConfig,
+    threads: usize,
+    verbose: bool,
+) -> Result<()> {
+    let (threads, reason) = if threads > 0 {
+        (
+            Some(threads),
+            "set with --threads or in the config file".to_string(),
+        )
+    } else if args.stdin() || args.apply_plan().is_some() {
+        (None, "the directories to scan aren't known yet".to_string())
+    } else if let Some((root, kind)) = storage::slowest_storage(&args.directories(file_config)) {
+        (
+            kind.default_threads(),
+            format!("{} is on a {kind}", root.display()),
+        )
+    } else {
+        (
+            None,
+            "no spinning disk or network mount to scan".to_string(),
+        )
+    };
 
-    if scan_options.threads > 0 {
+    if verbose {
+        let count = threads.map_or_else(
+            || "one thread per CPU core".to_string(),
+            |threads| format!("{threads} threads"),
+        );
+        println!("{}", format!("Scanning with {count}: {reason}").dimmed());
+    }
+    if let Some(threads) = threads {
         rayon::ThreadPoolBuilder::new()
-            .num_threads(scan_options.threads)
+            .num_threads(threads)
             .build_global()?;
     }
+    Ok(())
+}
+
+/// Scan the local directories, then filter, select and clean the projects found.
+///
+/// Writes the metrics of the run to `--metrics-file`, if given, and ends
+/// with their one-line summary on stderr.
+fn run_local(
+    args: &Cli,
+    file_config: &FileConfig,
+    scan_options: clean_dev_dirs::config::ScanOptions,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    output_format: Option<OutputFormat>,
+    progress: ProgressMode,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let started = Instant::now();
+    let metrics = scan_and_clean(
+        args,
+        file_config,
+        scan_options,
+        opts,
+        output_format,
+        progress,
+        display,
+    )?;
+
+    if let Some(path) = args.metrics_file()
+        && let Err(e) = metrics.write(path)
+    {
+        eprintln!("{} {e}", display.text(Message::Warning).yellow());
+    }
 
-    let scanner = Scanner::new(scan_options, project_filter).with_quiet(json_mode);
-    let projects = scanner.scan_directories(&dirs);
+    eprintln!("{}", metrics.summary_line(started.elapsed()));
+    Ok(())
+}
+
+/// The steps of [`run_local`], returning the metrics of the run.
+fn scan_and_clean(
+    args: &Cli,
+    file_config: &FileConfig,
+    scan_options: clean_dev_dirs::config::ScanOptions,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    output_format: Option<OutputFormat>,
+    progress: ProgressMode,
+    display: &DisplayOptions,
+) -> Result<RunMetrics> {
+    let dirs = if args.stdin() {
+        read_stdin_roots()?
+    } else {
+        args.directories(file_config)
+    };
+    let type_filter = args.type_filter(file_config);
+    let filter_options = args.filter_options(file_config);
+    let root_budgets = args.root_budgets(file_config)?;
+    let quiet = output_format.is_some();
+    let verbose = scan_options.verbose && !quiet;
+    let record_scans = scan_options.record_scans;
+
+    let scanner = Scanner::new(scan_options, type_filter).with_progress(progress);
+    let scan_started = Instant::now();
+    let mut projects = if args.stdin() {
+        scanner.scan_roots(&dirs)
+    } else {
+        scanner.scan_directories(&dirs)
+    };
+    let mut metrics = RunMetrics {
+        projects_detected: projects.len(),
+        scan_duration: scan_started.elapsed(),
+        ..RunMetrics::default()
+    };
+    if wants_last_commits(&filter_options, opts, output_format, record_scans) {
+        annotate_last_commits(&mut projects);
+    }
+    track_size_changes(&mut projects, &opts.paths, quiet, display);
+    if record_scans {
+        record_scan(&opts.paths, &dirs, &projects, quiet, display);
+    }
 
-    if !json_mode {
-        println!("Found {} projects", projects.len());
+    if !quiet {
+        print_scanned_projects(&projects, display);
     }
 
     if projects.is_empty() {
-        return print_empty_result(json_mode, "No development directories found!");
+        print_empty_result(
+            output_format,
+            opts,
+            display.text(Message::NothingFound),
+            display,
+        )?;
+        return Ok(metrics);
     }
 
-    let sort_opts = args.sort_options(&file_config);
+    let sort_opts = args.sort_options(file_config);
+    let all_projects = if root_budgets.is_empty() {
+        Vec::new()
+    } else {
+        projects.clone()
+    };
     let mut filtered_projects = filter_projects(projects, &filter_options)?;
     sort_projects(&mut filtered_projects, &sort_opts);
 
+    filtered_projects = skip_excluded_projects(filtered_projects, opts, quiet, verbose, display)?;
+
+    if !root_budgets.is_empty() {
+        filtered_projects = apply_root_budgets(
+            &all_projects,
+            filtered_projects,
+            &root_budgets,
+            quiet,
+            display,
+        );
+    }
+
+    if let Some(bucket) = args.clean_bucket() {
+        filtered_projects = select_bucket(filtered_projects, bucket, SystemTime::now());
+    }
+
     if filtered_projects.is_empty() {
-        return print_empty_result(json_mode, "No directories match the specified criteria!");
+        print_empty_result(
+            output_format,
+            opts,
+            display.text(Message::NoMatches),
+            display,
+        )?;
+        return Ok(metrics);
+    }
+
+    if args.bucket_ages() {
+        print_age_buckets(&filtered_projects, SystemTime::now(), display);
+        return Ok(metrics);
     }
 
     let total_size: u64 = filtered_projects.iter().map(Project::total_size).sum();
+    metrics.reclaimable_bytes = total_size;
+    metrics.projects_selected = filtered_projects.len();
     let projects: Projects = filtered_projects.into();
 
-    if !json_mode {
-        println!("\n{}", "Found projects:".bold());
-        projects.print_summary(total_size);
+    if !quiet {
+        print_found_projects(
+            &projects,
+            opts.use_trash && !opts.empty_trash,
+            verbose,
+            args.collapse_below(file_config),
+            display,
+        );
+        print_detections(&projects);
     }
 
-    let Some((projects, keep_executables)) =
-        resolve_keep_executables(projects, &execution_options)?
-    else {
-        return Ok(());
-    };
+    metrics.reclaimed_bytes = clean_or_preview(
+        projects,
+        &dirs,
+        args.plan_file(),
+        output_format,
+        progress,
+        opts,
+        display,
+    )?;
+
+    Ok(metrics)
+}
 
-    if execution_options.dry_run {
-        return print_dry_run(&projects, json_mode);
-    }
+// ── Helper functions ────────────────────────────────────────────────────
 
-    let confirm_size = projects.get_total_size();
-    if !confirm_cleanup(
-        projects.len(),
-        confirm_size,
-        execution_options.yes,
-        json_mode,
-    )? {
-        return Ok(());
+/// Take the instance lock at `path` before scanning for a run that may remove directories.
+///
+/// Returns `None` if another run holds the lock and `--lock-wait skip` says
+/// to leave the cleaning to that one.
+fn lock_instance(
+    path: &Path,
+    wait: LockWait,
+    quiet: bool,
+    display: &DisplayOptions,
+) -> Result<Option<InstanceLock>> {
+    if wait == LockWait::Wait {
+        if let Some(lock) = InstanceLock::acquire(path, LockWait::Skip)? {
+            return Ok(Some(lock));
+        }
+        if !quiet {
+            println!("{}", display.text(Message::WaitingForLock));
+        }
     }
 
-    run_cleanup(
-        projects,
-        keep_executables,
-        json_mode,
-        execution_options.use_trash,
-    )
+    let lock = InstanceLock::acquire(path, wait)?;
+    if lock.is_none() && !quiet {
+        println!("{}", display.text(Message::LockHeld));
+    }
+    Ok(lock)
 }
 
-// ── Helper functions ────────────────────────────────────────────────────
+/// Read the directories to check from stdin, for `--stdin`.
+fn read_stdin_roots() -> Result<Vec<PathBuf>> {
+    let mut input = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut input)
+        .map_err(|e| anyhow::anyhow!("Failed to read paths from stdin: {e}"))?;
+    Ok(parse_root_list(&input))
+}
 
 // ── Config subcommand ────────────────────────────────────────────────
 
@@ -165,6 +529,25 @@ const CONFIG_TEMPLATE: &str = r#"# clean-dev-dirs configuration
 # Default directory to scan (defaults to current directory when not set)
 # dir = "."
 
+# Several directories to scan, optionally with a size budget for their build artifacts
+# dirs = ["~/Projects", { path = "~/code", budget = "100GB" }]
+
+# Units used to display sizes: binary (GiB, MiB), decimal (GB, MB) or bytes
+# units = "decimal"
+
+# Language of the messages: en or de (default: from LC_ALL, LC_MESSAGES or LANG)
+# lang = "en"
+
+# Show full project paths instead of paths relative to the scan roots
+# absolute_paths = false
+
+# Where the run history, snoozes and size snapshots are kept
+# (default: ~/.local/state/clean-dev-dirs on Linux)
+# state_dir = "/scratch/clean-dev-dirs/state"
+
+# Where disposable data is kept (default: ~/.cache/clean-dev-dirs on Linux)
+# cache_dir = "/scratch/clean-dev-dirs/cache"
+
 [filtering]
 # Ignore projects whose build directory is smaller than this (e.g. "50MB", "1GiB")
 # keep_size = "0"
@@ -172,61 +555,261 @@ const CONFIG_TEMPLATE: &str = r#"# clean-dev-dirs configuration
 # Ignore projects compiled within the last N days (0 = no age filter)
 # keep_days = 0
 
-# Sort output by: size, age, name, type
+# Sort output by: size, age, name, type, files, growth (comma-separate for tie-breakers, e.g. "size,name")
 # sort = "size"
 
 # Reverse the sort order
 # reverse = false
 
+# List projects smaller than this as one summary line (e.g. "10MB"; "0" lists every project)
+# collapse_below = "0"
+
+# Only include projects whose name / root path matches a regular expression
+# name_filter = "-poc$"
+# path_filter = "/clients/"
+
+# Only include projects matching a filter expression (see --filter-expr)
+# filter_expr = "size > 1GB AND age > 90d OR type == node AND size > 300MB"
+
+# Skip projects with a high rebuild cost that would free less than this (e.g. "100MB")
+# min_benefit = "0"
+
+# Ignore projects whose git repository had a commit in the last N days (0 = no filter)
+# repo_idle_days = 0
+
+# Ignore projects whose build directories contain fewer than N files (0 = no filter)
+# min_files = 0
+
+# Only clean build artifacts older than the project's newest source file
+# stale_only = false
+
+# Only clean build directories up to this risk level ("low", "medium" or "high")
+# max_risk = "high"
+
+# Build directories to clean, or to leave alone, by name or path within the
+# project (e.g. keep node_modules but clean .next and dist)
+# only_artifacts = [".next", "dist"]
+# skip_artifacts = ["node_modules"]
+
+# Project types to include, in addition to project_type (empty = all types)
+# types = ["rust", "node"]
+
+# Project types never to scan
+# exclude_types = ["python"]
+
 [scanning]
-# Number of threads to use for scanning (0 = all CPU cores)
+# Number of threads to use for scanning (0 = chosen by the storage scanned:
+# all CPU cores on SSDs, fewer on spinning disks and network mounts)
 # threads = 0
 
 # Show access errors encountered during scanning
 # verbose = false
 
-# Directories to skip during scanning
+# Directory names not checked for a project (what's below them is still scanned)
 # skip = []
 
-# Directories to ignore entirely during scanning
+# Directory names never descended into during scanning
 # ignore = []
 
+# Check hidden directories (names starting with ".") for projects too
+# scan_hidden = false
+
+# Hidden directory names checked for projects even without scan_hidden (.cargo always is)
+# allow_hidden = [".config", ".local"]
+
+# Directory names never checked for a project, besides the built-in ones
+# (target, build, dist, out, vendor, node_modules, ...)
+# exclude_names = []
+
+# Built-in names to check for a project after all. The built-in names are
+# only passed over inside a directory that looks like a project itself.
+# no_exclude_names = ["dist"]
+
+# Stop sizing a build directory at this size and show it as an estimate
+# quick_size_threshold = "5GB"
+
+# How build directories are measured: "portable" (walkdir, the same everywhere)
+# or "native" (the platform's directory APIs; faster on large trees)
+# size_backend = "portable"
+
+# Projects whose root contains a file with this name are never cleaned
+# ("" turns marker files off)
+# keep_marker = ".clean-dev-dirs-keep"
+
+# Where artifact ages come from: "dir-mtime" (the directory's own mtime) or
+# "newest-file" (newest file inside the artifacts; slower but more accurate)
+# age_source = "dir-mtime"
+
+# Which symbolic links to directories are followed: "never", "roots" (scan
+# roots that are links, but no links below them) or "always" (each directory
+# is scanned once however many links lead to it)
+# follow_symlinks = "roots"
+
+# Also clean .cargo/registry, .cargo/git and .sccache kept inside Rust projects
+# cargo_caches = false
+
+# Also clean conda environments in env/ or .conda/ inside Python projects
+# conda_envs = false
+
+# Clean only build tool caches inside Node.js projects instead of node_modules,
+# and which ones (relative to the project root)
+# node_caches_only = false
+# node_caches = ["node_modules/.cache", "node_modules/.vite", ".turbo"]
+
+# Also clean container caches inside projects, and which ones (patterns relative
+# to the project root, where ** stands for any number of directories)
+# container_caches = false
+# container_cache_patterns = [".buildx-cache", ".docker/cache", ".docker/buildkit", ".docker/**/node_modules"]
+
+# Clean only these subdirectories of Rust target directories instead of target/:
+# "incremental", "fingerprint", "deps" and "doc"
+# rust_subdirs = ["incremental", "doc"]
+
+# Also report and prune dangling Docker images, stopped containers and build cache
+# docker = false
+
+# Append every scan to scans.db in the state directory, for `clean-dev-dirs query`
+# record_scans = false
+
 [execution]
 # Copy compiled executables to <project>/bin/ before cleaning
 # keep_executables = false
 
+# With keep_executables, remove older preserved copies instead of keeping them
+# keep_latest_only = false
+
 # Use interactive project selection
 # interactive = false
 
+# Projects selected when interactive selection starts (all, none, stale, large)
+# preselect = "all"
+
 # Preview what would be cleaned without deleting anything
 # dry_run = false
 
 # Move build dirs to system trash instead of permanently deleting (default: true)
 # use_trash = true
+
+# Skip projects with uncommitted changes in their git repository
+# skip_dirty = false
+
+# Include projects owned by other users (skipped by default)
+# all_users = false
+
+# Show a desktop notification when cleanup finishes
+# notify = false
+
+# Re-measure each build dir just before removal and report the verified space freed
+# verify_sizes = false
+
+# Also remove directories left empty by a removal (e.g. vendor/ after vendor/bundle)
+# prune_empty_parents = false
+
+# Move build dirs to the trash in batches per volume, many per call; much
+# faster on macOS when trashing hundreds of directories
+# batch_trash = false
+
+# Rename build dirs to a hidden sibling before deleting them permanently, so
+# builds running meanwhile never see them half-removed
+# rename_first = false
+
+# Record each removal in journal.jsonl in the state directory before it begins
+# and after it ends, so `clean-dev-dirs status` can tell what a crashed run did
+# journal = false
+
+# Make the first run a dry run that explains how to clean for real. On by
+# default only while there is no config file, so it is off once this file exists.
+# safe_mode = false
+
+# Retries for a failed removal (e.g. files briefly locked by antivirus), and the
+# wait in milliseconds before the first retry, doubled for each further retry
+# delete_retries = 3
+# delete_retry_delay = 100
+
+# What a run does while another one is cleaning: "wait", "skip" (exit
+# successfully) or "error"
+# lock_wait = "error"
+
+# CI mode: no prompts, colors or spinners, plain progress lines, and cleaning
+# requires --yes. Detected from the CI environment variable when not set here.
+# ci = false
+
+[hooks]
+# Command run after each completed cleanup. It receives CLEAN_DEV_DIRS_PROJECT_COUNT,
+# CLEAN_DEV_DIRS_SUCCESS_COUNT, CLEAN_DEV_DIRS_FAILURE_COUNT, CLEAN_DEV_DIRS_TOTAL_FREED
+# and CLEAN_DEV_DIRS_TOTAL_FREED_FORMATTED in its environment.
+# post_clean = "~/bin/after-clean.sh"
+
+# Command run before each project is cleaned; a non-zero exit skips the project.
+# Placeholders {path}, {name}, {type} and {size} are replaced with quoted values.
+# pre_project_clean = "/usr/local/bin/backup.sh {path} {type}"
+
+[caches]
+# Also report and clean package manager caches (same as --system-caches)
+# enabled = false
+
+# With caches enabled, each cache can be left out individually
+# homebrew = true         # Homebrew downloads, cleaned with `brew cleanup`
+# apt = true              # downloaded .deb packages, cleaned with `apt-get clean`
+# pip = true              # pip's wheel and HTTP cache
+# cargo_registry = true   # downloaded crates (the registry index is kept)
+# go_modules = true       # the Go module cache
+# sccache = true          # sccache's local compiler cache
+
+# Extra directories (relative to the project root) to clean per project type.
+# Use [artifacts.all] to apply to every project type.
+# [artifacts.rust]
+# extra = [".criterion", "flamegraphs"]
+#
+# Risk levels of build directories, by their path in the project or their name
+# [artifacts.go]
+# risk = { vendor = "medium" }
+#
+# Build directories never cleaned for a type, by their path or name
+# [artifacts.node]
+# exclude = ["dist"]
+#
+# Python virtual environments (venv/, .venv/) are kept unless included
+# [artifacts.python]
+# include_venv = true
+
+# Argument lists run with `clean-dev-dirs @<name>` or `clean-dev-dirs run <name>`;
+# arguments given after the alias are added to them
+[aliases]
+# weekly = "--keep-days 14 --keep-size 200MB --yes"
 "#;
 
 /// Dispatch a `config` subcommand.
-fn handle_config_command(cmd: &ConfigCommand) -> Result<()> {
+fn handle_config_command(cmd: &ConfigCommand, profile: Option<&str>) -> Result<()> {
     match cmd {
         ConfigCommand::Path => match FileConfig::config_path() {
             Some(path) => println!("{}", path.display()),
             None => bail!("Could not determine the config directory on this platform"),
         },
-        ConfigCommand::Show => show_config()?,
+        ConfigCommand::Show => show_config(profile)?,
         ConfigCommand::Init => init_config()?,
+        ConfigCommand::Validate => validate_config()?,
+        ConfigCommand::Get { key } => get_config_value(key)?,
+        ConfigCommand::Set { key, value } => set_config_value(key, value)?,
     }
     Ok(())
 }
 
 /// Print the effective configuration (file values merged with defaults).
-fn show_config() -> Result<()> {
+///
+/// When a profile is given, its values are merged over the top-level config first.
+fn show_config(profile: Option<&str>) -> Result<()> {
     let path = FileConfig::config_path();
 
-    let (file_exists, config) = match &path {
+    let (file_exists, mut config) = match &path {
         Some(p) if p.exists() => (true, FileConfig::load()?),
         _ => (false, FileConfig::default()),
     };
 
+    if let Some(name) = profile {
+        config = config.with_profile(name)?;
+    }
+
     match &path {
         Some(p) if file_exists => println!("Config file: {} (found)", p.display()),
         Some(p) => println!(
@@ -236,38 +819,97 @@ fn show_config() -> Result<()> {
         None => println!("Config file: (cannot determine path on this platform)"),
     }
 
+    if let Some(name) = profile {
+        println!("Profile:     {name}");
+    }
+
     println!();
     println!("{}", format_config(&config));
     Ok(())
 }
 
-/// Format a [`FileConfig`] as a human-readable table, showing defaults for `None` fields.
-fn format_config(config: &clean_dev_dirs::config::file::FileConfig) -> String {
-    fn show_str(val: Option<&str>, default: &str) -> String {
-        val.map_or_else(
-            || format!("\"{default}\"  (default)"),
-            |v| format!("\"{v}\""),
-        )
+/// Check the config file and print every problem found with its line number.
+///
+/// Warnings alone still count as valid; errors make the command fail.
+fn validate_config() -> Result<()> {
+    let Some(path) = FileConfig::config_path() else {
+        bail!("Could not determine the config directory on this platform");
+    };
+    if !path.exists() {
+        println!("No config file at {}; nothing to check.", path.display());
+        return Ok(());
     }
-    fn show_bool(val: Option<bool>, default: bool) -> String {
-        val.map_or_else(|| format!("{default}  (default)"), |v| v.to_string())
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read config file at {}: {e}", path.display()))?;
+    let diagnostics = validate::validate(&content);
+
+    println!("Config file: {}", path.display());
+    if diagnostics.is_empty() {
+        println!("{}", "No problems found.".green());
+        return Ok(());
     }
-    fn show_u32(val: Option<u32>, default: u32) -> String {
-        val.map_or_else(|| format!("{default}  (default)"), |v| v.to_string())
+
+    let mut errors = 0;
+    for diagnostic in &diagnostics {
+        let label = match diagnostic.severity {
+            Severity::Error => {
+                errors += 1;
+                "error:".red().bold()
+            }
+            Severity::Warning => "warning:".yellow().bold(),
+        };
+        println!("  {label} {diagnostic}");
     }
-    fn show_usize(val: Option<usize>, default: &str) -> String {
-        val.map_or_else(|| format!("{default}  (default)"), |v| v.to_string())
+
+    let warnings = diagnostics.len() - errors;
+    if errors > 0 {
+        bail!("Found {errors} errors and {warnings} warnings in the config file");
     }
-    fn show_paths(val: Option<&[std::path::PathBuf]>) -> String {
-        match val {
-            Some(v) if !v.is_empty() => {
-                let items: Vec<String> = v.iter().map(|p| format!("\"{}\"", p.display())).collect();
-                format!("[{}]", items.join(", "))
-            }
-            _ => "[]  (default)".to_string(),
-        }
+    println!("Found {warnings} warnings.");
+    Ok(())
+}
+
+/// Print the value of the setting `key` from the config file.
+fn get_config_value(key: &str) -> Result<()> {
+    let path = FileConfig::config_path();
+    let content = match &path {
+        Some(p) if p.exists() => std::fs::read_to_string(p)
+            .map_err(|e| anyhow::anyhow!("Failed to read config file at {}: {e}", p.display()))?,
+        _ => String::new(),
+    };
+
+    match edit::get(&content, key)? {
+        Some(value) => println!("{value}"),
+        None => bail!("`{key}` is not set in the config file"),
+    }
+    Ok(())
+}
+
+/// Set `key` to `value` in the config file, creating it from the template if needed.
+fn set_config_value(key: &str, value: &str) -> Result<()> {
+    let Some(path) = FileConfig::config_path() else {
+        bail!("Could not determine the config directory on this platform");
+    };
+
+    let content = if path.exists() {
+        std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read config file at {}: {e}", path.display()))?
+    } else {
+        CONFIG_TEMPLATE.to_string()
+    };
+    let updated = edit::set(&content, key, value)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
+    std::fs::write(&path, updated)?;
+    println!("Set {key} in {}", path.display());
+    Ok(())
+}
 
+/// Format a [`FileConfig`] as a human-readable table, showing defaults for `None` fields.
+fn format_config(config: &clean_dev_dirs::config::file::FileConfig) -> String {
     let dir_str = config.dir.as_ref().map_or_else(
         || "\".\"  (default)".to_string(),
         |p| format!("\"{}\"", p.display()),
@@ -277,45 +919,296 @@ fn format_config(config: &clean_dev_dirs::config::file::FileConfig) -> String {
         "\
 project_type  = {project_type}
 dir           = {dir}
+units         = {units}
+lang          = {lang}
+absolute_paths = {absolute_paths}
+state_dir     = {state_dir}
+cache_dir     = {cache_dir}
 
-[filtering]
-keep_size     = {keep_size}
-keep_days     = {keep_days}
-sort          = {sort}
-reverse       = {reverse}
+{filtering}
 
 [scanning]
 threads       = {threads}
 verbose       = {verbose}
 skip          = {skip}
 ignore        = {ignore}
+scan_hidden   = {scan_hidden}
+allow_hidden  = {allow_hidden}
+exclude_names = {exclude_names}
+no_exclude_names = {no_exclude_names}
+quick_size_threshold = {quick_size_threshold}
+size_backend  = {size_backend}
+keep_marker   = {keep_marker}
+age_source    = {age_source}
+follow_symlinks = {follow_symlinks}
+cargo_caches  = {cargo_caches}
+conda_envs    = {conda_envs}
+node_caches_only = {node_caches_only}
+node_caches   = {node_caches}
+container_caches = {container_caches}
+container_cache_patterns = {container_cache_patterns}
+rust_subdirs  = {rust_subdirs}
+docker        = {docker}
+record_scans  = {record_scans}
+
+{execution}
+
+{hooks}
+
+{caches}{artifacts}",
+        project_type = show_str(config.project_type.as_deref(), "all"),
+        dir = dir_str,
+        units = show_str(config.units.as_deref(), "decimal"),
+        lang = show_str(config.lang.as_deref(), Lang::from_env().code()),
+        absolute_paths = show_val(config.absolute_paths, false),
+        state_dir = show_dir(config.state_dir.as_deref(), default_state_dir()),
+        cache_dir = show_dir(config.cache_dir.as_deref(), default_cache_dir()),
+        filtering = format_filtering_config(&config.filtering),
+        threads = show_val(config.scanning.threads, "0 (by storage)"),
+        verbose = show_val(config.scanning.verbose, false),
+        skip = show_paths(config.scanning.skip.as_deref()),
+        ignore = show_paths(config.scanning.ignore.as_deref()),
+        scan_hidden = show_val(config.scanning.scan_hidden, false),
+        allow_hidden = show_paths(config.scanning.allow_hidden.as_deref()),
+        exclude_names = show_paths(config.scanning.exclude_names.as_deref()),
+        no_exclude_names = show_paths(config.scanning.no_exclude_names.as_deref()),
+        quick_size_threshold = show_opt_str(config.scanning.quick_size_threshold.as_deref()),
+        size_backend = show_str(config.scanning.size_backend.as_deref(), "portable"),
+        keep_marker = show_str(config.scanning.keep_marker.as_deref(), DEFAULT_KEEP_MARKER),
+        age_source = show_str(config.scanning.age_source.as_deref(), "dir-mtime"),
+        follow_symlinks = show_str(config.scanning.follow_symlinks.as_deref(), "roots"),
+        cargo_caches = show_val(config.scanning.cargo_caches, false),
+        conda_envs = show_val(config.scanning.conda_envs, false),
+        node_caches_only = show_val(config.scanning.node_caches_only, false),
+        node_caches = config.scanning.node_caches.as_deref().map_or_else(
+            || format!("{DEFAULT_NODE_CACHES:?}  (default)"),
+            |caches| show_paths(Some(caches))
+        ),
+        container_caches = show_val(config.scanning.container_caches, false),
+        container_cache_patterns = config
+            .scanning
+            .container_cache_patterns
+            .as_deref()
+            .map_or_else(
+                || format!("{DEFAULT_CONTAINER_CACHES:?}  (default)"),
+                |patterns| show_list(Some(patterns))
+            ),
+        rust_subdirs = show_list(config.scanning.rust_subdirs.as_deref()),
+        docker = show_val(config.scanning.docker, false),
+        record_scans = show_val(config.scanning.record_scans, false),
+        execution = format_execution_config(&config.execution),
+        hooks = format_hooks_config(&config.hooks),
+        caches = format_caches_config(&config.caches),
+        artifacts = format_artifacts_config(&config.artifacts),
+    )
+}
+
+/// Format the `[artifacts.<type>]` tables of the config, if there are any.
+fn format_artifacts_config(
+    artifacts: &std::collections::BTreeMap<
+        String,
+        clean_dev_dirs::config::file::FileArtifactConfig,
+    >,
+) -> String {
+    use std::fmt::Write as _;
+
+    let mut output = String::new();
+    for (kind, artifacts) in artifacts {
+        let _ = write!(
+            output,
+            "\n\n[artifacts.{kind}]\nextra            = {}",
+            show_paths(artifacts.extra.as_deref())
+        );
+        if let Some(risk) = artifacts.risk.as_ref().filter(|risk| !risk.is_empty()) {
+            let levels: Vec<String> = risk
+                .iter()
+                .map(|(dir, level)| format!("\"{dir}\" = \"{level}\""))
+                .collect();
+            let _ = write!(output, "\nrisk             = {{ {} }}", levels.join(", "));
+        }
+        if let Some(exclude) = artifacts.exclude.as_deref() {
+            let _ = write!(output, "\nexclude          = {}", show_list(Some(exclude)));
+        }
+        if let Some(include_venv) = artifacts.include_venv {
+            let _ = write!(output, "\ninclude_venv     = {include_venv}");
+        }
+    }
+    output
+}
+
+/// Format the `[filtering]` table of the config, showing defaults for `None` fields.
+fn format_filtering_config(filtering: &clean_dev_dirs::config::file::FileFilterConfig) -> String {
+    format!(
+        "\
+[filtering]
+keep_size     = {keep_size}
+keep_days     = {keep_days}
+sort          = {sort}
+reverse       = {reverse}
+collapse_below = {collapse_below}
+name_filter   = {name_filter}
+path_filter   = {path_filter}
+min_benefit   = {min_benefit}
+repo_idle_days = {repo_idle_days}
+min_files     = {min_files}
+stale_only    = {stale_only}
+max_risk      = {max_risk}
+only_artifacts = {only_artifacts}
+skip_artifacts = {skip_artifacts}
+filter_expr   = {filter_expr}
+types         = {types}
+exclude_types = {exclude_types}",
+        keep_size = show_str(filtering.keep_size.as_deref(), "0"),
+        keep_days = show_val(filtering.keep_days, 0),
+        sort = show_opt_str(filtering.sort.as_deref()),
+        reverse = show_val(filtering.reverse, false),
+        collapse_below = show_str(filtering.collapse_below.as_deref(), "0"),
+        name_filter = show_opt_str(filtering.name_filter.as_deref()),
+        path_filter = show_opt_str(filtering.path_filter.as_deref()),
+        min_benefit = show_str(filtering.min_benefit.as_deref(), "0"),
+        repo_idle_days = show_val(filtering.repo_idle_days, 0),
+        min_files = show_val(filtering.min_files, 0),
+        stale_only = show_val(filtering.stale_only, false),
+        max_risk = show_opt_str(filtering.max_risk.as_deref()),
+        only_artifacts = show_list(filtering.only_artifacts.as_deref()),
+        skip_artifacts = show_list(filtering.skip_artifacts.as_deref()),
+        filter_expr = show_opt_str(filtering.filter_expr.as_deref()),
+        types = show_list(filtering.types.as_deref()),
+        exclude_types = show_list(filtering.exclude_types.as_deref()),
+    )
+}
 
+/// Format the `[execution]` table of the config, showing defaults for `None` fields.
+fn format_execution_config(
+    execution: &clean_dev_dirs::config::file::FileExecutionConfig,
+) -> String {
+    format!(
+        "\
 [execution]
 keep_executables = {keep_executables}
+keep_latest_only = {keep_latest_only}
 interactive      = {interactive}
+preselect        = {preselect}
 dry_run          = {dry_run}
-use_trash        = {use_trash}",
-        project_type = show_str(config.project_type.as_deref(), "all"),
-        dir = dir_str,
-        keep_size = show_str(config.filtering.keep_size.as_deref(), "0"),
-        keep_days = show_u32(config.filtering.keep_days, 0),
-        sort = config
-            .filtering
-            .sort
-            .as_deref()
-            .map_or_else(|| "(none)  (default)".to_string(), |v| format!("\"{v}\""),),
-        reverse = show_bool(config.filtering.reverse, false),
-        threads = show_usize(config.scanning.threads, "0 (all cores)"),
-        verbose = show_bool(config.scanning.verbose, false),
-        skip = show_paths(config.scanning.skip.as_deref()),
-        ignore = show_paths(config.scanning.ignore.as_deref()),
-        keep_executables = show_bool(config.execution.keep_executables, false),
-        interactive = show_bool(config.execution.interactive, false),
-        dry_run = show_bool(config.execution.dry_run, false),
-        use_trash = show_bool(config.execution.use_trash, true),
+use_trash        = {use_trash}
+skip_dirty       = {skip_dirty}
+all_users        = {all_users}
+notify           = {notify}
+verify_sizes     = {verify_sizes}
+prune_empty_parents = {prune_empty_parents}
+batch_trash      = {batch_trash}
+rename_first     = {rename_first}
+journal          = {journal}
+safe_mode        = {safe_mode}
+delete_retries   = {delete_retries}
+delete_retry_delay = {delete_retry_delay}
+lock_wait        = {lock_wait}
+ci               = {ci}",
+        keep_executables = show_val(execution.keep_executables, false),
+        keep_latest_only = show_val(execution.keep_latest_only, false),
+        interactive = show_val(execution.interactive, false),
+        preselect = show_str(execution.preselect.as_deref(), "all"),
+        dry_run = show_val(execution.dry_run, false),
+        use_trash = show_val(execution.use_trash, true),
+        skip_dirty = show_val(execution.skip_dirty, false),
+        all_users = show_val(execution.all_users, false),
+        notify = show_val(execution.notify, false),
+        verify_sizes = show_val(execution.verify_sizes, false),
+        prune_empty_parents = show_val(execution.prune_empty_parents, false),
+        batch_trash = show_val(execution.batch_trash, false),
+        rename_first = show_val(execution.rename_first, false),
+        journal = show_val(execution.journal, false),
+        safe_mode = show_val(execution.safe_mode, false),
+        delete_retries = show_val(execution.delete_retries, 3),
+        delete_retry_delay = show_val(execution.delete_retry_delay, "100 (ms)"),
+        lock_wait = show_str(execution.lock_wait.as_deref(), "error"),
+        ci = show_val(execution.ci, "(from CI env var)"),
+    )
+}
+
+/// Format the `[hooks]` table of the config, showing defaults for `None` fields.
+fn format_hooks_config(hooks: &clean_dev_dirs::config::file::FileHooksConfig) -> String {
+    format!(
+        "\
+[hooks]
+post_clean       = {post_clean}
+pre_project_clean = {pre_project_clean}",
+        post_clean = show_opt_str(hooks.post_clean.as_deref()),
+        pre_project_clean = show_opt_str(hooks.pre_project_clean.as_deref()),
+    )
+}
+
+/// Format the `[caches]` table of the config, showing defaults for `None` fields.
+fn format_caches_config(caches: &clean_dev_dirs::config::file::FileCachesConfig) -> String {
+    format!(
+        "\
+[caches]
+enabled          = {enabled}
+homebrew         = {homebrew}
+apt              = {apt}
+pip              = {pip}
+cargo_registry   = {cargo_registry}
+go_modules       = {go_modules}
+sccache          = {sccache}",
+        enabled = show_val(caches.enabled, false),
+        homebrew = show_val(caches.homebrew, true),
+        apt = show_val(caches.apt, true),
+        pip = show_val(caches.pip, true),
+        cargo_registry = show_val(caches.cargo_registry, true),
+        go_modules = show_val(caches.go_modules, true),
+        sccache = show_val(caches.sccache, true),
+    )
+}
+
+/// Show a quoted config string, or `default` marked as such.
+fn show_str(val: Option<&str>, default: &str) -> String {
+    val.map_or_else(
+        || format!("\"{default}\"  (default)"),
+        |v| format!("\"{v}\""),
     )
 }
 
+/// Show a quoted config string that has no default.
+fn show_opt_str(val: Option<&str>) -> String {
+    val.map_or_else(|| "(none)  (default)".to_string(), |v| format!("\"{v}\""))
+}
+
+/// Show a config value, or `default` marked as such.
+fn show_val(val: Option<impl std::fmt::Display>, default: impl std::fmt::Display) -> String {
+    val.map_or_else(|| format!("{default}  (default)"), |v| v.to_string())
+}
+
+/// Show a config directory, or the platform's `default` marked as such.
+fn show_dir(val: Option<&Path>, default: Option<PathBuf>) -> String {
+    match (val, default) {
+        (Some(dir), _) => format!("\"{}\"", dir.display()),
+        (None, Some(dir)) => format!("\"{}\"  (default)", dir.display()),
+        (None, None) => "(none)  (default)".to_string(),
+    }
+}
+
+/// Show a list of config paths, or an empty default.
+fn show_paths(val: Option<&[std::path::PathBuf]>) -> String {
+    match val {
+        Some(v) if !v.is_empty() => {
+            let items: Vec<String> = v.iter().map(|p| format!("\"{}\"", p.display())).collect();
+            format!("[{}]", items.join(", "))
+        }
+        _ => "[]  (default)".to_string(),
+    }
+}
+
+/// Format an optional list of strings for display.
+fn show_list(val: Option<&[String]>) -> String {
+    match val {
+        Some(v) if !v.is_empty() => {
+            let items: Vec<String> = v.iter().map(|s| format!("\"{s}\"")).collect();
+            format!("[{}]", items.join(", "))
+        }
+        _ => "[]  (default)".to_string(),
+    }
+}
+
 /// Write a default config template to the config file path if it does not exist yet.
 fn init_config() -> Result<()> {
     let Some(path) = FileConfig::config_path() else {
@@ -344,110 +1237,1841 @@ fn init_config() -> Result<()> {
     Ok(())
 }
 
-/// Load the configuration file, falling back to defaults on failure.
-fn load_config(json_mode: bool) -> FileConfig {
-    match FileConfig::load() {
-        std::result::Result::Ok(config) => config,
-        Err(e) => {
-            if !json_mode {
-                eprintln!("{} {e}", "Warning: Failed to load config file:".yellow());
-            }
-            FileConfig::default()
-        }
+/// Switch to CI mode: plain output and no prompts.
+///
+/// Colors are disabled and interactive selection is turned off. Since nobody
+/// is there to confirm, cleaning requires `--yes`; dry runs are always allowed.
+fn apply_ci_mode(opts: &mut clean_dev_dirs::ExecutionOptions) -> Result<()> {
+    colored::control::set_override(false);
+    opts.interactive = false;
+
+    if !opts.dry_run && !opts.yes {
+        bail!("CI mode refuses to clean without --yes (use --dry-run to preview)");
     }
-}
 
-/// Emit an empty-projects result in JSON or human-readable form.
-fn print_empty_result(json_mode: bool, message: &str) -> Result<()> {
-    if json_mode {
-        let output = JsonOutput::from_projects_dry_run(&[]);
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        println!("{}", message.green());
-    }
     Ok(())
 }
 
-/// Handle interactive project selection and the keep-executables prompt.
+/// Turn the first run into a dry run when safe mode is on for it (see
+/// [`safe_mode`]), and record that the first run was made.
 ///
-/// Returns `Ok(Some((projects, keep)))` where `projects` is the user-selected
-/// subset (interactive mode) or the full set (non-interactive), and `keep` is
-/// the resolved keep-executables flag. Returns `Ok(None)` when the user
+/// An explicit `--yes`, `--dry-run` or `--apply-plan` is left alone: a plan
+/// is applied on purpose, often as root (whose state directory has never
+/// seen a run) to remove what a normal run couldn't. The notice goes to
+/// stderr so that `--json` output stays valid.
+fn apply_safe_mode(
+    opts: &mut clean_dev_dirs::ExecutionOptions,
+    config: &FileConfig,
+    applying_plan: bool,
+    display: &DisplayOptions,
+) {
+    if !safe_mode::is_first_run(&opts.paths) {
+        return;
+    }
+
+    let has_config = FileConfig::config_path().is_some_and(|path| path.exists());
+    let explicit = opts.dry_run || opts.yes || applying_plan;
+    if safe_mode::enabled(config.execution.safe_mode, has_config) && !explicit {
+        opts.dry_run = true;
+        eprintln!("{}\n", display.text(Message::SafeMode).yellow());
+    }
+
+    if let Err(e) = safe_mode::record_first_run(&opts.paths) {
+        eprintln!("{} {e:#}", display.text(Message::Warning).yellow());
+    }
+}
+
+/// Reject option combinations that can't work together, and a filter
+/// expression that can't be parsed, before anything is scanned.
+fn check_option_conflicts(
+    args: &Cli,
+    file_config: &FileConfig,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    quiet: bool,
+) -> Result<()> {
+    if quiet && opts.interactive {
+        bail!("--json/--output and --interactive cannot be used together");
+    }
+
+    if quiet && !opts.dry_run && !opts.yes {
+        bail!(
+            "--json/--output cannot ask for confirmation; pass --yes to clean or --dry-run to preview"
+        );
+    }
+
+    if args.plan_file().is_some() && !opts.dry_run {
+        bail!("--plan-file only records a plan; combine it with --dry-run");
+    }
+
+    let filter_expr = args.filter_options(file_config).filter_expr;
+    if let Some(expr) = filter_expr.filter(|expr| !expr.trim().is_empty()) {
+        FilterExpr::parse(&expr)?;
+    }
+
+    Ok(())
+}
+
+/// Write rendered output to stdout as it is; `--print0` paths needn't be
+/// valid UTF-8.
+fn print_bytes(bytes: &[u8]) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(bytes)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Load the configuration file, falling back to defaults on failure.
+fn load_config(quiet: bool) -> FileConfig {
+    match FileConfig::load() {
+        std::result::Result::Ok(config) => config,
+        Err(e) => {
+            if !quiet {
+                eprintln!("{} {e:#}", "Warning: Failed to load config file:".yellow());
+            }
+            FileConfig::default()
+        }
+    }
+}
+
+/// Print the JSON Schema of the `--json` output.
+fn print_schema() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&json_schema())?);
+    Ok(())
+}
+
+/// Parse the command line, first expanding the alias it starts with (see
+/// [`alias`]).
+///
+/// Returns the parsed arguments, and the expanded arguments without the
+/// program name, for passing on to `--host` machines. The config file is
+/// only read here when an alias is invoked, and then has to load, since the
+/// alias can't be expanded otherwise.
+fn parse_args() -> Result<(Cli, Vec<String>)> {
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+    if invoked_alias(&args).is_some() {
+        args = alias::expand(args, &FileConfig::load()?.aliases)?;
+    }
+
+    let raw_args = args
+        .iter()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    Ok((Cli::parse_from(args), raw_args))
+}
+
+/// Emit an empty-projects result in the requested format or human-readable form.
+///
+/// The files asked for with `--json-file` and `--report-html` are written as well.
+fn print_empty_result(
+    output_format: Option<OutputFormat>,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    message: &str,
+    display: &DisplayOptions,
+) -> Result<()> {
+    if let Some(format) = output_format {
+        print_bytes(&render(format, &[], None, display)?)?;
+    } else {
+        println!("{}", message.green());
+    }
+    write_result_files(&[], None, opts, display)
+}
+
+/// Write the JSON document for `--json-file` and the HTML report for
+/// `--report-html`, if they were asked for.
+fn write_result_files(
+    projects: &[Project],
+    clean_result: Option<&CleanResult>,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    display: &DisplayOptions,
+) -> Result<()> {
+    if let Some(path) = &opts.json_file {
+        write_json_file(path, projects, clean_result, display)?;
+    }
+    if let Some(path) = &opts.report_html {
+        write_html_report(path, projects, clean_result, display)?;
+    }
+    Ok(())
+}
+
+/// Drop the dirty, foreign and snoozed projects, as far as `opts` asks for it.
+///
+/// # Errors
+///
+/// Returns an error if the snooze file exists but can't be loaded.
+fn skip_excluded_projects(
+    mut projects: Vec<Project>,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    quiet: bool,
+    verbose: bool,
+    display: &DisplayOptions,
+) -> Result<Vec<Project>> {
+    if opts.skip_dirty {
+        projects = skip_dirty_projects(projects, quiet, display);
+    }
+
+    if !opts.all_users {
+        projects = skip_foreign_projects(projects, quiet, verbose, display);
+    }
+
+    skip_snoozed_projects(projects, &opts.paths, quiet, display)
+}
+
+/// Drop projects with uncommitted git changes, reporting each one skipped.
+fn skip_dirty_projects(
+    projects: Vec<Project>,
+    quiet: bool,
+    display: &DisplayOptions,
+) -> Vec<Project> {
+    let (clean, dirty, errors) = partition_dirty(projects);
+
+    for e in &errors {
+        eprintln!("{} {e:#}", display.text(Message::Warning).yellow());
+    }
+    if !quiet {
+        for project in &dirty {
+            println!(
+                "  {} {}",
+                project.display_with(display),
+                "dirty - skipped".yellow()
+            );
+        }
+    }
+
+    clean
+}
+
+/// Drop snoozed projects, listing them greyed out with their expiry date.
+///
+/// # Errors
+///
+/// Returns an error if the snooze file exists but can't be read or parsed:
+/// cleaning without it would clean every snoozed project. Only a missing
+/// file means nothing is snoozed.
+fn skip_snoozed_projects(
+    projects: Vec<Project>,
+    paths: &Paths,
+    quiet: bool,
+    display: &DisplayOptions,
+) -> Result<Vec<Project>> {
+    let Some(path) = Snoozes::default_path(paths) else {
+        return Ok(projects);
+    };
+    let active = Snoozes::load(&path).map_err(|e| {
+        anyhow::anyhow!(
+            "{e:#}\nFix or remove {} to clean again; nothing was cleaned",
+            path.display()
+        )
+    })?;
+
+    let (kept, snoozed) = active.partition(projects);
+    if !quiet {
+        for (project, until) in &snoozed {
+            let line = format!(
+                "{} snoozed until {}",
+                project.display_with(display),
+                until.format("%Y-%m-%d %H:%M")
+            );
+            println!("  {}", line.dimmed());
+        }
+    }
+
+    Ok(kept)
+}
+
+/// Compare the sizes with the previous run and remember them for the next one.
+///
+/// A size snapshot file that can't be read or written is reported and
+/// otherwise ignored.
+fn track_size_changes(
+    projects: &mut [Project],
+    paths: &Paths,
+    quiet: bool,
+    display: &DisplayOptions,
+) {
+    let Some(path) = SizeSnapshots::default_path(paths) else {
+        return;
+    };
+
+    let result = SizeSnapshots::load(&path).and_then(|mut snapshots| {
+        annotate_size_changes(projects, &snapshots);
+        snapshots.record(projects, Local::now().timestamp());
+        snapshots.save(&path)
+    });
+    if let Err(e) = result
+        && !quiet
+    {
+        eprintln!("{} {e}", display.text(Message::Warning).yellow());
+    }
+}
+
+/// Append the projects found to the scan database.
+#[cfg(feature = "scan-db")]
+fn record_scan(
+    paths: &Paths,
+    roots: &[PathBuf],
+    projects: &[Project],
+    quiet: bool,
+    display: &DisplayOptions,
+) {
+    let Some(path) = scan_db::database_path(paths) else {
+        return;
+    };
+
+    if let Err(e) = scan_db::record(&path, roots, projects, Local::now().timestamp())
+        && !quiet
+    {
+        eprintln!("{} {e:#}", display.text(Message::Warning).yellow());
+    }
+}
+
+/// Warn that scans can't be recorded without the `scan-db` feature.
+#[cfg(not(feature = "scan-db"))]
+fn record_scan(
+    _paths: &Paths,
+    _roots: &[PathBuf],
+    _projects: &[Project],
+    quiet: bool,
+    display: &DisplayOptions,
+) {
+    if !quiet {
+        eprintln!(
+            "{} record_scans needs clean-dev-dirs built with the scan-db feature",
+            display.text(Message::Warning).yellow()
+        );
+    }
+}
+
+/// Forget the remembered sizes of cleaned build directories, so that their
+/// growth is measured from nothing when they come back.
+fn forget_cleaned_sizes(paths: &Paths, projects: &[Project], display: &DisplayOptions) {
+    let Some(path) = SizeSnapshots::default_path(paths) else {
+        return;
+    };
+
+    let result = SizeSnapshots::load(&path).and_then(|mut snapshots| {
+        snapshots.forget(projects);
+        snapshots.save(&path)
+    });
+    if let Err(e) = result {
+        eprintln!("{} {e}", display.text(Message::Warning).yellow());
+    }
+}
+
+/// Whether anything in this run uses [`Project::last_commit`]: the
+/// `repo_idle_days` filter, the interactive list, the structured outputs and
+/// reports that have a last-commit column, or the recorded scans. Looking it
+/// up runs git for every repository, so it's skipped otherwise.
+const fn wants_last_commits(
+    filter_options: &clean_dev_dirs::config::FilterOptions,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    output_format: Option<OutputFormat>,
+    record_scans: bool,
+) -> bool {
+    filter_options.repo_idle_days > 0
+        || opts.interactive
+        || output_format.is_some()
+        || opts.json_file.is_some()
+        || opts.report_html.is_some()
+        || record_scans
+}
+
+/// Print how many projects the scan found and list the protected ones greyed
+/// out; the filters leave them out.
+fn print_scanned_projects(projects: &[Project], display: &DisplayOptions) {
+    println!(
+        "{}",
+        Message::FoundCount.format(display.lang, &[("count", &projects.len())])
+    );
+    for project in projects.iter().filter(|p| p.protected) {
+        let line = format!("{} protected - skipped", project.display_with(display));
+        println!("  {}", line.dimmed());
+    }
+}
+
+/// Drop projects owned by other users, reporting how many were skipped.
+///
+/// In verbose mode every skipped project is listed with its owner.
+fn skip_foreign_projects(
+    projects: Vec<Project>,
+    quiet: bool,
+    verbose: bool,
+    display: &DisplayOptions,
+) -> Vec<Project> {
+    let (kept, foreign) = partition_owned(projects);
+
+    if !quiet && !foreign.is_empty() {
+        if verbose {
+            for project in &foreign {
+                let owner = owner_name(&project.root_path).unwrap_or_else(|| "unknown".into());
+                println!(
+                    "  {} {}",
+                    project.display_with(display),
+                    format!("owned by {owner} - skipped").yellow()
+                );
+            }
+        }
+        let count = if foreign.len() == 1 {
+            "1 project".to_string()
+        } else {
+            format!("{} projects", foreign.len())
+        };
+        println!(
+            "  {}",
+            format!("Skipped {count} owned by other users (use --all-users to include them)")
+                .yellow()
+        );
+    }
+
+    kept
+}
+
+/// Print the per-type summary of the projects about to be cleaned.
+///
+/// In verbose mode each project is also listed with its size and owner,
+/// except that projects smaller than `collapse_below` share one line.
+fn print_found_projects(
+    projects: &Projects,
+    trashing: bool,
+    verbose: bool,
+    collapse_below: u64,
+    display: &DisplayOptions,
+) {
+    println!("\n{}", display.text(Message::FoundProjects).bold());
+    projects.print_summary(projects.get_total_size(), trashing, display);
+    if !verbose {
+        return;
+    }
+
+    println!();
+    let now = Local::now().timestamp();
+    let (listed, collapsed) = projects.split_small(collapse_below);
+    for project in listed {
+        let owner = owner_name(&project.root_path).unwrap_or_else(|| "unknown".into());
+        let growth = project
+            .size_change
+            .map(|change| format!(" ({})", change.describe(now, display.units)))
+            .unwrap_or_default();
+        println!(
+            "  {}  {}{growth}  {} files, {} dirs  risk: {}  owner: {}",
+            project.display_with(display),
+            project.formatted_size(display.units),
+            project.total_files(),
+            project.total_dirs(),
+            project.risk(&display.risk_overrides),
+            owner.bright_white()
+        );
+        if !project.target_breakdown.is_empty() {
+            let parts: Vec<String> = project
+                .target_breakdown
+                .iter()
+                .map(|subdir| {
+                    let path = subdir.path.strip_prefix(&project.root_path);
+                    let path = path.unwrap_or(&subdir.path).display();
+                    format!("{path} {}", display.bytes(subdir.size))
+                })
+                .collect();
+            println!("    {}", parts.join(", ").dimmed());
+        }
+    }
+    if collapsed.count > 0 {
+        println!("  {}", collapsed.describe(display.units).dimmed());
+    }
+}
+
+/// Print why each project was detected, for `--explain`.
+///
+/// Prints nothing unless the scan recorded detections.
+fn print_detections(projects: &Projects) {
+    let explained: Vec<_> = projects
+        .as_slice()
+        .iter()
+        .filter_map(|project| Some((project, project.detection.as_ref()?)))
+        .collect();
+    if explained.is_empty() {
+        return;
+    }
+
+    println!("\n{}", "Detection:".bold());
+    for (project, detection) in explained {
+        let markers = if detection.markers.is_empty() {
+            "none".to_string()
+        } else {
+            detection.markers.join(", ")
+        };
+        let artifacts: Vec<String> = detection
+            .artifacts
+            .iter()
+            .map(|artifact| {
+                artifact
+                    .strip_prefix(&project.root_path)
+                    .unwrap_or(artifact)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        println!(
+            "  {}",
+            project.root_path.display().to_string().bright_white()
+        );
+        println!("    detector:  {}", detection.detector);
+        println!("    markers:   {markers}");
+        println!("    artifacts: {}", artifacts.join(", "));
+    }
+}
+
+/// Trace how the scan treats the `--why` path and print each step.
+fn explain_path(
+    args: &Cli,
+    file_config: &FileConfig,
+    output_format: Option<OutputFormat>,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let Some(path) = args.why() else {
+        return Ok(());
+    };
+    let scanner = Scanner::new(
+        args.scan_options(file_config)?,
+        args.type_filter(file_config),
+    );
+    let trace = scanner.trace(
+        path,
+        &args.directories(file_config),
+        &args.filter_options(file_config),
+    )?;
+
+    if output_format.is_some() {
+        println!("{}", serde_json::to_string_pretty(&trace)?);
+    } else {
+        print_trace(&trace, display);
+    }
+    Ok(())
+}
+
+/// Print the steps of a `--why` trace.
+fn print_trace(trace: &PathTrace, display: &DisplayOptions) {
+    println!(
+        "{} {}",
+        "Why".bold(),
+        trace.path.display().to_string().bright_white()
+    );
+    let dir = trace.build_dir_of.as_deref().unwrap_or(&trace.path);
+    if let Some(owner) = &trace.build_dir_of {
+        println!("  a build directory of {}", owner.display());
+    }
+
+    println!("\n{}", "Walk:".bold());
+    if let Some(root) = &trace.scan_root {
+        println!("  inside scanned directory {}", root.display());
+    }
+    if trace.pruned_by.is_empty() {
+        println!("  {} checked for projects", "[OK]".green());
+    }
+    for reason in &trace.pruned_by {
+        println!("  {} {}: {}", "[--]".red(), reason.rule, reason.detail);
+    }
+
+    println!("\n{}", "Detectors:".bold());
+    for detector in &trace.detectors {
+        println!(
+            "  {:<8} {}",
+            detector.detector,
+            describe_detector(detector, dir)
+        );
+    }
+
+    if !trace.projects.is_empty() {
+        println!("\n{}", "Projects:".bold());
+    }
+    for project in &trace.projects {
+        let name = project.name.as_deref().unwrap_or("unnamed");
+        println!(
+            "  {} ({}, {}): {}",
+            project.root.display().to_string().bright_white(),
+            project.kind,
+            name,
+            display.bytes(project.size)
+        );
+        if project.filtered_by.is_empty() {
+            println!("    {} passes all filters", "[OK]".green());
+        }
+        for reason in &project.filtered_by {
+            println!("    {} {}: {}", "[--]".red(), reason.rule, reason.detail);
+        }
+    }
+
+    let verdict = if trace.cleaned {
+        "would be cleaned".green()
+    } else {
+        "would not be cleaned".yellow()
+    };
+    println!("\n{} {verdict}", "Result:".bold());
+}
+
+/// What a detector found, with its build directories shown relative to `dir`.
+fn describe_detector(detector: &DetectorTrace, dir: &Path) -> String {
+    let found = if detector.markers_found.is_empty() {
+        "matched".to_string()
+    } else {
+        format!("found {}", detector.markers_found.join(", "))
+    };
+    match detector.outcome {
+        DetectorOutcome::Skipped => "skipped by the project type filter".dimmed().to_string(),
+        DetectorOutcome::NoMarkers if detector.markers_missing.is_empty() => {
+            "not a project of this type".dimmed().to_string()
+        }
+        DetectorOutcome::NoMarkers => format!("missing {}", detector.markers_missing.join(", "))
+            .dimmed()
+            .to_string(),
+        DetectorOutcome::NothingToClean => format!("{found}, but nothing to clean"),
+        DetectorOutcome::Claimed => {
+            format!("{found}, but an earlier detector claimed its build directories")
+        }
+        DetectorOutcome::Detected => {
+            let artifacts: Vec<String> = detector
+                .artifacts
+                .iter()
+                .map(|artifact| {
+                    artifact
+                        .strip_prefix(dir)
+                        .unwrap_or(artifact)
+                        .display()
+                        .to_string()
+                })
+                .collect();
+            format!("{found}, cleans {}", artifacts.join(", "))
+                .green()
+                .to_string()
+        }
+    }
+}
+
+/// Print the number of projects and the space of each age bucket at the
+/// time `now`, for `--bucket-ages`.
+fn print_age_buckets(projects: &[Project], now: SystemTime, display: &DisplayOptions) {
+    let totals = bucket_totals(projects, now);
+    println!("\n{}", "Projects by age of their build artifacts:".bold());
+    for total in &totals {
+        let line = format!(
+            "  {:<8} {:>4} projects  {:>10}",
+            total.bucket.label(),
+            total.count,
+            display.bytes(total.size)
+        );
+        if total.count == 0 {
+            println!("{}", line.dimmed());
+        } else {
+            println!("{}", line.bright_white());
+        }
+    }
+
+    let count: usize = totals.iter().map(|t| t.count).sum();
+    let size: u64 = totals.iter().map(|t| t.size).sum();
+    println!(
+        "  {:<8} {count:>4} projects  {:>10}",
+        "Total".bold(),
+        display.bytes(size)
+    );
+    let undated = projects.len() - count;
+    if undated > 0 {
+        println!(
+            "{}",
+            format!("  {undated} projects aren't in any bucket: their age can't be read.").dimmed()
+        );
+    }
+    println!(
+        "\n{}",
+        "Use --clean-bucket <BUCKET> to clean all projects of a bucket.".dimmed()
+    );
+}
+
+/// Keep only the projects needed to bring budgeted roots under their budget,
+/// reporting how each budgeted root stands.
+fn apply_root_budgets(
+    all: &[Project],
+    candidates: Vec<Project>,
+    budgets: &[RootBudget],
+    quiet: bool,
+    display: &DisplayOptions,
+) -> Vec<Project> {
+    let (planned, reports) = plan_budget_cleanup(all, candidates, budgets);
+
+    if !quiet {
+        for report in &reports {
+            let usage = format!(
+                "{} of {} budget",
+                display.bytes(report.total),
+                display.bytes(report.budget)
+            );
+            if !report.is_over_budget() {
+                println!(
+                    "  {} {usage} - {}",
+                    report.root.display(),
+                    "within budget".green()
+                );
+                continue;
+            }
+
+            println!(
+                "  {} {usage} - proposing {} project(s) ({})",
+                report.root.display(),
+                report.proposed_count,
+                display.bytes(report.proposed_size)
+            );
+            if !report.is_satisfied() {
+                println!(
+                    "    {}",
+                    "not enough cleanable projects to get back under budget".yellow()
+                );
+            }
+        }
+    }
+
+    planned
+}
+
+/// Select, confirm and clean the projects, or preview them in a dry run.
+///
+/// A dry run records the selected projects in `plan_file`, if given.
+/// Returns the number of bytes freed, which is 0 unless a cleanup ran.
+fn clean_or_preview(
+    projects: Projects,
+    roots: &[PathBuf],
+    plan_file: Option<&Path>,
+    output_format: Option<OutputFormat>,
+    progress: ProgressMode,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    display: &DisplayOptions,
+) -> Result<u64> {
+    let Some((mut projects, keep_executables)) = resolve_keep_executables(projects, opts, display)?
+    else {
+        return Ok(0);
+    };
+
+    if opts.dry_run {
+        if let Some(path) = plan_file {
+            // A plan records exact sizes to check against when it's applied,
+            // not the lower bounds of --quick-size-threshold.
+            projects.measure_estimated_sizes();
+            save_plan(&projects, path, output_format.is_some(), display)?;
+        }
+        print_dry_run(&projects, output_format, opts, display)?;
+        return Ok(0);
+    }
+
+    // Directories that were only sized up to --quick-size-threshold are
+    // measured now that they're going to be removed.
+    projects.measure_estimated_sizes();
+
+    let projects = confirm_tools_in_use(projects, opts.yes, display)?;
+    if projects.as_slice().is_empty() {
+        println!("{}", display.text(Message::CleanupCancelled).yellow());
+        return Ok(0);
+    }
+
+    if !opts.yes && !confirm_cleanup(&projects, opts.use_trash, display)? {
+        println!("{}", display.text(Message::CleanupCancelled).yellow());
+        return Ok(0);
+    }
+
+    run_cleanup(
+        projects,
+        roots,
+        keep_executables,
+        output_format,
+        progress,
+        opts,
+        display,
+    )
+}
+
+/// Record the projects' build directories as a cleanup plan in `path`.
+fn save_plan(
+    projects: &Projects,
+    path: &Path,
+    quiet: bool,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let plan = CleanupPlan::from_projects(projects.as_slice());
+    plan.save(path)?;
+
+    if !quiet {
+        let saved = Message::PlanSaved.format(
+            display.lang,
+            &[("count", &plan.artifact_count()), ("path", &path.display())],
+        );
+        println!("\n{saved}");
+    }
+    Ok(())
+}
+
+/// Detect the projects at the roots of `plan` again, the way a scan with
+/// the current options would select them.
+///
+/// The type filter, the project filters and the skip rules (`skip_dirty`,
+/// other users' projects, snoozes) apply as they do to a scan, so a plan
+/// can't remove anything a scan wouldn't.
+fn detect_planned(
+    plan: &CleanupPlan,
+    args: &Cli,
+    file_config: &FileConfig,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    quiet: bool,
+    display: &DisplayOptions,
+) -> Result<Vec<Project>> {
+    let scanner = Scanner::new(
+        args.scan_options(file_config)?,
+        args.type_filter(file_config),
+    )
+    .with_progress(ProgressMode::Hidden);
+    let projects = filter_projects(
+        scanner.scan_roots(&plan.roots()),
+        &args.filter_options(file_config),
+    )?;
+    skip_excluded_projects(projects, opts, quiet, false, display)
+}
+
+/// Keep the build directories of a cleanup plan that are still safe to remove.
+///
+/// Every rejected directory is reported with the reason, followed by a
+/// summary of what is left.
+fn check_plan(
+    plan: CleanupPlan,
+    path: &Path,
+    tolerance_percent: u32,
+    detected: &[Project],
+    trashing: bool,
+    quiet: bool,
+    display: &DisplayOptions,
+) -> Vec<Project> {
+    let created_at = plan.created_at.clone();
+    let check = plan.validate(tolerance_percent, detected);
+
+    if !quiet {
+        let loaded = Message::PlanLoaded.format(
+            display.lang,
+            &[("path", &path.display()), ("created", &created_at)],
+        );
+        println!("{loaded}");
+        for (dir, rejection) in &check.rejected {
+            println!(
+                "  {} {}",
+                dir.display(),
+                Message::PlanRejected
+                    .format(display.lang, &[("reason", rejection)])
+                    .yellow()
+            );
+        }
+        if !check.projects.is_empty() {
+            let projects = Projects::from(check.projects.clone());
+            println!("\n{}", display.text(Message::PlannedProjects).bold());
+            projects.print_summary(projects.get_total_size(), trashing, display);
+        }
+    }
+
+    check.projects
+}
+
+/// Handle interactive project selection and the keep-executables prompt.
+///
+/// Returns `Ok(Some((projects, keep)))` where `projects` is the user-selected
+/// subset (interactive mode) or the full set (non-interactive), and `keep` is
+/// the resolved keep-executables flag. Returns `Ok(None)` when the user
 /// selected zero projects (caller should exit).
 fn resolve_keep_executables(
     projects: Projects,
     opts: &clean_dev_dirs::ExecutionOptions,
+    display: &DisplayOptions,
 ) -> Result<Option<(Projects, bool)>> {
     let mut keep = opts.keep_executables;
 
-    if opts.interactive {
-        let selected = projects.interactive_selection()?;
-        if selected.is_empty() {
-            println!("{}", "No projects selected for cleaning!".green());
-            return Ok(None);
+    if opts.interactive {
+        let selected = projects.interactive_selection(opts.preselect, display)?;
+        if selected.is_empty() {
+            println!("{}", display.text(Message::NoneSelected).green());
+            return Ok(None);
+        }
+
+        if !keep {
+            keep = Confirm::new(display.text(Message::KeepExecutables))
+                .with_default(false)
+                .prompt()?;
+        }
+
+        return Ok(Some((Projects::from(selected), keep)));
+    }
+
+    Ok(Some((projects, keep)))
+}
+
+/// Warn about projects that a build daemon or language server is working in,
+/// and unless `yes` is set, ask whether to clean each of them anyway.
+///
+/// Returns the projects to go ahead with.
+fn confirm_tools_in_use(
+    projects: Projects,
+    yes: bool,
+    display: &DisplayOptions,
+) -> Result<Projects> {
+    let tools = running_tools();
+    if tools.is_empty() {
+        return Ok(projects);
+    }
+
+    let mut kept = Vec::new();
+    for project in projects.as_slice() {
+        let users: Vec<String> = tools
+            .iter()
+            .filter(|process| process.uses(project))
+            .map(|process| format!("{} (pid {})", process.tool, process.pid))
+            .collect();
+        if !users.is_empty() {
+            let path = project.root_path.display();
+            eprintln!(
+                "{} {}",
+                display.text(Message::Warning).yellow(),
+                Message::ToolsInUse.format(
+                    display.lang,
+                    &[("path", &path), ("tools", &users.join(", "))]
+                )
+            );
+            if !yes
+                && !confirm(
+                    &Message::CleanAnyway.format(display.lang, &[("path", &path)]),
+                    display,
+                )?
+            {
+                continue;
+            }
+        }
+        kept.push(project.clone());
+    }
+
+    Ok(Projects::from(kept))
+}
+
+/// Ask the user to confirm before proceeding with deletion.
+///
+/// Only called when `--yes`/`-y` was not passed; dry runs and machine-readable
+/// output never get here. The default answer is no, and cancelling the prompt
+/// counts as no. Returns `Ok(true)` to proceed, `Ok(false)` to abort.
+///
+/// # Errors
+///
+/// Returns an error when there is no terminal to ask on, so that unattended
+/// runs without `--yes` fail instead of hanging or deleting unconfirmed.
+fn confirm_cleanup(projects: &Projects, use_trash: bool, display: &DisplayOptions) -> Result<bool> {
+    let count: usize = projects.as_slice().iter().map(|p| p.build_arts.len()).sum();
+    let size_str = display.bytes(projects.get_total_size());
+    let dirs = if count == 1 {
+        Message::Directory
+    } else {
+        Message::Directories
+    };
+    let question = if use_trash {
+        Message::ConfirmTrash
+    } else {
+        Message::ConfirmDelete
+    };
+
+    confirm(
+        &question.format(
+            display.lang,
+            &[
+                ("count", &count),
+                ("dirs", &display.text(dirs)),
+                ("size", &size_str),
+            ],
+        ),
+        display,
+    )
+}
+
+/// Ask a yes/no question that defaults to no.
+///
+/// # Errors
+///
+/// Returns an error when there is no terminal to ask on.
+fn confirm(question: &str, display: &DisplayOptions) -> Result<bool> {
+    match Confirm::new(question).with_default(false).prompt() {
+        std::result::Result::Ok(confirmed) => Ok(confirmed),
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(false),
+        Err(InquireError::NotTTY) => {
+            bail!(display.text(Message::NoTerminal))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Scan the `--host` machines over SSH, then clean them after confirmation.
+///
+/// Hosts that can't be scanned are reported and left out.
+fn run_remote(
+    hosts: &[RemoteHost],
+    opts: &clean_dev_dirs::ExecutionOptions,
+    verbose: bool,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let mut scanned = Vec::new();
+    for host in hosts {
+        let scanning = Message::ScanningHost.format(display.lang, &[("host", &host.host)]);
+        println!("{scanning}");
+        match host.scan() {
+            std::result::Result::Ok(output) if output.projects.is_empty() => {
+                let nothing = display.text(Message::HostNothingToClean);
+                println!("  {}: {}", host.host, nothing.green());
+            }
+            std::result::Result::Ok(output) => scanned.push((host, output)),
+            Err(e) => eprintln!("{} {e:#}", display.text(Message::Warning).yellow()),
+        }
+    }
+
+    if scanned.is_empty() {
+        println!("{}", display.text(Message::NothingOnAnyHost).green());
+        return Ok(());
+    }
+
+    println!("\n{}", display.text(Message::FoundProjects).bold());
+    for (host, output) in &scanned {
+        print_remote_projects(&host.host, output, verbose, display);
+    }
+    let count: usize = scanned.iter().map(|(_, o)| o.summary.total_projects).sum();
+    let size = display.bytes(
+        scanned
+            .iter()
+            .map(|(_, o)| o.summary.total_size)
+            .sum::<u64>(),
+    );
+    let total = size.bright_green().bold();
+    let total = Message::TotalReclaimable.format(display.lang, &[("size", &total)]);
+    println!("  {total}");
+
+    let proceed = if opts.dry_run {
+        println!(
+            "\n{} {}",
+            display.text(Message::DryRunComplete).yellow(),
+            Message::WouldFree.format(display.lang, &[("size", &size)])
+        );
+        false
+    } else {
+        let question = Message::ConfirmRemote.format(
+            display.lang,
+            &[
+                ("count", &count),
+                ("size", &size),
+                ("hosts", &scanned.len()),
+            ],
+        );
+        let confirmed = opts.yes || confirm(&question, display)?;
+        if !confirmed {
+            println!("{}", display.text(Message::CleanupCancelled).yellow());
+        }
+        confirmed
+    };
+    if !proceed {
+        for (host, _) in &scanned {
+            host.discard_plan();
         }
+        return Ok(());
+    }
 
-        if !keep {
-            keep = Confirm::new("Keep compiled executables before cleaning?")
-                .with_default(false)
-                .prompt()?;
+    let mut freed = 0;
+    for (host, _) in &scanned {
+        let cleaning = Message::CleaningHost.format(display.lang, &[("host", &host.host)]);
+        println!("{cleaning}");
+        match host.apply() {
+            std::result::Result::Ok(output) => {
+                if let Some(cleanup) = output.cleanup {
+                    freed += cleanup.total_freed;
+                    let cleaned = Message::HostCleaned.format(
+                        display.lang,
+                        &[
+                            ("host", &host.host),
+                            ("count", &cleanup.success_count),
+                            ("size", &cleanup.total_freed_formatted.bright_green()),
+                        ],
+                    );
+                    println!("  {cleaned}");
+                    for error in &cleanup.errors {
+                        eprintln!("    {}", error.red());
+                    }
+                }
+            }
+            Err(e) => eprintln!("{} {e:#}", display.text(Message::Warning).yellow()),
         }
+    }
+    let freed = display.bytes(freed).bright_green().bold();
+    let freed = Message::SpaceFreed.format(display.lang, &[("size", &freed)]);
+    println!("\n{freed}");
 
-        return Ok(Some((Projects::from(selected), keep)));
+    Ok(())
+}
+
+/// Report the reclaimable Docker data, then prune it after confirmation.
+///
+/// An unreachable Docker daemon is reported as a warning; the build
+/// directories have already been taken care of at this point.
+fn run_docker(
+    docker: &Docker,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let usage = match docker.usage() {
+        std::result::Result::Ok(usage) => usage,
+        Err(e) => {
+            eprintln!("{} {e:#}", display.text(Message::Warning).yellow());
+            return Ok(());
+        }
+    };
+
+    if usage.is_empty() {
+        println!("{}", display.text(Message::NoDockerData).green());
+        return Ok(());
     }
 
-    Ok(Some((projects, keep)))
+    let items: Vec<_> = usage
+        .iter()
+        .map(|item| (format!("{} ({})", item.resource, item.count), item.size))
+        .collect();
+    clean_extras(
+        "Docker",
+        display.text(Message::DockerData),
+        &items,
+        opts,
+        |i| Ok(docker.prune(usage[i].resource)?),
+        display,
+    )
 }
 
-/// Ask the user to confirm before proceeding with deletion.
+/// Run the subcommands that work on the saved state or the config file
+/// rather than scanning, or return `None` for the others.
+fn run_state_command(
+    command: Option<&Commands>,
+    file_config: &FileConfig,
+    display: &DisplayOptions,
+) -> Option<Result<()>> {
+    let paths = Paths::from_config(file_config);
+    Some(match command? {
+        Commands::Snooze {
+            path,
+            duration,
+            list,
+            clear,
+        } => snooze(&paths, path.as_deref(), *duration, *list, *clear),
+        Commands::Trash {
+            command: TrashCommand::Empty,
+        } => empty_trash(&paths),
+        Commands::History { since, limit } => show_history(&paths, *since, *limit, display),
+        Commands::Status => show_status(&paths),
+        Commands::Query { sql } => query_scans(&paths, sql.as_deref()),
+        Commands::Discover { yes } => discover(*yes, display),
+        _ => return None,
+    })
+}
+
+/// Report the package manager caches found, then clean them after confirmation.
+fn run_system_caches(
+    caches: &[SystemCache],
+    opts: &clean_dev_dirs::ExecutionOptions,
+    verbose: bool,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let usage = caches::measure(caches);
+
+    if usage.is_empty() {
+        println!("{}", display.text(Message::NoSystemCaches).green());
+        return Ok(());
+    }
+
+    let items: Vec<_> = usage
+        .iter()
+        .map(|item| {
+            let mut label = item.cache.to_string();
+            if verbose {
+                let paths: Vec<_> = item.paths.iter().map(|p| p.display().to_string()).collect();
+                label = format!("{label} [{}]", paths.join(", "));
+            }
+            (label, item.size)
+        })
+        .collect();
+    let strategy = RemovalStrategy::from_use_trash(opts.use_trash);
+    clean_extras(
+        display.text(Message::SystemCaches),
+        display.text(Message::SystemCachesNoun),
+        &items,
+        opts,
+        |i| Ok(usage[i].clean(strategy)?),
+        display,
+    )
+}
+
+/// List `items` (labels with their sizes) under `heading`, then clean them
+/// after confirmation, or only preview them in a dry run.
 ///
-/// Skipped when `--yes`/`-y` was passed or `--json` mode is active.
-/// Returns `Ok(true)` to proceed, `Ok(false)` to abort.
-fn confirm_cleanup(count: usize, total_size: u64, yes: bool, json_mode: bool) -> Result<bool> {
-    if yes || json_mode {
-        return Ok(true);
-    }
-    let size_str = format_size(total_size, DECIMAL);
-    let plural = if count == 1 { "" } else { "s" };
-    let confirmed = Confirm::new(&format!("Clean {count} project{plural} ({size_str})?"))
-        .with_default(false)
-        .prompt()?;
-    Ok(confirmed)
-}
-
-/// Print dry-run results in JSON or human-readable format.
-fn print_dry_run(projects: &Projects, json_mode: bool) -> Result<()> {
-    if json_mode {
-        let output = JsonOutput::from_projects_dry_run(projects.as_slice());
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        let size = projects.get_total_size();
+/// `clean` cleans the item at the given index and returns the space freed.
+/// Failures are reported as warnings and don't stop the other items.
+fn clean_extras(
+    heading: &str,
+    noun: &str,
+    items: &[(String, u64)],
+    opts: &clean_dev_dirs::ExecutionOptions,
+    mut clean: impl FnMut(usize) -> Result<u64>,
+    display: &DisplayOptions,
+) -> Result<()> {
+    println!("\n{}", format!("{heading}:").bold());
+    for (label, size) in items {
+        println!(
+            "  {}: {}",
+            label.bold(),
+            display.bytes(*size).bright_white()
+        );
+    }
+    let size = display.bytes(items.iter().map(|(_, size)| size).sum::<u64>());
+    let total = size.bright_green().bold();
+    let total = Message::TotalReclaimable.format(display.lang, &[("size", &total)]);
+    println!("  {total}");
+
+    if opts.dry_run {
         println!(
             "\n{} {}",
-            "[dry-run] Complete.".yellow(),
-            format!("Would free up {}", format_size(size, DECIMAL)).bright_white()
+            display.text(Message::DryRunComplete).yellow(),
+            Message::WouldFree.format(display.lang, &[("size", &size)])
         );
+        return Ok(());
+    }
+
+    let question = Message::ConfirmRemove.format(display.lang, &[("size", &size), ("what", &noun)]);
+    if !opts.yes && !confirm(&question, display)? {
+        println!("{}", display.text(Message::RemovalCancelled).yellow());
+        return Ok(());
+    }
+
+    let mut freed = 0;
+    for i in 0..items.len() {
+        match clean(i) {
+            std::result::Result::Ok(reclaimed) => freed += reclaimed,
+            Err(e) => eprintln!("{} {e:#}", display.text(Message::Warning).yellow()),
+        }
     }
+    let freed = display.bytes(freed).bright_green().bold();
+    let freed = Message::SpaceFreedFrom.format(display.lang, &[("what", &noun), ("size", &freed)]);
+    println!("\n{freed}");
+
     Ok(())
 }
 
-/// Perform the actual cleanup and print results.
+/// Print what a remote scan found on `host`.
+///
+/// In verbose mode each project is listed with its size.
+fn print_remote_projects(host: &str, output: &JsonOutput, verbose: bool, display: &DisplayOptions) {
+    let found = Message::HostProjects.format(
+        display.lang,
+        &[
+            ("host", &host.bold()),
+            (
+                "count",
+                &output.summary.total_projects.to_string().bright_white(),
+            ),
+            ("size", &output.summary.total_size_formatted.bright_white()),
+        ],
+    );
+    println!("  {found}");
+    if verbose {
+        for project in &output.projects {
+            println!(
+                "    {}  {}",
+                project.root_path, project.build_artifacts_size_formatted
+            );
+        }
+    }
+}
+
+/// Print dry-run results in the requested format or human-readable form.
+///
+/// The files asked for with `--json-file` and `--report-html` are written as well.
+fn print_dry_run(
+    projects: &Projects,
+    output_format: Option<OutputFormat>,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    display: &DisplayOptions,
+) -> Result<()> {
+    if let Some(format) = output_format {
+        print_bytes(&render(format, projects.as_slice(), None, display)?)?;
+    } else {
+        let size = display.bytes(projects.get_total_size());
+        let would_free = if projects.size_is_estimated() {
+            Message::WouldFreeAtLeast
+        } else {
+            Message::WouldFree
+        };
+        println!(
+            "\n{} {}",
+            display.text(Message::DryRunComplete).yellow(),
+            would_free
+                .format(display.lang, &[("size", &size)])
+                .bright_white()
+        );
+    }
+    write_result_files(projects.as_slice(), None, opts, display)
+}
+
+/// Perform the actual cleanup, print results, and run post-cleanup hooks.
+///
+/// Returns the number of bytes freed.
 fn run_cleanup(
     projects: Projects,
+    roots: &[PathBuf],
     keep_executables: bool,
-    json_mode: bool,
+    output_format: Option<OutputFormat>,
+    progress: ProgressMode,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    display: &DisplayOptions,
+) -> Result<u64> {
+    let journal = start_journal(roots, opts)?;
+    let removal = Removal {
+        strategy: RemovalStrategy::from_use_trash(opts.use_trash),
+        retry: RetryPolicy {
+            retries: opts.delete_retries,
+            delay: opts.delete_retry_delay,
+        },
+        prune_empty_parents: opts.prune_empty_parents,
+        batch_trash: opts.batch_trash,
+        rename_first: opts.rename_first,
+        journal: journal.as_ref(),
+    };
+    let snapshot: Vec<_> = projects.as_slice().to_vec();
+    let started = chrono::Utc::now().timestamp();
+    let mut result = Cleaner::clean_projects(
+        projects,
+        keep_executables.then_some(opts.keep_latest_only),
+        progress,
+        removal,
+        opts.pre_project_clean
+            .as_deref()
+            .map(|template| PreCleanHook {
+                template,
+                stdout_to_stderr: output_format.is_some(),
+            }),
+        opts.verify_sizes,
+        display,
+    );
+
+    let trash_items = settle_trash(&mut result, started, opts.empty_trash, display);
+    if let Some(journal) = &journal
+        && let Err(e) = journal.finish()
+    {
+        eprintln!("{} {e:#}", display.text(Message::Warning).yellow());
+    }
+
+    if let Some(format) = output_format {
+        print_bytes(&render(format, &snapshot, Some(&result), display)?)?;
+    } else {
+        Cleaner::print_summary(&result, display);
+    }
+
+    offer_permission_plan(&result, &opts.paths, opts.use_trash, display);
+    run_post_clean_hooks(&result, opts, output_format.is_some(), display);
+    record_history(&opts.paths, roots, &result, trash_items, display);
+    forget_cleaned_sizes(&opts.paths, &snapshot, display);
+
+    write_result_files(&snapshot, Some(&result), opts, display)?;
+
+    Ok(result.total_freed)
+}
+
+/// Look up the trash entries of the build directories `result` moved to the
+/// trash since `since`, and purge them right away with `empty_trash`.
+///
+/// Returns the entries left in the trash, to record in the history.
+fn settle_trash(
+    result: &mut CleanResult,
+    since: i64,
+    empty_trash: bool,
+    display: &DisplayOptions,
+) -> Vec<TrashedItem> {
+    if result.trashed.is_empty() {
+        return Vec::new();
+    }
+    let items = match trash_bin::identify(&result.trashed, since) {
+        std::result::Result::Ok(items) => items,
+        Err(e) => {
+            eprintln!("{} {e}", display.text(Message::Warning).yellow());
+            return Vec::new();
+        }
+    };
+
+    // Once they're out of the trash too, the space is reclaimed like a
+    // permanent deletion and there's nothing left to empty later.
+    if empty_trash {
+        match trash_bin::purge(&items) {
+            std::result::Result::Ok(_) => {
+                result.trashed.clear();
+                return Vec::new();
+            }
+            Err(e) => eprintln!("{} {e}", display.text(Message::Warning).yellow()),
+        }
+    }
+    items
+}
+
+/// Record the build directories that couldn't be removed for lack of
+/// permission in a plan, and show the command that removes them with
+/// elevated privileges.
+///
+/// Nothing is ever run elevated on the user's behalf. The plan goes to
+/// `permission-denied-plan.json` in the state directory, replacing the one
+/// of an earlier run. Messages go to stderr, so they don't mix with `--json`.
+fn offer_permission_plan(
+    result: &CleanResult,
+    paths: &Paths,
     use_trash: bool,
+    display: &DisplayOptions,
+) {
+    if result.permission_denied.is_empty() {
+        return;
+    }
+    let plan = CleanupPlan::from_remaining(&result.permission_denied);
+    let Some(dir) = paths.state_dir() else {
+        return;
+    };
+    let path = dir.join("permission-denied-plan.json");
+    let path = std::path::absolute(&path).unwrap_or(path);
+    if plan.projects.is_empty() {
+        return;
+    }
+
+    let saved = std::fs::create_dir_all(&dir)
+        .map_err(anyhow::Error::from)
+        .and_then(|()| Ok(plan.save(&path)?));
+    if let Err(e) = saved {
+        eprintln!("{} {e}", display.text(Message::Warning).yellow());
+        return;
+    }
+
+    // sudo resets PATH, which often leaves out ~/.cargo/bin, so the command
+    // names this executable by its full path. The projects belong to the
+    // user, not to root, so the elevated run needs --all-users to not skip
+    // them when re-detecting the plan.
+    let exe = std::env::current_exe()
+        .map_or_else(|_| "clean-dev-dirs".to_string(), |exe| quote_arg(&exe));
+    let mut command = format!("{exe} --apply-plan {} --all-users", quote_arg(&path));
+    if !use_trash {
+        command.push_str(" --permanent");
+    }
+    eprintln!(
+        "\n{} {}",
+        "[!]".yellow(),
+        Message::PermissionDenied.format(display.lang, &[("count", &plan.artifact_count())])
+    );
+    eprintln!("  {}", display.text(Message::OwnedByRoot));
+    if cfg!(windows) {
+        eprintln!("  {}", display.text(Message::RunAsAdministrator));
+        eprintln!("    {}", command.bold());
+    } else {
+        eprintln!("  {}", display.text(Message::RunToRemove));
+        eprintln!("    {}", format!("sudo {command}").bold());
+    }
+}
+
+/// `path` as a single shell argument, quoted if it needs to be.
+///
+/// POSIX shells get single quotes, in which nothing is special except the
+/// quote itself (written as `'\''`); Windows shells get double quotes.
+fn quote_arg(path: &Path) -> String {
+    let arg = path.display().to_string();
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/\\:._-".contains(c))
+    {
+        arg
+    } else if cfg!(windows) {
+        format!("\"{arg}\"")
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Start the journal of a cleanup of `roots`, if `opts` asks for one.
+///
+/// A journal that can't be written stops the run before anything is removed,
+/// since it was asked for to account for every removal.
+fn start_journal(
+    roots: &[PathBuf],
+    opts: &clean_dev_dirs::ExecutionOptions,
+) -> Result<Option<Journal>> {
+    if !opts.journal {
+        return Ok(None);
+    }
+    let Some(path) = Journal::default_path(&opts.paths) else {
+        bail!("Could not determine the state directory for the journal");
+    };
+    Ok(Journal::start(&path, roots).map(Some)?)
+}
+
+/// Append the finished run to the history log shown by `history`.
+///
+/// Failures are reported as warnings on stderr; the cleanup itself already succeeded.
+fn record_history(
+    paths: &Paths,
+    roots: &[PathBuf],
+    result: &CleanResult,
+    trash_items: Vec<TrashedItem>,
+    display: &DisplayOptions,
+) {
+    let Some(path) = history::history_path(paths) else {
+        return;
+    };
+
+    let record = RunRecord::new(roots.to_vec(), result, trash_items);
+    if let Err(e) = history::append(&path, &record) {
+        eprintln!("{} {e}", display.text(Message::Warning).yellow());
+    }
+}
+
+// ── Status subcommand ────────────────────────────────────────────────
+
+/// Show what the journal says about the last journaled cleanup run.
+fn show_status(paths: &Paths) -> Result<()> {
+    let Some(path) = Journal::default_path(paths) else {
+        bail!("Could not determine the state directory for the journal");
+    };
+    let summary = JournalSummary::from_entries(&journal::load(&path)?);
+    let Some(started_at) = &summary.started_at else {
+        println!(
+            "{}",
+            "No journaled cleanup run yet; pass --journal or set journal = true under [execution]."
+                .yellow()
+        );
+        return Ok(());
+    };
+
+    let roots: Vec<String> = summary
+        .roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect();
+    println!(
+        "{} started {}{} in {}",
+        "Last journaled run:".bold(),
+        local_time(started_at),
+        summary
+            .pid
+            .map(|pid| format!(" (pid {pid})"))
+            .unwrap_or_default(),
+        roots.join(", ").dimmed()
+    );
+    match &summary.finished_at {
+        Some(finished_at) => println!("  {} {}", "Completed".green(), local_time(finished_at)),
+        None => println!(
+            "  {}",
+            "Did not complete: it is still running, or it was stopped".red()
+        ),
+    }
+
+    let removed = summary.with_outcome(&RemovalOutcome::Removed);
+    println!("  {} build directories removed", removed.len());
+    for removal in &summary.removals {
+        match &removal.outcome {
+            RemovalOutcome::Removed => {}
+            RemovalOutcome::Failed(error) => {
+                println!(
+                    "  {} {}: {error}",
+                    "Failed:".yellow(),
+                    removal.path.display()
+                );
+            }
+            RemovalOutcome::Interrupted => {
+                let state = if removal.path.exists() {
+                    "still there, possibly partly removed"
+                } else {
+                    "gone"
+                };
+                println!(
+                    "  {} {} ({state})",
+                    "Interrupted:".red(),
+                    removal.path.display()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// An RFC 3339 timestamp in local time, or unchanged if it can't be parsed.
+fn local_time(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp).map_or_else(
+        |_| timestamp.to_string(),
+        |at| {
+            at.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        },
+    )
+}
+
+// ── Trash subcommand ─────────────────────────────────────────────────
+
+/// Permanently delete every build directory a recorded run moved to the trash.
+///
+/// Other items in the trash are left alone. The recorded entries are dropped
+/// from the history afterwards, since they're purged or already gone.
+fn empty_trash(paths: &Paths) -> Result<()> {
+    let Some(path) = history::history_path(paths) else {
+        bail!("Could not determine the state directory for the history log");
+    };
+
+    let items: Vec<TrashedItem> = history::load(&path)?
+        .into_iter()
+        .flat_map(|record| record.trash_items)
+        .collect();
+    let count = trash_bin::purge(&items)?;
+    history::forget_trash_items(&path)?;
+
+    if count == 0 {
+        println!("{}", "No build directories found in the trash.".yellow());
+    } else {
+        println!("Removed {count} build directories from the trash.");
+    }
+    Ok(())
+}
+
+// ── Snooze subcommand ────────────────────────────────────────────────
+
+/// Snooze `path`, or list or clear the snoozes.
+fn snooze(
+    paths: &Paths,
+    path: Option<&Path>,
+    duration: Duration,
+    list: bool,
+    clear: bool,
 ) -> Result<()> {
-    let removal_strategy = RemovalStrategy::from_use_trash(use_trash);
-    let snapshot: Vec<_> = projects.as_slice().to_vec();
-    let result = Cleaner::clean_projects(projects, keep_executables, json_mode, removal_strategy);
+    let Some(file) = Snoozes::default_path(paths) else {
+        bail!("Could not determine the state directory for the snooze file");
+    };
+    let mut snoozes = Snoozes::load(&file)?;
+
+    if list {
+        if snoozes.is_empty() {
+            println!("Nothing is snoozed.");
+        }
+        for (path, until) in snoozes.iter() {
+            let until = until.map_or_else(String::new, |t| t.format("%Y-%m-%d %H:%M").to_string());
+            println!("  {}  until {until}", path.display());
+        }
+        return Ok(());
+    }
+
+    let path = path
+        .map(|p| {
+            std::fs::canonicalize(p)
+                .map_err(|e| anyhow::anyhow!("Cannot snooze {}: {e}", p.display()))
+        })
+        .transpose()?;
+
+    match (path, clear) {
+        (Some(path), true) => {
+            if snoozes.remove(&path) {
+                println!("Unsnoozed {}", path.display());
+            } else {
+                println!("{} was not snoozed", path.display());
+            }
+        }
+        (None, _) => {
+            snoozes.clear();
+            println!("Cleared all snoozes");
+        }
+        (Some(path), false) => {
+            let until = snoozes.add(path.clone(), duration)?;
+            println!(
+                "Snoozed {} until {}",
+                path.display(),
+                until.format("%Y-%m-%d %H:%M")
+            );
+        }
+    }
+
+    Ok(snoozes.save(&file)?)
+}
+
+// ── Discover subcommand ──────────────────────────────────────────────
+
+/// Suggest likely code roots and offer to save them as `dirs` in the config file.
+fn discover(yes: bool, display: &DisplayOptions) -> Result<()> {
+    let Some(home) = dirs::home_dir() else {
+        bail!("Could not determine the home directory");
+    };
+    let Some(path) = FileConfig::config_path() else {
+        bail!("Could not determine the config directory on this platform");
+    };
+
+    let candidates = discover::discover_roots(&home);
+    if candidates.is_empty() {
+        println!(
+            "{}",
+            "No likely code roots found in the home directory.".yellow()
+        );
+        return Ok(());
+    }
+
+    println!("{}", "Likely code roots:".bold());
+    let dirs: Vec<String> = candidates
+        .iter()
+        .map(|c| discover::display_path(&c.path, &home))
+        .collect();
+    for (dir, candidate) in dirs.iter().zip(&candidates) {
+        println!(
+            "  {:<24} {} checkout{}",
+            dir.bright_white(),
+            candidate.checkouts,
+            if candidate.checkouts == 1 { "" } else { "s" },
+        );
+    }
 
-    if json_mode {
-        let output = JsonOutput::from_projects_cleanup(&snapshot, &result);
-        println!("{}", serde_json::to_string_pretty(&output)?);
+    let content = if path.exists() {
+        std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read config file {}: {e}", path.display()))?
     } else {
-        Cleaner::print_summary(&result);
+        CONFIG_TEMPLATE.to_string()
+    };
+    let updated = match discover::config_with_dirs(&content, &dirs) {
+        std::result::Result::Ok(updated) => updated,
+        Err(e) => {
+            println!("\n{e} ({}); not changing it.", path.display());
+            return Ok(());
+        }
+    };
+
+    let question = format!("Save them as `dirs` in {}?", path.display());
+    let save = yes || (std::io::stdin().is_terminal() && confirm(&question, display)?);
+    if !save {
+        println!("\nRun `clean-dev-dirs discover --yes` to save them.");
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, updated)
+        .map_err(|e| anyhow::anyhow!("Failed to write config file {}: {e}", path.display()))?;
+    println!("Saved to: {}", path.display());
+    Ok(())
+}
+
+// ── Query subcommand ─────────────────────────────────────────────────
+
+/// Print the result of `sql` on the scan database as tab-separated lines
+/// with a header, or the database schema when there is no `sql`.
+#[cfg(feature = "scan-db")]
+fn query_scans(paths: &Paths, sql: Option<&str>) -> Result<()> {
+    let Some(sql) = sql else {
+        print!("{}", scan_db::SCHEMA);
+        return Ok(());
+    };
+    let Some(path) = scan_db::database_path(paths) else {
+        bail!("Could not determine the state directory for the scan database");
+    };
+
+    let result = scan_db::query(&path, sql)?;
+    println!("{}", result.columns.join("\t"));
+    for row in &result.rows {
+        let values: Vec<&str> = row.iter().map(|v| v.as_deref().unwrap_or("")).collect();
+        println!("{}", values.join("\t"));
+    }
+    Ok(())
+}
+
+/// Fail: the scan database needs the `scan-db` feature.
+#[cfg(not(feature = "scan-db"))]
+fn query_scans(_paths: &Paths, _sql: Option<&str>) -> Result<()> {
+    bail!("query needs clean-dev-dirs built with the scan-db feature")
+}
+
+// ── History subcommand ───────────────────────────────────────────────
+
+/// Print the most recent `limit` runs and the totals of all runs within `since`.
+fn show_history(
+    paths: &Paths,
+    since: Option<Duration>,
+    limit: usize,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let Some(path) = history::history_path(paths) else {
+        bail!("Could not determine the state directory for the history log");
+    };
+
+    let mut records = history::load(&path)?;
+    if let Some(age) = since {
+        records = history::since(records, age);
+    }
+
+    if records.is_empty() {
+        println!("{}", "No cleanup runs recorded yet.".yellow());
+        return Ok(());
     }
 
+    println!("{}", "Recent runs:".bold());
+    for record in records.iter().rev().take(limit) {
+        let finished = record.finished_at().map_or_else(
+            || record.finished_at.clone(),
+            |at| {
+                at.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            },
+        );
+        let roots: Vec<String> = record
+            .roots
+            .iter()
+            .map(|r| r.display().to_string())
+            .collect();
+        println!(
+            "  {finished}  {:>10}  {} projects  {}",
+            display.bytes(record.bytes_freed).bright_green(),
+            record.projects_cleaned,
+            roots.join(", ").dimmed(),
+        );
+    }
+
+    let totals = HistoryTotals::from_records(&records);
+    println!(
+        "\n{} {} across {} projects in {} runs",
+        "Total reclaimed:".bold(),
+        display.bytes(totals.bytes_freed).bright_green().bold(),
+        totals.projects_cleaned,
+        totals.runs,
+    );
+
     Ok(())
 }
+
+/// Send the `--notify` notification and run the `post_clean` hook, if configured.
+///
+/// Failures are reported as warnings on stderr; the cleanup itself already succeeded.
+/// With `structured_output`, the hook's stdout goes to stderr so that it
+/// doesn't corrupt the report printed on stdout.
+fn run_post_clean_hooks(
+    result: &CleanResult,
+    opts: &clean_dev_dirs::ExecutionOptions,
+    structured_output: bool,
+    display: &DisplayOptions,
+) {
+    if opts.notify
+        && let Err(e) = hooks::send_notification(result, display.units)
+    {
+        eprintln!("{} {e:#}", display.text(Message::Warning).yellow());
+    }
+
+    if let Some(command) = &opts.post_clean {
+        match hooks::run_post_clean(command, result, display.units, structured_output) {
+            std::result::Result::Ok(status) if !status.success() => {
+                eprintln!(
+                    "{} post_clean hook exited with {status}",
+                    display.text(Message::Warning).yellow()
+                );
+            }
+            std::result::Result::Ok(_) => {}
+            Err(e) => eprintln!("{} {e:#}", display.text(Message::Warning).yellow()),
+        }
+    }
+}
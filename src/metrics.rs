@@ -0,0 +1,178 @@
+//! Prometheus metrics for monitoring disk hygiene across machines.
+//!
+//! `--metrics-file <PATH>` writes the metrics of a run in the Prometheus text
+//! exposition format, ready for the node exporter's textfile collector. On
+//! build servers that run clean-dev-dirs from cron or a systemd timer, this
+//! lets fleet monitoring track how much space is reclaimable and reclaimed.
+//!
+//! Every run also ends with a one-line summary of the same numbers on stderr,
+//! in `key=value` form, for wrapper scripts that don't want to parse the
+//! regular output.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::error::{CleanDevDirsError, Result, ResultExt};
+
+/// Metrics collected during a single run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RunMetrics {
+    /// Number of projects found by the scan, before filtering
+    pub projects_detected: usize,
+
+    /// Number of projects that matched the filters and were cleaned or previewed
+    pub projects_selected: usize,
+
+    /// Total artifact size of the projects that matched the filters, in bytes
+    pub reclaimable_bytes: u64,
+
+    /// Bytes actually freed by the run (0 for dry runs)
+    pub reclaimed_bytes: u64,
+
+    /// Time spent scanning
+    pub scan_duration: Duration,
+}
+
+impl RunMetrics {
+    /// Render the metrics in the Prometheus text exposition format.
+    ///
+    /// `finished_at` becomes the `clean_dev_dirs_last_run_timestamp_seconds`
+    /// gauge, which makes stale metrics (a run that stopped happening) easy
+    /// to alert on.
+    #[must_use]
+    pub fn render(&self, finished_at: SystemTime) -> String {
+        let timestamp = finished_at
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        let gauges: [(&str, &str, String); 5] = [
+            (
+                "clean_dev_dirs_projects_detected",
+                "Number of projects with build artifacts found by the last scan.",
+                self.projects_detected.to_string(),
+            ),
+            (
+                "clean_dev_dirs_reclaimable_bytes",
+                "Size of the build artifacts matching the filters in the last run.",
+                self.reclaimable_bytes.to_string(),
+            ),
+            (
+                "clean_dev_dirs_reclaimed_bytes",
+                "Bytes freed by the last run (0 for dry runs).",
+                self.reclaimed_bytes.to_string(),
+            ),
+            (
+                "clean_dev_dirs_scan_duration_seconds",
+                "Time spent scanning in the last run.",
+                format!("{:.3}", self.scan_duration.as_secs_f64()),
+            ),
+            (
+                "clean_dev_dirs_last_run_timestamp_seconds",
+                "Unix time at which the last run finished.",
+                timestamp.to_string(),
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, help, value) in gauges {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} gauge");
+            let _ = writeln!(out, "{name} {value}");
+        }
+        out
+    }
+
+    /// The one-line `key=value` summary of a run that took `duration`.
+    ///
+    /// For example `projects=42 bytes=123456789 freed=0 duration_ms=1532`,
+    /// where `projects` and `bytes` describe the projects that matched the
+    /// filters and `freed` is what the run actually freed (0 for dry runs).
+    #[must_use]
+    pub fn summary_line(&self, duration: Duration) -> String {
+        format!(
+            "projects={} bytes={} freed={} duration_ms={}",
+            self.projects_selected,
+            self.reclaimable_bytes,
+            self.reclaimed_bytes,
+            duration.as_millis()
+        )
+    }
+
+    /// Write the metrics to `path`, replacing it atomically.
+    ///
+    /// The metrics go to a temporary file next to `path` that is then renamed
+    /// over it, so a collector never reads a half-written file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written or renamed.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+
+        fs::write(&tmp, self.render(SystemTime::now())).with_context(|| {
+            CleanDevDirsError::output(format!("Failed to write metrics file {}", path.display()))
+        })?;
+        fs::rename(&tmp, path).with_context(|| {
+            CleanDevDirsError::output(format!("Failed to write metrics file {}", path.display()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample() -> RunMetrics {
+        RunMetrics {
+            projects_detected: 12,
+            projects_selected: 4,
+            reclaimable_bytes: 3_000_000,
+            reclaimed_bytes: 1_000_000,
+            scan_duration: Duration::from_millis(1_250),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let text = sample().render(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+
+        assert!(text.contains("# TYPE clean_dev_dirs_projects_detected gauge\n"));
+        assert!(text.contains("\nclean_dev_dirs_projects_detected 12\n"));
+        assert!(text.contains("\nclean_dev_dirs_reclaimable_bytes 3000000\n"));
+        assert!(text.contains("\nclean_dev_dirs_reclaimed_bytes 1000000\n"));
+        assert!(text.contains("\nclean_dev_dirs_scan_duration_seconds 1.250\n"));
+        assert!(text.ends_with("\nclean_dev_dirs_last_run_timestamp_seconds 1700000000\n"));
+    }
+
+    #[test]
+    fn test_summary_line() {
+        assert_eq!(
+            sample().summary_line(Duration::from_millis(2_345)),
+            "projects=4 bytes=3000000 freed=1000000 duration_ms=2345"
+        );
+        assert_eq!(
+            RunMetrics::default().summary_line(Duration::ZERO),
+            "projects=0 bytes=0 freed=0 duration_ms=0"
+        );
+    }
+
+    #[test]
+    fn test_write_replaces_file() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("clean_dev_dirs.prom");
+        fs::write(&path, "stale")?;
+
+        sample().write(&path)?;
+
+        let content = fs::read_to_string(&path)?;
+        assert!(content.starts_with("# HELP clean_dev_dirs_projects_detected"));
+        assert!(!tmp.path().join("clean_dev_dirs.prom.tmp").exists());
+        Ok(())
+    }
+}
@@ -1,23 +1,77 @@
-//! Structured JSON output for scripting and piping.
+//! Structured output for scripting and piping.
 //!
 //! This module provides serializable data structures that represent the
 //! complete output of a scan or cleanup operation. When the `--json` flag
 //! is passed, these structures are serialized to stdout as a single JSON
-//! object, replacing all human-readable output.
-
-use std::collections::BTreeMap;
-
-use humansize::{DECIMAL, format_size};
-use serde::Serialize;
+//! object, replacing all human-readable output. The same project list can
+//! also be rendered as CSV or as a Markdown table (see [`OutputFormat`]).
+//!
+//! The format is versioned by [`JSON_SCHEMA_VERSION`] and described by the
+//! JSON Schema returned from [`json_schema`] (printed by the `schema`
+//! subcommand). Changes within a schema version are additive only: fields may
+//! be added, but existing fields are never removed, renamed, or retyped.
+
+use std::{collections::BTreeMap, fmt::Write as _, fs, path::Path, time::SystemTime};
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cleaner::CleanResult,
+    config::{DisplayOptions, RustSubdir},
+    error::{CleanDevDirsError, Result, ResultExt},
+    filtering::artifact_mtime,
+    project::{Detection, Project, ProjectType, RebuildCost},
+    risk::RiskLevel,
+    utils::{SizeUnits, format_bytes},
+    volumes::{VolumeUsage, group_by_volume},
+};
+
+/// Machine-readable output formats selectable with `--output`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum OutputFormat {
+    /// A single JSON document (same as `--json`)
+    Json,
+
+    /// CSV rows: `path,name,type,size_bytes,files,age_days,action`
+    Csv,
+
+    /// A Markdown table suitable for pasting into issue trackers
+    #[value(name = "md")]
+    Markdown,
+
+    /// Build directory paths separated by NUL bytes (`--print0`)
+    #[value(skip)]
+    Paths0,
+
+    /// The total reclaimable size in bytes (`--total-only`)
+    #[value(skip)]
+    Total,
+
+    /// The total reclaimable size in the selected units (`--total-only --units`)
+    #[value(skip)]
+    TotalFormatted,
+}
 
-use crate::project::{Project, ProjectType};
+/// Version of the `--json` output format.
+///
+/// Bumped only for breaking changes; new fields can appear without a bump.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
 
 /// Top-level JSON output emitted when `--json` is active.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct JsonOutput {
+    /// Version of the output format (see [`JSON_SCHEMA_VERSION`]).
+    pub schema_version: u32,
+
     /// The execution mode: `"dry_run"` or `"cleanup"`.
     pub mode: String,
 
+    /// Units of every `*_formatted` size (`"binary"`, `"decimal"` or `"bytes"`).
+    #[serde(default)]
+    pub units: SizeUnits,
+
     /// List of projects that were found (and matched filters).
     pub projects: Vec<JsonProjectEntry>,
 
@@ -31,7 +85,7 @@ pub struct JsonOutput {
 }
 
 /// A single project entry in the JSON output.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct JsonProjectEntry {
     /// Project name extracted from config files, or `null`.
     pub name: Option<String>,
@@ -49,12 +103,76 @@ pub struct JsonProjectEntry {
     /// Total size of the build artifacts in bytes.
     pub build_artifacts_size: u64,
 
-    /// Human-readable formatted size (e.g. `"1.23 GB"`).
+    /// Total size of the build artifacts in bytes (same as `build_artifacts_size`).
+    #[serde(default)]
+    pub size_bytes: u64,
+
+    /// Whether the size is only a lower bound, because sizing stopped at
+    /// `--quick-size-threshold`.
+    #[serde(default)]
+    pub size_estimated: bool,
+
+    /// Human-readable formatted size in the selected `units` (e.g. `"1.23 GB"`).
     pub build_artifacts_size_formatted: String,
+
+    /// Number of files in the build artifacts.
+    pub file_count: u64,
+
+    /// Number of subdirectories in the build artifacts.
+    pub dir_count: u64,
+
+    /// Estimated cost of regenerating the artifacts (`"low"`, `"medium"`, `"high"`).
+    pub rebuild_cost: RebuildCost,
+
+    /// Highest risk of deleting any of the build directories (`"low"`,
+    /// `"medium"`, `"high"`).
+    #[serde(default)]
+    pub risk: RiskLevel,
+
+    /// Days since the enclosing git repository's last commit, or `null` outside git.
+    pub last_commit_age_days: Option<i64>,
+
+    /// The scanned directory the project was found under, or `null` if unknown.
+    #[serde(default)]
+    pub scan_root: Option<String>,
+
+    /// Path of the project root relative to `scan_root` (`""` for the scan
+    /// root itself), or `null` if the scan root is unknown.
+    #[serde(default)]
+    pub relative_path: Option<String>,
+
+    /// Bytes the build artifacts grew since the previous run (negative if they
+    /// shrank), or `null` if they weren't measured before.
+    #[serde(default)]
+    pub size_change_bytes: Option<i64>,
+
+    /// Which detector matched the project, the marker files it found and the
+    /// build directories it chose. Only present with `--explain`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detection: Option<Detection>,
+
+    /// Sizes of the well-known subdirectories of a Rust project's target
+    /// directories. Only present with `--verbose`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub target_breakdown: Vec<JsonSubdirSize>,
+}
+
+/// The size of a well-known subdirectory of a Rust target directory.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct JsonSubdirSize {
+    /// Which subdirectory it is (`"incremental"`, `"fingerprint"`, `"deps"`
+    /// or `"doc"`).
+    pub kind: RustSubdir,
+
+    /// Absolute path to the subdirectory.
+    pub path: String,
+
+    /// Size of the subdirectory in bytes.
+    pub size_bytes: u64,
 }
 
 /// Aggregated summary across all matched projects.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct JsonSummary {
     /// Total number of projects found.
     pub total_projects: usize,
@@ -65,12 +183,47 @@ pub struct JsonSummary {
     /// Human-readable formatted total size.
     pub total_size_formatted: String,
 
+    /// Whether the total size is only a lower bound, because the size of at
+    /// least one project is estimated.
+    #[serde(default)]
+    pub size_estimated: bool,
+
     /// Per-type breakdown (key is the project type name).
     pub by_type: BTreeMap<String, JsonTypeSummary>,
+
+    /// Per-volume breakdown, ordered by mount point.
+    #[serde(default)]
+    pub volumes: Vec<JsonVolumeSummary>,
+}
+
+/// Reclaimable and free space on one volume (filesystem or drive).
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct JsonVolumeSummary {
+    /// Mount point (Unix) or volume root (Windows), e.g. `"/home"` or `"D:\\"`.
+    pub mount_point: String,
+
+    /// Total size in bytes of the build directories on this volume.
+    pub reclaimable: u64,
+
+    /// Human-readable formatted reclaimable size.
+    pub reclaimable_formatted: String,
+
+    /// Number of build directories on this volume.
+    pub artifact_count: usize,
+
+    /// Bytes currently available on the volume, or `null` if unknown. In
+    /// cleanup mode this is measured after cleaning.
+    pub free_space: Option<u64>,
+
+    /// Bytes expected to be available once the build directories are
+    /// removed, or `null` if unknown or after a cleanup, where `free_space`
+    /// already reflects it. Directories moved to the trash only free their
+    /// space once the trash is emptied.
+    pub projected_free_space: Option<u64>,
 }
 
 /// Per-project-type count and size.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct JsonTypeSummary {
     /// Number of projects of this type.
     pub count: usize,
@@ -83,7 +236,7 @@ pub struct JsonTypeSummary {
 }
 
 /// Results of a cleanup operation.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct JsonCleanupResult {
     /// Number of projects successfully cleaned.
     pub success_count: usize,
@@ -91,27 +244,46 @@ pub struct JsonCleanupResult {
     /// Number of projects that failed to clean.
     pub failure_count: usize,
 
-    /// Total bytes actually freed.
+    /// Total bytes freed: `verified_freed` when available, `estimated_freed` otherwise.
     pub total_freed: u64,
 
     /// Human-readable formatted freed size.
     pub total_freed_formatted: String,
 
+    /// Bytes freed according to the sizes measured during the scan.
+    pub estimated_freed: u64,
+
+    /// Bytes freed according to sizes re-measured just before removal,
+    /// or `null` when `--verify-sizes` was not used.
+    pub verified_freed: Option<u64>,
+
     /// Error messages for projects that failed.
     pub errors: Vec<String>,
+
+    /// Projects that were deliberately left alone, such as those vetoed by
+    /// the `pre_project_clean` hook, each with the reason.
+    #[serde(default)]
+    pub skipped: Vec<String>,
+
+    /// Whether the build directories were moved to the trash, in which case
+    /// the freed space isn't reclaimed until the trash is emptied.
+    #[serde(default)]
+    pub moved_to_trash: bool,
 }
 
 impl JsonOutput {
     /// Build a `JsonOutput` from a slice of projects in dry-run mode.
     #[must_use]
-    pub fn from_projects_dry_run(projects: &[Project]) -> Self {
+    pub fn from_projects_dry_run(projects: &[Project], display: &DisplayOptions) -> Self {
         Self {
+            schema_version: JSON_SCHEMA_VERSION,
             mode: "dry_run".to_string(),
+            units: display.units,
             projects: projects
                 .iter()
-                .map(JsonProjectEntry::from_project)
+                .map(|project| JsonProjectEntry::from_project(project, display))
                 .collect(),
-            summary: JsonSummary::from_projects(projects),
+            summary: JsonSummary::from_projects(projects, display.units),
             cleanup: None,
         }
     }
@@ -120,24 +292,36 @@ impl JsonOutput {
     #[must_use]
     pub fn from_projects_cleanup(
         projects: &[Project],
-        clean_result: &crate::cleaner::CleanResult,
+        clean_result: &CleanResult,
+        display: &DisplayOptions,
     ) -> Self {
+        let mut summary = JsonSummary::from_projects(projects, display.units);
+        for volume in &mut summary.volumes {
+            volume.projected_free_space = None;
+        }
+
         Self {
+            schema_version: JSON_SCHEMA_VERSION,
             mode: "cleanup".to_string(),
+            units: display.units,
             projects: projects
                 .iter()
-                .map(JsonProjectEntry::from_project)
+                .map(|project| JsonProjectEntry::from_project(project, display))
                 .collect(),
-            summary: JsonSummary::from_projects(projects),
-            cleanup: Some(JsonCleanupResult::from_clean_result(clean_result)),
+            summary,
+            cleanup: Some(JsonCleanupResult::from_clean_result(
+                clean_result,
+                display.units,
+            )),
         }
     }
 }
 
 impl JsonProjectEntry {
-    /// Convert a `Project` into a `JsonProjectEntry`.
+    /// Convert a `Project` into a `JsonProjectEntry`, with sizes formatted
+    /// and risks rated as `display` selects.
     #[must_use]
-    pub fn from_project(project: &Project) -> Self {
+    pub fn from_project(project: &Project, display: &DisplayOptions) -> Self {
         let total = project.total_size();
         Self {
             name: project.name.clone(),
@@ -149,36 +333,47 @@ impl JsonProjectEntry {
                 .map(|a| a.path.display().to_string())
                 .collect(),
             build_artifacts_size: total,
-            build_artifacts_size_formatted: format_size(total, DECIMAL),
+            size_bytes: total,
+            size_estimated: project.size_is_estimated(),
+            build_artifacts_size_formatted: display.bytes(total),
+            file_count: project.total_files(),
+            dir_count: project.total_dirs(),
+            rebuild_cost: project.rebuild_cost(),
+            risk: project.risk(&display.risk_overrides),
+            last_commit_age_days: project.last_commit_age_days(),
+            scan_root: project
+                .scan_root
+                .as_ref()
+                .map(|root| root.display().to_string()),
+            relative_path: project
+                .scan_root
+                .as_ref()
+                .and_then(|root| project.root_path.strip_prefix(root).ok())
+                .map(|relative| relative.display().to_string()),
+            size_change_bytes: project.size_change.map(|change| change.bytes),
+            detection: project.detection.clone(),
+            target_breakdown: project
+                .target_breakdown
+                .iter()
+                .map(|subdir| JsonSubdirSize {
+                    kind: subdir.kind,
+                    path: subdir.path.display().to_string(),
+                    size_bytes: subdir.size,
+                })
+                .collect(),
         }
     }
 }
 
 impl JsonSummary {
-    /// Compute summary statistics from a slice of projects.
+    /// Compute summary statistics from a slice of projects, with sizes
+    /// formatted in `units`.
     #[must_use]
-    pub fn from_projects(projects: &[Project]) -> Self {
+    pub fn from_projects(projects: &[Project], units: SizeUnits) -> Self {
         let mut by_type: BTreeMap<String, (usize, u64)> = BTreeMap::new();
 
         for project in projects {
-            let key = match project.kind {
-                ProjectType::Rust => "rust",
-                ProjectType::Node => "node",
-                ProjectType::Python => "python",
-                ProjectType::Go => "go",
-                ProjectType::Java => "java",
-                ProjectType::Cpp => "cpp",
-                ProjectType::Swift => "swift",
-                ProjectType::DotNet => "dotnet",
-                ProjectType::Ruby => "ruby",
-                ProjectType::Elixir => "elixir",
-                ProjectType::Deno => "deno",
-                ProjectType::Php => "php",
-                ProjectType::Haskell => "haskell",
-                ProjectType::Dart => "dart",
-                ProjectType::Zig => "zig",
-                ProjectType::Scala => "scala",
-            };
+            let key = type_name(&project.kind);
 
             let entry = by_type.entry(key.to_string()).or_insert((0, 0));
             entry.0 += 1;
@@ -190,7 +385,8 @@ impl JsonSummary {
         Self {
             total_projects: projects.len(),
             total_size,
-            total_size_formatted: format_size(total_size, DECIMAL),
+            total_size_formatted: format_bytes(total_size, units),
+            size_estimated: projects.iter().any(Project::size_is_estimated),
             by_type: by_type
                 .into_iter()
                 .map(|(k, (count, size))| {
@@ -199,25 +395,576 @@ impl JsonSummary {
                         JsonTypeSummary {
                             count,
                             size,
-                            size_formatted: format_size(size, DECIMAL),
+                            size_formatted: format_bytes(size, units),
                         },
                     )
                 })
                 .collect(),
+            volumes: group_by_volume(projects)
+                .iter()
+                .map(|usage| JsonVolumeSummary::from_usage(usage, units))
+                .collect(),
+        }
+    }
+}
+
+impl JsonVolumeSummary {
+    /// Convert a `VolumeUsage` into a `JsonVolumeSummary`, with sizes
+    /// formatted in `units`.
+    #[must_use]
+    pub fn from_usage(usage: &VolumeUsage, units: SizeUnits) -> Self {
+        Self {
+            mount_point: usage.mount_point.display().to_string(),
+            reclaimable: usage.reclaimable,
+            reclaimable_formatted: format_bytes(usage.reclaimable, units),
+            artifact_count: usage.artifacts,
+            free_space: usage.free,
+            projected_free_space: usage.projected_free(),
         }
     }
 }
 
 impl JsonCleanupResult {
-    /// Convert a `CleanResult` into a `JsonCleanupResult`.
+    /// Convert a `CleanResult` into a `JsonCleanupResult`, with sizes
+    /// formatted in `units`.
     #[must_use]
-    pub fn from_clean_result(result: &crate::cleaner::CleanResult) -> Self {
+    pub fn from_clean_result(result: &CleanResult, units: SizeUnits) -> Self {
         Self {
             success_count: result.success_count,
             failure_count: result.errors.len(),
             total_freed: result.total_freed,
-            total_freed_formatted: format_size(result.total_freed, DECIMAL),
-            errors: result.errors.clone(),
+            total_freed_formatted: format_bytes(result.total_freed, units),
+            estimated_freed: result.estimated_freed,
+            verified_freed: result.verified_freed,
+            errors: result.errors.iter().map(ToString::to_string).collect(),
+            skipped: result.skipped.clone(),
+            moved_to_trash: !result.trashed.is_empty(),
+        }
+    }
+}
+
+/// Render projects (and optional cleanup results) in the given format.
+///
+/// Pass `clean_result` after an actual cleanup; `None` renders a dry run.
+/// Sizes meant for people are formatted as `display` selects.
+/// The returned text always ends with a newline, except for
+/// [`OutputFormat::Paths0`], which ends with a NUL byte (or is empty). It's
+/// returned as bytes since the paths of that format are written unchanged,
+/// even when they aren't valid UTF-8.
+/// The total formats print only the total size of the build directories,
+/// not any cleanup results.
+///
+/// # Errors
+///
+/// Returns an error if JSON serialization fails.
+pub fn render(
+    format: OutputFormat,
+    projects: &[Project],
+    clean_result: Option<&CleanResult>,
+    display: &DisplayOptions,
+) -> Result<Vec<u8>> {
+    let text = match format {
+        OutputFormat::Json => {
+            let output = clean_result.map_or_else(
+                || JsonOutput::from_projects_dry_run(projects, display),
+                |result| JsonOutput::from_projects_cleanup(projects, result, display),
+            );
+            let mut json = serde_json::to_string_pretty(&output).context(
+                CleanDevDirsError::output("Failed to serialize the JSON output"),
+            )?;
+            json.push('\n');
+            json
+        }
+        OutputFormat::Csv => render_csv(projects, clean_result),
+        OutputFormat::Markdown => render_markdown(projects, clean_result, display.units),
+        OutputFormat::Paths0 => return Ok(render_paths0(projects)),
+        OutputFormat::Total | OutputFormat::TotalFormatted => {
+            let total: u64 = projects.iter().map(Project::total_size).sum();
+            if format == OutputFormat::Total {
+                format!("{total}\n")
+            } else {
+                format!("{}\n", display.bytes(total))
+            }
         }
+    };
+    Ok(text.into_bytes())
+}
+
+/// Write the JSON document for `projects` (and optional cleanup results) to
+/// `path`, for `--json-file`.
+///
+/// The document is the one [`render`] produces for [`OutputFormat::Json`].
+/// It is written next to `path` first and then renamed over it, so a reader
+/// never sees a half-written file.
+///
+/// # Errors
+///
+/// Returns an error if JSON serialization fails or the file can't be written.
+pub fn write_json_file(
+    path: &Path,
+    projects: &[Project],
+    clean_result: Option<&CleanResult>,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let json = render(OutputFormat::Json, projects, clean_result, display)?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+
+    fs::write(&tmp, json).with_context(|| {
+        CleanDevDirsError::output(format!("Failed to write JSON file {}", path.display()))
+    })?;
+    fs::rename(&tmp, path).with_context(|| {
+        CleanDevDirsError::output(format!("Failed to write JSON file {}", path.display()))
+    })
+}
+
+/// Render the path of every build directory, each followed by a NUL byte,
+/// for `xargs -0` and similar tools.
+///
+/// On Unix the paths are written as their raw bytes, so names that aren't
+/// valid UTF-8 reach `xargs -0` unchanged.
+fn render_paths0(projects: &[Project]) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    for artifact in projects.iter().flat_map(|p| &p.build_arts) {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            out.extend_from_slice(artifact.path.as_os_str().as_bytes());
+        }
+        #[cfg(not(unix))]
+        out.extend_from_slice(artifact.path.to_string_lossy().as_bytes());
+        out.push(0);
+    }
+    out
+}
+
+/// Render one CSV row per project, followed by a totals row.
+fn render_csv(projects: &[Project], clean_result: Option<&CleanResult>) -> String {
+    let mut out = String::from("path,name,type,size_bytes,files,age_days,action\n");
+
+    for project in projects {
+        let row = [
+            project.root_path.display().to_string(),
+            project.name.clone().unwrap_or_default(),
+            type_name(&project.kind).to_string(),
+            project.total_size().to_string(),
+            project.total_files().to_string(),
+            artifact_age_days(project).map_or_else(String::new, |d| d.to_string()),
+            action(project, clean_result).to_string(),
+        ];
+        let cells: Vec<String> = row.iter().map(|cell| csv_escape(cell)).collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+
+    let total: u64 = projects.iter().map(Project::total_size).sum();
+    let files: u64 = projects.iter().map(Project::total_files).sum();
+    let _ = writeln!(out, "TOTAL,,,{total},{files},,");
+    out
+}
+
+/// Render a Markdown table of projects, followed by a totals line.
+fn render_markdown(
+    projects: &[Project],
+    clean_result: Option<&CleanResult>,
+    units: SizeUnits,
+) -> String {
+    let mut out = String::from(
+        "| Path | Name | Type | Size | Files | Age (days) | Action |\n|------|------|------|------|-------|------------|--------|\n",
+    );
+
+    for project in projects {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            markdown_escape(&project.root_path.display().to_string()),
+            markdown_escape(project.name.as_deref().unwrap_or("")),
+            type_name(&project.kind),
+            project.formatted_size(units),
+            project.total_files(),
+            artifact_age_days(project).map_or_else(String::new, |d| d.to_string()),
+            action(project, clean_result),
+        );
+    }
+
+    let total: u64 = projects.iter().map(Project::total_size).sum();
+    let freed = clean_result.map_or(total, |r| r.total_freed);
+    let verb = if clean_result.is_some() {
+        "freed"
+    } else {
+        "reclaimable"
+    };
+    let _ = writeln!(
+        out,
+        "\n**Total:** {} projects, {} {verb}",
+        projects.len(),
+        format_bytes(freed, units)
+    );
+    out
+}
+
+/// Lowercase type name used in machine-readable output.
+#[must_use]
+pub const fn type_name(kind: &ProjectType) -> &'static str {
+    match kind {
+        ProjectType::Rust => "rust",
+        ProjectType::Node => "node",
+        ProjectType::Python => "python",
+        ProjectType::Go => "go",
+        ProjectType::Java => "java",
+        ProjectType::Cpp => "cpp",
+        ProjectType::Swift => "swift",
+        ProjectType::DotNet => "dotnet",
+        ProjectType::Ruby => "ruby",
+        ProjectType::Elixir => "elixir",
+        ProjectType::Deno => "deno",
+        ProjectType::Php => "php",
+        ProjectType::Haskell => "haskell",
+        ProjectType::Dart => "dart",
+        ProjectType::Zig => "zig",
+        ProjectType::Scala => "scala",
+        ProjectType::Android => "android",
+        ProjectType::Lua => "lua",
+        ProjectType::Nim => "nim",
+        ProjectType::OCaml => "ocaml",
+        ProjectType::Erlang => "erlang",
+        ProjectType::Julia => "julia",
+        ProjectType::R => "r",
+    }
+}
+
+/// Days since the primary build artifact was last modified.
+pub(crate) fn artifact_age_days(project: &Project) -> Option<u64> {
+    let modified = artifact_mtime(project)?;
+    let age = SystemTime::now().duration_since(modified).ok()?;
+    Some(age.as_secs() / 86_400)
+}
+
+/// What happened (or would happen) to a project.
+pub(crate) fn action(project: &Project, clean_result: Option<&CleanResult>) -> &'static str {
+    let Some(result) = clean_result else {
+        return "would_clean";
+    };
+
+    if result
+        .errors
+        .iter()
+        .any(|failure| failure.root == project.root_path)
+    {
+        "failed"
+    } else {
+        "cleaned"
+    }
+}
+
+/// Quote a CSV cell when it contains a delimiter, quote, or line break.
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Escape pipe characters so a value can't break a Markdown table row.
+fn markdown_escape(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+/// Generate the JSON Schema describing [`JsonOutput`].
+#[must_use]
+pub fn json_schema() -> schemars::Schema {
+    schemars::schema_for!(JsonOutput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::CleanFailure;
+    use crate::project::BuildArtifacts;
+    use std::path::PathBuf;
+
+    fn sample_projects() -> Vec<Project> {
+        vec![
+            Project::new(
+                ProjectType::Rust,
+                PathBuf::from("/work/api"),
+                vec![BuildArtifacts {
+                    path: PathBuf::from("/work/api/target"),
+                    size: 1_000,
+                    files: 0,
+                    dirs: 0,
+                    newest_file: None,
+                    estimated: false,
+                }],
+                Some("api".to_string()),
+            ),
+            Project::new(
+                ProjectType::Node,
+                PathBuf::from("/work/web, app"),
+                vec![BuildArtifacts {
+                    path: PathBuf::from("/work/web, app/node_modules"),
+                    size: 500,
+                    files: 0,
+                    dirs: 0,
+                    newest_file: None,
+                    estimated: false,
+                }],
+                None,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_output_includes_schema_version() -> anyhow::Result<()> {
+        let output = JsonOutput::from_projects_dry_run(&[], &DisplayOptions::default());
+        let value = serde_json::to_value(&output)?;
+
+        assert_eq!(value["schema_version"], JSON_SCHEMA_VERSION);
+        assert_eq!(value["mode"], "dry_run");
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_schema_describes_output() -> anyhow::Result<()> {
+        let schema = serde_json::to_value(json_schema())?;
+
+        let required = schema["required"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("schema has no required list"))?;
+        assert!(required.iter().any(|v| v == "schema_version"));
+        assert!(required.iter().any(|v| v == "projects"));
+        assert!(schema["properties"]["cleanup"].is_object());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_json_file() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let path = tmp.path().join("out.json");
+        fs::write(&path, "stale")?;
+
+        write_json_file(&path, &sample_projects(), None, &DisplayOptions::default())?;
+
+        let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        assert_eq!(value["mode"], "dry_run");
+        assert_eq!(value["projects"].as_array().map(Vec::len), Some(2));
+        assert!(!tmp.path().join("out.json.tmp").exists());
+        assert!(
+            write_json_file(
+                &tmp.path().join("missing/out.json"),
+                &[],
+                None,
+                &DisplayOptions::default()
+            )
+            .is_err()
+        );
+        Ok(())
+    }
+
+    fn text(
+        format: OutputFormat,
+        projects: &[Project],
+        clean_result: Option<&CleanResult>,
+    ) -> anyhow::Result<String> {
+        let rendered = render(format, projects, clean_result, &DisplayOptions::default())?;
+        Ok(String::from_utf8(rendered)?)
+    }
+
+    #[test]
+    fn test_render_csv() -> anyhow::Result<()> {
+        let csv = text(OutputFormat::Csv, &sample_projects(), None)?;
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "path,name,type,size_bytes,files,age_days,action");
+        assert_eq!(lines[1], "/work/api,api,rust,1000,0,,would_clean");
+        assert_eq!(lines[2], "\"/work/web, app\",,node,500,0,,would_clean");
+        assert_eq!(lines[3], "TOTAL,,,1500,0,,");
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_markdown_after_cleanup() -> anyhow::Result<()> {
+        let result = CleanResult {
+            success_count: 1,
+            total_freed: 1_000,
+            estimated_freed: 1_000,
+            verified_freed: None,
+            estimated_size: 1_500,
+            errors: vec![CleanFailure {
+                root: PathBuf::from("/work/web, app"),
+                message: "permission denied".to_string(),
+            }],
+            skipped: Vec::new(),
+            trashed: Vec::new(),
+            permission_denied: Vec::new(),
+        };
+        let md = text(OutputFormat::Markdown, &sample_projects(), Some(&result))?;
+
+        assert!(md.starts_with("| Path | Name | Type | Size | Files | Age (days) | Action |"));
+        assert!(md.contains("| /work/api | api | rust | 1 kB | 0 |  | cleaned |"));
+        assert!(md.contains("| failed |"));
+        assert!(md.contains("**Total:** 2 projects, 1 kB freed"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_matches_the_failed_project_only() {
+        let projects = sample_projects();
+        let mut result = CleanResult {
+            success_count: 1,
+            total_freed: 0,
+            estimated_freed: 0,
+            verified_freed: None,
+            estimated_size: 0,
+            errors: vec![CleanFailure {
+                root: PathBuf::from("/work/api: x"),
+                message: "busy".to_string(),
+            }],
+            skipped: Vec::new(),
+            trashed: Vec::new(),
+            permission_denied: Vec::new(),
+        };
+        assert_eq!(action(&projects[0], Some(&result)), "cleaned");
+
+        result.errors[0].root = PathBuf::from("/work/api");
+        assert_eq!(action(&projects[0], Some(&result)), "failed");
+        assert_eq!(action(&projects[1], Some(&result)), "cleaned");
+        assert_eq!(action(&projects[1], None), "would_clean");
+    }
+
+    #[test]
+    fn test_estimated_sizes_are_flagged() -> anyhow::Result<()> {
+        let mut projects = sample_projects();
+        projects[0].build_arts[0].estimated = true;
+
+        let json = JsonOutput::from_projects_dry_run(&projects, &DisplayOptions::default());
+        assert!(json.projects[0].size_estimated);
+        assert!(!json.projects[1].size_estimated);
+        assert!(json.summary.size_estimated);
+
+        let md = text(OutputFormat::Markdown, &projects, None)?;
+        assert!(md.contains("| ≥1 kB (estimated) |"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary_groups_sizes_by_volume() {
+        let projects = sample_projects();
+
+        let dry_run = JsonOutput::from_projects_dry_run(&projects, &DisplayOptions::default());
+        let volumes = &dry_run.summary.volumes;
+        assert!(!volumes.is_empty());
+        assert_eq!(volumes.iter().map(|v| v.reclaimable).sum::<u64>(), 1_500);
+        assert_eq!(volumes.iter().map(|v| v.artifact_count).sum::<usize>(), 2);
+
+        let result = CleanResult {
+            success_count: 2,
+            total_freed: 1_500,
+            estimated_freed: 1_500,
+            verified_freed: None,
+            estimated_size: 1_500,
+            errors: Vec::new(),
+            skipped: Vec::new(),
+            trashed: Vec::new(),
+            permission_denied: Vec::new(),
+        };
+        let cleanup =
+            JsonOutput::from_projects_cleanup(&projects, &result, &DisplayOptions::default());
+        assert!(
+            cleanup
+                .summary
+                .volumes
+                .iter()
+                .all(|v| v.projected_free_space.is_none())
+        );
+    }
+
+    #[test]
+    fn test_detection_only_present_when_explained() -> anyhow::Result<()> {
+        let mut projects = sample_projects();
+        projects[0].detection = Some(Detection {
+            detector: "rust".to_string(),
+            markers: vec!["Cargo.toml".to_string()],
+            artifacts: vec![PathBuf::from("/work/api/target")],
+        });
+
+        let value = serde_json::to_value(JsonOutput::from_projects_dry_run(
+            &projects,
+            &DisplayOptions::default(),
+        ))?;
+        let detection = &value["projects"][0]["detection"];
+        assert_eq!(detection["detector"], "rust");
+        assert_eq!(detection["markers"], serde_json::json!(["Cargo.toml"]));
+        assert_eq!(
+            detection["artifacts"],
+            serde_json::json!(["/work/api/target"])
+        );
+        assert!(value["projects"][1].get("detection").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_paths0() -> anyhow::Result<()> {
+        let paths = text(OutputFormat::Paths0, &sample_projects(), None)?;
+
+        assert_eq!(paths, "/work/api/target\0/work/web, app/node_modules\0");
+        assert!(text(OutputFormat::Paths0, &[], None)?.is_empty());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_paths0_keeps_non_utf8_bytes() -> anyhow::Result<()> {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let root = PathBuf::from(OsStr::from_bytes(b"/work/caf\xe9"));
+        let project = Project::new(
+            ProjectType::Rust,
+            root.clone(),
+            vec![BuildArtifacts {
+                path: root.join("target"),
+                size: 1,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            None,
+        );
+
+        let paths = render(
+            OutputFormat::Paths0,
+            &[project],
+            None,
+            &DisplayOptions::default(),
+        )?;
+        assert_eq!(paths, b"/work/caf\xe9/target\0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_total() -> anyhow::Result<()> {
+        let projects = sample_projects();
+        let total: u64 = projects.iter().map(Project::total_size).sum();
+
+        assert_eq!(
+            text(OutputFormat::Total, &projects, None)?,
+            format!("{total}\n")
+        );
+        assert_eq!(
+            text(OutputFormat::TotalFormatted, &projects, None)?,
+            format!("{}\n", format_bytes(total, SizeUnits::Decimal))
+        );
+        assert_eq!(text(OutputFormat::Total, &[], None)?, "0\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(markdown_escape("a|b"), "a\\|b");
     }
 }
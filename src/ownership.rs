@@ -0,0 +1,314 @@
+//! File ownership checks for projects.
+//!
+//! On shared machines a scan can find projects that belong to other users.
+//! Those are skipped by default: this module compares the owner of a
+//! project's root directory with the user running the tool, using the uid on
+//! Unix and the owner SID on Windows. When the owner can't be determined, the
+//! project is treated as owned by the current user.
+
+use std::{path::Path, sync::OnceLock};
+
+use rayon::prelude::*;
+
+use crate::project::Project;
+
+/// Check whether `path` is owned by someone other than the current user.
+///
+/// Returns `false` when either owner can't be determined, and on platforms
+/// without ownership information.
+#[must_use]
+pub fn is_foreign(path: &Path) -> bool {
+    static CURRENT_USER: OnceLock<Option<sys::OwnerId>> = OnceLock::new();
+
+    let current = CURRENT_USER.get_or_init(sys::current_user);
+    differs(sys::owner_of(path), current.as_ref())
+}
+
+/// Name of the user owning `path`, if it can be determined.
+///
+/// Falls back to the numeric uid on Unix when the user has no passwd entry.
+#[must_use]
+pub fn owner_name(path: &Path) -> Option<String> {
+    sys::owner_name(path)
+}
+
+/// Split projects into those owned by the current user and those owned by others.
+///
+/// Ownership is taken from each project's root directory.
+///
+/// # Returns
+///
+/// An `(owned, foreign)` tuple preserving the original relative order.
+#[must_use]
+pub fn partition_owned(projects: Vec<Project>) -> (Vec<Project>, Vec<Project>) {
+    let (foreign, owned): (Vec<_>, Vec<_>) = projects
+        .into_par_iter()
+        .partition(|project| is_foreign(&project.root_path));
+    (owned, foreign)
+}
+
+/// Whether a known owner differs from the known current user.
+fn differs<T: PartialEq>(owner: Option<T>, current: Option<&T>) -> bool {
+    matches!((owner, current), (Some(owner), Some(current)) if owner != *current)
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::{ffi::CStr, fs, os::unix::fs::MetadataExt, path::Path, ptr};
+
+    /// Numeric user id.
+    pub(super) type OwnerId = u32;
+
+    pub(super) fn owner_of(path: &Path) -> Option<OwnerId> {
+        fs::metadata(path).ok().map(|metadata| metadata.uid())
+    }
+
+    // Same signature as on Windows, where looking up the current user can fail
+    #[allow(clippy::unnecessary_wraps)]
+    pub(super) fn current_user() -> Option<OwnerId> {
+        // SAFETY: geteuid has no preconditions and always succeeds.
+        Some(unsafe { libc::geteuid() })
+    }
+
+    pub(super) fn owner_name(path: &Path) -> Option<String> {
+        let uid = owner_of(path)?;
+        Some(user_name(uid).unwrap_or_else(|| uid.to_string()))
+    }
+
+    /// Look up the login name for `uid` in the user database.
+    fn user_name(uid: OwnerId) -> Option<String> {
+        const MAX_BUFFER: usize = 1 << 20;
+
+        let mut buffer = vec![0 as libc::c_char; 1024];
+        loop {
+            // SAFETY: passwd is a plain C struct for which all-zero is a valid value.
+            let mut entry: libc::passwd = unsafe { std::mem::zeroed() };
+            let mut result = ptr::null_mut();
+            // SAFETY: every pointer is valid for the duration of the call and
+            // `buffer.len()` is the exact size of the buffer.
+            let status = unsafe {
+                libc::getpwuid_r(
+                    uid,
+                    &raw mut entry,
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                    &raw mut result,
+                )
+            };
+
+            if status == libc::ERANGE && buffer.len() < MAX_BUFFER {
+                buffer.resize(buffer.len() * 2, 0);
+                continue;
+            }
+            if status != 0 || result.is_null() {
+                return None;
+            }
+
+            // SAFETY: on success pw_name points to a NUL-terminated string in `buffer`.
+            let name = unsafe { CStr::from_ptr(entry.pw_name) };
+            return Some(name.to_string_lossy().into_owned());
+        }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::{os::windows::ffi::OsStrExt, path::Path, ptr};
+
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, ERROR_SUCCESS, HANDLE, LocalFree},
+        Security::{
+            Authorization::{GetNamedSecurityInfoW, SE_FILE_OBJECT},
+            GetLengthSid, GetTokenInformation, LookupAccountSidW, OWNER_SECURITY_INFORMATION,
+            PSECURITY_DESCRIPTOR, PSID, SID_NAME_USE, TOKEN_QUERY, TOKEN_USER, TokenUser,
+        },
+        System::Threading::{GetCurrentProcess, OpenProcessToken},
+    };
+
+    /// Binary security identifier (SID) of an account.
+    pub(super) type OwnerId = Vec<u8>;
+
+    /// Copy a SID out of memory owned by the system.
+    fn copy_sid(sid: PSID) -> OwnerId {
+        // SAFETY: callers pass a pointer to a valid SID, which is
+        // `GetLengthSid` bytes long.
+        unsafe {
+            let len = GetLengthSid(sid) as usize;
+            std::slice::from_raw_parts(sid.cast::<u8>(), len).to_vec()
+        }
+    }
+
+    pub(super) fn owner_of(path: &Path) -> Option<OwnerId> {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut owner: PSID = ptr::null_mut();
+        let mut descriptor: PSECURITY_DESCRIPTOR = ptr::null_mut();
+
+        // SAFETY: `wide` is NUL-terminated and the out pointers are valid.
+        let status = unsafe {
+            GetNamedSecurityInfoW(
+                wide.as_ptr(),
+                SE_FILE_OBJECT,
+                OWNER_SECURITY_INFORMATION,
+                &raw mut owner,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &raw mut descriptor,
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return None;
+        }
+
+        // `owner` points into `descriptor`, so copy it before freeing
+        let sid = (!owner.is_null()).then(|| copy_sid(owner));
+        // SAFETY: the descriptor was allocated by GetNamedSecurityInfoW.
+        unsafe { LocalFree(descriptor) };
+        sid
+    }
+
+    pub(super) fn current_user() -> Option<OwnerId> {
+        let mut token: HANDLE = ptr::null_mut();
+        // SAFETY: the pseudo handle of the current process is always valid.
+        if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &raw mut token) } == 0 {
+            return None;
+        }
+
+        let mut len = 0u32;
+        // SAFETY: a null buffer of length 0 only queries the required size.
+        unsafe { GetTokenInformation(token, TokenUser, ptr::null_mut(), 0, &raw mut len) };
+
+        // A u64 buffer keeps the TOKEN_USER suitably aligned
+        let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+        // SAFETY: `buffer` holds at least `len` bytes.
+        let ok = unsafe {
+            GetTokenInformation(
+                token,
+                TokenUser,
+                buffer.as_mut_ptr().cast(),
+                len,
+                &raw mut len,
+            )
+        };
+        // SAFETY: `token` was opened above and is closed exactly once.
+        unsafe { CloseHandle(token) };
+        if ok == 0 {
+            return None;
+        }
+
+        // SAFETY: on success the buffer starts with a TOKEN_USER whose SID
+        // points into the same buffer.
+        let user = unsafe { &*buffer.as_ptr().cast::<TOKEN_USER>() };
+        Some(copy_sid(user.User.Sid))
+    }
+
+    pub(super) fn owner_name(path: &Path) -> Option<String> {
+        const NAME_LEN: u32 = 256;
+
+        let mut sid = owner_of(path)?;
+        let mut name = [0u16; NAME_LEN as usize];
+        let mut domain = [0u16; NAME_LEN as usize];
+        let (mut name_len, mut domain_len) = (NAME_LEN, NAME_LEN);
+        let mut kind: SID_NAME_USE = 0;
+
+        // SAFETY: `sid` is a valid SID and the lengths match the buffers.
+        let ok = unsafe {
+            LookupAccountSidW(
+                ptr::null(),
+                sid.as_mut_ptr().cast(),
+                name.as_mut_ptr(),
+                &raw mut name_len,
+                domain.as_mut_ptr(),
+                &raw mut domain_len,
+                &raw mut kind,
+            )
+        };
+        if ok == 0 {
+            return None;
+        }
+
+        let name = String::from_utf16_lossy(&name[..name_len as usize]);
+        let domain = String::from_utf16_lossy(&domain[..domain_len as usize]);
+        Some(if domain.is_empty() {
+            name
+        } else {
+            format!("{domain}\\{name}")
+        })
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod sys {
+    use std::path::Path;
+
+    /// No ownership information is available on this platform.
+    pub(super) type OwnerId = ();
+
+    pub(super) const fn owner_of(_path: &Path) -> Option<OwnerId> {
+        None
+    }
+
+    pub(super) const fn current_user() -> Option<OwnerId> {
+        None
+    }
+
+    pub(super) const fn owner_name(_path: &Path) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_differs() {
+        assert!(differs(Some(1), Some(&2)));
+        assert!(!differs(Some(1), Some(&1)));
+        // Unknown owners never count as foreign
+        assert!(!differs(None, Some(&1)));
+        assert!(!differs(Some(1), None));
+    }
+
+    #[test]
+    fn test_own_directory_is_not_foreign() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        assert!(!is_foreign(tmp.path()));
+        assert!(!is_foreign(&tmp.path().join("missing")));
+        Ok(())
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn test_owner_name() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        assert!(owner_name(tmp.path()).is_some_and(|name| !name.is_empty()));
+        assert!(owner_name(&tmp.path().join("missing")).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_partition_owned_keeps_own_projects() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = Project::new(
+            ProjectType::Rust,
+            tmp.path().to_path_buf(),
+            vec![BuildArtifacts {
+                path: tmp.path().join("target"),
+                size: 1,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            None,
+        );
+
+        let (owned, foreign) = partition_owned(vec![project]);
+        assert_eq!(owned.len(), 1);
+        assert!(foreign.is_empty());
+        Ok(())
+    }
+}
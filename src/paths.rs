@@ -0,0 +1,194 @@
+//! Where clean-dev-dirs keeps its own files.
+//!
+//! What the tool remembers between runs (the cleanup history, snoozed
+//! projects and the build directory sizes of the last scan) lives in the
+//! state directory. Data that can be thrown away at any time belongs in the
+//! cache directory. Both default to the platform's conventions:
+//!
+//! | | Linux | macOS | Windows |
+//! |-|-------|-------|---------|
+//! | State | `$XDG_STATE_HOME/clean-dev-dirs` (`~/.local/state/...`) | `~/Library/Application Support/clean-dev-dirs` | `%LOCALAPPDATA%\clean-dev-dirs` |
+//! | Cache | `$XDG_CACHE_HOME/clean-dev-dirs` (`~/.cache/...`) | `~/Library/Caches/clean-dev-dirs` | `%LOCALAPPDATA%\clean-dev-dirs` |
+//!
+//! The `state_dir` and `cache_dir` config settings move them elsewhere, for
+//! example to a scratch disk on a build server. [`Paths`] holds the
+//! directories in use and is handed to whatever reads or writes these files.
+//!
+//! Earlier versions kept the state files in the local data directory
+//! (`~/.local/share/clean-dev-dirs` on Linux). A file found there is still
+//! used until one exists in the state directory.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::file::{FileConfig, expand_tilde};
+
+/// Name of the directory created inside the platform directories.
+const APP_DIR: &str = "clean-dev-dirs";
+
+/// The directories the tool keeps its files in: the configured ones, or
+/// the platform defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Paths {
+    /// Directory for the files kept between runs, if not the default
+    state_dir: Option<PathBuf>,
+
+    /// Directory for disposable data, if not the default
+    cache_dir: Option<PathBuf>,
+}
+
+impl Paths {
+    /// Use `state_dir` and `cache_dir` instead of the platform defaults,
+    /// where given.
+    #[must_use]
+    pub const fn new(state_dir: Option<PathBuf>, cache_dir: Option<PathBuf>) -> Self {
+        Self {
+            state_dir,
+            cache_dir,
+        }
+    }
+
+    /// The directories set in the config file, with `~` expanded.
+    #[must_use]
+    pub fn from_config(config: &FileConfig) -> Self {
+        Self::new(
+            config.state_dir.as_deref().map(expand_tilde),
+            config.cache_dir.as_deref().map(expand_tilde),
+        )
+    }
+
+    /// The directory for the files kept between runs.
+    ///
+    /// Returns `None` if no directory was configured and the platform's
+    /// can't be determined.
+    #[must_use]
+    pub fn state_dir(&self) -> Option<PathBuf> {
+        self.state_dir.clone().or_else(default_state_dir)
+    }
+
+    /// The directory for disposable data.
+    ///
+    /// Returns `None` if no directory was configured and the platform's
+    /// can't be determined.
+    #[must_use]
+    pub fn cache_dir(&self) -> Option<PathBuf> {
+        self.cache_dir.clone().or_else(default_cache_dir)
+    }
+
+    /// The path of the state file `name`, such as `history.jsonl`.
+    ///
+    /// Falls back to the file in the local data directory used by earlier
+    /// versions while the state directory has none, unless `state_dir` was
+    /// configured.
+    #[must_use]
+    pub fn state_file(&self, name: &str) -> Option<PathBuf> {
+        let legacy_dir = if self.state_dir.is_some() {
+            None
+        } else {
+            dirs::data_local_dir().map(|dir| dir.join(APP_DIR))
+        };
+
+        resolve_state_file(name, self.state_dir().as_deref(), legacy_dir.as_deref())
+    }
+}
+
+/// The platform's state directory for the tool, used unless `state_dir` is set.
+///
+/// Only Linux and the BSDs have a separate state directory; elsewhere it's
+/// the local data directory.
+#[must_use]
+pub fn default_state_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join(APP_DIR))
+}
+
+/// The platform's cache directory for the tool, used unless `cache_dir` is set.
+#[must_use]
+pub fn default_cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(APP_DIR))
+}
+
+/// The state file `name` in `state_dir`, or in `legacy_dir` if only that one
+/// has it.
+fn resolve_state_file(
+    name: &str,
+    state_dir: Option<&Path>,
+    legacy_dir: Option<&Path>,
+) -> Option<PathBuf> {
+    let Some(state_dir) = state_dir else {
+        return legacy_dir.map(|dir| dir.join(name));
+    };
+
+    let file = state_dir.join(name);
+    if file.exists() {
+        return Some(file);
+    }
+
+    legacy_dir
+        .map(|dir| dir.join(name))
+        .filter(|legacy| legacy.exists())
+        .or(Some(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_state_file_prefers_state_dir() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let state = tmp.path().join("state");
+        let legacy = tmp.path().join("share");
+
+        // Nothing recorded yet: new files go to the state directory
+        assert_eq!(
+            resolve_state_file("history.jsonl", Some(&state), Some(&legacy)),
+            Some(state.join("history.jsonl"))
+        );
+
+        // An existing file of an earlier version keeps being used...
+        fs::create_dir_all(&legacy)?;
+        fs::write(legacy.join("history.jsonl"), "")?;
+        assert_eq!(
+            resolve_state_file("history.jsonl", Some(&state), Some(&legacy)),
+            Some(legacy.join("history.jsonl"))
+        );
+
+        // ...until the state directory has one
+        fs::create_dir_all(&state)?;
+        fs::write(state.join("history.jsonl"), "")?;
+        assert_eq!(
+            resolve_state_file("history.jsonl", Some(&state), Some(&legacy)),
+            Some(state.join("history.jsonl"))
+        );
+
+        assert_eq!(
+            resolve_state_file("sizes.json", None, Some(&legacy)),
+            Some(legacy.join("sizes.json"))
+        );
+        assert_eq!(resolve_state_file("sizes.json", None, None), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_paths_from_config() -> anyhow::Result<()> {
+        let config: FileConfig =
+            toml::from_str("state_dir = \"/scratch/state\"\ncache_dir = \"~/cache\"\n")?;
+        let paths = Paths::from_config(&config);
+
+        assert_eq!(paths.state_dir(), Some(PathBuf::from("/scratch/state")));
+        assert_eq!(
+            paths.state_file("history.jsonl"),
+            Some(PathBuf::from("/scratch/state/history.jsonl"))
+        );
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(paths.cache_dir(), Some(home.join("cache")));
+        }
+        assert_eq!(Paths::from_config(&FileConfig::default()), Paths::default());
+        assert_eq!(Paths::default().cache_dir(), default_cache_dir());
+        Ok(())
+    }
+}
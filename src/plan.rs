@@ -0,0 +1,558 @@
+//! Cleanup plans recorded in one run and applied in another.
+//!
+//! `--dry-run --plan-file plan.json` writes the exact build directories that
+//! would be removed, with the size measured for each. The file can be
+//! reviewed (or approved) before `--apply-plan plan.json` removes them in a
+//! later run, without walking the directory trees again. Since time passes
+//! between the two runs, and a plan file is only as trustworthy as whoever
+//! wrote it, every directory is re-validated first. A directory is only
+//! removed if it lies inside its project, if detecting the project again
+//! finds it as a build directory that the current filters select, and if its
+//! size changed by no more than a tolerance.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{CleanDevDirsError, Result, ResultExt},
+    project::{BuildArtifacts, Project, ProjectType},
+    utils::{DirStats, SizeUnits, format_bytes},
+};
+
+/// Version of the plan file format.
+///
+/// Plans written with a different version are refused.
+pub const PLAN_VERSION: u32 = 1;
+
+/// Default allowed size difference, in percent, between the size recorded
+/// in a plan and the size measured when applying it.
+pub const DEFAULT_TOLERANCE_PERCENT: u32 = 10;
+
+/// A list of build directories to remove, as recorded by a dry run.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CleanupPlan {
+    /// Version of the plan format (see [`PLAN_VERSION`])
+    pub version: u32,
+
+    /// When the plan was recorded, in RFC 3339 format
+    pub created_at: String,
+
+    /// Projects whose build directories are to be removed
+    pub projects: Vec<PlannedProject>,
+}
+
+/// A project in a [`CleanupPlan`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlannedProject {
+    /// Type of the project
+    pub kind: ProjectType,
+
+    /// Root directory of the project
+    pub root_path: PathBuf,
+
+    /// Name of the project, if known
+    pub name: Option<String>,
+
+    /// Build directories to remove
+    pub artifacts: Vec<PlannedArtifact>,
+}
+
+/// A build directory in a [`CleanupPlan`], with its size when planned.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlannedArtifact {
+    /// Path to the build directory
+    pub path: PathBuf,
+
+    /// Size of the build directory when the plan was recorded, in bytes
+    pub size: u64,
+}
+
+/// Why a planned build directory is not removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    /// The directory isn't inside the root of its project, or leads out of
+    /// it through a symbolic link
+    OutsideProject,
+
+    /// The directory no longer exists
+    Missing,
+
+    /// Detecting the project again doesn't find the directory as one of its
+    /// build directories, or the filters leave it out
+    NotDetected,
+
+    /// The directory's size moved outside the tolerance
+    SizeChanged {
+        /// Size recorded in the plan, in bytes
+        planned: u64,
+
+        /// Size measured now, in bytes
+        actual: u64,
+    },
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutsideProject => write!(f, "is not inside its project"),
+            Self::Missing => write!(f, "no longer exists"),
+            Self::NotDetected => write!(f, "is not a build directory a scan would clean"),
+            Self::SizeChanged { planned, actual } => write!(
+                f,
+                "size changed from {} to {}",
+                format_bytes(*planned, SizeUnits::Decimal),
+                format_bytes(*actual, SizeUnits::Decimal)
+            ),
+        }
+    }
+}
+
+/// The outcome of re-validating a plan.
+#[derive(Debug, Default)]
+pub struct PlanCheck {
+    /// Projects with the build directories that are still safe to remove,
+    /// carrying freshly measured sizes
+    pub projects: Vec<Project>,
+
+    /// Build directories that are left alone, and why
+    pub rejected: Vec<(PathBuf, Rejection)>,
+}
+
+impl CleanupPlan {
+    /// Record a plan for removing the build directories of `projects`.
+    ///
+    /// Paths are made absolute, so the plan can be applied from any directory.
+    #[must_use]
+    pub fn from_projects(projects: &[Project]) -> Self {
+        Self {
+            version: PLAN_VERSION,
+            created_at: Local::now().to_rfc3339(),
+            projects: projects
+                .iter()
+                .map(|project| PlannedProject {
+                    kind: project.kind.clone(),
+                    root_path: absolute(&project.root_path),
+                    name: project.name.clone(),
+                    artifacts: project
+                        .build_arts
+                        .iter()
+                        .map(|artifact| PlannedArtifact {
+                            path: absolute(&artifact.path),
+                            size: artifact.size,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Record a plan for the build directories of `projects` that still exist,
+    /// measured again.
+    ///
+    /// This is for directories a cleanup failed to remove: part of each may
+    /// already be gone, so the sizes found by the scan no longer apply.
+    /// Projects with nothing left are dropped.
+    #[must_use]
+    pub fn from_remaining(projects: &[Project]) -> Self {
+        let remaining: Vec<Project> = projects
+            .iter()
+            .filter_map(|project| {
+                let mut project = project.clone();
+                project.build_arts.retain(|artifact| artifact.path.is_dir());
+                for artifact in &mut project.build_arts {
+                    artifact.size = DirStats::collect(&artifact.path).size;
+                }
+                (!project.build_arts.is_empty()).then_some(project)
+            })
+            .collect();
+
+        Self::from_projects(&remaining)
+    }
+
+    /// Write the plan to `path` as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut json = serde_json::to_string_pretty(self)
+            .context(CleanDevDirsError::state("Failed to serialize the plan"))?;
+        json.push('\n');
+        fs::write(path, json).with_context(|| {
+            CleanDevDirsError::state(format!("Failed to write plan file {}", path.display()))
+        })
+    }
+
+    /// Read a plan previously written by [`save`](CleanupPlan::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed, or was written
+    /// in a different plan format version.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| {
+            CleanDevDirsError::state(format!("Failed to read plan file {}", path.display()))
+        })?;
+        let plan: Self = serde_json::from_str(&content).with_context(|| {
+            CleanDevDirsError::state(format!("Invalid plan file {}", path.display()))
+        })?;
+
+        if plan.version != PLAN_VERSION {
+            return Err(CleanDevDirsError::state(format!(
+                "Unsupported plan version {} in {} (expected {PLAN_VERSION})",
+                plan.version,
+                path.display()
+            )));
+        }
+
+        Ok(plan)
+    }
+
+    /// Number of build directories in the plan.
+    #[must_use]
+    pub fn artifact_count(&self) -> usize {
+        self.projects.iter().map(|p| p.artifacts.len()).sum()
+    }
+
+    /// The root directories of the planned projects, without duplicates.
+    #[must_use]
+    pub fn roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = self.projects.iter().map(|p| p.root_path.clone()).collect();
+        roots.sort();
+        roots.dedup();
+        roots
+    }
+
+    /// Re-measure every planned build directory and keep the ones still safe to remove.
+    ///
+    /// `detected` are the projects found by detecting the planned
+    /// [`roots`](Self::roots) again, with the current filters applied. A
+    /// directory is rejected when it isn't inside its project root (also
+    /// after resolving symbolic links), when it no longer exists, when no
+    /// project of the planned type and root in `detected` lists it, or when
+    /// its size now differs from the planned size by more than
+    /// `tolerance_percent` percent of the planned size. Projects left
+    /// without build directories are dropped.
+    #[must_use]
+    pub fn validate(self, tolerance_percent: u32, detected: &[Project]) -> PlanCheck {
+        self.projects
+            .into_par_iter()
+            .map(|planned| {
+                let mut check = PlanCheck::default();
+                let mut build_arts = Vec::new();
+                let current = detected
+                    .iter()
+                    .filter(|p| p.kind == planned.kind && p.root_path == planned.root_path)
+                    .flat_map(|p| &p.build_arts)
+                    .map(|a| a.path.as_path())
+                    .collect::<Vec<_>>();
+
+                for artifact in planned.artifacts {
+                    let checked = check_location(&artifact.path, &planned.root_path, &current)
+                        .and_then(|()| check_artifact(&artifact, tolerance_percent));
+                    match checked {
+                        Ok(measured) => build_arts.push(measured),
+                        Err(rejection) => check.rejected.push((artifact.path, rejection)),
+                    }
+                }
+
+                if !build_arts.is_empty() {
+                    check.projects.push(Project::new(
+                        planned.kind,
+                        planned.root_path,
+                        build_arts,
+                        planned.name,
+                    ));
+                }
+                check
+            })
+            .reduce(PlanCheck::default, |mut a, b| {
+                a.projects.extend(b.projects);
+                a.rejected.extend(b.rejected);
+                a
+            })
+    }
+}
+
+/// `path` made absolute against the current directory, or unchanged if that fails.
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Check that a planned build directory at `path` lies inside the project in
+/// `root` and is one of the `current` build directories of that project.
+fn check_location(path: &Path, root: &Path, current: &[&Path]) -> Result<(), Rejection> {
+    let leaves = path
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir));
+    if leaves || path == root || !path.starts_with(root) {
+        return Err(Rejection::OutsideProject);
+    }
+    if !path.is_dir() {
+        return Err(Rejection::Missing);
+    }
+    match (fs::canonicalize(path), fs::canonicalize(root)) {
+        (Ok(path), Ok(root)) if path != root && path.starts_with(&root) => {}
+        _ => return Err(Rejection::OutsideProject),
+    }
+    if !current.contains(&path) {
+        return Err(Rejection::NotDetected);
+    }
+    Ok(())
+}
+
+/// Re-measure a planned build directory, rejecting it if it has gone or changed.
+fn check_artifact(
+    artifact: &PlannedArtifact,
+    tolerance_percent: u32,
+) -> Result<BuildArtifacts, Rejection> {
+    let stats = DirStats::collect(&artifact.path);
+    if !within_tolerance(artifact.size, stats.size, tolerance_percent) {
+        return Err(Rejection::SizeChanged {
+            planned: artifact.size,
+            actual: stats.size,
+        });
+    }
+
+    Ok(BuildArtifacts {
+        path: artifact.path.clone(),
+        size: stats.size,
+        files: stats.files,
+        dirs: stats.dirs,
+        newest_file: stats.newest_file,
+        estimated: stats.estimated,
+    })
+}
+
+/// Whether `actual` differs from `planned` by at most `tolerance_percent` percent of `planned`.
+fn within_tolerance(planned: u64, actual: u64, tolerance_percent: u32) -> bool {
+    u128::from(planned.abs_diff(actual)) * 100
+        <= u128::from(planned) * u128::from(tolerance_percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Create a Rust project whose `target/` holds `size` bytes.
+    fn create_project(root: &Path, size: usize) -> anyhow::Result<Project> {
+        let target = root.join("target");
+        fs::create_dir_all(&target)?;
+        fs::write(target.join("app"), vec![0u8; size])?;
+
+        Ok(Project::new(
+            ProjectType::Rust,
+            root.to_path_buf(),
+            vec![BuildArtifacts {
+                path: target,
+                size: size as u64,
+                files: 1,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some("app".to_string()),
+        ))
+    }
+
+    #[test]
+    fn test_within_tolerance() {
+        assert!(within_tolerance(1_000, 1_000, 0));
+        assert!(within_tolerance(1_000, 1_100, 10));
+        assert!(within_tolerance(1_000, 900, 10));
+        assert!(!within_tolerance(1_000, 1_101, 10));
+        assert!(!within_tolerance(1_000, 899, 10));
+        assert!(!within_tolerance(0, 1, 100));
+        assert!(within_tolerance(u64::MAX, u64::MAX / 2 + 1, 50));
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_project(&tmp.path().join("app"), 100)?;
+        let plan_path = tmp.path().join("plan.json");
+
+        CleanupPlan::from_projects(&[project]).save(&plan_path)?;
+        let plan = CleanupPlan::load(&plan_path)?;
+
+        assert_eq!(plan.version, PLAN_VERSION);
+        assert_eq!(plan.artifact_count(), 1);
+        assert_eq!(plan.projects[0].kind, ProjectType::Rust);
+        assert_eq!(plan.projects[0].name.as_deref(), Some("app"));
+        assert_eq!(plan.projects[0].artifacts[0].size, 100);
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_paths_are_absolute() {
+        let project = Project::new(
+            ProjectType::Node,
+            PathBuf::from("web"),
+            vec![BuildArtifacts {
+                path: PathBuf::from("web/node_modules"),
+                size: 1,
+                files: 1,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            None,
+        );
+
+        let plan = CleanupPlan::from_projects(&[project]);
+
+        assert!(plan.projects[0].root_path.is_absolute());
+        assert!(plan.projects[0].artifacts[0].path.is_absolute());
+        assert!(
+            plan.projects[0].artifacts[0]
+                .path
+                .ends_with("web/node_modules")
+        );
+    }
+
+    #[test]
+    fn test_from_remaining_measures_what_is_left() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let partly = create_project(&tmp.path().join("partly"), 100)?;
+        let gone = create_project(&tmp.path().join("gone"), 100)?;
+
+        fs::write(tmp.path().join("partly/target/app"), vec![0u8; 40])?;
+        fs::remove_dir_all(tmp.path().join("gone/target"))?;
+
+        let plan = CleanupPlan::from_remaining(&[partly, gone]);
+
+        assert_eq!(plan.projects.len(), 1);
+        assert_eq!(plan.projects[0].name.as_deref(), Some("app"));
+        assert_eq!(plan.projects[0].artifacts[0].size, 40);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_rejects_other_versions() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let plan_path = tmp.path().join("plan.json");
+        fs::write(
+            &plan_path,
+            r#"{"version": 99, "created_at": "", "projects": []}"#,
+        )?;
+
+        let err = CleanupPlan::load(&plan_path).err();
+        assert!(err.is_some_and(|e| e.to_string().contains("Unsupported plan version 99")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_keeps_unchanged_directories() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_project(&tmp.path().join("app"), 100)?;
+
+        let check = CleanupPlan::from_projects(std::slice::from_ref(&project))
+            .validate(DEFAULT_TOLERANCE_PERCENT, &[project]);
+
+        assert!(check.rejected.is_empty());
+        assert_eq!(check.projects.len(), 1);
+        assert_eq!(check.projects[0].total_size(), 100);
+        assert_eq!(check.projects[0].total_files(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_rejects_missing_and_changed_directories() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let gone = create_project(&tmp.path().join("gone"), 100)?;
+        let grown = create_project(&tmp.path().join("grown"), 100)?;
+        let detected = [gone, grown];
+        let plan = CleanupPlan::from_projects(&detected);
+
+        fs::remove_dir_all(tmp.path().join("gone").join("target"))?;
+        fs::write(tmp.path().join("grown/target/app"), vec![0u8; 500])?;
+
+        let check = plan.validate(DEFAULT_TOLERANCE_PERCENT, &detected);
+
+        assert!(check.projects.is_empty());
+        assert_eq!(
+            check.rejected,
+            vec![
+                (tmp.path().join("gone/target"), Rejection::Missing),
+                (
+                    tmp.path().join("grown/target"),
+                    Rejection::SizeChanged {
+                        planned: 100,
+                        actual: 500
+                    }
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_rejects_directories_outside_the_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_project(&tmp.path().join("app"), 100)?;
+        let outside = tmp.path().join("outside");
+        fs::create_dir(&outside)?;
+
+        let mut plan = CleanupPlan::from_projects(std::slice::from_ref(&project));
+        plan.projects[0].artifacts.push(PlannedArtifact {
+            path: outside.clone(),
+            size: 0,
+        });
+        plan.projects[0].artifacts.push(PlannedArtifact {
+            path: tmp.path().join("app/../outside"),
+            size: 0,
+        });
+
+        let check = plan.validate(DEFAULT_TOLERANCE_PERCENT, &[project]);
+        assert_eq!(check.projects.len(), 1);
+        assert_eq!(
+            check.rejected,
+            vec![
+                (outside, Rejection::OutsideProject),
+                (tmp.path().join("app/../outside"), Rejection::OutsideProject),
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_validate_rejects_links_out_of_the_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let mut project = create_project(&tmp.path().join("app"), 100)?;
+        let elsewhere = tmp.path().join("elsewhere");
+        fs::create_dir(&elsewhere)?;
+        let link = tmp.path().join("app/dist");
+        std::os::unix::fs::symlink(&elsewhere, &link)?;
+        project.build_arts.push(BuildArtifacts {
+            path: link.clone(),
+            ..project.build_arts[0].clone()
+        });
+
+        let check = CleanupPlan::from_projects(std::slice::from_ref(&project))
+            .validate(DEFAULT_TOLERANCE_PERCENT, &[project]);
+        assert_eq!(check.rejected, vec![(link, Rejection::OutsideProject)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_rejects_directories_no_longer_detected() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = create_project(&tmp.path().join("app"), 100)?;
+        let target = project.build_arts[0].path.clone();
+
+        let plan = CleanupPlan::from_projects(&[project]);
+        let check = plan.validate(DEFAULT_TOLERANCE_PERCENT, &[]);
+        assert!(check.projects.is_empty());
+        assert_eq!(check.rejected, vec![(target, Rejection::NotDetected)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+//! Spotting build daemons and language servers that work in a project.
+//!
+//! Deleting build directories from under a running Gradle daemon,
+//! rust-analyzer, tsserver or Metro bundler leaves it with caches and open
+//! files that no longer exist, which shows up as confusing errors in the
+//! editor or the next build. Before cleaning, the running processes are
+//! inspected for such tools, and a project is in use by one when its
+//! working directory or any file it has open lies inside the project.
+//!
+//! Processes are read from `/proc` on Linux, and with `ps` and `lsof` on
+//! macOS. Elsewhere, or when a process can't be inspected (such as one
+//! owned by another user), nothing is found and cleaning goes ahead as
+//! before.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::project::Project;
+
+/// A running build daemon or language server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ToolProcess {
+    /// Process ID
+    pub pid: u32,
+
+    /// Name of the tool, such as `rust-analyzer`
+    pub tool: &'static str,
+
+    /// The working directory of the process and the files it has open
+    pub paths: Vec<PathBuf>,
+}
+
+impl ToolProcess {
+    /// Whether the process works inside `project`.
+    ///
+    /// The paths of a process are absolute with symlinks resolved, so the
+    /// project root is resolved the same way to compare them.
+    #[must_use]
+    pub fn uses(&self, project: &Project) -> bool {
+        let root =
+            fs::canonicalize(&project.root_path).unwrap_or_else(|_| project.root_path.clone());
+        self.paths.iter().any(|path| path.starts_with(&root))
+    }
+}
+
+/// Find the running build daemons and language servers.
+///
+/// Returns an empty list where processes can't be inspected.
+#[must_use]
+pub fn running_tools() -> Vec<ToolProcess> {
+    let tools: Vec<(u32, &'static str)> = platform::processes()
+        .into_iter()
+        .filter_map(|(pid, args)| identify_tool(&args).map(|tool| (pid, tool)))
+        .collect();
+    if tools.is_empty() {
+        return Vec::new();
+    }
+
+    let pids: Vec<u32> = tools.iter().map(|&(pid, _)| pid).collect();
+    let mut paths = platform::paths_in_use(&pids);
+    tools
+        .into_iter()
+        .map(|(pid, tool)| ToolProcess {
+            pid,
+            tool,
+            paths: paths.remove(&pid).unwrap_or_default(),
+        })
+        .filter(|process| !process.paths.is_empty())
+        .collect()
+}
+
+/// The tool a process with the command line `args` belongs to, if it is
+/// one that keeps state about a project's build directories.
+fn identify_tool(args: &[String]) -> Option<&'static str> {
+    let program = args.first().and_then(|arg| file_name(arg))?;
+    let names: Vec<&str> = args.iter().filter_map(|arg| file_name(arg)).collect();
+    let has_component = |name: &str| {
+        args.iter()
+            .any(|arg| Path::new(arg).components().any(|c| c.as_os_str() == name))
+    };
+
+    if matches!(program, "rust-analyzer" | "rust-analyzer.exe") {
+        Some("rust-analyzer")
+    } else if args.iter().any(|arg| arg.ends_with(".GradleDaemon")) {
+        Some("Gradle daemon")
+    } else if names
+        .iter()
+        .any(|name| matches!(*name, "tsserver" | "tsserver.js"))
+    {
+        Some("tsserver")
+    } else if has_component("metro")
+        || (args.iter().any(|arg| arg == "start")
+            && (has_component("react-native") || has_component("expo")))
+    {
+        Some("Metro bundler")
+    } else {
+        None
+    }
+}
+
+/// The last component of `arg` taken as a path.
+fn file_name(arg: &str) -> Option<&str> {
+    Path::new(arg).file_name()?.to_str()
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::{collections::HashMap, fs, path::PathBuf};
+
+    /// The PIDs and command lines of the running processes.
+    pub(super) fn processes() -> Vec<(u32, Vec<String>)> {
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let pid = entry.file_name().to_str()?.parse().ok()?;
+                let cmdline = fs::read(entry.path().join("cmdline")).ok()?;
+                let args = cmdline
+                    .split(|&byte| byte == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                    .collect();
+                Some((pid, args))
+            })
+            .collect()
+    }
+
+    /// The working directory and open files of each of `pids` that can be read.
+    pub(super) fn paths_in_use(pids: &[u32]) -> HashMap<u32, Vec<PathBuf>> {
+        pids.iter()
+            .map(|&pid| {
+                let proc_dir = PathBuf::from(format!("/proc/{pid}"));
+                let open_files = fs::read_dir(proc_dir.join("fd"))
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .filter_map(|fd| fs::read_link(fd.path()).ok())
+                    .filter(|path| path.is_absolute());
+                let paths = fs::read_link(proc_dir.join("cwd"))
+                    .into_iter()
+                    .chain(open_files)
+                    .collect();
+                (pid, paths)
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::{collections::HashMap, path::PathBuf, process::Command};
+
+    /// The PIDs and command lines of the running processes, from `ps`.
+    ///
+    /// Arguments are split at spaces, since `ps` joins them with spaces.
+    pub(super) fn processes() -> Vec<(u32, Vec<String>)> {
+        let Ok(output) = Command::new("ps")
+            .args(["-axww", "-o", "pid=,args="])
+            .output()
+        else {
+            return Vec::new();
+        };
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (pid, args) = line.trim().split_once(' ')?;
+                let args = args.split_whitespace().map(str::to_string).collect();
+                Some((pid.parse().ok()?, args))
+            })
+            .collect()
+    }
+
+    /// The working directory and open files of each of `pids`, from `lsof`.
+    pub(super) fn paths_in_use(pids: &[u32]) -> HashMap<u32, Vec<PathBuf>> {
+        let pid_list: Vec<String> = pids.iter().map(u32::to_string).collect();
+        let Ok(output) = Command::new("lsof")
+            .args(["-n", "-P", "-F", "pn", "-p", &pid_list.join(",")])
+            .output()
+        else {
+            return HashMap::new();
+        };
+
+        // One `p<pid>` line per process, followed by an `n<name>` line per file
+        let mut paths: HashMap<u32, Vec<PathBuf>> = HashMap::new();
+        let mut current = None;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some(pid) = line.strip_prefix('p') {
+                current = pid.parse().ok();
+            } else if let (Some(pid), Some(name)) = (current, line.strip_prefix('n'))
+                && name.starts_with('/')
+            {
+                paths.entry(pid).or_default().push(PathBuf::from(name));
+            }
+        }
+        paths
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use std::{collections::HashMap, path::PathBuf};
+
+    /// Other processes' working directories aren't readable here.
+    pub(super) const fn processes() -> Vec<(u32, Vec<String>)> {
+        Vec::new()
+    }
+
+    /// Other processes' working directories aren't readable here.
+    pub(super) fn paths_in_use(_pids: &[u32]) -> HashMap<u32, Vec<PathBuf>> {
+        HashMap::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &str) -> Vec<String> {
+        command.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_identify_tool() {
+        let cases = [
+            ("/home/u/.cargo/bin/rust-analyzer", Some("rust-analyzer")),
+            (
+                "/usr/lib/jvm/bin/java -Xmx2g -cp gradle-launcher.jar \
+                 org.gradle.launcher.daemon.bootstrap.GradleDaemon 8.5",
+                Some("Gradle daemon"),
+            ),
+            (
+                "/usr/bin/node /app/node_modules/typescript/lib/tsserver.js --useInferredProjectPerProjectRoot",
+                Some("tsserver"),
+            ),
+            (
+                "node /app/node_modules/metro/src/cli.js serve",
+                Some("Metro bundler"),
+            ),
+            (
+                "node /app/node_modules/react-native/cli.js start",
+                Some("Metro bundler"),
+            ),
+            ("node /app/server.js start", None),
+            ("/usr/bin/vim src/main.rs", None),
+        ];
+
+        for (command, tool) in cases {
+            assert_eq!(identify_tool(&args(command)), tool, "{command}");
+        }
+        assert_eq!(identify_tool(&[]), None);
+    }
+
+    #[test]
+    fn test_uses_project() {
+        let project = Project::new(
+            crate::project::ProjectType::Rust,
+            PathBuf::from("/code/app"),
+            vec![],
+            None,
+        );
+        let process = |paths: &[&str]| ToolProcess {
+            pid: 1,
+            tool: "rust-analyzer",
+            paths: paths.iter().map(PathBuf::from).collect(),
+        };
+
+        assert!(process(&["/code/app"]).uses(&project));
+        assert!(process(&["/home/u", "/code/app/target/debug/.fingerprint/x"]).uses(&project));
+        assert!(!process(&["/code/app2"]).uses(&project));
+        assert!(!process(&["/code"]).uses(&project));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_finds_a_running_tool_in_its_working_directory() -> anyhow::Result<()> {
+        let paths = std::env::var_os("PATH").unwrap_or_default();
+        let Some(sleep) = std::env::split_paths(&paths)
+            .map(|dir| dir.join("sleep"))
+            .find(|path| path.is_file())
+        else {
+            return Ok(()); // no `sleep` to stand in for the tool
+        };
+        let tmp = tempfile::TempDir::new()?;
+        let server = tmp.path().join("rust-analyzer");
+        std::fs::copy(sleep, &server)?;
+        let project = tmp.path().join("app");
+        std::fs::create_dir(&project)?;
+
+        let Ok(mut child) = std::process::Command::new(&server)
+            .arg("5")
+            .current_dir(&project)
+            .spawn()
+        else {
+            return Ok(()); // the copy can't be executed here, e.g. noexec /tmp
+        };
+        // Until the child has called exec, it still shows the test's command line
+        let found = (0..50).find_map(|_| {
+            let found = running_tools()
+                .into_iter()
+                .find(|process| process.pid == child.id());
+            if found.is_none() {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+            found
+        });
+        child.kill()?;
+        child.wait()?;
+
+        let found = found.ok_or_else(|| anyhow::anyhow!("rust-analyzer not found"))?;
+        assert_eq!(found.tool, "rust-analyzer");
+        assert!(found.paths.contains(&project));
+        Ok(())
+    }
+}
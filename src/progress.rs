@@ -0,0 +1,277 @@
+//! Progress reporting for the scanning and cleaning stages.
+//!
+//! On a terminal, progress is shown with `indicatif` spinners and bars that
+//! redraw in place; parallel stages add a spinner per running task below the
+//! overall bar. CI logs handle carriage returns poorly, so in CI mode the
+//! same progress state is kept in a hidden bar and a background thread prints
+//! a plain ASCII status line every [`PLAIN_PROGRESS_INTERVAL`] instead.
+
+use std::{
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// How often a status line is printed in [`ProgressMode::Plain`].
+pub const PLAIN_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How progress is reported to the user.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Animated spinners and progress bars (interactive terminals)
+    #[default]
+    Bar,
+
+    /// Periodic plain ASCII status lines (CI logs)
+    Plain,
+
+    /// No progress output at all (machine-readable output formats)
+    Hidden,
+}
+
+impl ProgressMode {
+    /// Pick the mode for a run.
+    ///
+    /// Machine-readable output hides progress entirely; otherwise CI mode gets
+    /// plain status lines and everything else gets animated bars.
+    #[must_use]
+    pub const fn new(quiet: bool, ci: bool) -> Self {
+        if quiet {
+            Self::Hidden
+        } else if ci {
+            Self::Plain
+        } else {
+            Self::Bar
+        }
+    }
+
+    /// Start a spinner for a stage of unknown length.
+    #[must_use]
+    pub fn spinner(self, message: &str) -> Progress {
+        let bar = if self == Self::Bar {
+            let pb = ProgressBar::new_spinner();
+            if let Ok(style) = ProgressStyle::default_spinner().template("{spinner:.green} {msg}") {
+                pb.set_style(style);
+            }
+            pb.enable_steady_tick(Duration::from_millis(100));
+            pb
+        } else {
+            ProgressBar::with_draw_target(None, ProgressDrawTarget::hidden())
+        };
+        bar.set_message(message.to_string());
+
+        Progress::new(self, bar, None)
+    }
+
+    /// Start a progress bar for a stage of `len` steps.
+    ///
+    /// Tasks running in parallel can show their own spinner below the bar
+    /// through [`Progress::worker`].
+    #[must_use]
+    pub fn bar(self, len: u64) -> Progress {
+        if self != Self::Bar {
+            let bar = ProgressBar::with_draw_target(Some(len), ProgressDrawTarget::hidden());
+            return Progress::new(self, bar, None);
+        }
+
+        let multi = MultiProgress::new();
+        let bar = multi.add(ProgressBar::new(len));
+        if let Ok(style) = ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
+        {
+            bar.set_style(style.progress_chars("█▉▊▋▌▍▎▏  "));
+        }
+
+        Progress::new(self, bar, Some(multi))
+    }
+}
+
+/// A running progress indicator created by [`ProgressMode`].
+#[derive(Debug)]
+pub struct Progress {
+    mode: ProgressMode,
+    bar: ProgressBar,
+    multi: Option<MultiProgress>,
+    reporter: Option<PlainReporter>,
+}
+
+impl Progress {
+    fn new(mode: ProgressMode, bar: ProgressBar, multi: Option<MultiProgress>) -> Self {
+        let reporter = (mode == ProgressMode::Plain)
+            .then(|| PlainReporter::start(bar.clone(), PLAIN_PROGRESS_INTERVAL));
+
+        Self {
+            mode,
+            bar,
+            multi,
+            reporter,
+        }
+    }
+
+    /// Add a spinner for one task running in parallel with others.
+    ///
+    /// The spinner is shown below the overall bar until the returned
+    /// [`Worker`] is dropped. Outside of [`ProgressMode::Bar`] it is hidden.
+    #[must_use]
+    pub fn worker(&self) -> Worker {
+        let spinner = self.multi.as_ref().map_or_else(
+            || ProgressBar::with_draw_target(None, ProgressDrawTarget::hidden()),
+            |multi| {
+                let spinner = multi.add(ProgressBar::new_spinner());
+                if let Ok(style) =
+                    ProgressStyle::default_spinner().template("  {spinner:.green} {msg}")
+                {
+                    spinner.set_style(style);
+                }
+                spinner.enable_steady_tick(Duration::from_millis(100));
+                spinner
+            },
+        );
+
+        Worker { spinner }
+    }
+
+    /// Replace the status message.
+    pub fn set_message(&self, message: String) {
+        self.bar.set_message(message);
+    }
+
+    /// Advance the progress by one step.
+    pub fn inc(&self) {
+        self.bar.inc(1);
+    }
+
+    /// Stop reporting and print `message` as the final status.
+    pub fn finish_with_message(mut self, message: &'static str) {
+        // Stop the reporter first so its last line can't follow the final one
+        drop(self.reporter.take());
+
+        match self.mode {
+            ProgressMode::Bar => self.bar.finish_with_message(message),
+            ProgressMode::Plain => println!("{message}"),
+            ProgressMode::Hidden => self.bar.finish(),
+        }
+    }
+}
+
+/// Spinner for a single task of a parallel stage, created by [`Progress::worker`].
+///
+/// It is removed from the display when dropped.
+#[derive(Debug)]
+pub struct Worker {
+    spinner: ProgressBar,
+}
+
+impl Worker {
+    /// Describe what the task is currently doing.
+    pub fn set_message(&self, message: String) {
+        self.spinner.set_message(message);
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.spinner.finish_and_clear();
+    }
+}
+
+/// Background thread that prints the state of a hidden bar at a fixed interval.
+///
+/// The thread stops when the reporter is dropped.
+#[derive(Debug)]
+struct PlainReporter {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PlainReporter {
+    fn start(bar: ProgressBar, interval: Duration) -> Self {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            while stopped.recv_timeout(interval) == Err(RecvTimeoutError::Timeout) {
+                println!("{}", status_line(&bar));
+            }
+        });
+
+        Self {
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for PlainReporter {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Format a plain status line, e.g. `[20s] 3/12 Cleaned api (1.20 MB)`.
+fn status_line(bar: &ProgressBar) -> String {
+    use std::fmt::Write as _;
+
+    let mut line = format!("[{}s]", bar.elapsed().as_secs());
+    if let Some(len) = bar.length() {
+        let _ = write!(line, " {}/{len}", bar.position());
+    }
+    let message = bar.message();
+    if !message.is_empty() {
+        line.push(' ');
+        line.push_str(&message);
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_mode_new() {
+        assert_eq!(ProgressMode::new(true, true), ProgressMode::Hidden);
+        assert_eq!(ProgressMode::new(false, true), ProgressMode::Plain);
+        assert_eq!(ProgressMode::new(false, false), ProgressMode::Bar);
+    }
+
+    #[test]
+    fn test_status_line() {
+        let bar = ProgressBar::with_draw_target(Some(12), ProgressDrawTarget::hidden());
+        bar.inc(3);
+        bar.set_message("Cleaned api (1.20 MB)");
+
+        let line = status_line(&bar);
+        assert_eq!(line, "[0s] 3/12 Cleaned api (1.20 MB)");
+        assert!(line.is_ascii());
+    }
+
+    #[test]
+    fn test_status_line_spinner() {
+        let progress = ProgressMode::Plain.spinner("Scanning... 12 found");
+
+        assert_eq!(status_line(&progress.bar), "[0s] Scanning... 12 found");
+        progress.finish_with_message("[OK] done");
+    }
+
+    #[test]
+    fn test_worker_outside_bar_mode_is_hidden() {
+        let progress = ProgressMode::Hidden.bar(2);
+        let worker = progress.worker();
+        worker.set_message("Removing /p/target".to_string());
+
+        assert!(worker.spinner.is_hidden());
+        drop(worker);
+        progress.finish_with_message("[OK] done");
+    }
+
+    #[test]
+    fn test_plain_reporter_stops_on_drop() {
+        let progress = ProgressMode::Plain.bar(3);
+        progress.inc();
+        progress.finish_with_message("[OK] done");
+    }
+}
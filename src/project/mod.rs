@@ -10,11 +10,15 @@
 //! - [`Projects`] - A collection of projects with batch operations
 //! - [`ProjectType`] - Enumeration of supported project types (Rust, Node.js, Python, Go, Java, C/C++, Swift, .NET, Ruby, Elixir, Deno)
 //! - [`BuildArtifacts`] - Information about build directories and their sizes
+//! - [`RebuildCost`] - Heuristic estimate of how expensive a project is to rebuild
 
 #[allow(clippy::module_inception)]
 // This is acceptable as it is the main module for project management
 pub mod project;
 pub mod projects;
+mod selection;
 
-pub use project::{BuildArtifacts, Project, ProjectType};
-pub use projects::Projects;
+pub use project::{
+    BuildArtifacts, Detection, Project, ProjectDisplay, ProjectType, RebuildCost, SubdirSize,
+};
+pub use projects::{CollapsedProjects, Projects};
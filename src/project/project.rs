@@ -5,17 +5,28 @@
 
 use std::{
     fmt::{Display, Formatter, Result},
-    path::PathBuf,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
 };
 
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{DisplayOptions, RustSubdir},
+    detector::{CONDA_ENV_DIRS, VENV_DIRS},
+    risk::{RiskLevel, RiskOverrides, artifact_risk},
+    snapshots::SizeChange,
+    utils::{DirStats, SizeUnits, format_bytes},
+};
 
 /// Enumeration of supported development project types.
 ///
 /// This enum distinguishes between different types of development projects
 /// that the tool can detect and clean. Each project type has its own
 /// characteristic files and build directories.
-#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ProjectType {
     /// Rust project with Cargo.toml and target/ directory
@@ -36,10 +47,10 @@ pub enum ProjectType {
     /// and various cache/build directories like `__pycache__`, `.pytest_cache`, etc.
     Python,
 
-    /// Go project with `go.mod` and vendor/ directory
+    /// Go project with `go.mod` and a vendor/ or bin/ directory
     ///
-    /// Go projects are identified by the presence of both a `go.mod`
-    /// file and a `vendor/` directory in the same location.
+    /// Go projects are identified by a `go.mod` file next to a `vendor/`
+    /// directory or a `bin/` directory of compiled executables.
     Go,
 
     /// Java/Kotlin project with pom.xml or build.gradle and target/ or build/ directory
@@ -52,7 +63,9 @@ pub enum ProjectType {
     /// C/C++ project with CMakeLists.txt or Makefile and build/ directory
     ///
     /// C/C++ projects are identified by the presence of build system files
-    /// (`CMakeLists.txt` or `Makefile`) alongside a `build/` directory.
+    /// (`CMakeLists.txt` or `Makefile`) alongside a `build/` directory, or by
+    /// Visual Studio C++ projects with their `.vs/` cache and configuration
+    /// output directories.
     Cpp,
 
     /// Swift project with Package.swift and .build/ directory
@@ -110,11 +123,88 @@ pub enum ProjectType {
     /// file alongside a `zig-cache/` or `zig-out/` directory.
     Zig,
 
-    /// Scala project with build.sbt and target/ directory
+    /// Scala project with build.sbt and target/ or project/target/ directories
     ///
     /// Scala projects are identified by the presence of a `build.sbt`
-    /// file and a `target/` directory.
+    /// file and a `target/` directory, or the `project/target/` directory
+    /// of the compiled sbt build definition.
     Scala,
+
+    /// Android project with Gradle settings and module build/ or .cxx/ directories
+    ///
+    /// Android projects are identified by a `settings.gradle(.kts)` file next
+    /// to `gradle.properties` or `local.properties`, with a build script that
+    /// applies an Android plugin. All module build directories belong to the
+    /// one project.
+    Android,
+
+    /// Lua project with a rockspec and `lua_modules`/ or .luarocks/
+    ///
+    /// Lua projects are identified by a `*.rockspec` file alongside a
+    /// `lua_modules/` or `.luarocks/` directory managed by `LuaRocks`.
+    Lua,
+
+    /// Nim project with a .nimble file and nimcache/ directory
+    ///
+    /// Nim projects are identified by the presence of a `*.nimble` file
+    /// and a `nimcache/` directory.
+    Nim,
+
+    /// OCaml project with dune-project and _build/ directory
+    ///
+    /// OCaml projects are identified by the presence of a `dune-project`
+    /// file and a `_build/` directory left by dune.
+    OCaml,
+
+    /// Erlang project with rebar.config and _build/ directory
+    ///
+    /// Erlang projects are identified by the presence of a `rebar.config`
+    /// file and a `_build/` directory left by rebar3, without the `mix.exs`
+    /// of an Elixir project.
+    Erlang,
+
+    /// Julia project with Project.toml and .julia/compiled/ or deps/usr/
+    ///
+    /// Julia projects are identified by a `Project.toml` file alongside the
+    /// precompile caches of a project-local `.julia/` depot or the
+    /// `deps/usr/` directory of a built package.
+    Julia,
+
+    /// R project with renv.lock and renv/library/ directory
+    ///
+    /// R projects are identified by the presence of a `renv.lock` file and
+    /// the `renv/library/` directory where renv installs packages.
+    R,
+}
+
+/// Rough estimate of how expensive it is to regenerate a project's artifacts.
+///
+/// Variants are ordered from cheapest to most expensive, so they can be
+/// compared directly (`RebuildCost::High > RebuildCost::Low`).
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RebuildCost {
+    /// Artifacts are regenerated almost for free (e.g. Python bytecode caches).
+    Low,
+
+    /// A rebuild or reinstall takes noticeable but moderate time.
+    Medium,
+
+    /// A rebuild means a long compile or a large dependency re-download.
+    High,
+}
+
+impl Display for RebuildCost {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let label = match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        };
+        write!(f, "{label}")
+    }
 }
 
 /// Information about build artifacts that can be cleaned.
@@ -135,6 +225,29 @@ pub struct BuildArtifacts {
     /// This value is calculated by recursively summing the sizes of all files
     /// within the build directory. It's used for filtering and reporting purposes.
     pub size: u64,
+
+    /// Number of files within the build directory
+    ///
+    /// Collected in the same walk as `size`. Large trees such as
+    /// `node_modules/` can exhaust inodes long before disk space.
+    pub files: u64,
+
+    /// Number of subdirectories within the build directory
+    ///
+    /// Collected in the same walk as `size`.
+    pub dirs: u64,
+
+    /// Most recent modification time of any file within the build directory
+    ///
+    /// Collected in the same walk as `size`; `None` if the directory has no
+    /// readable files or hasn't been measured.
+    pub newest_file: Option<SystemTime>,
+
+    /// Whether `size` and the counts are lower bounds
+    ///
+    /// Set when sizing stopped at `--quick-size-threshold`. The exact figures
+    /// are measured once the project is selected for cleaning.
+    pub estimated: bool,
 }
 
 /// Representation of a development project with cleanable build artifacts.
@@ -165,6 +278,78 @@ pub struct Project {
     /// For Node.js projects, this is extracted from the `name` field in `package.json`.
     /// May be `None` if the name cannot be determined or parsed.
     pub name: Option<String>,
+
+    /// Whether the project has opted out of cleanup.
+    ///
+    /// Set by the scanner from a `protected = true` entry in the project's
+    /// `.clean-dev-dirs.toml`. Protected projects are never cleaned.
+    pub protected: bool,
+
+    /// Unix timestamp (seconds) of the latest commit in the enclosing git repository.
+    ///
+    /// `None` when the project is not inside a git repository or the lookup
+    /// hasn't been performed (see [`crate::vcs::annotate_last_commits`]).
+    pub last_commit: Option<i64>,
+
+    /// Most recent modification time of any file in the build artifacts.
+    ///
+    /// Measured by the scanner when ages come from
+    /// [`AgeSource::NewestFile`](crate::config::AgeSource::NewestFile); `None`
+    /// otherwise, in which case the artifact directory's own modification
+    /// time is used (see [`crate::filtering::artifact_modified`]).
+    pub newest_file: Option<SystemTime>,
+
+    /// The scanned directory this project was found under.
+    ///
+    /// When the same project is reachable from several scan roots (nested
+    /// roots, or a symlinked alias), this is the first root that found it.
+    /// `None` for projects not produced by a scan.
+    pub scan_root: Option<PathBuf>,
+
+    /// How much the build artifacts grew since the previous scan.
+    ///
+    /// `None` when they weren't measured before or the comparison hasn't been
+    /// made (see [`crate::snapshots::annotate_size_changes`]).
+    pub size_change: Option<SizeChange>,
+
+    /// Why the project was detected the way it was.
+    ///
+    /// Only recorded when scanning with `--explain`.
+    pub detection: Option<Detection>,
+
+    /// Sizes of the well-known subdirectories of a Rust project's target
+    /// directories, such as `target/debug/incremental/`.
+    ///
+    /// Only measured by the scanner in verbose mode; empty otherwise.
+    pub target_breakdown: Vec<SubdirSize>,
+}
+
+/// The size of a well-known subdirectory of a Rust target directory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SubdirSize {
+    /// Which subdirectory it is
+    pub kind: RustSubdir,
+
+    /// Path to the subdirectory
+    pub path: PathBuf,
+
+    /// Total size of its files in bytes
+    pub size: u64,
+}
+
+/// Which detector recognized a project, and from what.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Detection {
+    /// Name of the detector that matched (e.g. `"rust"`)
+    pub detector: String,
+
+    /// The files in the project root the detector looks for that were found
+    /// (e.g. `["Cargo.toml"]`)
+    pub markers: Vec<String>,
+
+    /// The build directories the detector chose, before configured extra
+    /// artifacts were added
+    pub artifacts: Vec<PathBuf>,
 }
 
 impl Project {
@@ -193,6 +378,10 @@ impl Project {
     /// let build_arts = vec![BuildArtifacts {
     ///     path: PathBuf::from("/path/to/project/target"),
     ///     size: 1024,
+    ///     files: 12,
+    ///     dirs: 3,
+    ///     newest_file: None,
+    ///     estimated: false,
     /// }];
     ///
     /// let project = Project::new(
@@ -214,6 +403,13 @@ impl Project {
             root_path,
             build_arts,
             name,
+            protected: false,
+            last_commit: None,
+            newest_file: None,
+            scan_root: None,
+            size_change: None,
+            detection: None,
+            target_breakdown: Vec::new(),
         }
     }
 
@@ -222,16 +418,202 @@ impl Project {
     pub fn total_size(&self) -> u64 {
         self.build_arts.iter().map(|a| a.size).sum()
     }
+
+    /// Return true if the size of any build artifact directory is only a lower bound.
+    #[must_use]
+    pub fn size_is_estimated(&self) -> bool {
+        self.build_arts.iter().any(|a| a.estimated)
+    }
+
+    /// Format the total size for display, e.g. `"1.20 GB"`, or
+    /// `"≥5.00 GB (estimated)"` when it's only a lower bound.
+    #[must_use]
+    pub fn formatted_size(&self, units: SizeUnits) -> String {
+        let size = format_bytes(self.total_size(), units);
+        if self.size_is_estimated() {
+            format!("≥{size} (estimated)")
+        } else {
+            size
+        }
+    }
+
+    /// Measure the exact size of the build artifact directories whose size
+    /// is only a lower bound.
+    pub fn measure_estimated_sizes(&mut self) {
+        for artifact in self.build_arts.iter_mut().filter(|a| a.estimated) {
+            let stats = DirStats::collect(&artifact.path);
+            artifact.size = stats.size;
+            artifact.files = stats.files;
+            artifact.dirs = stats.dirs;
+            artifact.newest_file = stats.newest_file;
+            artifact.estimated = false;
+        }
+    }
+
+    /// Return the number of files across all build artifact directories.
+    #[must_use]
+    pub fn total_files(&self) -> u64 {
+        self.build_arts.iter().map(|a| a.files).sum()
+    }
+
+    /// Return the number of subdirectories across all build artifact directories.
+    #[must_use]
+    pub fn total_dirs(&self) -> u64 {
+        self.build_arts.iter().map(|a| a.dirs).sum()
+    }
+
+    /// Number of whole days since the enclosing git repository's last commit.
+    ///
+    /// Returns `None` when [`Project::last_commit`] is unknown.
+    #[must_use]
+    pub fn last_commit_age_days(&self) -> Option<i64> {
+        let last_commit = self.last_commit?;
+        Some((chrono::Utc::now().timestamp() - last_commit).max(0) / 86_400)
+    }
+
+    /// Estimate how costly it would be to regenerate this project's artifacts.
+    ///
+    /// The estimate starts from the project type (slow compilers and
+    /// dependency-heavy package managers are `High`, bytecode caches are `Low`)
+    /// and is raised one level when the artifacts exceed 1 GB. A Python
+    /// project with a virtual or conda environment to clean is always `High`,
+    /// since every package in it has to be installed again.
+    #[must_use]
+    pub fn rebuild_cost(&self) -> RebuildCost {
+        const LARGE_ARTIFACTS: u64 = 1_000_000_000;
+
+        if self.kind == ProjectType::Python && self.has_environment() {
+            return RebuildCost::High;
+        }
+
+        let base = match self.kind {
+            ProjectType::Python | ProjectType::Go | ProjectType::Lua => RebuildCost::Low,
+            ProjectType::Java
+            | ProjectType::Cpp
+            | ProjectType::Swift
+            | ProjectType::DotNet
+            | ProjectType::Ruby
+            | ProjectType::Elixir
+            | ProjectType::Deno
+            | ProjectType::Php
+            | ProjectType::Dart
+            | ProjectType::Zig
+            | ProjectType::Nim
+            | ProjectType::OCaml
+            | ProjectType::Erlang
+            | ProjectType::Julia
+            | ProjectType::R => RebuildCost::Medium,
+            ProjectType::Rust
+            | ProjectType::Node
+            | ProjectType::Haskell
+            | ProjectType::Scala
+            | ProjectType::Android => RebuildCost::High,
+        };
+
+        if self.total_size() < LARGE_ARTIFACTS {
+            return base;
+        }
+
+        match base {
+            RebuildCost::Low => RebuildCost::Medium,
+            RebuildCost::Medium | RebuildCost::High => RebuildCost::High,
+        }
+    }
+
+    /// Whether one of the build directories is a virtual or conda
+    /// environment in the project root.
+    fn has_environment(&self) -> bool {
+        self.build_arts.iter().any(|artifact| {
+            artifact.path.parent() == Some(self.root_path.as_path())
+                && artifact.path.file_name().is_some_and(|name| {
+                    VENV_DIRS
+                        .iter()
+                        .chain(&CONDA_ENV_DIRS)
+                        .any(|dir| name == *dir)
+                })
+        })
+    }
+
+    /// How risky it is to delete `artifact`, one of this project's build
+    /// directories, with the levels from `overrides` (see [`crate::risk`]).
+    #[must_use]
+    pub fn artifact_risk(&self, artifact: &BuildArtifacts, overrides: &RiskOverrides) -> RiskLevel {
+        artifact_risk(overrides, &self.kind, &self.root_path, &artifact.path)
+    }
+
+    /// The highest risk of deleting any of this project's build directories.
+    #[must_use]
+    pub fn risk(&self, overrides: &RiskOverrides) -> RiskLevel {
+        self.build_arts
+            .iter()
+            .map(|artifact| self.artifact_risk(artifact, overrides))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The project's path as shown in human-readable output.
+    ///
+    /// Relative to the directory that holds its [`scan_root`](Self::scan_root),
+    /// so it starts with the name of the scan root: a project in
+    /// `/home/user/code/web/app` found by scanning `~/code` is shown as
+    /// `code/web/app`. That keeps paths short and still tells several scan
+    /// roots apart. The absolute path is shown when `opts` asks for
+    /// absolute paths, and for projects without a scan root or outside it.
+    #[must_use]
+    pub fn display_path(&self, opts: &DisplayOptions) -> PathBuf {
+        if opts.absolute_paths {
+            return self.root_path.clone();
+        }
+
+        self.scan_root
+            .as_deref()
+            .and_then(|root| path_from_root_name(&self.root_path, root))
+            .unwrap_or_else(|| self.root_path.clone())
+    }
+
+    /// The project formatted for human-readable output with `opts`.
+    #[must_use]
+    pub const fn display_with<'a>(&'a self, opts: &'a DisplayOptions) -> ProjectDisplay<'a> {
+        ProjectDisplay {
+            project: self,
+            opts,
+        }
+    }
+}
+
+/// A project formatted for display, returned by [`Project::display_with`].
+#[derive(Clone, Copy, Debug)]
+pub struct ProjectDisplay<'a> {
+    project: &'a Project,
+    opts: &'a DisplayOptions,
 }
 
-impl Display for Project {
+/// `path` relative to the parent of the scan root `root`, or `None` if it
+/// isn't inside `root`.
+fn path_from_root_name(path: &Path, root: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(root).ok()?;
+
+    // A root such as `.` is named by the directory it resolves to
+    let name = match root.file_name() {
+        Some(name) => PathBuf::from(name),
+        None => PathBuf::from(fs::canonicalize(root).ok()?.file_name()?),
+    };
+    if relative.as_os_str().is_empty() {
+        Some(name)
+    } else {
+        Some(name.join(relative))
+    }
+}
+
+impl Display for ProjectDisplay<'_> {
     /// Format the project for display with the appropriate emoji and name.
     ///
     /// This implementation provides a human-readable representation of the project
     /// that includes:
     /// - An emoji indicator based on the project type (🦀 for Rust, 📦 for Node.js, 🐍 for Python, 🐹 for Go)
     /// - The project name if available, otherwise just the path
-    /// - The project's root path
+    /// - The project's path, relative to its scan root unless absolute paths
+    ///   are selected (see [`display_path`](Project::display_path))
     ///
     /// # Examples
     ///
@@ -245,7 +627,8 @@ impl Display for Project {
     /// - `[net] my-dotnet-project (/path/to/project)`
     /// - `[rs] /path/to/unnamed/project` (when no name is available)
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let icon = match self.kind {
+        let project = self.project;
+        let icon = match project.kind {
             ProjectType::Rust => "[rs]",
             ProjectType::Node => "[js]",
             ProjectType::Python => "[py]",
@@ -258,16 +641,24 @@ impl Display for Project {
             ProjectType::Elixir => "[ex]",
             ProjectType::Deno => "[deno]",
             ProjectType::Php => "[php]",
-            ProjectType::Haskell => "λ",
+            ProjectType::Haskell => "[hs]",
             ProjectType::Dart => "[dart]",
             ProjectType::Zig => "[zig]",
             ProjectType::Scala => "[scala]",
+            ProjectType::Android => "[android]",
+            ProjectType::Lua => "[lua]",
+            ProjectType::Nim => "[nim]",
+            ProjectType::OCaml => "[ml]",
+            ProjectType::Erlang => "[erl]",
+            ProjectType::Julia => "[jl]",
+            ProjectType::R => "[r]",
         };
 
-        if let Some(name) = &self.name {
-            write!(f, "{icon} {name} ({})", self.root_path.display())
+        let path = project.display_path(self.opts);
+        if let Some(name) = &project.name {
+            write!(f, "{icon} {name} ({})", path.display())
         } else {
-            write!(f, "{icon} {}", self.root_path.display())
+            write!(f, "{icon} {}", path.display())
         }
     }
 }
@@ -282,6 +673,10 @@ mod tests {
         BuildArtifacts {
             path: PathBuf::from(path),
             size,
+            files: 0,
+            dirs: 0,
+            newest_file: None,
+            estimated: false,
         }
     }
 
@@ -367,6 +762,7 @@ mod tests {
 
     #[test]
     fn test_project_display_with_name() {
+        let display = DisplayOptions::default();
         let rust_project = create_test_project(
             ProjectType::Rust,
             "/path/to/rust-project",
@@ -376,7 +772,7 @@ mod tests {
         );
 
         let expected = "[rs] my-rust-app (/path/to/rust-project)";
-        assert_eq!(format!("{rust_project}"), expected);
+        assert_eq!(rust_project.display_with(&display).to_string(), expected);
 
         let node_project = create_test_project(
             ProjectType::Node,
@@ -387,7 +783,7 @@ mod tests {
         );
 
         let expected = "[js] my-node-app (/path/to/node-project)";
-        assert_eq!(format!("{node_project}"), expected);
+        assert_eq!(node_project.display_with(&display).to_string(), expected);
 
         let python_project = create_test_project(
             ProjectType::Python,
@@ -398,7 +794,7 @@ mod tests {
         );
 
         let expected = "[py] my-python-app (/path/to/python-project)";
-        assert_eq!(format!("{python_project}"), expected);
+        assert_eq!(python_project.display_with(&display).to_string(), expected);
 
         let go_project = create_test_project(
             ProjectType::Go,
@@ -409,7 +805,7 @@ mod tests {
         );
 
         let expected = "[go] my-go-app (/path/to/go-project)";
-        assert_eq!(format!("{go_project}"), expected);
+        assert_eq!(go_project.display_with(&display).to_string(), expected);
 
         let java_project = create_test_project(
             ProjectType::Java,
@@ -420,7 +816,7 @@ mod tests {
         );
 
         let expected = "[java] my-java-app (/path/to/java-project)";
-        assert_eq!(format!("{java_project}"), expected);
+        assert_eq!(java_project.display_with(&display).to_string(), expected);
 
         let cpp_project = create_test_project(
             ProjectType::Cpp,
@@ -431,7 +827,7 @@ mod tests {
         );
 
         let expected = "[cpp] my-cpp-app (/path/to/cpp-project)";
-        assert_eq!(format!("{cpp_project}"), expected);
+        assert_eq!(cpp_project.display_with(&display).to_string(), expected);
 
         let swift_project = create_test_project(
             ProjectType::Swift,
@@ -442,7 +838,7 @@ mod tests {
         );
 
         let expected = "[swift] my-swift-app (/path/to/swift-project)";
-        assert_eq!(format!("{swift_project}"), expected);
+        assert_eq!(swift_project.display_with(&display).to_string(), expected);
 
         let dotnet_project = create_test_project(
             ProjectType::DotNet,
@@ -453,7 +849,7 @@ mod tests {
         );
 
         let expected = "[net] my-dotnet-app (/path/to/dotnet-project)";
-        assert_eq!(format!("{dotnet_project}"), expected);
+        assert_eq!(dotnet_project.display_with(&display).to_string(), expected);
 
         let ruby_project = create_test_project(
             ProjectType::Ruby,
@@ -464,7 +860,7 @@ mod tests {
         );
 
         let expected = "[rb] my-ruby-gem (/path/to/ruby-project)";
-        assert_eq!(format!("{ruby_project}"), expected);
+        assert_eq!(ruby_project.display_with(&display).to_string(), expected);
 
         let elixir_project = create_test_project(
             ProjectType::Elixir,
@@ -475,7 +871,7 @@ mod tests {
         );
 
         let expected = "[ex] my_elixir_app (/path/to/elixir-project)";
-        assert_eq!(format!("{elixir_project}"), expected);
+        assert_eq!(elixir_project.display_with(&display).to_string(), expected);
 
         let deno_project = create_test_project(
             ProjectType::Deno,
@@ -486,7 +882,7 @@ mod tests {
         );
 
         let expected = "[deno] my-deno-app (/path/to/deno-project)";
-        assert_eq!(format!("{deno_project}"), expected);
+        assert_eq!(deno_project.display_with(&display).to_string(), expected);
     }
 
     #[test]
@@ -500,7 +896,12 @@ mod tests {
         );
 
         let expected = "[rs] /path/to/unnamed-project";
-        assert_eq!(format!("{rust_project}"), expected);
+        assert_eq!(
+            rust_project
+                .display_with(&DisplayOptions::default())
+                .to_string(),
+            expected
+        );
 
         let node_project = create_test_project(
             ProjectType::Node,
@@ -511,7 +912,54 @@ mod tests {
         );
 
         let expected = "[js] /some/other/path";
-        assert_eq!(format!("{node_project}"), expected);
+        assert_eq!(
+            node_project
+                .display_with(&DisplayOptions::default())
+                .to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_path_from_root_name() -> anyhow::Result<()> {
+        let root = Path::new("/home/user/code");
+        assert_eq!(
+            path_from_root_name(Path::new("/home/user/code/web/app"), root),
+            Some(PathBuf::from("code/web/app"))
+        );
+        assert_eq!(path_from_root_name(root, root), Some(PathBuf::from("code")));
+        assert_eq!(path_from_root_name(Path::new("/srv/app"), root), None);
+
+        let tmp = tempfile::TempDir::new()?;
+        let cwd = tmp.path().join("work").join(".");
+        std::fs::create_dir_all(cwd.join("app"))?;
+        assert_eq!(
+            path_from_root_name(&cwd.join("app"), &cwd),
+            Some(PathBuf::from("work/app"))
+        );
+
+        let mut project = create_test_project(
+            ProjectType::Rust,
+            "/home/user/code/api",
+            "/home/user/code/api/target",
+            1024,
+            Some("api".to_string()),
+        );
+        project.scan_root = Some(root.to_path_buf());
+        assert_eq!(
+            project.display_with(&DisplayOptions::default()).to_string(),
+            "[rs] api (code/api)"
+        );
+
+        let absolute = DisplayOptions {
+            absolute_paths: true,
+            ..DisplayOptions::default()
+        };
+        assert_eq!(
+            project.display_with(&absolute).to_string(),
+            "[rs] api (/home/user/code/api)"
+        );
+        Ok(())
     }
 
     #[test]
@@ -553,7 +1001,10 @@ mod tests {
         );
 
         assert_eq!(project.total_size(), 0);
-        assert_eq!(format!("{project}"), "[py] empty-project (/empty/project)");
+        assert_eq!(
+            project.display_with(&DisplayOptions::default()).to_string(),
+            "[py] empty-project (/empty/project)"
+        );
     }
 
     #[test]
@@ -569,4 +1020,57 @@ mod tests {
 
         assert_eq!(project.total_size(), large_size);
     }
+
+    #[test]
+    fn test_rebuild_cost_by_type() {
+        let python = create_test_project(ProjectType::Python, "/py", "/py/__pycache__", 1024, None);
+        let java = create_test_project(ProjectType::Java, "/java", "/java/target", 1024, None);
+        let node = create_test_project(ProjectType::Node, "/js", "/js/node_modules", 1024, None);
+
+        assert_eq!(python.rebuild_cost(), RebuildCost::Low);
+        assert_eq!(java.rebuild_cost(), RebuildCost::Medium);
+        assert_eq!(node.rebuild_cost(), RebuildCost::High);
+    }
+
+    #[test]
+    fn test_rebuild_cost_raised_for_large_artifacts() {
+        let python = create_test_project(
+            ProjectType::Python,
+            "/py",
+            "/py/__pycache__",
+            2_000_000_000,
+            None,
+        );
+        let rust = create_test_project(ProjectType::Rust, "/rs", "/rs/target", 2_000_000_000, None);
+
+        assert_eq!(python.rebuild_cost(), RebuildCost::Medium);
+        assert_eq!(rust.rebuild_cost(), RebuildCost::High);
+    }
+
+    #[test]
+    fn test_rebuild_cost_of_python_environments() {
+        for env in ["/py/.venv", "/py/venv", "/py/env", "/py/.conda"] {
+            let project = create_test_project(ProjectType::Python, "/py", env, 1024, None);
+            assert_eq!(project.rebuild_cost(), RebuildCost::High, "{env}");
+        }
+
+        let nested = create_test_project(ProjectType::Python, "/py", "/py/src/env", 1024, None);
+        assert_eq!(nested.rebuild_cost(), RebuildCost::Low);
+    }
+
+    #[test]
+    fn test_rebuild_cost_ordering_and_display() {
+        assert!(RebuildCost::High > RebuildCost::Medium);
+        assert!(RebuildCost::Medium > RebuildCost::Low);
+        assert_eq!(RebuildCost::High.to_string(), "high");
+    }
+
+    #[test]
+    fn test_last_commit_age_days() {
+        let mut project = create_test_project(ProjectType::Rust, "/r", "/r/target", 0, None);
+        assert_eq!(project.last_commit_age_days(), None);
+
+        project.last_commit = Some(chrono::Utc::now().timestamp() - 3 * 86_400 - 60);
+        assert_eq!(project.last_commit_age_days(), Some(3));
+    }
 }
@@ -4,15 +4,19 @@
 //! development projects and provides various operations on them, including
 //! interactive selection, summary reporting, and parallel iteration support.
 
-use anyhow::Result;
+use chrono::Local;
 use colored::Colorize;
-use humansize::{DECIMAL, format_size};
-use inquire::{MultiSelect, list_option::ListOption};
 use rayon::prelude::*;
 
-use crate::project::ProjectType;
+use crate::config::{DisplayOptions, Preselect};
+use crate::error::Result;
+use crate::i18n::Message;
+use crate::project::{ProjectType, RebuildCost};
+use crate::utils::{SizeUnits, format_bytes};
+use crate::volumes::group_by_volume;
 
 use super::Project;
+use super::selection::{self, SelectionItem, SelectionState};
 
 /// A collection of development projects with associated operations.
 ///
@@ -23,6 +27,31 @@ use super::Project;
 #[derive(Debug)]
 pub struct Projects(Vec<Project>);
 
+/// Small projects shown as a single line of a project listing.
+///
+/// See [`Projects::split_small`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CollapsedProjects {
+    /// Number of projects collapsed
+    pub count: usize,
+
+    /// Total artifact size of the collapsed projects, in bytes
+    pub size: u64,
+}
+
+impl CollapsedProjects {
+    /// Describe the projects with their size in `units`, e.g. `137 small
+    /// projects totalling 420 MB`.
+    #[must_use]
+    pub fn describe(&self, units: SizeUnits) -> String {
+        format!(
+            "{} small projects totalling {}",
+            self.count,
+            format_bytes(self.size, units)
+        )
+    }
+}
+
 impl From<Vec<Project>> for Projects {
     /// Create a `Projects` collection from a vector of projects.
     ///
@@ -131,31 +160,55 @@ impl Projects {
         self.0.iter().map(Project::total_size).sum()
     }
 
+    /// Return true if the size of any project is only a lower bound.
+    #[must_use]
+    pub fn size_is_estimated(&self) -> bool {
+        self.0.iter().any(Project::size_is_estimated)
+    }
+
+    /// Measure the exact size of every build directory whose size is only a
+    /// lower bound (see `--quick-size-threshold`), in parallel.
+    ///
+    /// Called once the projects to clean are known, so enormous directories
+    /// are only walked completely when they are about to be removed.
+    pub fn measure_estimated_sizes(&mut self) {
+        self.0
+            .par_iter_mut()
+            .for_each(Project::measure_estimated_sizes);
+    }
+
     /// Present an interactive selection interface for choosing projects to clean.
     ///
     /// This method displays a multi-select dialog that allows users to choose
     /// which projects they want to clean. Each project is shown with its type
-    /// icon, path, and reclaimable space. All projects are selected by default.
+    /// icon, path, and reclaimable space. The projects matching `preselect`
+    /// start out selected, and the header shows the running total of the
+    /// selected projects' reclaimable space.
     ///
     /// # Returns
     ///
     /// - `Ok(Vec<Project>)` - The projects selected by the user
-    /// - `Err(anyhow::Error)` - If the interactive dialog fails or is canceled
+    /// - `Err(CleanDevDirsError)` - If the interactive dialog fails or is canceled
     ///
     /// # Interface Details
     ///
     /// - Uses a colorful theme for better visual appeal
     /// - Shows project type icons (🦀 Rust, 📦 Node.js, 🐍 Python, 🐹 Go, ☕ Java, ⚙️ C/C++, 🐦 Swift, 🔷 .NET)
     /// - Displays project paths and sizes in human-readable format
-    /// - Allows toggling selections with space bar
+    /// - Allows toggling selections with space bar, and selecting all or none
+    ///   with the right and left arrow keys
+    /// - `/` fuzzy-filters by project name, path and type; `t` toggles all shown
+    ///   projects of the type under the cursor
+    /// - `d` shows the largest entries of the build directories of the project
+    ///   under the cursor
     /// - Confirms selection with the Enter key
     ///
     /// # Examples
     ///
     /// ```
     /// # use crate::Projects;
-    /// # use anyhow::Result;
-    /// let selected_projects = projects.interactive_selection()?;
+    /// # use crate::Result;
+    /// let selected_projects = projects.interactive_selection(Preselect::Stale)?;
     /// println!("User selected {} projects", selected_projects.len());
     /// ```
     ///
@@ -163,53 +216,28 @@ impl Projects {
     ///
     /// This method can fail if:
     /// - The terminal doesn't support interactive input
-    /// - The user cancels the dialog (Ctrl+C)
+    /// - The user cancels the dialog (Esc or Ctrl+C)
     /// - There are I/O errors with the terminal
-    pub fn interactive_selection(&self) -> Result<Vec<Project>> {
-        let items: Vec<String> = self
-            .0
-            .iter()
-            .map(|p| {
-                let icon = icon_for_project_type(&p.kind);
-                format!(
-                    "{icon} {} ({})",
-                    p.root_path.display(),
-                    format_size(p.total_size(), DECIMAL)
-                )
-            })
-            .collect();
-
-        let defaults: Vec<usize> = (0..self.0.len()).collect();
-
-        let selections = MultiSelect::new("Select projects to clean:", items)
-            .with_default(&defaults)
-            .with_formatter(&|opts: &[ListOption<&String>]| {
-                opts.iter()
-                    .map(|o| o.value.as_str())
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            })
-            .prompt()?;
-
-        Ok(selections
-            .iter()
-            .filter_map(|selected_item| {
-                self.0
-                    .iter()
-                    .enumerate()
-                    .find(|(_, p)| {
-                        let icon = icon_for_project_type(&p.kind);
-                        let expected = format!(
-                            "{icon} {} ({})",
-                            p.root_path.display(),
-                            format_size(p.total_size(), DECIMAL)
-                        );
-                        &expected == selected_item
-                    })
-                    .map(|(i, _)| i)
-            })
-            .map(|i| self.0[i].clone())
-            .collect())
+    pub fn interactive_selection(
+        &self,
+        preselect: Preselect,
+        display: &DisplayOptions,
+    ) -> Result<Vec<Project>> {
+        let state = SelectionState::new(
+            self.0
+                .iter()
+                .map(|p| SelectionItem::new(p, selection_label(p, display)))
+                .collect(),
+            self.0
+                .iter()
+                .map(|p| selection::is_preselected(p, preselect))
+                .collect(),
+            display,
+        );
+
+        let selected = selection::prompt(display.text(Message::SelectProjects), state)?;
+
+        Ok(selected.into_iter().map(|i| self.0[i].clone()).collect())
     }
 
     /// Get the number of projects in the collection.
@@ -257,6 +285,29 @@ impl Projects {
         &self.0
     }
 
+    /// Separate the projects to list individually from those smaller than `threshold`.
+    ///
+    /// Used by `--collapse-below` to keep tiny entries such as `__pycache__`
+    /// directories from flooding a listing: they are only counted, while the
+    /// collection itself (and what gets cleaned) stays the same. The listed
+    /// projects keep their order. A single small project is not worth a
+    /// summary line and is listed like the others, as is everything when
+    /// `threshold` is 0.
+    #[must_use]
+    pub fn split_small(&self, threshold: u64) -> (Vec<&Project>, CollapsedProjects) {
+        let is_small = |p: &&Project| p.total_size() < threshold;
+        if self.0.iter().filter(is_small).count() < 2 {
+            return (self.0.iter().collect(), CollapsedProjects::default());
+        }
+
+        let (small, listed): (Vec<&Project>, Vec<&Project>) = self.0.iter().partition(is_small);
+        let collapsed = CollapsedProjects {
+            count: small.len(),
+            size: small.iter().map(|p| p.total_size()).sum(),
+        };
+        (listed, collapsed)
+    }
+
     /// Print a detailed summary of the projects and their reclaimable space.
     ///
     /// This method analyzes the collection and prints statistics including:
@@ -277,7 +328,7 @@ impl Projects {
     /// ```
     /// # use crate::Projects;
     /// let total_size = projects.get_total_size();
-    /// projects.print_summary(total_size);
+    /// projects.print_summary(total_size, false, &DisplayOptions::default());
     /// ```
     ///
     /// # Output Format
@@ -291,9 +342,16 @@ impl Projects {
     ///   ⚙️ 1 C/C++ project (0.3 GB)
     ///   🐦 1 Swift project (0.2 GB)
     ///   🔷 1 .NET/C# project (0.1 GB)
+    ///   Warning: 8 projects with a high rebuild cost (use --min-benefit to skip small ones)
     ///   💾 Total reclaimable space: 4.0 GB
+    ///     on /: 3.2 GB (12.0 GB free, 15.2 GB after cleaning)
+    ///     on /mnt/data: 0.8 GB (1.1 GB free, 1.9 GB after cleaning)
     /// ```
-    pub fn print_summary(&self, total_size: u64) {
+    ///
+    /// With `trashing`, the build directories go to the trash, which frees
+    /// nothing until it's emptied, so the projected free space is labeled
+    /// "after emptying the trash" instead.
+    pub fn print_summary(&self, total_size: u64, trashing: bool, display: &DisplayOptions) {
         let type_entries: &[(ProjectType, &str, &str)] = &[
             (ProjectType::Rust, "[rs]", "Rust"),
             (ProjectType::Node, "[js]", "Node.js"),
@@ -318,18 +376,84 @@ impl Projects {
                 println!(
                     "  {icon} {} {label} projects ({})",
                     count.to_string().bright_white(),
-                    format_size(size, DECIMAL).bright_white()
+                    display.bytes(size).bright_white()
                 );
             }
         }
 
-        println!(
-            "  Total reclaimable space: {}",
-            format_size(total_size, DECIMAL).bright_green().bold()
-        );
+        let expensive = self
+            .0
+            .iter()
+            .filter(|p| p.rebuild_cost() == RebuildCost::High)
+            .count();
+        if expensive > 0 {
+            println!(
+                "  {} {} with a high rebuild cost (use --min-benefit to skip small ones)",
+                display.text(Message::Warning).yellow(),
+                if expensive == 1 {
+                    "1 project".to_string()
+                } else {
+                    format!("{expensive} projects")
+                }
+            );
+        }
+
+        let total = if self.size_is_estimated() {
+            Message::TotalReclaimableAtLeast
+        } else {
+            Message::TotalReclaimable
+        };
+        let size = display.bytes(total_size).bright_green().bold();
+        println!("  {}", total.format(display.lang, &[("size", &size)]));
+
+        let after = if trashing {
+            "after emptying the trash"
+        } else {
+            "after cleaning"
+        };
+        for volume in group_by_volume(&self.0) {
+            let free = match (volume.free, volume.projected_free()) {
+                (Some(free), Some(projected)) => format!(
+                    " ({} free, {} {after})",
+                    display.bytes(free),
+                    display.bytes(projected).bright_white()
+                ),
+                _ => String::new(),
+            };
+            println!(
+                "    on {}: {}{free}",
+                volume.mount_point.display(),
+                display.bytes(volume.reclaimable)
+            );
+        }
     }
 }
 
+/// Build the line shown for a project in the interactive selection list.
+fn selection_label(project: &Project, display: &DisplayOptions) -> String {
+    let icon = icon_for_project_type(&project.kind);
+    let last_commit = project
+        .last_commit_age_days()
+        .map(|days| format!(", last commit {days}d ago"))
+        .unwrap_or_default();
+    let growth = project
+        .size_change
+        .map(|change| {
+            format!(
+                ", {}",
+                change.describe(Local::now().timestamp(), display.units)
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        "{icon} {} ({}{growth}, rebuild cost: {}, risk: {}{last_commit})",
+        project.display_path(display).display(),
+        project.formatted_size(display.units),
+        project.rebuild_cost(),
+        project.risk(&display.risk_overrides)
+    )
+}
+
 /// Return the icon for a given project type.
 const fn icon_for_project_type(kind: &ProjectType) -> &'static str {
     match kind {
@@ -345,9 +469,77 @@ const fn icon_for_project_type(kind: &ProjectType) -> &'static str {
         ProjectType::Elixir => "[ex]",
         ProjectType::Deno => "[deno]",
         ProjectType::Php => "[php]",
-        ProjectType::Haskell => "λ",
+        ProjectType::Haskell => "[hs]",
         ProjectType::Dart => "[dart]",
         ProjectType::Zig => "[zig]",
         ProjectType::Scala => "[scala]",
+        ProjectType::Android => "[android]",
+        ProjectType::Lua => "[lua]",
+        ProjectType::Nim => "[nim]",
+        ProjectType::OCaml => "[ml]",
+        ProjectType::Erlang => "[erl]",
+        ProjectType::Julia => "[jl]",
+        ProjectType::R => "[r]",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::project::BuildArtifacts;
+
+    fn project(name: &str, size: u64) -> Project {
+        let root = PathBuf::from("/work").join(name);
+        Project::new(
+            ProjectType::Python,
+            root.clone(),
+            vec![BuildArtifacts {
+                path: root.join("__pycache__"),
+                size,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some(name.to_string()),
+        )
+    }
+
+    fn names<'a>(listed: &[&'a Project]) -> Vec<&'a str> {
+        listed.iter().filter_map(|p| p.name.as_deref()).collect()
+    }
+
+    #[test]
+    fn test_split_small() {
+        let projects = Projects::from(vec![
+            project("big", 50_000_000),
+            project("tiny", 2_000),
+            project("medium", 10_000_000),
+            project("small", 400_000),
+        ]);
+
+        let (listed, collapsed) = projects.split_small(10_000_000);
+        assert_eq!(names(&listed), vec!["big", "medium"]);
+        assert_eq!(
+            collapsed,
+            CollapsedProjects {
+                count: 2,
+                size: 402_000
+            }
+        );
+        assert_eq!(
+            collapsed.describe(SizeUnits::Decimal),
+            "2 small projects totalling 402 kB"
+        );
+
+        // A lone small project is listed rather than summarized
+        let (listed, collapsed) = projects.split_small(100_000);
+        assert_eq!(listed.len(), 4);
+        assert_eq!(collapsed.count, 0);
+
+        let (listed, _) = projects.split_small(0);
+        assert_eq!(listed.len(), 4);
     }
 }
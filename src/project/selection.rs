@@ -0,0 +1,769 @@
+//! Interactive multi-select prompt for choosing projects to clean.
+//!
+//! The prompt keeps a running total of the selected projects' reclaimable
+//! space in its header, updated on every toggle. Pressing `/` starts a fuzzy
+//! filter over project names, paths and types, and `t` toggles all shown
+//! projects of the type under the cursor. `d` shows the largest entries of
+//! the build directories of the project under the cursor, measured when first
+//! asked for and remembered for the rest of the prompt. Key handling is kept
+//! separate from terminal I/O in [`SelectionState`] so it can be tested
+//! without a terminal.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write, stdout},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+
+use crate::{
+    config::{DisplayOptions, Preselect},
+    error::{CleanDevDirsError, Result, ResultExt},
+    filtering::artifact_modified,
+    i18n::{Lang, Message},
+    output::type_name,
+    utils::{SizeUnits, child_sizes, format_bytes},
+};
+
+use super::Project;
+
+/// Projects whose artifacts are older than this count as stale.
+const STALE_AFTER: Duration = Duration::from_hours(30 * 24);
+
+/// Projects with at least this many bytes of artifacts count as large.
+const LARGE_ARTIFACTS: u64 = 1_000_000_000;
+
+/// Number of options shown at once.
+const PAGE_SIZE: usize = 15;
+
+/// Number of entries of each build directory shown in the details.
+const DETAIL_ENTRIES: usize = 10;
+
+/// Whether `project` starts out checked under the given rule.
+pub(super) fn is_preselected(project: &Project, preselect: Preselect) -> bool {
+    match preselect {
+        Preselect::All => true,
+        Preselect::None => false,
+        Preselect::Stale => SystemTime::now()
+            .duration_since(artifact_modified(project))
+            .is_ok_and(|age| age >= STALE_AFTER),
+        Preselect::Large => project.total_size() >= LARGE_ARTIFACTS,
+    }
+}
+
+/// Case-insensitive fuzzy match: every character of `needle` appears in
+/// `haystack`, in order but not necessarily next to each other.
+fn fuzzy_match(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars().flat_map(char::to_lowercase);
+    needle
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|wanted| haystack.any(|c| c == wanted))
+}
+
+/// What the prompt should do after a key press.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum KeyOutcome {
+    /// Keep prompting
+    Continue,
+    /// The user confirmed the selection
+    Submit,
+    /// The user aborted the prompt
+    Cancel,
+}
+
+/// One option of the prompt.
+#[derive(Debug)]
+pub(super) struct SelectionItem {
+    label: String,
+    search_text: String,
+    group: &'static str,
+    size: u64,
+    artifacts: Vec<PathBuf>,
+}
+
+impl SelectionItem {
+    /// Describe `project` as an option shown as `label`.
+    ///
+    /// The filter matches against the project's name, path and type, and `t`
+    /// toggles projects of the same type together.
+    pub(super) fn new(project: &Project, label: String) -> Self {
+        let group = type_name(&project.kind);
+        Self {
+            label,
+            search_text: format!(
+                "{} {} {group}",
+                project.name.as_deref().unwrap_or(""),
+                project.root_path.display()
+            ),
+            group,
+            size: project.total_size(),
+            artifacts: project
+                .build_arts
+                .iter()
+                .map(|artifact| artifact.path.clone())
+                .collect(),
+        }
+    }
+}
+
+/// Options, checked state, filter, and cursor position of the prompt.
+#[derive(Debug)]
+pub(super) struct SelectionState {
+    items: Vec<SelectionItem>,
+    checked: Vec<bool>,
+    filter: String,
+    editing_filter: bool,
+    /// Indices into `items` of the options matching `filter`
+    visible: Vec<usize>,
+    /// Position of the cursor within `visible`
+    cursor: usize,
+    /// Index into `items` of the option whose details are shown
+    details: Option<usize>,
+    /// Entries of the build directories measured so far, largest first
+    breakdowns: HashMap<PathBuf, Vec<(PathBuf, u64)>>,
+    /// Units sizes are shown in
+    units: SizeUnits,
+    /// Language of the header and help lines
+    lang: Lang,
+}
+
+impl SelectionState {
+    /// Create the prompt state, with `checked[i]` as the initial state of option `i`
+    /// and sizes and messages shown as `display` selects.
+    pub(super) fn new(
+        items: Vec<SelectionItem>,
+        checked: Vec<bool>,
+        display: &DisplayOptions,
+    ) -> Self {
+        let visible = (0..items.len()).collect();
+        Self {
+            items,
+            checked,
+            filter: String::new(),
+            editing_filter: false,
+            visible,
+            cursor: 0,
+            details: None,
+            breakdowns: HashMap::new(),
+            units: display.units,
+            lang: display.lang,
+        }
+    }
+
+    /// Indices of the checked options, in order, including ones hidden by the filter.
+    pub(super) fn selected(&self) -> Vec<usize> {
+        (0..self.checked.len())
+            .filter(|&i| self.checked[i])
+            .collect()
+    }
+
+    /// Total size of the checked options, in bytes.
+    pub(super) fn selected_size(&self) -> u64 {
+        self.selected().iter().map(|&i| self.items[i].size).sum()
+    }
+
+    /// Recompute the visible options after the filter changed.
+    fn apply_filter(&mut self) {
+        self.visible = (0..self.items.len())
+            .filter(|&i| fuzzy_match(&self.filter, &self.items[i].search_text))
+            .collect();
+        self.cursor = 0;
+    }
+
+    /// Check or uncheck every visible option.
+    fn set_visible(&mut self, value: bool) {
+        for &i in &self.visible {
+            self.checked[i] = value;
+        }
+    }
+
+    /// Toggle the visible options in the same group as the one under the cursor.
+    ///
+    /// If all of them are checked they are unchecked, otherwise all are checked.
+    fn toggle_group(&mut self) {
+        let Some(&current) = self.visible.get(self.cursor) else {
+            return;
+        };
+        let group = self.items[current].group;
+        let members: Vec<usize> = self
+            .visible
+            .iter()
+            .copied()
+            .filter(|&i| self.items[i].group == group)
+            .collect();
+
+        let value = !members.iter().all(|&i| self.checked[i]);
+        for i in members {
+            self.checked[i] = value;
+        }
+    }
+
+    /// Apply a key press.
+    pub(super) fn handle_key(&mut self, key: KeyEvent) -> KeyOutcome {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return KeyOutcome::Cancel;
+        }
+
+        if self.details.is_some() {
+            self.details = None;
+            return KeyOutcome::Continue;
+        }
+
+        let len = self.visible.len();
+        match key.code {
+            KeyCode::Up if len > 0 => self.cursor = (self.cursor + len - 1) % len,
+            KeyCode::Down if len > 0 => self.cursor = (self.cursor + 1) % len,
+            KeyCode::PageUp => self.cursor = self.cursor.saturating_sub(PAGE_SIZE),
+            KeyCode::PageDown => {
+                self.cursor = (self.cursor + PAGE_SIZE).min(len.saturating_sub(1));
+            }
+            _ if self.editing_filter => self.handle_filter_key(key),
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Esc => return KeyOutcome::Cancel,
+            KeyCode::Enter => return KeyOutcome::Submit,
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = len.saturating_sub(1),
+            KeyCode::Char(' ') => {
+                if let Some(&i) = self.visible.get(self.cursor) {
+                    self.checked[i] = !self.checked[i];
+                }
+            }
+            KeyCode::Char('t') => self.toggle_group(),
+            KeyCode::Char('d') => self.details = self.visible.get(self.cursor).copied(),
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Right => self.set_visible(true),
+            KeyCode::Left => self.set_visible(false),
+            _ => {}
+        }
+
+        KeyOutcome::Continue
+    }
+
+    /// Apply a key press while the filter is being typed.
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.editing_filter = false,
+            KeyCode::Esc => {
+                self.editing_filter = false;
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.apply_filter();
+            }
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.apply_filter();
+            }
+            _ => {}
+        }
+    }
+
+    /// Header line with the running selection total.
+    pub(super) fn header(&self, message: &str) -> String {
+        format!(
+            "{} {} {}",
+            "?".green(),
+            message.bold(),
+            Message::SelectionTotal
+                .format(
+                    self.lang,
+                    &[
+                        ("selected", &self.selected().len()),
+                        ("total", &self.items.len()),
+                        ("size", &format_bytes(self.selected_size(), self.units)),
+                    ]
+                )
+                .cyan()
+        )
+    }
+
+    /// Line showing the filter, if one is set or being typed.
+    fn filter_line(&self) -> Option<String> {
+        if !self.editing_filter && self.filter.is_empty() {
+            return None;
+        }
+
+        let caret = if self.editing_filter { "_" } else { "" };
+        Some(format!(
+            "  /{}{caret} {}",
+            self.filter,
+            format!("({} of {} shown)", self.visible.len(), self.items.len()).dimmed()
+        ))
+    }
+
+    /// Help line for the current mode.
+    const fn help(&self) -> &'static str {
+        if self.details.is_some() {
+            Message::DetailsHelp.text(self.lang)
+        } else if self.editing_filter {
+            Message::FilterHelp.text(self.lang)
+        } else {
+            Message::SelectionHelp.text(self.lang)
+        }
+    }
+
+    /// Whether the shown details include build directories not measured yet.
+    fn breakdown_pending(&self) -> bool {
+        self.details.is_some_and(|i| {
+            self.items[i]
+                .artifacts
+                .iter()
+                .any(|dir| !self.breakdowns.contains_key(dir))
+        })
+    }
+
+    /// Measure the entries of the shown build directories not measured yet.
+    fn load_breakdowns(&mut self) {
+        let Some(i) = self.details else {
+            return;
+        };
+        for dir in &self.items[i].artifacts {
+            if !self.breakdowns.contains_key(dir) {
+                self.breakdowns.insert(dir.clone(), child_sizes(dir));
+            }
+        }
+    }
+
+    /// Lines for the details of the shown option, each cut to `width` characters.
+    ///
+    /// Every build directory is followed by its largest entries, or by a
+    /// placeholder while it hasn't been measured.
+    fn detail_lines(&self, width: usize) -> Vec<String> {
+        let Some(i) = self.details else {
+            return Vec::new();
+        };
+        let item = &self.items[i];
+
+        let mut lines = vec![format!("  {}", item.label).cyan().to_string()];
+        for dir in &item.artifacts {
+            lines.push(format!("  {}", dir.display()));
+            match self.breakdowns.get(dir) {
+                None => lines.push("      measuring...".dimmed().to_string()),
+                Some(entries) if entries.is_empty() => {
+                    lines.push("      (empty)".dimmed().to_string());
+                }
+                Some(entries) => lines.extend(breakdown_lines(dir, entries, self.units)),
+            }
+        }
+
+        lines
+            .into_iter()
+            .map(|line| line.chars().take(width).collect())
+            .collect()
+    }
+
+    /// Lines for the visible page of options, each cut to `width` characters.
+    fn option_lines(&self, width: usize) -> Vec<String> {
+        if self.visible.is_empty() {
+            return vec!["  (no matching projects)".dimmed().to_string()];
+        }
+
+        let start = self
+            .cursor
+            .saturating_sub(PAGE_SIZE - 1)
+            .min(self.visible.len().saturating_sub(PAGE_SIZE));
+
+        self.visible
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(PAGE_SIZE)
+            .map(|(pos, &i)| {
+                let pointer = if pos == self.cursor { ">" } else { " " };
+                let checkbox = if self.checked[i] { "[x]" } else { "[ ]" };
+                let line: String = format!("{pointer} {checkbox} {}", self.items[i].label)
+                    .chars()
+                    .take(width)
+                    .collect();
+                if pos == self.cursor {
+                    line.cyan().to_string()
+                } else {
+                    line
+                }
+            })
+            .collect()
+    }
+}
+
+/// The largest [`DETAIL_ENTRIES`] of `entries` of `dir`, with the rest summed
+/// up and sizes in `units`.
+fn breakdown_lines(dir: &Path, entries: &[(PathBuf, u64)], units: SizeUnits) -> Vec<String> {
+    let mut lines: Vec<String> = entries
+        .iter()
+        .take(DETAIL_ENTRIES)
+        .map(|(path, size)| {
+            let name = path.strip_prefix(dir).unwrap_or(path);
+            format!("    {:>12}  {}", format_bytes(*size, units), name.display())
+        })
+        .collect();
+
+    let rest = &entries[entries.len().min(DETAIL_ENTRIES)..];
+    if !rest.is_empty() {
+        let size: u64 = rest.iter().map(|(_, size)| size).sum();
+        lines.push(
+            format!(
+                "    {:>12}  ...and {} more",
+                format_bytes(size, units),
+                rest.len()
+            )
+            .dimmed()
+            .to_string(),
+        );
+    }
+    lines
+}
+
+/// Run the prompt on the terminal.
+///
+/// Returns the indices of the selected options.
+///
+/// # Errors
+///
+/// Returns an error if the terminal can't be put into raw mode (e.g. stdin is
+/// not a TTY), on I/O errors, or if the user cancels the prompt.
+pub(super) fn prompt(message: &str, mut state: SelectionState) -> Result<Vec<usize>> {
+    let terminal_error = || CleanDevDirsError::output("Failed to show the selection prompt");
+    terminal::enable_raw_mode().with_context(terminal_error)?;
+    let outcome = run_prompt(message, &mut state);
+    terminal::disable_raw_mode().with_context(terminal_error)?;
+
+    let mut out = stdout();
+    queue!(out, cursor::Show).with_context(terminal_error)?;
+    match outcome.with_context(terminal_error)? {
+        KeyOutcome::Submit => {
+            println!(
+                "{} {} {}",
+                "?".green(),
+                message.bold(),
+                format!(
+                    "{} project{} ({})",
+                    state.selected().len(),
+                    if state.selected().len() == 1 { "" } else { "s" },
+                    format_bytes(state.selected_size(), state.units)
+                )
+                .cyan()
+            );
+            Ok(state.selected())
+        }
+        KeyOutcome::Cancel | KeyOutcome::Continue => {
+            println!(
+                "{} {} {}",
+                "?".green(),
+                message.bold(),
+                Message::Cancelled.text(state.lang).red()
+            );
+            Err(CleanDevDirsError::output(
+                Message::SelectionCancelled.text(state.lang),
+            ))
+        }
+    }
+}
+
+/// Draw and redraw the prompt until the user submits or cancels.
+///
+/// Leaves the prompt area cleared.
+fn run_prompt(message: &str, state: &mut SelectionState) -> io::Result<KeyOutcome> {
+    let mut out = stdout();
+    queue!(out, cursor::Hide)?;
+    let mut drawn = 0;
+
+    loop {
+        clear_lines(&mut out, drawn)?;
+
+        let width = terminal::size().map_or(80, |(cols, _)| usize::from(cols));
+        let mut lines = vec![state.header(message)];
+        if state.details.is_some() {
+            lines.extend(state.detail_lines(width));
+        } else {
+            lines.extend(state.filter_line());
+            lines.extend(state.option_lines(width));
+        }
+        lines.push(
+            state
+                .help()
+                .chars()
+                .take(width)
+                .collect::<String>()
+                .dimmed()
+                .to_string(),
+        );
+
+        queue!(out, Print(lines.join("\r\n")))?;
+        out.flush()?;
+        drawn = lines.len();
+
+        // Measure after drawing the placeholder, then draw the results
+        if state.breakdown_pending() {
+            state.load_breakdowns();
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()?
+            && key.kind != KeyEventKind::Release
+        {
+            let outcome = state.handle_key(key);
+            if outcome != KeyOutcome::Continue {
+                clear_lines(&mut out, drawn)?;
+                out.flush()?;
+                return Ok(outcome);
+            }
+        }
+    }
+}
+
+/// Move to the first of the last `count` drawn lines and clear everything below.
+fn clear_lines(out: &mut impl Write, count: usize) -> io::Result<()> {
+    if count == 0 {
+        return Ok(());
+    }
+    queue!(out, cursor::MoveToColumn(0))?;
+    if count > 1 {
+        queue!(
+            out,
+            cursor::MoveUp(u16::try_from(count - 1).unwrap_or(u16::MAX))
+        )?;
+    }
+    queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use std::path::PathBuf;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn project(kind: ProjectType, name: &str, size: u64) -> Project {
+        Project::new(
+            kind,
+            PathBuf::from("/work").join(name),
+            vec![BuildArtifacts {
+                // Missing directory: modification time falls back to the epoch
+                path: PathBuf::from("/nonexistent/target"),
+                size,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some(name.to_string()),
+        )
+    }
+
+    fn state_of(projects: &[Project], checked: &[bool]) -> SelectionState {
+        SelectionState::new(
+            projects
+                .iter()
+                .map(|p| SelectionItem::new(p, format!("project {}", p.total_size())))
+                .collect(),
+            checked.to_vec(),
+            &DisplayOptions::default(),
+        )
+    }
+
+    fn state(sizes: &[u64], checked: &[bool]) -> SelectionState {
+        let projects: Vec<_> = sizes
+            .iter()
+            .map(|&size| project(ProjectType::Rust, &format!("p{size}"), size))
+            .collect();
+        state_of(&projects, checked)
+    }
+
+    fn type_text(s: &mut SelectionState, text: &str) {
+        for c in text.chars() {
+            s.handle_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_preselect_rules() {
+        let small = project(ProjectType::Rust, "small", 10);
+        let large = project(ProjectType::Rust, "large", 2_000_000_000);
+
+        assert!(is_preselected(&small, Preselect::All));
+        assert!(!is_preselected(&small, Preselect::None));
+        assert!(is_preselected(&small, Preselect::Stale));
+        assert!(!is_preselected(&small, Preselect::Large));
+        assert!(is_preselected(&large, Preselect::Large));
+    }
+
+    #[test]
+    fn test_toggle_updates_running_total() {
+        let mut s = state(&[100, 200, 300], &[false, true, false]);
+        assert_eq!(s.selected_size(), 200);
+
+        assert_eq!(s.handle_key(key(KeyCode::Char(' '))), KeyOutcome::Continue);
+        assert_eq!(s.selected(), vec![0, 1]);
+        assert_eq!(s.selected_size(), 300);
+
+        s.handle_key(key(KeyCode::Down));
+        s.handle_key(key(KeyCode::Char(' ')));
+        assert_eq!(s.selected(), vec![0]);
+        assert!(s.header("Select").contains("1 of 3 selected, 100 B"));
+    }
+
+    #[test]
+    fn test_select_all_none_and_cursor_wrap() {
+        let mut s = state(&[1, 2, 3], &[false, false, false]);
+
+        s.handle_key(key(KeyCode::Right));
+        assert_eq!(s.selected(), vec![0, 1, 2]);
+        s.handle_key(key(KeyCode::Left));
+        assert!(s.selected().is_empty());
+
+        s.handle_key(key(KeyCode::Up));
+        s.handle_key(key(KeyCode::Char(' ')));
+        assert_eq!(s.selected(), vec![2]);
+    }
+
+    #[test]
+    fn test_submit_and_cancel() {
+        let mut s = state(&[1], &[true]);
+
+        assert_eq!(s.handle_key(key(KeyCode::Enter)), KeyOutcome::Submit);
+        assert_eq!(s.handle_key(key(KeyCode::Esc)), KeyOutcome::Cancel);
+        assert_eq!(
+            s.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            KeyOutcome::Cancel
+        );
+    }
+
+    #[test]
+    fn test_option_lines_follow_cursor() {
+        let sizes: Vec<u64> = (0..40).collect();
+        let mut s = state(&sizes, &[false; 40]);
+        s.handle_key(key(KeyCode::End));
+
+        let lines = s.option_lines(200);
+        assert_eq!(lines.len(), PAGE_SIZE);
+        assert!(lines[PAGE_SIZE - 1].contains("project 39"));
+        assert!(lines[0].contains("project 25"));
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("", "anything"));
+        assert!(fuzzy_match("api", "/work/my-api rust"));
+        assert!(fuzzy_match("MAR", "/work/my-api rust"));
+        assert!(!fuzzy_match("ipa", "/work/my-api"));
+    }
+
+    #[test]
+    fn test_filter_limits_visible_and_bulk_toggles() {
+        let projects = [
+            project(ProjectType::Rust, "web-api", 1),
+            project(ProjectType::Node, "web-ui", 2),
+            project(ProjectType::Rust, "cli", 4),
+        ];
+        let mut s = state_of(&projects, &[false; 3]);
+
+        s.handle_key(key(KeyCode::Char('/')));
+        type_text(&mut s, "web");
+        assert_eq!(s.visible, vec![0, 1]);
+        assert!(s.filter_line().is_some_and(|l| l.contains("/web")));
+
+        // 't' is part of the filter while typing it
+        s.handle_key(key(KeyCode::Char('t')));
+        assert_eq!(s.filter, "webt");
+        assert!(s.selected().is_empty());
+        s.handle_key(key(KeyCode::Backspace));
+        s.handle_key(key(KeyCode::Enter));
+
+        // Select all only affects the filtered projects
+        s.handle_key(key(KeyCode::Right));
+        assert_eq!(s.selected(), vec![0, 1]);
+
+        // Esc clears the filter before it cancels
+        assert_eq!(s.handle_key(key(KeyCode::Esc)), KeyOutcome::Continue);
+        assert_eq!(s.visible, vec![0, 1, 2]);
+        assert!(s.filter_line().is_none());
+    }
+
+    #[test]
+    fn test_filter_matches_type() {
+        let projects = [
+            project(ProjectType::Rust, "a", 1),
+            project(ProjectType::Node, "b", 2),
+        ];
+        let mut s = state_of(&projects, &[false; 2]);
+
+        s.handle_key(key(KeyCode::Char('/')));
+        type_text(&mut s, "node");
+        assert_eq!(s.visible, vec![1]);
+    }
+
+    #[test]
+    fn test_toggle_group_of_cursor_type() {
+        let projects = [
+            project(ProjectType::Rust, "a", 1),
+            project(ProjectType::Node, "b", 2),
+            project(ProjectType::Rust, "c", 4),
+        ];
+        let mut s = state_of(&projects, &[true, false, false]);
+
+        s.handle_key(key(KeyCode::Char('t')));
+        assert_eq!(s.selected(), vec![0, 2]);
+        s.handle_key(key(KeyCode::Char('t')));
+        assert!(s.selected().is_empty());
+
+        // Only projects shown by the filter are toggled
+        s.handle_key(key(KeyCode::Char('/')));
+        type_text(&mut s, "/work/c");
+        s.handle_key(key(KeyCode::Enter));
+        s.handle_key(key(KeyCode::Char('t')));
+        assert_eq!(s.selected(), vec![2]);
+    }
+
+    #[test]
+    fn test_details_are_measured_once_and_closed_by_any_key() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let target = tmp.path().join("app/target");
+        std::fs::create_dir_all(&target)?;
+        for i in 0..12 {
+            std::fs::write(target.join(format!("f{i:02}")), vec![0u8; (i + 1) * 10])?;
+        }
+        let mut app = project(ProjectType::Rust, "app", 780);
+        app.build_arts[0].path.clone_from(&target);
+        let mut s = state_of(&[app], &[true]);
+
+        assert!(!s.breakdown_pending());
+        s.handle_key(key(KeyCode::Char('d')));
+        assert!(s.breakdown_pending());
+        assert!(s.detail_lines(200).iter().any(|l| l.contains("measuring")));
+
+        s.load_breakdowns();
+        assert!(!s.breakdown_pending());
+        let lines = s.detail_lines(200);
+        assert_eq!(lines.len(), 2 + DETAIL_ENTRIES + 1);
+        assert!(lines[2].contains("f11"));
+        assert!(lines[lines.len() - 1].contains("...and 2 more"));
+
+        // Enter leaves the details instead of confirming
+        assert_eq!(s.handle_key(key(KeyCode::Enter)), KeyOutcome::Continue);
+        assert_eq!(s.help(), Message::SelectionHelp.text(Lang::En));
+        assert_eq!(s.selected(), vec![0]);
+
+        s.handle_key(key(KeyCode::Char('d')));
+        assert!(!s.breakdown_pending());
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+//! Scanning and cleaning other machines over SSH.
+//!
+//! `--host builder1` runs the tool on a remote machine instead of locally,
+//! using a copy of clean-dev-dirs installed there. The remote run is a dry
+//! run that reports its projects as JSON and records a cleanup plan (see
+//! [`crate::plan`]) on the remote machine. The reports of all hosts are shown
+//! together locally, and after confirmation each host applies its plan, so
+//! exactly the confirmed directories are removed.
+//!
+//! The local `ssh` executable is used, so host aliases, keys and agents from
+//! the SSH configuration apply. It runs in batch mode and never prompts for
+//! passwords. Remote commands are run by a POSIX shell.
+
+use std::process::Command;
+
+use crate::{
+    error::{CleanDevDirsError, Result, ResultExt},
+    output::JsonOutput,
+};
+
+/// Command that runs clean-dev-dirs on a remote machine unless `--remote-bin` is given.
+pub const DEFAULT_REMOTE_BIN: &str = "clean-dev-dirs";
+
+/// A remote machine to scan and clean.
+#[derive(Clone, Debug)]
+pub struct RemoteHost {
+    /// SSH destination: a host name, `user@host`, or an alias from the SSH config
+    pub host: String,
+
+    /// Command that runs clean-dev-dirs on the remote machine
+    ///
+    /// Passed to the remote shell unquoted, so `~/bin/clean-dev-dirs` works.
+    pub bin: String,
+
+    /// Arguments passed on to every remote run
+    pub args: Vec<String>,
+
+    /// Local SSH client executable
+    ssh: String,
+
+    /// Path of the plan file on the remote machine, relative to the remote home
+    plan: String,
+}
+
+impl RemoteHost {
+    /// Create a remote host that runs `bin` with `args`.
+    #[must_use]
+    pub fn new(host: &str, bin: &str, args: Vec<String>) -> Self {
+        Self {
+            host: host.to_string(),
+            bin: bin.to_string(),
+            args,
+            ssh: "ssh".to_string(),
+            plan: format!(".clean-dev-dirs-plan-{}.json", std::process::id()),
+        }
+    }
+
+    /// Scan the remote machine in a dry run, recording a plan of what would be cleaned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if SSH or the remote command fails, or the remote
+    /// output can't be parsed.
+    pub fn scan(&self) -> Result<JsonOutput> {
+        self.run_json(
+            &["--dry-run", "--json", "--plan-file", &self.plan],
+            CleanDevDirsError::scan,
+        )
+    }
+
+    /// Clean the directories planned by the last [`scan`](RemoteHost::scan).
+    ///
+    /// The remote plan is removed afterwards, whether cleaning succeeded or not.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if SSH or the remote command fails, or the remote
+    /// output can't be parsed.
+    pub fn apply(&self) -> Result<JsonOutput> {
+        let output = self.run_json(
+            &["--apply-plan", &self.plan, "--yes", "--json"],
+            CleanDevDirsError::cleanup,
+        );
+        self.discard_plan();
+        output
+    }
+
+    /// Remove the plan recorded by [`scan`](RemoteHost::scan) from the remote machine.
+    ///
+    /// Errors are ignored; a leftover plan is harmless.
+    pub fn discard_plan(&self) {
+        let _ = self
+            .ssh_command(&format!("rm -f {}", quote(&self.plan)))
+            .output();
+    }
+
+    /// Run the remote command with `extra` arguments and parse its JSON output.
+    ///
+    /// Failures are reported as errors of `stage`, the scan or the cleanup.
+    fn run_json(
+        &self,
+        extra: &[&str],
+        stage: fn(String) -> CleanDevDirsError,
+    ) -> Result<JsonOutput> {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(String::as_str)
+            .chain(extra.iter().copied())
+            .map(quote)
+            .collect();
+        let command = format!("{} {}", self.bin, args.join(" "));
+
+        let output = self
+            .ssh_command(&command)
+            .output()
+            .with_context(|| stage(format!("Failed to run {}", self.ssh)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(stage(format!(
+                "{} failed on {}: {}",
+                self.bin,
+                self.host,
+                stderr.trim()
+            )));
+        }
+
+        serde_json::from_slice(&output.stdout).with_context(|| {
+            stage(format!(
+                "Unexpected output from {} on {}",
+                self.bin, self.host
+            ))
+        })
+    }
+
+    /// Build the SSH command that runs `remote_command` on the host.
+    fn ssh_command(&self, remote_command: &str) -> Command {
+        let mut command = Command::new(&self.ssh);
+        command
+            .args(["-o", "BatchMode=yes", "--"])
+            .arg(&self.host)
+            .arg(remote_command);
+        command
+    }
+}
+
+/// Select the command-line arguments to pass on to the remote runs.
+///
+/// Drops the options that only apply locally (`--host`, `--remote-bin`) and
+/// the ones the remote runs set themselves (`--yes`, `--dry-run`).
+#[must_use]
+pub fn forwarded_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut forwarded = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--host" | "--remote-bin" => {
+                args.next();
+            }
+            "-y" | "--yes" | "--dry-run" => {}
+            _ if arg.starts_with("--host=") || arg.starts_with("--remote-bin=") => {}
+            _ => forwarded.push(arg),
+        }
+    }
+
+    forwarded
+}
+
+/// Quote a value so a POSIX shell treats it as a single argument.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DisplayOptions;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_forwarded_args() {
+        let args = strings(&[
+            "/srv/builds",
+            "--host",
+            "builder1",
+            "-y",
+            "--keep-days",
+            "30",
+            "--host=builder2",
+            "--remote-bin",
+            "~/bin/clean-dev-dirs",
+            "--dry-run",
+            "--permanent",
+        ]);
+
+        assert_eq!(
+            forwarded_args(args),
+            strings(&["/srv/builds", "--keep-days", "30", "--permanent"])
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("plain"), "'plain'");
+        assert_eq!(quote("it's"), "'it'\\''s'");
+        assert_eq!(quote("$(rm -rf ~)"), "'$(rm -rf ~)'");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_and_apply_through_ssh() -> anyhow::Result<()> {
+        use std::{fs, os::unix::fs::PermissionsExt, path::PathBuf};
+
+        use crate::{
+            cleaner::CleanResult,
+            project::{BuildArtifacts, Project, ProjectType},
+        };
+
+        let tmp = tempfile::TempDir::new()?;
+        let dir = tmp.path();
+        let project = Project::new(
+            ProjectType::Rust,
+            PathBuf::from("/srv/builds/app"),
+            vec![BuildArtifacts {
+                path: PathBuf::from("/srv/builds/app/target"),
+                size: 1_000,
+                files: 10,
+                dirs: 2,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some("app".to_string()),
+        );
+        let result = CleanResult {
+            success_count: 1,
+            total_freed: 1_000,
+            estimated_freed: 1_000,
+            verified_freed: None,
+            estimated_size: 1_000,
+            errors: Vec::new(),
+            skipped: Vec::new(),
+            trashed: Vec::new(),
+            permission_denied: Vec::new(),
+        };
+        fs::write(
+            dir.join("scan.json"),
+            serde_json::to_string(&JsonOutput::from_projects_dry_run(
+                std::slice::from_ref(&project),
+                &DisplayOptions::default(),
+            ))?,
+        )?;
+        fs::write(
+            dir.join("apply.json"),
+            serde_json::to_string(&JsonOutput::from_projects_cleanup(
+                &[project],
+                &result,
+                &DisplayOptions::default(),
+            ))?,
+        )?;
+
+        // Stands in for ssh: drops the options and host, then runs the command
+        let ssh = dir.join("ssh");
+        fs::write(&ssh, "#!/bin/sh\nshift 4\nexec sh -c \"$1\"\n")?;
+        // Stands in for the remote clean-dev-dirs: logs its arguments and
+        // prints the prepared output
+        let bin = dir.join("clean-dev-dirs");
+        fs::write(
+            &bin,
+            format!(
+                "#!/bin/sh\necho \"$@\" >> '{log}'\ncase \"$*\" in\n  *--apply-plan*) cat '{apply}' ;;\n  *) cat '{scan}' ;;\nesac\n",
+                log = dir.join("log").display(),
+                apply = dir.join("apply.json").display(),
+                scan = dir.join("scan.json").display(),
+            ),
+        )?;
+        for script in [&ssh, &bin] {
+            fs::set_permissions(script, fs::Permissions::from_mode(0o755))?;
+        }
+
+        let mut remote = RemoteHost::new(
+            "builder1",
+            &bin.display().to_string(),
+            strings(&["/srv/builds", "--name", "it's"]),
+        );
+        remote.ssh = ssh.display().to_string();
+
+        let scanned = remote.scan()?;
+        assert_eq!(scanned.summary.total_size, 1_000);
+        assert_eq!(scanned.projects[0].file_count, 10);
+
+        let applied = remote.apply()?;
+        assert_eq!(applied.cleanup.map(|c| c.success_count), Some(1));
+
+        let log = fs::read_to_string(dir.join("log"))?;
+        let plan = &remote.plan;
+        assert_eq!(
+            log,
+            format!(
+                "/srv/builds --name it's --dry-run --json --plan-file {plan}\n\
+                 /srv/builds --name it's --apply-plan {plan} --yes --json\n"
+            )
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_remote_failure_is_reported() {
+        let mut remote = RemoteHost::new("builder1", "clean-dev-dirs", Vec::new());
+        remote.ssh = "false".to_string();
+
+        let err = remote.scan().err();
+        assert!(err.is_some_and(|e| e.to_string().contains("failed on builder1")));
+    }
+}
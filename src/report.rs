@@ -0,0 +1,467 @@
+//! Self-contained HTML report of a scan or cleanup.
+//!
+//! `--report-html <FILE>` writes a single HTML file next to the normal
+//! output: the totals, bar charts of the reclaimable space and project count
+//! per project type, the volumes the build directories are on, and a table
+//! of every project that can be sorted by clicking a column header. Styles
+//! and the few lines of script for sorting are inline and nothing is loaded
+//! from the network, so the file can be mailed around or attached to a
+//! ticket and still open anywhere.
+
+use std::{fmt::Write as _, fs, path::Path};
+
+use chrono::Local;
+
+use crate::{
+    cleaner::CleanResult,
+    config::DisplayOptions,
+    error::{CleanDevDirsError, Result, ResultExt},
+    output::{JsonSummary, action, artifact_age_days, type_name},
+    project::Project,
+    utils::{SizeUnits, format_bytes},
+};
+
+/// Styles of the report.
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 72rem; padding: 0 1rem; color: #1f2328; }
+h1 { margin-bottom: 0.25rem; }
+.meta { color: #59636e; margin-top: 0; }
+.totals { display: flex; flex-wrap: wrap; gap: 1rem; margin: 1.5rem 0; }
+.total { border: 1px solid #d1d9e0; border-radius: 6px; padding: 0.75rem 1rem; min-width: 10rem; }
+.total .value { font-size: 1.5rem; font-weight: 600; }
+.total .label { color: #59636e; }
+.charts { display: flex; flex-wrap: wrap; gap: 2rem; }
+.chart { flex: 1 1 20rem; }
+.bar-row { display: grid; grid-template-columns: 6rem 1fr 7rem; gap: 0.5rem; align-items: center; margin: 0.25rem 0; }
+.bar-track { background: #eff2f5; border-radius: 3px; height: 1rem; }
+.bar { background: #2f81f7; border-radius: 3px; height: 100%; }
+.bar-value { text-align: right; font-variant-numeric: tabular-nums; }
+table { border-collapse: collapse; width: 100%; margin: 1rem 0 2rem; }
+th, td { border-bottom: 1px solid #d1d9e0; padding: 0.35rem 0.5rem; text-align: left; }
+th { background: #f6f8fa; cursor: pointer; user-select: none; white-space: nowrap; }
+th[aria-sort="ascending"]::after { content: " \25B2"; }
+th[aria-sort="descending"]::after { content: " \25BC"; }
+td.number, th.number { text-align: right; font-variant-numeric: tabular-nums; }
+td.path { font-family: ui-monospace, monospace; word-break: break-all; }
+tfoot td { font-weight: 600; }
+.failed { color: #cf222e; }
+"#;
+
+/// Sorts a table when one of its column headers is clicked. Cells sort by
+/// their `data-value` if they have one, and by their text otherwise.
+const SCRIPT: &str = r#"
+function sortTable(table, column, numeric) {
+  var header = table.tHead.rows[0].cells[column];
+  var ascending = header.getAttribute("aria-sort") !== "ascending";
+  Array.prototype.forEach.call(table.tHead.rows[0].cells, function (cell) {
+    cell.removeAttribute("aria-sort");
+  });
+  header.setAttribute("aria-sort", ascending ? "ascending" : "descending");
+  function key(row) {
+    var cell = row.cells[column];
+    var value = cell.hasAttribute("data-value") ? cell.getAttribute("data-value") : cell.textContent;
+    return numeric ? (value === "" ? -1 : Number(value)) : value.toLowerCase();
+  }
+  var body = table.tBodies[0];
+  var rows = Array.prototype.slice.call(body.rows);
+  rows.sort(function (a, b) {
+    var x = key(a), y = key(b);
+    var order = numeric ? x - y : x.localeCompare(y);
+    return ascending ? order : -order;
+  });
+  rows.forEach(function (row) { body.appendChild(row); });
+}
+document.querySelectorAll("table.sortable th").forEach(function (header) {
+  header.addEventListener("click", function () {
+    sortTable(header.closest("table"), header.cellIndex, header.classList.contains("number"));
+  });
+});
+"#;
+
+/// Render the HTML report for `projects`.
+///
+/// Pass `clean_result` after an actual cleanup; `None` renders a dry run.
+/// Sizes and risks are shown as `display` selects.
+#[must_use]
+pub fn render_html(
+    projects: &[Project],
+    clean_result: Option<&CleanResult>,
+    display: &DisplayOptions,
+) -> String {
+    let summary = JsonSummary::from_projects(projects, display.units);
+    let mode = if clean_result.is_some() {
+        "cleanup"
+    } else {
+        "dry run"
+    };
+
+    let mut out = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    out.push_str("<meta charset=\"utf-8\">\n");
+    out.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    out.push_str("<title>clean-dev-dirs report</title>\n");
+    let _ = writeln!(out, "<style>{STYLE}</style>\n</head>\n<body>");
+    out.push_str("<h1>Build directories</h1>\n");
+    let _ = writeln!(
+        out,
+        "<p class=\"meta\">{mode} on {} by clean-dev-dirs {}</p>",
+        Local::now().format("%Y-%m-%d %H:%M"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let roots = scan_roots(projects);
+    if !roots.is_empty() {
+        let _ = writeln!(out, "<p>Scanned: {}</p>", roots.join(", "));
+    }
+
+    render_totals(&mut out, &summary, clean_result, display.units);
+    if projects.is_empty() {
+        out.push_str("<p>No build directories were found.</p>\n");
+    } else {
+        render_charts(&mut out, &summary);
+        render_volumes(&mut out, &summary, display.units);
+        render_projects(&mut out, projects, clean_result, display);
+    }
+    if let Some(result) = clean_result.filter(|r| !r.errors.is_empty()) {
+        out.push_str("<h2>Errors</h2>\n<ul>\n");
+        for error in &result.errors {
+            let _ = writeln!(
+                out,
+                "<li class=\"failed\">{}</li>",
+                escape(&error.to_string())
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+
+    let _ = writeln!(out, "<script>{SCRIPT}</script>\n</body>\n</html>");
+    out
+}
+
+/// Write the HTML report for `projects` (and optional cleanup results) to
+/// `path`, for `--report-html`.
+///
+/// Like [`write_json_file`](crate::output::write_json_file), the report is
+/// written next to `path` first and then renamed over it.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be written.
+pub fn write_html_report(
+    path: &Path,
+    projects: &[Project],
+    clean_result: Option<&CleanResult>,
+    display: &DisplayOptions,
+) -> Result<()> {
+    let html = render_html(projects, clean_result, display);
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+
+    fs::write(&tmp, html).with_context(|| {
+        CleanDevDirsError::output(format!("Failed to write HTML report {}", path.display()))
+    })?;
+    fs::rename(&tmp, path).with_context(|| {
+        CleanDevDirsError::output(format!("Failed to write HTML report {}", path.display()))
+    })
+}
+
+/// The directories the projects were found under, escaped, in the order
+/// they first appear.
+fn scan_roots(projects: &[Project]) -> Vec<String> {
+    let mut roots: Vec<String> = Vec::new();
+    for root in projects.iter().filter_map(|p| p.scan_root.as_ref()) {
+        let root = escape(&root.display().to_string());
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// The boxes with the number of projects and the space they take up.
+fn render_totals(
+    out: &mut String,
+    summary: &JsonSummary,
+    clean_result: Option<&CleanResult>,
+    units: SizeUnits,
+) {
+    let reclaimable = if summary.size_estimated {
+        format!("at least {}", summary.total_size_formatted)
+    } else {
+        summary.total_size_formatted.clone()
+    };
+    let mut totals = vec![
+        (summary.total_projects.to_string(), "projects"),
+        (reclaimable, "reclaimable"),
+    ];
+    if let Some(result) = clean_result {
+        let freed = if result.trashed.is_empty() {
+            "freed"
+        } else {
+            "moved to the trash"
+        };
+        totals.push((format_bytes(result.total_freed, units), freed));
+        totals.push((result.errors.len().to_string(), "failed"));
+    }
+
+    out.push_str("<div class=\"totals\">\n");
+    for (value, label) in totals {
+        let _ = writeln!(
+            out,
+            "<div class=\"total\"><div class=\"value\">{value}</div><div class=\"label\">{label}</div></div>"
+        );
+    }
+    out.push_str("</div>\n");
+}
+
+/// Bar charts of the size and number of projects per project type, largest
+/// first.
+fn render_charts(out: &mut String, summary: &JsonSummary) {
+    let mut types: Vec<_> = summary.by_type.iter().collect();
+    types.sort_by(|(a_name, a), (b_name, b)| b.size.cmp(&a.size).then(a_name.cmp(b_name)));
+
+    let sizes: Vec<_> = types
+        .iter()
+        .map(|(name, t)| (name.as_str(), t.size, t.size_formatted.clone()))
+        .collect();
+    let counts: Vec<_> = types
+        .iter()
+        .map(|(name, t)| {
+            (
+                name.as_str(),
+                u64::try_from(t.count).unwrap_or(u64::MAX),
+                t.count.to_string(),
+            )
+        })
+        .collect();
+
+    out.push_str("<h2>By project type</h2>\n<div class=\"charts\">\n");
+    render_bar_chart(out, "Reclaimable space", &sizes);
+    render_bar_chart(out, "Projects", &counts);
+    out.push_str("</div>\n");
+}
+
+/// A bar chart with one bar per `(label, value, shown value)`, scaled to the
+/// largest value.
+fn render_bar_chart(out: &mut String, title: &str, bars: &[(&str, u64, String)]) {
+    let max = bars
+        .iter()
+        .map(|(_, value, _)| *value)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let _ = writeln!(out, "<div class=\"chart\">\n<h3>{title}</h3>");
+    for (label, value, shown) in bars {
+        let _ = writeln!(
+            out,
+            "<div class=\"bar-row\"><span>{}</span><div class=\"bar-track\"><div class=\"bar\" style=\"width: {}\"></div></div><span class=\"bar-value\">{}</span></div>",
+            escape(label),
+            percent(*value, max),
+            escape(shown)
+        );
+    }
+    out.push_str("</div>\n");
+}
+
+/// The volumes the build directories are on, with their free space.
+fn render_volumes(out: &mut String, summary: &JsonSummary, units: SizeUnits) {
+    if summary.volumes.is_empty() {
+        return;
+    }
+
+    out.push_str("<h2>Volumes</h2>\n<table class=\"sortable\">\n<thead><tr>");
+    out.push_str("<th>Volume</th><th class=\"number\">Build directories</th><th class=\"number\">Reclaimable</th><th class=\"number\">Free</th>");
+    out.push_str("</tr></thead>\n<tbody>\n");
+    for volume in &summary.volumes {
+        let free = volume
+            .free_space
+            .map_or_else(String::new, |free| format_bytes(free, units));
+        let _ = writeln!(
+            out,
+            "<tr><td class=\"path\">{}</td><td class=\"number\">{}</td><td class=\"number\" data-value=\"{}\">{}</td><td class=\"number\" data-value=\"{}\">{free}</td></tr>",
+            escape(&volume.mount_point),
+            volume.artifact_count,
+            volume.reclaimable,
+            volume.reclaimable_formatted,
+            volume
+                .free_space
+                .map_or_else(String::new, |f| f.to_string()),
+        );
+    }
+    out.push_str("</tbody>\n</table>\n");
+}
+
+/// The table of projects, with a totals row that stays at the bottom when
+/// the table is sorted.
+fn render_projects(
+    out: &mut String,
+    projects: &[Project],
+    clean_result: Option<&CleanResult>,
+    display: &DisplayOptions,
+) {
+    out.push_str("<h2>Projects</h2>\n<table class=\"sortable\">\n<thead><tr>");
+    out.push_str("<th>Path</th><th>Name</th><th>Type</th><th class=\"number\">Size</th><th class=\"number\">Files</th><th class=\"number\">Age (days)</th><th class=\"number\">Last commit (days)</th><th>Risk</th><th>Action</th>");
+    out.push_str("</tr></thead>\n<tbody>\n");
+
+    for project in projects {
+        let age = artifact_age_days(project).map_or_else(String::new, |d| d.to_string());
+        let commit_age = project
+            .last_commit_age_days()
+            .map_or_else(String::new, |d| d.to_string());
+        let action = action(project, clean_result);
+        let class = if action == "failed" {
+            " class=\"failed\""
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "<tr{class}><td class=\"path\">{}</td><td>{}</td><td>{}</td><td class=\"number\" data-value=\"{}\">{}</td><td class=\"number\">{}</td><td class=\"number\">{age}</td><td class=\"number\">{commit_age}</td><td>{}</td><td>{action}</td></tr>",
+            escape(&project.root_path.display().to_string()),
+            escape(project.name.as_deref().unwrap_or("")),
+            type_name(&project.kind),
+            project.total_size(),
+            project.formatted_size(display.units),
+            project.total_files(),
+            project.risk(&display.risk_overrides),
+        );
+    }
+
+    let total: u64 = projects.iter().map(Project::total_size).sum();
+    let files: u64 = projects.iter().map(Project::total_files).sum();
+    let _ = writeln!(
+        out,
+        "</tbody>\n<tfoot><tr><td>Total</td><td></td><td></td><td class=\"number\">{}</td><td class=\"number\">{files}</td><td></td><td></td><td></td><td></td></tr></tfoot>\n</table>",
+        display.bytes(total)
+    );
+}
+
+/// `value` as a percentage of `max`, with one decimal, for a CSS width.
+fn percent(value: u64, max: u64) -> String {
+    let permille = u128::from(value) * 1000 / u128::from(max.max(1));
+    format!("{}.{}%", permille / 10, permille % 10)
+}
+
+/// Escape the characters that are special in HTML text and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::CleanFailure;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use std::path::PathBuf;
+
+    fn project(kind: ProjectType, root: &str, artifact: &str, size: u64) -> Project {
+        let root = PathBuf::from(root);
+        Project::new(
+            kind,
+            root.clone(),
+            vec![BuildArtifacts {
+                path: root.join(artifact),
+                size,
+                files: 3,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            None,
+        )
+    }
+
+    #[test]
+    fn test_render_html() {
+        let mut projects = vec![
+            project(ProjectType::Rust, "/srv/build/<api>", "target", 4_000),
+            project(ProjectType::Node, "/srv/build/web", "node_modules", 1_000),
+            project(ProjectType::Rust, "/srv/build/cli", "target", 2_000),
+        ];
+        projects[0].name = Some("api & co".to_string());
+
+        let html = render_html(&projects, None, &DisplayOptions::default());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("dry run on"));
+        // Paths and names are escaped
+        assert!(html.contains("/srv/build/&lt;api&gt;"));
+        assert!(html.contains("api &amp; co"));
+        // The largest type has the full bar
+        assert!(html.contains(
+            "<span>rust</span><div class=\"bar-track\"><div class=\"bar\" style=\"width: 100.0%\">"
+        ));
+        assert!(html.contains(
+            "<span>node</span><div class=\"bar-track\"><div class=\"bar\" style=\"width: 16.6%\">"
+        ));
+        assert!(html.contains("data-value=\"4000\""));
+        // Nothing is loaded from elsewhere
+        assert!(!html.contains("src=") && !html.contains("href="));
+
+        let result = CleanResult {
+            success_count: 2,
+            total_freed: 6_000,
+            estimated_freed: 6_000,
+            verified_freed: None,
+            estimated_size: 7_000,
+            errors: vec![CleanFailure {
+                root: PathBuf::from("/srv/build/web"),
+                message: "busy".to_string(),
+            }],
+            skipped: Vec::new(),
+            trashed: Vec::new(),
+            permission_denied: Vec::new(),
+        };
+        let html = render_html(&projects, Some(&result), &DisplayOptions::default());
+        assert!(html.contains("cleanup on"));
+        assert!(html.contains("<tr class=\"failed\"><td class=\"path\">/srv/build/web</td>"));
+        assert!(html.contains("<li class=\"failed\">Failed to clean /srv/build/web: busy</li>"));
+
+        assert!(
+            render_html(&[], None, &DisplayOptions::default())
+                .contains("No build directories were found.")
+        );
+    }
+
+    #[test]
+    fn test_write_html_report() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let path = tmp.path().join("report.html");
+
+        write_html_report(
+            &path,
+            &[project(ProjectType::Go, "/srv/go", "vendor", 10)],
+            None,
+            &DisplayOptions::default(),
+        )?;
+        assert!(fs::read_to_string(&path)?.contains("/srv/go"));
+        assert!(!tmp.path().join("report.html.tmp").exists());
+        assert!(
+            write_html_report(
+                &tmp.path().join("missing/report.html"),
+                &[],
+                None,
+                &DisplayOptions::default()
+            )
+            .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(0, 0), "0.0%");
+        assert_eq!(percent(1, 3), "33.3%");
+        assert_eq!(percent(u64::MAX, u64::MAX), "100.0%");
+    }
+}
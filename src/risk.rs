@@ -0,0 +1,303 @@
+//! How risky it is to delete each kind of build directory.
+//!
+//! Not every build directory is equally disposable: `__pycache__/` is
+//! written again on the next import, `node_modules/` or a `.venv/` has to be
+//! reinstalled (and may come back with different versions), and a `vendor/`
+//! directory may hold dependencies that were patched by hand. Each build
+//! directory is rated by its path within the project, and `--max-risk`
+//! leaves out the ones rated above a level, so unattended runs can stick to
+//! the directories that are always safe to delete.
+//!
+//! The built-in ratings can be changed per project type (or for `all`) in
+//! the config file, keyed by the directory's path within the project or its
+//! name:
+//!
+//! ```toml
+//! [artifacts.go]
+//! risk = { vendor = "medium" }
+//! ```
+//!
+//! The binary reads them into [`RiskOverrides`] and passes them to the
+//! filters and the output along with the other options.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    path::{Component, Path},
+};
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::file::FileConfig, output::type_name, project::ProjectType};
+
+/// How much could be lost by deleting a build directory.
+///
+/// Variants are ordered from safest to riskiest, so they can be compared
+/// directly (`RiskLevel::High > RiskLevel::Low`).
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    Default,
+    ValueEnum,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    /// Regenerated by the next build without anything being downloaded
+    #[default]
+    Low,
+
+    /// Has to be reinstalled, which downloads dependencies again
+    Medium,
+
+    /// May hold changes that can't be recreated, such as patched dependencies
+    High,
+}
+
+impl Display for RiskLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// Risk levels set in the config file, keyed by project type name (or
+/// `all`) and then by directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RiskOverrides(BTreeMap<String, BTreeMap<String, RiskLevel>>);
+
+impl RiskOverrides {
+    /// The `risk` tables of the `[artifacts.<type>]` sections of the config.
+    ///
+    /// Levels other than `low`, `medium` and `high` are ignored.
+    #[must_use]
+    pub fn from_config(config: &FileConfig) -> Self {
+        Self(
+            config
+                .artifacts
+                .iter()
+                .map(|(kind, artifacts)| {
+                    let levels = artifacts
+                        .risk
+                        .iter()
+                        .flatten()
+                        .filter_map(|(dir, level)| {
+                            let level = RiskLevel::from_str(level, true).ok()?;
+                            Some((normalize_key(dir), level))
+                        })
+                        .collect();
+                    (kind.to_lowercase(), levels)
+                })
+                .collect(),
+        )
+    }
+
+    /// The level set for the directory `key` of a project of type `kind`,
+    /// falling back to the ones set for `all`.
+    fn get(&self, kind: &str, key: &str) -> Option<RiskLevel> {
+        [kind, "all"]
+            .into_iter()
+            .find_map(|kind| self.0.get(kind)?.get(key).copied())
+    }
+}
+
+/// The risk of deleting the build directory `artifact` of a `kind` project
+/// in `root`.
+///
+/// The directory is looked up by its path within the project (such as
+/// `vendor/bundle`), then by its name and then by the first component of its
+/// path, so a Rust target triple counts as `target` and a nested
+/// `__pycache__` as `__pycache__`. A level from `overrides` wins over the
+/// built-in one; directories that neither knows are [`RiskLevel::Medium`],
+/// since nothing says they are only build output.
+#[must_use]
+pub fn artifact_risk(
+    overrides: &RiskOverrides,
+    kind: &ProjectType,
+    root: &Path,
+    artifact: &Path,
+) -> RiskLevel {
+    let keys = artifact_keys(root, artifact);
+
+    let kind_name = type_name(kind);
+    keys.iter()
+        .find_map(|key| overrides.get(kind_name, key))
+        .or_else(|| keys.iter().find_map(|key| builtin_risk(kind, key)))
+        .unwrap_or(RiskLevel::Medium)
+}
+
+/// The keys the build directory `artifact` of the project in `root` is
+/// known by, in order: its path within the project, its name and the first
+/// component of that path.
+pub(crate) fn artifact_keys(root: &Path, artifact: &Path) -> Vec<String> {
+    let relative = artifact.strip_prefix(root).unwrap_or(artifact);
+    let components: Vec<String> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+
+    [
+        Some(components.join("/")),
+        components.last().cloned(),
+        components.first().cloned(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// The built-in rating of the directory `key` of a `kind` project, if it
+/// has one.
+fn builtin_risk(kind: &ProjectType, key: &str) -> Option<RiskLevel> {
+    match key {
+        // Go modules, Composer and Deno: vendored dependencies are sometimes
+        // patched in place, and the patches are gone once they're deleted
+        "vendor" => Some(RiskLevel::High),
+        // Installed executables that may be in use outside the project
+        "bin" if *kind == ProjectType::Go => Some(RiskLevel::Medium),
+        // Compiler output and caches, written again by the next build or
+        // test run without downloading anything
+        "target" | "build" | "obj" | "_build" | "__pycache__" | ".pytest_cache" | ".coverage"
+        | ".next" | ".nuxt" | ".svelte-kit" | "storybook-static" | ".gradle" | ".cxx"
+        | ".stack-work" | "dist-newstyle" | "zig-cache" | "zig-out" | "nimcache" | ".sccache"
+        | ".vs" | "Debug" | "Release" | "x64/Debug" | "x64/Release" | "ARM64/Debug"
+        | "ARM64/Release" | ".julia/compiled" | ".julia/logs" => Some(RiskLevel::Low),
+        "bin" if *kind == ProjectType::DotNet => Some(RiskLevel::Low),
+        key if key.ends_with(".egg-info") => Some(RiskLevel::Low),
+        key if *kind == ProjectType::Rust && key.starts_with("target-") => Some(RiskLevel::Low),
+        // Dependencies to download again, and `dist`, which is a common name
+        // for published or hand-written files as well
+        "dist" | "node_modules" | "venv" | ".venv" | ".tox" | ".eggs" | ".bundle"
+        | "vendor/bundle" | "lua_modules" | ".luarocks" | ".julia" | "deps/usr"
+        | "renv/library" | ".dart_tool" | ".build" | ".cargo/registry" | ".cargo/git" | "env"
+        | ".conda" => Some(RiskLevel::Medium),
+        _ => None,
+    }
+}
+
+/// A directory key from the config, with `/` separators and without a
+/// leading `./` or trailing `/`.
+pub(crate) fn normalize_key(key: &str) -> String {
+    let key = key.replace('\\', "/");
+    let key = key.strip_prefix("./").unwrap_or(&key);
+    key.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::file::FileArtifactConfig;
+
+    fn risk(overrides: &RiskOverrides, kind: &ProjectType, relative: &str) -> RiskLevel {
+        let root = Path::new("/work/app");
+        artifact_risk(overrides, kind, root, &root.join(relative))
+    }
+
+    #[test]
+    fn test_builtin_risk() {
+        let none = RiskOverrides::default();
+        let cases = [
+            (ProjectType::Python, "__pycache__", RiskLevel::Low),
+            (ProjectType::Python, "src/pkg/__pycache__", RiskLevel::Low),
+            (ProjectType::Python, ".venv", RiskLevel::Medium),
+            (ProjectType::Rust, "target", RiskLevel::Low),
+            (
+                ProjectType::Rust,
+                "target/x86_64-unknown-linux-gnu",
+                RiskLevel::Low,
+            ),
+            (ProjectType::Rust, ".cargo/registry", RiskLevel::Medium),
+            (
+                ProjectType::Node,
+                "apps/site/node_modules",
+                RiskLevel::Medium,
+            ),
+            (ProjectType::Node, "dist", RiskLevel::Medium),
+            (ProjectType::Julia, ".julia/compiled", RiskLevel::Low),
+            (ProjectType::Julia, "deps/usr", RiskLevel::Medium),
+            (ProjectType::Ruby, "vendor/bundle", RiskLevel::Medium),
+            (ProjectType::Go, "vendor", RiskLevel::High),
+            (ProjectType::Go, "bin", RiskLevel::Medium),
+            (ProjectType::DotNet, "bin", RiskLevel::Low),
+            (ProjectType::Cpp, "x64/Release", RiskLevel::Low),
+            (ProjectType::Python, "mypkg.egg-info", RiskLevel::Low),
+            (ProjectType::Rust, "target-xtask", RiskLevel::Low),
+            (ProjectType::Scala, "project/target", RiskLevel::Low),
+            (ProjectType::Android, "app/.cxx", RiskLevel::Low),
+            // Unknown directories, such as those of custom detectors or
+            // container caches, aren't assumed to be build output
+            (ProjectType::Rust, "generated", RiskLevel::Medium),
+            (ProjectType::Node, ".buildx-cache", RiskLevel::Medium),
+            (ProjectType::Python, ".ipynb_checkpoints", RiskLevel::Medium),
+        ];
+
+        for (kind, relative, level) in cases {
+            assert_eq!(risk(&none, &kind, relative), level, "{kind:?} {relative}");
+        }
+    }
+
+    #[test]
+    fn test_overrides_from_config() {
+        let mut config = FileConfig::default();
+        for (kind, levels) in [
+            ("go", vec![("vendor/", "medium")]),
+            ("all", vec![("./coverage", "HIGH"), ("node_modules", "low")]),
+            ("node", vec![("node_modules", "risky")]),
+        ] {
+            let risk = levels
+                .into_iter()
+                .map(|(dir, level)| (dir.to_string(), level.to_string()))
+                .collect();
+            config.artifacts.insert(
+                kind.to_string(),
+                FileArtifactConfig {
+                    risk: Some(risk),
+                    ..FileArtifactConfig::default()
+                },
+            );
+        }
+        let overrides = RiskOverrides::from_config(&config);
+
+        assert_eq!(
+            risk(&overrides, &ProjectType::Go, "vendor"),
+            RiskLevel::Medium
+        );
+        assert_eq!(
+            risk(&overrides, &ProjectType::Php, "vendor"),
+            RiskLevel::High
+        );
+        assert_eq!(
+            risk(&overrides, &ProjectType::Rust, "coverage"),
+            RiskLevel::High
+        );
+        // An invalid level for the type leaves the one for `all` in effect
+        assert_eq!(
+            risk(&overrides, &ProjectType::Node, "node_modules"),
+            RiskLevel::Low
+        );
+    }
+
+    #[test]
+    fn test_risk_level_order() {
+        assert!(RiskLevel::High > RiskLevel::Medium);
+        assert!(RiskLevel::Medium > RiskLevel::Low);
+        assert_eq!(RiskLevel::Medium.to_string(), "medium");
+    }
+}
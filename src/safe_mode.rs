@@ -0,0 +1,95 @@
+//! Safe mode for the first run.
+//!
+//! Someone trying clean-dev-dirs out may not expect the very first run to
+//! delete anything. Unless `--yes` or `--apply-plan` is given, the first
+//! run on a machine without a config file is therefore a dry run, which
+//! says how to clean for real. The run is then recorded in `first-run` in
+//! the [state directory](Paths::state_dir), so safe mode only applies
+//! once. The history and size snapshots written by earlier versions count
+//! as earlier runs too, so upgrading doesn't bring safe mode back.
+//!
+//! `safe_mode` in the `[execution]` table of the config file overrides the
+//! default: `false` turns safe mode off, and `true` keeps it on for the
+//! first run even though there is a config file.
+
+use std::{fs, path::Path};
+
+use chrono::Local;
+
+use crate::error::{CleanDevDirsError, Result, ResultExt};
+use crate::paths::Paths;
+
+/// State file recording when the first run was made.
+const MARKER: &str = "first-run";
+
+/// State files whose presence shows that the tool ran before, including
+/// those left by versions that didn't record the first run.
+const EARLIER_RUN_FILES: [&str; 3] = [MARKER, "history.jsonl", "sizes.json"];
+
+/// Whether safe mode is on for the first run: `safe_mode` from the config
+/// file if set, otherwise only if there is no config file.
+#[must_use]
+pub fn enabled(safe_mode: Option<bool>, has_config: bool) -> bool {
+    safe_mode.unwrap_or(!has_config)
+}
+
+/// Whether no earlier run left its state files behind.
+///
+/// Without a state directory, no run could ever be recorded, so this is
+/// `false` rather than every run being the first.
+#[must_use]
+pub fn is_first_run(paths: &Paths) -> bool {
+    paths.state_dir().is_some()
+        && !EARLIER_RUN_FILES
+            .iter()
+            .filter_map(|name| paths.state_file(name))
+            .any(|path| path.exists())
+}
+
+/// Record that the first run has been made.
+///
+/// # Errors
+///
+/// Returns an error if the state directory can't be determined or written.
+pub fn record_first_run(paths: &Paths) -> Result<()> {
+    let dir = paths
+        .state_dir()
+        .ok_or_else(|| CleanDevDirsError::config("Could not determine the state directory"))?;
+    record_in(&dir)
+}
+
+/// Write the first-run marker to `state_dir`.
+fn record_in(state_dir: &Path) -> Result<()> {
+    fs::create_dir_all(state_dir).with_context(|| {
+        CleanDevDirsError::config(format!("Failed to create {}", state_dir.display()))
+    })?;
+    let path = state_dir.join(MARKER);
+    fs::write(&path, format!("{}\n", Local::now().to_rfc3339()))
+        .with_context(|| CleanDevDirsError::config(format!("Failed to write {}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enabled() {
+        // Unset: only without a config file
+        assert!(enabled(None, false));
+        assert!(!enabled(None, true));
+        // Set: regardless of the config file
+        assert!(enabled(Some(true), true));
+        assert!(!enabled(Some(false), false));
+    }
+
+    #[test]
+    fn test_record_in_creates_the_state_directory() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let dir = tmp.path().join("state").join("clean-dev-dirs");
+
+        record_in(&dir)?;
+        let recorded = fs::read_to_string(dir.join(MARKER))?;
+        assert!(chrono::DateTime::parse_from_rfc3339(recorded.trim()).is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+//! Scan results kept in an `SQLite` database.
+//!
+//! With `record_scans` (or `--record-scans`), every scan appends what it
+//! found to `scans.db` in the [state directory](Paths::state_dir):
+//! one row in `runs`, one per project in `projects` and one per build
+//! directory in `artifacts` (see [`SCHEMA`]). Where `sizes.json` only keeps
+//! the latest size of each build directory, the database keeps every scan,
+//! so disk usage can be followed over time with `clean-dev-dirs query` or
+//! any `SQLite` client.
+//!
+//! The module needs the `scan-db` cargo feature (on by default), which
+//! builds `SQLite` into the binary.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use rusqlite::{Connection, OpenFlags, Statement, params, types::ValueRef};
+
+use crate::{
+    error::{CleanDevDirsError, Result, ResultExt},
+    filtering::artifact_modified,
+    output::type_name,
+    paths::Paths,
+    project::Project,
+};
+
+/// The tables of the scan database. Times are Unix timestamps in seconds and
+/// sizes are bytes.
+pub const SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS runs (
+    id          INTEGER PRIMARY KEY,
+    scanned_at  INTEGER NOT NULL,  -- when the scan finished
+    roots       TEXT NOT NULL      -- scanned directories, one per line
+);
+
+CREATE TABLE IF NOT EXISTS projects (
+    id          INTEGER PRIMARY KEY,
+    run_id      INTEGER NOT NULL REFERENCES runs(id) ON DELETE CASCADE,
+    kind        TEXT NOT NULL,     -- project type, e.g. 'rust' or 'node'
+    name        TEXT,
+    root        TEXT NOT NULL,     -- project directory
+    size        INTEGER NOT NULL,  -- total size of its build directories
+    modified    INTEGER,           -- last modification of the build directories
+    last_commit INTEGER            -- latest commit of its git repository
+);
+
+CREATE TABLE IF NOT EXISTS artifacts (
+    id          INTEGER PRIMARY KEY,
+    project_id  INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    path        TEXT NOT NULL,     -- build directory
+    size        INTEGER NOT NULL,
+    files       INTEGER NOT NULL,
+    dirs        INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS projects_by_run ON projects(run_id);
+CREATE INDEX IF NOT EXISTS projects_by_root ON projects(root);
+CREATE INDEX IF NOT EXISTS artifacts_by_project ON artifacts(project_id);
+";
+
+/// Returns the path of the scan database.
+///
+/// The file is `scans.db` in the [state directory](Paths::state_dir), or
+/// `None` if that cannot be determined.
+#[must_use]
+pub fn database_path(paths: &Paths) -> Option<PathBuf> {
+    paths.state_dir().map(|dir| dir.join("scans.db"))
+}
+
+/// Append a scan of `roots` that found `projects` to the database at `path`,
+/// creating it if needed.
+///
+/// Returns the id of the new row in `runs`.
+///
+/// # Errors
+///
+/// Returns an error if the database or its directory can't be written.
+pub fn record(
+    path: &Path,
+    roots: &[PathBuf],
+    projects: &[Project],
+    scanned_at: i64,
+) -> Result<i64> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            CleanDevDirsError::state(format!("Failed to create {}", parent.display()))
+        })?;
+    }
+
+    let mut db = open(path)?;
+
+    let roots = roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    insert_run(&mut db, &roots, projects, scanned_at).with_context(|| {
+        CleanDevDirsError::state(format!("Failed to write scan database {}", path.display()))
+    })
+}
+
+/// Open the database at `path` for writing, creating its tables if needed.
+///
+/// `SQLite` leaves foreign keys unenforced unless they're turned on for each
+/// connection, so they are, and deleting a run deletes its projects and
+/// their artifacts as the schema says.
+fn open(path: &Path) -> Result<Connection> {
+    let db = Connection::open(path).with_context(|| {
+        CleanDevDirsError::state(format!("Failed to open scan database {}", path.display()))
+    })?;
+    db.pragma_update(None, "foreign_keys", true)
+        .and_then(|()| db.execute_batch(SCHEMA))
+        .with_context(|| {
+            CleanDevDirsError::state(format!("Failed to set up scan database {}", path.display()))
+        })?;
+    Ok(db)
+}
+
+/// Insert a run over `roots`, one per line, with its `projects` in one
+/// transaction, returning the id of the run.
+fn insert_run(
+    db: &mut Connection,
+    roots: &str,
+    projects: &[Project],
+    scanned_at: i64,
+) -> rusqlite::Result<i64> {
+    let tx = db.transaction()?;
+    tx.execute(
+        "INSERT INTO runs (scanned_at, roots) VALUES (?1, ?2)",
+        params![scanned_at, roots],
+    )?;
+    let run_id = tx.last_insert_rowid();
+
+    {
+        let mut insert_project = tx.prepare(
+            "INSERT INTO projects (run_id, kind, name, root, size, modified, last_commit)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        let mut insert_artifact = tx.prepare(
+            "INSERT INTO artifacts (project_id, path, size, files, dirs)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+
+        for project in projects {
+            let modified = artifact_modified(project)
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .and_then(|age| i64::try_from(age.as_secs()).ok());
+            insert_project.execute(params![
+                run_id,
+                type_name(&project.kind),
+                project.name,
+                project.root_path.display().to_string(),
+                sql_int(project.total_size()),
+                modified,
+                project.last_commit,
+            ])?;
+            let project_id = tx.last_insert_rowid();
+
+            for artifact in &project.build_arts {
+                insert_artifact.execute(params![
+                    project_id,
+                    artifact.path.display().to_string(),
+                    sql_int(artifact.size),
+                    sql_int(artifact.files),
+                    sql_int(artifact.dirs),
+                ])?;
+            }
+        }
+    }
+
+    tx.commit()?;
+    Ok(run_id)
+}
+
+/// `value` as an `SQLite` integer, which is signed.
+fn sql_int(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+/// The result of a [`query`]: column names and rows of rendered values.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryResult {
+    /// Names of the result columns
+    pub columns: Vec<String>,
+
+    /// One entry per row and column; `None` for SQL `NULL`
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+/// Run the SQL statement `sql` against the database at `path`.
+///
+/// The database is opened read-only, so the statement can't change it.
+/// Blobs are rendered as their length, since nothing stored here is one.
+///
+/// # Errors
+///
+/// Returns an error if no scans were recorded at `path` yet, or if the
+/// statement is invalid or tries to write.
+pub fn query(path: &Path, sql: &str) -> Result<QueryResult> {
+    if !path.exists() {
+        return Err(CleanDevDirsError::state(format!(
+            "No scans recorded in {} yet; set record_scans = true under [scanning] \
+             or pass --record-scans",
+            path.display()
+        )));
+    }
+
+    let db =
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).with_context(|| {
+            CleanDevDirsError::state(format!("Failed to open scan database {}", path.display()))
+        })?;
+    let mut statement = db
+        .prepare(sql)
+        .context(CleanDevDirsError::state("Invalid query"))?;
+
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let rows = query_rows(&mut statement, columns.len())
+        .context(CleanDevDirsError::state("Failed to run the query"))?;
+
+    Ok(QueryResult { columns, rows })
+}
+
+/// Run `statement`, rendering the first `width` columns of each row.
+fn query_rows(
+    statement: &mut Statement<'_>,
+    width: usize,
+) -> rusqlite::Result<Vec<Vec<Option<String>>>> {
+    let mut rows = Vec::new();
+    let mut results = statement.query([])?;
+    while let Some(row) = results.next()? {
+        let values = (0..width)
+            .map(|index| row.get_ref(index).map(render))
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.push(values);
+    }
+    Ok(rows)
+}
+
+/// Render an `SQLite` value for printing.
+fn render(value: ValueRef<'_>) -> Option<String> {
+    match value {
+        ValueRef::Null => None,
+        ValueRef::Integer(n) => Some(n.to_string()),
+        ValueRef::Real(x) => Some(x.to_string()),
+        ValueRef::Text(text) => Some(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(blob) => Some(format!("<{} bytes>", blob.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use tempfile::TempDir;
+
+    fn project(root: &str, size: u64) -> Project {
+        let root = PathBuf::from(root);
+        Project::new(
+            ProjectType::Rust,
+            root.clone(),
+            vec![BuildArtifacts {
+                path: root.join("target"),
+                size,
+                files: 12,
+                dirs: 3,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some("app".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_record_and_query_scans() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("state").join("scans.db");
+        let roots = vec![PathBuf::from("/work")];
+
+        let first = record(&path, &roots, &[project("/work/app", 100)], 1_000)?;
+        let second = record(
+            &path,
+            &roots,
+            &[project("/work/app", 300), project("/work/lib", 50)],
+            2_000,
+        )?;
+        assert!(second > first);
+
+        let result = query(
+            &path,
+            "SELECT r.scanned_at, SUM(a.size), MAX(p.last_commit) AS last_commit
+             FROM runs r JOIN projects p ON p.run_id = r.id JOIN artifacts a ON a.project_id = p.id
+             GROUP BY r.id ORDER BY r.id",
+        )?;
+        assert_eq!(result.columns[2], "last_commit");
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![Some("1000".to_string()), Some("100".to_string()), None],
+                vec![Some("2000".to_string()), Some("350".to_string()), None],
+            ]
+        );
+
+        let kinds = query(&path, "SELECT DISTINCT kind FROM projects")?;
+        assert_eq!(kinds.rows, vec![vec![Some("rust".to_string())]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deleting_a_run_cascades() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("scans.db");
+        let run = record(&path, &[], &[project("/work/app", 100)], 1_000)?;
+
+        let db = open(&path)?;
+        db.execute("DELETE FROM runs WHERE id = ?1", params![run])?;
+        let count = |table: &str| -> rusqlite::Result<i64> {
+            db.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+        };
+        assert_eq!(count("projects")?, 0);
+        assert_eq!(count("artifacts")?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_is_read_only() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("scans.db");
+        assert!(query(&path, "SELECT 1").is_err());
+
+        record(&path, &[], &[project("/work/app", 100)], 1_000)?;
+        assert!(query(&path, "DELETE FROM runs").is_err());
+        assert_eq!(
+            query(&path, "SELECT COUNT(*) FROM runs")?.rows[0][0].as_deref(),
+            Some("1")
+        );
+        Ok(())
+    }
+}
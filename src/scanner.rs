@@ -4,27 +4,86 @@
 //! to find development projects and their build artifacts. It supports parallel
 //! processing for improved performance and handles various error conditions
 //! gracefully.
+//!
+//! # Memory use
+//!
+//! Scans are built to cope with trees of millions of directories, such as a
+//! NAS full of checkouts. The directory walk runs on its own thread and
+//! streams entries to the detectors through a bounded queue, so at most
+//! [`ENTRY_QUEUE_CAPACITY`] directory entries are waiting at any time rather
+//! than the whole tree. Errors are counted, and only the first
+//! [`ScanErrors::VERBOSE_LIMIT`] are kept, and only in verbose mode. Peak
+//! memory therefore grows with the number of projects found and the depth of
+//! the tree, not with the number of directories walked.
 
 use std::{
+    collections::{HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
     sync::{
-        Arc, Mutex,
         atomic::{AtomicUsize, Ordering},
+        mpsc,
     },
+    thread,
 };
 
+use clap::ValueEnum;
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use serde_json::{Value, from_str};
 use walkdir::{DirEntry, WalkDir};
 
 use crate::{
-    config::{ProjectFilter, ScanOptions},
-    project::{BuildArtifacts, Project, ProjectType},
+    config::{
+        AgeSource, FilterOptions, FollowSymlinks, LocalConfig, ProjectFilter, RustSubdir,
+        ScanOptions, TypeFilter, local::resolve_within,
+    },
+    detector::{
+        DETECTORS, DetectContext, Detector, has_project_marker, is_container_cache, marker_trace,
+        target_subdirs,
+    },
+    error::{CleanDevDirsError, Result, ScanErrors},
+    filtering::filter_reasons,
+    progress::ProgressMode,
+    project::{BuildArtifacts, Project, ProjectType, SubdirSize},
+    risk::{artifact_keys, normalize_key},
+    utils::DirStats,
+    why::{DetectorOutcome, DetectorTrace, PathTrace, Reason, TracedProject},
 };
 
+/// How many walked directory entries may wait for detection at a time.
+///
+/// The directory walk pauses when the detectors fall this far behind.
+pub const ENTRY_QUEUE_CAPACITY: usize = 1024;
+
+/// Names of directories that only ever hold version control data, caches,
+/// virtual environments or installed dependencies, never a project.
+const NEVER_PROJECT_DIRS: [&str; 17] = [
+    ".git",
+    ".svn",
+    ".hg",
+    "__pycache__",
+    ".venv",
+    ".env",
+    ".pytest_cache",
+    ".tox",
+    ".eggs",
+    ".coverage",
+    "node_modules",
+    "zig-cache",
+    "dist-newstyle",
+    "lua_modules",
+    ".luarocks",
+    "nimcache",
+    ".julia",
+];
+
+/// Names that build, dependency and temporary directories usually have,
+/// but that a repository of its own may have as well.
+const GENERIC_BUILD_DIRS: [&str; 13] = [
+    "target", "build", "dist", "out", "venv", "env", "temp", "tmp", "vendor", "obj", "_build",
+    "zig-out", "renv",
+];
+
 /// Directory scanner for detecting development projects.
 ///
 /// The `Scanner` struct encapsulates the logic for traversing directory trees
@@ -37,10 +96,10 @@ pub struct Scanner {
     scan_options: ScanOptions,
 
     /// Filter to restrict scanning to specific project types
-    project_filter: ProjectFilter,
+    type_filter: TypeFilter,
 
-    /// When `true`, suppresses progress spinner output (used by `--json` mode).
-    quiet: bool,
+    /// How scanning progress is reported (hidden in `--json` mode).
+    progress: ProgressMode,
 }
 
 impl Scanner {
@@ -49,7 +108,8 @@ impl Scanner {
     /// # Arguments
     ///
     /// * `scan_options` - Configuration for scanning behavior (threads, verbosity, etc.)
-    /// * `project_filter` - Filter to restrict scanning to specific project types
+    /// * `type_filter` - Project types to scan for: a single [`ProjectFilter`]
+    ///   or a [`TypeFilter`] including and excluding several
     ///
     /// # Returns
     ///
@@ -68,11 +128,11 @@ impl Scanner {
     /// let scanner = Scanner::new(scan_options, ProjectFilter::All);
     /// ```
     #[must_use]
-    pub const fn new(scan_options: ScanOptions, project_filter: ProjectFilter) -> Self {
+    pub fn new(scan_options: ScanOptions, type_filter: impl Into<TypeFilter>) -> Self {
         Self {
             scan_options,
-            project_filter,
-            quiet: false,
+            type_filter: type_filter.into(),
+            progress: ProgressMode::Bar,
         }
     }
 
@@ -82,7 +142,21 @@ impl Scanner {
     /// required for `--json` output so that only the final JSON is printed.
     #[must_use]
     pub const fn with_quiet(mut self, quiet: bool) -> Self {
-        self.quiet = quiet;
+        if quiet {
+            self.progress = ProgressMode::Hidden;
+        } else if matches!(self.progress, ProgressMode::Hidden) {
+            self.progress = ProgressMode::Bar;
+        }
+        self
+    }
+
+    /// Set how scanning progress is reported.
+    ///
+    /// [`ProgressMode::Plain`] replaces the spinner with periodic plain status
+    /// lines, for CI logs.
+    #[must_use]
+    pub const fn with_progress(mut self, progress: ProgressMode) -> Self {
+        self.progress = progress;
         self
     }
 
@@ -122,58 +196,389 @@ impl Scanner {
     /// This method uses parallel processing for both directory traversal and
     /// size calculation to maximize performance on systems with multiple cores
     /// and fast storage.
+    #[must_use]
     pub fn scan_directory(&self, root: &Path) -> Vec<Project> {
-        let errors = Arc::new(Mutex::new(Vec::<String>::new()));
+        let errors = self.new_errors();
+        let projects = self.find_projects(root, &errors);
+        self.finish_projects(projects, &errors)
+    }
 
-        let progress = if self.quiet {
-            ProgressBar::hidden()
+    /// Collect errors for a scan, keeping them for reporting in verbose mode.
+    const fn new_errors(&self) -> ScanErrors {
+        ScanErrors::new(if self.scan_options.verbose {
+            ScanErrors::VERBOSE_LIMIT
         } else {
-            let pb = ProgressBar::new_spinner();
-            if let Ok(style) = ProgressStyle::default_spinner().template("{spinner:.green} {msg}") {
-                pb.set_style(style);
-            }
-            pb.set_message("Scanning...");
-            pb.enable_steady_tick(std::time::Duration::from_millis(100));
-            pb
-        };
+            0
+        })
+    }
 
-        let found_count = Arc::new(AtomicUsize::new(0));
-        let progress_clone = progress.clone();
-        let count_clone = Arc::clone(&found_count);
+    /// Walk `root` and detect the projects in it, without measuring them.
+    ///
+    /// The walk runs on its own thread and hands the entries over through a
+    /// queue of [`ENTRY_QUEUE_CAPACITY`] entries, so the tree is never held in
+    /// memory. The projects are returned in walk order.
+    ///
+    /// Symbolic links are followed as set by
+    /// [`follow_symlinks`](ScanOptions::follow_symlinks). When all of them
+    /// are, each directory is walked once, however many links lead to it.
+    fn find_projects(&self, root: &Path, errors: &ScanErrors) -> Vec<Project> {
+        let progress = self.progress.spinner("Scanning...");
+        let found_count = AtomicUsize::new(0);
+
+        let follow = self.scan_options.follow_symlinks;
+        let walker = WalkDir::new(root)
+            .follow_root_links(follow != FollowSymlinks::Never)
+            .follow_links(follow == FollowSymlinks::Always)
+            .max_depth(self.scan_options.max_depth.unwrap_or(usize::MAX));
+        let (sender, entries) = mpsc::sync_channel(ENTRY_QUEUE_CAPACITY);
+
+        let mut found: Vec<(usize, Vec<Project>)> = thread::scope(|scope| {
+            scope.spawn(move || {
+                let mut visited = HashSet::new();
+                let walk = walker.into_iter().filter_entry(|entry| {
+                    (entry.depth() == 0 || !self.is_ignored(entry.path()))
+                        && (follow != FollowSymlinks::Always || first_visit(&mut visited, entry))
+                });
+                let scannable = walk
+                    .filter_map(|entry| entry.map_err(|e| errors.record(walk_error(e))).ok())
+                    .filter(|entry| self.should_scan_entry(entry));
+                for indexed in scannable.enumerate() {
+                    // The receiver only goes away once detection is done
+                    if sender.send(indexed).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            entries
+                .into_iter()
+                .par_bridge()
+                .filter_map(|(index, entry)| {
+                    let mut projects = self.detect_projects(&entry, errors);
+                    if projects.is_empty() {
+                        return None;
+                    }
+                    Self::set_scan_root(&mut projects, root);
+                    let n =
+                        found_count.fetch_add(projects.len(), Ordering::Relaxed) + projects.len();
+                    progress.set_message(format!("Scanning... {n} found"));
+                    Some((index, projects))
+                })
+                .collect()
+        });
 
-        // Find all potential project directories
-        let walker = self.scan_options.max_depth.map_or_else(
-            || WalkDir::new(root),
-            |depth| WalkDir::new(root).max_depth(depth),
-        );
+        progress.finish_with_message("[OK] Directory scan complete");
 
-        let potential_projects: Vec<_> = walker
+        found.sort_unstable_by_key(|&(index, _)| index);
+        found
             .into_iter()
-            .filter_map(Result::ok)
-            .filter(|entry| self.should_scan_entry(entry))
-            .collect::<Vec<_>>()
-            .into_par_iter()
-            .filter_map(|entry| {
-                let result = self.detect_project(&entry, &errors);
-                if result.is_some() {
-                    let n = count_clone.fetch_add(1, Ordering::Relaxed) + 1;
-                    progress_clone.set_message(format!("Scanning... {n} found"));
-                }
-                result
+            .flat_map(|(_, projects)| projects)
+            .collect()
+    }
+
+    /// Detect projects in exactly the given directories, without walking below them.
+    ///
+    /// Used for `--stdin`, where another tool (such as `fd`) already found the
+    /// candidates. Each path is checked for a project the same way a scanned
+    /// directory is. A path that is itself a build directory, such as a
+    /// `node_modules/` or `target/`, stands for the project of its parent
+    /// directory that owns it. Paths that don't exist or hold no project are
+    /// left out, and each project is reported once, even when listed through
+    /// different paths.
+    #[must_use]
+    pub fn scan_roots(&self, roots: &[PathBuf]) -> Vec<Project> {
+        let errors = self.new_errors();
+
+        let potential_projects: Vec<_> = roots
+            .par_iter()
+            .flat_map_iter(|root| {
+                let mut projects = self.detect_at(root, &errors);
+                Self::set_scan_root(&mut projects, root);
+                projects
             })
             .collect();
 
-        progress.finish_with_message("[OK] Directory scan complete");
+        self.finish_projects(dedup_projects(potential_projects), &errors)
+    }
+
+    /// Detect the projects at `path`, or the project of its parent that owns
+    /// `path` as a build directory.
+    fn detect_at(&self, path: &Path, errors: &ScanErrors) -> Vec<Project> {
+        let entry_of = |path: &Path| WalkDir::new(path).max_depth(0).into_iter().next()?.ok();
+
+        let Some(entry) = entry_of(path) else {
+            errors.record(CleanDevDirsError::scan(format!(
+                "Cannot read {}",
+                path.display()
+            )));
+            return Vec::new();
+        };
+
+        let projects = self.detect_projects(&entry, errors);
+        if !projects.is_empty() {
+            return projects;
+        }
+
+        path.parent()
+            .and_then(entry_of)
+            .map(|parent| self.detect_projects(&parent, errors))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|project| project.build_arts.iter().any(|a| a.path == path))
+            .collect()
+    }
+
+    /// Trace how a scan of `roots` treats `path`, for `--why`.
+    ///
+    /// Goes through the steps of a scan for this one directory: whether the
+    /// walk reaches it and checks it for projects, what each detector makes
+    /// of it and, for the projects detected, which of `filter_opts` leave
+    /// them out. A `path` that is a build directory, such as a
+    /// `node_modules/`, is traced through the project directory that owns it,
+    /// like with [`scan_roots`](Scanner::scan_roots).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` can't be read, or if a size or pattern in
+    /// `filter_opts` is invalid.
+    pub fn trace(
+        &self,
+        path: &Path,
+        roots: &[PathBuf],
+        filter_opts: &FilterOptions,
+    ) -> Result<PathTrace> {
+        let errors = self.new_errors();
+        let entry_of = |path: &Path| WalkDir::new(path).max_depth(0).into_iter().next()?.ok();
+
+        let path = canonical_path(path);
+        let mut traced = entry_of(&path)
+            .ok_or_else(|| CleanDevDirsError::scan(format!("Cannot read {}", path.display())))?;
+        let mut projects = self.detect_projects(&traced, &errors);
+        let mut build_dir_of = None;
+        if projects.is_empty()
+            && let Some(parent) = path.parent().and_then(entry_of)
+        {
+            let owners: Vec<Project> = self
+                .detect_projects(&parent, &errors)
+                .into_iter()
+                .filter(|project| project.build_arts.iter().any(|a| a.path == path))
+                .collect();
+            if !owners.is_empty() {
+                build_dir_of = Some(parent.path().to_path_buf());
+                traced = parent;
+                projects = owners;
+            }
+        }
+
+        let (scan_root, pruned_by) = self.trace_walk(traced.path(), roots);
+        let detectors = self.trace_detectors(traced.path(), &errors);
+        let projects = projects
+            .into_iter()
+            .filter(|project| self.includes_kind(&project.kind))
+            .map(|project| {
+                let Some(measured) = self.finish_projects(vec![project.clone()], &errors).pop()
+                else {
+                    let empty = Reason::new("empty", "its build directories are empty");
+                    return Ok(TracedProject::new(&project, vec![empty]));
+                };
+                Ok(TracedProject::new(
+                    &measured,
+                    filter_reasons(&measured, filter_opts)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let cleaned = pruned_by.is_empty()
+            && projects
+                .iter()
+                .any(|project| project.filtered_by.is_empty());
+        Ok(PathTrace {
+            path,
+            build_dir_of,
+            scan_root,
+            pruned_by,
+            detectors,
+            projects,
+            cleaned,
+        })
+    }
+
+    /// Find the scan root `dir` lies in, and the rules that keep the walk
+    /// from checking `dir` for projects.
+    ///
+    /// `dir` has its symlinks resolved; the roots are resolved to compare them.
+    fn trace_walk(&self, dir: &Path, roots: &[PathBuf]) -> (Option<PathBuf>, Vec<Reason>) {
+        let Some((root, canonical_root)) = roots
+            .iter()
+            .map(|root| (root, canonical_path(root)))
+            .find(|(_, canonical)| dir.starts_with(canonical))
+        else {
+            let roots: Vec<String> = roots.iter().map(|r| r.display().to_string()).collect();
+            let outside = Reason::new(
+                "dirs",
+                format!("not inside the scanned directories ({})", roots.join(", ")),
+            );
+            return (None, vec![outside]);
+        };
+
+        let mut reasons = Vec::new();
+        let depth = dir
+            .strip_prefix(&canonical_root)
+            .map_or(0, |relative| relative.components().count());
+        if let Some(max_depth) = self.scan_options.max_depth
+            && depth > max_depth
+        {
+            reasons.push(Reason::new(
+                "max_depth",
+                format!(
+                    "{depth} levels below {}, deeper than the maximum depth of {max_depth}",
+                    root.display()
+                ),
+            ));
+        }
+
+        let walked_ancestors = dir.ancestors().skip(1).take_while(|a| *a != canonical_root);
+        for ancestor in walked_ancestors {
+            if self.is_ignored(ancestor) {
+                reasons.push(Reason::new(
+                    "ignore",
+                    format!("inside {}, which is ignored", ancestor.display()),
+                ));
+            }
+        }
+        if depth > 0 && self.is_ignored(dir) {
+            reasons.push(Reason::new("ignore", "its name is in the ignore list"));
+        }
+
+        reasons.extend(self.scan_entry_reasons(dir));
+        (Some(root.clone()), reasons)
+    }
+
+    /// The rules of [`should_scan_entry`](Scanner::should_scan_entry) that
+    /// `path` fails.
+    fn scan_entry_reasons(&self, path: &Path) -> Vec<Reason> {
+        let mut reasons = Vec::new();
+        if self.is_path_in_skip_list(path) {
+            reasons.push(Reason::new(
+                "skip",
+                "its name is in the skip list (directories below it are still scanned)",
+            ));
+        }
+        if is_inside_node_modules(path) {
+            reasons.push(Reason::new(
+                "node_modules",
+                "inside a node_modules directory, which is never scanned",
+            ));
+        }
+        if self.is_hidden_directory_to_skip(path) {
+            reasons.push(Reason::new(
+                "scan_hidden",
+                "hidden directory; scanned with scan_hidden or when listed in allow_hidden",
+            ));
+        }
+        if self.is_excluded(path) {
+            let detail = if Self::name_in_list(path, &self.scan_options.exclude_names) {
+                "its name is in exclude_names"
+            } else if Self::name_is_one_of(path, &NEVER_PROJECT_DIRS) {
+                "a directory that never holds a project; scanned when listed in no_exclude_names"
+            } else {
+                "a build directory of the project around it; scanned when listed in \
+                 no_exclude_names"
+            };
+            reasons.push(Reason::new("exclude_names", detail));
+        }
+        reasons
+    }
+
+    /// Run every detector on `path` and record what each one found.
+    ///
+    /// Build directories go to the first detector that reports them, as in
+    /// [`detect_projects`](Scanner::detect_projects), even when the type
+    /// filter then leaves the project out.
+    fn trace_detectors(&self, path: &Path, errors: &ScanErrors) -> Vec<DetectorTrace> {
+        let ctx = DetectContext::new(&self.scan_options, errors);
+        let mut claimed = HashSet::new();
+
+        DETECTORS
+            .iter()
+            .map(|&detector| {
+                let (markers_found, markers_missing) = marker_trace(path, detector.markers());
+                let mut artifacts = Vec::new();
+                let included = self.type_filter.includes(detector.name());
+                let outcome = if let Some(project) = self.try_detect(detector, path, &ctx) {
+                    artifacts = project
+                        .build_arts
+                        .into_iter()
+                        .map(|artifact| artifact.path)
+                        .filter(|artifact| claimed.insert(artifact.clone()))
+                        .collect();
+                    if !included {
+                        artifacts.clear();
+                        DetectorOutcome::Skipped
+                    } else if artifacts.is_empty() {
+                        DetectorOutcome::Claimed
+                    } else {
+                        DetectorOutcome::Detected
+                    }
+                } else if !included {
+                    DetectorOutcome::Skipped
+                } else if markers_found.is_empty() {
+                    DetectorOutcome::NoMarkers
+                } else {
+                    DetectorOutcome::NothingToClean
+                };
+
+                DetectorTrace {
+                    detector: detector.name(),
+                    outcome,
+                    markers_found,
+                    markers_missing,
+                    artifacts,
+                }
+            })
+            .collect()
+    }
 
-        // Process projects in parallel to calculate sizes
+    /// Size the detected projects and drop those without any build artifacts.
+    ///
+    /// Also collapses Node.js workspaces and Android modules, leaves the
+    /// contents of container caches to the project they belong to, drops
+    /// the projects of types the type filter leaves out and, in verbose
+    /// mode, prints the errors collected while scanning.
+    fn finish_projects(
+        &self,
+        potential_projects: Vec<Project>,
+        errors: &ScanErrors,
+    ) -> Vec<Project> {
+        let potential_projects = Self::merge_node_workspaces(potential_projects);
+        let potential_projects = Self::merge_android_modules(potential_projects);
+        let mut potential_projects = self.merge_container_caches(potential_projects);
+        potential_projects.retain(|project| self.includes_kind(&project.kind));
+
+        // Process projects in parallel to calculate sizes, file counts and ages
         let projects_with_sizes: Vec<_> = potential_projects
             .into_par_iter()
             .filter_map(|mut project| {
                 for artifact in &mut project.build_arts {
                     if artifact.size == 0 {
-                        artifact.size = Self::calculate_build_dir_size(&artifact.path);
+                        let stats = self.collect_build_dir_stats(&artifact.path);
+                        artifact.size = stats.size;
+                        artifact.files = stats.files;
+                        artifact.dirs = stats.dirs;
+                        artifact.newest_file = stats.newest_file;
+                        artifact.estimated = stats.estimated;
                     }
                 }
+                if self.scan_options.age_source == AgeSource::NewestFile {
+                    project.newest_file = project
+                        .build_arts
+                        .iter()
+                        .filter_map(|artifact| artifact.newest_file)
+                        .max();
+                }
+                if self.scan_options.verbose && project.kind == ProjectType::Rust {
+                    project.target_breakdown = self.target_breakdown(&project);
+                }
 
                 if project.total_size() > 0 {
                     Some(project)
@@ -183,22 +588,50 @@ impl Scanner {
             })
             .collect();
 
-        // Print errors if verbose
-        if self.scan_options.verbose
-            && let Ok(errors) = errors.lock()
-        {
-            for error in errors.iter() {
-                eprintln!("{}", error.red());
-            }
+        if self.scan_options.verbose {
+            Self::print_errors(errors);
         }
 
         projects_with_sizes
     }
 
+    /// Measure the well-known subdirectories of the target directories of the
+    /// Rust project `project`, for the verbose listing.
+    fn target_breakdown(&self, project: &Project) -> Vec<SubdirSize> {
+        project
+            .build_arts
+            .iter()
+            .filter(|artifact| is_in_target_dir(&project.root_path, &artifact.path))
+            .flat_map(|artifact| target_subdirs(&artifact.path, RustSubdir::value_variants()))
+            .map(|(kind, path)| SubdirSize {
+                kind,
+                size: self.collect_build_dir_stats(&path).size,
+                path,
+            })
+            .collect()
+    }
+
+    /// Print the kept scan errors, and how many more there were.
+    fn print_errors(errors: &ScanErrors) {
+        let kept = errors.take_kept();
+        for error in &kept {
+            eprintln!("{}", format!("{error:#}").red());
+        }
+
+        let more = errors.count().saturating_sub(kept.len());
+        if more > 0 {
+            eprintln!("{}", format!("... and {more} more errors").red());
+        }
+    }
+
     /// Scan multiple root directories and return a deduplicated list of projects.
     ///
-    /// Calls [`scan_directory`](Scanner::scan_directory) for each root and merges
-    /// the results, skipping any project whose `root_path` was already seen.
+    /// Walks each root like [`scan_directory`](Scanner::scan_directory) and
+    /// merges the results before measuring them. Roots that resolve to the same
+    /// directory are walked once, and a project reachable from several roots,
+    /// because one root contains another or through a symlink, is kept once
+    /// with the first root that found it as its
+    /// [`scan_root`](Project::scan_root).
     ///
     /// # Arguments
     ///
@@ -209,24 +642,31 @@ impl Scanner {
     /// A `Vec<Project>` containing all unique projects found across all roots.
     #[must_use]
     pub fn scan_directories(&self, roots: &[PathBuf]) -> Vec<Project> {
-        use std::collections::HashSet;
-        let mut seen: HashSet<PathBuf> = HashSet::new();
-        let mut result = Vec::new();
-        for root in roots {
-            for project in self.scan_directory(root) {
-                if seen.insert(project.root_path.clone()) {
-                    result.push(project);
-                }
-            }
+        let errors = self.new_errors();
+
+        let mut seen_roots: HashSet<PathBuf> = HashSet::new();
+        let potential_projects: Vec<_> = roots
+            .iter()
+            .filter(|root| seen_roots.insert(canonical_path(root)))
+            .flat_map(|root| self.find_projects(root, &errors))
+            .collect();
+
+        self.finish_projects(dedup_projects(potential_projects), &errors)
+    }
+
+    /// Record `root` as the scan root of `projects`.
+    fn set_scan_root(projects: &mut [Project], root: &Path) {
+        for project in projects {
+            project.scan_root = Some(root.to_path_buf());
         }
-        result
     }
 
-    /// Calculate the total size of a build directory.
+    /// Collect the size, file count and newest file time of a build directory.
     ///
-    /// This method recursively traverses the specified directory and sums up
-    /// the sizes of all files contained within it. It handles errors gracefully
-    /// and optionally reports them in verbose mode.
+    /// This method recursively traverses the specified directory once, with
+    /// the selected `--size-backend`, and gathers all statistics from the same
+    /// walk. Entries that can't be read are skipped. The walk stops early at
+    /// `--quick-size-threshold`, if set, and reports an estimate.
     ///
     /// # Arguments
     ///
@@ -234,76 +674,137 @@ impl Scanner {
     ///
     /// # Returns
     ///
-    /// The total size of all files in the directory, in bytes. Returns 0 if
-    /// the directory doesn't exist or cannot be accessed.
+    /// The statistics of all files in the directory. Empty statistics are
+    /// returned if the directory doesn't exist or cannot be accessed.
     ///
     /// # Performance
     ///
     /// This method can be CPU and I/O intensive for large directories with
     /// many files. It's designed to be called in parallel for multiple
     /// directories to maximize throughput.
-    fn calculate_build_dir_size(path: &Path) -> u64 {
+    fn collect_build_dir_stats(&self, path: &Path) -> DirStats {
         if !path.exists() {
-            return 0;
+            return DirStats::default();
         }
 
-        crate::utils::calculate_dir_size(path)
+        self.scan_options
+            .size_backend
+            .backend()
+            .collect(path, self.scan_options.quick_size_threshold)
     }
 
-    /// Detect a Node.js project in the specified directory.
-    ///
-    /// This method checks for the presence of both `package.json` and `node_modules/`
-    /// directory to identify a Node.js project. If found, it attempts to extract
-    /// the project name from the `package.json` file.
-    ///
-    /// # Arguments
-    ///
-    /// * `path` - Directory path to check for Node.js project
-    /// * `errors` - Shared error collection for reporting parsing issues
-    ///
-    /// # Returns
+    /// Collapse Node.js workspace members into their workspace root.
     ///
-    /// - `Some(Project)` if a valid Node.js project is detected
-    /// - `None` if the directory doesn't contain a Node.js project
+    /// A workspace root claims the build directories of its member packages,
+    /// so a member that was also detected on its own would be counted (and
+    /// cleaned) twice. Such members are removed from the list.
+    fn merge_node_workspaces(projects: Vec<Project>) -> Vec<Project> {
+        // Build directories of workspace members, with the root claiming them
+        let claimed: HashMap<PathBuf, PathBuf> = projects
+            .iter()
+            .filter(|p| p.kind == ProjectType::Node)
+            .flat_map(|p| {
+                p.build_arts
+                    .iter()
+                    .filter(|a| a.path.parent() != Some(p.root_path.as_path()))
+                    .map(|a| (a.path.clone(), p.root_path.clone()))
+            })
+            .collect();
+
+        if claimed.is_empty() {
+            return projects;
+        }
+
+        projects
+            .into_iter()
+            .filter(|p| {
+                p.kind != ProjectType::Node
+                    || !p.build_arts.iter().any(|a| {
+                        claimed.get(&a.path).is_some_and(|root| {
+                            *root != p.root_path && p.root_path.starts_with(root)
+                        })
+                    })
+            })
+            .collect()
+    }
+
+    /// Collapse the modules of Android projects into their project root.
     ///
-    /// # Detection Criteria
+    /// An Android project claims the `build/` and `.cxx/` directories of its
+    /// modules, which would otherwise also be detected on their own, mostly as
+    /// Gradle (Java) projects. Those artifacts are removed from the other
+    /// projects, and projects left without any are dropped.
+    fn merge_android_modules(projects: Vec<Project>) -> Vec<Project> {
+        let claimed: HashSet<PathBuf> = projects
+            .iter()
+            .filter(|p| p.kind == ProjectType::Android)
+            .flat_map(|p| p.build_arts.iter().map(|a| a.path.clone()))
+            .collect();
+
+        if claimed.is_empty() {
+            return projects;
+        }
+
+        projects
+            .into_iter()
+            .filter_map(|mut p| {
+                if p.kind != ProjectType::Android {
+                    p.build_arts.retain(|a| !claimed.contains(&a.path));
+                }
+                (!p.build_arts.is_empty()).then_some(p)
+            })
+            .collect()
+    }
+
+    /// Leave the contents of container caches to the project they belong to.
     ///
-    /// 1. `package.json` file exists in directory
-    /// 2. `node_modules/` subdirectory exists in directory
-    /// 3. The project name is extracted from `package.json` if possible
-    fn detect_node_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let package_json = path.join("package.json");
-        let node_modules = path.join("node_modules");
-
-        if package_json.exists() && node_modules.exists() {
-            let name = self.extract_node_project_name(&package_json, errors);
-
-            let build_arts = vec![BuildArtifacts {
-                path: path.join("node_modules"),
-                size: 0, // Will be calculated later
-            }];
-
-            return Some(Project::new(
-                ProjectType::Node,
-                path.to_path_buf(),
-                build_arts,
-                name,
-            ));
+    /// A container cache can hold, or be, the artifacts of what looks like a
+    /// project of its own, such as the `node_modules/` of an app mirrored for
+    /// a container. Artifacts inside the cache of another project are
+    /// removed, and projects left without any are dropped.
+    fn merge_container_caches(&self, projects: Vec<Project>) -> Vec<Project> {
+        if !self.scan_options.container_caches {
+            return projects;
+        }
+        let patterns = &self.scan_options.container_cache_patterns;
+        // Container caches, with the root of the project they belong to
+        let caches: Vec<(PathBuf, PathBuf)> = projects
+            .iter()
+            .flat_map(|p| {
+                p.build_arts
+                    .iter()
+                    .filter(|a| is_container_cache(&p.root_path, &a.path, patterns))
+                    .map(|a| (a.path.clone(), p.root_path.clone()))
+            })
+            .collect();
+
+        if caches.is_empty() {
+            return projects;
         }
 
-        None
+        projects
+            .into_iter()
+            .filter_map(|mut p| {
+                p.build_arts.retain(|a| {
+                    !caches
+                        .iter()
+                        .any(|(cache, root)| *root != p.root_path && a.path.starts_with(cache))
+                });
+                (!p.build_arts.is_empty()).then_some(p)
+            })
+            .collect()
     }
 
-    /// Detect if a directory entry represents a development project.
+    /// Detect the development projects in a directory entry.
     ///
-    /// This method examines a directory entry and determines if it contains
-    /// a development project based on the presence of characteristic files
+    /// This method examines a directory entry and determines which development
+    /// projects it contains based on the presence of characteristic files
     /// and directories. It respects the project filter settings.
     ///
+    /// A directory can hold several ecosystems at once, such as a Node.js
+    /// frontend next to a Python `.venv`; each one is reported as a separate
+    /// project with the same root.
+    ///
     /// # Arguments
     ///
     /// * `entry` - The directory entry to examine
@@ -311,357 +812,222 @@ impl Scanner {
     ///
     /// # Returns
     ///
-    /// - `Some(Project)` if a valid project is detected
-    /// - `None` if no project is found or the entry doesn't match filters
-    ///
-    /// # Project Detection Logic
-    ///
-    /// - **Rust projects**: Presence of both `Cargo.toml` and `target/` directory
-    /// - **Deno projects**: Presence of `deno.json`/`deno.jsonc` with `vendor/` or `node_modules/`
-    /// - **Node.js projects**: Presence of both `package.json` and `node_modules/` directory
-    /// - **Scala projects**: Presence of `build.sbt` with `target/`
-    /// - **Java/Kotlin projects**: Presence of `pom.xml` or `build.gradle` with `target/` or `build/`
-    /// - **Python projects**: Presence of configuration files and cache directories
-    /// - **Go projects**: Presence of both `go.mod` and `vendor/` directory
-    /// - **C/C++ projects**: Presence of `CMakeLists.txt` or `Makefile` with `build/`
-    /// - **Swift projects**: Presence of `Package.swift` with `.build/`
-    /// - **.NET/C# projects**: Presence of `.csproj` files with `bin/` or `obj/`
-    /// - **Ruby projects**: Presence of `Gemfile` with `.bundle/` or `vendor/bundle/`
-    /// - **Elixir projects**: Presence of `mix.exs` with `_build/`
-    /// - **PHP projects**: Presence of `composer.json` with `vendor/`
-    /// - **Haskell projects**: Presence of `stack.yaml` with `.stack-work/`, or `*.cabal` with `dist-newstyle/`
-    /// - **Dart/Flutter projects**: Presence of `pubspec.yaml` with `.dart_tool/` or `build/`
-    /// - **Zig projects**: Presence of `build.zig` with `zig-cache/` or `zig-out/`
-    fn detect_project(
-        &self,
-        entry: &DirEntry,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
+    /// The projects found, in detector order; empty if no project is found or
+    /// the entry doesn't match the filters.
+    ///
+    /// The detectors in [`DETECTORS`] describe what each project type looks like.
+    fn detect_projects(&self, entry: &DirEntry, errors: &ScanErrors) -> Vec<Project> {
         let path = entry.path();
 
         if !entry.file_type().is_dir() {
-            return None;
+            return Vec::new();
         }
 
-        // Detectors are tried in order; the first match wins.
-        // More specific ecosystems are checked before more generic ones
-        // (e.g. Scala before Java, since both use target/; Deno before
-        // Node since Deno 2 projects may also have a node_modules/).
-        self.try_detect(ProjectFilter::Rust, || {
-            self.detect_rust_project(path, errors)
-        })
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Deno, || {
-                self.detect_deno_project(path, errors)
-            })
-        })
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Node, || {
-                self.detect_node_project(path, errors)
-            })
-        })
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Scala, || {
-                self.detect_scala_project(path, errors)
-            })
-        })
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Java, || {
-                self.detect_java_project(path, errors)
-            })
-        })
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Swift, || {
-                self.detect_swift_project(path, errors)
-            })
-        })
-        .or_else(|| self.try_detect(ProjectFilter::DotNet, || Self::detect_dotnet_project(path)))
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Python, || {
-                self.detect_python_project(path, errors)
-            })
-        })
-        .or_else(|| self.try_detect(ProjectFilter::Go, || self.detect_go_project(path, errors)))
-        .or_else(|| self.try_detect(ProjectFilter::Cpp, || self.detect_cpp_project(path, errors)))
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Ruby, || {
-                self.detect_ruby_project(path, errors)
-            })
-        })
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Elixir, || {
-                self.detect_elixir_project(path, errors)
-            })
-        })
-        .or_else(|| self.try_detect(ProjectFilter::Php, || self.detect_php_project(path, errors)))
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Haskell, || {
-                self.detect_haskell_project(path, errors)
-            })
-        })
-        .or_else(|| {
-            self.try_detect(ProjectFilter::Dart, || {
-                self.detect_dart_project(path, errors)
+        // Every detector is tried, in priority order, and a build directory
+        // belongs to the first one that reports it. The type filter only
+        // applies once projects are finished, so excluding a type (say
+        // Android) leaves its projects out rather than handing them to a
+        // more general detector (Java) further down the list.
+        let ctx = DetectContext::new(&self.scan_options, errors);
+        let detected = DETECTORS
+            .iter()
+            .filter_map(|&detector| self.try_detect(detector, path, &ctx));
+
+        let mut claimed = HashSet::new();
+        let mut projects: Vec<Project> = detected
+            .filter_map(|mut project| {
+                project
+                    .build_arts
+                    .retain(|artifact| claimed.insert(artifact.path.clone()));
+                (!project.build_arts.is_empty()).then_some(project)
             })
-        })
-        .or_else(|| self.try_detect(ProjectFilter::Zig, || Self::detect_zig_project(path)))
-    }
+            .collect();
 
-    /// Run a detector only if the current project filter allows it.
-    ///
-    /// Returns `None` immediately (without calling `detect`) when the
-    /// active filter doesn't include `filter`.
-    fn try_detect(
-        &self,
-        filter: ProjectFilter,
-        detect: impl FnOnce() -> Option<Project>,
-    ) -> Option<Project> {
-        if self.project_filter == ProjectFilter::All || self.project_filter == filter {
-            detect()
-        } else {
-            None
-        }
+        Self::apply_local_config(&mut projects, errors);
+        self.apply_keep_marker(&mut projects);
+        projects
     }
 
-    /// Detect a Rust project in the specified directory.
-    ///
-    /// This method checks for the presence of both `Cargo.toml` and `target/`
-    /// directory to identify a Rust project. If found, it attempts to extract
-    /// the project name from the `Cargo.toml` file.
-    ///
-    /// # Arguments
-    ///
-    /// * `path` - Directory path to check for a Rust project
-    /// * `errors` - Shared error collection for reporting parsing issues
-    ///
-    /// # Returns
-    ///
-    /// - `Some(Project)` if a valid Rust project is detected
-    /// - `None` if the directory doesn't contain a Rust project
+    /// Merge a directory's `.clean-dev-dirs.toml` overrides into the projects detected there.
     ///
-    /// # Detection Criteria
+    /// Marks every project as protected when requested and appends any
+    /// `extra_artifacts` that exist on disk as additional build artifacts of
+    /// the first project (sized later, together with the detected ones).
     ///
-    /// 1. `Cargo.toml` file exists in directory
-    /// 2. `target/` subdirectory exists in directory
-    /// 3. The project name is extracted from `Cargo.toml` if possible
-    fn detect_rust_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let cargo_toml = path.join("Cargo.toml");
-        let target_dir = path.join("target");
+    /// The override file is a safety control, so one that exists but can't be
+    /// loaded protects every project of the directory, with a warning shown
+    /// whether or not the scan is verbose.
+    fn apply_local_config(projects: &mut [Project], errors: &ScanErrors) {
+        let Some(root) = projects.first().map(|p| p.root_path.clone()) else {
+            return;
+        };
 
-        if cargo_toml.exists() && target_dir.exists() {
-            // Skip workspace members — their artifacts are managed by the workspace root.
-            if Self::is_inside_cargo_workspace(path) {
-                return None;
+        let local = match LocalConfig::load(&root) {
+            Ok(Some(local)) => local,
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Warning: {e}; protecting {} until it is fixed (-v for details)",
+                        root.display()
+                    )
+                    .yellow()
+                );
+                for project in projects.iter_mut() {
+                    project.protected = true;
+                }
+                errors.record(e);
+                return;
             }
+        };
 
-            let name = self.extract_rust_project_name(&cargo_toml, errors);
-
-            let build_arts = vec![BuildArtifacts {
-                path: path.join("target"),
-                size: 0, // Will be calculated later
-            }];
-
-            return Some(Project::new(
-                ProjectType::Rust,
-                path.to_path_buf(),
-                build_arts,
-                name,
-            ));
+        for project in projects.iter_mut() {
+            project.protected = local.is_protected();
         }
 
-        None
+        let extras = local
+            .resolved_extra_artifacts(&root)
+            .into_iter()
+            .filter(|dir| {
+                !projects
+                    .iter()
+                    .flat_map(|p| &p.build_arts)
+                    .any(|a| a.path == *dir)
+            })
+            .collect();
+        Self::append_extra_artifacts(&mut projects[0], extras);
     }
 
-    /// Return true if the given `Cargo.toml` declares a `[workspace]` section.
-    fn is_cargo_workspace_root(cargo_toml: &Path) -> bool {
-        fs::read_to_string(cargo_toml)
-            .is_ok_and(|content| content.lines().any(|line| line.trim() == "[workspace]"))
+    /// Protect the projects of a directory that holds the `--keep-marker` file.
+    fn apply_keep_marker(&self, projects: &mut [Project]) {
+        let Some(marker) = &self.scan_options.keep_marker else {
+            return;
+        };
+        let Some(root) = projects.first().map(|p| p.root_path.clone()) else {
+            return;
+        };
+
+        if root.join(marker).exists() {
+            for project in projects.iter_mut() {
+                project.protected = true;
+            }
+        }
     }
 
-    /// Return true if `path` is inside a Rust workspace (an ancestor directory
-    /// contains a `Cargo.toml` that declares `[workspace]`).
-    fn is_inside_cargo_workspace(path: &Path) -> bool {
-        path.ancestors()
-            .skip(1) // skip `path` itself
-            .any(|ancestor| {
-                let cargo_toml = ancestor.join("Cargo.toml");
-                cargo_toml.exists() && Self::is_cargo_workspace_root(&cargo_toml)
-            })
+    /// Whether projects of type `kind` pass the type filter.
+    fn includes_kind(&self, kind: &ProjectType) -> bool {
+        DETECTORS
+            .iter()
+            .filter(|detector| detector.kind() == *kind)
+            .any(|detector| self.type_filter.includes(detector.name()))
     }
 
-    /// Extract the project name from a Cargo.toml file.
-    ///
-    /// This method performs simple TOML parsing to extract the project name
-    /// from a Rust project's `Cargo.toml` file. It uses a line-by-line approach
-    /// rather than a full TOML parser for simplicity and performance.
-    ///
-    /// # Arguments
-    ///
-    /// * `cargo_toml` - Path to the Cargo.toml file
-    /// * `errors` - Shared error collection for reporting parsing issues
-    ///
-    /// # Returns
+    /// Run a detector on `path`.
     ///
-    /// - `Some(String)` containing the project name if successfully extracted
-    /// - `None` if the name cannot be found or parsed
-    ///
-    /// # Parsing Strategy
-    ///
-    /// The method looks for lines matching the pattern `name = "project_name"`
-    /// and extracts the quoted string value. This trivial approach handles
-    /// most common cases without requiring a full TOML parser.
-    fn extract_rust_project_name(
+    /// When the detector matches, any configured extra artifacts for its
+    /// project type (and for `all`) are appended.
+    fn try_detect(
         &self,
-        cargo_toml: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let content = self.read_file_content(cargo_toml, errors)?;
-        Self::parse_toml_name_field(&content)
-    }
-
-    /// Extract a quoted string value from a line.
-    fn extract_quoted_value(line: &str) -> Option<String> {
-        let start = line.find('"')?;
-        let end = line.rfind('"')?;
+        detector: &dyn Detector,
+        path: &Path,
+        ctx: &DetectContext<'_>,
+    ) -> Option<Project> {
+        let mut project = detector.detect(path, ctx)?;
+        self.drop_excluded_artifacts(detector, &mut project);
 
-        if start == end {
-            return None;
+        let (own, all): (Vec<_>, Vec<_>) = self
+            .scan_options
+            .extra_artifacts
+            .iter()
+            .filter(|(key, _)| key.includes(detector.name()))
+            .partition(|(key, _)| **key != ProjectFilter::All);
+        let configured = own
+            .into_iter()
+            .chain(all)
+            .flat_map(|(_, dirs)| dirs)
+            .filter_map(|rel| resolve_within(&project.root_path, rel))
+            .collect::<Vec<_>>();
+        Self::append_extra_artifacts(&mut project, configured);
+
+        Some(project)
+    }
+
+    /// Remove the build directories excluded for the detector's project type
+    /// (and for `all`), matched by their path within the project or their
+    /// name.
+    fn drop_excluded_artifacts(&self, detector: &dyn Detector, project: &mut Project) {
+        let excluded: Vec<String> = self
+            .scan_options
+            .excluded_artifacts
+            .iter()
+            .filter(|(key, _)| key.includes(detector.name()))
+            .flat_map(|(_, dirs)| dirs)
+            .map(|dir| normalize_key(dir))
+            .collect();
+        if excluded.is_empty() {
+            return;
         }
 
-        Some(line[start + 1..end].to_string())
+        let root = &project.root_path;
+        project.build_arts.retain(|artifact| {
+            !artifact_keys(root, &artifact.path)
+                .iter()
+                .any(|key| excluded.contains(key))
+        });
     }
 
-    /// Extract the name from a single TOML line if it contains a name field.
-    fn extract_name_from_line(line: &str) -> Option<String> {
-        if !Self::is_name_line(line) {
-            return None;
+    /// Append existing directories to a project's build artifacts.
+    ///
+    /// Paths that don't exist, aren't directories, or are already listed are skipped.
+    fn append_extra_artifacts(project: &mut Project, dirs: Vec<PathBuf>) {
+        for dir in dirs {
+            let already_listed = project.build_arts.iter().any(|a| a.path == dir);
+            if dir.is_dir() && !already_listed {
+                project.build_arts.push(BuildArtifacts {
+                    path: dir,
+                    size: 0,
+                    files: 0,
+                    dirs: 0,
+                    newest_file: None, // Will be calculated later
+                    estimated: false,
+                });
+            }
         }
-
-        Self::extract_quoted_value(line)
     }
 
-    /// Extract the project name from a package.json file.
+    /// Determine if a directory entry should be scanned for projects.
     ///
-    /// This method parses a Node.js project's `package.json` file to extract
-    /// the project name. It uses full JSON parsing to handle the file format
-    /// correctly and safely.
+    /// This method implements the filtering logic to decide whether a directory
+    /// should be traversed during the scanning process. It applies various
+    /// exclusion rules to improve performance and avoid scanning irrelevant
+    /// directories.
     ///
     /// # Arguments
     ///
-    /// * `package_json` - Path to the package.json file
-    /// * `errors` - Shared error collection for reporting parsing issues
+    /// * `entry` - The directory entry to evaluate
     ///
     /// # Returns
     ///
-    /// - `Some(String)` containing the project name if successfully extracted
-    /// - `None` if the name cannot be found, parsed, or the file is invalid
+    /// - `true` if the directory should be scanned
+    /// - `false` if the directory should be skipped
     ///
-    /// # Error Handling
-    ///
-    /// This method handles both file I/O errors and JSON parsing errors gracefully.
-    /// Errors are optionally reported to the shared error collection in verbose mode.
-    fn extract_node_project_name(
-        &self,
-        package_json: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        match fs::read_to_string(package_json) {
-            Ok(content) => match from_str::<Value>(&content) {
-                Ok(json) => json
-                    .get("name")
-                    .and_then(|v| v.as_str())
-                    .map(std::string::ToString::to_string),
-                Err(e) => {
-                    if self.scan_options.verbose
-                        && let Ok(mut errs) = errors.lock()
-                    {
-                        errs.push(format!("Error parsing {}: {e}", package_json.display()));
-                    }
-                    None
-                }
-            },
-            Err(e) => {
-                if self.scan_options.verbose
-                    && let Ok(mut errs) = errors.lock()
-                {
-                    errs.push(format!("Error reading {}: {e}", package_json.display()));
-                }
-                None
-            }
-        }
-    }
-
-    /// Check if a line contains a name field assignment.
-    fn is_name_line(line: &str) -> bool {
-        line.starts_with("name") && line.contains('=')
-    }
-
-    /// Log a file reading error if verbose mode is enabled.
-    fn log_file_error(
-        &self,
-        file_path: &Path,
-        error: &std::io::Error,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) {
-        if self.scan_options.verbose
-            && let Ok(mut errs) = errors.lock()
-        {
-            errs.push(format!("Error reading {}: {error}", file_path.display()));
-        }
-    }
-
-    /// Parse the name field from TOML content.
-    fn parse_toml_name_field(content: &str) -> Option<String> {
-        for line in content.lines() {
-            if let Some(name) = Self::extract_name_from_line(line.trim()) {
-                return Some(name);
-            }
-        }
-        None
-    }
-
-    /// Read the content of a file and handle errors appropriately.
-    fn read_file_content(
-        &self,
-        file_path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        match fs::read_to_string(file_path) {
-            Ok(content) => Some(content),
-            Err(e) => {
-                self.log_file_error(file_path, &e, errors);
-                None
-            }
-        }
-    }
-
-    /// Determine if a directory entry should be scanned for projects.
-    ///
-    /// This method implements the filtering logic to decide whether a directory
-    /// should be traversed during the scanning process. It applies various
-    /// exclusion rules to improve performance and avoid scanning irrelevant
-    /// directories.
-    ///
-    /// # Arguments
-    ///
-    /// * `entry` - The directory entry to evaluate
-    ///
-    /// # Returns
-    ///
-    /// - `true` if the directory should be scanned
-    /// - `false` if the directory should be skipped
-    ///
-    /// # Exclusion Rules
+    /// # Exclusion Rules
     ///
     /// The following directories are excluded from scanning:
-    /// - Directories in the user-specified skip list
+    /// - Directories in the user-specified skip list (their children are
+    ///   still scanned)
     /// - Any directory inside a `node_modules/` directory (to avoid deep nesting)
-    /// - Hidden directories (starting with `.`) except `.cargo`
-    /// - Common build/temporary directories: `target`, `build`, `dist`, `out`, etc.
+    /// - Hidden directories (starting with `.`) except `.cargo` and the
+    ///   `allow_hidden` names, unless `scan_hidden` is set
+    /// - Directories named in `exclude_names`
+    ///
+    /// and, unless named in `no_exclude_names`:
+    ///
+    /// - Common build/temporary directories: `target`, `build`, `dist`, `out`,
+    ///   etc., but only directly inside a directory with a project marker
+    ///   file, so that a repository that happens to be called `dist` or
+    ///   `build` is still found
     /// - Version control directories: `.git`, `.svn`, `.hg`
     /// - Python cache and virtual environment directories
     /// - Temporary directories: `temp`, `tmp`
@@ -681,2598 +1047,3018 @@ impl Scanner {
         }
 
         // Skip any directory inside a node_modules directory
-        if path
-            .ancestors()
-            .any(|ancestor| ancestor.file_name().and_then(|n| n.to_str()) == Some("node_modules"))
-        {
+        if is_inside_node_modules(path) {
             return false;
         }
 
-        // Skip hidden directories (except .cargo for Rust)
-        if Self::is_hidden_directory_to_skip(path) {
+        // Skip hidden directories (except .cargo for Rust). Hidden build
+        // directories such as `.vs/` or `.dart_tool/` aren't walked into; the
+        // detectors find them from the project directory next to them.
+        if self.is_hidden_directory_to_skip(path) {
             return false;
         }
 
         // Skip common non-project directories
-        !Self::is_excluded_directory(path)
+        !self.is_excluded(path)
+    }
+
+    /// Check if a directory is excluded from being checked for a project
+    ///
+    /// Generic names such as `build` or `dist` only count inside a directory
+    /// that looks like a project itself.
+    fn is_excluded(&self, path: &Path) -> bool {
+        if Self::name_in_list(path, &self.scan_options.exclude_names) {
+            return true;
+        }
+        if Self::name_in_list(path, &self.scan_options.no_exclude_names) {
+            return false;
+        }
+
+        Self::name_is_one_of(path, &NEVER_PROJECT_DIRS)
+            || (Self::name_is_one_of(path, &GENERIC_BUILD_DIRS)
+                && path.parent().is_none_or(has_project_marker))
     }
 
-    /// Check if a path is in the skip list
+    /// Check if a directory's name is in the skip list
     fn is_path_in_skip_list(&self, path: &Path) -> bool {
-        self.scan_options.skip.iter().any(|skip| {
-            path.components().any(|component| {
-                component
-                    .as_os_str()
-                    .to_str()
-                    .is_some_and(|name| name == skip.to_string_lossy())
-            })
-        })
+        Self::name_in_list(path, &self.scan_options.skip)
+    }
+
+    /// Check if a directory's name is in the ignore list, so it must not be
+    /// descended into
+    fn is_ignored(&self, path: &Path) -> bool {
+        Self::name_in_list(path, &self.scan_options.ignore)
+    }
+
+    /// Check if the last component of `path` equals one of `names`
+    fn name_in_list(path: &Path, names: &[PathBuf]) -> bool {
+        path.file_name()
+            .is_some_and(|name| names.iter().any(|n| n.as_os_str() == name))
     }
 
     /// Check if directory is hidden and should be skipped
-    fn is_hidden_directory_to_skip(path: &Path) -> bool {
+    ///
+    /// Nothing is skipped for being hidden with `scan_hidden`; otherwise
+    /// `.cargo` and the `allow_hidden` names are the exceptions.
+    fn is_hidden_directory_to_skip(&self, path: &Path) -> bool {
+        if self.scan_options.scan_hidden
+            || Self::name_in_list(path, &self.scan_options.allow_hidden)
+        {
+            return false;
+        }
+
         path.file_name()
             .and_then(|n| n.to_str())
             .is_some_and(|name| name.starts_with('.') && name != ".cargo")
     }
 
     /// Check if directory is in the excluded list
-    fn is_excluded_directory(path: &Path) -> bool {
-        let excluded_dirs = [
-            "target",
-            "build",
-            "dist",
-            "out",
-            ".git",
-            ".svn",
-            ".hg",
-            "__pycache__",
-            "venv",
-            ".venv",
-            "env",
-            ".env",
-            "temp",
-            "tmp",
-            "vendor",
-            ".pytest_cache",
-            ".tox",
-            ".eggs",
-            ".coverage",
-            "node_modules",
-            "obj",
-            "_build",
-            "zig-cache",
-            "zig-out",
-            "dist-newstyle",
-        ];
+    pub(crate) fn is_excluded_directory(path: &Path) -> bool {
+        Self::name_is_one_of(path, &NEVER_PROJECT_DIRS)
+            || Self::name_is_one_of(path, &GENERIC_BUILD_DIRS)
+    }
 
+    /// Check if the last component of `path` is one of `names`
+    fn name_is_one_of(path: &Path, names: &[&str]) -> bool {
         path.file_name()
             .and_then(|n| n.to_str())
-            .is_some_and(|name| excluded_dirs.contains(&name))
+            .is_some_and(|name| names.contains(&name))
     }
+}
 
-    /// Detect a Python project in the specified directory.
-    ///
-    /// This method checks for Python configuration files and associated cache directories.
-    /// It looks for multiple build artifacts that can be cleaned.
-    ///
-    /// # Arguments
-    ///
-    /// * `path` - Directory path to check for a Python project
-    /// * `errors` - Shared error collection for reporting parsing issues
-    ///
-    /// # Returns
-    ///
-    /// - `Some(Project)` if a valid Python project is detected
-    /// - `None` if the directory doesn't contain a Python project
-    ///
-    /// # Detection Criteria
-    ///
-    /// A Python project is identified by having:
-    /// 1. At least one of: requirements.txt, setup.py, pyproject.toml, setup.cfg, Pipfile
-    /// 2. At least one of the cache/build directories: `__pycache__`, `.pytest_cache`, venv, .venv, build, dist, .eggs
-    fn detect_python_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let config_files = [
-            "requirements.txt",
-            "setup.py",
-            "pyproject.toml",
-            "setup.cfg",
-            "Pipfile",
-            "pipenv.lock",
-            "poetry.lock",
-        ];
-
-        let build_dirs = [
-            "__pycache__",
-            ".pytest_cache",
-            "venv",
-            ".venv",
-            "build",
-            "dist",
-            ".eggs",
-            ".tox",
-            ".coverage",
-        ];
-
-        // Check if any config file exists
-        let has_config = config_files.iter().any(|&file| path.join(file).exists());
-
-        if !has_config {
-            return None;
-        }
-
-        // Collect all existing cache/build directories.
-        let mut build_arts: Vec<BuildArtifacts> = build_dirs
-            .iter()
-            .filter_map(|&dir_name| {
-                let dir_path = path.join(dir_name);
-                if dir_path.exists() && dir_path.is_dir() {
-                    let size = crate::utils::calculate_dir_size(&dir_path);
-                    Some(BuildArtifacts {
-                        path: dir_path,
-                        size,
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        // Also collect any *.egg-info directories present in the project root.
-        if let Ok(entries) = std::fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let entry_path = entry.path();
-                if entry_path.is_dir()
-                    && entry_path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .is_some_and(|n| n.ends_with(".egg-info"))
-                {
-                    let size = crate::utils::calculate_dir_size(&entry_path);
-                    build_arts.push(BuildArtifacts {
-                        path: entry_path,
-                        size,
-                    });
-                }
-            }
-        }
+/// What identifies a directory however it is reached: its device and inode.
+#[cfg(unix)]
+type DirKey = (u64, u64);
 
-        if build_arts.is_empty() {
-            return None;
-        }
+/// What identifies a directory however it is reached: its resolved path.
+#[cfg(not(unix))]
+type DirKey = PathBuf;
 
-        let name = self.extract_python_project_name(path, errors);
+/// The [`DirKey`] of the directory of `entry`, following a symbolic link.
+#[cfg(unix)]
+fn dir_key(entry: &DirEntry) -> Option<DirKey> {
+    use std::os::unix::fs::MetadataExt;
 
-        Some(Project::new(
-            ProjectType::Python,
-            path.to_path_buf(),
-            build_arts,
-            name,
-        ))
-    }
+    let metadata = entry.metadata().ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
 
-    /// Detect a Go project in the specified directory.
-    ///
-    /// This method checks for the presence of both `go.mod` and `vendor/`
-    /// directory to identify a Go project. If found, it attempts to extract
-    /// the project name from the `go.mod` file.
-    ///
-    /// # Arguments
-    ///
-    /// * `path` - Directory path to check for a Go project
-    /// * `errors` - Shared error collection for reporting parsing issues
-    ///
-    /// # Returns
-    ///
-    /// - `Some(Project)` if a valid Go project is detected
-    /// - `None` if the directory doesn't contain a Go project
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `go.mod` file exists in directory
-    /// 2. `vendor/` subdirectory exists in directory
-    /// 3. The project name is extracted from `go.mod` if possible
-    fn detect_go_project(&self, path: &Path, errors: &Arc<Mutex<Vec<String>>>) -> Option<Project> {
-        let go_mod = path.join("go.mod");
-        let vendor_dir = path.join("vendor");
+/// The [`DirKey`] of the directory of `entry`, following a symbolic link.
+#[cfg(not(unix))]
+fn dir_key(entry: &DirEntry) -> Option<DirKey> {
+    fs::canonicalize(entry.path()).ok()
+}
 
-        if go_mod.exists() && vendor_dir.exists() {
-            let name = self.extract_go_project_name(&go_mod, errors);
+/// Record the directory of `entry` as visited, and return whether this is
+/// the first visit. Entries other than directories always are.
+fn first_visit(visited: &mut HashSet<DirKey>, entry: &DirEntry) -> bool {
+    !entry.file_type().is_dir() || dir_key(entry).is_none_or(|key| visited.insert(key))
+}
 
-            let build_arts = vec![BuildArtifacts {
-                path: path.join("vendor"),
-                size: 0, // Will be calculated later
-            }];
+/// The scan error for a directory the walk couldn't read.
+fn walk_error(error: walkdir::Error) -> CleanDevDirsError {
+    let context = error.path().map_or_else(
+        || "Cannot read directory".to_string(),
+        |path| format!("Cannot read {}", path.display()),
+    );
+    CleanDevDirsError::scan(context).with_source(error)
+}
 
-            return Some(Project::new(
-                ProjectType::Go,
-                path.to_path_buf(),
-                build_arts,
-                name,
-            ));
-        }
+/// Drop projects found more than once, keeping the first of each.
+///
+/// Two projects are the same when they have the same type and their roots
+/// resolve to the same directory, as with nested scan roots or a symlinked
+/// alias of a directory.
+fn dedup_projects(projects: Vec<Project>) -> Vec<Project> {
+    let mut seen: HashSet<(PathBuf, ProjectType)> = HashSet::new();
+    projects
+        .into_iter()
+        .filter(|project| seen.insert((canonical_path(&project.root_path), project.kind.clone())))
+        .collect()
+}
 
-        None
-    }
+/// Return true if `path` is a `node_modules` directory or lies inside one.
+fn is_inside_node_modules(path: &Path) -> bool {
+    path.ancestors()
+        .any(|ancestor| ancestor.file_name().and_then(|n| n.to_str()) == Some("node_modules"))
+}
 
-    /// Extract the project name from a Python project directory.
-    ///
-    /// This method attempts to extract the project name from various Python
-    /// configuration files in order of preference.
-    ///
-    /// # Arguments
-    ///
-    /// * `path` - Path to the Python project directory
-    /// * `errors` - Shared error collection for reporting parsing issues
-    ///
-    /// # Returns
-    ///
-    /// - `Some(String)` containing the project name if successfully extracted
-    /// - `None` if the name cannot be found or parsed
-    ///
-    /// # Extraction Order
-    ///
-    /// 1. pyproject.toml (from [project] name or [tool.poetry] name)
-    /// 2. setup.py (from name= parameter)
-    /// 3. setup.cfg (from [metadata] name)
-    /// 4. Use directory name as a fallback
-    fn extract_python_project_name(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        // Try files in order of preference
-        self.try_extract_from_pyproject_toml(path, errors)
-            .or_else(|| self.try_extract_from_setup_py(path, errors))
-            .or_else(|| self.try_extract_from_setup_cfg(path, errors))
-            .or_else(|| Self::fallback_to_directory_name(path))
-    }
+/// Return true if `path` is a Cargo target directory of the project in `root`
+/// (`target/` or `target-*/`) or lies inside one.
+fn is_in_target_dir(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .and_then(|first| first.as_os_str().to_str())
+        .is_some_and(|name| name == "target" || name.starts_with("target-"))
+}
 
-    /// Try to extract project name from pyproject.toml
-    fn try_extract_from_pyproject_toml(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let pyproject_toml = path.join("pyproject.toml");
-        if !pyproject_toml.exists() {
-            return None;
-        }
+/// `path` with symlinks resolved, or unchanged if it can't be resolved.
+fn canonical_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
 
-        let content = self.read_file_content(&pyproject_toml, errors)?;
-        Self::extract_name_from_toml_like_content(&content)
-    }
+/// Split a list of paths read from stdin for [`Scanner::scan_roots`].
+///
+/// Paths are separated by NUL bytes if there are any (as printed by
+/// `find -print0` or `fd -0`), and by newlines otherwise. Empty entries are
+/// skipped, and a trailing `\r` is removed from newline-separated ones.
+///
+/// The input is raw bytes, so paths that aren't valid UTF-8 are kept as they
+/// are on Unix.
+#[must_use]
+pub fn parse_root_list(input: &[u8]) -> Vec<PathBuf> {
+    let entries: Vec<&[u8]> = if input.contains(&0) {
+        input.split(|&b| b == 0).collect()
+    } else {
+        input
+            .split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .collect()
+    };
+
+    entries
+        .into_iter()
+        .filter(|entry| !entry.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
 
-    /// Try to extract project name from setup.py
-    fn try_extract_from_setup_py(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let setup_py = path.join("setup.py");
-        if !setup_py.exists() {
-            return None;
-        }
+/// A path from its raw bytes.
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
 
-        let content = self.read_file_content(&setup_py, errors)?;
-        Self::extract_name_from_python_content(&content)
-    }
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
 
-    /// Try to extract project name from setup.cfg
-    fn try_extract_from_setup_cfg(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let setup_cfg = path.join("setup.cfg");
-        if !setup_cfg.exists() {
-            return None;
-        }
+/// A path from its bytes, replacing what isn't valid UTF-8.
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
 
-        let content = self.read_file_content(&setup_cfg, errors)?;
-        Self::extract_name_from_cfg_content(&content)
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::DEFAULT_NODE_CACHES,
+        risk::RiskOverrides,
+        utils::{SizeBackendKind, SizeUnits},
+    };
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
 
-    /// Extract name from TOML-like content (pyproject.toml)
-    fn extract_name_from_toml_like_content(content: &str) -> Option<String> {
-        content
-            .lines()
-            .map(str::trim)
-            .find(|line| line.starts_with("name") && line.contains('='))
-            .and_then(Self::extract_quoted_value)
+    /// Create a scanner with default options and the given filter.
+    fn default_scanner(filter: impl Into<TypeFilter>) -> Scanner {
+        Scanner::new(
+            ScanOptions {
+                verbose: false,
+                threads: 1,
+                skip: vec![],
+                max_depth: None,
+                ..ScanOptions::default()
+            },
+            filter,
+        )
     }
 
-    /// Extract name from Python content (setup.py)
-    fn extract_name_from_python_content(content: &str) -> Option<String> {
-        content
-            .lines()
-            .map(str::trim)
-            .find(|line| line.contains("name") && line.contains('='))
-            .and_then(Self::extract_quoted_value)
+    /// Helper to create a file with content, ensuring parent dirs exist.
+    fn create_file(path: &Path, content: &str) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        Ok(())
     }
 
-    /// Extract name from INI-style configuration content (setup.cfg)
-    fn extract_name_from_cfg_content(content: &str) -> Option<String> {
-        let mut in_metadata_section = false;
+    // ── Static helper method tests ──────────────────────────────────────
 
-        for line in content.lines() {
-            let line = line.trim();
+    #[test]
+    fn test_is_hidden_directory_to_skip() {
+        let scanner = default_scanner(ProjectFilter::All);
 
-            if line == "[metadata]" {
-                in_metadata_section = true;
-            } else if line.starts_with('[') && line.ends_with(']') {
-                in_metadata_section = false;
-            } else if in_metadata_section && line.starts_with("name") && line.contains('=') {
-                return line.split('=').nth(1).map(|name| name.trim().to_string());
-            }
-        }
+        // Hidden directories should be skipped
+        assert!(scanner.is_hidden_directory_to_skip(Path::new("/some/.hidden")));
+        assert!(scanner.is_hidden_directory_to_skip(Path::new("/some/.git")));
+        assert!(scanner.is_hidden_directory_to_skip(Path::new("/some/.svn")));
+        assert!(scanner.is_hidden_directory_to_skip(Path::new(".env")));
 
-        None
-    }
+        // .cargo is the special exception — should NOT be skipped
+        assert!(!scanner.is_hidden_directory_to_skip(Path::new("/home/user/.cargo")));
+        assert!(!scanner.is_hidden_directory_to_skip(Path::new(".cargo")));
 
-    /// Fallback to directory name
-    fn fallback_to_directory_name(path: &Path) -> Option<String> {
-        path.file_name()
-            .and_then(|name| name.to_str())
-            .map(std::string::ToString::to_string)
+        // Non-hidden directories should not be skipped
+        assert!(!scanner.is_hidden_directory_to_skip(Path::new("/some/visible")));
+        assert!(!scanner.is_hidden_directory_to_skip(Path::new("src")));
     }
 
-    /// Extract the project name from a `go.mod` file.
-    ///
-    /// This method parses a Go project's `go.mod` file to extract
-    /// the module name, which typically represents the project.
-    ///
-    /// # Arguments
-    ///
-    /// * `go_mod` - Path to the `go.mod` file
-    /// * `errors` - Shared error collection for reporting parsing issues
-    ///
-    /// # Returns
-    ///
-    /// - `Some(String)` containing the module name if successfully extracted
-    /// - `None` if the name cannot be found or parsed
-    ///
-    /// # Parsing Strategy
-    ///
-    /// The method looks for the first line starting with `module ` and extracts
-    /// the module path. For better display, it takes the last component of the path.
-    fn extract_go_project_name(
-        &self,
-        go_mod: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let content = self.read_file_content(go_mod, errors)?;
-
-        for line in content.lines() {
-            let line = line.trim();
-            if line.starts_with("module ") {
-                let module_path = line.strip_prefix("module ")?.trim();
-
-                // Take the last component of the module path for a cleaner name
-                if let Some(name) = module_path.split('/').next_back() {
-                    return Some(name.to_string());
-                }
-
-                return Some(module_path.to_string());
-            }
-        }
+    #[test]
+    fn test_is_excluded_directory() {
+        // Build/artifact directories should be excluded
+        assert!(Scanner::is_excluded_directory(Path::new("/some/target")));
+        assert!(Scanner::is_excluded_directory(Path::new(
+            "/some/node_modules"
+        )));
+        assert!(Scanner::is_excluded_directory(Path::new(
+            "/some/__pycache__"
+        )));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/vendor")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/build")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/dist")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/out")));
 
-        None
-    }
+        // VCS directories should be excluded
+        assert!(Scanner::is_excluded_directory(Path::new("/some/.git")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/.svn")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/.hg")));
 
-    /// Detect a Java/Kotlin project in the specified directory.
-    ///
-    /// This method checks for Maven (`pom.xml`) or Gradle (`build.gradle`,
-    /// `build.gradle.kts`) configuration files and their associated build output
-    /// directories (`target/` for Maven, `build/` for Gradle).
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `pom.xml` + `target/` directory (Maven)
-    /// 2. `build.gradle` or `build.gradle.kts` + `build/` directory (Gradle)
-    fn detect_java_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let pom_xml = path.join("pom.xml");
-        let target_dir = path.join("target");
-
-        // Maven project: pom.xml + target/
-        if pom_xml.exists() && target_dir.exists() {
-            let name = self.extract_java_maven_project_name(&pom_xml, errors);
-
-            let build_arts = vec![BuildArtifacts {
-                path: target_dir,
-                size: 0,
-            }];
-
-            return Some(Project::new(
-                ProjectType::Java,
-                path.to_path_buf(),
-                build_arts,
-                name,
-            ));
-        }
+        // Python-specific directories
+        assert!(Scanner::is_excluded_directory(Path::new(
+            "/some/.pytest_cache"
+        )));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/.tox")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/.eggs")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/.coverage")));
 
-        // Gradle project: build.gradle(.kts) + build/
-        let has_gradle =
-            path.join("build.gradle").exists() || path.join("build.gradle.kts").exists();
-        let build_dir = path.join("build");
+        // Virtual environments
+        assert!(Scanner::is_excluded_directory(Path::new("/some/venv")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/.venv")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/env")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/.env")));
 
-        if has_gradle && build_dir.exists() {
-            let name = self.extract_java_gradle_project_name(path, errors);
+        // Temp directories
+        assert!(Scanner::is_excluded_directory(Path::new("/some/temp")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/tmp")));
 
-            let build_arts = vec![BuildArtifacts {
-                path: build_dir,
-                size: 0,
-            }];
+        // Non-excluded directories
+        assert!(!Scanner::is_excluded_directory(Path::new("/some/src")));
+        assert!(!Scanner::is_excluded_directory(Path::new("/some/lib")));
+        assert!(!Scanner::is_excluded_directory(Path::new("/some/app")));
+        assert!(!Scanner::is_excluded_directory(Path::new("/some/tests")));
+    }
 
-            return Some(Project::new(
-                ProjectType::Java,
-                path.to_path_buf(),
-                build_arts,
-                name,
-            ));
-        }
+    #[test]
+    fn test_is_path_in_skip_list() {
+        let scanner = Scanner::new(
+            ScanOptions {
+                verbose: false,
+                threads: 1,
+                skip: vec![PathBuf::from("skip-me"), PathBuf::from("also-skip")],
+                max_depth: None,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::All,
+        );
 
-        None
+        assert!(scanner.is_path_in_skip_list(Path::new("/root/skip-me")));
+        assert!(scanner.is_path_in_skip_list(Path::new("/root/also-skip")));
+        // Only the directory itself is skipped, not what's below it
+        assert!(!scanner.is_path_in_skip_list(Path::new("/root/skip-me/project")));
+        assert!(!scanner.is_path_in_skip_list(Path::new("/root/keep-me")));
+        assert!(!scanner.is_path_in_skip_list(Path::new("/root/src")));
     }
 
-    /// Extract the project name from a Maven `pom.xml` file.
-    ///
-    /// Looks for `<artifactId>` tags and extracts the text content.
-    fn extract_java_maven_project_name(
-        &self,
-        pom_xml: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let content = self.read_file_content(pom_xml, errors)?;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("<artifactId>") && trimmed.ends_with("</artifactId>") {
-                let name = trimmed
-                    .strip_prefix("<artifactId>")?
-                    .strip_suffix("</artifactId>")?;
-                return Some(name.to_string());
-            }
-        }
-
-        None
+    #[test]
+    fn test_is_path_in_empty_skip_list() {
+        let scanner = default_scanner(ProjectFilter::All);
+        assert!(!scanner.is_path_in_skip_list(Path::new("/any/path")));
     }
 
-    /// Extract the project name from a Gradle project.
-    ///
-    /// Looks for `settings.gradle` or `settings.gradle.kts` and extracts
-    /// the `rootProject.name` value. Falls back to directory name.
-    fn extract_java_gradle_project_name(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        for settings_file in &["settings.gradle", "settings.gradle.kts"] {
-            let settings_path = path.join(settings_file);
-            if settings_path.exists()
-                && let Some(content) = self.read_file_content(&settings_path, errors)
-            {
-                for line in content.lines() {
-                    let trimmed = line.trim();
-                    if trimmed.contains("rootProject.name") && trimmed.contains('=') {
-                        return Self::extract_quoted_value(trimmed).or_else(|| {
-                            trimmed
-                                .split('=')
-                                .nth(1)
-                                .map(|s| s.trim().trim_matches('\'').to_string())
-                        });
-                    }
-                }
-            }
-        }
+    // ── Scanning with special path characters ───────────────────────────
 
-        Self::fallback_to_directory_name(path)
-    }
+    #[test]
+    fn test_scan_directory_with_spaces_in_path() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path().join("path with spaces");
+        fs::create_dir_all(&base)?;
 
-    /// Detect a C/C++ project in the specified directory.
-    ///
-    /// This method checks for `CMakeLists.txt` or `Makefile` alongside a `build/`
-    /// directory to identify C/C++ projects.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `CMakeLists.txt` + `build/` directory (`CMake`)
-    /// 2. `Makefile` + `build/` directory (`Make`)
-    fn detect_cpp_project(&self, path: &Path, errors: &Arc<Mutex<Vec<String>>>) -> Option<Project> {
-        let build_dir = path.join("build");
+        let project = base.join("my project");
+        create_file(
+            &project.join("Cargo.toml"),
+            "[package]\nname = \"spaced\"\nversion = \"0.1.0\"",
+        )?;
+        create_file(&project.join("target/dummy"), "content")?;
 
-        if !build_dir.exists() {
-            return None;
-        }
+        let scanner = default_scanner(ProjectFilter::Rust);
+        let projects = scanner.scan_directory(&base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("spaced"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_directory_with_unicode_names() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        let cmake_file = path.join("CMakeLists.txt");
-        let makefile = path.join("Makefile");
+        let project = base.join("プロジェクト");
+        create_file(
+            &project.join("package.json"),
+            r#"{"name": "unicode-project"}"#,
+        )?;
+        create_file(&project.join("node_modules/dep.js"), "module.exports = {};")?;
 
-        if cmake_file.exists() || makefile.exists() {
-            let name = if cmake_file.exists() {
-                self.extract_cpp_cmake_project_name(&cmake_file, errors)
-            } else {
-                Self::fallback_to_directory_name(path)
-            };
+        let scanner = default_scanner(ProjectFilter::Node);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("unicode-project"));
+        Ok(())
+    }
 
-            let build_arts = vec![BuildArtifacts {
-                path: build_dir,
-                size: 0,
-            }];
+    #[test]
+    fn test_scan_directory_with_special_characters_in_name() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-            return Some(Project::new(
-                ProjectType::Cpp,
-                path.to_path_buf(),
-                build_arts,
-                name,
-            ));
-        }
+        let project = base.join("project-with-dashes_and_underscores.v2");
+        create_file(
+            &project.join("Cargo.toml"),
+            "[package]\nname = \"special-chars\"\nversion = \"0.1.0\"",
+        )?;
+        create_file(&project.join("target/dummy"), "content")?;
 
-        None
+        let scanner = default_scanner(ProjectFilter::Rust);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("special-chars"));
+        Ok(())
     }
 
-    /// Extract the project name from a `CMakeLists.txt` file.
-    ///
-    /// Looks for `project(name` patterns and extracts the project name.
-    fn extract_cpp_cmake_project_name(
-        &self,
-        cmake_file: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let content = self.read_file_content(cmake_file, errors)?;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("project(") || trimmed.starts_with("PROJECT(") {
-                let inner = trimmed
-                    .trim_start_matches("project(")
-                    .trim_start_matches("PROJECT(")
-                    .trim_end_matches(')')
-                    .trim();
-
-                // The project name is the first word/token
-                let name = inner.split_whitespace().next()?;
-                // Remove possible surrounding quotes
-                let name = name.trim_matches('"').trim_matches('\'');
-                if !name.is_empty() {
-                    return Some(name.to_string());
-                }
-            }
-        }
+    // ── Unix-specific scanning tests ────────────────────────────────────
 
-        Self::fallback_to_directory_name(cmake_file.parent()?)
-    }
+    #[test]
+    #[cfg(unix)]
+    fn test_hidden_directory_itself_not_detected_as_project_unix() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-    /// Detect a Swift project in the specified directory.
-    ///
-    /// This method checks for a `Package.swift` manifest and the `.build/`
-    /// directory to identify Swift Package Manager projects.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `Package.swift` file exists
-    /// 2. `.build/` directory exists
-    fn detect_swift_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let package_swift = path.join("Package.swift");
-        let build_dir = path.join(".build");
-
-        if package_swift.exists() && build_dir.exists() {
-            let name = self.extract_swift_project_name(&package_swift, errors);
-
-            let build_arts = vec![BuildArtifacts {
-                path: build_dir,
-                size: 0,
-            }];
-
-            return Some(Project::new(
-                ProjectType::Swift,
-                path.to_path_buf(),
-                build_arts,
-                name,
-            ));
-        }
+        // A hidden directory with Cargo.toml + target/ directly inside it
+        // should NOT be detected because the .hidden entry is filtered by
+        // is_hidden_directory_to_skip. However, non-hidden children inside
+        // hidden dirs CAN still be found because WalkDir descends into them.
+        let hidden = base.join(".hidden-project");
+        create_file(
+            &hidden.join("Cargo.toml"),
+            "[package]\nname = \"hidden\"\nversion = \"0.1.0\"",
+        )?;
+        create_file(&hidden.join("target/dummy"), "content")?;
 
-        None
-    }
+        // A visible project should be found
+        let visible = base.join("visible-project");
+        create_file(
+            &visible.join("Cargo.toml"),
+            "[package]\nname = \"visible\"\nversion = \"0.1.0\"",
+        )?;
+        create_file(&visible.join("target/dummy"), "content")?;
 
-    /// Extract the project name from a `Package.swift` file.
-    ///
-    /// Looks for `name:` inside the `Package(` initializer.
-    fn extract_swift_project_name(
-        &self,
-        package_swift: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let content = self.read_file_content(package_swift, errors)?;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.contains("name:") {
-                return Self::extract_quoted_value(trimmed);
-            }
-        }
+        let scanner = default_scanner(ProjectFilter::Rust);
+        let projects = scanner.scan_directory(base);
 
-        Self::fallback_to_directory_name(package_swift.parent()?)
+        // Only the visible project should be found; the hidden one is excluded
+        // because its directory name starts with '.'
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("visible"));
+        Ok(())
     }
 
-    /// Detect a .NET/C# project in the specified directory.
-    ///
-    /// This method checks for `.csproj` files alongside `bin/` and/or `obj/`
-    /// directories to identify .NET projects.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. At least one `.csproj` file exists in the directory
-    /// 2. At least one of `bin/` or `obj/` directories exists
-    fn detect_dotnet_project(path: &Path) -> Option<Project> {
-        let bin_dir = path.join("bin");
-        let obj_dir = path.join("obj");
-
-        let has_build_dir = bin_dir.exists() || obj_dir.exists();
-        if !has_build_dir {
-            return None;
-        }
+    #[test]
+    #[cfg(unix)]
+    fn test_projects_inside_hidden_dirs_are_still_traversed_unix() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        let csproj_file = Self::find_file_with_extension(path, "csproj")?;
+        // A non-hidden project nested inside a hidden directory.
+        // WalkDir still descends into .hidden, so the child project IS found.
+        let nested = base.join(".hidden-parent/visible-child");
+        create_file(
+            &nested.join("Cargo.toml"),
+            "[package]\nname = \"nested\"\nversion = \"0.1.0\"",
+        )?;
+        create_file(&nested.join("target/dummy"), "content")?;
 
-        // Collect bin/ and obj/ as separate build artifacts (both when present).
-        let build_arts: Vec<BuildArtifacts> = match (bin_dir.exists(), obj_dir.exists()) {
-            (true, true) => {
-                let bin_size = crate::utils::calculate_dir_size(&bin_dir);
-                let obj_size = crate::utils::calculate_dir_size(&obj_dir);
-                vec![
-                    BuildArtifacts {
-                        path: bin_dir,
-                        size: bin_size,
-                    },
-                    BuildArtifacts {
-                        path: obj_dir,
-                        size: obj_size,
-                    },
-                ]
-            }
-            (true, false) => vec![BuildArtifacts {
-                path: bin_dir,
-                size: 0,
-            }],
-            (false, true) => vec![BuildArtifacts {
-                path: obj_dir,
-                size: 0,
-            }],
-            (false, false) => return None,
-        };
+        let scanner = default_scanner(ProjectFilter::Rust);
+        let projects = scanner.scan_directory(base);
 
-        let name = csproj_file
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .map(std::string::ToString::to_string);
-
-        Some(Project::new(
-            ProjectType::DotNet,
-            path.to_path_buf(),
-            build_arts,
-            name,
-        ))
-    }
-
-    /// Find the first file with a given extension in a directory.
-    fn find_file_with_extension(dir: &Path, extension: &str) -> Option<std::path::PathBuf> {
-        let entries = fs::read_dir(dir).ok()?;
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some(extension) {
-                return Some(path);
-            }
-        }
-        None
+        // The child project has a non-hidden name, so it IS detected
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("nested"));
+        Ok(())
     }
 
-    /// Detect a Deno project in the specified directory.
-    ///
-    /// This method checks for a `deno.json` or `deno.jsonc` manifest alongside a
-    /// `vendor/` directory (from `deno vendor`) or a `node_modules/` directory
-    /// (Deno 2 npm support without a `package.json` to avoid overlap with Node.js).
-    ///
-    /// Deno detection runs before Node.js so that a project with `deno.json` and
-    /// `node_modules/` (but no `package.json`) is classified as Deno.
-    fn detect_deno_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let deno_json = path.join("deno.json");
-        let deno_jsonc = path.join("deno.jsonc");
-
-        if !deno_json.exists() && !deno_jsonc.exists() {
-            return None;
-        }
+    #[test]
+    fn test_generic_build_names_only_excluded_inside_projects() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        let config_path = if deno_json.exists() {
-            deno_json
-        } else {
-            deno_jsonc
+        // A repository that happens to be called "dist"...
+        create_file(&base.join("dist/package.json"), r#"{"name": "dist"}"#)?;
+        create_file(&base.join("dist/node_modules/dep/index.js"), "// dep")?;
+        // ...and a "build" directory of a project that looks like one
+        create_file(&base.join("app/package.json"), r#"{"name": "app"}"#)?;
+        create_file(&base.join("app/node_modules/dep/index.js"), "// dep")?;
+        create_file(
+            &base.join("app/build/package.json"),
+            r#"{"name": "bundle"}"#,
+        )?;
+        create_file(&base.join("app/build/node_modules/dep/index.js"), "// dep")?;
+
+        let scan = |exclude_names: &[&str], no_exclude_names: &[&str]| {
+            let scanner = Scanner::new(
+                ScanOptions {
+                    exclude_names: exclude_names.iter().map(PathBuf::from).collect(),
+                    no_exclude_names: no_exclude_names.iter().map(PathBuf::from).collect(),
+                    ..ScanOptions::default()
+                },
+                ProjectFilter::Node,
+            );
+            let mut names: Vec<_> = scanner
+                .scan_directory(base)
+                .into_iter()
+                .filter_map(|p| p.name)
+                .collect();
+            names.sort();
+            names
         };
 
-        // vendor/ directory (created by `deno vendor`)
-        let vendor_dir = path.join("vendor");
-        if vendor_dir.exists() {
-            let name = self.extract_deno_project_name(&config_path, errors);
-            return Some(Project::new(
-                ProjectType::Deno,
-                path.to_path_buf(),
-                vec![BuildArtifacts {
-                    path: vendor_dir,
-                    size: 0,
-                }],
-                name,
-            ));
-        }
-
-        // node_modules/ (Deno 2 npm support) — only when no package.json exists
-        let node_modules = path.join("node_modules");
-        if node_modules.exists() && !path.join("package.json").exists() {
-            let name = self.extract_deno_project_name(&config_path, errors);
-            return Some(Project::new(
-                ProjectType::Deno,
-                path.to_path_buf(),
-                vec![BuildArtifacts {
-                    path: node_modules,
-                    size: 0,
-                }],
-                name,
-            ));
-        }
-
-        None
+        assert_eq!(scan(&[], &[]), vec!["app", "dist"]);
+        assert_eq!(scan(&[], &["build"]), vec!["app", "bundle", "dist"]);
+        assert_eq!(scan(&["dist"], &[]), vec!["app"]);
+        Ok(())
     }
 
-    /// Extract the project name from a `deno.json` or `deno.jsonc` file.
-    ///
-    /// Parses the JSON file and reads the top-level `"name"` field.
-    /// Falls back to the directory name if the field is absent or the file cannot be parsed.
-    fn extract_deno_project_name(
-        &self,
-        config_path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        match fs::read_to_string(config_path) {
-            Ok(content) => {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content)
-                    && let Some(name) = json.get("name").and_then(|v| v.as_str())
-                {
-                    return Some(name.to_string());
-                }
-                Self::fallback_to_directory_name(config_path.parent()?)
-            }
-            Err(e) => {
-                self.log_file_error(config_path, &e, errors);
-                Self::fallback_to_directory_name(config_path.parent()?)
-            }
-        }
-    }
+    #[test]
+    fn test_hidden_projects_found_when_allowed() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-    /// Detect a Ruby project in the specified directory.
-    ///
-    /// This method checks for a `Gemfile` alongside a `.bundle/` or `vendor/bundle/`
-    /// directory. When both exist the larger one is selected as the primary artifact.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `Gemfile` file exists in directory
-    /// 2. At least one of `.bundle/` or `vendor/bundle/` directories exists
-    fn detect_ruby_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let gemfile = path.join("Gemfile");
-        if !gemfile.exists() {
-            return None;
+        for dir in [".worktree-main", ".config", ".git"] {
+            create_file(
+                &base.join(dir).join("Cargo.toml"),
+                "[package]\nname = \"hidden\"\nversion = \"0.1.0\"",
+            )?;
+            create_file(&base.join(dir).join("target/dummy"), "content")?;
         }
+        let scan = |scan_hidden, allow_hidden: &[&str]| {
+            let scanner = Scanner::new(
+                ScanOptions {
+                    scan_hidden,
+                    allow_hidden: allow_hidden.iter().map(PathBuf::from).collect(),
+                    ..ScanOptions::default()
+                },
+                ProjectFilter::Rust,
+            );
+            let mut roots: Vec<_> = scanner
+                .scan_directory(base)
+                .into_iter()
+                .map(|p| p.root_path)
+                .collect();
+            roots.sort();
+            roots
+        };
 
-        let bundle_dir = path.join(".bundle");
-        let vendor_bundle_dir = path.join("vendor").join("bundle");
-
-        let build_arts: Vec<BuildArtifacts> =
-            match (bundle_dir.exists(), vendor_bundle_dir.exists()) {
-                (true, true) => {
-                    let bundle_size = crate::utils::calculate_dir_size(&bundle_dir);
-                    let vendor_size = crate::utils::calculate_dir_size(&vendor_bundle_dir);
-                    vec![
-                        BuildArtifacts {
-                            path: bundle_dir,
-                            size: bundle_size,
-                        },
-                        BuildArtifacts {
-                            path: vendor_bundle_dir,
-                            size: vendor_size,
-                        },
-                    ]
-                }
-                (true, false) => vec![BuildArtifacts {
-                    path: bundle_dir,
-                    size: 0,
-                }],
-                (false, true) => vec![BuildArtifacts {
-                    path: vendor_bundle_dir,
-                    size: 0,
-                }],
-                (false, false) => return None,
-            };
-
-        let name = self.extract_ruby_project_name(path, errors);
+        assert!(scan(false, &[]).is_empty());
+        assert_eq!(scan(false, &[".config"]), vec![base.join(".config")]);
 
-        Some(Project::new(
-            ProjectType::Ruby,
-            path.to_path_buf(),
-            build_arts,
-            name,
-        ))
+        // Excluded directories such as .git stay excluded
+        assert_eq!(
+            scan(true, &[]),
+            vec![base.join(".config"), base.join(".worktree-main")]
+        );
+        Ok(())
     }
 
-    /// Extract the project name from a Ruby project directory.
-    ///
-    /// Looks for a `.gemspec` file and parses the `spec.name` or `s.name` assignment.
-    /// Falls back to the directory name.
-    fn extract_ruby_project_name(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let entries = fs::read_dir(path).ok()?;
-        for entry in entries.flatten() {
-            let entry_path = entry.path();
-            if entry_path.is_file()
-                && entry_path.extension().and_then(|e| e.to_str()) == Some("gemspec")
-                && let Some(content) = self.read_file_content(&entry_path, errors)
-            {
-                for line in content.lines() {
-                    let trimmed = line.trim();
-                    if trimmed.contains(".name")
-                        && trimmed.contains('=')
-                        && let Some(name) = Self::extract_quoted_value(trimmed)
-                    {
-                        return Some(name);
-                    }
-                }
-            }
-        }
+    #[test]
+    #[cfg(unix)]
+    fn test_dotcargo_directory_not_skipped_unix() {
+        let scanner = default_scanner(ProjectFilter::All);
 
-        Self::fallback_to_directory_name(path)
+        // .cargo is the exception — hidden but should NOT be skipped.
+        // Verify via the static method.
+        assert!(!scanner.is_hidden_directory_to_skip(Path::new("/home/user/.cargo")));
+
+        // Other dot-dirs ARE skipped
+        assert!(scanner.is_hidden_directory_to_skip(Path::new("/home/user/.local")));
+        assert!(scanner.is_hidden_directory_to_skip(Path::new("/home/user/.npm")));
     }
 
-    /// Detect an Elixir project in the specified directory.
-    ///
-    /// This method checks for the presence of both `mix.exs` and `_build/`
-    /// to identify an Elixir/Mix project.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `mix.exs` file exists in directory
-    /// 2. `_build/` subdirectory exists in directory
-    fn detect_elixir_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let mix_exs = path.join("mix.exs");
-        let build_dir = path.join("_build");
+    // ── Python project detection tests ──────────────────────────────────
 
-        if mix_exs.exists() && build_dir.exists() {
-            let name = self.extract_elixir_project_name(&mix_exs, errors);
+    #[test]
+    fn test_detect_python_with_pyproject_toml() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-            return Some(Project::new(
-                ProjectType::Elixir,
-                path.to_path_buf(),
-                vec![BuildArtifacts {
-                    path: build_dir,
-                    size: 0,
-                }],
-                name,
-            ));
-        }
+        let project = base.join("py-project");
+        create_file(
+            &project.join("pyproject.toml"),
+            "[project]\nname = \"my-py-lib\"\nversion = \"1.0.0\"\n",
+        )?;
+        let pycache = project.join("__pycache__");
+        fs::create_dir_all(&pycache)?;
+        create_file(&pycache.join("module.pyc"), "bytecode")?;
 
-        None
+        let scanner = default_scanner(ProjectFilter::Python);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Python);
+        Ok(())
     }
 
-    /// Extract the project name from a `mix.exs` file.
-    ///
-    /// Looks for the `app: :atom_name` pattern inside the Mix project definition.
-    /// Falls back to the directory name.
-    fn extract_elixir_project_name(
-        &self,
-        mix_exs: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let content = self.read_file_content(mix_exs, errors)?;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.contains("app:")
-                && let Some(pos) = trimmed.find("app:")
-            {
-                let after = trimmed[pos + 4..].trim_start();
-                if let Some(atom) = after.strip_prefix(':') {
-                    // Elixir atom names consist of alphanumeric chars and underscores
-                    let name: String = atom
-                        .chars()
-                        .take_while(|c| c.is_alphanumeric() || *c == '_')
-                        .collect();
-                    if !name.is_empty() {
-                        return Some(name);
-                    }
-                }
-            }
-        }
+    #[test]
+    fn test_detect_python_with_setup_py() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        Self::fallback_to_directory_name(mix_exs.parent()?)
-    }
+        let project = base.join("setup-project");
+        create_file(
+            &project.join("setup.py"),
+            "from setuptools import setup\nsetup(name=\"setup-lib\")\n",
+        )?;
+        let pycache = project.join("__pycache__");
+        fs::create_dir_all(&pycache)?;
+        create_file(&pycache.join("module.pyc"), "bytecode")?;
 
-    /// Detect a PHP project in the specified directory.
-    ///
-    /// This method checks for the presence of both `composer.json` and `vendor/`
-    /// to identify a PHP/Composer project.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `composer.json` file exists in directory
-    /// 2. `vendor/` subdirectory exists in directory
-    fn detect_php_project(&self, path: &Path, errors: &Arc<Mutex<Vec<String>>>) -> Option<Project> {
-        let composer_json = path.join("composer.json");
-        let vendor_dir = path.join("vendor");
-
-        if composer_json.exists() && vendor_dir.exists() {
-            let name = self.extract_php_project_name(&composer_json, errors);
+        let scanner = default_scanner(ProjectFilter::Python);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        Ok(())
+    }
 
-            return Some(Project::new(
-                ProjectType::Php,
-                path.to_path_buf(),
-                vec![BuildArtifacts {
-                    path: vendor_dir,
-                    size: 0,
-                }],
-                name,
-            ));
-        }
+    #[test]
+    fn test_detect_python_with_pipfile() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        None
-    }
+        let project = base.join("pipenv-project");
+        create_file(
+            &project.join("Pipfile"),
+            "[[source]]\nurl = \"https://pypi.org/simple\"",
+        )?;
+        let pycache = project.join("__pycache__");
+        fs::create_dir_all(&pycache)?;
+        create_file(&pycache.join("module.pyc"), "bytecode")?;
 
-    /// Extract the project name from a `composer.json` file.
-    ///
-    /// Parses the JSON and reads the top-level `"name"` field.
-    /// The name is typically `vendor/package`; only the package component is returned.
-    /// Falls back to the directory name if the field is absent or the file cannot be parsed.
-    fn extract_php_project_name(
-        &self,
-        composer_json: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        match fs::read_to_string(composer_json) {
-            Ok(content) => {
-                if let Ok(json) = from_str::<Value>(&content)
-                    && let Some(name) = json.get("name").and_then(|v| v.as_str())
-                {
-                    // composer.json name is "vendor/package"; return just the package part.
-                    let package = name.split('/').next_back().unwrap_or(name);
-                    return Some(package.to_string());
-                }
-                Self::fallback_to_directory_name(composer_json.parent()?)
-            }
-            Err(e) => {
-                self.log_file_error(composer_json, &e, errors);
-                Self::fallback_to_directory_name(composer_json.parent()?)
-            }
-        }
+        let scanner = default_scanner(ProjectFilter::Python);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        Ok(())
     }
 
-    /// Detect a Haskell project in the specified directory.
-    ///
-    /// Supports both Stack and Cabal build systems:
-    /// - Stack: `stack.yaml` + `.stack-work/`
-    /// - Cabal: (`cabal.project` or `*.cabal` file) + `dist-newstyle/`
-    fn detect_haskell_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        // Stack project: stack.yaml + .stack-work/
-        let stack_yaml = path.join("stack.yaml");
-        let stack_work = path.join(".stack-work");
-
-        if stack_yaml.exists() && stack_work.exists() {
-            let name = self.extract_haskell_project_name(path, errors);
-            return Some(Project::new(
-                ProjectType::Haskell,
-                path.to_path_buf(),
-                vec![BuildArtifacts {
-                    path: stack_work,
-                    size: 0,
-                }],
-                name,
-            ));
-        }
+    #[test]
+    fn test_detect_conda_project_checkpoints_and_envs() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = tmp.path().join("analysis");
+        create_file(&project.join("environment.yml"), "name: analysis\n")?;
+        create_file(
+            &project.join("notebooks/.ipynb_checkpoints/eda-checkpoint.ipynb"),
+            "{}",
+        )?;
+        create_file(&project.join("env/conda-meta/history"), "")?;
+        create_file(&project.join(".conda/bin/python"), "")?;
+
+        let paths = |scanner: Scanner| -> Vec<PathBuf> {
+            let projects = scanner.scan_directory(tmp.path());
+            projects[0]
+                .build_arts
+                .iter()
+                .map(|a| a.path.clone())
+                .collect()
+        };
 
-        // Cabal project: (cabal.project OR *.cabal file) + dist-newstyle/
-        let dist_newstyle = path.join("dist-newstyle");
-        if dist_newstyle.exists() {
-            let has_cabal_project = path.join("cabal.project").exists();
-            let has_cabal_file = Self::find_file_with_extension(path, "cabal").is_some();
-
-            if has_cabal_project || has_cabal_file {
-                let name = self.extract_haskell_project_name(path, errors);
-                return Some(Project::new(
-                    ProjectType::Haskell,
-                    path.to_path_buf(),
-                    vec![BuildArtifacts {
-                        path: dist_newstyle,
-                        size: 0,
-                    }],
-                    name,
-                ));
-            }
-        }
+        assert_eq!(
+            paths(default_scanner(ProjectFilter::Python)),
+            vec![project.join("notebooks/.ipynb_checkpoints")]
+        );
 
-        None
+        // Only directories with a conda-meta/ are environments
+        let scanner = Scanner::new(
+            ScanOptions {
+                conda_envs: true,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::Python,
+        );
+        assert_eq!(
+            paths(scanner),
+            vec![
+                project.join("notebooks/.ipynb_checkpoints"),
+                project.join("env"),
+            ]
+        );
+        Ok(())
     }
 
-    /// Extract the project name from a Haskell project directory.
-    ///
-    /// Looks for a `*.cabal` file and reads the `name:` field from it.
-    /// Also checks `package.yaml` (hpack). Falls back to the directory name.
-    fn extract_haskell_project_name(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        // Try *.cabal file first
-        if let Some(cabal_file) = Self::find_file_with_extension(path, "cabal")
-            && let Some(content) = self.read_file_content(&cabal_file, errors)
-        {
-            for line in content.lines() {
-                let trimmed = line.trim();
-                if let Some(rest) = trimmed.strip_prefix("name:") {
-                    let name = rest.trim().to_string();
-                    if !name.is_empty() {
-                        return Some(name);
-                    }
-                }
-            }
-        }
-
-        // Try package.yaml (hpack)
-        let package_yaml = path.join("package.yaml");
-        if package_yaml.exists()
-            && let Some(content) = self.read_file_content(&package_yaml, errors)
-        {
-            for line in content.lines() {
-                let trimmed = line.trim();
-                if let Some(rest) = trimmed.strip_prefix("name:") {
-                    let name = rest.trim().trim_matches('"').trim_matches('\'').to_string();
-                    if !name.is_empty() {
-                        return Some(name);
-                    }
-                }
-            }
-        }
+    #[test]
+    fn test_detect_python_venvs_only_when_included() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = tmp.path().join("service");
+        create_file(&project.join("requirements.txt"), "flask\n")?;
+        create_file(&project.join(".venv/pyvenv.cfg"), "home = /usr")?;
+        create_file(&project.join("venv/pyvenv.cfg"), "home = /usr")?;
+
+        // A project with nothing but its virtual environments isn't listed
+        assert!(
+            default_scanner(ProjectFilter::Python)
+                .scan_directory(tmp.path())
+                .is_empty()
+        );
 
-        Self::fallback_to_directory_name(path)
+        let scanner = Scanner::new(
+            ScanOptions {
+                python_venvs: true,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::Python,
+        );
+        let projects = scanner.scan_directory(tmp.path());
+        let paths: Vec<_> = projects[0].build_arts.iter().map(|a| &a.path).collect();
+        assert_eq!(paths, vec![&project.join("venv"), &project.join(".venv")]);
+        Ok(())
     }
 
-    /// Detect a Dart/Flutter project in the specified directory.
-    ///
-    /// This method checks for a `pubspec.yaml` manifest alongside `.dart_tool/`
-    /// and/or `build/` directories.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `pubspec.yaml` file exists in directory
-    /// 2. At least one of `.dart_tool/` or `build/` exists
-    fn detect_dart_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let pubspec_yaml = path.join("pubspec.yaml");
-        if !pubspec_yaml.exists() {
-            return None;
-        }
-
-        let dart_tool = path.join(".dart_tool");
-        let build_dir = path.join("build");
+    #[test]
+    fn test_excluded_artifacts_are_not_detected() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let web = tmp.path().join("web");
+        create_file(&web.join("package.json"), r#"{"name": "web"}"#)?;
+        create_file(
+            &web.join("node_modules/dep/index.js"),
+            "module.exports = {};",
+        )?;
+        create_file(&web.join("dist/index.js"), "console.log(1);")?;
+        let service = tmp.path().join("service");
+        create_file(&service.join("requirements.txt"), "flask\n")?;
+        create_file(&service.join("dist/app.whl"), "wheel")?;
 
-        let build_arts: Vec<BuildArtifacts> = match (dart_tool.exists(), build_dir.exists()) {
-            (true, true) => {
-                let dart_size = crate::utils::calculate_dir_size(&dart_tool);
-                let build_size = crate::utils::calculate_dir_size(&build_dir);
-                vec![
-                    BuildArtifacts {
-                        path: dart_tool,
-                        size: dart_size,
-                    },
-                    BuildArtifacts {
-                        path: build_dir,
-                        size: build_size,
-                    },
-                ]
-            }
-            (true, false) => vec![BuildArtifacts {
-                path: dart_tool,
-                size: 0,
-            }],
-            (false, true) => vec![BuildArtifacts {
-                path: build_dir,
-                size: 0,
-            }],
-            (false, false) => return None,
-        };
+        let mut excluded_artifacts = std::collections::HashMap::new();
+        excluded_artifacts.insert(ProjectFilter::Node, vec!["./dist/".to_string()]);
+        let scanner = Scanner::new(
+            ScanOptions {
+                excluded_artifacts,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::All,
+        );
+        let mut projects = scanner.scan_directory(tmp.path());
+        projects.sort_by(|a, b| a.root_path.cmp(&b.root_path));
 
-        let name = self.extract_dart_project_name(&pubspec_yaml, errors);
+        let paths: Vec<_> = projects
+            .iter()
+            .flat_map(|p| &p.build_arts)
+            .map(|a| &a.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![&service.join("dist"), &web.join("node_modules")]
+        );
 
-        Some(Project::new(
-            ProjectType::Dart,
-            path.to_path_buf(),
-            build_arts,
-            name,
-        ))
+        // A project left with nothing to clean isn't listed
+        let mut excluded_artifacts = std::collections::HashMap::new();
+        excluded_artifacts.insert(ProjectFilter::All, vec!["dist".to_string()]);
+        let scanner = Scanner::new(
+            ScanOptions {
+                excluded_artifacts,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::Python,
+        );
+        assert!(scanner.scan_directory(tmp.path()).is_empty());
+        Ok(())
     }
 
-    /// Extract the project name from a `pubspec.yaml` file.
-    ///
-    /// Reads the `name:` field from the YAML file using simple line parsing.
-    /// Falls back to the directory name.
-    fn extract_dart_project_name(
-        &self,
-        pubspec_yaml: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let content = self.read_file_content(pubspec_yaml, errors)?;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if let Some(rest) = trimmed.strip_prefix("name:") {
-                let name = rest.trim().trim_matches('"').trim_matches('\'').to_string();
-                if !name.is_empty() {
-                    return Some(name);
-                }
-            }
-        }
-
-        Self::fallback_to_directory_name(pubspec_yaml.parent()?)
-    }
+    // ── Go project detection tests ──────────────────────────────────────
 
-    /// Detect a Zig project in the specified directory.
-    ///
-    /// This method checks for a `build.zig` file alongside `zig-cache/` and/or
-    /// `zig-out/` directories.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `build.zig` file exists in directory
-    /// 2. At least one of `zig-cache/` or `zig-out/` exists
-    fn detect_zig_project(path: &Path) -> Option<Project> {
-        let build_zig = path.join("build.zig");
-        if !build_zig.exists() {
-            return None;
-        }
+    #[test]
+    fn test_detect_go_extracts_module_name() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        let zig_cache = path.join("zig-cache");
-        let zig_out = path.join("zig-out");
+        let project = base.join("go-service");
+        create_file(
+            &project.join("go.mod"),
+            "module github.com/user/my-service\n\ngo 1.21\n",
+        )?;
+        let vendor = project.join("vendor");
+        fs::create_dir_all(&vendor)?;
+        create_file(&vendor.join("modules.txt"), "vendor manifest")?;
 
-        let build_arts: Vec<BuildArtifacts> = match (zig_cache.exists(), zig_out.exists()) {
-            (true, true) => {
-                let cache_size = crate::utils::calculate_dir_size(&zig_cache);
-                let out_size = crate::utils::calculate_dir_size(&zig_out);
-                vec![
-                    BuildArtifacts {
-                        path: zig_cache,
-                        size: cache_size,
-                    },
-                    BuildArtifacts {
-                        path: zig_out,
-                        size: out_size,
-                    },
-                ]
-            }
-            (true, false) => vec![BuildArtifacts {
-                path: zig_cache,
-                size: 0,
-            }],
-            (false, true) => vec![BuildArtifacts {
-                path: zig_out,
-                size: 0,
-            }],
-            (false, false) => return None,
-        };
+        let scanner = default_scanner(ProjectFilter::Go);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        // Should extract last path component as name
+        assert_eq!(projects[0].name.as_deref(), Some("my-service"));
+        Ok(())
+    }
 
-        let name = Self::fallback_to_directory_name(path);
+    #[test]
+    fn test_detect_go_without_vendor() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        Some(Project::new(
-            ProjectType::Zig,
-            path.to_path_buf(),
-            build_arts,
-            name,
-        ))
+        let built = base.join("built");
+        create_file(&built.join("go.mod"), "module example.com/built\n")?;
+        create_file(&built.join("bin/server"), "\x7fELF binary")?;
+        // Scripts in bin/ make it part of the sources
+        let scripted = base.join("scripted");
+        create_file(&scripted.join("go.mod"), "module example.com/scripted\n")?;
+        create_file(&scripted.join("bin/server"), "\x7fELF binary")?;
+        create_file(&scripted.join("bin/deploy.sh"), "#!/bin/sh\n")?;
+        let plain = base.join("plain");
+        create_file(&plain.join("go.mod"), "module example.com/plain\n")?;
+        create_file(&plain.join("main.go"), "package main\n")?;
+
+        let projects = default_scanner(ProjectFilter::Go).scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("built"));
+        let paths: Vec<_> = projects[0].build_arts.iter().map(|a| &a.path).collect();
+        assert_eq!(paths, vec![&built.join("bin")]);
+        Ok(())
     }
 
-    /// Detect a Scala project in the specified directory.
-    ///
-    /// This method checks for the presence of both `build.sbt` and `target/`
-    /// to identify an sbt-based Scala project.
-    ///
-    /// # Detection Criteria
-    ///
-    /// 1. `build.sbt` file exists in directory
-    /// 2. `target/` subdirectory exists in directory
-    fn detect_scala_project(
-        &self,
-        path: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<Project> {
-        let build_sbt = path.join("build.sbt");
-        let target_dir = path.join("target");
+    // ── Java/Kotlin project detection tests ────────────────────────────
 
-        if build_sbt.exists() && target_dir.exists() {
-            let name = self.extract_scala_project_name(&build_sbt, errors);
+    #[test]
+    fn test_detect_java_maven_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-            return Some(Project::new(
-                ProjectType::Scala,
-                path.to_path_buf(),
-                vec![BuildArtifacts {
-                    path: target_dir,
-                    size: 0,
-                }],
-                name,
-            ));
-        }
+        let project = base.join("java-maven");
+        create_file(
+            &project.join("pom.xml"),
+            "<project>\n  <artifactId>my-java-app</artifactId>\n</project>",
+        )?;
+        create_file(&project.join("target/classes/Main.class"), "bytecode")?;
 
-        None
+        let scanner = default_scanner(ProjectFilter::Java);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Java);
+        assert_eq!(projects[0].name.as_deref(), Some("my-java-app"));
+        Ok(())
     }
 
-    /// Extract the project name from a `build.sbt` file.
-    ///
-    /// Looks for a `name := "..."` assignment in the file.
-    /// Falls back to the directory name.
-    fn extract_scala_project_name(
-        &self,
-        build_sbt: &Path,
-        errors: &Arc<Mutex<Vec<String>>>,
-    ) -> Option<String> {
-        let content = self.read_file_content(build_sbt, errors)?;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("name")
-                && trimmed.contains(":=")
-                && let Some(name) = Self::extract_quoted_value(trimmed)
-            {
-                return Some(name);
-            }
-        }
+    #[test]
+    fn test_detect_java_gradle_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("java-gradle");
+        create_file(&project.join("build.gradle"), "apply plugin: 'java'")?;
+        create_file(
+            &project.join("settings.gradle"),
+            "rootProject.name = \"my-gradle-app\"",
+        )?;
+        create_file(&project.join("build/classes/main/Main.class"), "bytecode")?;
 
-        Self::fallback_to_directory_name(build_sbt.parent()?)
+        let scanner = default_scanner(ProjectFilter::Java);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Java);
+        assert_eq!(projects[0].name.as_deref(), Some("my-gradle-app"));
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::PathBuf;
-    use tempfile::TempDir;
+    #[test]
+    fn test_detect_java_gradle_kts_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-    /// Create a scanner with default options and the given filter.
-    fn default_scanner(filter: ProjectFilter) -> Scanner {
-        Scanner::new(
-            ScanOptions {
-                verbose: false,
-                threads: 1,
-                skip: vec![],
-                max_depth: None,
-            },
-            filter,
-        )
-    }
+        let project = base.join("kotlin-gradle");
+        create_file(
+            &project.join("build.gradle.kts"),
+            "plugins { kotlin(\"jvm\") }",
+        )?;
+        create_file(
+            &project.join("settings.gradle.kts"),
+            "rootProject.name = \"my-kotlin-app\"",
+        )?;
+        create_file(
+            &project.join("build/classes/kotlin/main/MainKt.class"),
+            "bytecode",
+        )?;
 
-    /// Helper to create a file with content, ensuring parent dirs exist.
-    fn create_file(path: &Path, content: &str) -> anyhow::Result<()> {
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        fs::write(path, content)?;
+        let scanner = default_scanner(ProjectFilter::Java);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Java);
+        assert_eq!(projects[0].name.as_deref(), Some("my-kotlin-app"));
         Ok(())
     }
 
-    // ── Static helper method tests ──────────────────────────────────────
+    // ── C/C++ project detection tests ────────────────────────────────────
 
     #[test]
-    fn test_is_hidden_directory_to_skip() {
-        // Hidden directories should be skipped
-        assert!(Scanner::is_hidden_directory_to_skip(Path::new(
-            "/some/.hidden"
-        )));
-        assert!(Scanner::is_hidden_directory_to_skip(Path::new(
-            "/some/.git"
-        )));
-        assert!(Scanner::is_hidden_directory_to_skip(Path::new(
-            "/some/.svn"
-        )));
-        assert!(Scanner::is_hidden_directory_to_skip(Path::new(".env")));
-
-        // .cargo is the special exception — should NOT be skipped
-        assert!(!Scanner::is_hidden_directory_to_skip(Path::new(
-            "/home/user/.cargo"
-        )));
-        assert!(!Scanner::is_hidden_directory_to_skip(Path::new(".cargo")));
+    fn test_detect_cpp_cmake_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        // Non-hidden directories should not be skipped
-        assert!(!Scanner::is_hidden_directory_to_skip(Path::new(
-            "/some/visible"
-        )));
-        assert!(!Scanner::is_hidden_directory_to_skip(Path::new("src")));
+        let project = base.join("cpp-cmake");
+        create_file(
+            &project.join("CMakeLists.txt"),
+            "project(my-cpp-lib)\ncmake_minimum_required(VERSION 3.10)",
+        )?;
+        create_file(&project.join("build/CMakeCache.txt"), "cache")?;
+
+        let scanner = default_scanner(ProjectFilter::Cpp);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Cpp);
+        assert_eq!(projects[0].name.as_deref(), Some("my-cpp-lib"));
+        Ok(())
     }
 
     #[test]
-    fn test_is_excluded_directory() {
-        // Build/artifact directories should be excluded
-        assert!(Scanner::is_excluded_directory(Path::new("/some/target")));
-        assert!(Scanner::is_excluded_directory(Path::new(
-            "/some/node_modules"
-        )));
-        assert!(Scanner::is_excluded_directory(Path::new(
-            "/some/__pycache__"
-        )));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/vendor")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/build")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/dist")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/out")));
+    fn test_detect_cpp_makefile_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        // VCS directories should be excluded
-        assert!(Scanner::is_excluded_directory(Path::new("/some/.git")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/.svn")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/.hg")));
+        let project = base.join("cpp-make");
+        create_file(&project.join("Makefile"), "all:\n\tg++ -o main main.cpp")?;
+        create_file(&project.join("build/main.o"), "object")?;
 
-        // Python-specific directories
-        assert!(Scanner::is_excluded_directory(Path::new(
-            "/some/.pytest_cache"
-        )));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/.tox")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/.eggs")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/.coverage")));
+        let scanner = default_scanner(ProjectFilter::Cpp);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Cpp);
+        Ok(())
+    }
 
-        // Virtual environments
-        assert!(Scanner::is_excluded_directory(Path::new("/some/venv")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/.venv")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/env")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/.env")));
+    #[test]
+    fn test_detect_visual_studio_cpp_solution() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        // Temp directories
-        assert!(Scanner::is_excluded_directory(Path::new("/some/temp")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/tmp")));
+        let project = base.join("Renderer");
+        create_file(
+            &project.join("Renderer.sln"),
+            "Project(\"{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}\") = \"Renderer\", \"Renderer.vcxproj\"\n",
+        )?;
+        create_file(&project.join("Renderer.vcxproj"), "<Project />")?;
+        create_file(&project.join(".vs/Renderer/v17/Browse.VC.db"), "cache")?;
+        create_file(&project.join("x64/Debug/Renderer.exe"), "binary")?;
+        create_file(&project.join("x64/Release/Renderer.exe"), "binary")?;
 
-        // Non-excluded directories
-        assert!(!Scanner::is_excluded_directory(Path::new("/some/src")));
-        assert!(!Scanner::is_excluded_directory(Path::new("/some/lib")));
-        assert!(!Scanner::is_excluded_directory(Path::new("/some/app")));
-        assert!(!Scanner::is_excluded_directory(Path::new("/some/tests")));
+        let scanner = default_scanner(ProjectFilter::Cpp);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Cpp);
+        assert_eq!(projects[0].name.as_deref(), Some("Renderer"));
+
+        let paths: Vec<_> = projects[0].build_arts.iter().map(|a| &a.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                &project.join(".vs"),
+                &project.join("x64/Debug"),
+                &project.join("x64/Release"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_csharp_solution_not_detected_as_cpp() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("WebApi");
+        create_file(
+            &project.join("WebApi.sln"),
+            "Project(\"{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}\") = \"WebApi\", \"WebApi.csproj\"\n",
+        )?;
+        create_file(&project.join(".vs/WebApi/v17/.suo"), "cache")?;
+        create_file(&project.join("Release/WebApi.dll"), "binary")?;
+
+        let scanner = default_scanner(ProjectFilter::Cpp);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 0);
+        Ok(())
+    }
+
+    // ── Swift project detection tests ────────────────────────────────────
+
+    #[test]
+    fn test_detect_swift_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("swift-pkg");
+        create_file(
+            &project.join("Package.swift"),
+            "let package = Package(\n    name: \"my-swift-lib\",\n    targets: []\n)",
+        )?;
+        create_file(&project.join(".build/debug/my-swift-lib"), "binary")?;
+
+        let scanner = default_scanner(ProjectFilter::Swift);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Swift);
+        assert_eq!(projects[0].name.as_deref(), Some("my-swift-lib"));
+        Ok(())
+    }
+
+    // ── .NET/C# project detection tests ──────────────────────────────────
+
+    #[test]
+    fn test_detect_dotnet_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("dotnet-app");
+        create_file(
+            &project.join("MyApp.csproj"),
+            "<Project Sdk=\"Microsoft.NET.Sdk\">\n</Project>",
+        )?;
+        create_file(&project.join("bin/Debug/net8.0/MyApp.dll"), "assembly")?;
+        create_file(&project.join("obj/Debug/net8.0/MyApp.dll"), "intermediate")?;
+
+        let scanner = default_scanner(ProjectFilter::DotNet);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::DotNet);
+        assert_eq!(projects[0].name.as_deref(), Some("MyApp"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_dotnet_project_obj_only() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("dotnet-obj-only");
+        create_file(
+            &project.join("Lib.csproj"),
+            "<Project Sdk=\"Microsoft.NET.Sdk\">\n</Project>",
+        )?;
+        create_file(&project.join("obj/Debug/net8.0/Lib.dll"), "intermediate")?;
+
+        let scanner = default_scanner(ProjectFilter::DotNet);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::DotNet);
+        assert_eq!(projects[0].name.as_deref(), Some("Lib"));
+        Ok(())
+    }
+
+    // ── Excluded directory tests ─────────────────────────────────────────
+
+    #[test]
+    fn test_obj_directory_is_excluded() {
+        assert!(Scanner::is_excluded_directory(Path::new("/some/obj")));
+    }
+
+    // ── Cross-platform collect_build_dir_stats ──────────────────────────
+
+    #[test]
+    fn test_collect_build_dir_stats_empty() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let empty_dir = tmp.path().join("empty");
+        fs::create_dir_all(&empty_dir)?;
+
+        assert_eq!(
+            default_scanner(ProjectFilter::All).collect_build_dir_stats(&empty_dir),
+            DirStats::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_build_dir_stats_nonexistent() {
+        assert_eq!(
+            default_scanner(ProjectFilter::All)
+                .collect_build_dir_stats(Path::new("/nonexistent/path")),
+            DirStats::default()
+        );
+    }
+
+    #[test]
+    fn test_collect_build_dir_stats_with_nested_files() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let dir = tmp.path().join("nested");
+
+        create_file(&dir.join("file1.txt"), "hello")?; // 5 bytes
+        create_file(&dir.join("sub/file2.txt"), "world!")?; // 6 bytes
+        create_file(&dir.join("sub/deep/file3.txt"), "!")?; // 1 byte
+
+        let stats = default_scanner(ProjectFilter::All).collect_build_dir_stats(&dir);
+        assert_eq!(stats.size, 12);
+        assert_eq!(stats.files, 3);
+        assert_eq!(stats.dirs, 2);
+        assert!(stats.newest_file.is_some());
+
+        let native = Scanner::new(
+            ScanOptions {
+                size_backend: SizeBackendKind::Native,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::All,
+        );
+        assert_eq!(native.collect_build_dir_stats(&dir), stats);
+        Ok(())
+    }
+
+    // ── Quiet mode ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_scanner_quiet_mode() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("quiet-project");
+        create_file(
+            &project.join("Cargo.toml"),
+            "[package]\nname = \"quiet\"\nversion = \"0.1.0\"",
+        )?;
+        create_file(&project.join("target/dummy"), "content")?;
+
+        let scanner = default_scanner(ProjectFilter::Rust).with_quiet(true);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_streams_more_entries_than_the_queue_holds() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        for i in 0..ENTRY_QUEUE_CAPACITY + 200 {
+            fs::create_dir(base.join(format!("dir-{i}")))?;
+        }
+        for name in ["dir-3", "dir-700", "dir-1100"] {
+            create_file(
+                &base.join(name).join("Cargo.toml"),
+                &format!("[package]\nname = \"{name}\""),
+            )?;
+            create_file(&base.join(name).join("target/dummy"), "content")?;
+        }
+
+        let scanner = default_scanner(ProjectFilter::All).with_quiet(true);
+        let mut names: Vec<_> = scanner
+            .scan_directory(base)
+            .into_iter()
+            .filter_map(|p| p.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["dir-1100", "dir-3", "dir-700"]);
+        Ok(())
+    }
+
+    // ── Ruby project detection tests ─────────────────────────────────────
+
+    #[test]
+    fn test_detect_ruby_with_vendor_bundle() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("ruby-project");
+        create_file(
+            &project.join("Gemfile"),
+            "source 'https://rubygems.org'\ngem 'rails'",
+        )?;
+        create_file(
+            &project.join("my-app.gemspec"),
+            "Gem::Specification.new do |spec|\n  spec.name = \"my-ruby-gem\"\nend",
+        )?;
+        create_file(
+            &project.join("vendor/bundle/ruby/3.2.0/gems/rails/init.rb"),
+            "# rails",
+        )?;
+
+        let scanner = default_scanner(ProjectFilter::Ruby);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Ruby);
+        assert_eq!(projects[0].name.as_deref(), Some("my-ruby-gem"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_ruby_with_dot_bundle() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("ruby-dot-bundle");
+        create_file(&project.join("Gemfile"), "source 'https://rubygems.org'")?;
+        create_file(&project.join(".bundle/gems/rack-2.0/lib/rack.rb"), "# rack")?;
+
+        let scanner = default_scanner(ProjectFilter::Ruby);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Ruby);
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_ruby_no_artifact_not_detected() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        // Gemfile exists but no .bundle/ or vendor/bundle/
+        let project = base.join("gemfile-only");
+        create_file(&project.join("Gemfile"), "source 'https://rubygems.org'")?;
+
+        let scanner = default_scanner(ProjectFilter::Ruby);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_ruby_fallback_to_dir_name() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("my-ruby-app");
+        create_file(&project.join("Gemfile"), "source 'https://rubygems.org'")?;
+        create_file(
+            &project.join("vendor/bundle/gems/sinatra/lib/sinatra.rb"),
+            "# sinatra",
+        )?;
+
+        let scanner = default_scanner(ProjectFilter::Ruby);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("my-ruby-app"));
+        Ok(())
+    }
+
+    // ── Elixir project detection tests ───────────────────────────────────
+
+    #[test]
+    fn test_detect_elixir_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("elixir-project");
+        create_file(
+            &project.join("mix.exs"),
+            "defmodule MyApp.MixProject do\n  def project do\n    [app: :my_app,\n     version: \"0.1.0\"]\n  end\nend",
+        )?;
+        create_file(
+            &project.join("_build/dev/lib/my_app/.mix/compile.elixir"),
+            "# build",
+        )?;
+
+        let scanner = default_scanner(ProjectFilter::Elixir);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Elixir);
+        assert_eq!(projects[0].name.as_deref(), Some("my_app"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_elixir_no_build_not_detected() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("mix-only");
+        create_file(
+            &project.join("mix.exs"),
+            "defmodule MixOnly.MixProject do\n  def project do\n    [app: :mix_only]\n  end\nend",
+        )?;
+
+        let scanner = default_scanner(ProjectFilter::Elixir);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_elixir_fallback_to_dir_name() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("my_elixir_project");
+        create_file(&project.join("mix.exs"), "# minimal mix.exs without app:")?;
+        create_file(
+            &project.join("_build/prod/lib/my_elixir_project.beam"),
+            "bytecode",
+        )?;
+
+        let scanner = default_scanner(ProjectFilter::Elixir);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("my_elixir_project"));
+        Ok(())
+    }
+
+    // ── Deno project detection tests ─────────────────────────────────────
+
+    #[test]
+    fn test_detect_deno_with_vendor() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("deno-project");
+        create_file(
+            &project.join("deno.json"),
+            r#"{"name": "my-deno-app", "imports": {}}"#,
+        )?;
+        create_file(&project.join("vendor/modules.json"), "{}")?;
+
+        let scanner = default_scanner(ProjectFilter::Deno);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Deno);
+        assert_eq!(projects[0].name.as_deref(), Some("my-deno-app"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_deno_jsonc_config() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("deno-jsonc-project");
+        create_file(
+            &project.join("deno.jsonc"),
+            r#"{"name": "my-deno-jsonc-app", "tasks": {}}"#,
+        )?;
+        create_file(&project.join("vendor/modules.json"), "{}")?;
+
+        let scanner = default_scanner(ProjectFilter::Deno);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Deno);
+        assert_eq!(projects[0].name.as_deref(), Some("my-deno-jsonc-app"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_deno_node_modules_without_package_json() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("deno-npm-project");
+        create_file(&project.join("deno.json"), r#"{"nodeModulesDir": "auto"}"#)?;
+        create_file(
+            &project.join("node_modules/.deno/lodash/index.js"),
+            "// lodash",
+        )?;
+
+        let scanner = default_scanner(ProjectFilter::Deno);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Deno);
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_deno_node_modules_with_package_json_becomes_node() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        // deno.json + package.json + node_modules → Node project (not Deno)
+        let project = base.join("ambiguous-project");
+        create_file(&project.join("deno.json"), r"{}")?;
+        create_file(&project.join("package.json"), r#"{"name": "my-node-app"}"#)?;
+        create_file(&project.join("node_modules/dep/index.js"), "// dep")?;
+
+        let scanner = default_scanner(ProjectFilter::All);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Node);
+        Ok(())
     }
 
+    // ── Node.js workspace tests ─────────────────────────────────────────
+
     #[test]
-    fn test_extract_quoted_value() {
-        assert_eq!(
-            Scanner::extract_quoted_value(r#"name = "my-project""#),
-            Some("my-project".to_string())
-        );
+    fn test_node_workspace_reported_once_at_root() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let root = base.join("monorepo");
+        create_file(
+            &root.join("package.json"),
+            r#"{"name": "monorepo", "workspaces": ["packages/*"]}"#,
+        )?;
+        create_file(&root.join("node_modules/dep/index.js"), "// hoisted")?;
+        for member in ["web", "api"] {
+            let dir = root.join("packages").join(member);
+            create_file(
+                &dir.join("package.json"),
+                &format!(r#"{{"name": "{member}"}}"#),
+            )?;
+            create_file(&dir.join("node_modules/local/index.js"), "// local")?;
+        }
+
+        let scanner = default_scanner(ProjectFilter::Node);
+        let projects = scanner.scan_directory(base);
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("monorepo"));
+        assert_eq!(projects[0].build_arts.len(), 3);
+        assert_eq!(projects[0].build_arts[0].path, root.join("node_modules"));
         assert_eq!(
-            Scanner::extract_quoted_value(r#"name = "with spaces""#),
-            Some("with spaces".to_string())
+            projects[0].build_arts[1].path,
+            root.join("packages/api/node_modules")
         );
-        assert_eq!(Scanner::extract_quoted_value("no quotes here"), None);
-        // Single quote mark is not a pair
-        assert_eq!(Scanner::extract_quoted_value(r#"only "one"#), None);
+        assert_eq!(projects[0].total_size(), 10 + 2 * 8);
+        Ok(())
     }
 
     #[test]
-    fn test_is_name_line() {
-        assert!(Scanner::is_name_line("name = \"test\""));
-        assert!(Scanner::is_name_line("name=\"test\""));
-        assert!(!Scanner::is_name_line("version = \"1.0\""));
-        assert!(!Scanner::is_name_line("# name = \"commented\""));
-        assert!(!Scanner::is_name_line("name: \"yaml style\""));
-    }
+    fn test_node_framework_output_is_a_separate_artifact() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-    #[test]
-    fn test_parse_toml_name_field() {
-        let content = "[package]\nname = \"test-project\"\nversion = \"0.1.0\"\n";
-        assert_eq!(
-            Scanner::parse_toml_name_field(content),
-            Some("test-project".to_string())
-        );
+        let root = base.join("site");
+        create_file(
+            &root.join("package.json"),
+            r#"{"name": "site", "workspaces": ["apps/*"]}"#,
+        )?;
+        create_file(&root.join("node_modules/next/index.js"), "// dep")?;
+        create_file(&root.join(".next/server/page.js"), "// page")?;
+        // A member with its dependencies hoisted, but a build of its own
+        create_file(&root.join("apps/docs/package.json"), r#"{"name": "docs"}"#)?;
+        create_file(
+            &root.join("apps/docs/storybook-static/index.html"),
+            "<html>",
+        )?;
+        // Framework output is enough to be detected without node_modules
+        create_file(&base.join("kit/package.json"), r#"{"name": "kit"}"#)?;
+        create_file(&base.join("kit/.svelte-kit/output/app.js"), "// app")?;
 
-        let no_name = "[package]\nversion = \"0.1.0\"\n";
-        assert_eq!(Scanner::parse_toml_name_field(no_name), None);
+        let scanner = default_scanner(ProjectFilter::Node);
+        let mut projects = scanner.scan_directory(base);
+        projects.sort_by(|a, b| a.root_path.cmp(&b.root_path));
 
-        let empty = "";
-        assert_eq!(Scanner::parse_toml_name_field(empty), None);
+        let artifacts: Vec<Vec<PathBuf>> = projects
+            .iter()
+            .map(|p| p.build_arts.iter().map(|a| a.path.clone()).collect())
+            .collect();
+        assert_eq!(
+            artifacts,
+            vec![
+                vec![base.join("kit/.svelte-kit")],
+                vec![
+                    root.join("node_modules"),
+                    root.join(".next"),
+                    root.join("apps/docs/storybook-static"),
+                ],
+            ]
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_extract_name_from_cfg_content() {
-        let content = "[metadata]\nname = my-package\nversion = 1.0\n";
-        assert_eq!(
-            Scanner::extract_name_from_cfg_content(content),
-            Some("my-package".to_string())
-        );
+    fn test_pnpm_workspace_with_exclusion() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let root = base.join("pnpm-repo");
+        create_file(&root.join("package.json"), r#"{"name": "pnpm-repo"}"#)?;
+        create_file(
+            &root.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'packages/*'\n  - \"!packages/legacy\" # not migrated\n\ncatalog:\n  react: ^18\n",
+        )?;
+        create_file(&root.join("node_modules/.pnpm/dep/index.js"), "// store")?;
+        create_file(&root.join("packages/ui/package.json"), r#"{"name": "ui"}"#)?;
+        create_file(&root.join("packages/ui/node_modules/.bin/tool"), "#!")?;
+        create_file(
+            &root.join("packages/legacy/package.json"),
+            r#"{"name": "legacy"}"#,
+        )?;
+        create_file(&root.join("packages/legacy/node_modules/dep.js"), "//")?;
 
-        // Name in wrong section should not be found
-        let wrong_section = "[options]\nname = not-this\n";
-        assert_eq!(Scanner::extract_name_from_cfg_content(wrong_section), None);
+        let scanner = default_scanner(ProjectFilter::Node);
+        let mut projects = scanner.scan_directory(base);
+        projects.sort_by(|a, b| a.root_path.cmp(&b.root_path));
 
-        // Multiple sections — name must be in [metadata]
-        let multi = "[options]\nkey = val\n\n[metadata]\nname = correct\n\n[other]\nname = wrong\n";
+        // The excluded package stays a project of its own
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].name.as_deref(), Some("pnpm-repo"));
+        assert_eq!(projects[1].name.as_deref(), Some("legacy"));
         assert_eq!(
-            Scanner::extract_name_from_cfg_content(multi),
-            Some("correct".to_string())
+            projects[0]
+                .build_arts
+                .iter()
+                .map(|a| a.path.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                root.join("node_modules"),
+                root.join("packages/ui/node_modules")
+            ]
         );
+        Ok(())
     }
 
     #[test]
-    fn test_extract_name_from_python_content() {
-        let content = "from setuptools import setup\nsetup(\n    name=\"my-pkg\",\n)\n";
-        assert_eq!(
-            Scanner::extract_name_from_python_content(content),
-            Some("my-pkg".to_string())
-        );
+    fn test_workspace_member_outside_scan_stays_standalone() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path().join("monorepo");
+        create_file(
+            &root.join("package.json"),
+            r#"{"workspaces": ["packages/*"]}"#,
+        )?;
+        let member = root.join("packages/web");
+        create_file(&member.join("package.json"), r#"{"name": "web"}"#)?;
+        create_file(&member.join("node_modules/dep.js"), "//")?;
 
-        let no_name = "from setuptools import setup\nsetup(version=\"1.0\")\n";
-        assert_eq!(Scanner::extract_name_from_python_content(no_name), None);
-    }
+        let scanner = default_scanner(ProjectFilter::Node);
+        let projects = scanner.scan_directory(&root.join("packages"));
 
-    #[test]
-    fn test_fallback_to_directory_name() {
-        assert_eq!(
-            Scanner::fallback_to_directory_name(Path::new("/some/project-name")),
-            Some("project-name".to_string())
-        );
-        assert_eq!(
-            Scanner::fallback_to_directory_name(Path::new("/some/my_app")),
-            Some("my_app".to_string())
-        );
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name.as_deref(), Some("web"));
+        Ok(())
     }
 
     #[test]
-    fn test_is_path_in_skip_list() {
-        let scanner = Scanner::new(
-            ScanOptions {
-                verbose: false,
-                threads: 1,
-                skip: vec![PathBuf::from("skip-me"), PathBuf::from("also-skip")],
-                max_depth: None,
-            },
-            ProjectFilter::All,
-        );
+    fn test_detect_deno_no_artifact_not_detected() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        assert!(scanner.is_path_in_skip_list(Path::new("/root/skip-me/project")));
-        assert!(scanner.is_path_in_skip_list(Path::new("/root/also-skip")));
-        assert!(!scanner.is_path_in_skip_list(Path::new("/root/keep-me")));
-        assert!(!scanner.is_path_in_skip_list(Path::new("/root/src")));
+        let project = base.join("deno-no-artifact");
+        create_file(&project.join("deno.json"), r"{}")?;
+
+        let scanner = default_scanner(ProjectFilter::Deno);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 0);
+        Ok(())
     }
 
     #[test]
-    fn test_is_path_in_empty_skip_list() {
-        let scanner = default_scanner(ProjectFilter::All);
-        assert!(!scanner.is_path_in_skip_list(Path::new("/any/path")));
+    fn test_build_directory_is_excluded() {
+        assert!(Scanner::is_excluded_directory(Path::new("/some/_build")));
     }
 
-    // ── Scanning with special path characters ───────────────────────────
-
     #[test]
-    fn test_scan_directory_with_spaces_in_path() -> anyhow::Result<()> {
+    fn test_node_caches_only_keeps_dependencies() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
-        let base = tmp.path().join("path with spaces");
-        fs::create_dir_all(&base)?;
+        let base = tmp.path();
 
-        let project = base.join("my project");
+        let root = base.join("monorepo");
         create_file(
-            &project.join("Cargo.toml"),
-            "[package]\nname = \"spaced\"\nversion = \"0.1.0\"",
+            &root.join("package.json"),
+            r#"{"name": "monorepo", "workspaces": ["apps/*"]}"#,
         )?;
-        create_file(&project.join("target/dummy"), "content")?;
+        create_file(&root.join("node_modules/react/index.js"), "// dep")?;
+        create_file(&root.join("node_modules/.cache/babel/x.json"), "{}")?;
+        create_file(&root.join(".turbo/cache/abc.tar.zst"), "cache")?;
+        create_file(&root.join("apps/web/package.json"), r#"{"name": "web"}"#)?;
+        create_file(&root.join("apps/web/node_modules/.vite/deps/react.js"), "")?;
+        create_file(&base.join("plain/package.json"), r#"{"name": "plain"}"#)?;
+        create_file(&base.join("plain/node_modules/lodash/index.js"), "")?;
 
-        let scanner = default_scanner(ProjectFilter::Rust);
-        let projects = scanner.scan_directory(&base);
+        let scanner = Scanner::new(
+            ScanOptions {
+                node_caches_only: true,
+                node_caches: DEFAULT_NODE_CACHES.iter().map(PathBuf::from).collect(),
+                ..ScanOptions::default()
+            },
+            ProjectFilter::Node,
+        );
+        let projects = scanner.scan_directory(base);
+
+        // Projects without any cache are left out entirely
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("spaced"));
+        let paths: Vec<_> = projects[0].build_arts.iter().map(|a| &a.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                &root.join("node_modules/.cache"),
+                &root.join(".turbo"),
+                &root.join("apps/web/node_modules/.vite"),
+            ]
+        );
         Ok(())
     }
 
     #[test]
-    fn test_scan_directory_with_unicode_names() -> anyhow::Result<()> {
+    fn test_container_caches_only_when_enabled() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("プロジェクト");
-        create_file(
-            &project.join("package.json"),
-            r#"{"name": "unicode-project"}"#,
-        )?;
-        create_file(&project.join("node_modules/dep.js"), "module.exports = {};")?;
+        let app = base.join("app");
+        create_file(&app.join("package.json"), r#"{"name": "app"}"#)?;
+        create_file(&app.join("node_modules/react/index.js"), "// dep")?;
+        create_file(&app.join(".buildx-cache/blobs/sha256"), "layer")?;
+        // A container's copy of the app, with its own dependencies
+        let mirror = app.join("docker/volumes/web");
+        create_file(&mirror.join("package.json"), r#"{"name": "web"}"#)?;
+        create_file(&mirror.join("node_modules/react/index.js"), "// dep")?;
+        // A project with nothing to clean but its container cache
+        let api = base.join("api");
+        create_file(&api.join("requirements.txt"), "flask\n")?;
+        create_file(&api.join(".buildx-cache/index.json"), "{}")?;
+
+        let scan = |container_caches| {
+            let scanner = Scanner::new(
+                ScanOptions {
+                    container_caches,
+                    container_cache_patterns: vec![
+                        ".buildx-cache".to_string(),
+                        "docker/**/node_modules".to_string(),
+                    ],
+                    ..ScanOptions::default()
+                },
+                ProjectFilter::All,
+            );
+            let mut projects = scanner.scan_directory(base);
+            projects.sort_by(|a, b| a.root_path.cmp(&b.root_path));
+            projects
+                .into_iter()
+                .map(|p| {
+                    let paths = p.build_arts.into_iter().map(|a| a.path).collect::<Vec<_>>();
+                    (p.root_path, paths)
+                })
+                .collect::<Vec<_>>()
+        };
 
-        let scanner = default_scanner(ProjectFilter::Node);
-        let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("unicode-project"));
+        assert_eq!(
+            scan(false),
+            vec![
+                (app.clone(), vec![app.join("node_modules")]),
+                (mirror.clone(), vec![mirror.join("node_modules")]),
+            ]
+        );
+        assert_eq!(
+            scan(true),
+            vec![
+                (api.clone(), vec![api.join(".buildx-cache")],),
+                (
+                    app.clone(),
+                    vec![
+                        app.join("node_modules"),
+                        app.join(".buildx-cache"),
+                        mirror.join("node_modules"),
+                    ],
+                ),
+            ]
+        );
         Ok(())
     }
 
     #[test]
-    fn test_scan_directory_with_special_characters_in_name() -> anyhow::Result<()> {
+    fn test_rust_subdirs_and_target_breakdown() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
-        let base = tmp.path();
-
-        let project = base.join("project-with-dashes_and_underscores.v2");
+        let root = tmp.path().join("app");
         create_file(
-            &project.join("Cargo.toml"),
-            "[package]\nname = \"special-chars\"\nversion = \"0.1.0\"",
+            &root.join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"",
         )?;
-        create_file(&project.join("target/dummy"), "content")?;
+        create_file(&root.join("target/debug/incremental/app-1/s.bin"), "12345")?;
+        create_file(&root.join("target/debug/deps/libserde.rlib"), "123")?;
+        create_file(&root.join("target/doc/app/index.html"), "<html>")?;
+        let triple = root.join("target/x86_64-unknown-linux-gnu");
+        create_file(&triple.join("release/incremental/app-2/s.bin"), "1")?;
+        create_file(&triple.join("release/.fingerprint/app/lib-app"), "12")?;
+
+        let scanner = |rust_subdirs, verbose| {
+            Scanner::new(
+                ScanOptions {
+                    verbose,
+                    rust_subdirs,
+                    ..ScanOptions::default()
+                },
+                ProjectFilter::Rust,
+            )
+        };
 
-        let scanner = default_scanner(ProjectFilter::Rust);
-        let projects = scanner.scan_directory(base);
+        let projects = scanner(vec![RustSubdir::Incremental, RustSubdir::Doc], false)
+            .scan_directory(tmp.path());
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("special-chars"));
+        let paths: Vec<_> = projects[0].build_arts.iter().map(|a| &a.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                &root.join("target/debug/incremental"),
+                &root.join("target/doc"),
+                &triple.join("release/incremental"),
+            ]
+        );
+        assert!(projects[0].target_breakdown.is_empty());
+
+        let projects = scanner(vec![], true).scan_directory(tmp.path());
+        let breakdown: Vec<_> = projects[0]
+            .target_breakdown
+            .iter()
+            .map(|subdir| (subdir.kind, subdir.size))
+            .collect();
+        assert_eq!(
+            breakdown,
+            vec![
+                (RustSubdir::Deps, 3),
+                (RustSubdir::Incremental, 5),
+                (RustSubdir::Doc, 6),
+                (RustSubdir::Fingerprint, 2),
+                (RustSubdir::Incremental, 1),
+            ]
+        );
         Ok(())
     }
 
-    // ── Unix-specific scanning tests ────────────────────────────────────
-
     #[test]
-    #[cfg(unix)]
-    fn test_hidden_directory_itself_not_detected_as_project_unix() -> anyhow::Result<()> {
+    fn test_explain_records_detection() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        // A hidden directory with Cargo.toml + target/ directly inside it
-        // should NOT be detected because the .hidden entry is filtered by
-        // is_hidden_directory_to_skip. However, non-hidden children inside
-        // hidden dirs CAN still be found because WalkDir descends into them.
-        let hidden = base.join(".hidden-project");
-        create_file(
-            &hidden.join("Cargo.toml"),
-            "[package]\nname = \"hidden\"\nversion = \"0.1.0\"",
-        )?;
-        create_file(&hidden.join("target/dummy"), "content")?;
-
-        // A visible project should be found
-        let visible = base.join("visible-project");
-        create_file(
-            &visible.join("Cargo.toml"),
-            "[package]\nname = \"visible\"\nversion = \"0.1.0\"",
-        )?;
-        create_file(&visible.join("target/dummy"), "content")?;
+        let root = base.join("app");
+        create_file(&root.join("pyproject.toml"), "[project]\nname = \"app\"\n")?;
+        create_file(&root.join("requirements.txt"), "requests\n")?;
+        create_file(&root.join(".venv/lib/site.py"), "import os\n")?;
+        create_file(&root.join("__pycache__/app.pyc"), "bytecode")?;
 
-        let scanner = default_scanner(ProjectFilter::Rust);
-        let projects = scanner.scan_directory(base);
+        let explained = Scanner::new(
+            ScanOptions {
+                explain: true,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::All,
+        )
+        .scan_directory(base);
+        assert_eq!(explained.len(), 1);
+        let detection = explained[0]
+            .detection
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no detection recorded"))?;
+        assert_eq!(detection.detector, "python");
+        assert_eq!(
+            detection.markers,
+            vec!["pyproject.toml", "requirements.txt"]
+        );
+        assert!(detection.artifacts.contains(&root.join("__pycache__")));
+        // Virtual environments are kept unless `include_venv` is set
+        assert!(!detection.artifacts.contains(&root.join(".venv")));
 
-        // Only the visible project should be found; the hidden one is excluded
-        // because its directory name starts with '.'
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("visible"));
+        let projects = default_scanner(ProjectFilter::All).scan_directory(base);
+        assert!(projects[0].detection.is_none());
         Ok(())
     }
 
     #[test]
-    #[cfg(unix)]
-    fn test_projects_inside_hidden_dirs_are_still_traversed_unix() -> anyhow::Result<()> {
+    fn test_trace_reports_each_step() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
-        let base = tmp.path();
+        let base = canonical_path(tmp.path());
 
-        // A non-hidden project nested inside a hidden directory.
-        // WalkDir still descends into .hidden, so the child project IS found.
-        let nested = base.join(".hidden-parent/visible-child");
+        let root = base.join(".app");
         create_file(
-            &nested.join("Cargo.toml"),
-            "[package]\nname = \"nested\"\nversion = \"0.1.0\"",
+            &root.join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"",
         )?;
-        create_file(&nested.join("target/dummy"), "content")?;
+        create_file(&root.join("target/debug/app"), "binary")?;
+
+        let filter_opts = FilterOptions {
+            keep_size: "1MB".to_string(),
+            keep_days: 0,
+            name_pattern: None,
+            name_filter: None,
+            path_filter: None,
+            min_benefit: None,
+            repo_idle_days: 0,
+            min_files: 0,
+            stale_only: false,
+            max_risk: None,
+            risk_overrides: RiskOverrides::default(),
+            only_artifacts: vec![],
+            skip_artifacts: vec![],
+            filter_expr: None,
+        };
+        let scanner = default_scanner(ProjectFilter::All);
+        let trace = scanner.trace(&root, std::slice::from_ref(&base), &filter_opts)?;
+
+        assert_eq!(trace.scan_root, Some(base.clone()));
+        assert_eq!(trace.build_dir_of, None);
+        let rules: Vec<_> = trace.pruned_by.iter().map(|r| r.rule).collect();
+        assert_eq!(rules, vec!["scan_hidden"]);
+
+        let rust = &trace.detectors[0];
+        assert_eq!(rust.outcome, DetectorOutcome::Detected);
+        assert_eq!(rust.markers_found, vec!["Cargo.toml"]);
+        assert_eq!(rust.artifacts, vec![root.join("target")]);
+        let node = trace
+            .detectors
+            .iter()
+            .find(|d| d.detector == "node")
+            .ok_or_else(|| anyhow::anyhow!("node detector not traced"))?;
+        assert_eq!(node.outcome, DetectorOutcome::NoMarkers);
+        assert_eq!(
+            node.markers_missing,
+            vec!["package.json", "pnpm-workspace.yaml"]
+        );
 
-        let scanner = default_scanner(ProjectFilter::Rust);
-        let projects = scanner.scan_directory(base);
+        assert_eq!(trace.projects.len(), 1);
+        let rules: Vec<_> = trace.projects[0]
+            .filtered_by
+            .iter()
+            .map(|r| r.rule)
+            .collect();
+        assert_eq!(rules, vec!["keep_size"]);
+        assert!(!trace.cleaned);
+
+        // A build directory is traced through the project that owns it
+        let trace = scanner.trace(
+            &root.join("target"),
+            &[base.join("elsewhere")],
+            &filter_opts,
+        )?;
+        assert_eq!(trace.build_dir_of, Some(root.clone()));
+        assert_eq!(trace.pruned_by[0].rule, "dirs");
 
-        // The child project has a non-hidden name, so it IS detected
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("nested"));
+        let scanner = Scanner::new(
+            ScanOptions {
+                scan_hidden: true,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::Node,
+        );
+        let trace = scanner.trace(
+            &root,
+            &[base],
+            &FilterOptions {
+                keep_size: "0".to_string(),
+                ..filter_opts
+            },
+        )?;
+        assert!(trace.pruned_by.is_empty());
+        assert_eq!(trace.detectors[0].outcome, DetectorOutcome::Skipped);
+        assert!(trace.projects.is_empty());
+        assert!(!trace.cleaned);
         Ok(())
     }
 
     #[test]
-    #[cfg(unix)]
-    fn test_dotcargo_directory_not_skipped_unix() {
-        // .cargo is the exception — hidden but should NOT be skipped.
-        // Verify via the static method.
-        assert!(!Scanner::is_hidden_directory_to_skip(Path::new(
-            "/home/user/.cargo"
-        )));
+    fn test_unnamed_projects_fall_back_to_repository_then_directory() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
 
-        // Other dot-dirs ARE skipped
-        assert!(Scanner::is_hidden_directory_to_skip(Path::new(
-            "/home/user/.local"
-        )));
-        assert!(Scanner::is_hidden_directory_to_skip(Path::new(
-            "/home/user/.npm"
-        )));
+        let repo = base.join("checkout");
+        create_file(
+            &repo.join(".git/config"),
+            "[remote \"origin\"]\n\turl = https://example.com/team/storefront.git\n",
+        )?;
+        create_file(&repo.join("package.json"), r#"{"private": true}"#)?;
+        create_file(&repo.join("node_modules/x/index.js"), "x")?;
+        let nested = repo.join("tools/codegen");
+        create_file(&nested.join("package.json"), r#"{"name": "  "}"#)?;
+        create_file(&nested.join("node_modules/y/index.js"), "y")?;
+        let lib = base.join("lib");
+        create_file(&lib.join("package.json"), r#"{"name": "left-pad"}"#)?;
+        create_file(&lib.join("node_modules/z/index.js"), "z")?;
+
+        let mut names: Vec<_> = default_scanner(ProjectFilter::Node)
+            .scan_directory(base)
+            .into_iter()
+            .filter_map(|project| project.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["codegen", "left-pad", "storefront"]);
+        Ok(())
     }
 
-    // ── Python project detection tests ──────────────────────────────────
+    // ── Rust workspace awareness tests ─────────────────────────────────
 
     #[test]
-    fn test_detect_python_with_pyproject_toml() -> anyhow::Result<()> {
+    fn test_workspace_root_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("py-project");
+        // Workspace root: has [workspace] in Cargo.toml and a target/ dir with content.
+        let workspace = base.join("my-workspace");
         create_file(
-            &project.join("pyproject.toml"),
-            "[project]\nname = \"my-py-lib\"\nversion = \"1.0.0\"\n",
+            &workspace.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crate-a\"]\n\n[package]\nname = \"my-workspace\"\nversion = \"0.1.0\"\n",
         )?;
-        let pycache = project.join("__pycache__");
-        fs::create_dir_all(&pycache)?;
-        create_file(&pycache.join("module.pyc"), "bytecode")?;
+        create_file(&workspace.join("target/dummy"), "content")?;
 
-        let scanner = default_scanner(ProjectFilter::Python);
+        let scanner = default_scanner(ProjectFilter::Rust);
         let projects = scanner.scan_directory(base);
+
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Python);
+        assert_eq!(projects[0].root_path, workspace);
         Ok(())
     }
 
     #[test]
-    fn test_detect_python_with_setup_py() -> anyhow::Result<()> {
+    fn test_workspace_member_with_own_target_skipped() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("setup-project");
+        // Workspace root with content in target/.
+        let workspace = base.join("my-workspace");
         create_file(
-            &project.join("setup.py"),
-            "from setuptools import setup\nsetup(name=\"setup-lib\")\n",
+            &workspace.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crate-a\"]\n\n[package]\nname = \"my-workspace\"\nversion = \"0.1.0\"\n",
+        )?;
+        create_file(&workspace.join("target/dummy"), "content")?;
+
+        // Workspace member that also happens to have its own target/ directory.
+        let member = workspace.join("crate-a");
+        create_file(
+            &member.join("Cargo.toml"),
+            "[package]\nname = \"crate-a\"\nversion = \"0.1.0\"\n",
         )?;
-        let pycache = project.join("__pycache__");
-        fs::create_dir_all(&pycache)?;
-        create_file(&pycache.join("module.pyc"), "bytecode")?;
+        create_file(&member.join("target/dummy"), "content")?;
 
-        let scanner = default_scanner(ProjectFilter::Python);
+        let scanner = default_scanner(ProjectFilter::Rust);
         let projects = scanner.scan_directory(base);
+
+        // Only the workspace root should be reported; the member must be skipped.
         assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].root_path, workspace);
         Ok(())
     }
 
     #[test]
-    fn test_detect_python_with_pipfile() -> anyhow::Result<()> {
+    fn test_rust_local_caches_only_with_cargo_caches() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
-        let base = tmp.path();
-
-        let project = base.join("pipenv-project");
+        let project = tmp.path().join("ci-built");
         create_file(
-            &project.join("Pipfile"),
-            "[[source]]\nurl = \"https://pypi.org/simple\"",
+            &project.join("Cargo.toml"),
+            "[package]\nname = \"ci-built\"\nversion = \"0.1.0\"\n",
         )?;
-        let pycache = project.join("__pycache__");
-        fs::create_dir_all(&pycache)?;
-        create_file(&pycache.join("module.pyc"), "bytecode")?;
+        create_file(&project.join(".cargo/config.toml"), "[build]\n")?;
+        create_file(&project.join(".cargo/registry/cache/serde.crate"), "crate")?;
+        create_file(&project.join(".sccache/a/b/object"), "object")?;
+
+        // Without target/, the project only counts when its caches are cleaned
+        assert!(
+            default_scanner(ProjectFilter::Rust)
+                .scan_directory(tmp.path())
+                .is_empty()
+        );
+
+        create_file(&project.join("target/debug/app"), "binary")?;
+        let scanner = Scanner::new(
+            ScanOptions {
+                cargo_caches: true,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::Rust,
+        );
+        let projects = scanner.scan_directory(tmp.path());
 
-        let scanner = default_scanner(ProjectFilter::Python);
-        let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
+        let paths: Vec<_> = projects[0].build_arts.iter().map(|a| &a.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                &project.join("target"),
+                &project.join(".cargo/registry"),
+                &project.join(".sccache"),
+            ]
+        );
         Ok(())
     }
 
-    // ── Go project detection tests ──────────────────────────────────────
+    // ── PHP project detection tests ───────────────────────────────────────
 
     #[test]
-    fn test_detect_go_extracts_module_name() -> anyhow::Result<()> {
+    fn test_detect_php_project() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("go-service");
+        let project = base.join("php-project");
         create_file(
-            &project.join("go.mod"),
-            "module github.com/user/my-service\n\ngo 1.21\n",
+            &project.join("composer.json"),
+            r#"{"name": "acme/my-php-app", "require": {}}"#,
         )?;
-        let vendor = project.join("vendor");
-        fs::create_dir_all(&vendor)?;
-        create_file(&vendor.join("modules.txt"), "vendor manifest")?;
+        create_file(&project.join("vendor/autoload.php"), "<?php // autoloader")?;
 
-        let scanner = default_scanner(ProjectFilter::Go);
+        let scanner = default_scanner(ProjectFilter::Php);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        // Should extract last path component as name
-        assert_eq!(projects[0].name.as_deref(), Some("my-service"));
+        assert_eq!(projects[0].kind, ProjectType::Php);
+        // Only the package component (after /) should be returned.
+        assert_eq!(projects[0].name.as_deref(), Some("my-php-app"));
         Ok(())
     }
 
-    // ── Java/Kotlin project detection tests ────────────────────────────
-
     #[test]
-    fn test_detect_java_maven_project() -> anyhow::Result<()> {
+    fn test_detect_php_no_vendor_not_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("java-maven");
-        create_file(
-            &project.join("pom.xml"),
-            "<project>\n  <artifactId>my-java-app</artifactId>\n</project>",
-        )?;
-        create_file(&project.join("target/classes/Main.class"), "bytecode")?;
+        let project = base.join("php-no-vendor");
+        create_file(&project.join("composer.json"), r#"{"name": "acme/my-app"}"#)?;
 
-        let scanner = default_scanner(ProjectFilter::Java);
+        let scanner = default_scanner(ProjectFilter::Php);
         let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Java);
-        assert_eq!(projects[0].name.as_deref(), Some("my-java-app"));
+        assert_eq!(projects.len(), 0);
         Ok(())
     }
 
     #[test]
-    fn test_detect_java_gradle_project() -> anyhow::Result<()> {
+    fn test_detect_php_fallback_to_dir_name() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("java-gradle");
-        create_file(&project.join("build.gradle"), "apply plugin: 'java'")?;
-        create_file(
-            &project.join("settings.gradle"),
-            "rootProject.name = \"my-gradle-app\"",
-        )?;
-        create_file(&project.join("build/classes/main/Main.class"), "bytecode")?;
+        let project = base.join("my-php-project");
+        // composer.json without a "name" field
+        create_file(&project.join("composer.json"), r#"{"require": {}}"#)?;
+        create_file(&project.join("vendor/autoload.php"), "<?php")?;
 
-        let scanner = default_scanner(ProjectFilter::Java);
+        let scanner = default_scanner(ProjectFilter::Php);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Java);
-        assert_eq!(projects[0].name.as_deref(), Some("my-gradle-app"));
+        assert_eq!(projects[0].name.as_deref(), Some("my-php-project"));
         Ok(())
     }
 
+    // ── Haskell project detection tests ──────────────────────────────────
+
     #[test]
-    fn test_detect_java_gradle_kts_project() -> anyhow::Result<()> {
+    fn test_detect_haskell_stack_project() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("kotlin-gradle");
+        let project = base.join("haskell-stack");
         create_file(
-            &project.join("build.gradle.kts"),
-            "plugins { kotlin(\"jvm\") }",
+            &project.join("stack.yaml"),
+            "resolver: lts-21.0\npackages:\n  - .",
         )?;
         create_file(
-            &project.join("settings.gradle.kts"),
-            "rootProject.name = \"my-kotlin-app\"",
+            &project.join("my-haskell-lib.cabal"),
+            "name:          my-haskell-lib\nversion:       0.1.0.0\n",
         )?;
         create_file(
-            &project.join("build/classes/kotlin/main/MainKt.class"),
-            "bytecode",
+            &project.join(".stack-work/dist/x86_64-linux/ghc-9.4.7/build/Main.o"),
+            "object",
         )?;
 
-        let scanner = default_scanner(ProjectFilter::Java);
+        let scanner = default_scanner(ProjectFilter::Haskell);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Java);
-        assert_eq!(projects[0].name.as_deref(), Some("my-kotlin-app"));
+        assert_eq!(projects[0].kind, ProjectType::Haskell);
+        assert_eq!(projects[0].name.as_deref(), Some("my-haskell-lib"));
         Ok(())
     }
 
-    // ── C/C++ project detection tests ────────────────────────────────────
-
     #[test]
-    fn test_detect_cpp_cmake_project() -> anyhow::Result<()> {
+    fn test_detect_haskell_cabal_project() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("cpp-cmake");
+        let project = base.join("haskell-cabal");
+        create_file(&project.join("cabal.project"), "packages: .\n")?;
         create_file(
-            &project.join("CMakeLists.txt"),
-            "project(my-cpp-lib)\ncmake_minimum_required(VERSION 3.10)",
+            &project.join("my-cabal-lib.cabal"),
+            "name:          my-cabal-lib\nversion:       0.1.0.0\n",
+        )?;
+        create_file(
+            &project.join(
+                "dist-newstyle/build/x86_64-linux/ghc-9.4.7/my-cabal-lib-0.1.0.0/build/Main.o",
+            ),
+            "object",
         )?;
-        create_file(&project.join("build/CMakeCache.txt"), "cache")?;
 
-        let scanner = default_scanner(ProjectFilter::Cpp);
+        let scanner = default_scanner(ProjectFilter::Haskell);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Cpp);
-        assert_eq!(projects[0].name.as_deref(), Some("my-cpp-lib"));
+        assert_eq!(projects[0].kind, ProjectType::Haskell);
+        assert_eq!(projects[0].name.as_deref(), Some("my-cabal-lib"));
         Ok(())
     }
 
     #[test]
-    fn test_detect_cpp_makefile_project() -> anyhow::Result<()> {
+    fn test_detect_haskell_no_artifact_not_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("cpp-make");
-        create_file(&project.join("Makefile"), "all:\n\tg++ -o main main.cpp")?;
-        create_file(&project.join("build/main.o"), "object")?;
+        let project = base.join("haskell-no-artifact");
+        create_file(&project.join("stack.yaml"), "resolver: lts-21.0")?;
 
-        let scanner = default_scanner(ProjectFilter::Cpp);
+        let scanner = default_scanner(ProjectFilter::Haskell);
         let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Cpp);
+        assert_eq!(projects.len(), 0);
         Ok(())
     }
 
-    // ── Swift project detection tests ────────────────────────────────────
+    // ── Dart/Flutter project detection tests ─────────────────────────────
 
     #[test]
-    fn test_detect_swift_project() -> anyhow::Result<()> {
+    fn test_detect_dart_project_with_dart_tool() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("swift-pkg");
+        let project = base.join("dart-project");
         create_file(
-            &project.join("Package.swift"),
-            "let package = Package(\n    name: \"my-swift-lib\",\n    targets: []\n)",
+            &project.join("pubspec.yaml"),
+            "name: my_dart_app\nversion: 1.0.0\n",
         )?;
-        create_file(&project.join(".build/debug/my-swift-lib"), "binary")?;
-
-        let scanner = default_scanner(ProjectFilter::Swift);
-        let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Swift);
-        assert_eq!(projects[0].name.as_deref(), Some("my-swift-lib"));
-        Ok(())
-    }
-
-    // ── .NET/C# project detection tests ──────────────────────────────────
-
-    #[test]
-    fn test_detect_dotnet_project() -> anyhow::Result<()> {
-        let tmp = TempDir::new()?;
-        let base = tmp.path();
-
-        let project = base.join("dotnet-app");
         create_file(
-            &project.join("MyApp.csproj"),
-            "<Project Sdk=\"Microsoft.NET.Sdk\">\n</Project>",
+            &project.join(".dart_tool/package_config.json"),
+            r#"{"configVersion": 2}"#,
         )?;
-        create_file(&project.join("bin/Debug/net8.0/MyApp.dll"), "assembly")?;
-        create_file(&project.join("obj/Debug/net8.0/MyApp.dll"), "intermediate")?;
 
-        let scanner = default_scanner(ProjectFilter::DotNet);
+        let scanner = default_scanner(ProjectFilter::Dart);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::DotNet);
-        assert_eq!(projects[0].name.as_deref(), Some("MyApp"));
+        assert_eq!(projects[0].kind, ProjectType::Dart);
+        assert_eq!(projects[0].name.as_deref(), Some("my_dart_app"));
         Ok(())
     }
 
     #[test]
-    fn test_detect_dotnet_project_obj_only() -> anyhow::Result<()> {
+    fn test_detect_dart_project_with_build_dir() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("dotnet-obj-only");
+        let project = base.join("flutter-project");
         create_file(
-            &project.join("Lib.csproj"),
-            "<Project Sdk=\"Microsoft.NET.Sdk\">\n</Project>",
+            &project.join("pubspec.yaml"),
+            "name: my_flutter_app\nversion: 1.0.0\n",
+        )?;
+        create_file(
+            &project.join("build/flutter_assets/AssetManifest.json"),
+            "{}",
         )?;
-        create_file(&project.join("obj/Debug/net8.0/Lib.dll"), "intermediate")?;
 
-        let scanner = default_scanner(ProjectFilter::DotNet);
+        let scanner = default_scanner(ProjectFilter::Dart);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::DotNet);
-        assert_eq!(projects[0].name.as_deref(), Some("Lib"));
-        Ok(())
-    }
-
-    // ── Excluded directory tests ─────────────────────────────────────────
-
-    #[test]
-    fn test_obj_directory_is_excluded() {
-        assert!(Scanner::is_excluded_directory(Path::new("/some/obj")));
-    }
-
-    // ── Cross-platform calculate_build_dir_size ─────────────────────────
-
-    #[test]
-    fn test_calculate_build_dir_size_empty() -> anyhow::Result<()> {
-        let tmp = TempDir::new()?;
-        let empty_dir = tmp.path().join("empty");
-        fs::create_dir_all(&empty_dir)?;
-
-        assert_eq!(Scanner::calculate_build_dir_size(&empty_dir), 0);
-        Ok(())
-    }
-
-    #[test]
-    fn test_calculate_build_dir_size_nonexistent() {
-        assert_eq!(
-            Scanner::calculate_build_dir_size(Path::new("/nonexistent/path")),
-            0
-        );
-    }
-
-    #[test]
-    fn test_calculate_build_dir_size_with_nested_files() -> anyhow::Result<()> {
-        let tmp = TempDir::new()?;
-        let dir = tmp.path().join("nested");
-
-        create_file(&dir.join("file1.txt"), "hello")?; // 5 bytes
-        create_file(&dir.join("sub/file2.txt"), "world!")?; // 6 bytes
-        create_file(&dir.join("sub/deep/file3.txt"), "!")?; // 1 byte
-
-        let size = Scanner::calculate_build_dir_size(&dir);
-        assert_eq!(size, 12);
+        assert_eq!(projects[0].kind, ProjectType::Dart);
+        assert_eq!(projects[0].name.as_deref(), Some("my_flutter_app"));
         Ok(())
     }
 
-    // ── Quiet mode ──────────────────────────────────────────────────────
-
     #[test]
-    fn test_scanner_quiet_mode() -> anyhow::Result<()> {
+    fn test_detect_dart_no_artifact_not_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("quiet-project");
-        create_file(
-            &project.join("Cargo.toml"),
-            "[package]\nname = \"quiet\"\nversion = \"0.1.0\"",
-        )?;
-        create_file(&project.join("target/dummy"), "content")?;
+        let project = base.join("pubspec-only");
+        create_file(&project.join("pubspec.yaml"), "name: empty_project\n")?;
 
-        let scanner = default_scanner(ProjectFilter::Rust).with_quiet(true);
+        let scanner = default_scanner(ProjectFilter::Dart);
         let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
+        assert_eq!(projects.len(), 0);
         Ok(())
     }
 
-    // ── Ruby project detection tests ─────────────────────────────────────
+    // ── Zig project detection tests ───────────────────────────────────────
 
     #[test]
-    fn test_detect_ruby_with_vendor_bundle() -> anyhow::Result<()> {
+    fn test_detect_zig_project_with_cache() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("ruby-project");
-        create_file(
-            &project.join("Gemfile"),
-            "source 'https://rubygems.org'\ngem 'rails'",
-        )?;
-        create_file(
-            &project.join("my-app.gemspec"),
-            "Gem::Specification.new do |spec|\n  spec.name = \"my-ruby-gem\"\nend",
-        )?;
+        let project = base.join("zig-project");
         create_file(
-            &project.join("vendor/bundle/ruby/3.2.0/gems/rails/init.rb"),
-            "# rails",
+            &project.join("build.zig"),
+            "const std = @import(\"std\");\npub fn build(b: *std.Build) void {}\n",
         )?;
+        create_file(&project.join("zig-cache/h/abc123.h"), "// generated")?;
 
-        let scanner = default_scanner(ProjectFilter::Ruby);
+        let scanner = default_scanner(ProjectFilter::Zig);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Ruby);
-        assert_eq!(projects[0].name.as_deref(), Some("my-ruby-gem"));
+        assert_eq!(projects[0].kind, ProjectType::Zig);
+        // Zig falls back to directory name.
+        assert_eq!(projects[0].name.as_deref(), Some("zig-project"));
         Ok(())
     }
 
     #[test]
-    fn test_detect_ruby_with_dot_bundle() -> anyhow::Result<()> {
+    fn test_detect_zig_project_with_out_dir() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("ruby-dot-bundle");
-        create_file(&project.join("Gemfile"), "source 'https://rubygems.org'")?;
-        create_file(&project.join(".bundle/gems/rack-2.0/lib/rack.rb"), "# rack")?;
+        let project = base.join("zig-out-project");
+        create_file(&project.join("build.zig"), "// zig build script")?;
+        create_file(&project.join("zig-out/bin/my-app"), "binary")?;
 
-        let scanner = default_scanner(ProjectFilter::Ruby);
+        let scanner = default_scanner(ProjectFilter::Zig);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Ruby);
+        assert_eq!(projects[0].kind, ProjectType::Zig);
         Ok(())
     }
 
     #[test]
-    fn test_detect_ruby_no_artifact_not_detected() -> anyhow::Result<()> {
+    fn test_detect_zig_no_artifact_not_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        // Gemfile exists but no .bundle/ or vendor/bundle/
-        let project = base.join("gemfile-only");
-        create_file(&project.join("Gemfile"), "source 'https://rubygems.org'")?;
+        let project = base.join("zig-no-artifact");
+        create_file(&project.join("build.zig"), "// zig build script")?;
 
-        let scanner = default_scanner(ProjectFilter::Ruby);
+        let scanner = default_scanner(ProjectFilter::Zig);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 0);
         Ok(())
     }
 
     #[test]
-    fn test_detect_ruby_fallback_to_dir_name() -> anyhow::Result<()> {
+    fn test_zig_cache_directory_is_excluded() {
+        assert!(Scanner::is_excluded_directory(Path::new("/some/zig-cache")));
+        assert!(Scanner::is_excluded_directory(Path::new("/some/zig-out")));
+        assert!(Scanner::is_excluded_directory(Path::new(
+            "/some/dist-newstyle"
+        )));
+    }
+
+    // ── Lua project detection tests ───────────────────────────────────────
+
+    #[test]
+    fn test_detect_lua_project_with_rocks_tree() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("my-ruby-app");
-        create_file(&project.join("Gemfile"), "source 'https://rubygems.org'")?;
+        let project = base.join("lua-app");
         create_file(
-            &project.join("vendor/bundle/gems/sinatra/lib/sinatra.rb"),
-            "# sinatra",
+            &project.join("lua-app-1.0-1.rockspec"),
+            "package = \"my-lua-app\"\nversion = \"1.0-1\"\n",
         )?;
+        create_file(
+            &project.join("lua_modules/share/lua/5.4/inspect.lua"),
+            "return {}",
+        )?;
+        create_file(&project.join(".luarocks/config-5.4.lua"), "-- config")?;
 
-        let scanner = default_scanner(ProjectFilter::Ruby);
+        let scanner = default_scanner(ProjectFilter::Lua);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("my-ruby-app"));
+        assert_eq!(projects[0].kind, ProjectType::Lua);
+        assert_eq!(projects[0].name.as_deref(), Some("my-lua-app"));
+        assert_eq!(projects[0].build_arts.len(), 2);
         Ok(())
     }
 
-    // ── Elixir project detection tests ───────────────────────────────────
-
     #[test]
-    fn test_detect_elixir_project() -> anyhow::Result<()> {
+    fn test_detect_lua_modules_without_rockspec_not_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("elixir-project");
-        create_file(
-            &project.join("mix.exs"),
-            "defmodule MyApp.MixProject do\n  def project do\n    [app: :my_app,\n     version: \"0.1.0\"]\n  end\nend",
-        )?;
-        create_file(
-            &project.join("_build/dev/lib/my_app/.mix/compile.elixir"),
-            "# build",
-        )?;
+        create_file(&base.join("scripts/lua_modules/foo.lua"), "return {}")?;
 
-        let scanner = default_scanner(ProjectFilter::Elixir);
-        let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Elixir);
-        assert_eq!(projects[0].name.as_deref(), Some("my_app"));
+        let scanner = default_scanner(ProjectFilter::Lua);
+        assert!(scanner.scan_directory(base).is_empty());
         Ok(())
     }
 
+    // ── Nim project detection tests ───────────────────────────────────────
+
     #[test]
-    fn test_detect_elixir_no_build_not_detected() -> anyhow::Result<()> {
+    fn test_detect_nim_project() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("mix-only");
+        let project = base.join("nim-project");
         create_file(
-            &project.join("mix.exs"),
-            "defmodule MixOnly.MixProject do\n  def project do\n    [app: :mix_only]\n  end\nend",
+            &project.join("jester.nimble"),
+            "version = \"0.6.0\"\nrequires \"nim >= 1.6\"\n",
         )?;
+        create_file(&project.join("nimcache/@mjester.nim.c"), "/* generated */")?;
 
-        let scanner = default_scanner(ProjectFilter::Elixir);
+        let scanner = default_scanner(ProjectFilter::Nim);
         let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 0);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Nim);
+        assert_eq!(projects[0].name.as_deref(), Some("jester"));
         Ok(())
     }
 
     #[test]
-    fn test_detect_elixir_fallback_to_dir_name() -> anyhow::Result<()> {
+    fn test_detect_nim_no_nimcache_not_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("my_elixir_project");
-        create_file(&project.join("mix.exs"), "# minimal mix.exs without app:")?;
         create_file(
-            &project.join("_build/prod/lib/my_elixir_project.beam"),
-            "bytecode",
+            &base.join("nim-project/jester.nimble"),
+            "version = \"0.6.0\"",
         )?;
 
-        let scanner = default_scanner(ProjectFilter::Elixir);
-        let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("my_elixir_project"));
+        let scanner = default_scanner(ProjectFilter::Nim);
+        assert!(scanner.scan_directory(base).is_empty());
         Ok(())
     }
 
-    // ── Deno project detection tests ─────────────────────────────────────
+    // ── OCaml project detection tests ─────────────────────────────────────
 
     #[test]
-    fn test_detect_deno_with_vendor() -> anyhow::Result<()> {
+    fn test_detect_ocaml_dune_project() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("deno-project");
+        let project = base.join("ocaml-project");
         create_file(
-            &project.join("deno.json"),
-            r#"{"name": "my-deno-app", "imports": {}}"#,
+            &project.join("dune-project"),
+            "(lang dune 3.0)\n(name parser_kit)\n",
         )?;
-        create_file(&project.join("vendor/modules.json"), "{}")?;
+        create_file(&project.join("_build/default/bin/main.exe"), "binary")?;
 
-        let scanner = default_scanner(ProjectFilter::Deno);
+        let scanner = default_scanner(ProjectFilter::OCaml);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Deno);
-        assert_eq!(projects[0].name.as_deref(), Some("my-deno-app"));
+        assert_eq!(projects[0].kind, ProjectType::OCaml);
+        assert_eq!(projects[0].name.as_deref(), Some("parser_kit"));
         Ok(())
     }
 
+    // ── Erlang project detection tests ────────────────────────────────────
+
     #[test]
-    fn test_detect_deno_jsonc_config() -> anyhow::Result<()> {
+    fn test_detect_erlang_rebar3_project() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("deno-jsonc-project");
+        let project = base.join("erlang-project");
+        create_file(&project.join("rebar.config"), "{deps, []}.\n")?;
         create_file(
-            &project.join("deno.jsonc"),
-            r#"{"name": "my-deno-jsonc-app", "tasks": {}}"#,
+            &project.join("src/chat_server.app.src"),
+            "{application, chat_server,\n [{vsn, \"0.1.0\"}]}.\n",
+        )?;
+        create_file(
+            &project.join("_build/default/lib/chat_server/ebin/chat.beam"),
+            "bytecode",
         )?;
-        create_file(&project.join("vendor/modules.json"), "{}")?;
 
-        let scanner = default_scanner(ProjectFilter::Deno);
+        let scanner = default_scanner(ProjectFilter::Erlang);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Deno);
-        assert_eq!(projects[0].name.as_deref(), Some("my-deno-jsonc-app"));
+        assert_eq!(projects[0].kind, ProjectType::Erlang);
+        assert_eq!(projects[0].name.as_deref(), Some("chat_server"));
         Ok(())
     }
 
     #[test]
-    fn test_detect_deno_node_modules_without_package_json() -> anyhow::Result<()> {
+    fn test_detect_mix_project_with_rebar_config_as_elixir() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("deno-npm-project");
-        create_file(&project.join("deno.json"), r#"{"nodeModulesDir": "auto"}"#)?;
+        let project = base.join("hybrid");
         create_file(
-            &project.join("node_modules/.deno/lodash/index.js"),
-            "// lodash",
+            &project.join("mix.exs"),
+            "defmodule Hybrid.MixProject do\n  def project do\n    [app: :hybrid]\n  end\nend",
+        )?;
+        create_file(&project.join("rebar.config"), "{deps, []}.\n")?;
+        create_file(
+            &project.join("_build/dev/lib/hybrid/ebin/x.beam"),
+            "bytecode",
         )?;
 
-        let scanner = default_scanner(ProjectFilter::Deno);
+        let scanner = default_scanner(ProjectFilter::All);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Deno);
+        assert_eq!(projects[0].kind, ProjectType::Elixir);
         Ok(())
     }
 
+    // ── Julia project detection tests ─────────────────────────────────────
+
     #[test]
-    fn test_detect_deno_node_modules_with_package_json_becomes_node() -> anyhow::Result<()> {
+    fn test_detect_julia_project_depot() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        // deno.json + package.json + node_modules → Node project (not Deno)
-        let project = base.join("ambiguous-project");
-        create_file(&project.join("deno.json"), r"{}")?;
-        create_file(&project.join("package.json"), r#"{"name": "my-node-app"}"#)?;
-        create_file(&project.join("node_modules/dep/index.js"), "// dep")?;
+        let project = base.join("julia-project");
+        create_file(
+            &project.join("Project.toml"),
+            "name = \"DataTools\"\nuuid = \"0b6ae3cc-0000-0000-0000-000000000000\"\n",
+        )?;
+        create_file(
+            &project.join("Manifest.toml"),
+            "julia_version = \"1.10.0\"\n",
+        )?;
+        create_file(
+            &project.join(".julia/compiled/v1.10/DataTools/abc.ji"),
+            "precompiled",
+        )?;
+        create_file(
+            &project.join(".julia/packages/CSV/abc/Project.toml"),
+            "name = \"CSV\"\n",
+        )?;
 
-        let scanner = default_scanner(ProjectFilter::All);
+        let scanner = default_scanner(ProjectFilter::Julia);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Node);
+        assert_eq!(projects[0].kind, ProjectType::Julia);
+        assert_eq!(projects[0].name.as_deref(), Some("DataTools"));
+        assert_eq!(projects[0].build_arts.len(), 1);
+        assert_eq!(
+            projects[0].build_arts[0].path,
+            project.join(".julia/compiled")
+        );
         Ok(())
     }
 
     #[test]
-    fn test_detect_deno_no_artifact_not_detected() -> anyhow::Result<()> {
+    fn test_detect_julia_without_artifacts_not_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("deno-no-artifact");
-        create_file(&project.join("deno.json"), r"{}")?;
+        create_file(
+            &base.join("julia-env/Project.toml"),
+            "[deps]\nCSV = \"336ed68f-0bac-5ca0-87d4-7b16caf5d00b\"\n",
+        )?;
 
-        let scanner = default_scanner(ProjectFilter::Deno);
+        let scanner = default_scanner(ProjectFilter::Julia);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 0);
         Ok(())
     }
 
-    #[test]
-    fn test_build_directory_is_excluded() {
-        assert!(Scanner::is_excluded_directory(Path::new("/some/_build")));
-    }
-
-    // ── Rust workspace awareness tests ─────────────────────────────────
+    // ── R project detection tests ─────────────────────────────────────────
 
     #[test]
-    fn test_is_cargo_workspace_root() -> anyhow::Result<()> {
+    fn test_detect_r_renv_project() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
-        let cargo_toml = tmp.path().join("Cargo.toml");
+        let base = tmp.path();
 
-        // A workspace root must contain a bare `[workspace]` section header.
+        let project = base.join("r-project");
         create_file(
-            &cargo_toml,
-            "[workspace]\nmembers = [\"crate-a\", \"crate-b\"]\n",
+            &project.join("renv.lock"),
+            "{\"R\": {\"Version\": \"4.3.2\"}}",
         )?;
-        assert!(Scanner::is_cargo_workspace_root(&cargo_toml));
-
-        // A regular package Cargo.toml is not a workspace root.
         create_file(
-            &cargo_toml,
-            "[package]\nname = \"my-crate\"\nversion = \"0.1.0\"\n",
+            &project.join("DESCRIPTION"),
+            "Package: tidyplot\nVersion: 0.1.0\n",
         )?;
-        assert!(!Scanner::is_cargo_workspace_root(&cargo_toml));
-
-        // A non-existent file returns false.
-        assert!(!Scanner::is_cargo_workspace_root(Path::new(
-            "/nonexistent/Cargo.toml"
-        )));
-        Ok(())
-    }
-
-    #[test]
-    fn test_workspace_root_detected() -> anyhow::Result<()> {
-        let tmp = TempDir::new()?;
-        let base = tmp.path();
-
-        // Workspace root: has [workspace] in Cargo.toml and a target/ dir with content.
-        let workspace = base.join("my-workspace");
+        create_file(&project.join("renv/activate.R"), "local({})")?;
         create_file(
-            &workspace.join("Cargo.toml"),
-            "[workspace]\nmembers = [\"crate-a\"]\n\n[package]\nname = \"my-workspace\"\nversion = \"0.1.0\"\n",
+            &project.join("renv/library/linux-ubuntu/R-4.3/x86_64-pc-linux-gnu/dplyr/DESCRIPTION"),
+            "Package: dplyr\n",
         )?;
-        create_file(&workspace.join("target/dummy"), "content")?;
 
-        let scanner = default_scanner(ProjectFilter::Rust);
+        let scanner = default_scanner(ProjectFilter::R);
         let projects = scanner.scan_directory(base);
-
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].root_path, workspace);
+        assert_eq!(projects[0].kind, ProjectType::R);
+        assert_eq!(projects[0].name.as_deref(), Some("tidyplot"));
+        assert_eq!(
+            projects[0].build_arts[0].path,
+            project.join("renv").join("library")
+        );
         Ok(())
     }
 
+    // ── Scala project detection tests ─────────────────────────────────────
+
     #[test]
-    fn test_workspace_member_with_own_target_skipped() -> anyhow::Result<()> {
+    fn test_detect_scala_project() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        // Workspace root with content in target/.
-        let workspace = base.join("my-workspace");
+        let project = base.join("scala-project");
         create_file(
-            &workspace.join("Cargo.toml"),
-            "[workspace]\nmembers = [\"crate-a\"]\n\n[package]\nname = \"my-workspace\"\nversion = \"0.1.0\"\n",
+            &project.join("build.sbt"),
+            "name := \"my-scala-app\"\nscalaVersion := \"3.3.0\"\n",
         )?;
-        create_file(&workspace.join("target/dummy"), "content")?;
-
-        // Workspace member that also happens to have its own target/ directory.
-        let member = workspace.join("crate-a");
         create_file(
-            &member.join("Cargo.toml"),
-            "[package]\nname = \"crate-a\"\nversion = \"0.1.0\"\n",
+            &project.join("target/scala-3.3.0/classes/Main.class"),
+            "bytecode",
         )?;
-        create_file(&member.join("target/dummy"), "content")?;
 
-        let scanner = default_scanner(ProjectFilter::Rust);
+        let scanner = default_scanner(ProjectFilter::Scala);
         let projects = scanner.scan_directory(base);
-
-        // Only the workspace root should be reported; the member must be skipped.
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].root_path, workspace);
+        assert_eq!(projects[0].kind, ProjectType::Scala);
+        assert_eq!(projects[0].name.as_deref(), Some("my-scala-app"));
         Ok(())
     }
 
-    // ── PHP project detection tests ───────────────────────────────────────
-
     #[test]
-    fn test_detect_php_project() -> anyhow::Result<()> {
+    fn test_detect_scala_meta_build_target() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("php-project");
+        let project = base.join("sbt-multi");
         create_file(
-            &project.join("composer.json"),
-            r#"{"name": "acme/my-php-app", "require": {}}"#,
+            &project.join("build.sbt"),
+            "lazy val root = (project in file(\".\"))\n  .settings(name := \"sbt-multi\")\n",
+        )?;
+        create_file(
+            &project.join("project/build.properties"),
+            "sbt.version=1.9.7\n",
+        )?;
+        create_file(&project.join("target/scala-3.3.0/Main.class"), "bytecode")?;
+        create_file(
+            &project.join("project/target/scala-2.12/sbt-1.0/classes/Build.class"),
+            "bytecode",
         )?;
-        create_file(&project.join("vendor/autoload.php"), "<?php // autoloader")?;
 
-        let scanner = default_scanner(ProjectFilter::Php);
+        let scanner = default_scanner(ProjectFilter::Scala);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Php);
-        // Only the package component (after /) should be returned.
-        assert_eq!(projects[0].name.as_deref(), Some("my-php-app"));
+        assert_eq!(projects[0].name.as_deref(), Some("sbt-multi"));
+
+        let paths: Vec<_> = projects[0].build_arts.iter().map(|a| &a.path).collect();
+        assert_eq!(
+            paths,
+            vec![&project.join("target"), &project.join("project/target")]
+        );
         Ok(())
     }
 
     #[test]
-    fn test_detect_php_no_vendor_not_detected() -> anyhow::Result<()> {
+    fn test_detect_scala_no_target_not_detected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("php-no-vendor");
-        create_file(&project.join("composer.json"), r#"{"name": "acme/my-app"}"#)?;
+        let project = base.join("sbt-only");
+        create_file(&project.join("build.sbt"), "name := \"unbuilt-project\"\n")?;
 
-        let scanner = default_scanner(ProjectFilter::Php);
+        let scanner = default_scanner(ProjectFilter::Scala);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 0);
         Ok(())
     }
 
     #[test]
-    fn test_detect_php_fallback_to_dir_name() -> anyhow::Result<()> {
+    fn test_detect_scala_fallback_to_dir_name() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("my-php-project");
-        // composer.json without a "name" field
-        create_file(&project.join("composer.json"), r#"{"require": {}}"#)?;
-        create_file(&project.join("vendor/autoload.php"), "<?php")?;
+        let project = base.join("my-scala-project");
+        // build.sbt without a name := field
+        create_file(&project.join("build.sbt"), "scalaVersion := \"3.3.0\"\n")?;
+        create_file(&project.join("target/scala-3.3.0/Main.class"), "bytecode")?;
 
-        let scanner = default_scanner(ProjectFilter::Php);
+        let scanner = default_scanner(ProjectFilter::Scala);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("my-php-project"));
+        assert_eq!(projects[0].name.as_deref(), Some("my-scala-project"));
         Ok(())
     }
 
-    // ── Haskell project detection tests ──────────────────────────────────
-
     #[test]
-    fn test_detect_haskell_stack_project() -> anyhow::Result<()> {
+    fn test_scala_detected_before_java_for_build_sbt_projects() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("haskell-stack");
-        create_file(
-            &project.join("stack.yaml"),
-            "resolver: lts-21.0\npackages:\n  - .",
-        )?;
-        create_file(
-            &project.join("my-haskell-lib.cabal"),
-            "name:          my-haskell-lib\nversion:       0.1.0.0\n",
-        )?;
+        // A project with both build.sbt and pom.xml should be detected as Scala.
+        let project = base.join("scala-maven-project");
+        create_file(&project.join("build.sbt"), "name := \"scala-maven\"\n")?;
         create_file(
-            &project.join(".stack-work/dist/x86_64-linux/ghc-9.4.7/build/Main.o"),
-            "object",
+            &project.join("pom.xml"),
+            "<project><artifactId>scala-maven</artifactId></project>",
         )?;
+        create_file(&project.join("target/scala-3.3.0/Main.class"), "bytecode")?;
 
-        let scanner = default_scanner(ProjectFilter::Haskell);
+        let scanner = default_scanner(ProjectFilter::All);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Haskell);
-        assert_eq!(projects[0].name.as_deref(), Some("my-haskell-lib"));
+        assert_eq!(projects[0].kind, ProjectType::Scala);
         Ok(())
     }
 
     #[test]
-    fn test_detect_haskell_cabal_project() -> anyhow::Result<()> {
+    fn test_rust_target_dirs_are_split_by_target_triple() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("haskell-cabal");
-        create_file(&project.join("cabal.project"), "packages: .\n")?;
+        let project = base.join("firmware");
         create_file(
-            &project.join("my-cabal-lib.cabal"),
-            "name:          my-cabal-lib\nversion:       0.1.0.0\n",
+            &project.join("Cargo.toml"),
+            "[package]\nname = \"firmware\"\n",
         )?;
+        create_file(&project.join("target/CACHEDIR.TAG"), "Signature")?;
+        create_file(&project.join("target/debug/deps/firmware"), "binary")?;
         create_file(
-            &project.join(
-                "dist-newstyle/build/x86_64-linux/ghc-9.4.7/my-cabal-lib-0.1.0.0/build/Main.o",
-            ),
-            "object",
+            &project.join("target/thumbv7em-none-eabihf/release/deps/firmware"),
+            "binary",
         )?;
+        create_file(
+            &project.join("target/wasm32-wasip1/debug/.fingerprint/firmware/hash"),
+            "hash",
+        )?;
+        create_file(&project.join("target-xtask/CACHEDIR.TAG"), "Signature")?;
+        create_file(&project.join("target-xtask/debug/xtask"), "binary")?;
+        // Not written by Cargo
+        create_file(&project.join("target-notes/todo.txt"), "notes")?;
 
-        let scanner = default_scanner(ProjectFilter::Haskell);
+        let scanner = default_scanner(ProjectFilter::Rust);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Haskell);
-        assert_eq!(projects[0].name.as_deref(), Some("my-cabal-lib"));
+
+        let mut artifacts: Vec<_> = projects[0]
+            .build_arts
+            .iter()
+            .filter_map(|a| a.path.strip_prefix(&project).ok())
+            .map(Path::to_path_buf)
+            .collect();
+        artifacts.sort();
+        assert_eq!(
+            artifacts,
+            [
+                "target/debug",
+                "target/thumbv7em-none-eabihf",
+                "target/wasm32-wasip1",
+                "target-xtask",
+            ]
+            .map(PathBuf::from)
+            .to_vec()
+        );
         Ok(())
     }
 
     #[test]
-    fn test_detect_haskell_no_artifact_not_detected() -> anyhow::Result<()> {
+    fn test_detect_android_project_with_modules() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("haskell-no-artifact");
-        create_file(&project.join("stack.yaml"), "resolver: lts-21.0")?;
+        let project = base.join("my-app");
+        create_file(
+            &project.join("settings.gradle.kts"),
+            "rootProject.name = \"MyApp\"\ninclude(\":app\")\ninclude(\":core:data\")\n",
+        )?;
+        create_file(
+            &project.join("gradle.properties"),
+            "android.useAndroidX=true\n",
+        )?;
+        create_file(&project.join(".gradle/8.7/checksums.lock"), "lock")?;
+        create_file(
+            &project.join("app/build.gradle.kts"),
+            "plugins { id(\"com.android.application\") }\n",
+        )?;
+        create_file(&project.join("app/build/outputs/app.apk"), "apk")?;
+        create_file(&project.join("app/.cxx/Debug/native.o"), "object")?;
+        create_file(
+            &project.join("core/data/build.gradle.kts"),
+            "plugins { id(\"com.android.library\") }\n",
+        )?;
+        create_file(
+            &project.join("core/data/build/intermediates/classes.jar"),
+            "jar",
+        )?;
 
-        let scanner = default_scanner(ProjectFilter::Haskell);
+        let scanner = default_scanner(ProjectFilter::All);
         let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 0);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].kind, ProjectType::Android);
+        assert_eq!(projects[0].name.as_deref(), Some("MyApp"));
+
+        let mut artifacts: Vec<_> = projects[0]
+            .build_arts
+            .iter()
+            .filter_map(|a| a.path.strip_prefix(&project).ok())
+            .map(Path::to_path_buf)
+            .collect();
+        artifacts.sort();
+        assert_eq!(
+            artifacts,
+            [".gradle", "app/.cxx", "app/build", "core/data/build"]
+                .map(PathBuf::from)
+                .to_vec()
+        );
         Ok(())
     }
 
-    // ── Dart/Flutter project detection tests ─────────────────────────────
-
     #[test]
-    fn test_detect_dart_project_with_dart_tool() -> anyhow::Result<()> {
+    fn test_excluded_android_project_is_not_cleaned_as_java() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("dart-project");
+        let project = base.join("my-app");
         create_file(
-            &project.join("pubspec.yaml"),
-            "name: my_dart_app\nversion: 1.0.0\n",
+            &project.join("settings.gradle.kts"),
+            "rootProject.name = \"MyApp\"\ninclude(\":app\")\n",
         )?;
         create_file(
-            &project.join(".dart_tool/package_config.json"),
-            r#"{"configVersion": 2}"#,
+            &project.join("gradle.properties"),
+            "android.useAndroidX=true\n",
         )?;
+        create_file(&project.join(".gradle/8.7/checksums.lock"), "lock")?;
+        create_file(
+            &project.join("app/build.gradle.kts"),
+            "plugins { id(\"com.android.application\") }\n",
+        )?;
+        create_file(&project.join("app/build/outputs/app.apk"), "apk")?;
 
-        let scanner = default_scanner(ProjectFilter::Dart);
-        let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Dart);
-        assert_eq!(projects[0].name.as_deref(), Some("my_dart_app"));
+        let without_android = TypeFilter::new([], [ProjectFilter::Android]);
+        assert!(
+            default_scanner(without_android)
+                .scan_directory(base)
+                .is_empty()
+        );
+        assert!(
+            default_scanner(ProjectFilter::Java)
+                .scan_directory(base)
+                .is_empty()
+        );
         Ok(())
     }
 
     #[test]
-    fn test_detect_dart_project_with_build_dir() -> anyhow::Result<()> {
+    fn test_plain_gradle_project_is_not_android() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("flutter-project");
+        let project = base.join("server");
         create_file(
-            &project.join("pubspec.yaml"),
-            "name: my_flutter_app\nversion: 1.0.0\n",
+            &project.join("settings.gradle"),
+            "rootProject.name = 'server'\n",
         )?;
         create_file(
-            &project.join("build/flutter_assets/AssetManifest.json"),
-            "{}",
+            &project.join("gradle.properties"),
+            "org.gradle.jvmargs=-Xmx2g\n",
         )?;
+        create_file(&project.join("build.gradle"), "plugins { id 'java' }\n")?;
+        create_file(&project.join("build/libs/server.jar"), "jar")?;
 
-        let scanner = default_scanner(ProjectFilter::Dart);
+        let scanner = default_scanner(ProjectFilter::All);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Dart);
-        assert_eq!(projects[0].name.as_deref(), Some("my_flutter_app"));
+        assert_eq!(projects[0].kind, ProjectType::Java);
         Ok(())
     }
 
     #[test]
-    fn test_detect_dart_no_artifact_not_detected() -> anyhow::Result<()> {
+    fn test_every_ecosystem_in_a_directory_is_reported() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("pubspec-only");
-        create_file(&project.join("pubspec.yaml"), "name: empty_project\n")?;
+        let project = base.join("fullstack");
+        create_file(&project.join("package.json"), r#"{"name": "fullstack"}"#)?;
+        create_file(&project.join("node_modules/dep/index.js"), "// dep")?;
+        create_file(&project.join("requirements.txt"), "flask\n")?;
+        create_file(&project.join("__pycache__/app.pyc"), "bytecode")?;
 
-        let scanner = default_scanner(ProjectFilter::Dart);
-        let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 0);
+        let scanner = default_scanner(ProjectFilter::All);
+        let mut projects = scanner.scan_directory(base);
+        projects.sort_by_key(|p| p.kind != ProjectType::Node);
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].kind, ProjectType::Node);
+        assert!(projects[0].build_arts[0].path.ends_with("node_modules"));
+        assert_eq!(projects[1].kind, ProjectType::Python);
+        assert!(
+            projects[1]
+                .build_arts
+                .iter()
+                .all(|a| !a.path.ends_with("node_modules"))
+        );
+        assert_eq!(projects[0].root_path, projects[1].root_path);
+
+        // Scanning the directory twice keeps both ecosystems, once each
+        let roots = [project.clone(), project];
+        assert_eq!(scanner.scan_directories(&roots).len(), 2);
         Ok(())
     }
 
-    // ── Zig project detection tests ───────────────────────────────────────
+    // ── Per-directory override tests ────────────────────────────────────
 
     #[test]
-    fn test_detect_zig_project_with_cache() -> anyhow::Result<()> {
+    fn test_local_config_adds_extra_artifacts() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("zig-project");
+        let project = base.join("with-coverage");
         create_file(
-            &project.join("build.zig"),
-            "const std = @import(\"std\");\npub fn build(b: *std.Build) void {}\n",
+            &project.join("Cargo.toml"),
+            "[package]\nname = \"with-coverage\"\n",
+        )?;
+        create_file(&project.join("target/debug/app"), "binary")?;
+        create_file(&project.join("coverage/lcov.info"), "coverage data")?;
+        create_file(
+            &project.join(".clean-dev-dirs.toml"),
+            "extra_artifacts = [\"coverage/\", \"missing\", \"../outside\"]\n",
         )?;
-        create_file(&project.join("zig-cache/h/abc123.h"), "// generated")?;
 
-        let scanner = default_scanner(ProjectFilter::Zig);
+        let scanner = default_scanner(ProjectFilter::All);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Zig);
-        // Zig falls back to directory name.
-        assert_eq!(projects[0].name.as_deref(), Some("zig-project"));
+
+        let paths: Vec<_> = projects[0].build_arts.iter().map(|a| &a.path).collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].ends_with("target"));
+        assert!(paths[1].ends_with("coverage"));
+        assert!(projects[0].build_arts[1].size > 0);
+        assert!(!projects[0].protected);
         Ok(())
     }
 
     #[test]
-    fn test_detect_zig_project_with_out_dir() -> anyhow::Result<()> {
+    fn test_local_config_marks_project_protected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("zig-out-project");
-        create_file(&project.join("build.zig"), "// zig build script")?;
-        create_file(&project.join("zig-out/bin/my-app"), "binary")?;
+        let project = base.join("keep-me");
+        create_file(&project.join("package.json"), r#"{"name": "keep-me"}"#)?;
+        create_file(&project.join("node_modules/dep/index.js"), "module")?;
+        create_file(&project.join(".clean-dev-dirs.toml"), "protected = true\n")?;
 
-        let scanner = default_scanner(ProjectFilter::Zig);
+        let scanner = default_scanner(ProjectFilter::All);
         let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Zig);
+        assert!(projects[0].protected);
         Ok(())
     }
 
     #[test]
-    fn test_detect_zig_no_artifact_not_detected() -> anyhow::Result<()> {
+    fn test_keep_marker_marks_project_protected() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("zig-no-artifact");
-        create_file(&project.join("build.zig"), "// zig build script")?;
+        for name in ["kept", "other"] {
+            let project = base.join(name);
+            create_file(&project.join("Cargo.toml"), "[package]\nname = \"app\"")?;
+            create_file(&project.join("target/debug/app"), "binary")?;
+        }
+        create_file(&base.join("kept/.keep-build"), "")?;
 
-        let scanner = default_scanner(ProjectFilter::Zig);
+        let scanner = Scanner::new(
+            ScanOptions {
+                keep_marker: Some(".keep-build".to_string()),
+                ..ScanOptions::default()
+            },
+            ProjectFilter::All,
+        );
         let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 0);
+        assert_eq!(projects.len(), 2);
+        for project in &projects {
+            assert_eq!(project.protected, project.root_path.ends_with("kept"));
+        }
+
+        // Without a marker name, marker files aren't looked for
+        let projects = default_scanner(ProjectFilter::All).scan_directory(base);
+        assert!(projects.iter().all(|p| !p.protected));
         Ok(())
     }
 
     #[test]
-    fn test_zig_cache_directory_is_excluded() {
-        assert!(Scanner::is_excluded_directory(Path::new("/some/zig-cache")));
-        assert!(Scanner::is_excluded_directory(Path::new("/some/zig-out")));
-        assert!(Scanner::is_excluded_directory(Path::new(
-            "/some/dist-newstyle"
-        )));
+    fn test_malformed_local_config_protects_the_project() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let base = tmp.path();
+
+        let project = base.join("bad-override");
+        create_file(&project.join("package.json"), r#"{"name": "bad-override"}"#)?;
+        create_file(&project.join("node_modules/dep/index.js"), "module")?;
+        create_file(&project.join(".clean-dev-dirs.toml"), "protected = [")?;
+
+        let scanner = default_scanner(ProjectFilter::All);
+        let projects = scanner.scan_directory(base);
+        assert_eq!(projects.len(), 1);
+        assert!(projects[0].protected);
+        assert_eq!(projects[0].build_arts.len(), 1);
+        Ok(())
     }
 
-    // ── Scala project detection tests ─────────────────────────────────────
+    // ── Configured extra artifact tests ─────────────────────────────────
 
     #[test]
-    fn test_detect_scala_project() -> anyhow::Result<()> {
+    fn test_configured_extra_artifacts_apply_to_matching_type() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("scala-project");
-        create_file(
-            &project.join("build.sbt"),
-            "name := \"my-scala-app\"\nscalaVersion := \"3.3.0\"\n",
-        )?;
+        let rust = base.join("bench-crate");
         create_file(
-            &project.join("target/scala-3.3.0/classes/Main.class"),
-            "bytecode",
+            &rust.join("Cargo.toml"),
+            "[package]\nname = \"bench-crate\"\n",
         )?;
+        create_file(&rust.join("target/debug/app"), "binary")?;
+        create_file(&rust.join(".criterion/report/index.html"), "report")?;
 
-        let scanner = default_scanner(ProjectFilter::Scala);
-        let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Scala);
-        assert_eq!(projects[0].name.as_deref(), Some("my-scala-app"));
+        let node = base.join("web-app");
+        create_file(&node.join("package.json"), r#"{"name": "web-app"}"#)?;
+        create_file(&node.join("node_modules/dep/index.js"), "module")?;
+        create_file(&node.join(".criterion/data.json"), "{}")?;
+
+        let mut extra_artifacts = std::collections::HashMap::new();
+        extra_artifacts.insert(ProjectFilter::Rust, vec![PathBuf::from(".criterion")]);
+
+        let scanner = Scanner::new(
+            ScanOptions {
+                threads: 1,
+                extra_artifacts,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::All,
+        );
+        let mut projects = scanner.scan_directory(base);
+        projects.sort_by(|a, b| a.root_path.cmp(&b.root_path));
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].kind, ProjectType::Rust);
+        assert_eq!(projects[0].build_arts.len(), 2);
+        assert!(projects[0].build_arts[1].path.ends_with(".criterion"));
+        assert_eq!(projects[1].kind, ProjectType::Node);
+        assert_eq!(projects[1].build_arts.len(), 1);
         Ok(())
     }
 
     #[test]
-    fn test_detect_scala_no_target_not_detected() -> anyhow::Result<()> {
+    fn test_configured_extra_artifacts_for_all_types() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        let project = base.join("sbt-only");
-        create_file(&project.join("build.sbt"), "name := \"unbuilt-project\"\n")?;
+        let node = base.join("web-app");
+        create_file(&node.join("package.json"), r#"{"name": "web-app"}"#)?;
+        create_file(&node.join("node_modules/dep/index.js"), "module")?;
+        create_file(&node.join("coverage/lcov.info"), "coverage")?;
 
-        let scanner = default_scanner(ProjectFilter::Scala);
+        let mut extra_artifacts = std::collections::HashMap::new();
+        extra_artifacts.insert(ProjectFilter::All, vec![PathBuf::from("coverage")]);
+
+        let scanner = Scanner::new(
+            ScanOptions {
+                threads: 1,
+                extra_artifacts,
+                ..ScanOptions::default()
+            },
+            ProjectFilter::All,
+        );
         let projects = scanner.scan_directory(base);
-        assert_eq!(projects.len(), 0);
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].build_arts.len(), 2);
+        assert!(projects[0].build_arts[1].path.ends_with("coverage"));
         Ok(())
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_detect_scala_fallback_to_dir_name() -> anyhow::Result<()> {
+    fn test_scan_directories_dedups_nested_roots_and_symlinks() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
-        let base = tmp.path();
+        let code = tmp.path().join("code");
+        let app = code.join("work/app");
+        create_file(&app.join("Cargo.toml"), "[package]\nname = \"app\"")?;
+        create_file(&app.join("target/debug/app"), "binary")?;
+        let alias = tmp.path().join("alias");
+        std::os::unix::fs::symlink(&code, &alias)?;
 
-        let project = base.join("my-scala-project");
-        // build.sbt without a name := field
-        create_file(&project.join("build.sbt"), "scalaVersion := \"3.3.0\"\n")?;
-        create_file(&project.join("target/scala-3.3.0/Main.class"), "bytecode")?;
+        let scanner = default_scanner(ProjectFilter::All);
+        let projects = scanner.scan_directories(&[code.clone(), code.join("work"), alias]);
 
-        let scanner = default_scanner(ProjectFilter::Scala);
-        let projects = scanner.scan_directory(base);
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].name.as_deref(), Some("my-scala-project"));
+        assert_eq!(projects[0].root_path, app);
+        assert_eq!(projects[0].scan_root.as_deref(), Some(code.as_path()));
         Ok(())
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_scala_detected_before_java_for_build_sbt_projects() -> anyhow::Result<()> {
+    fn test_follow_symlinks_modes() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let code = tmp.path().join("code");
+        create_file(&code.join("app/Cargo.toml"), "[package]\nname = \"app\"")?;
+        create_file(&code.join("app/target/debug/app"), "binary")?;
+        let drive = tmp.path().join("drive");
+        create_file(&drive.join("lib/Cargo.toml"), "[package]\nname = \"lib\"")?;
+        create_file(&drive.join("lib/target/debug/lib"), "binary")?;
+
+        // Two links to the same directory, and a loop back to the scan root
+        std::os::unix::fs::symlink(&drive, code.join("drive"))?;
+        std::os::unix::fs::symlink(&drive, code.join("drive-again"))?;
+        std::os::unix::fs::symlink(&code, code.join("app/loop"))?;
+        let alias = tmp.path().join("alias");
+        std::os::unix::fs::symlink(&code, &alias)?;
+
+        let scan = |follow_symlinks, root: &Path| {
+            let scanner = Scanner::new(
+                ScanOptions {
+                    follow_symlinks,
+                    ..ScanOptions::default()
+                },
+                ProjectFilter::All,
+            );
+            let mut names: Vec<_> = scanner
+                .scan_directory(root)
+                .into_iter()
+                .filter_map(|project| project.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(scan(FollowSymlinks::Roots, &alias), vec!["app"]);
+        assert_eq!(scan(FollowSymlinks::Always, &code), vec!["app", "lib"]);
+        assert!(scan(FollowSymlinks::Never, &alias).is_empty());
+        assert_eq!(scan(FollowSymlinks::Never, &code), vec!["app"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_roots_checks_only_the_given_paths() -> anyhow::Result<()> {
         let tmp = TempDir::new()?;
         let base = tmp.path();
 
-        // A project with both build.sbt and pom.xml should be detected as Scala.
-        let project = base.join("scala-maven-project");
-        create_file(&project.join("build.sbt"), "name := \"scala-maven\"\n")?;
-        create_file(
-            &project.join("pom.xml"),
-            "<project><artifactId>scala-maven</artifactId></project>",
-        )?;
-        create_file(&project.join("target/scala-3.3.0/Main.class"), "bytecode")?;
+        let web = base.join("web");
+        create_file(&web.join("package.json"), r#"{"name": "web"}"#)?;
+        create_file(&web.join("node_modules/dep/index.js"), "module")?;
+        create_file(&web.join("requirements.txt"), "requests")?;
+        create_file(&web.join("__pycache__/web.pyc"), "bytecode")?;
+        let api = base.join("api");
+        create_file(&api.join("Cargo.toml"), "[package]\nname = \"api\"")?;
+        create_file(&api.join("target/debug/api"), "binary")?;
+        let nested = base.join("api/tools/gen");
+        create_file(&nested.join("package.json"), r#"{"name": "gen"}"#)?;
+        create_file(&nested.join("node_modules/dep/index.js"), "module")?;
 
         let scanner = default_scanner(ProjectFilter::All);
-        let projects = scanner.scan_directory(base);
+        let projects = scanner.scan_roots(&[
+            web.join("node_modules"),
+            api.clone(),
+            api.join("target"),
+            base.join("missing"),
+        ]);
+
+        // The build directory stands for its own project only (not web's
+        // __pycache__), nothing below `api` is scanned and `api` is listed once
+        let mut names: Vec<_> = projects.iter().filter_map(|p| p.name.as_deref()).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["api", "web"]);
+        assert!(projects.iter().all(|p| p.build_arts.len() == 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_quick_size_threshold_estimates_large_artifacts() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let project = tmp.path().join("big");
+        create_file(&project.join("Cargo.toml"), "[package]\nname = \"big\"")?;
+        for i in 0..20 {
+            create_file(
+                &project.join(format!("target/debug/{i}.o")),
+                &"x".repeat(100),
+            )?;
+        }
+
+        let scanner = Scanner::new(
+            ScanOptions {
+                threads: 1,
+                quick_size_threshold: Some(500),
+                ..ScanOptions::default()
+            },
+            ProjectFilter::Rust,
+        );
+        let mut projects = scanner.scan_directory(tmp.path());
+
         assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].kind, ProjectType::Scala);
+        assert!(projects[0].size_is_estimated());
+        assert!((500..2_000).contains(&projects[0].total_size()));
+        assert!(
+            projects[0]
+                .formatted_size(SizeUnits::Decimal)
+                .ends_with("(estimated)")
+        );
+
+        projects[0].measure_estimated_sizes();
+        assert!(!projects[0].size_is_estimated());
+        assert_eq!(projects[0].total_size(), 2_000);
+        assert_eq!(projects[0].total_files(), 20);
         Ok(())
     }
+
+    #[test]
+    fn test_parse_root_list() {
+        assert_eq!(
+            parse_root_list(b"a/node_modules\r\n\nb\n"),
+            vec![PathBuf::from("a/node_modules"), PathBuf::from("b")]
+        );
+        assert_eq!(
+            parse_root_list(b"with\nnewline\0b\0"),
+            vec![PathBuf::from("with\nnewline"), PathBuf::from("b")]
+        );
+        assert!(parse_root_list(b"").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_root_list_keeps_non_utf8_paths() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        assert_eq!(
+            parse_root_list(b"caf\xe9/target\0b\0"),
+            vec![
+                PathBuf::from(OsStr::from_bytes(b"caf\xe9/target")),
+                PathBuf::from("b")
+            ]
+        );
+    }
 }
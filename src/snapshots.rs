@@ -0,0 +1,317 @@
+//! Build directory sizes remembered between runs.
+//!
+//! Every scan records the size of each build directory it measured in
+//! `sizes.json` in the cache directory, since it can be lost at any time
+//! without harm. The next
+//! scan compares the new sizes against it to show how much each project's
+//! artifacts grew since then, and `--sort growth` puts the fastest-growing
+//! projects first. Build directories removed by a cleanup are
+//! forgotten, so they start over from nothing.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{CleanDevDirsError, Result, ResultExt},
+    paths::Paths,
+    project::Project,
+    utils::{SizeUnits, format_bytes},
+};
+
+/// Size of a build directory when it was last measured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeRecord {
+    /// Size in bytes
+    pub size: u64,
+
+    /// When it was measured, as a Unix timestamp (seconds)
+    pub measured_at: i64,
+}
+
+/// How much a project's build artifacts changed since an earlier scan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct SizeChange {
+    /// Growth in bytes; negative if the artifacts shrank
+    pub bytes: i64,
+
+    /// When the earlier sizes were measured, as a Unix timestamp (seconds)
+    pub since: i64,
+}
+
+impl SizeChange {
+    /// Describe the change relative to `now` in `units`, like `+800 MB since
+    /// last week`.
+    #[must_use]
+    pub fn describe(&self, now: i64, units: SizeUnits) -> String {
+        let sign = if self.bytes < 0 { '-' } else { '+' };
+        format!(
+            "{sign}{} {}",
+            format_bytes(self.bytes.unsigned_abs(), units),
+            describe_elapsed(now - self.since)
+        )
+    }
+}
+
+/// The period since something that happened `seconds` ago, in words
+/// (`since yesterday`, `in 3 days`).
+fn describe_elapsed(seconds: i64) -> String {
+    let days = seconds.max(0) / 86_400;
+    match days {
+        0 => "since earlier today".to_string(),
+        1 => "since yesterday".to_string(),
+        2..=6 => format!("in {days} days"),
+        7..=13 => "since last week".to_string(),
+        14..=59 => format!("in {} weeks", days / 7),
+        _ => format!("in {} months", days / 30),
+    }
+}
+
+/// The remembered sizes, keyed by absolute build directory path.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SizeSnapshots {
+    entries: BTreeMap<PathBuf, SizeRecord>,
+}
+
+impl SizeSnapshots {
+    /// Returns the path of the size snapshot file.
+    ///
+    /// The file is `sizes.json` in the [cache directory](Paths::cache_dir),
+    /// since losing it only loses the size changes of the next scan, or
+    /// `None` if that cannot be determined.
+    #[must_use]
+    pub fn default_path(paths: &Paths) -> Option<PathBuf> {
+        paths.cache_dir().map(|dir| dir.join("sizes.json"))
+    }
+
+    /// Read the sizes stored at `path`.
+    ///
+    /// A missing file means nothing has been measured yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but can't be read or parsed.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).with_context(|| {
+            CleanDevDirsError::state(format!(
+                "Failed to read size snapshot file {}",
+                path.display()
+            ))
+        })?;
+        let entries = serde_json::from_str(&content).with_context(|| {
+            CleanDevDirsError::state(format!("Invalid size snapshot file {}", path.display()))
+        })?;
+        Ok(Self { entries })
+    }
+
+    /// Write the sizes to `path`, creating its directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file or its directory can't be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                CleanDevDirsError::state(format!("Failed to create {}", parent.display()))
+            })?;
+        }
+
+        let mut json = serde_json::to_string_pretty(&self.entries).context(
+            CleanDevDirsError::state("Failed to serialize the size snapshots"),
+        )?;
+        json.push('\n');
+        fs::write(path, json).with_context(|| {
+            CleanDevDirsError::state(format!(
+                "Failed to write size snapshot file {}",
+                path.display()
+            ))
+        })
+    }
+
+    /// How much the artifacts of `project` changed since they were recorded.
+    ///
+    /// Build directories that weren't recorded count as new, grown from
+    /// nothing. Returns `None` if none of them was recorded, or if the
+    /// current size is only an estimate.
+    #[must_use]
+    pub fn change(&self, project: &Project) -> Option<SizeChange> {
+        if project.size_is_estimated() {
+            return None;
+        }
+
+        let previous: Vec<&SizeRecord> = project
+            .build_arts
+            .iter()
+            .filter_map(|artifact| self.entries.get(&key(&artifact.path)))
+            .collect();
+        let since = previous.iter().map(|record| record.measured_at).min()?;
+        let before: u64 = previous.iter().map(|record| record.size).sum();
+
+        let bytes = i128::from(project.total_size()) - i128::from(before);
+        Some(SizeChange {
+            bytes: i64::try_from(bytes).unwrap_or(if bytes < 0 { i64::MIN } else { i64::MAX }),
+            since,
+        })
+    }
+
+    /// Remember the sizes of the build directories of `projects`, measured at `now`.
+    ///
+    /// Estimated sizes are not recorded.
+    pub fn record(&mut self, projects: &[Project], now: i64) {
+        for artifact in projects.iter().flat_map(|p| &p.build_arts) {
+            if !artifact.estimated {
+                self.entries.insert(
+                    key(&artifact.path),
+                    SizeRecord {
+                        size: artifact.size,
+                        measured_at: now,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Forget the build directories of `projects`, e.g. after cleaning them.
+    pub fn forget(&mut self, projects: &[Project]) {
+        for artifact in projects.iter().flat_map(|p| &p.build_arts) {
+            self.entries.remove(&key(&artifact.path));
+        }
+    }
+}
+
+/// Set [`Project::size_change`] from the sizes recorded in `snapshots`.
+pub fn annotate_size_changes(projects: &mut [Project], snapshots: &SizeSnapshots) {
+    for project in projects {
+        project.size_change = snapshots.change(project);
+    }
+}
+
+/// The key of a build directory: its absolute path, so relative scan roots
+/// match too. A directory that was just removed is resolved through its parent.
+fn key(path: &Path) -> PathBuf {
+    fs::canonicalize(path)
+        .ok()
+        .or_else(|| {
+            let parent = fs::canonicalize(path.parent()?).ok()?;
+            Some(parent.join(path.file_name()?))
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use tempfile::TempDir;
+
+    const DAY: i64 = 86_400;
+
+    fn project(root: &Path, artifacts: &[(&str, u64)]) -> Project {
+        let build_arts = artifacts
+            .iter()
+            .map(|&(dir, size)| BuildArtifacts {
+                path: root.join(dir),
+                size,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            })
+            .collect();
+        Project::new(ProjectType::Node, root.to_path_buf(), build_arts, None)
+    }
+
+    #[test]
+    fn test_change_since_recorded_sizes() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("node_modules"))?;
+
+        let mut snapshots = SizeSnapshots::default();
+        assert_eq!(
+            snapshots.change(&project(root, &[("node_modules", 100)])),
+            None
+        );
+
+        snapshots.record(&[project(root, &[("node_modules", 100)])], 10 * DAY);
+
+        // A new build directory counts as grown from nothing
+        let grown = project(root, &[("node_modules", 250), (".venv", 50)]);
+        assert_eq!(
+            snapshots.change(&grown),
+            Some(SizeChange {
+                bytes: 200,
+                since: 10 * DAY
+            })
+        );
+
+        let shrunk = project(root, &[("node_modules", 40)]);
+        assert_eq!(snapshots.change(&shrunk).map(|c| c.bytes), Some(-60));
+
+        let mut estimated = project(root, &[("node_modules", 500)]);
+        estimated.build_arts[0].estimated = true;
+        assert_eq!(snapshots.change(&estimated), None);
+
+        snapshots.forget(&[grown]);
+        assert_eq!(snapshots.change(&shrunk), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_and_load() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let file = tmp.path().join("state").join("sizes.json");
+
+        let mut snapshots = SizeSnapshots::default();
+        snapshots.record(&[project(Path::new("/work/api"), &[("target", 7)])], DAY);
+        snapshots.save(&file)?;
+
+        assert_eq!(SizeSnapshots::load(&file)?, snapshots);
+        assert_eq!(
+            SizeSnapshots::load(&tmp.path().join("missing.json"))?,
+            SizeSnapshots::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe() {
+        let change = SizeChange {
+            bytes: 800_000_000,
+            since: 0,
+        };
+        assert_eq!(
+            change.describe(8 * DAY, SizeUnits::Decimal),
+            "+800 MB since last week"
+        );
+        assert_eq!(
+            change.describe(DAY + 5, SizeUnits::Decimal),
+            "+800 MB since yesterday"
+        );
+
+        let change = SizeChange {
+            bytes: -1_500,
+            since: 0,
+        };
+        assert_eq!(
+            change.describe(3 * DAY, SizeUnits::Decimal),
+            "-1.50 kB in 3 days"
+        );
+        assert_eq!(
+            change.describe(90 * DAY, SizeUnits::Decimal),
+            "-1.50 kB in 3 months"
+        );
+        assert_eq!(
+            change.describe(60, SizeUnits::Decimal),
+            "-1.50 kB since earlier today"
+        );
+    }
+}
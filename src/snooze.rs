@@ -0,0 +1,244 @@
+//! Temporarily excluding projects from cleanup.
+//!
+//! `clean-dev-dirs snooze <path> --for 30d` keeps a project (or every
+//! project below a directory) out of cleanups until the snooze expires.
+//! Snoozes are kept in `snoozes.json` in the state directory (next to the run
+//! history), as a map from absolute path to expiry time.
+//! Expired snoozes are dropped whenever the file is loaded.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, FixedOffset, Local};
+
+use crate::error::{CleanDevDirsError, Result, ResultExt};
+use crate::paths::Paths;
+use crate::project::Project;
+
+/// The active snoozes, keyed by absolute path.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snoozes {
+    /// Expiry time of each snoozed path, in RFC 3339 format
+    entries: BTreeMap<PathBuf, String>,
+}
+
+impl Snoozes {
+    /// Returns the path of the snooze file.
+    ///
+    /// The file is `snoozes.json` in the [state directory](Paths::state_dir),
+    /// or `None` if that cannot be determined.
+    #[must_use]
+    pub fn default_path(paths: &Paths) -> Option<PathBuf> {
+        paths.state_file("snoozes.json")
+    }
+
+    /// Read the snoozes stored at `path`, leaving out expired ones.
+    ///
+    /// A missing file means nothing is snoozed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but can't be read or parsed.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).with_context(|| {
+            CleanDevDirsError::state(format!("Failed to read snooze file {}", path.display()))
+        })?;
+        let entries: BTreeMap<PathBuf, String> =
+            serde_json::from_str(&content).with_context(|| {
+                CleanDevDirsError::state(format!("Invalid snooze file {}", path.display()))
+            })?;
+
+        let now = Local::now();
+        let entries = entries
+            .into_iter()
+            .filter(|(_, until)| parse_time(until).is_some_and(|until| until > now))
+            .collect();
+        Ok(Self { entries })
+    }
+
+    /// Write the snoozes to `path`, creating its directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file or its directory can't be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                CleanDevDirsError::state(format!("Failed to create {}", parent.display()))
+            })?;
+        }
+
+        let mut json = serde_json::to_string_pretty(&self.entries)
+            .context(CleanDevDirsError::state("Failed to serialize the snoozes"))?;
+        json.push('\n');
+        fs::write(path, json).with_context(|| {
+            CleanDevDirsError::state(format!("Failed to write snooze file {}", path.display()))
+        })
+    }
+
+    /// Snooze `path` for `duration` from now, replacing any earlier snooze.
+    ///
+    /// Returns the expiry time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `duration` is too large to represent.
+    pub fn add(&mut self, path: PathBuf, duration: Duration) -> Result<DateTime<Local>> {
+        let until = chrono::Duration::from_std(duration)
+            .ok()
+            .and_then(|d| Local::now().checked_add_signed(d))
+            .ok_or_else(|| CleanDevDirsError::config("Snooze duration is too large"))?;
+
+        self.entries.insert(path, until.to_rfc3339());
+        Ok(until)
+    }
+
+    /// Remove the snooze of `path`. Returns whether it was snoozed.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        self.entries.remove(path).is_some()
+    }
+
+    /// Remove every snooze.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Return true if nothing is snoozed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The snoozed paths with their expiry times, in path order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, Option<DateTime<FixedOffset>>)> {
+        self.entries
+            .iter()
+            .map(|(path, until)| (path.as_path(), parse_time(until)))
+    }
+
+    /// When the snooze covering `path` expires, if `path` or one of its
+    /// ancestors is snoozed.
+    ///
+    /// `path` is made absolute first, so relative scan roots match too.
+    #[must_use]
+    pub fn snoozed_until(&self, path: &Path) -> Option<DateTime<FixedOffset>> {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        path.ancestors()
+            .find_map(|ancestor| self.entries.get(ancestor))
+            .and_then(|until| parse_time(until))
+    }
+
+    /// Split `projects` into those that can be cleaned and those that are
+    /// snoozed, with the expiry time of each snoozed project.
+    #[must_use]
+    pub fn partition(
+        &self,
+        projects: Vec<Project>,
+    ) -> (Vec<Project>, Vec<(Project, DateTime<FixedOffset>)>) {
+        if self.is_empty() {
+            return (projects, Vec::new());
+        }
+
+        let mut kept = Vec::new();
+        let mut snoozed = Vec::new();
+        for project in projects {
+            match self.snoozed_until(&project.root_path) {
+                Some(until) => snoozed.push((project, until)),
+                None => kept.push(project),
+            }
+        }
+        (kept, snoozed)
+    }
+}
+
+/// Parse an RFC 3339 expiry time as stored in the snooze file.
+fn parse_time(time: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(time).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::ProjectType;
+    use tempfile::TempDir;
+
+    fn project(root: &Path) -> Project {
+        Project::new(ProjectType::Rust, root.to_path_buf(), Vec::new(), None)
+    }
+
+    #[test]
+    fn test_save_and_load_drops_expired() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let file = tmp.path().join("state").join("snoozes.json");
+
+        let mut snoozes = Snoozes::default();
+        snoozes.add(PathBuf::from("/work/api"), Duration::from_hours(24))?;
+        snoozes.entries.insert(
+            PathBuf::from("/work/old"),
+            "2020-01-01T00:00:00+00:00".into(),
+        );
+        snoozes.save(&file)?;
+
+        let loaded = Snoozes::load(&file)?;
+        let paths: Vec<&Path> = loaded.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, vec![Path::new("/work/api")]);
+        assert!(Snoozes::load(&tmp.path().join("missing.json"))?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_fails_on_a_corrupt_file() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let file = tmp.path().join("snoozes.json");
+        fs::write(&file, "{\"/work/api\": ")?;
+
+        let error = Snoozes::load(&file).err().map(|e| e.to_string());
+        assert!(error.is_some_and(|e| e.starts_with("Invalid snooze file")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_partition_matches_path_and_descendants() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = fs::canonicalize(tmp.path())?;
+        for dir in ["api", "clients/web", "other"] {
+            fs::create_dir_all(root.join(dir))?;
+        }
+
+        let mut snoozes = Snoozes::default();
+        snoozes.add(root.join("api"), Duration::from_hours(1))?;
+        snoozes.add(root.join("clients"), Duration::from_hours(1))?;
+
+        let (kept, skipped) = snoozes.partition(vec![
+            project(&root.join("api")),
+            project(&root.join("clients/web")),
+            project(&root.join("other")),
+        ]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].root_path, root.join("other"));
+        assert_eq!(skipped.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_and_clear() -> anyhow::Result<()> {
+        let mut snoozes = Snoozes::default();
+        snoozes.add(PathBuf::from("/a"), Duration::from_hours(1))?;
+        snoozes.add(PathBuf::from("/b"), Duration::from_hours(1))?;
+
+        assert!(snoozes.remove(Path::new("/a")));
+        assert!(!snoozes.remove(Path::new("/a")));
+        snoozes.clear();
+        assert!(snoozes.is_empty());
+        Ok(())
+    }
+}
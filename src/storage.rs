@@ -0,0 +1,353 @@
+//! The kind of storage a directory is on, and how many threads suit it.
+//!
+//! A scan thread per CPU core is right for SSDs, but on a spinning disk the
+//! threads make the heads seek back and forth between directories, and on a
+//! network mount every thread adds round trips to a server that is shared
+//! with others. Unless a thread count is given, the scan therefore looks up
+//! the storage of each scanned directory and uses the default of the slowest
+//! one (see [`slowest_storage`]).
+//!
+//! On Linux, network filesystems are recognized by their type in
+//! `/proc/self/mountinfo`, and spinning disks by the `rotational` flag the
+//! kernel keeps for each block device. On Windows, the drive type tells
+//! network drives apart; on macOS, the filesystem type does. Anything else
+//! is [`StorageKind::Unknown`] and scanned with all cores, as before.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+/// Threads used to scan a spinning disk, which can only read in one place
+/// at a time.
+const ROTATIONAL_THREADS: usize = 2;
+
+/// Threads used to scan a network mount.
+const NETWORK_THREADS: usize = 4;
+
+/// The kind of storage a directory is on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StorageKind {
+    /// A solid-state drive or another device without seek times
+    SolidState,
+
+    /// A spinning hard disk
+    Rotational,
+
+    /// A network filesystem, such as NFS or SMB
+    Network,
+
+    /// Storage that couldn't be identified
+    Unknown,
+}
+
+impl StorageKind {
+    /// The number of scan threads that suits this storage, or `None` for
+    /// one per CPU core.
+    #[must_use]
+    pub const fn default_threads(self) -> Option<usize> {
+        match self {
+            Self::Rotational => Some(ROTATIONAL_THREADS),
+            Self::Network => Some(NETWORK_THREADS),
+            Self::SolidState | Self::Unknown => None,
+        }
+    }
+}
+
+impl Display for StorageKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::SolidState => "solid-state drive",
+            Self::Rotational => "rotational disk",
+            Self::Network => "network mount",
+            Self::Unknown => "unknown storage",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// The kind of storage `path` is on.
+#[must_use]
+pub fn storage_kind(path: &Path) -> StorageKind {
+    sys::storage_kind(path).unwrap_or(StorageKind::Unknown)
+}
+
+/// The first of `roots` on the storage that suits the fewest threads, with
+/// its storage kind, or `None` if all of them can be scanned with one
+/// thread per CPU core.
+#[must_use]
+pub fn slowest_storage(roots: &[PathBuf]) -> Option<(PathBuf, StorageKind)> {
+    slowest(roots.iter().map(|root| (root.clone(), storage_kind(root))))
+}
+
+/// The first of `kinds` whose storage suits the fewest threads, if any
+/// suits fewer than one per CPU core.
+fn slowest(
+    kinds: impl IntoIterator<Item = (PathBuf, StorageKind)>,
+) -> Option<(PathBuf, StorageKind)> {
+    kinds
+        .into_iter()
+        .filter_map(|(root, kind)| Some((kind.default_threads()?, root, kind)))
+        .min_by_key(|(threads, _, _)| *threads)
+        .map(|(_, root, kind)| (root, kind))
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use super::StorageKind;
+
+    /// Filesystem types that are served over the network.
+    const NETWORK_FS_TYPES: [&str; 15] = [
+        "nfs",
+        "nfs4",
+        "cifs",
+        "smb3",
+        "smbfs",
+        "ncpfs",
+        "afs",
+        "ceph",
+        "glusterfs",
+        "lustre",
+        "9p",
+        "davfs",
+        "fuse.sshfs",
+        "fuse.rclone",
+        "fuse.s3fs",
+    ];
+
+    /// One line of `/proc/self/mountinfo`.
+    #[derive(Debug, PartialEq, Eq)]
+    pub(super) struct Mount<'a> {
+        /// Where the filesystem is mounted
+        pub(super) path: PathBuf,
+
+        /// The `major:minor` number of its device
+        pub(super) device: &'a str,
+
+        /// Its filesystem type, such as `ext4` or `nfs4`
+        pub(super) fs_type: &'a str,
+
+        /// What was mounted, such as `/dev/sda1` or `server:/export`
+        pub(super) source: &'a str,
+    }
+
+    pub(super) fn storage_kind(path: &Path) -> Option<StorageKind> {
+        let path = fs::canonicalize(path).ok()?;
+        let mountinfo = fs::read_to_string("/proc/self/mountinfo").ok()?;
+        let mount = mount_of(&mountinfo, &path)?;
+
+        if is_network_fs(mount.fs_type) {
+            return Some(StorageKind::Network);
+        }
+        let rotational = rotational(mount.device, mount.source)?;
+        Some(if rotational {
+            StorageKind::Rotational
+        } else {
+            StorageKind::SolidState
+        })
+    }
+
+    /// The mount in `mountinfo` that `path` is on: the one with the longest
+    /// mount point containing it, and the last of those if one filesystem
+    /// was mounted over another.
+    pub(super) fn mount_of<'a>(mountinfo: &'a str, path: &Path) -> Option<Mount<'a>> {
+        mountinfo
+            .lines()
+            .filter_map(parse_mount)
+            .filter(|mount| path.starts_with(&mount.path))
+            .max_by_key(|mount| mount.path.components().count())
+    }
+
+    /// Parse a line of `/proc/self/mountinfo`, in which the mount point is
+    /// the fifth field and the filesystem type and source follow the `-`
+    /// separator.
+    fn parse_mount(line: &str) -> Option<Mount<'_>> {
+        let (fields, rest) = line.split_once(" - ")?;
+        let mut fields = fields.split(' ');
+        let device = fields.nth(2)?;
+        let mount_point = unescape(fields.nth(1)?);
+        let mut rest = rest.split(' ');
+        Some(Mount {
+            path: PathBuf::from(mount_point),
+            device,
+            fs_type: rest.next()?,
+            source: rest.next()?,
+        })
+    }
+
+    /// Undo the octal escapes (`\040` for a space) of a mountinfo field.
+    fn unescape(field: &str) -> String {
+        let mut out = String::with_capacity(field.len());
+        let mut rest = field;
+        while let Some(index) = rest.find('\\') {
+            out.push_str(&rest[..index]);
+            let code = rest.get(index + 1..index + 4);
+            if let Some(byte) = code.and_then(|code| u8::from_str_radix(code, 8).ok()) {
+                out.push(char::from(byte));
+                rest = &rest[index + 4..];
+            } else {
+                out.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Whether filesystems of type `fs_type` are served over the network.
+    pub(super) fn is_network_fs(fs_type: &str) -> bool {
+        NETWORK_FS_TYPES.contains(&fs_type)
+    }
+
+    /// Whether the block device of a mount is a spinning disk, looked up by
+    /// its device number and then by the device it was mounted from (for
+    /// filesystems such as btrfs, whose device numbers are virtual).
+    fn rotational(device: &str, source: &str) -> Option<bool> {
+        let by_number = Path::new("/sys/dev/block").join(device);
+        let by_name = fs::canonicalize(source)
+            .ok()
+            .and_then(|source| Some(Path::new("/sys/class/block").join(source.file_name()?)));
+
+        std::iter::once(by_number)
+            .chain(by_name)
+            .find_map(|dir| rotational_flag(&dir))
+    }
+
+    /// The `rotational` flag of the block device in `dir` of sysfs.
+    /// Partitions keep it in the directory of their disk.
+    fn rotational_flag(dir: &Path) -> Option<bool> {
+        [
+            dir.join("queue/rotational"),
+            dir.join("../queue/rotational"),
+        ]
+        .iter()
+        .find_map(|file| fs::read_to_string(file).ok())
+        .map(|flag| flag.trim() == "1")
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod sys {
+    use std::{
+        ffi::{CStr, CString},
+        os::unix::ffi::OsStrExt,
+        path::Path,
+    };
+
+    use super::StorageKind;
+
+    /// Filesystem types that are served over the network.
+    const NETWORK_FS_TYPES: [&str; 5] = ["nfs", "smbfs", "afpfs", "webdav", "cifs"];
+
+    pub(super) fn storage_kind(path: &Path) -> Option<StorageKind> {
+        let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+        // SAFETY: statfs is a plain C struct for which all-zero is a valid value.
+        let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+        // SAFETY: `path` is NUL-terminated and `stats` is a valid out pointer.
+        if unsafe { libc::statfs(path.as_ptr(), &raw mut stats) } != 0 {
+            return None;
+        }
+
+        // SAFETY: the kernel NUL-terminates the filesystem type name.
+        let fs_type = unsafe { CStr::from_ptr(stats.f_fstypename.as_ptr()) };
+        let fs_type = fs_type.to_str().ok()?;
+        NETWORK_FS_TYPES
+            .contains(&fs_type)
+            .then_some(StorageKind::Network)
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::{os::windows::ffi::OsStrExt, path::Path};
+
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+
+    use super::StorageKind;
+    use crate::volumes::volume_of;
+
+    /// The drive type `GetDriveTypeW` returns for network drives.
+    const DRIVE_REMOTE: u32 = 4;
+
+    pub(super) fn storage_kind(path: &Path) -> Option<StorageKind> {
+        let root = volume_of(path)?;
+        let root: Vec<u16> = root.as_os_str().encode_wide().chain(Some(0)).collect();
+        // SAFETY: `root` is NUL-terminated.
+        let drive_type = unsafe { GetDriveTypeW(root.as_ptr()) };
+        (drive_type == DRIVE_REMOTE).then_some(StorageKind::Network)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod sys {
+    use std::path::Path;
+
+    use super::StorageKind;
+
+    /// Storage can't be identified on this platform.
+    pub(super) const fn storage_kind(_path: &Path) -> Option<StorageKind> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slowest_storage_decides() {
+        let kinds = |kinds: &[(&str, StorageKind)]| {
+            kinds
+                .iter()
+                .map(|(root, kind)| (PathBuf::from(root), *kind))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            slowest(kinds(&[
+                ("/ssd", StorageKind::SolidState),
+                ("/nfs", StorageKind::Network),
+                ("/hdd", StorageKind::Rotational),
+                ("/hdd2", StorageKind::Rotational),
+            ])),
+            Some((PathBuf::from("/hdd"), StorageKind::Rotational))
+        );
+        assert_eq!(
+            slowest(kinds(&[
+                ("/ssd", StorageKind::SolidState),
+                ("/tmp", StorageKind::Unknown),
+            ])),
+            None
+        );
+        assert_eq!(StorageKind::Network.default_threads(), Some(4));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_mount_of() {
+        let mountinfo = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+40 22 8:1 / /mnt/data rw,relatime shared:20 - ext4 /dev/sda1 rw
+41 22 0:52 / /mnt/team\\040share rw,relatime shared:21 - nfs4 server:/export rw,vers=4.2
+42 40 0:53 / /mnt/data rw,relatime shared:22 - btrfs /dev/sdb rw
+";
+        let mount = |path| sys::mount_of(mountinfo, Path::new(path));
+
+        assert_eq!(
+            mount("/home/me/code").map(|m| m.source),
+            Some("/dev/nvme0n1p2")
+        );
+        // The later mount over /mnt/data wins
+        assert_eq!(mount("/mnt/data/builds").map(|m| m.fs_type), Some("btrfs"));
+        let share = mount("/mnt/team share/ci");
+        assert_eq!(share.as_ref().map(|m| m.device), Some("0:52"));
+        assert!(share.is_some_and(|m| sys::is_network_fs(m.fs_type)));
+        // A sibling with a longer name isn't below the mount point
+        assert_eq!(mount("/mnt/database").map(|m| m.fs_type), Some("ext4"));
+    }
+}
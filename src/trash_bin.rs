@@ -0,0 +1,201 @@
+//! Emptying cleaned build directories from the system trash.
+//!
+//! Moving build directories to the trash keeps them recoverable, but their
+//! space isn't reclaimed until the trash is emptied. This module permanently
+//! deletes the trashed build directories, and only those: right after a run
+//! moves directories to the trash, their entries are looked up and recorded
+//! as [`TrashedItem`]s, and only entries matching a record exactly (the same
+//! entry, original path and deletion time) are purged. Unrelated files in the
+//! trash, and other items later trashed from the same path, are never touched.
+//!
+//! Listing and purging the trash is supported on Windows and on
+//! Freedesktop-compliant Unix systems (Linux, BSD), but not on macOS.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CleanDevDirsError, Result};
+
+/// Whether the platform lets build directories be removed from the trash.
+pub const SUPPORTED: bool = cfg!(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+));
+
+/// A build directory moved to the trash by clean-dev-dirs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedItem {
+    /// Where the directory was before it was trashed
+    pub original_path: PathBuf,
+
+    /// The entry in the trash: the path of its `.trashinfo` file on
+    /// Freedesktop systems, its parsing name in the Recycle Bin on Windows
+    pub id: String,
+
+    /// When the directory was trashed, in seconds since the Unix epoch
+    pub deleted_at: i64,
+}
+
+/// Look up the trash entries of the directories at `paths`, which were moved
+/// to the trash no earlier than `since` (in seconds since the Unix epoch).
+///
+/// A path trashed more than once since then stands for its latest entry.
+/// Paths without an entry are left out.
+///
+/// # Errors
+///
+/// Returns an error if the trash can't be listed. On platforms where it
+/// can't be listed at all (see [`SUPPORTED`]), returns no items.
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn identify(paths: &[PathBuf], since: i64) -> Result<Vec<TrashedItem>> {
+    use std::collections::{HashMap, HashSet};
+
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let paths: HashSet<PathBuf> = paths.iter().map(|path| trashed_from(path)).collect();
+
+    let mut latest: HashMap<PathBuf, TrashedItem> = HashMap::new();
+    for item in trash::os_limited::list()
+        .map_err(|e| CleanDevDirsError::cleanup(format!("Failed to list the trash: {e}")))?
+    {
+        let original_path = item.original_path();
+        if item.time_deleted < since || !paths.contains(&original_path) {
+            continue;
+        }
+        let newer = latest
+            .get(&original_path)
+            .is_none_or(|known| known.deleted_at < item.time_deleted);
+        if newer {
+            latest.insert(
+                original_path.clone(),
+                TrashedItem {
+                    original_path,
+                    id: item.id.to_string_lossy().into_owned(),
+                    deleted_at: item.time_deleted,
+                },
+            );
+        }
+    }
+
+    let mut items: Vec<_> = latest.into_values().collect();
+    items.sort_by(|a, b| a.original_path.cmp(&b.original_path));
+    Ok(items)
+}
+
+/// The location the trash records for a directory trashed from `path`:
+/// absolute, with its parent canonicalized the way the `trash` crate does.
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn trashed_from(path: &std::path::Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let parent = path.parent().and_then(|parent| parent.canonicalize().ok());
+    match (parent, path.file_name()) {
+        (Some(parent), Some(name)) => parent.join(name),
+        _ => path,
+    }
+}
+
+/// Look up the trash entries of the directories at `paths`.
+///
+/// # Errors
+///
+/// Never fails: the trash can't be listed on this platform, so there are no
+/// items.
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn identify(_paths: &[PathBuf], _since: i64) -> Result<Vec<TrashedItem>> {
+    Ok(Vec::new())
+}
+
+/// Permanently delete the trash entries recorded in `items`.
+///
+/// Only entries that match a record in id, original path and deletion time
+/// are deleted; records whose entry is gone are passed over. Returns the
+/// number of entries deleted.
+///
+/// # Errors
+///
+/// Returns an error if the trash can't be listed or purged, or if the
+/// platform doesn't support it (see [`SUPPORTED`]).
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn purge(items: &[TrashedItem]) -> Result<usize> {
+    use trash::os_limited::{list, purge_all};
+
+    if items.is_empty() {
+        return Ok(0);
+    }
+
+    let entries: Vec<_> = list()
+        .map_err(|e| CleanDevDirsError::cleanup(format!("Failed to list the trash: {e}")))?
+        .into_iter()
+        .filter(|entry| {
+            items.iter().any(|item| {
+                item.id == entry.id.to_string_lossy()
+                    && item.deleted_at == entry.time_deleted
+                    && item.original_path == entry.original_path()
+            })
+        })
+        .collect();
+    let count = entries.len();
+
+    purge_all(entries)
+        .map_err(|e| CleanDevDirsError::cleanup(format!("Failed to empty the trash: {e}")))?;
+    Ok(count)
+}
+
+/// Permanently delete the trash entries recorded in `items`.
+///
+/// # Errors
+///
+/// Always returns an error: the trash can't be purged on this platform.
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn purge(_items: &[TrashedItem]) -> Result<usize> {
+    Err(CleanDevDirsError::cleanup(
+        "Emptying the trash isn't supported on this platform; empty it from the file manager",
+    ))
+}
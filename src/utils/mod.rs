@@ -1,8 +1,13 @@
 //! Utility functions and helpers.
 //!
 //! This module contains utility functions used throughout the application,
-//! such as size parsing and formatting helpers.
+//! such as size parsing and formatting helpers, directory sizing backends, and
+//! robust directory removal.
 
+pub mod remove;
 pub mod size;
+pub mod sizing;
 
-pub use size::{calculate_dir_size, parse_size};
+pub use remove::{force_remove_dir, remove_empty_dir, rename_aside, restore_aside, stale_asides};
+pub use size::{DirStats, SizeUnits, calculate_dir_size, child_sizes, format_bytes, parse_size};
+pub use sizing::{SizeBackend, SizeBackendKind};
@@ -0,0 +1,416 @@
+//! Permanent removal of directory trees.
+//!
+//! `fs::remove_dir_all` on its own trips over trees that build tools produce
+//! routinely: read-only files and directories (Go's module cache, git objects,
+//! files with the Windows read-only attribute), and on Windows, paths longer
+//! than `MAX_PATH` (260 characters) or with reserved device names such as
+//! `aux.js`, both common deep inside `node_modules`. Windows paths are
+//! therefore passed in their `\\?\` extended-length form, which skips the
+//! Win32 path length limit and name parsing, and read-only permissions are
+//! cleared when a removal is denied.
+//!
+//! Removing a large tree in place also leaves it half-present for as long as
+//! the removal takes, which breaks a build that uses it at the same time.
+//! [`rename_aside`] moves it out of the way first, so its path is gone at once.
+//! A tree whose removal fails is moved back with [`restore_aside`], and one
+//! left aside by a run that was interrupted is found by [`stale_asides`].
+
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// Counter that keeps the names from [`rename_aside`] unique within a run.
+static RENAMED: AtomicU32 = AtomicU32::new(0);
+
+/// Remove a directory and all of its contents.
+///
+/// If the removal fails with a permission error, write permission is restored
+/// on everything left in the tree and the removal is tried once more.
+///
+/// # Errors
+///
+/// Returns the error from the last removal attempt.
+pub fn force_remove_dir(path: &Path) -> io::Result<()> {
+    let path = extended_length_path(path);
+
+    match fs::remove_dir_all(&path) {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            make_writable(&path);
+            fs::remove_dir_all(&path)
+        }
+        result => result,
+    }
+}
+
+/// Rename the directory at `path` to a unique hidden sibling and return the
+/// new path.
+///
+/// The sibling is named `.<name>.deleting-<pid>-<n>`. A rename within one
+/// directory is atomic, so `path` disappears at once and the tree can be
+/// removed from its new place at leisure.
+///
+/// # Errors
+///
+/// Returns the error of the rename, e.g. when `path` is a mount point, which
+/// can't be renamed, or when `path` has no parent directory.
+pub fn rename_aside(path: &Path) -> io::Result<PathBuf> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no parent directory", path.display()),
+        ));
+    };
+
+    loop {
+        let mut aside = OsString::from(".");
+        aside.push(name);
+        aside.push(format!(
+            ".deleting-{}-{}",
+            std::process::id(),
+            RENAMED.fetch_add(1, Ordering::Relaxed)
+        ));
+        let aside = parent.join(aside);
+
+        // Renaming onto an existing empty directory would replace it
+        if fs::symlink_metadata(&aside).is_err() {
+            fs::rename(extended_length_path(path), extended_length_path(&aside))?;
+            return Ok(aside);
+        }
+    }
+}
+
+/// Move the directory renamed aside to `aside` back to its original `path`.
+///
+/// # Errors
+///
+/// Returns an error if something has taken the place of `path` in the
+/// meantime, such as a build that started over, or if the rename fails.
+pub fn restore_aside(aside: &Path, path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists again", path.display()),
+        ));
+    }
+    fs::rename(extended_length_path(aside), extended_length_path(path))
+}
+
+/// The siblings that [`rename_aside`] made of `path` in other processes,
+/// sorted. They're left over from runs that were interrupted before the
+/// removal was done.
+#[must_use]
+pub fn stale_asides(path: &Path) -> Vec<PathBuf> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Vec::new();
+    };
+    let mut prefix = OsString::from(".");
+    prefix.push(name);
+    prefix.push(".deleting-");
+    let prefix = prefix.to_string_lossy().into_owned();
+    let own = format!("{}-", std::process::id());
+
+    let mut stale: Vec<PathBuf> = fs::read_dir(parent)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix(&prefix).is_some_and(|suffix| {
+                let mut parts = suffix.splitn(2, '-');
+                let (Some(pid), Some(n)) = (parts.next(), parts.next()) else {
+                    return false;
+                };
+                let numeric =
+                    |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+                numeric(pid) && numeric(n) && !suffix.starts_with(&own)
+            })
+        })
+        .map(|entry| entry.path())
+        .collect();
+    stale.sort();
+    stale
+}
+
+/// Convert `path` to its `\\?\` extended-length form.
+///
+/// The path is made absolute first, since extended-length paths are used
+/// verbatim: `.`, `..` and `/` separators are not resolved by Windows.
+/// Paths that are already verbatim, or can't be made absolute, are returned
+/// unchanged.
+#[cfg(windows)]
+fn extended_length_path(path: &Path) -> PathBuf {
+    use std::{
+        ffi::OsString,
+        path::{Component, Prefix},
+    };
+
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+
+    let mut extended = match absolute.components().next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::Disk(_) => OsString::from(r"\\?\"),
+            Prefix::UNC(..) => {
+                // \\server\share\dir becomes \\?\UNC\server\share\dir
+                let mut extended = OsString::from(r"\\?\UNC\");
+                extended.push(
+                    absolute
+                        .as_os_str()
+                        .to_string_lossy()
+                        .trim_start_matches('\\'),
+                );
+                return PathBuf::from(extended);
+            }
+            _ => return absolute,
+        },
+        _ => return absolute,
+    };
+    extended.push(absolute.as_os_str());
+    PathBuf::from(extended)
+}
+
+/// Paths have no length limit worth working around outside Windows.
+#[cfg(not(windows))]
+fn extended_length_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// Remove the directory at `path` if it's empty.
+///
+/// Uses the extended-length form of the path on Windows, like
+/// [`force_remove_dir`], so empty parents deep inside a tree go as well.
+///
+/// # Errors
+///
+/// Returns an error if the directory isn't empty or can't be removed.
+pub fn remove_empty_dir(path: &Path) -> io::Result<()> {
+    fs::remove_dir(extended_length_path(path))
+}
+
+/// Restore write permission on `path` and everything below it.
+///
+/// Symlinks are not followed. Errors are ignored: whatever can't be fixed
+/// makes the following removal attempt fail with a meaningful error.
+fn make_writable(path: &Path) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if metadata.file_type().is_symlink() {
+        return;
+    }
+
+    set_writable(path, metadata.permissions(), metadata.is_dir());
+
+    if metadata.is_dir()
+        && let Ok(entries) = fs::read_dir(path)
+    {
+        for entry in entries.flatten() {
+            make_writable(&entry.path());
+        }
+    }
+}
+
+#[cfg(unix)]
+fn set_writable(path: &Path, mut permissions: fs::Permissions, is_dir: bool) {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Directories also need to be listable and searchable to empty them
+    let required = if is_dir { 0o700 } else { 0o200 };
+    if permissions.mode() & required != required {
+        permissions.set_mode(permissions.mode() | required);
+        let _ = fs::set_permissions(path, permissions);
+    }
+}
+
+#[cfg(not(unix))]
+fn set_writable(path: &Path, mut permissions: fs::Permissions, _is_dir: bool) {
+    if permissions.readonly() {
+        // Clears the read-only attribute; there are no per-user mode bits here
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        let _ = fs::set_permissions(path, permissions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Build a directory chain whose full path is well over 260 characters.
+    fn create_deep_tree(root: &Path) -> io::Result<PathBuf> {
+        let mut deep = root.to_path_buf();
+        while deep.as_os_str().len() <= 400 {
+            deep.push("a-rather-long-directory-name-from-node-modules");
+        }
+        fs::create_dir_all(extended_length_path(&deep))?;
+        fs::write(extended_length_path(&deep.join("index.js")), "x")?;
+        Ok(deep)
+    }
+
+    #[test]
+    fn test_removes_paths_longer_than_max_path() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let node_modules = tmp.path().join("node_modules");
+        let deep = create_deep_tree(&node_modules)?;
+        assert!(deep.join("index.js").as_os_str().len() > 260);
+
+        force_remove_dir(&node_modules)?;
+
+        assert!(!node_modules.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_removes_read_only_files() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let target = tmp.path().join("target");
+        fs::create_dir_all(target.join("debug"))?;
+        let file = target.join("debug").join("app");
+        fs::write(&file, "binary")?;
+
+        let mut permissions = fs::metadata(&file)?.permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&file, permissions)?;
+
+        force_remove_dir(&target)?;
+
+        assert!(!target.exists());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_removes_read_only_directories() -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new()?;
+        let cache = tmp.path().join("pkg").join("mod");
+        let module = cache.join("example.com@v1.0.0");
+        fs::create_dir_all(&module)?;
+        fs::write(module.join("go.mod"), "module example.com")?;
+        // Like Go's module cache, which is made read-only on purpose
+        fs::set_permissions(&module, fs::Permissions::from_mode(0o555))?;
+
+        force_remove_dir(&cache)?;
+
+        assert!(!cache.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_aside_frees_the_path() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let target = tmp.path().join("target");
+        fs::create_dir_all(target.join("debug"))?;
+        fs::write(target.join("debug").join("app"), "binary")?;
+
+        let first = rename_aside(&target)?;
+        assert!(!target.exists());
+        assert_eq!(first.parent(), Some(tmp.path()));
+        assert!(first.join("debug").join("app").exists());
+
+        fs::create_dir(&target)?;
+        let second = rename_aside(&target)?;
+        assert_ne!(first, second);
+
+        assert!(rename_aside(&tmp.path().join("missing")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_aside() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let target = tmp.path().join("target");
+        fs::create_dir_all(target.join("debug"))?;
+
+        let aside = rename_aside(&target)?;
+        restore_aside(&aside, &target)?;
+        assert!(target.join("debug").exists());
+        assert!(!aside.exists());
+
+        let aside = rename_aside(&target)?;
+        fs::create_dir(&target)?;
+        assert!(restore_aside(&aside, &target).is_err());
+        assert!(aside.join("debug").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_asides() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let target = tmp.path().join("target");
+        for name in [
+            ".target.deleting-1-0",
+            ".target.deleting-1-1",
+            ".target.deleting-x-0",
+            ".target-2.deleting-1-0",
+            ".node_modules.deleting-1-0",
+        ] {
+            fs::create_dir(tmp.path().join(name))?;
+        }
+        fs::create_dir(&target)?;
+        let own = rename_aside(&target)?;
+
+        assert_eq!(
+            stale_asides(&target),
+            vec![
+                tmp.path().join(".target.deleting-1-0"),
+                tmp.path().join(".target.deleting-1-1"),
+            ]
+        );
+        assert!(!stale_asides(&target).contains(&own));
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_directory_is_an_error() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let err = force_remove_dir(&tmp.path().join("missing")).err();
+        assert!(err.is_some_and(|e| e.kind() == io::ErrorKind::NotFound));
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_extended_length_path() {
+        assert_eq!(
+            extended_length_path(Path::new(r"C:\code\app\node_modules")),
+            PathBuf::from(r"\\?\C:\code\app\node_modules")
+        );
+        assert_eq!(
+            extended_length_path(Path::new(r"C:\code\app\..\web/node_modules")),
+            PathBuf::from(r"\\?\C:\code\web\node_modules")
+        );
+        assert_eq!(
+            extended_length_path(Path::new(r"\\server\share\app\node_modules")),
+            PathBuf::from(r"\\?\UNC\server\share\app\node_modules")
+        );
+        assert_eq!(
+            extended_length_path(Path::new(r"\\?\C:\already\verbatim")),
+            PathBuf::from(r"\\?\C:\already\verbatim")
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_removes_reserved_names() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let node_modules = tmp.path().join("node_modules");
+        let package = node_modules.join("aux-package");
+        fs::create_dir_all(&package)?;
+        fs::write(extended_length_path(&package.join("aux.js")), "x")?;
+        fs::write(extended_length_path(&package.join("con")), "x")?;
+
+        force_remove_dir(&node_modules)?;
+
+        assert!(!node_modules.exists());
+        Ok(())
+    }
+}
@@ -1,34 +1,158 @@
 //! Size parsing and manipulation utilities.
 //!
 //! This module provides functions for parsing human-readable size strings
-//! (like "100MB" or "1.5GiB") into byte values, and for measuring directory
-//! sizes on disk.
+//! (like "100MB" or "1.5GiB") into byte values, formatting byte counts in the
+//! units selected with `--units`, and for measuring directory trees on disk.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use clap::ValueEnum;
+use humansize::{BINARY, DECIMAL, format_size};
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
 
-use std::path::Path;
+use crate::error::{CleanDevDirsError, Result};
 
-use anyhow::Result;
-use walkdir::WalkDir;
+/// Units used to display sizes, selectable with `--units`.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, Default, ValueEnum, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeUnits {
+    /// Powers of 1024: KiB, MiB, GiB
+    Binary,
 
-/// Calculate the total size of a directory and all its contents, in bytes.
+    /// Powers of 1000: kB, MB, GB
+    #[default]
+    Decimal,
+
+    /// Exact byte counts
+    Bytes,
+}
+
+/// Format a byte count for display in the given units.
 ///
-/// Recursively traverses the directory tree using `walkdir` and sums the sizes
-/// of all files found. Errors for individual entries (permission denied, broken
-/// symlinks, etc.) are silently skipped so the function always returns a result.
+/// # Examples
 ///
-/// Returns `0` if the path does not exist or cannot be traversed at the root level.
+/// ```
+/// # use clean_dev_dirs::utils::{SizeUnits, format_bytes};
+/// assert_eq!(format_bytes(1_500_000, SizeUnits::Decimal), "1.50 MB");
+/// assert_eq!(format_bytes(1_500_000, SizeUnits::Bytes), "1500000 B");
+/// ```
 #[must_use]
-pub fn calculate_dir_size(path: &Path) -> u64 {
-    let mut total = 0u64;
+pub fn format_bytes(size: u64, units: SizeUnits) -> String {
+    match units {
+        SizeUnits::Binary => format_size(size, BINARY),
+        SizeUnits::Decimal => format_size(size, DECIMAL),
+        SizeUnits::Bytes => format!("{size} B"),
+    }
+}
+
+/// Statistics about the files in a directory tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DirStats {
+    /// Total size of all files, in bytes.
+    pub size: u64,
 
-    for entry in WalkDir::new(path).into_iter().flatten() {
-        if entry.file_type().is_file()
-            && let Ok(metadata) = entry.metadata()
-        {
-            total += metadata.len();
+    /// Number of files.
+    pub files: u64,
+
+    /// Number of subdirectories, not counting the directory itself.
+    pub dirs: u64,
+
+    /// Most recent modification time of any file, or `None` if there are no
+    /// readable files.
+    pub newest_file: Option<SystemTime>,
+
+    /// Whether the walk stopped early at a size limit, so every figure is a
+    /// lower bound (see [`DirStats::collect_up_to`]).
+    pub estimated: bool,
+}
+
+impl DirStats {
+    /// Collect statistics for a directory and all its contents.
+    ///
+    /// Recursively traverses the directory tree once using `walkdir`, so
+    /// sizes, counts and times all come from the same walk. Errors for
+    /// individual entries (permission denied, broken symlinks, etc.) are
+    /// silently skipped so the function always returns a result.
+    ///
+    /// Returns empty statistics if the path does not exist or cannot be
+    /// traversed at the root level.
+    #[must_use]
+    pub fn collect(path: &Path) -> Self {
+        Self::collect_up_to(path, None)
+    }
+
+    /// Like [`DirStats::collect`], but stop walking once the size reaches `limit`.
+    ///
+    /// Sizing an enormous directory can take minutes. When the walk stops
+    /// early, `estimated` is set and the statistics only cover the part of
+    /// the tree seen so far. `None` walks the whole tree.
+    #[must_use]
+    pub fn collect_up_to(path: &Path, limit: Option<u64>) -> Self {
+        let mut stats = Self::default();
+
+        for entry in WalkDir::new(path).into_iter().flatten() {
+            if limit.is_some_and(|limit| stats.size >= limit) {
+                stats.estimated = true;
+                break;
+            }
+
+            if entry.file_type().is_dir() {
+                if entry.depth() > 0 {
+                    stats.dirs += 1;
+                }
+            } else if entry.file_type().is_file()
+                && let Ok(metadata) = entry.metadata()
+            {
+                stats.size += metadata.len();
+                stats.files += 1;
+                stats.newest_file = stats.newest_file.max(metadata.modified().ok());
+            }
         }
+
+        stats
     }
+}
 
-    total
+/// Calculate the total size of a directory and all its contents, in bytes.
+///
+/// Shorthand for the `size` of [`DirStats::collect`].
+///
+/// Returns `0` if the path does not exist or cannot be traversed at the root level.
+#[must_use]
+pub fn calculate_dir_size(path: &Path) -> u64 {
+    DirStats::collect(path).size
+}
+
+/// Measure each entry directly inside `dir`, largest first.
+///
+/// Subdirectories are measured in parallel with [`calculate_dir_size`], so a
+/// build directory can be broken down without walking it more than once.
+/// Returns an empty list if `dir` can't be read.
+#[must_use]
+pub fn child_sizes(dir: &Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    let mut sizes: Vec<(PathBuf, u64)> = paths
+        .into_par_iter()
+        .map(|path| {
+            let size = calculate_dir_size(&path);
+            (path, size)
+        })
+        .collect();
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sizes
 }
 
 /// Parse a human-readable size string into bytes.
@@ -43,7 +167,7 @@ pub fn calculate_dir_size(path: &Path) -> u64 {
 /// # Returns
 ///
 /// - `Ok(u64)` - The size in bytes
-/// - `Err(anyhow::Error)` - If the string format is invalid or causes overflow
+/// - `Err(CleanDevDirsError::Config)` - If the string format is invalid or causes overflow
 ///
 /// # Errors
 ///
@@ -57,8 +181,7 @@ pub fn calculate_dir_size(path: &Path) -> u64 {
 ///
 /// ```
 /// # use clean_dev_dirs::utils::parse_size;
-/// # use anyhow::Result;
-/// # fn main() -> Result<()> {
+/// # fn main() -> clean_dev_dirs::Result<()> {
 /// assert_eq!(parse_size("100KB")?, 100_000);
 /// assert_eq!(parse_size("1.5MB")?, 1_500_000);
 /// assert_eq!(parse_size("1GiB")?, 1_073_741_824);
@@ -110,7 +233,9 @@ fn parse_size_unit(size_str: &str) -> (&str, u64) {
 fn parse_decimal_size(number_str: &str, multiplier: u64) -> Result<u64> {
     let parts: Vec<&str> = number_str.split('.').collect();
     if parts.len() != 2 {
-        return Err(anyhow::anyhow!("Invalid decimal format: {number_str}"));
+        return Err(CleanDevDirsError::config(format!(
+            "Invalid decimal format: {number_str}"
+        )));
     }
 
     let integer_part: u64 = parts[0].parse().unwrap_or(0);
@@ -127,37 +252,74 @@ fn parse_decimal_size(number_str: &str, multiplier: u64) -> Result<u64> {
 fn parse_fractional_part(fractional_str: &str) -> Result<u64> {
     let fractional_digits = fractional_str.len();
     if fractional_digits > 9 {
-        return Err(anyhow::anyhow!("Too many decimal places: {fractional_str}"));
+        return Err(CleanDevDirsError::config(format!(
+            "Too many decimal places: {fractional_str}"
+        )));
     }
 
-    let fractional_part: u64 = fractional_str.parse()?;
-    let fractional_multiplier = 10u64.pow(9 - u32::try_from(fractional_digits)?);
+    let fractional_part: u64 = fractional_str.parse().map_err(|e| invalid_number(&e))?;
+    let fractional_multiplier = 10u64.pow(9 - u32::try_from(fractional_digits).unwrap_or(9));
 
     Ok(fractional_part * fractional_multiplier)
 }
 
 /// Parse an integer size value.
 fn parse_integer_size(number_str: &str, multiplier: u64) -> Result<u64> {
-    let number: u64 = number_str.parse()?;
+    let number: u64 = number_str.parse().map_err(|e| invalid_number(&e))?;
     multiply_with_overflow_check(number, multiplier)
 }
 
+/// The error for a size whose number can't be parsed.
+fn invalid_number(error: &std::num::ParseIntError) -> CleanDevDirsError {
+    CleanDevDirsError::config(error.to_string())
+}
+
 /// Multiply two values with overflow checking.
 fn multiply_with_overflow_check(a: u64, b: u64) -> Result<u64> {
     a.checked_mul(b)
-        .ok_or_else(|| anyhow::anyhow!("Size value overflow: {a} * {b}"))
+        .ok_or_else(|| CleanDevDirsError::config(format!("Size value overflow: {a} * {b}")))
 }
 
 /// Add two values with overflow checking.
 fn add_with_overflow_check(a: u64, b: u64) -> Result<u64> {
     a.checked_add(b)
-        .ok_or_else(|| anyhow::anyhow!("Final overflow: {a} + {b}"))
+        .ok_or_else(|| CleanDevDirsError::config(format!("Final overflow: {a} + {b}")))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_child_sizes_largest_first() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        fs::create_dir_all(tmp.path().join("debug/deps"))?;
+        fs::create_dir(tmp.path().join("release"))?;
+        fs::write(tmp.path().join("debug/deps/libfoo.rlib"), vec![0u8; 300])?;
+        fs::write(tmp.path().join("release/app"), vec![0u8; 100])?;
+        fs::write(tmp.path().join(".rustc_info.json"), vec![0u8; 20])?;
+
+        let sizes = child_sizes(tmp.path());
+        assert_eq!(
+            sizes,
+            vec![
+                (tmp.path().join("debug"), 300),
+                (tmp.path().join("release"), 100),
+                (tmp.path().join(".rustc_info.json"), 20),
+            ]
+        );
+        assert!(child_sizes(&tmp.path().join("missing")).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_bytes_each_unit() {
+        assert_eq!(format_bytes(1_500_000, SizeUnits::Decimal), "1.50 MB");
+        assert_eq!(format_bytes(1_572_864, SizeUnits::Binary), "1.50 MiB");
+        assert_eq!(format_bytes(1_572_864, SizeUnits::Bytes), "1572864 B");
+        assert_eq!(format_bytes(0, SizeUnits::Decimal), "0 B");
+    }
+
     #[test]
     fn test_parse_size_zero() -> anyhow::Result<()> {
         assert_eq!(parse_size("0")?, 0);
@@ -334,4 +496,46 @@ mod tests {
         assert_eq!(parse_size("0.0MB")?, 0);
         Ok(())
     }
+
+    #[test]
+    fn test_dir_stats() -> anyhow::Result<()> {
+        use std::fs::{self, File, FileTimes};
+        use std::time::Duration;
+
+        let tmp = tempfile::TempDir::new()?;
+        let nested = tmp.path().join("debug").join("deps");
+        fs::create_dir_all(&nested)?;
+        let stats = DirStats::collect(tmp.path());
+        assert_eq!((stats.files, stats.dirs, stats.newest_file), (0, 2, None));
+
+        let old = SystemTime::now() - Duration::from_hours(90 * 24);
+        let older = old - Duration::from_hours(24);
+        for (name, mtime) in [("old.rlib", old), ("older.rlib", older)] {
+            let file = File::create(nested.join(name))?;
+            file.set_times(FileTimes::new().set_modified(mtime))?;
+        }
+
+        let stats = DirStats::collect(tmp.path());
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.dirs, 2);
+        assert_eq!(stats.newest_file, Some(old));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dir_stats_collect_up_to_stops_at_limit() -> anyhow::Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        for i in 0..10 {
+            std::fs::write(tmp.path().join(format!("{i}.o")), [0u8; 100])?;
+        }
+
+        let full = DirStats::collect_up_to(tmp.path(), Some(5_000));
+        assert_eq!((full.size, full.files, full.estimated), (1_000, 10, false));
+
+        let partial = DirStats::collect_up_to(tmp.path(), Some(250));
+        assert!(partial.estimated);
+        assert_eq!(partial.size, 300);
+        assert_eq!(partial.files, 3);
+        Ok(())
+    }
 }
@@ -0,0 +1,507 @@
+//! Backends for measuring directory trees.
+//!
+//! Sizing build directories is the slowest part of a scan. The
+//! [`SizeBackend`] trait lets the scanner swap the way trees are walked:
+//!
+//! - [`PortableBackend`] walks with `walkdir` and works everywhere. It is the
+//!   default.
+//! - [`NativeBackend`] uses the platform's directory APIs directly. On Unix it
+//!   reads each directory once and `fstatat`s the entries relative to the open
+//!   directory, so no path is resolved per file. On Windows it lists each
+//!   directory with `FindFirstFileExW` and large fetches, which returns sizes
+//!   and times with the listing, so no file is opened at all. Elsewhere it
+//!   falls back to the portable walk.
+//!
+//! Both backends count the same things: regular files, subdirectories below
+//! the root, and no symbolic links.
+
+use std::{fmt, path::Path};
+
+use clap::ValueEnum;
+
+use super::DirStats;
+
+/// A way of collecting [`DirStats`] for a directory tree.
+pub trait SizeBackend: Send + Sync + fmt::Debug {
+    /// Collect statistics for `path` and all its contents, stopping once the
+    /// size reaches `limit` (see [`DirStats::collect_up_to`]).
+    ///
+    /// Entries that can't be read are skipped. Returns empty statistics if
+    /// `path` doesn't exist.
+    fn collect(&self, path: &Path, limit: Option<u64>) -> DirStats;
+}
+
+/// The portable backend: a `walkdir` walk, as done by [`DirStats::collect_up_to`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PortableBackend;
+
+impl SizeBackend for PortableBackend {
+    fn collect(&self, path: &Path, limit: Option<u64>) -> DirStats {
+        DirStats::collect_up_to(path, limit)
+    }
+}
+
+/// The platform-accelerated backend, falling back to [`PortableBackend`]
+/// where no native implementation exists.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NativeBackend;
+
+impl SizeBackend for NativeBackend {
+    fn collect(&self, path: &Path, limit: Option<u64>) -> DirStats {
+        sys::collect(path, limit)
+    }
+}
+
+/// Which [`SizeBackend`] to measure build directories with, selectable with
+/// `--size-backend`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Default)]
+pub enum SizeBackendKind {
+    /// Walk with `walkdir`, the same on every platform
+    #[default]
+    Portable,
+
+    /// Use the platform's directory APIs where available
+    Native,
+}
+
+impl SizeBackendKind {
+    /// The backend this kind stands for.
+    #[must_use]
+    pub fn backend(self) -> &'static dyn SizeBackend {
+        match self {
+            Self::Portable => &PortableBackend,
+            Self::Native => &NativeBackend,
+        }
+    }
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::{
+        ffi::{CStr, CString},
+        fs,
+        os::unix::ffi::OsStrExt,
+        path::{Path, PathBuf},
+        time::{Duration, SystemTime},
+    };
+
+    use super::DirStats;
+
+    /// What a directory entry turned out to be.
+    enum Kind {
+        Dir,
+        File {
+            size: u64,
+            modified: Option<SystemTime>,
+        },
+        Other,
+    }
+
+    pub(super) fn collect(path: &Path, limit: Option<u64>) -> DirStats {
+        let mut stats = DirStats::default();
+
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => {
+                stats.size = metadata.len();
+                stats.files = 1;
+                stats.newest_file = metadata.modified().ok();
+                return stats;
+            }
+            Ok(metadata) if metadata.is_dir() => {}
+            _ => return stats,
+        }
+
+        // Directories are opened by path one at a time, so a deep tree never
+        // holds more than one descriptor per thread
+        let mut pending = vec![path.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            if !read_dir(&dir, limit, &mut stats, &mut pending) {
+                stats.estimated = true;
+                break;
+            }
+        }
+        stats
+    }
+
+    /// Add the entries of `dir` to `stats` and its subdirectories to `pending`.
+    ///
+    /// Returns `false` if `limit` was reached.
+    fn read_dir(
+        dir: &Path,
+        limit: Option<u64>,
+        stats: &mut DirStats,
+        pending: &mut Vec<PathBuf>,
+    ) -> bool {
+        let Ok(c_dir) = CString::new(dir.as_os_str().as_bytes()) else {
+            return true;
+        };
+        // SAFETY: `c_dir` is a valid NUL-terminated path.
+        let fd = unsafe {
+            libc::open(
+                c_dir.as_ptr(),
+                libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return true;
+        }
+        // SAFETY: `fd` is an open directory descriptor, owned by the stream
+        // from here on.
+        let stream = unsafe { libc::fdopendir(fd) };
+        if stream.is_null() {
+            // SAFETY: `fd` is open and no stream took ownership of it.
+            unsafe { libc::close(fd) };
+            return true;
+        }
+
+        let mut within_limit = true;
+        loop {
+            if limit.is_some_and(|limit| stats.size >= limit) {
+                within_limit = false;
+                break;
+            }
+
+            // SAFETY: `stream` is an open directory stream.
+            let entry = unsafe { libc::readdir(stream) };
+            if entry.is_null() {
+                break;
+            }
+            // SAFETY: a non-null entry stays valid until the next readdir,
+            // and its name is NUL-terminated.
+            let (entry, name) = unsafe { (&*entry, CStr::from_ptr((*entry).d_name.as_ptr())) };
+            if matches!(name.to_bytes(), b"." | b"..") {
+                continue;
+            }
+
+            match kind_of(fd, name, entry) {
+                Kind::Dir => {
+                    stats.dirs += 1;
+                    pending.push(dir.join(std::ffi::OsStr::from_bytes(name.to_bytes())));
+                }
+                Kind::File { size, modified } => {
+                    stats.size += size;
+                    stats.files += 1;
+                    stats.newest_file = stats.newest_file.max(modified);
+                }
+                Kind::Other => {}
+            }
+        }
+
+        // SAFETY: `stream` is open and closed exactly once, which also
+        // closes `fd`.
+        unsafe { libc::closedir(stream) };
+        within_limit
+    }
+
+    /// Find out what the entry `name` in the directory `fd` is.
+    ///
+    /// The type reported by `readdir` saves a `stat` call for directories;
+    /// files are always `stat`ed for their size and time.
+    fn kind_of(fd: libc::c_int, name: &CStr, entry: &libc::dirent) -> Kind {
+        if entry_is_dir(entry) {
+            return Kind::Dir;
+        }
+
+        // SAFETY: stat is a plain C struct for which all-zero is a valid value.
+        let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+        // SAFETY: `fd` is an open directory and `name` is NUL-terminated.
+        let status =
+            unsafe { libc::fstatat(fd, name.as_ptr(), &raw mut stat, libc::AT_SYMLINK_NOFOLLOW) };
+        if status != 0 {
+            return Kind::Other;
+        }
+
+        match stat.st_mode & libc::S_IFMT {
+            libc::S_IFDIR => Kind::Dir,
+            // `time_t` and `c_long` are narrower than i64 on 32-bit targets
+            #[allow(clippy::useless_conversion)]
+            libc::S_IFREG => Kind::File {
+                size: u64::try_from(stat.st_size).unwrap_or(0),
+                modified: system_time(stat.st_mtime.into(), stat.st_mtime_nsec.into()),
+            },
+            _ => Kind::Other,
+        }
+    }
+
+    /// Whether `readdir` already reported `entry` as a directory.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd"
+    ))]
+    const fn entry_is_dir(entry: &libc::dirent) -> bool {
+        entry.d_type == libc::DT_DIR
+    }
+
+    /// Whether `readdir` already reported `entry` as a directory; this
+    /// platform doesn't report types.
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd"
+    )))]
+    const fn entry_is_dir(_entry: &libc::dirent) -> bool {
+        false
+    }
+
+    /// Convert a `stat` modification time into a [`SystemTime`].
+    fn system_time(secs: i64, nsecs: i64) -> Option<SystemTime> {
+        let nanos = Duration::from_nanos(u64::try_from(nsecs).unwrap_or(0));
+        match u64::try_from(secs) {
+            Ok(secs) => SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs) + nanos),
+            Err(_) => SystemTime::UNIX_EPOCH
+                .checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+                .checked_add(nanos),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::{
+        fs,
+        os::windows::ffi::{OsStrExt, OsStringExt},
+        path::{Path, PathBuf},
+        ptr,
+        time::{Duration, SystemTime},
+    };
+
+    use windows_sys::Win32::{
+        Foundation::{FILETIME, INVALID_HANDLE_VALUE},
+        Storage::FileSystem::{
+            FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_REPARSE_POINT, FIND_FIRST_EX_LARGE_FETCH,
+            FindClose, FindExInfoBasic, FindExSearchNameMatch, FindFirstFileExW, FindNextFileW,
+            WIN32_FIND_DATAW,
+        },
+    };
+
+    use super::DirStats;
+
+    /// Reparse tags with this bit set are links (symbolic links, junctions),
+    /// which the walk doesn't follow or count.
+    const NAME_SURROGATE: u32 = 0x2000_0000;
+
+    /// Seconds between 1601-01-01, the `FILETIME` epoch, and the Unix epoch.
+    const FILETIME_UNIX_OFFSET: u64 = 11_644_473_600;
+
+    pub(super) fn collect(path: &Path, limit: Option<u64>) -> DirStats {
+        let mut stats = DirStats::default();
+
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => {
+                stats.size = metadata.len();
+                stats.files = 1;
+                stats.newest_file = metadata.modified().ok();
+                return stats;
+            }
+            Ok(metadata) if metadata.is_dir() => {}
+            _ => return stats,
+        }
+
+        let mut pending = vec![path.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            if !read_dir(&dir, limit, &mut stats, &mut pending) {
+                stats.estimated = true;
+                break;
+            }
+        }
+        stats
+    }
+
+    /// Add the entries of `dir` to `stats` and its subdirectories to `pending`.
+    ///
+    /// Returns `false` if `limit` was reached.
+    fn read_dir(
+        dir: &Path,
+        limit: Option<u64>,
+        stats: &mut DirStats,
+        pending: &mut Vec<PathBuf>,
+    ) -> bool {
+        let pattern: Vec<u16> = dir
+            .join("*")
+            .as_os_str()
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+        // SAFETY: WIN32_FIND_DATAW is a plain C struct for which all-zero is
+        // a valid value.
+        let mut data: WIN32_FIND_DATAW = unsafe { std::mem::zeroed() };
+        // SAFETY: `pattern` is NUL-terminated and `data` is a valid
+        // WIN32_FIND_DATAW, as FindExInfoBasic expects.
+        let handle = unsafe {
+            FindFirstFileExW(
+                pattern.as_ptr(),
+                FindExInfoBasic,
+                (&raw mut data).cast(),
+                FindExSearchNameMatch,
+                ptr::null(),
+                FIND_FIRST_EX_LARGE_FETCH,
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return true;
+        }
+
+        let mut within_limit = true;
+        loop {
+            if limit.is_some_and(|limit| stats.size >= limit) {
+                within_limit = false;
+                break;
+            }
+            add_entry(dir, &data, stats, pending);
+
+            // SAFETY: `handle` is an open search handle and `data` is valid.
+            if unsafe { FindNextFileW(handle, &raw mut data) } == 0 {
+                break;
+            }
+        }
+
+        // SAFETY: `handle` is open and closed exactly once.
+        unsafe { FindClose(handle) };
+        within_limit
+    }
+
+    /// Add one entry found in `dir` to `stats`, queueing it if it's a directory.
+    fn add_entry(
+        dir: &Path,
+        data: &WIN32_FIND_DATAW,
+        stats: &mut DirStats,
+        pending: &mut Vec<PathBuf>,
+    ) {
+        let len = data
+            .cFileName
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(data.cFileName.len());
+        let name = &data.cFileName[..len];
+        if name == [u16::from(b'.')] || name == [u16::from(b'.'); 2] {
+            return;
+        }
+
+        let attributes = data.dwFileAttributes;
+        if attributes & FILE_ATTRIBUTE_REPARSE_POINT != 0 && data.dwReserved0 & NAME_SURROGATE != 0
+        {
+            return;
+        }
+
+        if attributes & FILE_ATTRIBUTE_DIRECTORY != 0 {
+            stats.dirs += 1;
+            pending.push(dir.join(std::ffi::OsString::from_wide(name)));
+        } else {
+            stats.size += (u64::from(data.nFileSizeHigh) << 32) | u64::from(data.nFileSizeLow);
+            stats.files += 1;
+            stats.newest_file = stats.newest_file.max(system_time(&data.ftLastWriteTime));
+        }
+    }
+
+    /// Convert a `FILETIME` (100 ns intervals since 1601) into a [`SystemTime`].
+    fn system_time(time: &FILETIME) -> Option<SystemTime> {
+        let ticks = (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime);
+        let since_1601 = Duration::new(
+            ticks / 10_000_000,
+            u32::try_from(ticks % 10_000_000).unwrap_or(0) * 100,
+        );
+        let offset = Duration::from_secs(FILETIME_UNIX_OFFSET);
+
+        match since_1601.checked_sub(offset) {
+            Some(since_unix) => SystemTime::UNIX_EPOCH.checked_add(since_unix),
+            None => SystemTime::UNIX_EPOCH.checked_sub(offset - since_1601),
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod sys {
+    use std::path::Path;
+
+    use super::DirStats;
+
+    pub(super) fn collect(path: &Path, limit: Option<u64>) -> DirStats {
+        DirStats::collect_up_to(path, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn build_tree(root: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(root.join("deps/nested/deeper"))?;
+        fs::create_dir_all(root.join("empty"))?;
+        fs::write(root.join("a.bin"), vec![0u8; 1_000])?;
+        fs::write(root.join("deps/b.rlib"), vec![0u8; 2_500])?;
+        fs::write(root.join("deps/nested/deeper/c.o"), vec![0u8; 40])?;
+        fs::write(root.join("deps/nested/empty.d"), "")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_backends_agree() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        build_tree(tmp.path())?;
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(tmp.path().join("deps"), tmp.path().join("link"))?;
+            std::os::unix::fs::symlink(tmp.path().join("a.bin"), tmp.path().join("a.link"))?;
+        }
+
+        let portable = PortableBackend.collect(tmp.path(), None);
+        let native = NativeBackend.collect(tmp.path(), None);
+
+        assert_eq!(portable.size, 3_540);
+        assert_eq!(portable.files, 4);
+        assert_eq!(portable.dirs, 4);
+        assert!(portable.newest_file.is_some());
+        assert_eq!(native, portable);
+        Ok(())
+    }
+
+    #[test]
+    fn test_native_backend_edge_cases() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let file = tmp.path().join("file.bin");
+        fs::write(&file, vec![0u8; 123])?;
+
+        assert_eq!(
+            NativeBackend.collect(&file, None),
+            PortableBackend.collect(&file, None)
+        );
+        assert_eq!(
+            NativeBackend.collect(&tmp.path().join("missing"), None),
+            DirStats::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_native_backend_stops_at_limit() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        for i in 0..10 {
+            fs::write(tmp.path().join(format!("{i}.bin")), vec![0u8; 100])?;
+        }
+
+        let stats = NativeBackend.collect(tmp.path(), Some(250));
+        assert!(stats.estimated);
+        assert!((250..1_000).contains(&stats.size));
+
+        assert!(!NativeBackend.collect(tmp.path(), Some(5_000)).estimated);
+        Ok(())
+    }
+
+    #[test]
+    fn test_kind_selects_backend() {
+        let path = Path::new("/definitely/not/here");
+        for kind in [SizeBackendKind::Portable, SizeBackendKind::Native] {
+            assert_eq!(kind.backend().collect(path, None), DirStats::default());
+        }
+        assert_eq!(SizeBackendKind::default(), SizeBackendKind::Portable);
+    }
+}
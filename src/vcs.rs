@@ -0,0 +1,471 @@
+//! Version-control checks for projects.
+//!
+//! This module inspects the git work tree that contains a project so that
+//! cleanup can be skipped for projects with work in progress, and so that
+//! projects can be filtered by how recently their repository was committed to.
+//! It shells out to the `git` executable. A project outside a repository is
+//! clean and its last commit is unknown; inside one, a project whose status
+//! git can't report is taken as dirty, so it's kept rather than cleaned.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    error::{CleanDevDirsError, Result},
+    project::Project,
+};
+
+/// Check whether a project has uncommitted changes in its git work tree.
+///
+/// Staged, unstaged and untracked changes under the project root count as
+/// dirty. Changes inside the project's build artifact directories are ignored,
+/// since those are exactly what is about to be removed.
+///
+/// Returns `false` if the project is not inside a git repository.
+///
+/// # Errors
+///
+/// Returns a scan error if git can't be run or fails inside the repository;
+/// the project should then be treated as dirty.
+pub fn is_dirty(project: &Project) -> Result<bool> {
+    if repository_root(&project.root_path).is_none() {
+        return Ok(false);
+    }
+
+    let excludes = project.build_arts.iter().filter_map(|artifact| {
+        artifact
+            .path
+            .strip_prefix(&project.root_path)
+            .ok()
+            .map(|rel| format!(":(exclude){}", rel.display()))
+    });
+
+    let context = || {
+        format!(
+            "Failed to check {} for changes",
+            project.root_path.display()
+        )
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&project.root_path)
+        .args(["status", "--porcelain", "--untracked-files=all", "--", "."])
+        .args(excludes)
+        .output()
+        .map_err(|e| CleanDevDirsError::scan(context()).with_source(e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CleanDevDirsError::scan(format!(
+            "{}: git status exited with {}: {}",
+            context(),
+            output.status,
+            stderr.trim()
+        )));
+    }
+    Ok(!output.stdout.is_empty())
+}
+
+/// Check whether git tracks any file inside the directory `path`.
+///
+/// A directory outside a repository is untracked. Inside a repository, a
+/// directory is taken as tracked when git can't be run or fails, so that
+/// it's kept rather than removed by mistake.
+#[must_use]
+pub fn is_tracked(path: &Path) -> bool {
+    if repository_root(path).is_none() {
+        return false;
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["ls-files", "--", "."])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => !output.stdout.is_empty(),
+        _ => true,
+    }
+}
+
+/// Split projects into those safe to clean and those with uncommitted changes.
+///
+/// Projects whose status couldn't be checked count as dirty, and the errors
+/// are returned so they can be reported.
+///
+/// # Returns
+///
+/// A `(clean, dirty, errors)` tuple; the projects keep their original
+/// relative order.
+#[must_use]
+pub fn partition_dirty(
+    projects: Vec<Project>,
+) -> (Vec<Project>, Vec<Project>, Vec<CleanDevDirsError>) {
+    let checked: Vec<_> = projects
+        .into_par_iter()
+        .map(|project| {
+            let dirty = is_dirty(&project);
+            (project, dirty)
+        })
+        .collect();
+
+    let (mut clean, mut dirty, mut errors) = (Vec::new(), Vec::new(), Vec::new());
+    for (project, state) in checked {
+        match state {
+            Ok(false) => clean.push(project),
+            Ok(true) => dirty.push(project),
+            Err(e) => {
+                errors.push(e);
+                dirty.push(project);
+            }
+        }
+    }
+    (clean, dirty, errors)
+}
+
+/// Look up the timestamp of the latest commit in the repository containing `path`.
+///
+/// Returns the commit time as a Unix timestamp in seconds, or `None` if `path`
+/// is not inside a git repository, the repository has no commits, or git
+/// cannot be run.
+#[must_use]
+pub fn last_commit_time(path: &Path) -> Option<i64> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["log", "-1", "--format=%ct"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// The root of the git work tree containing `path`: the closest ancestor
+/// (or `path` itself) with a `.git` directory, or a `.git` file as in
+/// linked worktrees and submodules.
+fn repository_root(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|dir| dir.join(".git").exists())
+}
+
+/// The name of the repository whose work tree is rooted at `root`, from the
+/// URL of its `origin` remote (or its first remote, if there's no `origin`).
+///
+/// Reads the repository's `config` file rather than running git, since it's
+/// asked for every project without a name while scanning. Returns `None` if
+/// `root` isn't the root of a work tree or the repository has no remote.
+#[must_use]
+pub fn remote_repository_name(root: &Path) -> Option<String> {
+    let config = fs::read_to_string(config_path(root)?).ok()?;
+
+    let mut section = String::new();
+    let mut remotes: Vec<(String, String)> = Vec::new();
+    for line in config.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = header.to_string();
+        } else if let Some(remote) = section
+            .strip_prefix("remote \"")
+            .and_then(|s| s.strip_suffix('"'))
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "url"
+        {
+            remotes.push((remote.to_string(), value.trim().to_string()));
+        }
+    }
+
+    let (_, url) = remotes
+        .iter()
+        .find(|(remote, _)| remote == "origin")
+        .or_else(|| remotes.first())?;
+    repository_name_from_url(url)
+}
+
+/// The `config` file of the repository whose work tree is rooted at `root`.
+///
+/// In linked worktrees and submodules, `.git` is a file pointing to the git
+/// directory; a worktree's git directory shares the main repository's config.
+fn config_path(root: &Path) -> Option<PathBuf> {
+    let dot_git = root.join(".git");
+    let git_dir = if dot_git.is_dir() {
+        dot_git
+    } else {
+        let pointer = fs::read_to_string(&dot_git).ok()?;
+        root.join(pointer.trim().strip_prefix("gitdir:")?.trim())
+    };
+
+    let common_dir = fs::read_to_string(git_dir.join("commondir"))
+        .map_or_else(|_| git_dir.clone(), |common| git_dir.join(common.trim()));
+    Some(common_dir.join("config"))
+}
+
+/// The last component of a remote URL, without a `.git` suffix.
+///
+/// Handles `https://host/owner/repo.git`, `git@host:owner/repo.git` and
+/// local paths alike.
+fn repository_name_from_url(url: &str) -> Option<String> {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let name = url.rsplit(['/', ':', '\\']).next()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Fill in [`Project::last_commit`] for every project.
+///
+/// Each project's repository root is looked up once, and git is asked once
+/// per repository rather than once per project, in parallel, since a
+/// monorepo can hold hundreds of projects that share the same last commit.
+/// Projects outside any work tree don't run git at all.
+pub fn annotate_last_commits(projects: &mut [Project]) {
+    let roots: Vec<Option<PathBuf>> = projects
+        .iter()
+        .map(|project| repository_root(&project.root_path).map(Path::to_path_buf))
+        .collect();
+    let mut repositories: Vec<&Path> = roots.iter().flatten().map(PathBuf::as_path).collect();
+    repositories.sort_unstable();
+    repositories.dedup();
+
+    let commits: HashMap<&Path, Option<i64>> = repositories
+        .into_par_iter()
+        .map(|root| (root, last_commit_time(root)))
+        .collect();
+
+    for (project, root) in projects.iter_mut().zip(&roots) {
+        project.last_commit = root
+            .as_deref()
+            .and_then(|root| commits.get(root).copied().flatten());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+
+    fn rust_project(root: &Path) -> Project {
+        Project::new(
+            ProjectType::Rust,
+            root.to_path_buf(),
+            vec![BuildArtifacts {
+                path: root.join("target"),
+                size: 0,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            None,
+        )
+    }
+
+    #[test]
+    fn test_project_outside_repo_is_not_dirty() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        fs::write(tmp.path().join("Cargo.toml"), "[package]")?;
+
+        assert!(!is_dirty(&rust_project(tmp.path()))?);
+        assert!(last_commit_time(tmp.path()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_dirty_detection() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        if !git(root, &["init", "-q"]) {
+            return Ok(()); // git is not available in this environment
+        }
+
+        fs::write(root.join("Cargo.toml"), "[package]")?;
+        assert!(git(root, &["add", "."]));
+        assert!(git(root, &["commit", "-q", "-m", "init"]));
+
+        let project = rust_project(root);
+        assert!(!is_dirty(&project)?);
+
+        // Changes inside the build directory don't count
+        fs::create_dir_all(root.join("target/debug"))?;
+        fs::write(root.join("target/debug/app"), "binary")?;
+        assert!(!is_dirty(&project)?);
+
+        // Untracked source files do
+        fs::write(root.join("main.rs"), "fn main() {}")?;
+        assert!(is_dirty(&project)?);
+
+        let (clean, dirty, errors) = partition_dirty(vec![project]);
+        assert!(clean.is_empty());
+        assert_eq!(dirty.len(), 1);
+        assert!(errors.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreadable_repository_counts_as_dirty() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::write(root.join("Cargo.toml"), "[package]")?;
+        fs::write(root.join(".git"), "gitdir: /nonexistent/worktree")?;
+
+        let project = rust_project(root);
+        assert!(is_dirty(&project).is_err());
+
+        let (clean, dirty, errors) = partition_dirty(vec![project]);
+        assert!(clean.is_empty());
+        assert_eq!(dirty.len(), 1);
+        assert_eq!(errors.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_tracked() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("dist"))?;
+        fs::create_dir_all(root.join("out"))?;
+        fs::write(root.join("dist/index.js"), "")?;
+        fs::write(root.join("out/index.js"), "")?;
+        assert!(!is_tracked(&root.join("dist")));
+
+        if !git(root, &["init", "-q"]) || !git(root, &["add", "dist"]) {
+            return Ok(());
+        }
+        assert!(is_tracked(&root.join("dist")));
+        assert!(!is_tracked(&root.join("out")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotate_last_commits() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        if !git(root, &["init", "-q"]) {
+            return Ok(()); // git is not available in this environment
+        }
+
+        let mut projects = vec![rust_project(root)];
+        annotate_last_commits(&mut projects);
+        assert!(projects[0].last_commit.is_none()); // no commits yet
+
+        fs::write(root.join("Cargo.toml"), "[package]")?;
+        assert!(git(root, &["add", "."]));
+        assert!(git(
+            root,
+            &[
+                "commit",
+                "-q",
+                "-m",
+                "init",
+                "--date",
+                "2020-01-01T00:00:00Z"
+            ]
+        ));
+
+        annotate_last_commits(&mut projects);
+        assert!(projects[0].last_commit.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_projects_share_their_repository_lookup() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let root = tmp.path().join("monorepo");
+        fs::create_dir_all(root.join("crates/a"))?;
+        fs::create_dir_all(root.join("crates/b"))?;
+        let outside = tmp.path().join("scratch");
+        fs::create_dir_all(&outside)?;
+        if !git(&root, &["init", "-q"]) {
+            return Ok(()); // git is not available in this environment
+        }
+
+        fs::write(root.join("crates/a/Cargo.toml"), "[package]")?;
+        assert!(git(&root, &["add", "."]));
+        assert!(git(&root, &["commit", "-q", "-m", "init"]));
+
+        assert_eq!(
+            repository_root(&root.join("crates/a")),
+            Some(root.as_path())
+        );
+        assert_eq!(repository_root(&outside), None);
+
+        let mut projects = vec![
+            rust_project(&root.join("crates/a")),
+            rust_project(&root.join("crates/b")),
+            rust_project(&outside),
+        ];
+        annotate_last_commits(&mut projects);
+        assert!(projects[0].last_commit.is_some());
+        assert_eq!(projects[0].last_commit, projects[1].last_commit);
+        assert!(projects[2].last_commit.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_repository_name_from_url() {
+        let cases = [
+            ("https://github.com/owner/tool.git", Some("tool")),
+            ("git@github.com:owner/tool.git", Some("tool")),
+            ("ssh://git@host/owner/tool/", Some("tool")),
+            ("host:tool", Some("tool")),
+            ("/srv/git/tool.git", Some("tool")),
+            ("", None),
+        ];
+
+        for (url, name) in cases {
+            assert_eq!(repository_name_from_url(url).as_deref(), name, "{url}");
+        }
+    }
+
+    #[test]
+    fn test_remote_repository_name() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let main = tmp.path().join("main");
+        fs::create_dir_all(main.join(".git/worktrees/feature"))?;
+        fs::write(
+            main.join(".git/config"),
+            "[core]\n\tbare = false\n[remote \"upstream\"]\n\turl = https://example.com/org/upstream.git\n\
+             [remote \"origin\"]\n\turl = git@example.com:me/widget.git\n",
+        )?;
+        assert_eq!(remote_repository_name(&main).as_deref(), Some("widget"));
+        assert_eq!(remote_repository_name(&main.join("sub")), None);
+
+        // A linked worktree shares the main repository's config
+        let worktree = tmp.path().join("feature");
+        fs::create_dir_all(&worktree)?;
+        fs::write(main.join(".git/worktrees/feature/commondir"), "../..\n")?;
+        fs::write(
+            worktree.join(".git"),
+            format!(
+                "gitdir: {}\n",
+                main.join(".git/worktrees/feature").display()
+            ),
+        )?;
+        assert_eq!(remote_repository_name(&worktree).as_deref(), Some("widget"));
+
+        let local = tmp.path().join("local");
+        fs::create_dir_all(local.join(".git"))?;
+        fs::write(local.join(".git/config"), "[core]\n\tbare = false\n")?;
+        assert_eq!(remote_repository_name(&local), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+//! Reclaimable space grouped by volume.
+//!
+//! Build directories found across several drives free space on each of them
+//! separately. This module groups the build directories of a set of projects
+//! by the filesystem they live on, identified by its mount point on Unix and
+//! its volume root (e.g. `D:\`) on Windows, and looks up the space currently
+//! free there with `statvfs` or `GetDiskFreeSpaceExW`.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::project::Project;
+
+/// Reclaimable space on one volume.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VolumeUsage {
+    /// Mount point (Unix) or volume root (Windows) of the volume
+    pub mount_point: PathBuf,
+
+    /// Total size of the build directories on the volume, in bytes
+    pub reclaimable: u64,
+
+    /// Number of build directories on the volume
+    pub artifacts: usize,
+
+    /// Space currently available on the volume, in bytes, or `None` if it
+    /// can't be determined
+    pub free: Option<u64>,
+}
+
+impl VolumeUsage {
+    /// Space available on the volume once its build directories are removed.
+    #[must_use]
+    pub fn projected_free(&self) -> Option<u64> {
+        self.free.map(|free| free.saturating_add(self.reclaimable))
+    }
+}
+
+/// Group the build directories of `projects` by the volume they are on.
+///
+/// Build directories whose volume can't be determined are left out. The
+/// result is ordered by mount point.
+#[must_use]
+pub fn group_by_volume(projects: &[Project]) -> Vec<VolumeUsage> {
+    let mut volumes: BTreeMap<PathBuf, (u64, usize)> = BTreeMap::new();
+
+    for artifact in projects.iter().flat_map(|p| &p.build_arts) {
+        let Some(mount_point) = volume_of(&artifact.path) else {
+            continue;
+        };
+        let entry = volumes.entry(mount_point).or_default();
+        entry.0 += artifact.size;
+        entry.1 += 1;
+    }
+
+    volumes
+        .into_iter()
+        .map(|(mount_point, (reclaimable, artifacts))| VolumeUsage {
+            free: sys::free_space(&mount_point),
+            mount_point,
+            reclaimable,
+            artifacts,
+        })
+        .collect()
+}
+
+/// Mount point of the volume holding `path`.
+///
+/// Paths that no longer exist (e.g. build directories that were just
+/// removed) are looked up through their closest existing ancestor.
+#[must_use]
+pub fn volume_of(path: &Path) -> Option<PathBuf> {
+    let existing = path.ancestors().find(|ancestor| ancestor.exists())?;
+    sys::mount_point(existing)
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::{
+        ffi::CString,
+        fs,
+        os::unix::{ffi::OsStrExt, fs::MetadataExt},
+        path::{Path, PathBuf},
+    };
+
+    /// The topmost ancestor of `path` on the same device as `path`.
+    pub(super) fn mount_point(path: &Path) -> Option<PathBuf> {
+        let path = fs::canonicalize(path).ok()?;
+        let device = fs::metadata(&path).ok()?.dev();
+
+        path.ancestors()
+            .take_while(|ancestor| {
+                fs::metadata(ancestor).is_ok_and(|metadata| metadata.dev() == device)
+            })
+            .last()
+            .map(Path::to_path_buf)
+    }
+
+    pub(super) fn free_space(mount_point: &Path) -> Option<u64> {
+        let path = CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+        // SAFETY: statvfs is a plain C struct for which all-zero is a valid value.
+        let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+        // SAFETY: `path` is NUL-terminated and `stats` is a valid out pointer.
+        if unsafe { libc::statvfs(path.as_ptr(), &raw mut stats) } != 0 {
+            return None;
+        }
+
+        // The field types are narrower than u64 on some platforms
+        #[allow(clippy::useless_conversion)]
+        u64::from(stats.f_bavail).checked_mul(u64::from(stats.f_frsize))
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::{
+        ffi::OsString,
+        os::windows::ffi::{OsStrExt, OsStringExt},
+        path::{Path, PathBuf},
+        ptr,
+    };
+
+    use windows_sys::Win32::Storage::FileSystem::{GetDiskFreeSpaceExW, GetVolumePathNameW};
+
+    /// Encode `path` as a NUL-terminated wide string.
+    fn wide(path: &Path) -> Vec<u16> {
+        path.as_os_str().encode_wide().chain(Some(0)).collect()
+    }
+
+    /// The root of the volume holding `path`, such as `C:\` or a mounted folder.
+    pub(super) fn mount_point(path: &Path) -> Option<PathBuf> {
+        const MAX_LEN: u32 = 32_768;
+
+        let path = wide(path);
+        let mut root = vec![0u16; MAX_LEN as usize];
+        // SAFETY: `path` is NUL-terminated and `root` holds `MAX_LEN` characters.
+        if unsafe { GetVolumePathNameW(path.as_ptr(), root.as_mut_ptr(), MAX_LEN) } == 0 {
+            return None;
+        }
+
+        let len = root.iter().position(|&c| c == 0).unwrap_or(root.len());
+        Some(PathBuf::from(OsString::from_wide(&root[..len])))
+    }
+
+    pub(super) fn free_space(mount_point: &Path) -> Option<u64> {
+        let root = wide(mount_point);
+        let mut available = 0u64;
+        // SAFETY: `root` is NUL-terminated and the unused outputs may be null.
+        let ok = unsafe {
+            GetDiskFreeSpaceExW(
+                root.as_ptr(),
+                &raw mut available,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+        (ok != 0).then_some(available)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod sys {
+    use std::path::{Path, PathBuf};
+
+    /// Volumes can't be told apart on this platform, so everything is
+    /// reported under the root directory.
+    pub(super) fn mount_point(_path: &Path) -> Option<PathBuf> {
+        Some(PathBuf::from("/"))
+    }
+
+    pub(super) const fn free_space(_mount_point: &Path) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::project::{BuildArtifacts, ProjectType};
+
+    fn project(root: &Path, artifacts: &[(&str, u64)]) -> Project {
+        let build_arts = artifacts
+            .iter()
+            .map(|&(dir, size)| BuildArtifacts {
+                path: root.join(dir),
+                size,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            })
+            .collect();
+        Project::new(ProjectType::Rust, root.to_path_buf(), build_arts, None)
+    }
+
+    #[test]
+    fn test_group_by_volume_sums_artifacts_on_the_same_volume() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        fs::create_dir_all(tmp.path().join("a/target"))?;
+        fs::create_dir_all(tmp.path().join("b/target"))?;
+
+        // A removed build directory is still grouped through its parent
+        let volumes = group_by_volume(&[
+            project(&tmp.path().join("a"), &[("target", 1_000)]),
+            project(&tmp.path().join("b"), &[("target", 500), ("gone", 25)]),
+        ]);
+
+        assert_eq!(volumes.len(), 1);
+        let volume = &volumes[0];
+        assert!(fs::canonicalize(tmp.path())?.starts_with(&volume.mount_point));
+        assert_eq!(volume.reclaimable, 1_525);
+        assert_eq!(volume.artifacts, 3);
+        #[cfg(any(unix, windows))]
+        assert!(volume.free.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_projected_free() {
+        let mut volume = VolumeUsage {
+            mount_point: PathBuf::from("/data"),
+            reclaimable: 300,
+            artifacts: 1,
+            free: Some(1_000),
+        };
+        assert_eq!(volume.projected_free(), Some(1_300));
+
+        volume.free = None;
+        assert_eq!(volume.projected_free(), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Traces of how a scan treats one directory, for `--why`.
+//!
+//! A directory can be missing from the results for many reasons: the walk
+//! never reached it, no detector recognized it, an earlier detector claimed
+//! its build directories, or a filter left its project out. A [`PathTrace`]
+//! records each of these steps for a single path, as
+//! [`Scanner::trace`](crate::scanner::Scanner::trace) goes through them, so
+//! the answer doesn't have to be guessed from the options in effect.
+//!
+//! Every rule that leaves a directory out is reported as a [`Reason`] named
+//! after the option or config key behind it (such as `keep_days` or
+//! `scan_hidden`), which scripts can match on.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::project::Project;
+
+/// One rule that leaves a directory or project out.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Reason {
+    /// The option or config key behind the rule (e.g. `"keep_days"`)
+    pub rule: &'static str,
+
+    /// What the rule found, in words
+    pub detail: String,
+}
+
+impl Reason {
+    /// A reason from `rule` with the explanation `detail`.
+    #[must_use]
+    pub fn new(rule: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            rule,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// What one detector made of the traced directory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectorOutcome {
+    /// Left out by the project type filter; build directories it reports
+    /// still aren't given to a later detector
+    Skipped,
+
+    /// None of the files it recognizes projects by are there
+    NoMarkers,
+
+    /// A project of its type, but without anything to clean
+    NothingToClean,
+
+    /// Its build directories all belong to a detector listed before it
+    Claimed,
+
+    /// Detected a project with build directories to clean
+    Detected,
+}
+
+/// How one detector was applied to the traced directory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DetectorTrace {
+    /// Name of the detector (e.g. `"rust"`)
+    pub detector: &'static str,
+
+    /// What the detector found
+    pub outcome: DetectorOutcome,
+
+    /// The marker files it looks for that are there
+    pub markers_found: Vec<String>,
+
+    /// The markers it looks for (file names or `*.ext` patterns) that
+    /// nothing in the directory matches
+    pub markers_missing: Vec<String>,
+
+    /// The build directories it would clean, after those claimed by earlier
+    /// detectors are taken out
+    pub artifacts: Vec<PathBuf>,
+}
+
+/// A project detected in the traced directory, and the filters it fails.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TracedProject {
+    /// Root directory of the project
+    pub root: PathBuf,
+
+    /// Project type (e.g. `"rust"`)
+    pub kind: String,
+
+    /// Name of the project, if it could be determined
+    pub name: Option<String>,
+
+    /// Total size of its build directories in bytes
+    pub size: u64,
+
+    /// Its build directories
+    pub artifacts: Vec<PathBuf>,
+
+    /// The filters that leave it out; empty if it would be cleaned
+    pub filtered_by: Vec<Reason>,
+}
+
+impl TracedProject {
+    /// Describe `project`, which fails the filters `filtered_by`.
+    #[must_use]
+    pub fn new(project: &Project, filtered_by: Vec<Reason>) -> Self {
+        Self {
+            root: project.root_path.clone(),
+            kind: crate::output::type_name(&project.kind).to_string(),
+            name: project.name.clone(),
+            size: project.total_size(),
+            artifacts: project.build_arts.iter().map(|a| a.path.clone()).collect(),
+            filtered_by,
+        }
+    }
+}
+
+/// How a scan treats one path, step by step.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PathTrace {
+    /// The path that was asked about
+    pub path: PathBuf,
+
+    /// The project directory that owns `path` as a build directory, when
+    /// `path` is one (such as a `node_modules/`). The walk and the detectors
+    /// are then traced for this directory instead.
+    pub build_dir_of: Option<PathBuf>,
+
+    /// The scanned directory the traced directory lies in, if any
+    pub scan_root: Option<PathBuf>,
+
+    /// Why the walk doesn't check the traced directory for projects; empty
+    /// if it does
+    pub pruned_by: Vec<Reason>,
+
+    /// Every detector, in the order they are tried
+    pub detectors: Vec<DetectorTrace>,
+
+    /// The projects detected, with the filters each one fails
+    pub projects: Vec<TracedProject>,
+
+    /// Whether a scan with the same options would clean something in the
+    /// traced directory
+    pub cleaned: bool,
+}
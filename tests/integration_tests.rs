@@ -163,6 +163,7 @@ fn test_scanner_finds_rust_projects() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -194,6 +195,7 @@ fn test_scanner_finds_node_projects() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Node);
@@ -225,6 +227,7 @@ fn test_scanner_finds_python_projects() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Python);
@@ -260,6 +263,7 @@ fn test_scanner_finds_go_projects() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Go);
@@ -293,6 +297,7 @@ fn test_scanner_finds_all_project_types() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::All);
@@ -314,7 +319,7 @@ fn test_scanner_finds_all_project_types() -> anyhow::Result<()> {
 }
 
 #[test]
-fn test_scanner_skips_directories() -> anyhow::Result<()> {
+fn test_scanner_ignores_directories() -> anyhow::Result<()> {
     let temp_dir = create_test_directory()?;
     let base_path = temp_dir.path();
 
@@ -326,20 +331,57 @@ fn test_scanner_skips_directories() -> anyhow::Result<()> {
     let scan_options = ScanOptions {
         verbose: false,
         threads: 1,
-        skip: vec![PathBuf::from("skip-me"), PathBuf::from("target")],
+        ignore: vec![PathBuf::from("skip-me"), PathBuf::from("target")],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
     let projects = scanner.scan_directory(base_path);
 
-    // Should only find the top-level project, not the ones in skipped directories
+    // Should only find the top-level project, not the ones in ignored directories
     assert_eq!(projects.len(), 1);
     assert!(projects[0].root_path.ends_with("rust-project"));
 
     Ok(())
 }
 
+#[test]
+fn test_scanner_skip_still_descends_but_ignore_does_not() -> anyhow::Result<()> {
+    let temp_dir = create_test_directory()?;
+    let base_path = temp_dir.path();
+
+    // A project that is itself named like the skipped/ignored directory,
+    // with another project nested inside it
+    let outer = create_rust_project(base_path, "sandbox")?;
+    create_rust_project(&outer, "inner")?;
+
+    let scan = |skip: Vec<PathBuf>, ignore: Vec<PathBuf>| {
+        let scan_options = ScanOptions {
+            threads: 1,
+            skip,
+            ignore,
+            ..ScanOptions::default()
+        };
+        let mut names: Vec<String> = Scanner::new(scan_options, ProjectFilter::Rust)
+            .scan_directory(base_path)
+            .into_iter()
+            .filter_map(|p| p.name)
+            .collect();
+        names.sort();
+        names
+    };
+
+    let sandbox = vec![PathBuf::from("sandbox")];
+    assert_eq!(scan(Vec::new(), Vec::new()), vec!["inner", "sandbox"]);
+    // skip: `sandbox` itself isn't a project, but what's below it is scanned
+    assert_eq!(scan(sandbox.clone(), Vec::new()), vec!["inner"]);
+    // ignore: nothing below `sandbox` is scanned at all
+    assert!(scan(Vec::new(), sandbox).is_empty());
+
+    Ok(())
+}
+
 #[test]
 fn test_scanner_calculates_build_directory_sizes() -> anyhow::Result<()> {
     let temp_dir = create_test_directory()?;
@@ -357,6 +399,7 @@ fn test_scanner_calculates_build_directory_sizes() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -388,6 +431,7 @@ fn test_scanner_handles_empty_directories() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -417,6 +461,7 @@ fn test_scanner_handles_missing_build_directories() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -443,6 +488,7 @@ fn test_scanner_nested_projects() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::All);
@@ -482,6 +528,7 @@ fn test_scanner_with_multiple_threads() -> anyhow::Result<()> {
         threads: 4, // Use multiple threads
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::All);
@@ -502,6 +549,10 @@ fn test_build_artifacts_structure() -> anyhow::Result<()> {
     let artifacts = BuildArtifacts {
         path: target_path.clone(),
         size: 12345,
+        files: 0,
+        dirs: 0,
+        newest_file: None,
+        estimated: false,
     };
 
     assert_eq!(artifacts.path, target_path);
@@ -547,6 +598,7 @@ fn test_scanner_with_spaces_in_directory_names() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::All);
@@ -576,6 +628,7 @@ fn test_scanner_with_unicode_directory_names() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::All);
@@ -606,6 +659,7 @@ fn test_scanner_with_deeply_nested_directories() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -632,6 +686,7 @@ fn test_scanner_with_special_characters_in_paths() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::All);
@@ -671,6 +726,7 @@ fn test_scanner_hidden_directory_itself_not_detected_unix() -> anyhow::Result<()
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -700,6 +756,7 @@ fn test_scanner_traverses_into_hidden_dirs_finds_visible_children_unix() -> anyh
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -734,6 +791,7 @@ fn test_executable_preservation_integration_unix() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -742,7 +800,7 @@ fn test_executable_preservation_integration_unix() -> anyhow::Result<()> {
     assert_eq!(projects.len(), 1);
 
     // Preserve executables
-    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0])?;
+    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0], false)?;
     assert_eq!(preserved.len(), 1);
     assert!(preserved[0].destination.exists());
     assert!(
@@ -773,6 +831,7 @@ fn test_scanner_symlink_handling_unix() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -806,6 +865,7 @@ fn test_executable_preservation_integration_windows() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -813,7 +873,7 @@ fn test_executable_preservation_integration_windows() -> anyhow::Result<()> {
 
     assert_eq!(projects.len(), 1);
 
-    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0])?;
+    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0], false)?;
     assert_eq!(preserved.len(), 1);
     assert!(preserved[0].destination.exists());
     assert!(
@@ -846,6 +906,7 @@ fn test_scanner_with_windows_long_paths() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Rust);
@@ -878,6 +939,7 @@ fn test_python_whl_preservation_cross_platform() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Python);
@@ -885,7 +947,7 @@ fn test_python_whl_preservation_cross_platform() -> anyhow::Result<()> {
 
     assert_eq!(projects.len(), 1);
 
-    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0])?;
+    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0], false)?;
     // Should find the .whl file on any platform
     assert_eq!(preserved.len(), 1);
     assert!(preserved[0].destination.to_string_lossy().ends_with(".whl"));
@@ -912,6 +974,7 @@ fn test_python_so_preservation_unix() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Python);
@@ -919,7 +982,7 @@ fn test_python_so_preservation_unix() -> anyhow::Result<()> {
 
     assert_eq!(projects.len(), 1);
 
-    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0])?;
+    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0], false)?;
     assert_eq!(preserved.len(), 1);
     assert!(preserved[0].destination.to_string_lossy().ends_with(".so"));
 
@@ -945,6 +1008,7 @@ fn test_python_pyd_preservation_windows() -> anyhow::Result<()> {
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner = Scanner::new(scan_options, ProjectFilter::Python);
@@ -952,7 +1016,7 @@ fn test_python_pyd_preservation_windows() -> anyhow::Result<()> {
 
     assert_eq!(projects.len(), 1);
 
-    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0])?;
+    let preserved = clean_dev_dirs::executables::preserve_executables(&projects[0], false)?;
     assert_eq!(preserved.len(), 1);
     assert!(preserved[0].destination.to_string_lossy().ends_with(".pyd"));
 
@@ -1046,12 +1110,14 @@ fn test_parallel_and_single_thread_produce_same_results() -> anyhow::Result<()>
         threads: 1,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
     let multi_thread = ScanOptions {
         verbose: false,
         threads: 4,
         skip: vec![],
         max_depth: None,
+        ..ScanOptions::default()
     };
 
     let scanner1 = Scanner::new(single_thread, ProjectFilter::All);
@@ -1074,3 +1140,192 @@ fn test_parallel_and_single_thread_produce_same_results() -> anyhow::Result<()>
 
     Ok(())
 }
+
+#[test]
+fn test_newest_file_age_source() -> anyhow::Result<()> {
+    use clean_dev_dirs::config::AgeSource;
+    use std::fs::{File, FileTimes};
+    use std::time::{Duration, SystemTime};
+
+    let temp_dir = create_test_directory()?;
+    let base_path = temp_dir.path();
+    let project_path = create_rust_project(base_path, "aged")?;
+
+    // Backdate the files; the directories inside target/ keep a fresh mtime
+    let day = Duration::from_hours(24);
+    let last_build = SystemTime::now() - 30 * day;
+    for (file, mtime) in [
+        ("target/debug/build.log", last_build - 10 * day),
+        ("target/release/binary", last_build),
+    ] {
+        File::options()
+            .write(true)
+            .open(project_path.join(file))?
+            .set_times(FileTimes::new().set_modified(mtime))?;
+    }
+
+    let scan = |age_source| {
+        let scan_options = ScanOptions {
+            threads: 1,
+            age_source,
+            ..ScanOptions::default()
+        };
+        Scanner::new(scan_options, ProjectFilter::Rust).scan_directory(base_path)
+    };
+
+    let projects = scan(AgeSource::NewestFile);
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].newest_file, Some(last_build));
+
+    let projects = scan(AgeSource::DirMtime);
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].newest_file, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_cleanup_plan_from_scan() -> anyhow::Result<()> {
+    use clean_dev_dirs::plan::{CleanupPlan, DEFAULT_TOLERANCE_PERCENT, Rejection};
+
+    let temp_dir = create_test_directory()?;
+    let base_path = temp_dir.path();
+    create_rust_project(base_path, "kept")?;
+    let removed = create_rust_project(base_path, "removed")?;
+
+    let scan_options = ScanOptions {
+        threads: 1,
+        ..ScanOptions::default()
+    };
+    let projects = Scanner::new(scan_options, ProjectFilter::Rust).scan_directory(base_path);
+    assert_eq!(projects.len(), 2);
+
+    let plan_path = base_path.join("plan.json");
+    CleanupPlan::from_projects(&projects).save(&plan_path)?;
+
+    // The target directory disappears between planning and applying
+    fs::remove_dir_all(removed.join("target"))?;
+
+    let check = CleanupPlan::load(&plan_path)?.validate(DEFAULT_TOLERANCE_PERCENT, &projects);
+    assert_eq!(check.projects.len(), 1);
+    assert_eq!(check.projects[0].name.as_deref(), Some("kept"));
+    assert_eq!(check.rejected.len(), 1);
+    assert_eq!(check.rejected[0].1, Rejection::Missing);
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_sizes_reports_measured_freed_space() -> anyhow::Result<()> {
+    use clean_dev_dirs::cleaner::{Cleaner, Removal, RemovalStrategy, RetryPolicy};
+    use clean_dev_dirs::progress::ProgressMode;
+    use clean_dev_dirs::project::{Project, Projects};
+
+    let temp_dir = create_test_directory()?;
+    let base_path = temp_dir.path();
+
+    let clean_with = |name: &str, verify_sizes: bool| -> anyhow::Result<_> {
+        let project_path = create_rust_project(base_path, name)?;
+        let target = project_path.join("target");
+        let actual = clean_dev_dirs::utils::calculate_dir_size(&target);
+
+        // Pretend the scan saw a much larger directory than is left now
+        let project = Project::new(
+            ProjectType::Rust,
+            project_path,
+            vec![BuildArtifacts {
+                path: target.clone(),
+                size: 1_000_000,
+                files: 0,
+                dirs: 0,
+                newest_file: None,
+                estimated: false,
+            }],
+            Some(name.to_string()),
+        );
+        let result = Cleaner::clean_projects(
+            Projects::from(vec![project]),
+            None,
+            ProgressMode::Hidden,
+            Removal {
+                strategy: RemovalStrategy::Permanent,
+                retry: RetryPolicy::default(),
+                prune_empty_parents: false,
+                batch_trash: false,
+                rename_first: false,
+                journal: None,
+            },
+            None,
+            verify_sizes,
+            &clean_dev_dirs::DisplayOptions::default(),
+        );
+        assert!(!target.exists());
+        Ok((result, actual))
+    };
+
+    let (result, actual) = clean_with("verified", true)?;
+    assert_eq!(result.estimated_freed, 1_000_000);
+    assert_eq!(result.verified_freed, Some(actual));
+    assert_eq!(result.total_freed, actual);
+
+    let (result, _) = clean_with("estimated", false)?;
+    assert_eq!(result.verified_freed, None);
+    assert_eq!(result.total_freed, 1_000_000);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pre_project_clean_hook_can_veto_cleanup() -> anyhow::Result<()> {
+    use clean_dev_dirs::cleaner::{Cleaner, Removal, RemovalStrategy, RetryPolicy};
+    use clean_dev_dirs::progress::ProgressMode;
+    use clean_dev_dirs::project::Projects;
+
+    let temp_dir = create_test_directory()?;
+    let base_path = temp_dir.path();
+
+    let keep = create_rust_project(base_path, "keep-me")?;
+    let clean = create_rust_project(base_path, "clean-me")?;
+
+    let scanner = Scanner::new(
+        ScanOptions {
+            threads: 1,
+            ..ScanOptions::default()
+        },
+        ProjectFilter::Rust,
+    );
+    let projects = scanner.scan_directory(base_path);
+    assert_eq!(projects.len(), 2);
+
+    // Veto any project whose path ends in "keep-me"
+    let hook = "case {path} in *keep-me) exit 1 ;; *) exit 0 ;; esac";
+    let result = Cleaner::clean_projects(
+        Projects::from(projects),
+        None,
+        ProgressMode::Hidden,
+        Removal {
+            strategy: RemovalStrategy::Permanent,
+            retry: RetryPolicy::default(),
+            prune_empty_parents: false,
+            batch_trash: false,
+            rename_first: false,
+            journal: None,
+        },
+        Some(clean_dev_dirs::hooks::PreCleanHook {
+            template: hook,
+            stdout_to_stderr: false,
+        }),
+        false,
+        &clean_dev_dirs::DisplayOptions::default(),
+    );
+
+    assert_eq!(result.success_count, 1);
+    assert!(result.errors.is_empty());
+    assert_eq!(result.skipped.len(), 1);
+    assert!(result.skipped[0].contains("pre_project_clean"));
+    assert!(keep.join("target").exists());
+    assert!(!clean.join("target").exists());
+
+    Ok(())
+}